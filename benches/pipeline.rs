@@ -42,6 +42,7 @@ fn bench_full_pipeline(c: &mut Criterion) {
                     output_path: Some(output_path.clone()),
                     target_platform: "Sql160".to_string(),
                     verbose: false,
+                    ..Default::default()
                 };
                 rust_sqlpackage::build_dacpac(options).unwrap()
             })
@@ -61,6 +62,7 @@ fn bench_full_pipeline(c: &mut Criterion) {
                     output_path: Some(output_path.clone()),
                     target_platform: "Sql160".to_string(),
                     verbose: false,
+                    ..Default::default()
                 };
                 rust_sqlpackage::build_dacpac(options).unwrap()
             })
@@ -80,6 +82,7 @@ fn bench_full_pipeline(c: &mut Criterion) {
                     output_path: Some(output_path.clone()),
                     target_platform: "Sql160".to_string(),
                     verbose: false,
+                    ..Default::default()
                 };
                 rust_sqlpackage::build_dacpac(options).unwrap()
             })
@@ -0,0 +1,122 @@
+//! Async front-end to [`build_dacpac`] (enabled with the `async` feature)
+//!
+//! Services embedding the compiler can't block their executor for a long build.
+//! [`build_dacpac_async`] runs the build on rayon's thread pool and returns a
+//! [`BuildHandle`] that streams [`BuildProgress`] and resolves to the dacpac's path. It
+//! only needs tokio's channels, so any executor can await it:
+//!
+//! ```rust,ignore
+//! let mut build = rust_sqlpackage::build_dacpac_async(options);
+//! while let Some(progress) = build.progress().await {
+//!     println!("{:?}", progress);
+//! }
+//! let dacpac_path = build.finish().await?;
+//! ```
+
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+
+use anyhow::Result;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::progress::{BuildProgress, ProgressCallback};
+use crate::util::CancellationToken;
+use crate::{build_dacpac, BuildOptions};
+
+/// A build running in the background, see [`build_dacpac_async`]
+#[derive(Debug)]
+pub struct BuildHandle {
+    progress: mpsc::UnboundedReceiver<BuildProgress>,
+    result: oneshot::Receiver<Result<PathBuf>>,
+    cancellation: CancellationToken,
+}
+
+/// Start [`build_dacpac`] on the thread pool and return at once
+///
+/// Progress goes to the handle as well as to any `on_progress` callback in `options`.
+pub fn build_dacpac_async(mut options: BuildOptions) -> BuildHandle {
+    let cancellation = options
+        .cancellation
+        .get_or_insert_with(CancellationToken::new)
+        .clone();
+
+    let (progress_tx, progress) = mpsc::unbounded_channel();
+    let callback = options.on_progress.take();
+    options.on_progress = Some(ProgressCallback::new(move |stage| {
+        if let Some(callback) = &callback {
+            callback.report(stage);
+        }
+        // The handle may have been dropped; the build carries on regardless
+        let _ = progress_tx.send(stage.clone());
+    }));
+
+    let (result_tx, result) = oneshot::channel();
+    rayon::spawn(move || {
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| build_dacpac(options)))
+            .unwrap_or_else(|_| Err(anyhow::anyhow!("the build panicked")));
+        let _ = result_tx.send(outcome);
+    });
+
+    BuildHandle {
+        progress,
+        result,
+        cancellation,
+    }
+}
+
+impl BuildHandle {
+    /// The next finished stage, or `None` once the build is over
+    pub async fn progress(&mut self) -> Option<BuildProgress> {
+        self.progress.recv().await
+    }
+
+    /// Stop the build at its next check, making [`finish`](Self::finish) return
+    /// [`SqlPackageError::Cancelled`](crate::SqlPackageError::Cancelled)
+    pub fn cancel(&self) {
+        self.cancellation.cancel();
+    }
+
+    /// Wait for the build and return what [`build_dacpac`] returned
+    pub async fn finish(self) -> Result<PathBuf> {
+        self.result
+            .await
+            .unwrap_or_else(|_| Err(anyhow::anyhow!("the build stopped without a result")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::InMemoryProject;
+
+    #[tokio::test]
+    async fn test_build_dacpac_async() {
+        let project = InMemoryProject::new(&[
+            (
+                "Tables/Users.sql",
+                "CREATE TABLE [dbo].[Users] ([Id] INT NOT NULL);",
+            ),
+            (
+                "Views/Names.sql",
+                "CREATE VIEW [dbo].[Names] AS SELECT [Id] FROM [dbo].[Users];",
+            ),
+        ])
+        .unwrap();
+        let mut build = build_dacpac_async(BuildOptions {
+            project_path: project.project_path().to_path_buf(),
+            ..Default::default()
+        });
+
+        let mut stages = Vec::new();
+        while let Some(progress) = build.progress().await {
+            stages.push(progress);
+        }
+        let dacpac_path = build.finish().await.unwrap();
+        assert!(dacpac_path.exists());
+        assert_eq!(stages[0], BuildProgress::ProjectLoaded { sql_files: 2 });
+        assert_eq!(
+            stages.last(),
+            Some(&BuildProgress::DacpacWritten { path: dacpac_path })
+        );
+    }
+}
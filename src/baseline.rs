@@ -0,0 +1,282 @@
+//! Baseline dacpacs built with DacFx (`baseline`)
+//!
+//! Builds a project with `dotnet build` (Microsoft.Build.Sql), keeps the dacpac as the
+//! baseline that `compare` checks rust-sqlpackage's output against, and records the
+//! tool versions that produced it in `<name>.baseline.json` next to it, so a changed
+//! baseline can be traced to a toolchain upgrade.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::LazyLock;
+
+use anyhow::Result;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::compare::reader::DacpacContents;
+use crate::error::SqlPackageError;
+use crate::project::parse_sqlproj;
+
+/// `Sdk="Microsoft.Build.Sql/0.2.0"` on `<Project>`, or `<Sdk Name=".." Version=".." />`
+static SDK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?s)<Project\b[^>]*\bSdk="(?P<attr>[^"]+)"|<Sdk\b[^>]*\bName="(?P<name>[^"]+)"[^>]*\bVersion="(?P<version>[^"]+)""#,
+    )
+    .unwrap()
+});
+
+static PRODUCT_VERSION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<ProductVersion>([^<]+)</ProductVersion>").unwrap());
+
+/// How the baseline is built
+#[derive(Debug, Clone)]
+pub struct BaselineOptions {
+    /// MSBuild configuration
+    pub configuration: String,
+    /// The `dotnet` executable
+    pub dotnet: PathBuf,
+}
+
+impl Default for BaselineOptions {
+    fn default() -> Self {
+        Self {
+            configuration: "Debug".to_string(),
+            dotnet: PathBuf::from("dotnet"),
+        }
+    }
+}
+
+/// What `<name>.baseline.json` records about a baseline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineRecord {
+    /// The project, as given
+    pub project: String,
+    /// SHA256 of the baseline dacpac (uppercase hex)
+    pub sha256: String,
+    /// When the baseline was built (RFC 3339)
+    pub created: String,
+    pub configuration: String,
+    /// `dotnet --version`
+    pub dotnet_sdk: String,
+    /// The project SDK, e.g. `Microsoft.Build.Sql/0.2.0`
+    pub build_sdk: Option<String>,
+    /// DacFx version, from the dacpac's Origin.xml
+    pub dacfx: Option<String>,
+    /// rust-sqlpackage version that ran the build
+    pub rust_sqlpackage: String,
+}
+
+/// Where the baseline of `project_path` is stored by default:
+/// `baseline/<project>.dacpac` in the project directory
+pub fn default_output_path(project_path: &Path) -> Result<PathBuf> {
+    let project = parse_sqlproj(project_path)?;
+    Ok(project
+        .project_dir
+        .join("baseline")
+        .join(format!("{}.dacpac", project.name)))
+}
+
+/// Where the record of the baseline at `dacpac_path` is written
+pub fn record_path(dacpac_path: &Path) -> PathBuf {
+    let stem = dacpac_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    dacpac_path.with_file_name(format!("{}.baseline.json", stem))
+}
+
+/// Build `project_path` with `dotnet build` and store the dacpac at `output_path`
+///
+/// The build writes into a temporary folder, leaving the project's `bin` folder (where
+/// rust-sqlpackage writes by default) alone.
+pub fn create_baseline(
+    project_path: &Path,
+    output_path: &Path,
+    options: &BaselineOptions,
+) -> Result<BaselineRecord> {
+    let project = parse_sqlproj(project_path)?;
+    let dotnet_sdk = run_dotnet(options, &["--version".as_ref()])?
+        .trim()
+        .to_string();
+
+    let staging = std::env::temp_dir().join(format!(
+        "rust-sqlpackage-baseline-{}-{}",
+        project.name,
+        std::process::id()
+    ));
+    let built = build_with_dotnet(project_path, &staging, options).and_then(|()| {
+        let dacpac = staging.join(format!("{}.dacpac", project.name));
+        std::fs::read(&dacpac).map_err(|_| {
+            baseline_error(format!(
+                "dotnet build did not produce {}.dacpac",
+                project.name
+            ))
+        })
+    });
+    let _ = std::fs::remove_dir_all(&staging);
+    let bytes = built?;
+
+    let write_error = |e| SqlPackageError::OutputWriteError {
+        path: output_path.to_path_buf(),
+        source: e,
+    };
+    if let Some(parent) = output_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(write_error)?;
+    }
+    std::fs::write(output_path, &bytes).map_err(write_error)?;
+
+    let sqlproj = std::fs::read_to_string(project_path).unwrap_or_default();
+    let origin = DacpacContents::from_path(output_path)?
+        .get_string("Origin.xml")
+        .unwrap_or_default();
+    let record = BaselineRecord {
+        project: project_path.display().to_string(),
+        sha256: format!("{:X}", Sha256::digest(&bytes)),
+        created: chrono::Utc::now().to_rfc3339(),
+        configuration: options.configuration.clone(),
+        dotnet_sdk,
+        build_sdk: project_sdk(&sqlproj),
+        dacfx: PRODUCT_VERSION_RE
+            .captures(&origin)
+            .map(|c| c[1].trim().to_string()),
+        rust_sqlpackage: crate::self_update::VERSION.to_string(),
+    };
+    let record_path = record_path(output_path);
+    std::fs::write(&record_path, serde_json::to_string_pretty(&record)? + "\n").map_err(|e| {
+        SqlPackageError::OutputWriteError {
+            path: record_path.clone(),
+            source: e,
+        }
+    })?;
+    Ok(record)
+}
+
+fn build_with_dotnet(project_path: &Path, staging: &Path, options: &BaselineOptions) -> Result<()> {
+    run_dotnet(
+        options,
+        &[
+            "build".as_ref(),
+            project_path.as_os_str(),
+            "--configuration".as_ref(),
+            options.configuration.as_ref(),
+            "--output".as_ref(),
+            staging.as_os_str(),
+            "-nologo".as_ref(),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Run dotnet, returning its stdout; a failed run is an error with the end of its output
+fn run_dotnet(options: &BaselineOptions, args: &[&std::ffi::OsStr]) -> Result<String> {
+    let output = Command::new(&options.dotnet)
+        .args(args)
+        .output()
+        .map_err(|e| {
+            baseline_error(format!(
+                "cannot run {} ({}); install the .NET SDK or pass --dotnet",
+                options.dotnet.display(),
+                e
+            ))
+        })?;
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    if !output.status.success() {
+        // MSBuild reports errors on stdout
+        let text = format!("{}{}", stdout, String::from_utf8_lossy(&output.stderr));
+        let lines: Vec<&str> = text.lines().collect();
+        let tail = lines[lines.len().saturating_sub(20)..].join("\n");
+        return Err(baseline_error(format!(
+            "{} {} failed:\n{}",
+            options.dotnet.display(),
+            args.first()
+                .map(|arg| arg.to_string_lossy())
+                .unwrap_or_default(),
+            tail
+        )));
+    }
+    Ok(stdout)
+}
+
+/// The SDK named in a project file, as `Name/Version`
+fn project_sdk(sqlproj: &str) -> Option<String> {
+    let captures = SDK_RE.captures(sqlproj)?;
+    match captures.name("attr") {
+        Some(attr) => Some(attr.as_str().to_string()),
+        None => Some(format!("{}/{}", &captures["name"], &captures["version"])),
+    }
+}
+
+fn baseline_error(message: String) -> anyhow::Error {
+    SqlPackageError::BaselineError { message }.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_sdk() {
+        assert_eq!(
+            project_sdk(r#"<Project DefaultTargets="Build" Sdk="Microsoft.Build.Sql/0.2.0">"#),
+            Some("Microsoft.Build.Sql/0.2.0".to_string())
+        );
+        assert_eq!(
+            project_sdk(
+                r#"<Project><Sdk Name="Microsoft.Build.Sql" Version="0.1.12-preview" /></Project>"#
+            ),
+            Some("Microsoft.Build.Sql/0.1.12-preview".to_string())
+        );
+        assert_eq!(project_sdk(r#"<Project ToolsVersion="4.0">"#), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_create_baseline_with_dotnet_stand_in() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/simple_table");
+        let dir = tempfile::tempdir().unwrap();
+        let built = dir.path().join("built.dacpac");
+        crate::build_dacpac(crate::BuildOptions {
+            project_path: fixture.join("project.sqlproj"),
+            output_path: Some(built.clone()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        // Prints a version, or copies the prebuilt dacpac into --output
+        let dotnet = dir.path().join("dotnet");
+        std::fs::write(
+            &dotnet,
+            format!(
+                "#!/bin/sh\n[ \"$1\" = --version ] && {{ echo 8.0.404; exit 0; }}\nmkdir -p \"$6\" && cp '{}' \"$6/project.dacpac\"\n",
+                built.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&dotnet, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let output = dir.path().join("baseline/project.dacpac");
+        let options = BaselineOptions {
+            dotnet,
+            ..Default::default()
+        };
+        let record = create_baseline(&fixture.join("project.sqlproj"), &output, &options).unwrap();
+        assert_eq!(record.dotnet_sdk, "8.0.404");
+        assert_eq!(record.configuration, "Debug");
+        assert!(record.dacfx.is_some());
+        assert_eq!(
+            std::fs::read(&output).unwrap(),
+            std::fs::read(&built).unwrap()
+        );
+
+        let saved: BaselineRecord =
+            serde_json::from_str(&std::fs::read_to_string(record_path(&output)).unwrap()).unwrap();
+        assert_eq!(saved, record);
+        assert_eq!(
+            record_path(&output),
+            dir.path().join("baseline/project.baseline.json")
+        );
+    }
+}
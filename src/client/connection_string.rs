@@ -0,0 +1,234 @@
+//! ADO.NET connection string parsing
+//!
+//! Supports the keywords SqlPackage users put in `/SourceConnectionString` and
+//! `/TargetConnectionString`; keywords that only configure the .NET client (pooling,
+//! MARS, ...) are ignored.
+
+use std::time::Duration;
+
+use anyhow::Result;
+
+use super::{Authentication, ConnectionOptions};
+use crate::error::SqlPackageError;
+
+pub(super) fn parse(connection_string: &str) -> Result<ConnectionOptions> {
+    let mut options = ConnectionOptions::default();
+    let mut user = None;
+    let mut password = None;
+    let mut integrated = false;
+
+    for (key, value) in pairs(connection_string)? {
+        match key.as_str() {
+            "server" | "data source" | "address" | "addr" | "network address" => {
+                parse_server(&value, &mut options)?
+            }
+            "database" | "initial catalog" => options.database = Some(value),
+            "user id" | "uid" | "user" => user = Some(value),
+            "password" | "pwd" => password = Some(value),
+            "integrated security" | "trusted_connection" => {
+                integrated = value.eq_ignore_ascii_case("sspi") || parse_bool(&key, &value)?
+            }
+            "authentication" => match value.to_lowercase().replace(' ', "").as_str() {
+                "sqlpassword" => {}
+                _ => {
+                    return Err(invalid(format!(
+                        "unsupported Authentication '{}' (use SqlPassword, Integrated Security or an access token)",
+                        value
+                    )))
+                }
+            },
+            "encrypt" => {
+                options.encrypt = match value.to_lowercase().as_str() {
+                    "mandatory" | "strict" => true,
+                    "optional" => false,
+                    _ => parse_bool(&key, &value)?,
+                }
+            }
+            "trustservercertificate" | "trust server certificate" => {
+                options.trust_server_certificate = parse_bool(&key, &value)?
+            }
+            "application name" | "app" => options.application_name = value,
+            "connect timeout" | "connection timeout" | "timeout" => {
+                options.connect_timeout = parse_seconds(&key, &value)?
+            }
+            "command timeout" => options.command_timeout = parse_seconds(&key, &value)?,
+            _ => {}
+        }
+    }
+
+    // Without credentials, keep integrated authentication (or an access token set later)
+    if let (false, Some(user)) = (integrated, user) {
+        options.authentication = Authentication::SqlServer {
+            user,
+            password: password.unwrap_or_default(),
+        };
+    }
+    Ok(options)
+}
+
+/// `key=value` pairs separated by `;`, with lower-cased keys and optionally quoted values
+fn pairs(connection_string: &str) -> Result<Vec<(String, String)>> {
+    let mut pairs = Vec::new();
+    let mut chars = connection_string.chars().peekable();
+    loop {
+        let key: String = chars.by_ref().take_while(|c| *c != '=').collect();
+        let key = key.trim().trim_start_matches(';').trim().to_lowercase();
+        if key.is_empty() {
+            break;
+        }
+
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+        let value = match chars.peek() {
+            Some(&quote) if quote == '"' || quote == '\'' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        // A doubled quote stands for itself
+                        Some(c) if c == quote && chars.peek() == Some(&quote) => {
+                            value.push(c);
+                            chars.next();
+                        }
+                        Some(c) if c == quote => break,
+                        Some(c) => value.push(c),
+                        None => {
+                            return Err(invalid(format!("unterminated quoted value for '{}'", key)))
+                        }
+                    }
+                }
+                chars.by_ref().take_while(|c| *c != ';').for_each(drop);
+                value
+            }
+            _ => {
+                let value: String = chars.by_ref().take_while(|c| *c != ';').collect();
+                value.trim().to_string()
+            }
+        };
+        pairs.push((key.split_whitespace().collect::<Vec<_>>().join(" "), value));
+    }
+    Ok(pairs)
+}
+
+/// `tcp:host,port`, `host\instance`, `.` or `(local)`
+fn parse_server(value: &str, options: &mut ConnectionOptions) -> Result<()> {
+    let value = value.trim();
+    let value = value
+        .get(..4)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("tcp:"))
+        .map_or(value, |_| &value[4..]);
+    let (server, port) = match value.split_once(',') {
+        Some((server, port)) => {
+            let port = port
+                .trim()
+                .parse()
+                .map_err(|_| invalid(format!("invalid port in Server '{}'", value)))?;
+            (server.trim(), Some(port))
+        }
+        None => (value, None),
+    };
+    let (host, instance) = match server.split_once('\\') {
+        Some((host, instance)) => (host, Some(instance.to_string())),
+        None => (server, None),
+    };
+    options.host = match host {
+        "." | "(local)" => "localhost".to_string(),
+        host => host.to_string(),
+    };
+    options.instance = instance;
+    options.port = port;
+    Ok(())
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" => Ok(true),
+        "false" | "no" => Ok(false),
+        _ => Err(invalid(format!(
+            "'{}' is not a boolean for '{}'",
+            value, key
+        ))),
+    }
+}
+
+fn parse_seconds(key: &str, value: &str) -> Result<Duration> {
+    value.parse().map(Duration::from_secs).map_err(|_| {
+        invalid(format!(
+            "'{}' is not a number of seconds for '{}'",
+            value, key
+        ))
+    })
+}
+
+fn invalid(message: String) -> anyhow::Error {
+    SqlPackageError::InvalidConnectionString { message }.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sql_login() {
+        let options = parse(
+            "Server=tcp:sales.database.windows.net,1433;Initial Catalog=Sales;User ID=deploy;Password='p;w''d';Encrypt=True;TrustServerCertificate=False;Connection Timeout=30;Command Timeout=120",
+        )
+        .unwrap();
+        assert_eq!(options.host, "sales.database.windows.net");
+        assert_eq!(options.port, Some(1433));
+        assert_eq!(options.database.as_deref(), Some("Sales"));
+        assert_eq!(
+            options.authentication,
+            Authentication::SqlServer {
+                user: "deploy".to_string(),
+                password: "p;w'd".to_string(),
+            }
+        );
+        assert!(options.encrypt);
+        assert!(!options.trust_server_certificate);
+        assert_eq!(options.connect_timeout, Duration::from_secs(30));
+        assert_eq!(options.command_timeout, Duration::from_secs(120));
+        assert_eq!(
+            options.to_string(),
+            "Server=sales.database.windows.net,1433;Initial Catalog=Sales;User ID=deploy;Password=***"
+        );
+    }
+
+    #[test]
+    fn test_parse_integrated_named_instance() {
+        let options =
+            parse("Data Source=.\\SQLEXPRESS;Database=Dev;Integrated Security=SSPI;Pooling=false")
+                .unwrap();
+        assert_eq!(options.host, "localhost");
+        assert_eq!(options.instance.as_deref(), Some("SQLEXPRESS"));
+        assert_eq!(options.port, None);
+        assert_eq!(options.authentication, Authentication::Integrated);
+        assert_eq!(options.server_name(), "localhost\\SQLEXPRESS");
+    }
+
+    #[test]
+    fn test_parse_access_token() {
+        let options = parse("Server=db;Database=Dev").unwrap();
+        assert_eq!(options.authentication, Authentication::Integrated);
+        let options = options.with_access_token("token");
+        assert_eq!(
+            options.authentication,
+            Authentication::AzureAdToken("token".to_string())
+        );
+        let debug = format!("{:?}", options);
+        assert!(debug.contains("AzureAdToken(***)") && !debug.contains("\"token\""));
+        assert_eq!(
+            options.to_string(),
+            "Server=db;Initial Catalog=Dev;Access Token=***"
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("Server=db,port;Integrated Security=true").is_err());
+        assert!(parse("Server=db;Integrated Security=maybe").is_err());
+        assert!(parse("Server=db;Password='open").is_err());
+        assert!(parse("Server=db;Authentication=ActiveDirectoryMSI").is_err());
+    }
+}
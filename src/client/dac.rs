@@ -0,0 +1,166 @@
+//! Data-tier application registration and drift reports
+//!
+//! Registering a deployed dacpac records it, with its name and version, in the server's
+//! DAC registry (`msdb` on SQL Server, `master` on Azure SQL Database), as SqlPackage
+//! does with `/p:RegisterDataTierApplication=True`. The registered dacpac is then the
+//! baseline that [`drift_report`] compares the live schema against.
+
+use anyhow::Result;
+use chrono::NaiveDateTime;
+
+use super::drift::{
+    compared_type_codes, detect_drift, element_type_for, DatabaseObject, DriftReport,
+};
+use super::{Connection, ConnectionOptions};
+use crate::compare::reader::DacpacContents;
+use crate::error::SqlPackageError;
+
+/// A dacpac registered for a database
+#[derive(Debug, Clone)]
+pub struct RegisteredDacpac {
+    /// Application name from the dacpac's DacMetadata.xml
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    /// When the dacpac was registered, in server time
+    pub registered_at: NaiveDateTime,
+    /// The dacpac file
+    pub dacpac: Vec<u8>,
+}
+
+/// Register `dacpac` for the database of `options`, replacing any earlier registration
+pub async fn register_dacpac(
+    options: &ConnectionOptions,
+    dacpac: &[u8],
+    description: &str,
+) -> Result<()> {
+    let database = target_database(options)?;
+    let contents = DacpacContents::from_bytes(dacpac)?;
+    let (name, version) = dac_identity(&contents, &database);
+
+    let mut registry = Connection::connect(&registry_options(options).await?).await?;
+    registry
+        .execute(
+            "DECLARE @id UNIQUEIDENTIFIER = (SELECT instance_id FROM dbo.sysdac_instances WHERE instance_name = @P1);
+IF @id IS NOT NULL EXEC dbo.sp_sysdac_delete_instance @instance_id = @id;
+EXEC dbo.sp_sysdac_add_instance @type_name = @P2, @instance_name = @P1, @type_version = @P3, @description = @P4, @type_stream = @P5;",
+            &[&database, &name, &version, &description, &dacpac.to_vec()],
+        )
+        .await?;
+    Ok(())
+}
+
+/// The dacpac registered for the database of `options`, if any
+pub async fn registered_dacpac(options: &ConnectionOptions) -> Result<Option<RegisteredDacpac>> {
+    let database = target_database(options)?;
+    let mut registry = Connection::connect(&registry_options(options).await?).await?;
+    let rows = registry
+        .query_with(
+            "SELECT type_name, type_version, description, date_created, type_stream
+FROM dbo.sysdac_instances WHERE instance_name = @P1",
+            &[&database],
+        )
+        .await?;
+
+    Ok(rows.first().map(|row| RegisteredDacpac {
+        name: row.get::<&str, _>(0).unwrap_or_default().to_string(),
+        version: row.get::<&str, _>(1).unwrap_or_default().to_string(),
+        description: row.get::<&str, _>(2).unwrap_or_default().to_string(),
+        registered_at: row.get::<NaiveDateTime, _>(3).unwrap_or_default(),
+        dacpac: row.get::<&[u8], _>(4).unwrap_or_default().to_vec(),
+    }))
+}
+
+/// Compare the database of `options` with its registered dacpac
+pub async fn drift_report(options: &ConnectionOptions) -> Result<DriftReport> {
+    let registered =
+        registered_dacpac(options)
+            .await?
+            .ok_or_else(|| SqlPackageError::DacNotRegistered {
+                database: options.database.clone().unwrap_or_default(),
+            })?;
+    let contents = DacpacContents::from_bytes(&registered.dacpac)?;
+    let model_xml =
+        contents
+            .get_string("model.xml")
+            .ok_or_else(|| SqlPackageError::InvalidDacpac {
+                path: "<registered dacpac>".into(),
+                message: "no model.xml".to_string(),
+            })?;
+
+    let objects = database_objects(options).await?;
+    detect_drift(&model_xml, &objects, registered.registered_at)
+}
+
+/// User objects of the compared types in the database of `options`
+async fn database_objects(options: &ConnectionOptions) -> Result<Vec<DatabaseObject>> {
+    let type_codes: Vec<String> = compared_type_codes()
+        .map(|code| format!("'{}'", code))
+        .collect();
+    let sql = format!(
+        "SELECT s.name, o.name, o.type, o.modify_date,
+    QUOTENAME(OBJECT_SCHEMA_NAME(o.parent_object_id)) + N'.' + QUOTENAME(OBJECT_NAME(o.parent_object_id))
+FROM sys.objects AS o
+JOIN sys.schemas AS s ON s.schema_id = o.schema_id
+WHERE o.is_ms_shipped = 0 AND o.type IN ({})",
+        type_codes.join(", ")
+    );
+
+    let mut connection = Connection::connect(options).await?;
+    let rows = connection.query(&sql).await?;
+    Ok(rows
+        .iter()
+        .filter_map(|row| {
+            Some(DatabaseObject {
+                schema: row.get::<&str, _>(0)?.to_string(),
+                name: row.get::<&str, _>(1)?.to_string(),
+                element_type: element_type_for(row.get::<&str, _>(2)?)?.to_string(),
+                parent: row.get::<&str, _>(4).map(str::to_string),
+                modify_date: row.get::<NaiveDateTime, _>(3)?,
+            })
+        })
+        .collect())
+}
+
+fn target_database(options: &ConnectionOptions) -> Result<String> {
+    options.database.clone().ok_or_else(|| {
+        SqlPackageError::InvalidConnectionString {
+            message:
+                "a database (Initial Catalog) is required for data-tier application registration"
+                    .to_string(),
+        }
+        .into()
+    })
+}
+
+/// Connection options for the DAC registry: `master` on Azure SQL Database (engine
+/// edition 5), `msdb` elsewhere
+async fn registry_options(options: &ConnectionOptions) -> Result<ConnectionOptions> {
+    let mut connection = Connection::connect(options).await?;
+    let rows = connection
+        .query("SELECT CAST(SERVERPROPERTY('EngineEdition') AS INT)")
+        .await?;
+    let is_azure_database = rows.first().and_then(|row| row.get::<i32, _>(0)) == Some(5);
+
+    let mut registry = options.clone();
+    registry.database = Some(if is_azure_database { "master" } else { "msdb" }.to_string());
+    Ok(registry)
+}
+
+/// Application name and version from DacMetadata.xml, defaulting to the database name
+fn dac_identity(contents: &DacpacContents, database: &str) -> (String, String) {
+    let metadata = contents.get_string("DacMetadata.xml").unwrap_or_default();
+    let document = roxmltree::Document::parse(&metadata).ok();
+    let text = |tag: &str| {
+        document.as_ref().and_then(|d| {
+            d.descendants()
+                .find(|n| n.has_tag_name(tag))
+                .and_then(|n| n.text())
+                .map(str::to_string)
+        })
+    };
+    (
+        text("Name").unwrap_or_else(|| database.to_string()),
+        text("Version").unwrap_or_else(|| "1.0.0.0".to_string()),
+    )
+}
@@ -0,0 +1,336 @@
+//! Transaction and retry policy for running deployment batches
+//!
+//! A deployment runs the `GO`-separated batches of a script in order. [`run_batches`]
+//! runs them either in one transaction, so a failure leaves the database untouched, or
+//! in a transaction per batch, so a long deployment keeps the batches that succeeded.
+//!
+//! Azure SQL drops connections during failovers and throttles busy databases; those
+//! errors are transient and the work is retried after a backoff. In single-transaction
+//! mode the whole deployment is retried (the server rolled it back); in per-batch mode
+//! only the failed batch is.
+
+use std::time::Duration;
+
+use anyhow::Result;
+
+use super::{Connection, ConnectionOptions};
+use crate::error::SqlPackageError;
+
+/// SQL Server and Azure SQL error numbers worth retrying, as used by Microsoft.Data.SqlClient's
+/// configurable retry logic: the database is unavailable, reconfiguring, or throttling
+pub const TRANSIENT_ERROR_NUMBERS: &[u32] = &[
+    1205,  // deadlock victim
+    4060,  // cannot open database (often during failover)
+    4221,  // login to read-secondary failed (replica catching up)
+    10928, // resource limit reached
+    10929, // resource limit reached
+    40143, // the service is busy
+    40197, // error processing the request (failover, upgrade)
+    40501, // the service is busy
+    40540, // the service has encountered an error
+    40613, // database not currently available
+    42108, // cannot connect to the SQL pool
+    42109, // the SQL pool is warming up
+    49918, // not enough resources
+    49919, // too many create or update operations
+    49920, // too many operations in progress
+];
+
+/// How batches are grouped into transactions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransactionMode {
+    /// All batches in one transaction, rolled back on failure
+    #[default]
+    Single,
+    /// Each batch in its own transaction
+    PerBatch,
+}
+
+impl std::str::FromStr for TransactionMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "single" => Ok(TransactionMode::Single),
+            "per-batch" | "perbatch" => Ok(TransactionMode::PerBatch),
+            _ => Err(format!("Unknown transaction mode: {}", s)),
+        }
+    }
+}
+
+/// Retries of transient failures, with exponential backoff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt (0 disables retrying)
+    pub max_retries: u32,
+    /// Wait before the first retry; doubled for each further retry
+    pub initial_delay: Duration,
+    /// Longest wait between retries
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retry
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Wait before retry number `retry` (1-based)
+    pub fn delay(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_delay
+            .checked_mul(factor)
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay))
+    }
+}
+
+/// Transaction, retry and timeout settings for a deployment
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeploymentOptions {
+    pub transaction_mode: TransactionMode,
+    pub retry: RetryPolicy,
+    /// Time allowed for each batch, overriding the connection's command timeout
+    pub command_timeout: Option<Duration>,
+}
+
+/// What a completed deployment did
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeploymentOutcome {
+    pub batches_executed: usize,
+    /// Transient failures that were retried
+    pub retries: u32,
+}
+
+/// Whether an error from [`Connection`] is transient: a connection that could not be
+/// opened or was dropped, or a server error in [`TRANSIENT_ERROR_NUMBERS`]
+pub fn is_transient(error: &anyhow::Error) -> bool {
+    match error.downcast_ref::<SqlPackageError>() {
+        Some(SqlPackageError::ConnectionError { number, .. }) => {
+            number.is_none_or(|n| TRANSIENT_ERROR_NUMBERS.contains(&n))
+        }
+        Some(SqlPackageError::QueryError {
+            number: Some(number),
+            ..
+        }) => TRANSIENT_ERROR_NUMBERS.contains(number),
+        _ => false,
+    }
+}
+
+/// Run `batches` in order with the transaction and retry policy of `options`
+pub async fn run_batches(
+    connection: &ConnectionOptions,
+    batches: &[String],
+    options: &DeploymentOptions,
+) -> Result<DeploymentOutcome> {
+    let mut connection = connection.clone();
+    if let Some(timeout) = options.command_timeout {
+        connection.command_timeout = timeout;
+    }
+    match options.transaction_mode {
+        TransactionMode::Single => {
+            run_single_transaction(&connection, batches, &options.retry).await
+        }
+        TransactionMode::PerBatch => run_per_batch(&connection, batches, &options.retry).await,
+    }
+}
+
+async fn run_single_transaction(
+    options: &ConnectionOptions,
+    batches: &[String],
+    retry: &RetryPolicy,
+) -> Result<DeploymentOutcome> {
+    let mut retries = 0;
+    loop {
+        match try_single_transaction(options, batches).await {
+            Ok(()) => {
+                return Ok(DeploymentOutcome {
+                    batches_executed: batches.len(),
+                    retries,
+                })
+            }
+            Err((_, error)) if is_transient(&error) && retries < retry.max_retries => {
+                retries += 1;
+                tokio::time::sleep(retry.delay(retries)).await;
+            }
+            Err((batch, error)) => return Err(batch_error(batch, error)),
+        }
+    }
+}
+
+/// Run every batch in one transaction; on failure, the 1-based failing batch (0 while
+/// connecting) and the error
+async fn try_single_transaction(
+    options: &ConnectionOptions,
+    batches: &[String],
+) -> std::result::Result<(), (usize, anyhow::Error)> {
+    let mut connection = Connection::connect(options).await.map_err(|e| (0, e))?;
+    connection
+        .execute_batch("SET XACT_ABORT ON; BEGIN TRANSACTION;")
+        .await
+        .map_err(|e| (0, e))?;
+    for (index, batch) in batches.iter().enumerate() {
+        if let Err(error) = connection.execute_batch(batch).await {
+            rollback(&mut connection).await;
+            return Err((index + 1, error));
+        }
+    }
+    connection
+        .execute_batch("COMMIT TRANSACTION;")
+        .await
+        .map_err(|e| (batches.len(), commit_failed(e)))
+}
+
+async fn run_per_batch(
+    options: &ConnectionOptions,
+    batches: &[String],
+    retry: &RetryPolicy,
+) -> Result<DeploymentOutcome> {
+    let mut retries = 0;
+    let mut connection: Option<Connection> = None;
+    for (index, batch) in batches.iter().enumerate() {
+        let mut attempt = 0;
+        loop {
+            let result = match connection.as_mut() {
+                Some(open) => run_in_transaction(open, batch).await,
+                None => match Connection::connect(options).await {
+                    Ok(open) => run_in_transaction(connection.insert(open), batch).await,
+                    Err(error) => Err(error),
+                },
+            };
+            match result {
+                Ok(()) => break,
+                Err(error) if is_transient(&error) && attempt < retry.max_retries => {
+                    // Reconnect: the failure may have dropped or broken the connection
+                    connection = None;
+                    attempt += 1;
+                    retries += 1;
+                    tokio::time::sleep(retry.delay(attempt)).await;
+                }
+                Err(error) => return Err(batch_error(index + 1, error)),
+            }
+        }
+    }
+    Ok(DeploymentOutcome {
+        batches_executed: batches.len(),
+        retries,
+    })
+}
+
+async fn run_in_transaction(connection: &mut Connection, batch: &str) -> Result<()> {
+    connection
+        .execute_batch("SET XACT_ABORT ON; BEGIN TRANSACTION;")
+        .await?;
+    if let Err(error) = connection.execute_batch(batch).await {
+        rollback(connection).await;
+        return Err(error);
+    }
+    connection
+        .execute_batch("COMMIT TRANSACTION;")
+        .await
+        .map_err(commit_failed)
+}
+
+/// A failed COMMIT may or may not have been applied, so it is never retried
+fn commit_failed(error: anyhow::Error) -> anyhow::Error {
+    SqlPackageError::QueryError {
+        message: format!(
+            "COMMIT failed and the changes may or may not have been applied: {}",
+            error
+        ),
+        number: None,
+    }
+    .into()
+}
+
+/// Roll back whatever is still open; XACT_ABORT may already have done so
+async fn rollback(connection: &mut Connection) {
+    if !connection.is_broken() {
+        let _ = connection
+            .execute_batch("IF @@TRANCOUNT > 0 ROLLBACK TRANSACTION;")
+            .await;
+    }
+}
+
+fn batch_error(batch: usize, error: anyhow::Error) -> anyhow::Error {
+    if batch == 0 {
+        return error;
+    }
+    SqlPackageError::DeploymentError {
+        batch,
+        message: error.to_string(),
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.delay(1), Duration::from_secs(1));
+        assert_eq!(policy.delay(2), Duration::from_secs(2));
+        assert_eq!(policy.delay(4), Duration::from_secs(8));
+        assert_eq!(policy.delay(10), Duration::from_secs(30));
+        assert_eq!(policy.delay(100), Duration::from_secs(30));
+        assert_eq!(RetryPolicy::none().max_retries, 0);
+    }
+
+    #[test]
+    fn test_is_transient() {
+        let query_error = |number| -> anyhow::Error {
+            SqlPackageError::QueryError {
+                message: "failed".to_string(),
+                number,
+            }
+            .into()
+        };
+        assert!(is_transient(&query_error(Some(40613))));
+        assert!(is_transient(&query_error(Some(1205))));
+        assert!(!is_transient(&query_error(Some(2714))));
+        assert!(!is_transient(&query_error(None)));
+
+        let connection_error = |number| -> anyhow::Error {
+            SqlPackageError::ConnectionError {
+                server: "db".to_string(),
+                message: "failed".to_string(),
+                number,
+            }
+            .into()
+        };
+        assert!(is_transient(&connection_error(None)));
+        assert!(is_transient(&connection_error(Some(40501))));
+        // Login failed
+        assert!(!is_transient(&connection_error(Some(18456))));
+
+        let timeout: anyhow::Error = SqlPackageError::CommandTimeout { seconds: 30 }.into();
+        assert!(!is_transient(&timeout));
+    }
+
+    #[test]
+    fn test_transaction_mode_from_str() {
+        assert_eq!(
+            "per-batch".parse::<TransactionMode>().unwrap(),
+            TransactionMode::PerBatch
+        );
+        assert_eq!(
+            "Single".parse::<TransactionMode>().unwrap(),
+            TransactionMode::Single
+        );
+        assert!("none".parse::<TransactionMode>().is_err());
+    }
+}
@@ -0,0 +1,277 @@
+//! Drift between a registered dacpac and the live schema
+//!
+//! A database registered as a data-tier application keeps the dacpac it was deployed
+//! from. Comparing that model with `sys.objects` finds changes made outside deployments,
+//! reported in the format of `sqlpackage /Action:DriftReport`:
+//!
+//! - **Additions:** objects in the database that are not in the registered model
+//! - **Removals:** objects in the model that are no longer in the database
+//! - **Modifications:** objects altered after the dacpac was registered
+//!
+//! Tables, views, procedures, functions, triggers and sequences are compared.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::Result;
+use chrono::NaiveDateTime;
+use quick_xml::escape::escape;
+use roxmltree::{Document, Node};
+
+/// Model element types for the `sys.objects` type codes that are compared
+const OBJECT_TYPES: &[(&str, &str)] = &[
+    ("U", "SqlTable"),
+    ("V", "SqlView"),
+    ("P", "SqlProcedure"),
+    ("FN", "SqlScalarFunction"),
+    ("IF", "SqlInlineTableValuedFunction"),
+    ("TF", "SqlMultiStatementTableValuedFunction"),
+    ("TR", "SqlDmlTrigger"),
+    ("SO", "SqlSequence"),
+];
+
+/// `sys.objects` type codes that [`detect_drift`] compares, for use in queries
+pub fn compared_type_codes() -> impl Iterator<Item = &'static str> {
+    OBJECT_TYPES.iter().map(|(code, _)| *code)
+}
+
+/// Model element type for a `sys.objects` type code
+pub fn element_type_for(type_code: &str) -> Option<&'static str> {
+    OBJECT_TYPES
+        .iter()
+        .find(|(code, _)| *code == type_code.trim())
+        .map(|(_, element_type)| *element_type)
+}
+
+/// An object in the live database
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatabaseObject {
+    pub schema: String,
+    pub name: String,
+    /// Model element type, e.g. `SqlTable`
+    pub element_type: String,
+    /// Table a trigger is defined on, as `[schema].[name]`
+    pub parent: Option<String>,
+    pub modify_date: NaiveDateTime,
+}
+
+/// An object reported as drifted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DriftObject {
+    /// `[schema].[name]`
+    pub name: String,
+    /// Table a trigger is defined on, or empty
+    pub parent: String,
+    pub element_type: String,
+}
+
+/// Objects added, removed and modified outside deployments
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DriftReport {
+    pub additions: Vec<DriftObject>,
+    pub removals: Vec<DriftObject>,
+    pub modifications: Vec<DriftObject>,
+}
+
+impl DriftReport {
+    /// Whether the database matches its registered model
+    pub fn is_empty(&self) -> bool {
+        self.additions.is_empty() && self.removals.is_empty() && self.modifications.is_empty()
+    }
+
+    /// The report as DriftReport XML, as written by `sqlpackage /Action:DriftReport`
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        xml.push_str(
+            "<DriftReport xmlns=\"http://schemas.microsoft.com/sqlserver/dac/DriftReport/2012/02\">\n",
+        );
+        for (section, objects) in [
+            ("Additions", &self.additions),
+            ("Removals", &self.removals),
+            ("Modifications", &self.modifications),
+        ] {
+            if objects.is_empty() {
+                xml.push_str(&format!("  <{} />\n", section));
+                continue;
+            }
+            xml.push_str(&format!("  <{}>\n", section));
+            for object in objects {
+                xml.push_str(&format!(
+                    "    <Object Name=\"{}\" Parent=\"{}\" Type=\"{}\" />\n",
+                    escape(&object.name),
+                    escape(&object.parent),
+                    escape(&object.element_type)
+                ));
+            }
+            xml.push_str(&format!("  </{}>\n", section));
+        }
+        xml.push_str("</DriftReport>\n");
+        xml
+    }
+}
+
+/// Compare the registered model with the database's objects
+///
+/// `registered_at` is when the dacpac was registered; objects modified later are
+/// reported as modifications. Names are compared case-insensitively.
+pub fn detect_drift(
+    model_xml: &str,
+    objects: &[DatabaseObject],
+    registered_at: NaiveDateTime,
+) -> Result<DriftReport> {
+    let document = Document::parse(model_xml)?;
+    let model: BTreeMap<(String, String), DriftObject> = document
+        .descendants()
+        .filter(|n| n.has_tag_name("Element") && n.parent_element().is_some_and(is_model))
+        .filter_map(|element| {
+            let element_type = element.attribute("Type")?;
+            OBJECT_TYPES.iter().find(|(_, t)| *t == element_type)?;
+            let name = element.attribute("Name")?;
+            let object = DriftObject {
+                name: name.to_string(),
+                parent: reference(element, "Parent").unwrap_or_default(),
+                element_type: element_type.to_string(),
+            };
+            Some(((element_type.to_string(), name.to_lowercase()), object))
+        })
+        .collect();
+
+    let mut report = DriftReport::default();
+    let mut found = BTreeSet::new();
+    for object in objects {
+        let name = format!(
+            "[{}].[{}]",
+            object.schema.replace(']', "]]"),
+            object.name.replace(']', "]]")
+        );
+        let key = (object.element_type.clone(), name.to_lowercase());
+        let drift = DriftObject {
+            name,
+            parent: object.parent.clone().unwrap_or_default(),
+            element_type: object.element_type.clone(),
+        };
+        if model.contains_key(&key) {
+            if object.modify_date > registered_at {
+                report.modifications.push(drift);
+            }
+            found.insert(key);
+        } else {
+            report.additions.push(drift);
+        }
+    }
+    report.removals = model
+        .into_iter()
+        .filter(|(key, _)| !found.contains(key))
+        .map(|(_, object)| object)
+        .collect();
+    Ok(report)
+}
+
+fn is_model(node: Node) -> bool {
+    node.has_tag_name("Model")
+}
+
+/// Name referenced by an element's relationship, e.g. a trigger's `Parent`
+fn reference(element: Node, relationship: &str) -> Option<String> {
+    element
+        .children()
+        .find(|n| n.has_tag_name("Relationship") && n.attribute("Name") == Some(relationship))?
+        .descendants()
+        .find(|n| n.has_tag_name("References"))?
+        .attribute("Name")
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODEL: &str = r#"<DataSchemaModel xmlns="http://schemas.microsoft.com/sqlserver/dac/Serialization/2012/02">
+  <Model>
+    <Element Type="SqlSchema" Name="[sales]" />
+    <Element Type="SqlTable" Name="[dbo].[Orders]" />
+    <Element Type="SqlTable" Name="[dbo].[Legacy]" />
+    <Element Type="SqlView" Name="[dbo].[OpenOrders]" />
+    <Element Type="SqlDmlTrigger" Name="[dbo].[TR_Orders]">
+      <Relationship Name="Parent">
+        <Entry>
+          <References Name="[dbo].[Orders]" />
+        </Entry>
+      </Relationship>
+    </Element>
+  </Model>
+</DataSchemaModel>"#;
+
+    fn at(time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn object(name: &str, element_type: &str, modified: &str) -> DatabaseObject {
+        DatabaseObject {
+            schema: "dbo".to_string(),
+            name: name.to_string(),
+            element_type: element_type.to_string(),
+            parent: None,
+            modify_date: at(modified),
+        }
+    }
+
+    #[test]
+    fn test_detect_drift() {
+        let objects = vec![
+            object("orders", "SqlTable", "2026-01-02 09:00"),
+            object("OpenOrders", "SqlView", "2026-01-01 09:00"),
+            object("TR_Orders", "SqlDmlTrigger", "2026-01-01 09:00"),
+            object("Hotfix", "SqlProcedure", "2026-01-03 09:00"),
+        ];
+        let report = detect_drift(MODEL, &objects, at("2026-01-01 12:00")).unwrap();
+
+        let names = |objects: &[DriftObject]| -> Vec<String> {
+            objects.iter().map(|o| o.name.clone()).collect()
+        };
+        assert_eq!(names(&report.additions), vec!["[dbo].[Hotfix]"]);
+        assert_eq!(names(&report.removals), vec!["[dbo].[Legacy]"]);
+        assert_eq!(names(&report.modifications), vec!["[dbo].[orders]"]);
+        assert!(!report.is_empty());
+
+        let unchanged = vec![
+            object("Orders", "SqlTable", "2026-01-01 09:00"),
+            object("Legacy", "SqlTable", "2026-01-01 09:00"),
+            object("OpenOrders", "SqlView", "2026-01-01 09:00"),
+            object("TR_Orders", "SqlDmlTrigger", "2026-01-01 09:00"),
+        ];
+        assert!(detect_drift(MODEL, &unchanged, at("2026-01-01 12:00"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_drift_report_xml() {
+        let report = DriftReport {
+            additions: vec![DriftObject {
+                name: "[dbo].[TR_Audit]".to_string(),
+                parent: "[dbo].[Orders]".to_string(),
+                element_type: "SqlDmlTrigger".to_string(),
+            }],
+            ..DriftReport::default()
+        };
+        assert_eq!(
+            report.to_xml(),
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<DriftReport xmlns="http://schemas.microsoft.com/sqlserver/dac/DriftReport/2012/02">
+  <Additions>
+    <Object Name="[dbo].[TR_Audit]" Parent="[dbo].[Orders]" Type="SqlDmlTrigger" />
+  </Additions>
+  <Removals />
+  <Modifications />
+</DriftReport>
+"#
+        );
+    }
+
+    #[test]
+    fn test_element_type_for() {
+        assert_eq!(element_type_for("U "), Some("SqlTable"));
+        assert_eq!(element_type_for("TR"), Some("SqlDmlTrigger"));
+        assert_eq!(element_type_for("PK"), None);
+    }
+}
@@ -0,0 +1,330 @@
+//! SQL Server connections (enabled with the `client` feature)
+//!
+//! The foundation for working against a live database (extract, publish, comparing a
+//! project with a deployed schema). Connections speak TDS through tiberius and support
+//! SQL Server logins, integrated authentication (the current Windows user, via SSPI) and
+//! Azure AD access tokens, with connect and command timeouts and a connection pool:
+//!
+//! ```rust,ignore
+//! use rust_sqlpackage::client::{ConnectionOptions, ConnectionPool};
+//!
+//! let options = ConnectionOptions::from_connection_string(
+//!     "Server=tcp:sales.database.windows.net,1433;Initial Catalog=Sales;Command Timeout=120",
+//! )?
+//! .with_access_token(token);
+//! let pool = ConnectionPool::new(options, 4);
+//! let mut connection = pool.get().await?;
+//! let tables = connection.query("SELECT name FROM sys.tables").await?;
+//! ```
+
+mod connection_string;
+pub mod dac;
+pub mod deploy;
+pub mod drift;
+mod pool;
+mod settings;
+
+pub use dac::{drift_report, register_dacpac, registered_dacpac, RegisteredDacpac};
+pub use deploy::{DeploymentOptions, RetryPolicy, TransactionMode};
+pub use drift::DriftReport;
+pub use pool::{ConnectionPool, PooledConnection};
+pub use settings::{resolve_connection, ACCESS_TOKEN_ENV, CONNECTION_STRING_ENV, PASSWORD_ENV};
+
+use std::fmt;
+use std::time::Duration;
+
+use anyhow::Result;
+use tiberius::{AuthMethod, Client, Config, EncryptionLevel, Row, SqlBrowser, ToSql};
+use tokio::net::TcpStream;
+use tokio_util::compat::{Compat, TokioAsyncWriteCompatExt};
+
+use crate::error::SqlPackageError;
+
+/// How to authenticate with the server
+#[derive(Clone, PartialEq, Eq)]
+pub enum Authentication {
+    /// SQL Server login and password
+    SqlServer { user: String, password: String },
+    /// The current Windows user (SSPI); only supported on Windows
+    Integrated,
+    /// Azure AD access token for `https://database.windows.net/`, e.g. from
+    /// `az account get-access-token --resource https://database.windows.net/`
+    AzureAdToken(String),
+}
+
+// Keep passwords and tokens out of logs and error messages
+impl fmt::Debug for Authentication {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Authentication::SqlServer { user, .. } => f
+                .debug_struct("SqlServer")
+                .field("user", user)
+                .field("password", &"***")
+                .finish(),
+            Authentication::Integrated => write!(f, "Integrated"),
+            Authentication::AzureAdToken(_) => write!(f, "AzureAdToken(***)"),
+        }
+    }
+}
+
+/// Server, database, credentials and timeouts for a connection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionOptions {
+    /// Host name or IP address
+    pub host: String,
+    /// TCP port (defaults to 1433, or the SQL Browser's answer for a named instance)
+    pub port: Option<u16>,
+    /// Named instance, resolved through the SQL Browser service when no port is given
+    pub instance: Option<String>,
+    /// Initial database (the login's default database when not set)
+    pub database: Option<String>,
+    pub authentication: Authentication,
+    /// Encrypt the whole connection rather than only the login
+    pub encrypt: bool,
+    /// Accept the server certificate without validating it
+    pub trust_server_certificate: bool,
+    /// Reported to the server as the client application
+    pub application_name: String,
+    /// Time allowed to open a connection and log in
+    pub connect_timeout: Duration,
+    /// Time allowed for each command
+    pub command_timeout: Duration,
+}
+
+impl Default for ConnectionOptions {
+    fn default() -> Self {
+        Self {
+            host: "localhost".to_string(),
+            port: None,
+            instance: None,
+            database: None,
+            authentication: Authentication::Integrated,
+            encrypt: true,
+            trust_server_certificate: false,
+            application_name: "rust-sqlpackage".to_string(),
+            connect_timeout: Duration::from_secs(15),
+            command_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl ConnectionOptions {
+    /// Parse an ADO.NET connection string, as accepted by SqlPackage's
+    /// `/SourceConnectionString` and `/TargetConnectionString`
+    pub fn from_connection_string(connection_string: &str) -> Result<Self> {
+        connection_string::parse(connection_string)
+    }
+
+    /// Authenticate with an Azure AD access token instead of the connection string's credentials
+    pub fn with_access_token(mut self, token: impl Into<String>) -> Self {
+        self.authentication = Authentication::AzureAdToken(token.into());
+        self
+    }
+
+    /// `host`, `host\instance` or `host,port`, for messages
+    pub fn server_name(&self) -> String {
+        match (&self.instance, self.port) {
+            (_, Some(port)) => format!("{},{}", self.host, port),
+            (Some(instance), None) => format!("{}\\{}", self.host, instance),
+            (None, None) => self.host.clone(),
+        }
+    }
+
+    fn to_config(&self) -> Result<Config> {
+        let mut config = Config::new();
+        config.host(&self.host);
+        if let Some(port) = self.port {
+            config.port(port);
+        }
+        if let Some(instance) = &self.instance {
+            config.instance_name(instance);
+        }
+        if let Some(database) = &self.database {
+            config.database(database);
+        }
+        config.application_name(&self.application_name);
+        config.encryption(if self.encrypt {
+            EncryptionLevel::Required
+        } else {
+            EncryptionLevel::Off
+        });
+        if self.trust_server_certificate {
+            config.trust_cert();
+        }
+        config.authentication(self.auth_method()?);
+        Ok(config)
+    }
+
+    fn auth_method(&self) -> Result<AuthMethod> {
+        match &self.authentication {
+            Authentication::SqlServer { user, password } => {
+                Ok(AuthMethod::sql_server(user, password))
+            }
+            Authentication::AzureAdToken(token) => Ok(AuthMethod::aad_token(token)),
+            #[cfg(windows)]
+            Authentication::Integrated => Ok(AuthMethod::Integrated),
+            #[cfg(not(windows))]
+            Authentication::Integrated => Err(SqlPackageError::InvalidConnectionString {
+                message: "integrated authentication is only supported on Windows; use a SQL Server login or an Azure AD access token".to_string(),
+            }
+            .into()),
+        }
+    }
+}
+
+/// The options as a connection string, with the password or access token masked
+impl fmt::Display for ConnectionOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Server={}", self.server_name())?;
+        if let Some(database) = &self.database {
+            write!(f, ";Initial Catalog={}", database)?;
+        }
+        match &self.authentication {
+            Authentication::SqlServer { user, .. } => {
+                write!(f, ";User ID={};Password=***", user)
+            }
+            Authentication::Integrated => write!(f, ";Integrated Security=SSPI"),
+            Authentication::AzureAdToken(_) => write!(f, ";Access Token=***"),
+        }
+    }
+}
+
+/// An open connection to SQL Server
+pub struct Connection {
+    client: Client<Compat<TcpStream>>,
+    command_timeout: Duration,
+    /// Set when a command timed out part-way, leaving unread data on the stream
+    broken: bool,
+}
+
+impl Connection {
+    /// Connect and log in, following Azure SQL gateway redirects
+    pub async fn connect(options: &ConnectionOptions) -> Result<Self> {
+        let mut config = options.to_config()?;
+        let use_browser = options.instance.is_some() && options.port.is_none();
+
+        let connect = async {
+            let tcp = if use_browser {
+                TcpStream::connect_named(&config).await?
+            } else {
+                TcpStream::connect(config.get_addr()).await?
+            };
+            tcp.set_nodelay(true)?;
+            match Client::connect(config.clone(), tcp.compat_write()).await {
+                Err(tiberius::error::Error::Routing { host, port }) => {
+                    config.host(&host);
+                    config.port(port);
+                    let tcp = TcpStream::connect(config.get_addr()).await?;
+                    tcp.set_nodelay(true)?;
+                    Client::connect(config, tcp.compat_write()).await
+                }
+                result => result,
+            }
+        };
+
+        let connection_error = |message: String, number| SqlPackageError::ConnectionError {
+            server: options.server_name(),
+            message,
+            number,
+        };
+        let client = tokio::time::timeout(options.connect_timeout, connect)
+            .await
+            .map_err(|_| {
+                connection_error(
+                    format!("timed out after {}s", options.connect_timeout.as_secs()),
+                    None,
+                )
+            })?
+            .map_err(|e| connection_error(e.to_string(), error_number(&e)))?;
+
+        Ok(Self {
+            client,
+            command_timeout: options.command_timeout,
+            broken: false,
+        })
+    }
+
+    /// Run a batch of statements, discarding any results
+    pub async fn execute_batch(&mut self, sql: &str) -> Result<()> {
+        let command_timeout = self.command_timeout;
+        let client = &mut self.client;
+        let result = tokio::time::timeout(command_timeout, async {
+            client.simple_query(sql).await?.into_results().await
+        })
+        .await;
+        self.finish(result).map(|_| ())
+    }
+
+    /// Run a query and return the rows of its first result set
+    pub async fn query(&mut self, sql: &str) -> Result<Vec<Row>> {
+        let command_timeout = self.command_timeout;
+        let client = &mut self.client;
+        let result = tokio::time::timeout(command_timeout, async {
+            client.simple_query(sql).await?.into_first_result().await
+        })
+        .await;
+        self.finish(result)
+    }
+
+    /// Run a parameterized statement (parameters are `@P1`, `@P2`, ...) and return the
+    /// number of rows affected
+    pub async fn execute(&mut self, sql: &str, params: &[&dyn ToSql]) -> Result<u64> {
+        let command_timeout = self.command_timeout;
+        let client = &mut self.client;
+        let result = tokio::time::timeout(command_timeout, async {
+            Ok(client.execute(sql, params).await?.total())
+        })
+        .await;
+        self.finish(result)
+    }
+
+    /// Run a parameterized query and return the rows of its first result set
+    pub async fn query_with(&mut self, sql: &str, params: &[&dyn ToSql]) -> Result<Vec<Row>> {
+        let command_timeout = self.command_timeout;
+        let client = &mut self.client;
+        let result = tokio::time::timeout(command_timeout, async {
+            client.query(sql, params).await?.into_first_result().await
+        })
+        .await;
+        self.finish(result)
+    }
+
+    /// The underlying tiberius client, for streaming results and bulk loads
+    pub fn client_mut(&mut self) -> &mut Client<Compat<TcpStream>> {
+        &mut self.client
+    }
+
+    /// Whether the connection can no longer be used (a command timed out mid-result)
+    pub fn is_broken(&self) -> bool {
+        self.broken
+    }
+
+    fn finish<T>(
+        &mut self,
+        result: std::result::Result<tiberius::Result<T>, tokio::time::error::Elapsed>,
+    ) -> Result<T> {
+        match result {
+            Ok(Ok(value)) => Ok(value),
+            Ok(Err(e)) => Err(SqlPackageError::QueryError {
+                message: e.to_string(),
+                number: error_number(&e),
+            }
+            .into()),
+            Err(_) => {
+                self.broken = true;
+                Err(SqlPackageError::CommandTimeout {
+                    seconds: self.command_timeout.as_secs(),
+                }
+                .into())
+            }
+        }
+    }
+}
+
+/// SQL Server error number of a server-reported error
+fn error_number(error: &tiberius::error::Error) -> Option<u32> {
+    match error {
+        tiberius::error::Error::Server(token) => Some(token.code()),
+        _ => None,
+    }
+}
@@ -0,0 +1,102 @@
+//! A small pool of reusable connections
+//!
+//! Deployment runs many short commands against the same database; reusing logged-in
+//! connections avoids a TLS handshake and login per command. The pool opens
+//! connections on demand up to `max_size` and hands idle ones back out, dropping any
+//! that a timed-out command left unusable.
+
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use super::{Connection, ConnectionOptions};
+use crate::error::SqlPackageError;
+
+/// Connections to one server and database, shared between tasks
+#[derive(Clone)]
+pub struct ConnectionPool {
+    options: Arc<ConnectionOptions>,
+    idle: Arc<Mutex<Vec<Connection>>>,
+    permits: Arc<Semaphore>,
+}
+
+impl ConnectionPool {
+    /// A pool opening at most `max_size` (at least 1) connections at a time
+    pub fn new(options: ConnectionOptions, max_size: usize) -> Self {
+        Self {
+            options: Arc::new(options),
+            idle: Arc::new(Mutex::new(Vec::new())),
+            permits: Arc::new(Semaphore::new(max_size.max(1))),
+        }
+    }
+
+    pub fn options(&self) -> &ConnectionOptions {
+        &self.options
+    }
+
+    /// An idle connection, or a new one; waits while `max_size` connections are in use
+    pub async fn get(&self) -> Result<PooledConnection> {
+        let permit = self.permits.clone().acquire_owned().await.map_err(|_| {
+            SqlPackageError::ConnectionError {
+                server: self.options.server_name(),
+                message: "connection pool is closed".to_string(),
+                number: None,
+            }
+        })?;
+
+        let idle = self.idle.lock().ok().and_then(|mut idle| idle.pop());
+        let connection = match idle {
+            Some(connection) => connection,
+            None => Connection::connect(&self.options).await?,
+        };
+        Ok(PooledConnection {
+            connection: Some(connection),
+            idle: Arc::clone(&self.idle),
+            _permit: permit,
+        })
+    }
+
+    /// Number of open connections waiting to be reused
+    pub fn idle_count(&self) -> usize {
+        self.idle.lock().map(|idle| idle.len()).unwrap_or(0)
+    }
+}
+
+/// A connection borrowed from a [`ConnectionPool`], returned to it when dropped
+pub struct PooledConnection {
+    connection: Option<Connection>,
+    idle: Arc<Mutex<Vec<Connection>>>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Deref for PooledConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.connection
+            .as_ref()
+            .expect("connection is only taken on drop")
+    }
+}
+
+impl DerefMut for PooledConnection {
+    fn deref_mut(&mut self) -> &mut Connection {
+        self.connection
+            .as_mut()
+            .expect("connection is only taken on drop")
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            if !connection.is_broken() {
+                if let Ok(mut idle) = self.idle.lock() {
+                    idle.push(connection);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Connection settings from the command line, the config file and the environment
+//!
+//! Secrets need not appear on command lines or in config files. A connection string
+//! without a password is completed from an environment variable, and a named connection
+//! in `.sqlpackage.toml` only says which variables to read:
+//!
+//! ```toml
+//! [connections.staging]
+//! connection_string = "Server=tcp:staging.database.windows.net;Initial Catalog=Sales;User ID=deploy"
+//! password_env = "STAGING_DB_PASSWORD"
+//! ```
+
+use anyhow::Result;
+
+use super::{Authentication, ConnectionOptions};
+use crate::config::ConnectionConfig;
+use crate::error::SqlPackageError;
+
+/// Environment variable holding the connection string when none is given otherwise
+pub const CONNECTION_STRING_ENV: &str = "SQLPACKAGE_CONNECTION_STRING";
+/// Environment variable holding the password of a connection string's `User ID`
+pub const PASSWORD_ENV: &str = "SQLPACKAGE_PASSWORD";
+/// Environment variable holding an Azure AD access token
+pub const ACCESS_TOKEN_ENV: &str = "SQLPACKAGE_ACCESS_TOKEN";
+
+/// Connection options from the first of: `connection_string`, the named connection
+/// `connection` and `SQLPACKAGE_CONNECTION_STRING`
+///
+/// A SQL Server login without a password takes it from the connection's `password_env`
+/// or `SQLPACKAGE_PASSWORD`. An access token comes from `access_token` or, unless the
+/// connection string names a SQL Server login, the connection's `access_token_env` or
+/// `SQLPACKAGE_ACCESS_TOKEN`.
+pub fn resolve_connection(
+    connection_string: Option<&str>,
+    connection: Option<&ConnectionConfig>,
+    access_token: Option<String>,
+) -> Result<ConnectionOptions> {
+    resolve_with(connection_string, connection, access_token, |name| {
+        std::env::var(name).ok().filter(|value| !value.is_empty())
+    })
+}
+
+fn resolve_with(
+    connection_string: Option<&str>,
+    connection: Option<&ConnectionConfig>,
+    access_token: Option<String>,
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<ConnectionOptions> {
+    let mut options = match (connection_string, connection) {
+        (Some(connection_string), _) => {
+            ConnectionOptions::from_connection_string(connection_string)?
+        }
+        (None, Some(connection)) => named_connection(connection, &env)?,
+        (None, None) => {
+            let connection_string = env(CONNECTION_STRING_ENV).ok_or_else(|| {
+                invalid(format!(
+                    "no connection string; pass --connection-string or --connection-name, or set {}",
+                    CONNECTION_STRING_ENV
+                ))
+            })?;
+            ConnectionOptions::from_connection_string(&connection_string)?
+        }
+    };
+
+    if let Authentication::SqlServer { password, .. } = &mut options.authentication {
+        if password.is_empty() {
+            let named = required_var(&env, connection.and_then(|c| c.password_env.as_deref()))?;
+            if let Some(value) = named.or_else(|| env(PASSWORD_ENV)) {
+                *password = value;
+            }
+        }
+    }
+
+    // A token left in the environment does not override a login given explicitly
+    let access_token = match access_token {
+        Some(token) => Some(token),
+        None if matches!(options.authentication, Authentication::SqlServer { .. }) => None,
+        None => required_var(&env, connection.and_then(|c| c.access_token_env.as_deref()))?
+            .or_else(|| env(ACCESS_TOKEN_ENV)),
+    };
+    Ok(match access_token {
+        Some(token) => options.with_access_token(token),
+        None => options,
+    })
+}
+
+/// Connection options of a `[connections.<name>]` entry, before adding its secrets
+fn named_connection(
+    connection: &ConnectionConfig,
+    env: &impl Fn(&str) -> Option<String>,
+) -> Result<ConnectionOptions> {
+    if let Some(connection_string) = required_var(env, connection.connection_string_env.as_deref())?
+    {
+        return ConnectionOptions::from_connection_string(&connection_string);
+    }
+    let connection_string = connection.connection_string.as_deref().ok_or_else(|| {
+        invalid("the connection has no connection_string or connection_string_env".to_string())
+    })?;
+    let options = ConnectionOptions::from_connection_string(connection_string)?;
+    match &options.authentication {
+        Authentication::SqlServer { password, .. } if !password.is_empty() => Err(invalid(
+            "the config file's connection string has a password; name a variable holding it with password_env".to_string(),
+        )),
+        _ => Ok(options),
+    }
+}
+
+/// The value of a variable a named connection asks for, which must be set
+fn required_var(
+    env: &impl Fn(&str) -> Option<String>,
+    name: Option<&str>,
+) -> Result<Option<String>> {
+    name.map(|name| {
+        env(name).ok_or_else(|| invalid(format!("environment variable {} is not set", name)))
+    })
+    .transpose()
+}
+
+fn invalid(message: String) -> anyhow::Error {
+    SqlPackageError::InvalidConnectionString { message }.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn resolve(
+        connection_string: Option<&str>,
+        connection: Option<&ConnectionConfig>,
+        vars: &[(&str, &str)],
+    ) -> Result<ConnectionOptions> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        resolve_with(connection_string, connection, None, |name| {
+            vars.get(name).cloned()
+        })
+    }
+
+    fn sql_login(user: &str, password: &str) -> Authentication {
+        Authentication::SqlServer {
+            user: user.to_string(),
+            password: password.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_from_environment() {
+        let options = resolve(
+            None,
+            None,
+            &[
+                (
+                    CONNECTION_STRING_ENV,
+                    "Server=db;Database=Sales;User ID=deploy",
+                ),
+                (PASSWORD_ENV, "secret"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(options.host, "db");
+        assert_eq!(options.authentication, sql_login("deploy", "secret"));
+
+        // A password in the connection string wins over the environment
+        let options = resolve(
+            Some("Server=db;User ID=deploy;Password=given"),
+            None,
+            &[(PASSWORD_ENV, "secret")],
+        )
+        .unwrap();
+        assert_eq!(options.authentication, sql_login("deploy", "given"));
+
+        let options = resolve(Some("Server=db"), None, &[(ACCESS_TOKEN_ENV, "token")]).unwrap();
+        assert_eq!(
+            options.authentication,
+            Authentication::AzureAdToken("token".to_string())
+        );
+
+        // A SQL login in the connection string also wins over a token in the environment
+        let options = resolve(
+            Some("Server=db;User ID=deploy;Password=given"),
+            None,
+            &[(ACCESS_TOKEN_ENV, "stale")],
+        )
+        .unwrap();
+        assert_eq!(options.authentication, sql_login("deploy", "given"));
+
+        assert!(resolve(None, None, &[]).is_err());
+    }
+
+    #[test]
+    fn test_resolve_named_connection() {
+        let connection = ConnectionConfig {
+            connection_string: Some("Server=staging;User ID=deploy".to_string()),
+            password_env: Some("STAGING_PASSWORD".to_string()),
+            ..ConnectionConfig::default()
+        };
+        let options = resolve(
+            None,
+            Some(&connection),
+            &[("STAGING_PASSWORD", "secret"), (PASSWORD_ENV, "other")],
+        )
+        .unwrap();
+        assert_eq!(options.host, "staging");
+        assert_eq!(options.authentication, sql_login("deploy", "secret"));
+
+        // Variables the connection names must be set
+        let error = resolve(None, Some(&connection), &[(PASSWORD_ENV, "other")]).unwrap_err();
+        assert!(error.to_string().contains("STAGING_PASSWORD"));
+
+        let connection = ConnectionConfig {
+            connection_string_env: Some("STAGING_DB".to_string()),
+            ..ConnectionConfig::default()
+        };
+        let options = resolve(None, Some(&connection), &[("STAGING_DB", "Server=env")]).unwrap();
+        assert_eq!(options.host, "env");
+
+        // Passwords don't belong in the config file
+        let connection = ConnectionConfig {
+            connection_string: Some("Server=staging;User ID=deploy;Password=secret".to_string()),
+            ..ConnectionConfig::default()
+        };
+        assert!(resolve(None, Some(&connection), &[]).is_err());
+    }
+}
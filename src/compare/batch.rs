@@ -0,0 +1,270 @@
+//! Comparing many pairs of dacpacs at once (`compare-batch`)
+//!
+//! The manifest is a JSON array of pairs, with paths relative to the manifest's folder:
+//!
+//! ```json
+//! [
+//!   { "name": "Sales", "rust": "out/Sales.dacpac", "dotnet": "baseline/Sales.dacpac" },
+//!   { "rust": "out/Billing.dacpac", "dotnet": "baseline/Billing.dacpac", "max_differences": 3 }
+//! ]
+//! ```
+//!
+//! `name` defaults to the file stem of `rust`, and `max_differences` overrides the
+//! batch's tolerance for one pair. Pairs are compared in parallel.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use rayon::prelude::*;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::breaking::FailOn;
+use super::known::KnownDifferences;
+use super::report::json_report;
+use super::types::{CompareOptions, CompareResult};
+use crate::error::SqlPackageError;
+
+/// A pair of dacpacs listed in the manifest
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchPair {
+    #[serde(default)]
+    pub name: String,
+    pub rust: PathBuf,
+    pub dotnet: PathBuf,
+    /// Differences tolerated for this pair, instead of the batch's
+    pub max_differences: Option<usize>,
+}
+
+/// The pairs listed in the manifest at `path`, with paths resolved against its folder
+pub fn load_manifest(path: &Path) -> Result<Vec<BatchPair>> {
+    let invalid = |message: String| SqlPackageError::InvalidCompareManifest {
+        path: path.to_path_buf(),
+        message,
+    };
+    let text = std::fs::read_to_string(path).map_err(|e| invalid(e.to_string()))?;
+    let mut pairs: Vec<BatchPair> =
+        serde_json::from_str(&text).map_err(|e| invalid(e.to_string()))?;
+    let root = path.parent().unwrap_or(Path::new(""));
+    for pair in &mut pairs {
+        pair.rust = root.join(&pair.rust);
+        pair.dotnet = root.join(&pair.dotnet);
+        if pair.name.is_empty() {
+            pair.name = pair
+                .rust
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+        }
+    }
+    if let Some(name) = pairs
+        .iter()
+        .enumerate()
+        .find(|(i, pair)| pairs[..*i].iter().any(|p| p.name == pair.name))
+        .map(|(_, pair)| &pair.name)
+    {
+        return Err(invalid(format!("more than one pair is named {}", name)).into());
+    }
+    Ok(pairs)
+}
+
+/// How a batch decides whether a pair passes
+#[derive(Debug, Clone, Default)]
+pub struct BatchOptions {
+    pub compare: CompareOptions,
+    pub known: KnownDifferences,
+    /// `any`, `element` or `property`
+    pub fail_on: FailOn,
+    pub max_differences: usize,
+}
+
+/// Result of comparing one pair
+#[derive(Debug)]
+pub struct PairOutcome {
+    pub pair: BatchPair,
+    /// The comparison, with known differences left out, or why it could not be made
+    pub result: std::result::Result<CompareResult, String>,
+    /// Differences of the kind selected by `fail_on`
+    pub differences: usize,
+    pub known_left_out: usize,
+    pub passed: bool,
+}
+
+/// Results of a batch, in manifest order
+#[derive(Debug)]
+pub struct BatchReport {
+    pub outcomes: Vec<PairOutcome>,
+}
+
+impl BatchReport {
+    pub fn passed(&self) -> usize {
+        self.outcomes.iter().filter(|o| o.passed).count()
+    }
+
+    /// Pairs that could not be compared
+    pub fn errors(&self) -> usize {
+        self.outcomes.iter().filter(|o| o.result.is_err()).count()
+    }
+
+    /// Whether every pair passed
+    pub fn is_ok(&self) -> bool {
+        self.outcomes.iter().all(|o| o.passed)
+    }
+}
+
+/// Compare every pair in parallel
+pub fn compare_batch(pairs: Vec<BatchPair>, options: &BatchOptions) -> Result<BatchReport> {
+    let differences_of: fn(&CompareResult) -> usize = match options.fail_on {
+        FailOn::Any => |r| r.element_differences() + r.property_differences(),
+        FailOn::Element => CompareResult::element_differences,
+        FailOn::Property => CompareResult::property_differences,
+        FailOn::Breaking | FailOn::DataLoss => {
+            return Err(anyhow::anyhow!(
+                "compare-batch supports --fail-on any, element and property"
+            ));
+        }
+    };
+    let outcomes = pairs
+        .into_par_iter()
+        .map(|pair| {
+            let compared =
+                super::compare_dacpacs_with_options(&pair.rust, &pair.dotnet, &options.compare);
+            let max_differences = pair.max_differences.unwrap_or(options.max_differences);
+            match compared {
+                Ok(mut result) => {
+                    let known_left_out = options.known.apply(&mut result);
+                    let differences = differences_of(&result);
+                    PairOutcome {
+                        pair,
+                        result: Ok(result),
+                        differences,
+                        known_left_out,
+                        passed: differences <= max_differences,
+                    }
+                }
+                Err(e) => PairOutcome {
+                    pair,
+                    result: Err(format!("{:#}", e)),
+                    differences: 0,
+                    known_left_out: 0,
+                    passed: false,
+                },
+            }
+        })
+        .collect();
+    Ok(BatchReport { outcomes })
+}
+
+/// One line per pair with its status, then the totals
+pub fn format_batch_report(report: &BatchReport) -> String {
+    let width = report
+        .outcomes
+        .iter()
+        .map(|o| o.pair.name.len())
+        .max()
+        .unwrap_or(0);
+    let mut out = String::from("=== Batch Compare ===\n\n");
+    for outcome in &report.outcomes {
+        let (status, detail) = match &outcome.result {
+            Err(message) => ("ERROR", message.clone()),
+            Ok(result) => {
+                let mut detail = format!(
+                    "{} difference(s): {} element, {} property",
+                    outcome.differences,
+                    result.element_differences(),
+                    result.property_differences()
+                );
+                if outcome.known_left_out > 0 {
+                    detail.push_str(&format!(", {} known", outcome.known_left_out));
+                }
+                (if outcome.passed { "PASS" } else { "FAIL" }, detail)
+            }
+        };
+        out.push_str(&format!(
+            "{:<5} {:<width$}  {}\n",
+            status,
+            outcome.pair.name,
+            detail,
+            width = width
+        ));
+    }
+    let errors = report.errors();
+    out.push_str(&format!(
+        "\n{} of {} passed, {} failed",
+        report.passed(),
+        report.outcomes.len(),
+        report.outcomes.len() - report.passed() - errors
+    ));
+    if errors > 0 {
+        out.push_str(&format!(", {} could not be compared", errors));
+    }
+    out.push('\n');
+    out
+}
+
+/// The batch as JSON, with each compared pair's full report
+pub fn format_batch_json_report(report: &BatchReport) -> String {
+    let pairs: Vec<Value> = report
+        .outcomes
+        .iter()
+        .map(|outcome| {
+            let (status, error, details) = match &outcome.result {
+                Err(message) => ("error", Some(message), Value::Null),
+                Ok(result) if outcome.passed => ("pass", None, json_report(result)),
+                Ok(result) => ("fail", None, json_report(result)),
+            };
+            json!({
+                "name": outcome.pair.name,
+                "rust": outcome.pair.rust.display().to_string(),
+                "dotnet": outcome.pair.dotnet.display().to_string(),
+                "status": status,
+                "differences": outcome.differences,
+                "known_left_out": outcome.known_left_out,
+                "error": error,
+                "report": details,
+            })
+        })
+        .collect();
+    let summary = json!({
+        "pairs": pairs,
+        "passed": report.passed(),
+        "failed": report.outcomes.len() - report.passed() - report.errors(),
+        "errors": report.errors(),
+    });
+    format!("{:#}\n", summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_manifest_resolves_paths_and_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pairs.json");
+        std::fs::write(
+            &path,
+            r#"[
+                { "name": "Sales", "rust": "out/Sales.dacpac", "dotnet": "base/Sales.dacpac" },
+                { "rust": "out/Billing.dacpac", "dotnet": "base/Billing.dacpac", "max_differences": 2 }
+            ]"#,
+        )
+        .unwrap();
+
+        let pairs = load_manifest(&path).unwrap();
+        assert_eq!(pairs.len(), 2);
+        assert_eq!(pairs[0].name, "Sales");
+        assert_eq!(pairs[0].rust, dir.path().join("out/Sales.dacpac"));
+        assert_eq!(pairs[1].name, "Billing");
+        assert_eq!(pairs[1].dotnet, dir.path().join("base/Billing.dacpac"));
+        assert_eq!(pairs[1].max_differences, Some(2));
+
+        std::fs::write(
+            &path,
+            r#"[{ "rust": "a/X.dacpac", "dotnet": "b.dacpac" }, { "rust": "c/X.dacpac", "dotnet": "d.dacpac" }]"#,
+        )
+        .unwrap();
+        assert!(load_manifest(&path).is_err());
+    }
+}
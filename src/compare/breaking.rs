@@ -0,0 +1,461 @@
+//! Breaking-change classification of a model diff
+//!
+//! Sorts the changes between an old and a new build into those that can break existing
+//! callers or data (dropped objects, columns and parameters, narrowed data types, columns
+//! made NOT NULL) and those that cannot (new objects, widened types, changed definitions).
+//! Schema-compatibility policies use this through `compare --objects --fail-on breaking`.
+
+use super::model_diff::{ElementDiff, ModelDiff};
+use super::types::ElementKey;
+
+/// Which differences make `compare` exit with a failure code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FailOn {
+    /// Any difference
+    #[default]
+    Any,
+    /// Elements (or files) present on only one side
+    Element,
+    /// Property, relationship or content differences of elements (or files) on both sides
+    Property,
+    /// Breaking changes only (`--objects`)
+    Breaking,
+    /// Changes that can lose existing data (`--objects`, see [`super::data_loss`])
+    DataLoss,
+}
+
+impl std::str::FromStr for FailOn {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "any" => Ok(FailOn::Any),
+            "element" => Ok(FailOn::Element),
+            "property" => Ok(FailOn::Property),
+            "breaking" => Ok(FailOn::Breaking),
+            "data-loss" | "dataloss" => Ok(FailOn::DataLoss),
+            _ => Err(format!(
+                "Unknown --fail-on value: {} (expected any, element, property, breaking or data-loss)",
+                s
+            )),
+        }
+    }
+}
+
+/// One change between two builds, classified as breaking or not
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassifiedChange {
+    /// Object or column/parameter the change applies to
+    pub key: ElementKey,
+    /// What changed, e.g. `dropped` or `type narrowed from bigint to int`
+    pub description: String,
+    pub breaking: bool,
+}
+
+/// Classify every change in `diff`: dropped objects first, then changed, then new objects
+pub fn classify_changes(diff: &ModelDiff) -> Vec<ClassifiedChange> {
+    let mut changes = Vec::new();
+    for key in &diff.removed {
+        changes.push(ClassifiedChange {
+            key: key.clone(),
+            description: "dropped".to_string(),
+            breaking: is_breaking_drop(key),
+        });
+    }
+    for element in &diff.changed {
+        classify_element(element, &mut changes);
+    }
+    for key in &diff.added {
+        changes.push(ClassifiedChange {
+            key: key.clone(),
+            description: "added".to_string(),
+            breaking: false,
+        });
+    }
+    changes
+}
+
+/// The breaking changes in `diff`
+pub fn breaking_changes(diff: &ModelDiff) -> Vec<ClassifiedChange> {
+    classify_changes(diff)
+        .into_iter()
+        .filter(|c| c.breaking)
+        .collect()
+}
+
+fn classify_element(element: &ElementDiff, changes: &mut Vec<ClassifiedChange>) {
+    for key in &element.removed_children {
+        changes.push(ClassifiedChange {
+            key: key.clone(),
+            description: "dropped".to_string(),
+            breaking: true,
+        });
+    }
+    for key in &element.added_children {
+        changes.push(ClassifiedChange {
+            key: key.clone(),
+            description: "added".to_string(),
+            breaking: false,
+        });
+    }
+
+    for property in &element.properties {
+        let (description, breaking) = match property.name.as_str() {
+            "Type" => {
+                let old = property.old.as_deref().unwrap_or_default();
+                let new = property.new.as_deref().unwrap_or_default();
+                match compare_types(old, new) {
+                    TypeChange::Widened => (format!("type widened from {} to {}", old, new), false),
+                    TypeChange::Narrowed => {
+                        (format!("type narrowed from {} to {}", old, new), true)
+                    }
+                    TypeChange::Changed => (format!("type changed from {} to {}", old, new), true),
+                }
+            }
+            // Rows inserted without a value for the column start failing
+            "IsNullable" if property.new.as_deref() == Some("False") => {
+                ("changed to NOT NULL".to_string(), true)
+            }
+            "IsNullable" => ("changed to NULL".to_string(), false),
+            name => (format!("{} changed", name), false),
+        };
+        changes.push(ClassifiedChange {
+            key: element.key.clone(),
+            description,
+            breaking,
+        });
+    }
+    for relationship in &element.relationships {
+        changes.push(ClassifiedChange {
+            key: element.key.clone(),
+            description: format!("{} changed", relationship.name),
+            breaking: false,
+        });
+    }
+
+    for child in &element.changed_children {
+        classify_element(child, changes);
+    }
+}
+
+/// Dropping an object breaks whatever references it; indexes and statistics only affect
+/// performance
+fn is_breaking_drop(key: &ElementKey) -> bool {
+    let element_type = match key {
+        ElementKey::Named { element_type, .. }
+        | ElementKey::Composite { element_type, .. }
+        | ElementKey::Singleton { element_type } => element_type.as_str(),
+    };
+    !matches!(
+        element_type,
+        "SqlIndex" | "SqlColumnStoreIndex" | "SqlFullTextIndex" | "SqlStatistic"
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum TypeChange {
+    /// Every old value fits the new type
+    Widened,
+    /// Some old values no longer fit (shorter, less precise, or no longer Unicode)
+    Narrowed,
+    /// A different kind of type, e.g. `int` to `varchar(10)`
+    Changed,
+}
+
+/// Compare two data types as written by [`super::model_diff::type_description`]
+pub(super) fn compare_types(old: &str, new: &str) -> TypeChange {
+    let old = ParsedType::parse(old);
+    let new = ParsedType::parse(new);
+    let widened = |fits: bool| {
+        if fits {
+            TypeChange::Widened
+        } else {
+            TypeChange::Narrowed
+        }
+    };
+
+    if let (Some(old_rank), Some(new_rank)) = (integer_rank(&old.base), integer_rank(&new.base)) {
+        return widened(new_rank >= old_rank);
+    }
+    // An integer fits a decimal with enough digits before the point
+    if let (Some(rank), true) = (integer_rank(&old.base), is_decimal(&new.base)) {
+        let (precision, scale) = new.precision_scale();
+        return widened(precision.saturating_sub(scale) >= INTEGER_DIGITS[rank]);
+    }
+    if is_decimal(&old.base) && is_decimal(&new.base) {
+        let (old_precision, old_scale) = old.precision_scale();
+        let (new_precision, new_scale) = new.precision_scale();
+        return widened(
+            new_scale >= old_scale
+                && new_precision.saturating_sub(new_scale)
+                    >= old_precision.saturating_sub(old_scale),
+        );
+    }
+    if let (Some(old_unicode), Some(new_unicode)) =
+        (string_is_unicode(&old.base), string_is_unicode(&new.base))
+    {
+        return widened((new_unicode || !old_unicode) && new.length() >= old.length());
+    }
+    if is_binary(&old.base) && is_binary(&new.base) {
+        return widened(new.length() >= old.length());
+    }
+    if let (Some(old_rank), Some(new_rank)) = (date_time_rank(&old.base), date_time_rank(&new.base))
+    {
+        return widened(
+            new_rank >= old_rank && new.fractional_seconds() >= old.fractional_seconds(),
+        );
+    }
+    if old.base == "time" && new.base == "time" {
+        return widened(new.fractional_seconds() >= old.fractional_seconds());
+    }
+    if let (Some(old_bits), Some(new_bits)) = (float_bits(&old), float_bits(&new)) {
+        return widened(new_bits >= old_bits);
+    }
+    if old.base == "smallmoney" && new.base == "money" {
+        return TypeChange::Widened;
+    }
+    if old.base == "money" && new.base == "smallmoney" {
+        return TypeChange::Narrowed;
+    }
+    TypeChange::Changed
+}
+
+/// Digits needed to hold any value of each integer type, by [`integer_rank`]
+const INTEGER_DIGITS: [u32; 4] = [3, 5, 10, 19];
+
+/// A data type split into its base name and arguments: `decimal(18,2)`
+struct ParsedType {
+    base: String,
+    args: Vec<String>,
+}
+
+impl ParsedType {
+    fn parse(description: &str) -> Self {
+        let description = description.trim().to_lowercase();
+        match description.split_once('(') {
+            Some((base, args)) => Self {
+                base: base.trim().to_string(),
+                args: args
+                    .trim_end_matches(')')
+                    .split(',')
+                    .map(|a| a.trim().to_string())
+                    .collect(),
+            },
+            None => Self {
+                base: description,
+                args: Vec::new(),
+            },
+        }
+    }
+
+    fn arg(&self, index: usize) -> Option<u32> {
+        self.args.get(index).and_then(|a| a.parse().ok())
+    }
+
+    /// Length in characters or bytes; `max` is unbounded and a missing length means 1
+    fn length(&self) -> u32 {
+        match self.args.first().map(String::as_str) {
+            Some("max") => u32::MAX,
+            _ => self.arg(0).unwrap_or(1),
+        }
+    }
+
+    /// `decimal` defaults to a precision of 18 and a scale of 0
+    fn precision_scale(&self) -> (u32, u32) {
+        (self.arg(0).unwrap_or(18), self.arg(1).unwrap_or(0))
+    }
+
+    /// Fractional-seconds precision of `time`, `datetime2` and `datetimeoffset` (default 7)
+    fn fractional_seconds(&self) -> u32 {
+        match self.base.as_str() {
+            "date" => 0,
+            "smalldatetime" => 0,
+            "datetime" => 3,
+            _ => self.arg(0).unwrap_or(7),
+        }
+    }
+}
+
+fn integer_rank(base: &str) -> Option<usize> {
+    ["tinyint", "smallint", "int", "bigint"]
+        .iter()
+        .position(|t| *t == base)
+}
+
+fn is_decimal(base: &str) -> bool {
+    base == "decimal" || base == "numeric"
+}
+
+/// Whether a character type stores Unicode, or `None` for other types
+fn string_is_unicode(base: &str) -> Option<bool> {
+    match base {
+        "char" | "varchar" => Some(false),
+        "nchar" | "nvarchar" => Some(true),
+        _ => None,
+    }
+}
+
+fn is_binary(base: &str) -> bool {
+    base == "binary" || base == "varbinary"
+}
+
+/// Date and time types ordered by the range of values they hold
+fn date_time_rank(base: &str) -> Option<usize> {
+    [
+        "date",
+        "smalldatetime",
+        "datetime",
+        "datetime2",
+        "datetimeoffset",
+    ]
+    .iter()
+    .position(|t| *t == base)
+}
+
+/// Storage size of `real` and `float(n)` in bits
+fn float_bits(parsed: &ParsedType) -> Option<u32> {
+    match parsed.base.as_str() {
+        "real" => Some(24),
+        "float" if parsed.arg(0).is_some_and(|n| n <= 24) => Some(24),
+        "float" => Some(53),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compare::model_diff::diff_model_xml;
+    use crate::compare::test_fixtures::{model, property, typed};
+
+    #[test]
+    fn test_compare_types() {
+        assert_eq!(compare_types("int", "bigint"), TypeChange::Widened);
+        assert_eq!(compare_types("bigint", "int"), TypeChange::Narrowed);
+        assert_eq!(compare_types("int", "decimal(10,0)"), TypeChange::Widened);
+        assert_eq!(compare_types("int", "decimal(10,2)"), TypeChange::Narrowed);
+        // Fewer digits before the point: 16 -> 15
+        assert_eq!(
+            compare_types("decimal(18,2)", "decimal(19,4)"),
+            TypeChange::Narrowed
+        );
+        assert_eq!(
+            compare_types("decimal(18,2)", "decimal(20,4)"),
+            TypeChange::Widened
+        );
+        assert_eq!(
+            compare_types("decimal(18,4)", "decimal(18,2)"),
+            TypeChange::Narrowed
+        );
+        assert_eq!(
+            compare_types("varchar(50)", "nvarchar(50)"),
+            TypeChange::Widened
+        );
+        assert_eq!(
+            compare_types("nvarchar(50)", "varchar(100)"),
+            TypeChange::Narrowed
+        );
+        assert_eq!(
+            compare_types("varchar(100)", "varchar(50)"),
+            TypeChange::Narrowed
+        );
+        assert_eq!(
+            compare_types("varchar(max)", "varchar(8000)"),
+            TypeChange::Narrowed
+        );
+        assert_eq!(
+            compare_types("varbinary(16)", "varbinary(max)"),
+            TypeChange::Widened
+        );
+        assert_eq!(compare_types("datetime", "datetime2"), TypeChange::Widened);
+        assert_eq!(
+            compare_types("datetime2(7)", "datetime2(3)"),
+            TypeChange::Narrowed
+        );
+        assert_eq!(compare_types("datetime2", "date"), TypeChange::Narrowed);
+        assert_eq!(compare_types("time(7)", "time(0)"), TypeChange::Narrowed);
+        assert_eq!(compare_types("real", "float"), TypeChange::Widened);
+        assert_eq!(compare_types("int", "varchar(10)"), TypeChange::Changed);
+        assert_eq!(
+            compare_types("[dbo].[Phone]", "varchar(20)"),
+            TypeChange::Changed
+        );
+    }
+
+    #[test]
+    fn test_classify_changes() {
+        let old = model(&format!(
+            r#"<Element Type="SqlTable" Name="[dbo].[T]"><Relationship Name="Columns">{}{}{}</Relationship></Element>
+<Element Type="SqlProcedure" Name="[dbo].[P]"><Relationship Name="Parameters">{}{}</Relationship></Element>
+<Element Type="SqlIndex" Name="[dbo].[T].[IX]"/>
+<Element Type="SqlView" Name="[dbo].[V]"/>"#,
+            typed("SqlSimpleColumn", "[dbo].[T].[Id]", "int", "", ""),
+            typed(
+                "SqlSimpleColumn",
+                "[dbo].[T].[Name]",
+                "varchar",
+                &property("Length", "100"),
+                ""
+            ),
+            typed("SqlSimpleColumn", "[dbo].[T].[Old]", "int", "", ""),
+            typed("SqlSubroutineParameter", "[dbo].[P].[@A]", "int", "", ""),
+            typed("SqlSubroutineParameter", "[dbo].[P].[@B]", "int", "", ""),
+        ));
+        let new = model(&format!(
+            r#"<Element Type="SqlTable" Name="[dbo].[T]"><Relationship Name="Columns">{}{}</Relationship></Element>
+<Element Type="SqlProcedure" Name="[dbo].[P]"><Relationship Name="Parameters">{}</Relationship></Element>
+<Element Type="SqlView" Name="[dbo].[V2]"/>"#,
+            typed("SqlSimpleColumn", "[dbo].[T].[Id]", "bigint", "", ""),
+            typed(
+                "SqlSimpleColumn",
+                "[dbo].[T].[Name]",
+                "varchar",
+                &property("Length", "50"),
+                ""
+            ),
+            typed("SqlSubroutineParameter", "[dbo].[P].[@A]", "int", "", ""),
+        ));
+        let diff = diff_model_xml(&old, &new).unwrap();
+        let changes = classify_changes(&diff);
+        let described: Vec<(String, &str, bool)> = changes
+            .iter()
+            .map(|c| (c.key.to_string(), c.description.as_str(), c.breaking))
+            .collect();
+
+        assert!(described.contains(&("SqlIndex [dbo].[T].[IX]".to_string(), "dropped", false)));
+        assert!(described.contains(&("SqlView [dbo].[V]".to_string(), "dropped", true)));
+        assert!(described.contains(&("SqlView [dbo].[V2]".to_string(), "added", false)));
+        assert!(described.contains(&(
+            "SqlSimpleColumn [dbo].[T].[Old]".to_string(),
+            "dropped",
+            true
+        )));
+        assert!(described.contains(&(
+            "SqlSimpleColumn [dbo].[T].[Id]".to_string(),
+            "type widened from int to bigint",
+            false
+        )));
+        assert!(described.contains(&(
+            "SqlSimpleColumn [dbo].[T].[Name]".to_string(),
+            "type narrowed from varchar(100) to varchar(50)",
+            true
+        )));
+        assert!(described.contains(&(
+            "SqlSubroutineParameter [dbo].[P].[@B]".to_string(),
+            "dropped",
+            true
+        )));
+
+        let breaking = breaking_changes(&diff);
+        assert_eq!(breaking.len(), 4);
+        assert!(breaking.iter().all(|c| c.breaking));
+    }
+
+    #[test]
+    fn test_fail_on_from_str() {
+        assert_eq!("breaking".parse::<FailOn>().unwrap(), FailOn::Breaking);
+        assert_eq!("ANY".parse::<FailOn>().unwrap(), FailOn::Any);
+        assert_eq!("data-loss".parse::<FailOn>().unwrap(), FailOn::DataLoss);
+        assert!("never".parse::<FailOn>().is_err());
+    }
+}
@@ -0,0 +1,401 @@
+//! Markdown changelog of schema changes between two builds
+//!
+//! Turns a [`ModelDiff`] into release notes grouped by kind of object, preceded by the
+//! breaking changes (see [`super::breaking`]):
+//!
+//! ```markdown
+//! ## Tables
+//!
+//! - New: `[dbo].[Customers]`
+//! - Altered: `[dbo].[Orders]`
+//!   - Added column `[ShippedAt]`
+//!   - Altered column `[Total]`: `decimal(18,2)` → `decimal(19,4)`
+//!
+//! ## Indexes
+//!
+//! - Dropped: `[dbo].[Orders].[IX_Orders_Legacy]`
+//! ```
+
+use std::fmt::Write;
+
+use super::breaking::breaking_changes;
+use super::model_diff::{ElementDiff, ModelDiff, PropertyChange};
+use super::types::ElementKey;
+use crate::model_dump::name_parts;
+use crate::parser::identifier_utils::quote_identifier;
+
+/// Longest property value quoted in full; longer values (scripts) are reported as changed
+const MAX_VALUE_LENGTH: usize = 60;
+
+/// Section headings, in the order they appear in the changelog
+const CATEGORIES: &[&str] = &[
+    "Schemas",
+    "Tables",
+    "Views",
+    "Stored procedures",
+    "Functions",
+    "Triggers",
+    "Indexes",
+    "Constraints",
+    "Sequences",
+    "Types",
+    "Other objects",
+];
+
+/// Render a Markdown changelog of `diff`, titled with the old and new build labels
+pub fn render_changelog(diff: &ModelDiff, old_label: &str, new_label: &str) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Schema changes: {} → {}\n", old_label, new_label);
+
+    if diff.is_empty() {
+        out.push_str("No schema changes.\n");
+        return out;
+    }
+
+    let _ = writeln!(
+        out,
+        "{} new, {} dropped, {} altered.\n",
+        diff.added.len(),
+        diff.removed.len(),
+        diff.changed.len()
+    );
+
+    let breaking = breaking_changes(diff);
+    if !breaking.is_empty() {
+        out.push_str("## Breaking changes\n\n");
+        for change in &breaking {
+            let _ = writeln!(out, "- `{}`: {}", change.key, change.description);
+        }
+        out.push('\n');
+    }
+
+    for category in CATEGORIES {
+        let in_category = |key: &&ElementKey| category_of(element_type(key)) == *category;
+        let added: Vec<&ElementKey> = diff.added.iter().filter(in_category).collect();
+        let removed: Vec<&ElementKey> = diff.removed.iter().filter(in_category).collect();
+        let changed: Vec<&ElementDiff> = diff
+            .changed
+            .iter()
+            .filter(|c| in_category(&&c.key))
+            .collect();
+        if added.is_empty() && removed.is_empty() && changed.is_empty() {
+            continue;
+        }
+
+        let _ = writeln!(out, "## {}\n", category);
+        for key in added {
+            let _ = writeln!(out, "- New: {}", object_label(key, category));
+        }
+        for key in removed {
+            let _ = writeln!(out, "- Dropped: {}", object_label(key, category));
+        }
+        for element in changed {
+            let _ = writeln!(out, "- Altered: {}", object_label(&element.key, category));
+            for line in change_lines(element) {
+                let _ = writeln!(out, "  - {}", line);
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Changelog section for an element type
+fn category_of(element_type: &str) -> &'static str {
+    match element_type {
+        "SqlSchema" => "Schemas",
+        "SqlTable" => "Tables",
+        "SqlView" => "Views",
+        "SqlProcedure" => "Stored procedures",
+        "SqlScalarFunction"
+        | "SqlInlineTableValuedFunction"
+        | "SqlMultiStatementTableValuedFunction" => "Functions",
+        "SqlDmlTrigger" => "Triggers",
+        "SqlIndex" | "SqlColumnStoreIndex" | "SqlFullTextIndex" => "Indexes",
+        "SqlSequence" => "Sequences",
+        "SqlTableType" | "SqlUserDefinedDataType" => "Types",
+        t if t.ends_with("Constraint") => "Constraints",
+        _ => "Other objects",
+    }
+}
+
+fn element_type(key: &ElementKey) -> &str {
+    match key {
+        ElementKey::Named { element_type, .. }
+        | ElementKey::Composite { element_type, .. }
+        | ElementKey::Singleton { element_type } => element_type,
+    }
+}
+
+/// `` `[dbo].[Orders]` ``, with the element type where the section does not imply it
+fn object_label(key: &ElementKey, category: &str) -> String {
+    let name = match key {
+        ElementKey::Named { name, .. } => format!("`{}`", name),
+        // Unnamed constraints are identified by the table or column they are defined on
+        ElementKey::Composite { composite, .. } => {
+            let target = composite
+                .rsplit(',')
+                .next()
+                .and_then(|part| part.split_once('='))
+                .map_or(composite.as_str(), |(_, target)| target);
+            format!("unnamed on `{}`", target)
+        }
+        ElementKey::Singleton { element_type } => return element_type.clone(),
+    };
+    match category {
+        "Constraints" | "Types" | "Other objects" => {
+            format!("{} ({})", name, element_type(key))
+        }
+        _ => name,
+    }
+}
+
+/// One line per change of an altered object, including changes to its columns and parameters
+fn change_lines(element: &ElementDiff) -> Vec<String> {
+    let mut lines = Vec::new();
+    for key in &element.added_children {
+        lines.push(format!("Added {} `{}`", child_kind(key), child_name(key)));
+    }
+    for key in &element.removed_children {
+        lines.push(format!("Dropped {} `{}`", child_kind(key), child_name(key)));
+    }
+    for child in &element.changed_children {
+        let changes: Vec<String> = child
+            .properties
+            .iter()
+            .map(describe_property)
+            .chain(
+                child
+                    .relationships
+                    .iter()
+                    .map(|r| format!("{} changed", r.name)),
+            )
+            .collect();
+        let prefix = format!(
+            "Altered {} `{}`",
+            child_kind(&child.key),
+            child_name(&child.key)
+        );
+        if changes.is_empty() {
+            lines.push(prefix);
+        } else {
+            lines.push(format!("{}: {}", prefix, changes.join(", ")));
+        }
+    }
+    for property in &element.properties {
+        lines.push(capitalize(&describe_property(property)));
+    }
+    for relationship in &element.relationships {
+        lines.push(format!("{} changed", relationship.name));
+    }
+    lines
+}
+
+/// `` `int` → `bigint` `` for type changes, `now NOT NULL` for nullability, otherwise the
+/// property name with its old and new values
+fn describe_property(change: &PropertyChange) -> String {
+    match change.name.as_str() {
+        "Type" => format!("{} → {}", quoted(&change.old), quoted(&change.new)),
+        "IsNullable" => match change.new.as_deref() {
+            Some("False") => "now NOT NULL".to_string(),
+            _ => "now NULL".to_string(),
+        },
+        name if name.ends_with("Script") => "definition changed".to_string(),
+        name => {
+            let is_long = |v: &Option<String>| {
+                v.as_ref()
+                    .is_some_and(|v| v.contains('\n') || v.len() > MAX_VALUE_LENGTH)
+            };
+            if is_long(&change.old) || is_long(&change.new) {
+                format!("`{}` changed", name)
+            } else {
+                format!(
+                    "`{}` {} → {}",
+                    name,
+                    quoted(&change.old),
+                    quoted(&change.new)
+                )
+            }
+        }
+    }
+}
+
+fn quoted(value: &Option<String>) -> String {
+    match value {
+        Some(v) => format!("`{}`", v),
+        None => "(none)".to_string(),
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// `column`, `parameter`, or the element type for other inline elements
+fn child_kind(key: &ElementKey) -> String {
+    match element_type(key) {
+        "SqlSimpleColumn" | "SqlComputedColumn" => "column".to_string(),
+        "SqlSubroutineParameter" => "parameter".to_string(),
+        other => other.strip_prefix("Sql").unwrap_or(other).to_string(),
+    }
+}
+
+/// Last part of a child's name: `[dbo].[Orders].[Total]` -> `[Total]`
+fn child_name(key: &ElementKey) -> String {
+    match key {
+        ElementKey::Named { name, .. } => match name_parts(name).last() {
+            Some(last) => quote_identifier(last),
+            None => name.clone(),
+        },
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compare::model_diff::diff_model_xml;
+    use crate::compare::test_fixtures::{column, model, property, table};
+
+    #[test]
+    fn test_render_changelog() {
+        let old = model(&format!(
+            r#"{}{}<Element Type="SqlIndex" Name="[dbo].[Orders].[IX_Legacy]"/>"#,
+            table(
+                "[dbo].[Orders]",
+                &[
+                    column(
+                        "[dbo].[Orders].[Id]",
+                        "int",
+                        "",
+                        &property("IsNullable", "False")
+                    ),
+                    column(
+                        "[dbo].[Orders].[Total]",
+                        "int",
+                        "",
+                        &property("IsNullable", "True")
+                    ),
+                    column(
+                        "[dbo].[Orders].[Notes]",
+                        "ntext",
+                        "",
+                        &property("IsNullable", "True")
+                    ),
+                ]
+            ),
+            table(
+                "[dbo].[Legacy]",
+                &[column(
+                    "[dbo].[Legacy].[Id]",
+                    "int",
+                    "",
+                    &property("IsNullable", "False")
+                )]
+            ),
+        ));
+        let new = model(&format!(
+            "{}{}",
+            table(
+                "[dbo].[Orders]",
+                &[
+                    column(
+                        "[dbo].[Orders].[Id]",
+                        "int",
+                        "",
+                        &property("IsNullable", "False")
+                    ),
+                    column(
+                        "[dbo].[Orders].[Total]",
+                        "bigint",
+                        "",
+                        &property("IsNullable", "False")
+                    ),
+                    column(
+                        "[dbo].[Orders].[ShippedAt]",
+                        "datetime2",
+                        "",
+                        &property("IsNullable", "True")
+                    ),
+                ]
+            ),
+            table(
+                "[dbo].[Customers]",
+                &[column(
+                    "[dbo].[Customers].[Id]",
+                    "int",
+                    "",
+                    &property("IsNullable", "False")
+                )]
+            ),
+        ));
+        let diff = diff_model_xml(&old, &new).unwrap();
+        let changelog = render_changelog(&diff, "v1.dacpac", "v2.dacpac");
+
+        let expected = "\
+# Schema changes: v1.dacpac → v2.dacpac
+
+1 new, 2 dropped, 1 altered.
+
+## Breaking changes
+
+- `SqlTable [dbo].[Legacy]`: dropped
+- `SqlSimpleColumn [dbo].[Orders].[Notes]`: dropped
+- `SqlSimpleColumn [dbo].[Orders].[Total]`: changed to NOT NULL
+
+## Tables
+
+- New: `[dbo].[Customers]`
+- Dropped: `[dbo].[Legacy]`
+- Altered: `[dbo].[Orders]`
+  - Added column `[ShippedAt]`
+  - Dropped column `[Notes]`
+  - Altered column `[Total]`: now NOT NULL, `int` → `bigint`
+
+## Indexes
+
+- Dropped: `[dbo].[Orders].[IX_Legacy]`
+
+";
+        assert_eq!(changelog, expected);
+    }
+
+    #[test]
+    fn test_render_changelog_without_changes() {
+        let xml = model(&table(
+            "[dbo].[Orders]",
+            &[column(
+                "[dbo].[Orders].[Id]",
+                "int",
+                "",
+                &property("IsNullable", "False"),
+            )],
+        ));
+        let diff = diff_model_xml(&xml, &xml).unwrap();
+        assert_eq!(
+            render_changelog(&diff, "a", "b"),
+            "# Schema changes: a → b\n\nNo schema changes.\n"
+        );
+    }
+
+    #[test]
+    fn test_object_label() {
+        let key = ElementKey::Composite {
+            element_type: "SqlDefaultConstraint".to_string(),
+            composite: "DefiningTable=[dbo].[T],ForColumn=[dbo].[T].[C]".to_string(),
+        };
+        assert_eq!(
+            object_label(&key, "Constraints"),
+            "unnamed on `[dbo].[T].[C]` (SqlDefaultConstraint)"
+        );
+        let key = ElementKey::Named {
+            element_type: "SqlView".to_string(),
+            name: "[dbo].[V]".to_string(),
+        };
+        assert_eq!(object_label(&key, "Views"), "`[dbo].[V]`");
+    }
+}
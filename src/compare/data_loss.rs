@@ -0,0 +1,201 @@
+//! Possible data loss in a model diff
+//!
+//! Deploying some changes loses data already stored in the database, or fails on it.
+//! Like DacFx's `BlockOnPossibleDataLoss`, these are reported as warnings naming the
+//! affected tables and columns:
+//!
+//! - a table or column is dropped
+//! - a column's type is narrowed (`varchar(100)` to `varchar(50)`) or changed to an
+//!   unrelated type, so existing values may be truncated or fail to convert
+//! - a column is changed to NOT NULL without a default constraint, so existing NULLs
+//!   have no value to take
+
+use super::breaking::{compare_types, TypeChange};
+use super::model_diff::{ElementDiff, ModelDiff};
+use super::types::ElementKey;
+
+/// A change that can lose or fail on existing data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataLossWarning {
+    /// The table or column affected
+    pub key: ElementKey,
+    /// Why data may be lost, e.g. `column dropped`
+    pub reason: String,
+}
+
+/// Changes in `diff` that can lose existing data, in table order
+pub fn data_loss_warnings(diff: &ModelDiff) -> Vec<DataLossWarning> {
+    let mut warnings = Vec::new();
+    for key in diff.removed.iter().filter(|key| is_table(key)) {
+        warnings.push(DataLossWarning {
+            key: key.clone(),
+            reason: "table dropped".to_string(),
+        });
+    }
+    for table in diff.changed.iter().filter(|c| is_table(&c.key)) {
+        for key in table.removed_children.iter().filter(|key| is_column(key)) {
+            warnings.push(DataLossWarning {
+                key: key.clone(),
+                reason: "column dropped".to_string(),
+            });
+        }
+        for column in table.changed_children.iter().filter(|c| is_column(&c.key)) {
+            column_warnings(column, diff, &mut warnings);
+        }
+    }
+    warnings
+}
+
+fn column_warnings(column: &ElementDiff, diff: &ModelDiff, warnings: &mut Vec<DataLossWarning>) {
+    if let Some(change) = column.property("Type") {
+        let old = change.old.as_deref().unwrap_or_default();
+        let new = change.new.as_deref().unwrap_or_default();
+        let reason = match compare_types(old, new) {
+            TypeChange::Widened => None,
+            TypeChange::Narrowed => Some(format!(
+                "type narrowed from {} to {}; values may be truncated",
+                old, new
+            )),
+            TypeChange::Changed => Some(format!(
+                "type changed from {} to {}; values may fail to convert",
+                old, new
+            )),
+        };
+        if let Some(reason) = reason {
+            warnings.push(DataLossWarning {
+                key: column.key.clone(),
+                reason,
+            });
+        }
+    }
+
+    let made_not_null = column
+        .property("IsNullable")
+        .is_some_and(|change| change.new.as_deref() == Some("False"));
+    let has_default = match &column.key {
+        ElementKey::Named { name, .. } => diff.defaulted_columns.contains(name),
+        _ => false,
+    };
+    if made_not_null && !has_default {
+        warnings.push(DataLossWarning {
+            key: column.key.clone(),
+            reason: "changed to NOT NULL without a default; existing NULLs have no value"
+                .to_string(),
+        });
+    }
+}
+
+fn element_type(key: &ElementKey) -> &str {
+    match key {
+        ElementKey::Named { element_type, .. }
+        | ElementKey::Composite { element_type, .. }
+        | ElementKey::Singleton { element_type } => element_type,
+    }
+}
+
+fn is_table(key: &ElementKey) -> bool {
+    element_type(key) == "SqlTable"
+}
+
+/// Stored columns; computed columns are recalculated, so dropping them loses nothing
+fn is_column(key: &ElementKey) -> bool {
+    element_type(key) == "SqlSimpleColumn"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compare::model_diff::diff_model_xml;
+    use crate::compare::test_fixtures::{column, model, property, table};
+
+    #[test]
+    fn test_data_loss_warnings() {
+        let old = model(&format!(
+            "{}{}",
+            table(
+                "[dbo].[T]",
+                &[
+                    column(
+                        "[dbo].[T].[Dropped]",
+                        "int",
+                        "",
+                        &property("IsNullable", "True")
+                    ),
+                    column(
+                        "[dbo].[T].[Shrunk]",
+                        "varchar",
+                        &property("Length", "100"),
+                        &property("IsNullable", "True")
+                    ),
+                    column(
+                        "[dbo].[T].[Grown]",
+                        "varchar",
+                        &property("Length", "50"),
+                        &property("IsNullable", "True")
+                    ),
+                    column(
+                        "[dbo].[T].[Required]",
+                        "int",
+                        "",
+                        &property("IsNullable", "True")
+                    ),
+                    column(
+                        "[dbo].[T].[Defaulted]",
+                        "int",
+                        "",
+                        &property("IsNullable", "True")
+                    ),
+                ]
+            ),
+            r#"<Element Type="SqlTable" Name="[dbo].[Legacy]"/>"#
+        ));
+        let new = model(&format!(
+            "{}{}",
+            table(
+                "[dbo].[T]",
+                &[
+                    column(
+                        "[dbo].[T].[Shrunk]",
+                        "varchar",
+                        &property("Length", "50"),
+                        &property("IsNullable", "True")
+                    ),
+                    column(
+                        "[dbo].[T].[Grown]",
+                        "varchar",
+                        &property("Length", "100"),
+                        &property("IsNullable", "True")
+                    ),
+                    column(
+                        "[dbo].[T].[Required]",
+                        "int",
+                        "",
+                        &property("IsNullable", "False")
+                    ),
+                    column(
+                        "[dbo].[T].[Defaulted]",
+                        "int",
+                        "",
+                        &property("IsNullable", "False")
+                    ),
+                ]
+            ),
+            r#"<Element Type="SqlDefaultConstraint"><Relationship Name="DefiningTable"><Entry><References Name="[dbo].[T]"/></Entry></Relationship><Relationship Name="ForColumn"><Entry><References Name="[dbo].[T].[Defaulted]"/></Entry></Relationship></Element>"#
+        ));
+        let diff = diff_model_xml(&old, &new).unwrap();
+        let warnings: Vec<String> = data_loss_warnings(&diff)
+            .iter()
+            .map(|w| format!("{}: {}", w.key, w.reason))
+            .collect();
+
+        assert_eq!(
+            warnings,
+            vec![
+                "SqlTable [dbo].[Legacy]: table dropped",
+                "SqlSimpleColumn [dbo].[T].[Dropped]: column dropped",
+                "SqlSimpleColumn [dbo].[T].[Required]: changed to NOT NULL without a default; existing NULLs have no value",
+                "SqlSimpleColumn [dbo].[T].[Shrunk]: type narrowed from varchar(100) to varchar(50); values may be truncated",
+            ]
+        );
+    }
+}
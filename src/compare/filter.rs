@@ -0,0 +1,137 @@
+//! Narrowing a comparison to some element types and names
+//!
+//! `compare --only SqlProcedure --name 'dbo.usp_*'` compares only the procedures whose
+//! name matches the glob. Types match with or without the `Sql` prefix; names match
+//! bare (`usp_Orders`) or schema-qualified (`dbo.usp_Orders`), without brackets. Unnamed
+//! elements such as default constraints are matched by the table they belong to.
+//! Matching is case-insensitive, as for build filters. `--ignore SqlPermissionStatement`
+//! leaves a type out instead.
+
+use glob::{MatchOptions, Pattern};
+
+use super::types::ElementKey;
+use crate::model_dump::name_parts;
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: false,
+    require_literal_leading_dot: false,
+};
+
+/// Element types and a name pattern that compared elements must match
+#[derive(Debug, Clone, Default)]
+pub struct KeyFilter {
+    /// Element types to keep (all types when empty)
+    pub element_types: Vec<String>,
+    /// Glob that element names must match
+    pub name: Option<Pattern>,
+    /// Element types left out, whatever `element_types` keeps
+    pub ignored_types: Vec<String>,
+}
+
+impl KeyFilter {
+    /// Whether the filter keeps every element
+    pub fn is_empty(&self) -> bool {
+        self.element_types.is_empty() && self.name.is_none() && self.ignored_types.is_empty()
+    }
+
+    /// Whether an element with `key` is compared
+    pub fn matches(&self, key: &ElementKey) -> bool {
+        self.matches_type(key) && self.matches_name(key)
+    }
+
+    fn matches_type(&self, key: &ElementKey) -> bool {
+        let element_type = match key {
+            ElementKey::Named { element_type, .. }
+            | ElementKey::Composite { element_type, .. }
+            | ElementKey::Singleton { element_type } => element_type.as_str(),
+        };
+        let bare = element_type.strip_prefix("Sql").unwrap_or(element_type);
+        let is = |wanted: &String| {
+            wanted.eq_ignore_ascii_case(element_type) || wanted.eq_ignore_ascii_case(bare)
+        };
+        (self.element_types.is_empty() || self.element_types.iter().any(is))
+            && !self.ignored_types.iter().any(is)
+    }
+
+    fn matches_name(&self, key: &ElementKey) -> bool {
+        let Some(pattern) = &self.name else {
+            return true;
+        };
+        let name = match key {
+            ElementKey::Named { name, .. } => name.as_str(),
+            // DefiningTable=[dbo].[T],ForColumn=... names the owning table first
+            ElementKey::Composite { composite, .. } => composite
+                .split(',')
+                .next()
+                .and_then(|part| part.strip_prefix("DefiningTable="))
+                .unwrap_or(""),
+            ElementKey::Singleton { .. } => return false,
+        };
+        let parts = name_parts(name);
+        let qualified = parts.join(".");
+        pattern.matches_with(&qualified, MATCH_OPTIONS)
+            || parts
+                .last()
+                .is_some_and(|last| pattern.matches_with(last, MATCH_OPTIONS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named(element_type: &str, name: &str) -> ElementKey {
+        ElementKey::Named {
+            element_type: element_type.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_key_filter() {
+        let filter = KeyFilter {
+            element_types: vec!["SqlProcedure".to_string()],
+            name: Some(Pattern::new("dbo.usp_*").unwrap()),
+            ..KeyFilter::default()
+        };
+        assert!(filter.matches(&named("SqlProcedure", "[dbo].[usp_GetOrders]")));
+        assert!(filter.matches(&named("SqlProcedure", "[DBO].[USP_GetOrders]")));
+        assert!(!filter.matches(&named("SqlProcedure", "[sales].[usp_GetOrders]")));
+        assert!(!filter.matches(&named("SqlTable", "[dbo].[usp_GetOrders]")));
+
+        // Bare names and types without the Sql prefix
+        let filter = KeyFilter {
+            element_types: vec!["table".to_string()],
+            name: Some(Pattern::new("Orders").unwrap()),
+            ..KeyFilter::default()
+        };
+        assert!(filter.matches(&named("SqlTable", "[sales].[Orders]")));
+        assert!(!filter.matches(&named("SqlTable", "[sales].[OrderLines]")));
+
+        // Unnamed elements follow their table; singletons have no name
+        let filter = KeyFilter {
+            name: Some(Pattern::new("dbo.Orders").unwrap()),
+            ..KeyFilter::default()
+        };
+        assert!(filter.matches(&ElementKey::Composite {
+            element_type: "SqlDefaultConstraint".to_string(),
+            composite: "DefiningTable=[dbo].[Orders],ForColumn=[dbo].[Orders].[Status]".to_string(),
+        }));
+        assert!(!filter.matches(&ElementKey::Singleton {
+            element_type: "SqlDatabaseOptions".to_string(),
+        }));
+        assert!(KeyFilter::default().matches(&ElementKey::Singleton {
+            element_type: "SqlDatabaseOptions".to_string(),
+        }));
+
+        // Ignored types are left out whatever else matches
+        let filter = KeyFilter {
+            ignored_types: vec!["PermissionStatement".to_string()],
+            ..KeyFilter::default()
+        };
+        assert!(!filter.is_empty());
+        assert!(filter.matches(&named("SqlTable", "[dbo].[Orders]")));
+        assert!(!filter.matches(&named("SqlPermissionStatement", "[Grant.Select]")));
+    }
+}
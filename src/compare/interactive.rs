@@ -0,0 +1,327 @@
+//! Terminal UI for browsing a comparison (`compare --interactive`)
+//!
+//! Lists the differing elements with what differs; Enter shows an element's XML from
+//! both dacpacs side by side, and `i` marks it as a known difference (or unmarks it),
+//! saving it to the ignore file so later comparisons leave it out.
+
+use std::path::Path;
+
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+
+use super::known::KnownDifferences;
+use super::reader::DacpacContents;
+use super::types::{CompareResult, ElementKey, KeyCase};
+use super::xml_view::{element_xml_by_key, side_by_side};
+
+/// How an element differs between the two dacpacs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DifferenceKind {
+    MissingInRust,
+    ExtraInRust,
+    Changed,
+}
+
+impl DifferenceKind {
+    fn label(self) -> &'static str {
+        match self {
+            DifferenceKind::MissingInRust => "missing",
+            DifferenceKind::ExtraInRust => "extra",
+            DifferenceKind::Changed => "changed",
+        }
+    }
+}
+
+/// A differing element, as listed
+#[derive(Debug, Clone)]
+pub struct Difference {
+    pub key: ElementKey,
+    pub kind: DifferenceKind,
+    /// What differs, as the text report shows it
+    pub details: Vec<String>,
+}
+
+/// The differing elements of `result`, in report order
+pub fn differences(result: &CompareResult) -> Vec<Difference> {
+    let Some(elements) = &result.elements_result else {
+        return Vec::new();
+    };
+    let one_sided = |keys: &[ElementKey], kind| {
+        keys.iter()
+            .map(move |key| Difference {
+                key: key.clone(),
+                kind,
+                details: Vec::new(),
+            })
+            .collect::<Vec<_>>()
+    };
+    let mut differences = one_sided(&elements.missing_in_rust, DifferenceKind::MissingInRust);
+    differences.extend(one_sided(
+        &elements.extra_in_rust,
+        DifferenceKind::ExtraInRust,
+    ));
+    differences.extend(
+        elements
+            .differences
+            .iter()
+            .map(|(key, details)| Difference {
+                key: key.clone(),
+                kind: DifferenceKind::Changed,
+                details: details.clone(),
+            }),
+    );
+    differences
+}
+
+/// State of the browser, apart from the terminal
+pub struct Browser {
+    pub differences: Vec<Difference>,
+    pub known: KnownDifferences,
+    pub selected: usize,
+    /// Side-by-side XML of the selected element, when expanded
+    pub expanded: Option<String>,
+    pub scroll: u16,
+    pub status: String,
+}
+
+impl Browser {
+    pub fn new(differences: Vec<Difference>, known: KnownDifferences) -> Self {
+        let status = match &known.path {
+            Some(path) => format!("known differences are saved to {}", path.display()),
+            None => "pass --ignore-file to save known differences".to_string(),
+        };
+        Self {
+            differences,
+            known,
+            selected: 0,
+            expanded: None,
+            scroll: 0,
+            status,
+        }
+    }
+
+    /// Handle a key press, with `element_view` rendering an element's XML; returns
+    /// false to quit
+    pub fn handle_key(
+        &mut self,
+        code: KeyCode,
+        element_view: impl Fn(&ElementKey) -> Result<String>,
+    ) -> Result<bool> {
+        match code {
+            KeyCode::Char('q') => return Ok(false),
+            KeyCode::Esc | KeyCode::Left if self.expanded.is_some() => {
+                self.expanded = None;
+                self.scroll = 0;
+            }
+            KeyCode::Esc => return Ok(false),
+            KeyCode::Up | KeyCode::Char('k') => self.select(self.selected.saturating_sub(1)),
+            KeyCode::Down | KeyCode::Char('j') => self.select(self.selected + 1),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(10),
+            KeyCode::Enter | KeyCode::Right => {
+                if let Some(difference) = self.differences.get(self.selected) {
+                    self.expanded = Some(element_view(&difference.key)?);
+                    self.scroll = 0;
+                }
+            }
+            KeyCode::Char('i') => self.toggle_known()?,
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    fn select(&mut self, index: usize) {
+        let index = index.min(self.differences.len().saturating_sub(1));
+        if index != self.selected {
+            self.selected = index;
+            self.expanded = None;
+            self.scroll = 0;
+        }
+    }
+
+    fn toggle_known(&mut self) -> Result<()> {
+        let Some(difference) = self.differences.get(self.selected) else {
+            return Ok(());
+        };
+        let key = difference.key.clone();
+        if self.known.contains(&key) {
+            self.known.remove(&key)?;
+            self.status = format!("{} is no longer a known difference", key);
+        } else {
+            self.known.add(&key)?;
+            self.status = format!("{} marked as a known difference", key);
+        }
+        Ok(())
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [title, body, footer] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(2),
+        ])
+        .areas(frame.area());
+        let [list_area, detail_area] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(body);
+
+        let known = self
+            .differences
+            .iter()
+            .filter(|d| self.known.contains(&d.key))
+            .count();
+        frame.render_widget(
+            Line::from(format!(
+                "{} differing elements, {} known",
+                self.differences.len(),
+                known
+            ))
+            .style(Style::new().add_modifier(Modifier::BOLD)),
+            title,
+        );
+
+        let items: Vec<ListItem> = self
+            .differences
+            .iter()
+            .map(|d| {
+                let mark = if self.known.contains(&d.key) {
+                    "✓"
+                } else {
+                    " "
+                };
+                ListItem::new(format!("{} {:<8} {}", mark, d.kind.label(), d.key))
+            })
+            .collect();
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        frame.render_stateful_widget(
+            List::new(items)
+                .block(Block::bordered().title("Elements"))
+                .highlight_style(Style::new().add_modifier(Modifier::REVERSED)),
+            list_area,
+            &mut state,
+        );
+
+        let (detail_title, detail) = match (&self.expanded, self.differences.get(self.selected)) {
+            (Some(xml), _) => ("XML (Esc to close)", xml.clone()),
+            (None, Some(d)) if d.details.is_empty() => (
+                "Details",
+                format!("{} in the rust dacpac (Enter for its XML)", d.kind.label()),
+            ),
+            (None, Some(d)) => ("Details", d.details.join("\n")),
+            (None, None) => ("Details", "No differences".to_string()),
+        };
+        frame.render_widget(
+            Paragraph::new(detail)
+                .block(Block::bordered().title(detail_title))
+                .scroll((self.scroll, 0)),
+            detail_area,
+        );
+
+        frame.render_widget(
+            Paragraph::new(vec![
+                Line::from(self.status.as_str()),
+                Line::from("↑/↓ select  Enter XML  PgUp/PgDn scroll  i known/unknown  q quit"),
+            ]),
+            footer,
+        );
+    }
+}
+
+/// Browse the differences of `result` until the user quits
+pub fn browse(
+    result: &CompareResult,
+    rust_path: &Path,
+    dotnet_path: &Path,
+    key_case: KeyCase,
+    known: KnownDifferences,
+) -> Result<()> {
+    let rust_model = model_xml(rust_path)?;
+    let dotnet_model = model_xml(dotnet_path)?;
+    let element_view = |key: &ElementKey| -> Result<String> {
+        Ok(side_by_side(
+            &element_xml_by_key(&rust_model, key, key_case)?.unwrap_or_default(),
+            &element_xml_by_key(&dotnet_model, key, key_case)?.unwrap_or_default(),
+            &file_name(rust_path),
+            &file_name(dotnet_path),
+        ))
+    };
+
+    let mut browser = Browser::new(differences(result), known);
+    let mut terminal = ratatui::init();
+    let outcome = (|| -> Result<()> {
+        loop {
+            terminal.draw(|frame| browser.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !browser.handle_key(key.code, element_view)? {
+                    return Ok(());
+                }
+            }
+        }
+    })();
+    ratatui::restore();
+    outcome
+}
+
+fn model_xml(path: &Path) -> Result<String> {
+    Ok(DacpacContents::from_path(path)?
+        .get_string("model.xml")
+        .unwrap_or_default())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|f| f.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_browser_marks_known_differences() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("compare-ignore.txt");
+        let key = |name: &str| ElementKey::Named {
+            element_type: "SqlTable".to_string(),
+            name: name.to_string(),
+        };
+        let differences = vec![
+            Difference {
+                key: key("[dbo].[A]"),
+                kind: DifferenceKind::MissingInRust,
+                details: Vec::new(),
+            },
+            Difference {
+                key: key("[dbo].[B]"),
+                kind: DifferenceKind::Changed,
+                details: vec!["  Property IsAnsiNullsOn: True != False".to_string()],
+            },
+        ];
+        let mut browser = Browser::new(differences, KnownDifferences::load(&path).unwrap());
+        let view = |key: &ElementKey| Ok(format!("xml of {}", key));
+
+        assert!(browser.handle_key(KeyCode::Down, view).unwrap());
+        assert!(browser.handle_key(KeyCode::Enter, view).unwrap());
+        assert_eq!(
+            browser.expanded.as_deref(),
+            Some("xml of SqlTable [dbo].[B]")
+        );
+        assert!(browser.handle_key(KeyCode::Esc, view).unwrap());
+        assert_eq!(browser.expanded, None);
+
+        browser.handle_key(KeyCode::Char('i'), view).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "SqlTable [dbo].[B]\n"
+        );
+        browser.handle_key(KeyCode::Char('i'), view).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "");
+        assert!(!browser.handle_key(KeyCode::Char('q'), view).unwrap());
+    }
+}
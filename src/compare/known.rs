@@ -0,0 +1,180 @@
+//! Known differences: elements whose differences a comparison leaves out
+//!
+//! The ignore file lists one element per line, as comparison reports show them
+//! (`SqlProcedure [dbo].[usp_Orders]`); a `#` at the start of a line or after a space
+//! starts a comment. Keys match
+//! case-insensitively. `compare --interactive` adds the elements marked as known.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use super::model_diff::ModelDiff;
+use super::types::{CompareResult, ElementKey};
+use crate::error::SqlPackageError;
+
+/// Element keys listed in an ignore file
+#[derive(Debug, Clone, Default)]
+pub struct KnownDifferences {
+    /// The ignore file, `None` when differences are not persisted
+    pub path: Option<PathBuf>,
+    /// Lowercased element keys
+    keys: BTreeSet<String>,
+}
+
+impl KnownDifferences {
+    /// The known differences in `path`; a missing file lists none
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => {
+                return Err(SqlPackageError::ConfigReadError {
+                    path: path.to_path_buf(),
+                    source: e,
+                }
+                .into())
+            }
+        };
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            keys: text
+                .lines()
+                .map(line_key)
+                .filter(|line| !line.is_empty())
+                .collect(),
+        })
+    }
+
+    /// Whether the element with `key` is a known difference
+    pub fn contains(&self, key: &ElementKey) -> bool {
+        self.keys.contains(&key.to_string().to_lowercase())
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Mark `key` as known, appending it to the ignore file
+    pub fn add(&mut self, key: &ElementKey) -> Result<()> {
+        if !self.keys.insert(key.to_string().to_lowercase()) {
+            return Ok(());
+        }
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut text = std::fs::read_to_string(path).unwrap_or_default();
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text.push_str(&format!("{}\n", key));
+        std::fs::write(path, text).map_err(|e| SqlPackageError::OutputWriteError {
+            path: path.clone(),
+            source: e,
+        })?;
+        Ok(())
+    }
+
+    /// Unmark `key`, removing its lines from the ignore file
+    pub fn remove(&mut self, key: &ElementKey) -> Result<()> {
+        let lowered = key.to_string().to_lowercase();
+        if !self.keys.remove(&lowered) {
+            return Ok(());
+        }
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let text = std::fs::read_to_string(path).unwrap_or_default();
+        let kept: String = text
+            .split_inclusive('\n')
+            .filter(|line| line_key(line) != lowered)
+            .collect();
+        std::fs::write(path, kept).map_err(|e| SqlPackageError::OutputWriteError {
+            path: path.clone(),
+            source: e,
+        })?;
+        Ok(())
+    }
+
+    /// Leave the known differences out of `result`, returning how many were left out
+    pub fn apply(&self, result: &mut CompareResult) -> usize {
+        let Some(elements) = &mut result.elements_result else {
+            return 0;
+        };
+        let before = elements.missing_in_rust.len()
+            + elements.extra_in_rust.len()
+            + elements.differences.len();
+        elements.missing_in_rust.retain(|key| !self.contains(key));
+        elements.extra_in_rust.retain(|key| !self.contains(key));
+        elements.differences.retain(|(key, _)| !self.contains(key));
+        before
+            - elements.missing_in_rust.len()
+            - elements.extra_in_rust.len()
+            - elements.differences.len()
+    }
+
+    /// Leave the known differences out of `diff`, returning how many were left out
+    pub fn apply_to_diff(&self, diff: &mut ModelDiff) -> usize {
+        let before = diff.added.len() + diff.removed.len() + diff.changed.len();
+        diff.added.retain(|key| !self.contains(key));
+        diff.removed.retain(|key| !self.contains(key));
+        diff.changed.retain(|element| !self.contains(&element.key));
+        before - diff.added.len() - diff.removed.len() - diff.changed.len()
+    }
+}
+
+/// The lowercased key on a line of the ignore file, without its comment
+fn line_key(line: &str) -> String {
+    let line = line.trim();
+    let key = if line.starts_with('#') {
+        ""
+    } else {
+        line.split(" #").next().unwrap_or_default()
+    };
+    key.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_differences_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("compare-ignore.txt");
+        std::fs::write(&path, "# parity gaps\nSqlTable [dbo].[Orders]  # ordering").unwrap();
+
+        let mut known = KnownDifferences::load(&path).unwrap();
+        let orders = ElementKey::Named {
+            element_type: "SqlTable".to_string(),
+            name: "[dbo].[ORDERS]".to_string(),
+        };
+        let options = ElementKey::Singleton {
+            element_type: "SqlDatabaseOptions".to_string(),
+        };
+        assert!(known.contains(&orders));
+        assert!(!known.contains(&options));
+
+        known.add(&options).unwrap();
+        known.add(&options).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "# parity gaps\nSqlTable [dbo].[Orders]  # ordering\nSqlDatabaseOptions\n"
+        );
+        assert!(KnownDifferences::load(&path).unwrap().contains(&options));
+
+        known.remove(&orders).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "# parity gaps\nSqlDatabaseOptions\n"
+        );
+        assert!(KnownDifferences::load(&dir.path().join("missing.txt"))
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -3,20 +3,35 @@
 //! Ports the functionality of `tools/compare_dacpacs.py` into the Rust codebase
 //! as a first-class module.
 
+pub mod batch;
+pub mod breaking;
+pub mod changelog;
+pub mod data_loss;
+pub mod filter;
+#[cfg(feature = "tui")]
+pub mod interactive;
+pub mod known;
+pub mod model_diff;
 pub mod model_xml;
 pub mod reader;
 pub mod report;
 pub mod simple_xml;
+#[cfg(test)]
+mod test_fixtures;
 pub mod text;
 pub mod types;
+pub mod xml_view;
 
 use std::collections::HashSet;
 use std::path::Path;
 
 use anyhow::Result;
 
+use model_diff::ModelDiff;
 use reader::DacpacContents;
-use types::{CompareResult, FileStatus};
+use types::{CompareOptions, CompareResult, FileStatus, KeyCase};
+
+use crate::error::SqlPackageError;
 
 /// Known files in a dacpac that we handle explicitly.
 const KNOWN_FILES: &[&str] = &[
@@ -30,11 +45,110 @@ const KNOWN_FILES: &[&str] = &[
 
 /// Compare two dacpac files and return a structured result.
 pub fn compare_dacpacs(rust_path: &Path, dotnet_path: &Path) -> Result<CompareResult> {
+    compare_dacpacs_with_options(rust_path, dotnet_path, &CompareOptions::default())
+}
+
+/// The model elements named `name` in two dacpacs, pretty-printed side by side
+///
+/// Fails when neither dacpac has an element with that name.
+pub fn show_element(
+    rust_path: &Path,
+    dotnet_path: &Path,
+    name: &str,
+    key_case: KeyCase,
+) -> Result<String> {
+    let (rust_title, rust_xml) = element_column(rust_path, name, key_case)?;
+    let (dotnet_title, dotnet_xml) = element_column(dotnet_path, name, key_case)?;
+    if rust_xml.is_none() && dotnet_xml.is_none() {
+        return Err(anyhow::anyhow!(
+            "No element named {} in either dacpac",
+            name
+        ));
+    }
+    Ok(xml_view::side_by_side(
+        rust_xml.as_deref().unwrap_or_default(),
+        dotnet_xml.as_deref().unwrap_or_default(),
+        &rust_title,
+        &dotnet_title,
+    ))
+}
+
+/// File name of a dacpac and its pretty-printed elements named `name`
+fn element_column(path: &Path, name: &str, key_case: KeyCase) -> Result<(String, Option<String>)> {
+    let contents = DacpacContents::from_path(path)?;
+    let model_xml =
+        contents
+            .get_string("model.xml")
+            .ok_or_else(|| SqlPackageError::InvalidDacpac {
+                path: path.to_path_buf(),
+                message: "no model.xml".to_string(),
+            })?;
+    let title = path
+        .file_name()
+        .map(|f| f.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string());
+    Ok((title, xml_view::element_xml(&model_xml, name, key_case)?))
+}
+
+/// Compare two dacpac files with `options` and return a structured result.
+pub fn compare_dacpacs_with_options(
+    rust_path: &Path,
+    dotnet_path: &Path,
+    options: &CompareOptions,
+) -> Result<CompareResult> {
     let rust_dac = DacpacContents::from_path(rust_path)?;
     let dotnet_dac = DacpacContents::from_path(dotnet_path)?;
 
     let mut file_results = Vec::new();
 
+    if options.filter.is_empty() {
+        compare_files(&rust_dac, &dotnet_dac, &mut file_results);
+    } else {
+        file_results.push((
+            "(files)".to_string(),
+            FileStatus::Skipped("skipped - comparing filtered model elements".to_string()),
+        ));
+    }
+
+    // model.xml - semantic comparison
+    let mut header_result = None;
+    let mut elements_result = None;
+    let mut duplicates = Vec::new();
+
+    match (
+        rust_dac.get_string("model.xml"),
+        dotnet_dac.get_string("model.xml"),
+    ) {
+        (Some(a), Some(b)) => {
+            let (header, elems, dupes) = model_xml::compare_model_xml(&a, &b, options);
+            // A filtered compare is about model elements only
+            header_result = options.filter.is_empty().then_some(header);
+            elements_result = Some(elems);
+            duplicates = dupes;
+        }
+        (None, Some(_)) => {
+            file_results.push(("model.xml".to_string(), FileStatus::MissingInRust));
+        }
+        (Some(_), None) => {
+            file_results.push(("model.xml".to_string(), FileStatus::MissingInDotnet));
+        }
+        (None, None) => {}
+    }
+
+    Ok(CompareResult {
+        file_results,
+        header_result,
+        elements_result,
+        duplicates,
+    })
+}
+
+/// Compare every file of the two dacpacs except model.xml
+fn compare_files(
+    rust_dac: &DacpacContents,
+    dotnet_dac: &DacpacContents,
+    file_results: &mut Vec<(String, FileStatus)>,
+) {
     // Origin.xml - skip (timestamps/GUIDs always differ)
     file_results.push((
         "Origin.xml".to_string(),
@@ -113,35 +227,36 @@ pub fn compare_dacpacs(rust_path: &Path, dotnet_path: &Path) -> Result<CompareRe
             }
         }
     }
+}
 
-    // model.xml - semantic comparison
-    let mut header_result = None;
-    let mut elements_result = None;
-    let mut duplicate_warnings = Vec::new();
+/// Diff two builds of a project at the object level (old first).
+///
+/// Each side is either a .dacpac or a .sqlproj, which is built in memory.
+pub fn diff_builds(old_path: &Path, new_path: &Path) -> Result<ModelDiff> {
+    let old_xml = load_model_xml(old_path)?;
+    let new_xml = load_model_xml(new_path)?;
+    model_diff::diff_model_xml(&old_xml, &new_xml)
+}
 
-    match (
-        rust_dac.get_string("model.xml"),
-        dotnet_dac.get_string("model.xml"),
-    ) {
-        (Some(a), Some(b)) => {
-            let (header, elems, dupes) = model_xml::compare_model_xml(&a, &b);
-            header_result = Some(header);
-            elements_result = Some(elems);
-            duplicate_warnings = dupes;
-        }
-        (None, Some(_)) => {
-            file_results.push(("model.xml".to_string(), FileStatus::MissingInRust));
-        }
-        (Some(_), None) => {
-            file_results.push(("model.xml".to_string(), FileStatus::MissingInDotnet));
-        }
-        (None, None) => {}
+/// model.xml of a dacpac, or generated from a sqlproj
+fn load_model_xml(path: &Path) -> Result<String> {
+    let is_project = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("sqlproj"));
+    if is_project {
+        let (project, model) = crate::load_model(path)?;
+        let mut buffer = Vec::new();
+        crate::dacpac::generate_model_xml(&mut buffer, &model, &project)?;
+        return Ok(String::from_utf8(buffer)?);
     }
 
-    Ok(CompareResult {
-        file_results,
-        header_result,
-        elements_result,
-        duplicate_warnings,
-    })
+    DacpacContents::from_path(path)?
+        .get_string("model.xml")
+        .ok_or_else(|| {
+            SqlPackageError::InvalidDacpac {
+                path: path.to_path_buf(),
+                message: "no model.xml".to_string(),
+            }
+            .into()
+        })
 }
@@ -0,0 +1,453 @@
+//! Object-level diff between two versions of a model
+//!
+//! Where [`super::model_xml`] checks that a rust and a dotnet model.xml are identical, this
+//! compares an old and a new build of the same project and reports which objects were
+//! added, removed or changed. Changed objects list their property and relationship
+//! changes, and those of their columns and parameters, e.g.
+//!
+//! ```text
+//! ~ SqlTable [dbo].[Orders]
+//!     + SqlSimpleColumn [dbo].[Orders].[Notes]
+//!     ~ SqlSimpleColumn [dbo].[Orders].[Total]
+//!         Type: decimal(18,2) -> decimal(19,4)
+//! ```
+//!
+//! Column and parameter data types are reported as a `Type` property (`nvarchar(50)`)
+//! rather than as changes to their inline `SqlTypeSpecifier` element. Annotations are
+//! ignored: they carry build details such as disambiguators, not schema changes.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::Result;
+
+use super::filter::KeyFilter;
+use super::model_xml::{
+    element_key, find_child, find_children, get_properties, get_ref_name,
+    inline_element_fingerprint, is_ns_element,
+};
+use super::types::{ElementKey, RelEntry};
+
+/// A property whose value differs between the old and new element
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyChange {
+    pub name: String,
+    /// Value in the old model (`None` when the property was not set)
+    pub old: Option<String>,
+    /// Value in the new model (`None` when the property was removed)
+    pub new: Option<String>,
+}
+
+/// Entries added to or removed from a relationship
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelationshipChange {
+    pub name: String,
+    pub added: Vec<RelEntry>,
+    pub removed: Vec<RelEntry>,
+}
+
+/// Changes to an element present in both models
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElementDiff {
+    pub key: ElementKey,
+    pub properties: Vec<PropertyChange>,
+    pub relationships: Vec<RelationshipChange>,
+    /// Named inline elements (columns, parameters) only in the new element
+    pub added_children: Vec<ElementKey>,
+    /// Named inline elements only in the old element
+    pub removed_children: Vec<ElementKey>,
+    /// Named inline elements present in both, with changes
+    pub changed_children: Vec<ElementDiff>,
+}
+
+impl ElementDiff {
+    /// Whether the element is unchanged
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty()
+            && self.relationships.is_empty()
+            && self.added_children.is_empty()
+            && self.removed_children.is_empty()
+            && self.changed_children.is_empty()
+    }
+
+    /// Change to the property `name`, if it changed
+    pub fn property(&self, name: &str) -> Option<&PropertyChange> {
+        self.properties.iter().find(|p| p.name == name)
+    }
+}
+
+/// Objects added, removed and changed between two models
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModelDiff {
+    pub added: Vec<ElementKey>,
+    pub removed: Vec<ElementKey>,
+    pub changed: Vec<ElementDiff>,
+    /// Columns with a default constraint in the new model, e.g. `[dbo].[Orders].[Status]`
+    pub defaulted_columns: BTreeSet<String>,
+}
+
+impl ModelDiff {
+    /// Whether the two models are equivalent
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Objects added or removed
+    pub fn element_differences(&self) -> usize {
+        self.added.len() + self.removed.len()
+    }
+
+    /// Objects changed
+    pub fn property_differences(&self) -> usize {
+        self.changed.len()
+    }
+
+    /// Keep only the objects `filter` matches
+    pub fn retain(&mut self, filter: &KeyFilter) {
+        self.added.retain(|key| filter.matches(key));
+        self.removed.retain(|key| filter.matches(key));
+        self.changed.retain(|element| filter.matches(&element.key));
+    }
+}
+
+/// Diff the `<Model>` elements of two model.xml documents (old first)
+pub fn diff_model_xml(old_xml: &str, new_xml: &str) -> Result<ModelDiff> {
+    let old_doc = roxmltree::Document::parse(old_xml)?;
+    let new_doc = roxmltree::Document::parse(new_xml)?;
+    let old_elements = index_model(&old_doc);
+    let new_elements = index_model(&new_doc);
+
+    let mut diff = ModelDiff::default();
+    for (key, new_element) in &new_elements {
+        match old_elements.get(key) {
+            None => diff.added.push(key.clone()),
+            Some(old_element) => {
+                let element_diff = diff_element(key.clone(), old_element, new_element);
+                if !element_diff.is_empty() {
+                    diff.changed.push(element_diff);
+                }
+            }
+        }
+    }
+    diff.defaulted_columns = new_elements
+        .values()
+        .filter(|element| element.attribute("Type") == Some("SqlDefaultConstraint"))
+        .filter_map(|element| get_ref_name(element, "ForColumn"))
+        .collect();
+    diff.removed = old_elements
+        .keys()
+        .filter(|key| !new_elements.contains_key(*key))
+        .cloned()
+        .collect();
+
+    Ok(diff)
+}
+
+/// Top-level elements keyed by [`element_key`], in key order
+fn index_model<'a, 'input>(
+    document: &'a roxmltree::Document<'input>,
+) -> BTreeMap<ElementKey, roxmltree::Node<'a, 'input>> {
+    let root = document.root_element();
+    let Some(model) = find_child(&root, "Model") else {
+        return BTreeMap::new();
+    };
+    model
+        .children()
+        .filter(|c| is_ns_element(c, "Element"))
+        .map(|element| (element_key(&element), element))
+        .collect()
+}
+
+/// Properties, relationship entries and named children of one element
+struct ElementContents<'a, 'input> {
+    properties: BTreeMap<String, String>,
+    relationships: BTreeMap<String, BTreeSet<RelEntry>>,
+    children: BTreeMap<ElementKey, roxmltree::Node<'a, 'input>>,
+}
+
+impl<'a, 'input> ElementContents<'a, 'input> {
+    fn of(element: &roxmltree::Node<'a, 'input>) -> Self {
+        let mut properties = get_properties(element);
+        let mut relationships = BTreeMap::new();
+        let mut children = BTreeMap::new();
+
+        for relationship in find_children(element, "Relationship") {
+            let name = relationship.attribute("Name").unwrap_or("").to_string();
+            let mut entries = BTreeSet::new();
+            for entry in find_children(&relationship, "Entry") {
+                if let Some(reference) = find_child(&entry, "References") {
+                    let mut target = reference.attribute("Name").unwrap_or("").to_string();
+                    if let Some(source) = reference.attribute("ExternalSource") {
+                        target = format!("{}@{}", target, source);
+                    }
+                    entries.insert(RelEntry::Ref(target));
+                } else if let Some(inline) = find_child(&entry, "Element") {
+                    if inline.attribute("Name").is_some() {
+                        children.insert(element_key(&inline), inline);
+                    } else if name == "TypeSpecifier" {
+                        properties.insert("Type".to_string(), type_description(&inline));
+                    } else {
+                        entries.insert(RelEntry::Inline(inline_element_fingerprint(&inline)));
+                    }
+                }
+            }
+            if !entries.is_empty() {
+                relationships.insert(name, entries);
+            }
+        }
+
+        Self {
+            properties,
+            relationships,
+            children,
+        }
+    }
+}
+
+fn diff_element(
+    key: ElementKey,
+    old: &roxmltree::Node<'_, '_>,
+    new: &roxmltree::Node<'_, '_>,
+) -> ElementDiff {
+    let old = ElementContents::of(old);
+    let new = ElementContents::of(new);
+
+    let property_names: BTreeSet<&String> =
+        old.properties.keys().chain(new.properties.keys()).collect();
+    let properties = property_names
+        .into_iter()
+        .filter(|name| old.properties.get(*name) != new.properties.get(*name))
+        .map(|name| PropertyChange {
+            name: name.clone(),
+            old: old.properties.get(name).cloned(),
+            new: new.properties.get(name).cloned(),
+        })
+        .collect();
+
+    let empty = BTreeSet::new();
+    let relationship_names: BTreeSet<&String> = old
+        .relationships
+        .keys()
+        .chain(new.relationships.keys())
+        .collect();
+    let relationships = relationship_names
+        .into_iter()
+        .filter_map(|name| {
+            let old_entries = old.relationships.get(name).unwrap_or(&empty);
+            let new_entries = new.relationships.get(name).unwrap_or(&empty);
+            let change = RelationshipChange {
+                name: name.clone(),
+                added: new_entries.difference(old_entries).cloned().collect(),
+                removed: old_entries.difference(new_entries).cloned().collect(),
+            };
+            (!change.added.is_empty() || !change.removed.is_empty()).then_some(change)
+        })
+        .collect();
+
+    let mut changed_children = Vec::new();
+    for (child_key, new_child) in &new.children {
+        if let Some(old_child) = old.children.get(child_key) {
+            let child_diff = diff_element(child_key.clone(), old_child, new_child);
+            if !child_diff.is_empty() {
+                changed_children.push(child_diff);
+            }
+        }
+    }
+
+    ElementDiff {
+        key,
+        properties,
+        relationships,
+        added_children: new
+            .children
+            .keys()
+            .filter(|k| !old.children.contains_key(*k))
+            .cloned()
+            .collect(),
+        removed_children: old
+            .children
+            .keys()
+            .filter(|k| !new.children.contains_key(*k))
+            .cloned()
+            .collect(),
+        changed_children,
+    }
+}
+
+/// Data type of a `SqlTypeSpecifier` as written in SQL, e.g. `nvarchar(50)`,
+/// `decimal(18,2)`, `varbinary(max)` or `[dbo].[Phone]` for user-defined types
+pub fn type_description(type_specifier: &roxmltree::Node) -> String {
+    let reference = find_children(type_specifier, "Relationship")
+        .into_iter()
+        .find(|r| r.attribute("Name") == Some("Type"))
+        .and_then(|r| find_child(&r, "Entry"))
+        .and_then(|e| find_child(&e, "References"));
+    let name = match reference {
+        Some(r) if r.attribute("ExternalSource") == Some("BuiltIns") => r
+            .attribute("Name")
+            .unwrap_or("")
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_string(),
+        Some(r) => r.attribute("Name").unwrap_or("").to_string(),
+        None => String::new(),
+    };
+
+    let properties = get_properties(type_specifier);
+    let property = |name: &str| properties.get(name).map(String::as_str);
+    if property("IsMax") == Some("True") {
+        format!("{}(max)", name)
+    } else if let Some(length) = property("Length") {
+        format!("{}({})", name, length)
+    } else if let Some(precision) = property("Precision") {
+        format!(
+            "{}({},{})",
+            name,
+            precision,
+            property("Scale").unwrap_or("0")
+        )
+    } else if let Some(scale) = property("Scale") {
+        format!("{}({})", name, scale)
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compare::test_fixtures::{column, model, property, table};
+
+    #[test]
+    fn test_diff_identical_models_is_empty() {
+        let xml = model(&table(
+            "[dbo].[T]",
+            &[column("[dbo].[T].[Id]", "int", "", "")],
+        ));
+        assert!(diff_model_xml(&xml, &xml).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_diff_added_removed_and_changed_objects() {
+        let old = model(&format!(
+            "{}{}",
+            table(
+                "[dbo].[T]",
+                &[
+                    column("[dbo].[T].[Id]", "int", "", ""),
+                    column(
+                        "[dbo].[T].[Name]",
+                        "nvarchar",
+                        &property("Length", "50"),
+                        ""
+                    ),
+                    column("[dbo].[T].[Legacy]", "bit", "", ""),
+                ]
+            ),
+            r#"<Element Type="SqlView" Name="[dbo].[Old]" />"#
+        ));
+        let new = model(&format!(
+            "{}{}",
+            table(
+                "[dbo].[T]",
+                &[
+                    column("[dbo].[T].[Id]", "int", "", ""),
+                    column(
+                        "[dbo].[T].[Name]",
+                        "nvarchar",
+                        &property("Length", "100"),
+                        &property("IsNullable", "False")
+                    ),
+                    column(
+                        "[dbo].[T].[Notes]",
+                        "nvarchar",
+                        &property("IsMax", "True"),
+                        ""
+                    ),
+                ]
+            ),
+            r#"<Element Type="SqlView" Name="[dbo].[New]" />"#
+        ));
+
+        let diff = diff_model_xml(&old, &new).unwrap();
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].to_string(), "SqlView [dbo].[New]");
+        assert_eq!(diff.removed[0].to_string(), "SqlView [dbo].[Old]");
+
+        assert_eq!(diff.changed.len(), 1);
+        let table = &diff.changed[0];
+        assert_eq!(table.key.to_string(), "SqlTable [dbo].[T]");
+        assert!(table.properties.is_empty());
+        assert!(table.relationships.is_empty());
+        assert_eq!(
+            table.added_children[0].to_string(),
+            "SqlSimpleColumn [dbo].[T].[Notes]"
+        );
+        assert_eq!(
+            table.removed_children[0].to_string(),
+            "SqlSimpleColumn [dbo].[T].[Legacy]"
+        );
+
+        let name = &table.changed_children[0];
+        assert_eq!(
+            name.property("Type"),
+            Some(&PropertyChange {
+                name: "Type".to_string(),
+                old: Some("nvarchar(50)".to_string()),
+                new: Some("nvarchar(100)".to_string()),
+            })
+        );
+        let nullable = name.property("IsNullable").unwrap();
+        assert_eq!(nullable.old, None);
+        assert_eq!(nullable.new.as_deref(), Some("False"));
+    }
+
+    #[test]
+    fn test_diff_relationship_changes() {
+        let old = model(
+            r#"<Element Type="SqlView" Name="[dbo].[V]">
+  <Relationship Name="QueryDependencies">
+    <Entry><References Name="[dbo].[A]" /></Entry>
+    <Entry><References Name="[dbo].[B]" /></Entry>
+  </Relationship>
+</Element>"#,
+        );
+        let new = model(
+            r#"<Element Type="SqlView" Name="[dbo].[V]">
+  <Relationship Name="QueryDependencies">
+    <Entry><References Name="[dbo].[B]" /></Entry>
+    <Entry><References Name="[dbo].[C]" /></Entry>
+  </Relationship>
+</Element>"#,
+        );
+
+        let diff = diff_model_xml(&old, &new).unwrap();
+        let relationships = &diff.changed[0].relationships;
+        assert_eq!(relationships.len(), 1);
+        assert_eq!(relationships[0].name, "QueryDependencies");
+        assert_eq!(
+            relationships[0].added,
+            vec![RelEntry::Ref("[dbo].[C]".to_string())]
+        );
+        assert_eq!(
+            relationships[0].removed,
+            vec![RelEntry::Ref("[dbo].[A]".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_type_description() {
+        let xml = r#"<Element xmlns="http://schemas.microsoft.com/sqlserver/dac/Serialization/2012/02" Type="SqlTypeSpecifier">
+  <Property Name="Precision" Value="18" />
+  <Property Name="Scale" Value="2" />
+  <Relationship Name="Type"><Entry><References ExternalSource="BuiltIns" Name="[decimal]" /></Entry></Relationship>
+</Element>"#;
+        let doc = roxmltree::Document::parse(xml).unwrap();
+        assert_eq!(type_description(&doc.root_element()), "decimal(18,2)");
+
+        let xml = r#"<Element xmlns="http://schemas.microsoft.com/sqlserver/dac/Serialization/2012/02" Type="SqlTypeSpecifier">
+  <Relationship Name="Type"><Entry><References Name="[dbo].[Phone]" /></Entry></Relationship>
+</Element>"#;
+        let doc = roxmltree::Document::parse(xml).unwrap();
+        assert_eq!(type_description(&doc.root_element()), "[dbo].[Phone]");
+    }
+}
@@ -2,30 +2,33 @@
 
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
-use super::types::{ElementKey, HeaderResult, ModelElementsResult, RelEntry};
+use super::types::{
+    CompareOptions, DuplicateElement, DuplicateKey, ElementKey, HeaderResult, ModelElementsResult,
+    RelEntry,
+};
 
 const NS: &str = "http://schemas.microsoft.com/sqlserver/dac/Serialization/2012/02";
 
 /// Check if a node is an element with the given local name in the DAC namespace.
-fn is_ns_element(node: &roxmltree::Node, local_name: &str) -> bool {
+pub(super) fn is_ns_element(node: &roxmltree::Node, local_name: &str) -> bool {
     node.is_element()
         && node.tag_name().name() == local_name
         && node.tag_name().namespace() == Some(NS)
 }
 
 /// Find the first child element with the given local name in the DAC namespace.
-fn find_child<'a>(
-    parent: &'a roxmltree::Node<'a, 'a>,
+pub(super) fn find_child<'a, 'input>(
+    parent: &roxmltree::Node<'a, 'input>,
     local_name: &str,
-) -> Option<roxmltree::Node<'a, 'a>> {
+) -> Option<roxmltree::Node<'a, 'input>> {
     parent.children().find(|c| is_ns_element(c, local_name))
 }
 
 /// Find all child elements with the given local name in the DAC namespace.
-fn find_children<'a>(
-    parent: &'a roxmltree::Node<'a, 'a>,
+pub(super) fn find_children<'a, 'input>(
+    parent: &roxmltree::Node<'a, 'input>,
     local_name: &str,
-) -> Vec<roxmltree::Node<'a, 'a>> {
+) -> Vec<roxmltree::Node<'a, 'input>> {
     parent
         .children()
         .filter(|c| is_ns_element(c, local_name))
@@ -34,7 +37,7 @@ fn find_children<'a>(
 
 /// Get the Name attribute of the first References in a named Relationship.
 /// Matches Python's `get_ref_name()`.
-fn get_ref_name(elem: &roxmltree::Node, rel_name: &str) -> Option<String> {
+pub(super) fn get_ref_name(elem: &roxmltree::Node, rel_name: &str) -> Option<String> {
     let rel = elem
         .children()
         .find(|c| is_ns_element(c, "Relationship") && c.attribute("Name") == Some(rel_name))?;
@@ -110,7 +113,7 @@ pub fn get_properties(elem: &roxmltree::Node) -> BTreeMap<String, String> {
 
 /// Create a fingerprint of an inline element for comparison (order-independent).
 /// Matches Python's `inline_element_fingerprint()`.
-fn inline_element_fingerprint(elem: &roxmltree::Node) -> String {
+pub(super) fn inline_element_fingerprint(elem: &roxmltree::Node) -> String {
     let type_part = elem.attribute("Type").unwrap_or("");
 
     // Properties sorted by Name
@@ -402,14 +405,15 @@ pub fn compare_header(
 
 /// Compare two model.xml documents semantically.
 /// Matches Python's `compare_model_xml()`.
+///
+/// Elements are matched by key under `options.key_case`. Keys that match but are spelled
+/// differently are reported as a `Name` difference, and results name elements as
+/// spelled in the rust model. With a filter, only matching elements are compared.
 pub fn compare_model_xml(
     xml_a: &str,
     xml_b: &str,
-) -> (
-    HeaderResult,
-    ModelElementsResult,
-    Vec<(String, Vec<ElementKey>)>,
-) {
+    options: &CompareOptions,
+) -> (HeaderResult, ModelElementsResult, Vec<DuplicateKey>) {
     let doc_a = roxmltree::Document::parse(xml_a).expect("Failed to parse rust model.xml");
     let doc_b = roxmltree::Document::parse(xml_b).expect("Failed to parse dotnet model.xml");
     let root_a = doc_a.root_element();
@@ -424,57 +428,78 @@ pub fn compare_model_xml(
     let model_a = find_child(&root_a, "Model").expect("No Model element in rust model.xml");
     let model_b = find_child(&root_b, "Model").expect("No Model element in dotnet model.xml");
 
-    // Index elements by key
+    // Index elements by normalized key, keeping the key as spelled
+    type ElementIndex<'a> = HashMap<ElementKey, (ElementKey, roxmltree::Node<'a, 'a>)>;
     fn index_elements<'a>(
         model: &roxmltree::Node<'a, 'a>,
-    ) -> (
-        HashMap<ElementKey, roxmltree::Node<'a, 'a>>,
-        Vec<ElementKey>,
-    ) {
+        options: &CompareOptions,
+        model_name: &str,
+    ) -> (ElementIndex<'a>, Vec<DuplicateKey>) {
         let mut index = HashMap::new();
-        let mut duplicates = Vec::new();
+        let mut occurrences: HashMap<ElementKey, Vec<DuplicateElement>> = HashMap::new();
         for elem in model.children().filter(|c| is_ns_element(c, "Element")) {
             let key = element_key(&elem);
-            if index.contains_key(&key) {
-                duplicates.push(key.clone());
+            if !options.filter.matches(&key) {
+                continue;
             }
-            index.insert(key, elem);
+            let normalized = key.normalized(options.key_case);
+            occurrences
+                .entry(normalized.clone())
+                .or_default()
+                .push(DuplicateElement {
+                    key: key.clone(),
+                    line: elem.document().text_pos_at(elem.range().start).row,
+                });
+            index.insert(normalized, (key, elem));
         }
+        let mut duplicates: Vec<DuplicateKey> = occurrences
+            .into_values()
+            .filter(|elements| elements.len() > 1)
+            .map(|elements| DuplicateKey {
+                model: model_name.to_string(),
+                elements,
+            })
+            .collect();
+        duplicates.sort_by_key(|duplicate| duplicate.elements[0].line);
         (index, duplicates)
     }
 
-    let (elems_a, dupes_a) = index_elements(&model_a);
-    let (elems_b, dupes_b) = index_elements(&model_b);
-
-    let mut duplicate_warnings = Vec::new();
-    if !dupes_a.is_empty() {
-        duplicate_warnings.push(("rust".to_string(), dupes_a));
-    }
-    if !dupes_b.is_empty() {
-        duplicate_warnings.push(("dotnet".to_string(), dupes_b));
-    }
+    let (elems_a, mut duplicates) = index_elements(&model_a, options, "rust");
+    let (elems_b, dupes_b) = index_elements(&model_b, options, "dotnet");
+    duplicates.extend(dupes_b);
 
     let keys_a: HashSet<&ElementKey> = elems_a.keys().collect();
     let keys_b: HashSet<&ElementKey> = elems_b.keys().collect();
 
-    let mut missing_in_rust: Vec<ElementKey> =
-        keys_b.difference(&keys_a).map(|k| (*k).clone()).collect();
+    let mut missing_in_rust: Vec<ElementKey> = keys_b
+        .difference(&keys_a)
+        .map(|k| elems_b[*k].0.clone())
+        .collect();
     missing_in_rust.sort_by_key(|a| a.to_string());
 
-    let mut extra_in_rust: Vec<ElementKey> =
-        keys_a.difference(&keys_b).map(|k| (*k).clone()).collect();
+    let mut extra_in_rust: Vec<ElementKey> = keys_a
+        .difference(&keys_b)
+        .map(|k| elems_a[*k].0.clone())
+        .collect();
     extra_in_rust.sort_by_key(|a| a.to_string());
 
     let mut common: Vec<&ElementKey> = keys_a.intersection(&keys_b).copied().collect();
-    common.sort_by_key(|a| a.to_string());
+    common.sort_by_key(|a| elems_a[*a].0.to_string());
 
     let mut differences = Vec::new();
     for key in common {
-        let elem_a = &elems_a[key];
-        let elem_b = &elems_b[key];
-        let diffs = diff_element(elem_a, elem_b);
+        let (key_a, elem_a) = &elems_a[key];
+        let (key_b, elem_b) = &elems_b[key];
+        let mut diffs = Vec::new();
+        if key_a != key_b {
+            diffs.push(format!(
+                "    Name: dotnet=\"{}\", rust=\"{}\"",
+                key_b, key_a
+            ));
+        }
+        diffs.extend(diff_element(elem_a, elem_b));
         if !diffs.is_empty() {
-            differences.push((key.clone(), diffs));
+            differences.push((key_a.clone(), diffs));
         }
     }
 
@@ -486,12 +511,13 @@ pub fn compare_model_xml(
         differences,
     };
 
-    (header_result, elements_result, duplicate_warnings)
+    (header_result, elements_result, duplicates)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::compare::types::KeyCase;
 
     const MINIMAL_MODEL: &str = r#"<?xml version="1.0" encoding="utf-8"?>
 <DataSchemaModel xmlns="http://schemas.microsoft.com/sqlserver/dac/Serialization/2012/02">
@@ -509,7 +535,8 @@ mod tests {
 
     #[test]
     fn test_identical_model() {
-        let (header, elems, dupes) = compare_model_xml(MINIMAL_MODEL, MINIMAL_MODEL);
+        let (header, elems, dupes) =
+            compare_model_xml(MINIMAL_MODEL, MINIMAL_MODEL, &CompareOptions::default());
         assert!(header.is_ok);
         assert!(elems.missing_in_rust.is_empty());
         assert!(elems.extra_in_rust.is_empty());
@@ -618,7 +645,7 @@ mod tests {
   </Model>
 </DataSchemaModel>"#;
 
-        let (_, elems, _) = compare_model_xml(xml_a, xml_b);
+        let (_, elems, _) = compare_model_xml(xml_a, xml_b, &CompareOptions::default());
         assert_eq!(elems.missing_in_rust.len(), 1);
         assert_eq!(
             elems.missing_in_rust[0],
@@ -652,11 +679,109 @@ mod tests {
   </Model>
 </DataSchemaModel>"#;
 
-        let (_, elems, _) = compare_model_xml(xml_a, xml_b);
+        let (_, elems, _) = compare_model_xml(xml_a, xml_b, &CompareOptions::default());
         assert_eq!(elems.differences.len(), 1);
         assert!(elems.differences[0].1[0].contains("IsDefault"));
     }
 
+    #[test]
+    fn test_key_case() {
+        let model = |elements: &str| {
+            format!(
+                r#"<?xml version="1.0" encoding="utf-8"?>
+<DataSchemaModel xmlns="http://schemas.microsoft.com/sqlserver/dac/Serialization/2012/02">
+  <Header />
+  <Model>{}</Model>
+</DataSchemaModel>"#,
+                elements
+            )
+        };
+        let xml_a = model(
+            r#"<Element Type="SqlTable" Name="[dbo].[Users]" />
+    <Element Type="SqlTable" Name="[dbo].[USERS]" />"#,
+        );
+        let xml_b = model(r#"<Element Type="SqlTable" Name="[dbo].[users]" />"#);
+
+        // Case-insensitive: one table, spelled differently, and a duplicate in rust
+        let (_, elems, dupes) = compare_model_xml(
+            &xml_a,
+            &xml_b,
+            &CompareOptions {
+                key_case: KeyCase::Insensitive,
+                ..CompareOptions::default()
+            },
+        );
+        assert!(elems.missing_in_rust.is_empty());
+        assert!(elems.extra_in_rust.is_empty());
+        assert_eq!(elems.differences.len(), 1);
+        assert_eq!(
+            elems.differences[0].1,
+            vec![
+                "    Name: dotnet=\"SqlTable [dbo].[users]\", rust=\"SqlTable [dbo].[USERS]\""
+                    .to_string()
+            ]
+        );
+        assert_eq!(dupes.len(), 1);
+        assert_eq!(dupes[0].model, "rust");
+        let spellings: Vec<String> = dupes[0]
+            .elements
+            .iter()
+            .map(|e| e.key.to_string())
+            .collect();
+        assert_eq!(
+            spellings,
+            vec!["SqlTable [dbo].[Users]", "SqlTable [dbo].[USERS]"]
+        );
+        assert!(dupes[0].elements[0].line < dupes[0].elements[1].line);
+
+        // Case-sensitive: three distinct tables, no duplicates
+        let (_, elems, dupes) = compare_model_xml(
+            &xml_a,
+            &xml_b,
+            &CompareOptions {
+                key_case: KeyCase::Sensitive,
+                ..CompareOptions::default()
+            },
+        );
+        assert_eq!(elems.missing_in_rust.len(), 1);
+        assert_eq!(elems.extra_in_rust.len(), 2);
+        assert!(dupes.is_empty());
+    }
+
+    #[test]
+    fn test_filtered_compare() {
+        let xml_a = r#"<?xml version="1.0" encoding="utf-8"?>
+<DataSchemaModel xmlns="http://schemas.microsoft.com/sqlserver/dac/Serialization/2012/02">
+  <Header />
+  <Model>
+    <Element Type="SqlTable" Name="[dbo].[Orders]" />
+    <Element Type="SqlProcedure" Name="[dbo].[usp_GetOrders]"><Property Name="IsAnsiNullsOn" Value="True" /></Element>
+  </Model>
+</DataSchemaModel>"#;
+        let xml_b = r#"<?xml version="1.0" encoding="utf-8"?>
+<DataSchemaModel xmlns="http://schemas.microsoft.com/sqlserver/dac/Serialization/2012/02">
+  <Header />
+  <Model>
+    <Element Type="SqlProcedure" Name="[dbo].[usp_GetOrders]" />
+    <Element Type="SqlProcedure" Name="[sales].[usp_GetOrders]" />
+  </Model>
+</DataSchemaModel>"#;
+
+        let options = CompareOptions {
+            filter: crate::compare::filter::KeyFilter {
+                element_types: vec!["SqlProcedure".to_string()],
+                name: Some(glob::Pattern::new("dbo.usp_*").unwrap()),
+                ..Default::default()
+            },
+            ..CompareOptions::default()
+        };
+        let (_, elems, _) = compare_model_xml(xml_a, xml_b, &options);
+        assert_eq!((elems.total_rust, elems.total_dotnet), (1, 1));
+        assert!(elems.missing_in_rust.is_empty());
+        assert!(elems.extra_in_rust.is_empty());
+        assert_eq!(elems.differences.len(), 1);
+    }
+
     #[test]
     fn test_header_comparison() {
         let xml_a = r#"<?xml version="1.0" encoding="utf-8"?>
@@ -679,7 +804,7 @@ mod tests {
   <Model />
 </DataSchemaModel>"#;
 
-        let (header, _, _) = compare_model_xml(xml_a, xml_b);
+        let (header, _, _) = compare_model_xml(xml_a, xml_b, &CompareOptions::default());
         assert!(!header.is_ok);
         assert!(!header.diffs.is_empty());
     }
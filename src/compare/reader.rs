@@ -2,7 +2,7 @@
 
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::Read;
+use std::io::{Cursor, Read, Seek};
 use std::path::Path;
 
 use anyhow::Result;
@@ -10,6 +10,16 @@ use zip::ZipArchive;
 
 use crate::error::SqlPackageError;
 
+/// Short names accepted for the standard dacpac parts.
+const PART_ALIASES: &[(&str, &str)] = &[
+    ("model", "model.xml"),
+    ("origin", "Origin.xml"),
+    ("metadata", "DacMetadata.xml"),
+    ("content-types", "[Content_Types].xml"),
+    ("predeploy", "predeploy.sql"),
+    ("postdeploy", "postdeploy.sql"),
+];
+
 /// All files from a dacpac ZIP, loaded into memory.
 pub struct DacpacContents {
     files: HashMap<String, Vec<u8>>,
@@ -22,8 +32,16 @@ impl DacpacContents {
             path: path.to_path_buf(),
             source: e,
         })?;
+        Self::from_reader(file, path)
+    }
+
+    /// Read all entries from a dacpac held in memory (e.g. one registered on a server).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Self::from_reader(Cursor::new(bytes), Path::new("<memory>"))
+    }
 
-        let mut archive = ZipArchive::new(file).map_err(|e| SqlPackageError::ZipError {
+    fn from_reader<R: Read + Seek>(reader: R, path: &Path) -> Result<Self> {
+        let mut archive = ZipArchive::new(reader).map_err(|e| SqlPackageError::ZipError {
             message: format!("Failed to read dacpac {}: {}", path.display(), e),
         })?;
 
@@ -59,6 +77,20 @@ impl DacpacContents {
         self.files.get(name).map(|v| v.as_slice())
     }
 
+    /// Find a part by file name or short name (`model`, `origin`, `metadata`,
+    /// `content-types`, `predeploy`, `postdeploy`), ignoring case.
+    pub fn find_part(&self, part: &str) -> Option<(&str, &[u8])> {
+        let part = part.trim();
+        let file_name = PART_ALIASES
+            .iter()
+            .find(|(alias, _)| alias.eq_ignore_ascii_case(part))
+            .map_or(part, |(_, file_name)| file_name);
+        self.files
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(file_name))
+            .map(|(name, data)| (name.as_str(), data.as_slice()))
+    }
+
     /// List all file names in the dacpac.
     pub fn file_names(&self) -> impl Iterator<Item = &str> {
         self.files.keys().map(|s| s.as_str())
@@ -104,6 +136,27 @@ mod tests {
         assert_eq!(names, vec!["a.xml", "b.sql"]);
     }
 
+    #[test]
+    fn test_find_part() {
+        let tmp = create_test_zip(&[("model.xml", b"<root/>"), ("Origin.xml", b"<o/>")]);
+        let contents = DacpacContents::from_path(tmp.path()).unwrap();
+        assert_eq!(
+            contents.find_part("model"),
+            Some(("model.xml", b"<root/>".as_slice()))
+        );
+        assert_eq!(contents.find_part("origin.xml").unwrap().0, "Origin.xml");
+        assert!(contents.find_part("metadata").is_none());
+    }
+
+    #[test]
+    fn test_read_from_bytes() {
+        let tmp = create_test_zip(&[("model.xml", b"<root/>")]);
+        let bytes = std::fs::read(tmp.path()).unwrap();
+        let contents = DacpacContents::from_bytes(&bytes).unwrap();
+        assert_eq!(contents.get_string("model.xml").unwrap(), "<root/>");
+        assert!(DacpacContents::from_bytes(b"not a zip").is_err());
+    }
+
     #[test]
     fn test_nonexistent_path() {
         let result = DacpacContents::from_path(Path::new("/nonexistent/file.dacpac"));
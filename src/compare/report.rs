@@ -1,6 +1,39 @@
-//! Human-readable comparison report printer
+//! Comparison report printers, human-readable or JSON
 
-use super::types::{CompareResult, FileStatus};
+use std::fmt::Write;
+
+use serde_json::{json, Value};
+
+use super::breaking::ClassifiedChange;
+use super::data_loss::DataLossWarning;
+use super::model_diff::{ElementDiff, ModelDiff, PropertyChange};
+use super::types::{CompareResult, DuplicateKey, FileStatus, RelEntry};
+
+/// Property values longer than this (or spanning lines, like scripts) are reported as
+/// "changed" instead of being printed
+const MAX_VALUE_LENGTH: usize = 60;
+
+/// Format of the `compare` report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportFormat {
+    /// Human-readable, as the Python tool prints it (default)
+    #[default]
+    Text,
+    /// JSON for CI tooling, see [`format_json_report`]
+    Json,
+}
+
+impl std::str::FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(ReportFormat::Text),
+            "json" => Ok(ReportFormat::Json),
+            _ => Err(format!("Unknown report format: {}", s)),
+        }
+    }
+}
 
 /// Print the comparison report to stdout, matching the Python tool's format.
 pub fn print_report(result: &CompareResult) {
@@ -90,4 +123,253 @@ pub fn print_report(result: &CompareResult) {
             elems.differences.len()
         );
     }
+
+    if !result.duplicates.is_empty() {
+        println!();
+        print!("{}", format_duplicates(&result.duplicates));
+    }
+}
+
+/// The duplicate keys section of the text report
+pub fn format_duplicates(duplicates: &[DuplicateKey]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "--- model.xml: Duplicate keys ---");
+    for model in ["rust", "dotnet"] {
+        let keys: Vec<&DuplicateKey> = duplicates.iter().filter(|d| d.model == model).collect();
+        if keys.is_empty() {
+            continue;
+        }
+        let _ = writeln!(
+            out,
+            "Duplicate keys in {} model.xml ({}); only the last element of each is compared:",
+            model,
+            keys.len()
+        );
+        for duplicate in keys {
+            let _ = writeln!(out, "  {}:", duplicate.elements[0].key);
+            for element in &duplicate.elements {
+                let _ = writeln!(out, "    line {}: {}", element.line, element.key);
+            }
+        }
+    }
+    out
+}
+
+/// The comparison as a JSON document
+///
+/// Lists the files, header and element results as the text report does, plus
+/// `duplicates` and the `element_differences`/`property_differences` counts that
+/// `--fail-on` uses.
+pub fn format_json_report(result: &CompareResult) -> String {
+    format!("{:#}\n", json_report(result))
+}
+
+/// The JSON report of [`format_json_report`] as a value
+pub fn json_report(result: &CompareResult) -> Value {
+    let files: Vec<Value> = result
+        .file_results
+        .iter()
+        .map(|(name, status)| {
+            let (status, details) = match status {
+                FileStatus::Ok => ("identical", vec![]),
+                FileStatus::Skipped(reason) => ("skipped", vec![reason.clone()]),
+                FileStatus::MissingInRust => ("missing_in_rust", vec![]),
+                FileStatus::MissingInDotnet => ("missing_in_dotnet", vec![]),
+                FileStatus::Different(lines) => ("different", lines.clone()),
+            };
+            json!({ "name": name, "status": status, "details": details })
+        })
+        .collect();
+    let header = result
+        .header_result
+        .as_ref()
+        .map(|header| json!({ "identical": header.is_ok, "differences": header.diffs }));
+    let elements = result.elements_result.as_ref().map(|elems| {
+        let keys = |keys: &[_]| -> Vec<String> { keys.iter().map(ToString::to_string).collect() };
+        let differences: Vec<Value> = elems
+            .differences
+            .iter()
+            .map(|(key, lines)| {
+                let details: Vec<&str> = lines.iter().map(|line| line.trim()).collect();
+                json!({ "element": key.to_string(), "details": details })
+            })
+            .collect();
+        json!({
+            "total_rust": elems.total_rust,
+            "total_dotnet": elems.total_dotnet,
+            "missing_in_rust": keys(&elems.missing_in_rust),
+            "extra_in_rust": keys(&elems.extra_in_rust),
+            "differences": differences,
+        })
+    });
+    let duplicates: Vec<Value> = result
+        .duplicates
+        .iter()
+        .map(|duplicate| {
+            let elements: Vec<Value> = duplicate
+                .elements
+                .iter()
+                .map(|element| json!({ "element": element.key.to_string(), "line": element.line }))
+                .collect();
+            json!({ "model": duplicate.model, "elements": elements })
+        })
+        .collect();
+    json!({
+        "files": files,
+        "header": header,
+        "elements": elements,
+        "duplicates": duplicates,
+        "element_differences": result.element_differences(),
+        "property_differences": result.property_differences(),
+    })
+}
+
+/// Print an object-level model diff to stdout.
+pub fn print_model_diff(diff: &ModelDiff) {
+    print!("{}", format_model_diff(diff));
+}
+
+/// Format an object-level model diff: added, removed and changed objects, with the
+/// property, relationship and column changes of each changed object.
+pub fn format_model_diff(diff: &ModelDiff) -> String {
+    let mut out = String::new();
+    out.push_str("=== Model Diff ===\n\n");
+
+    let _ = writeln!(out, "Added ({}):", diff.added.len());
+    if diff.added.is_empty() {
+        out.push_str("  (none)\n");
+    }
+    for key in &diff.added {
+        let _ = writeln!(out, "  + {}", key);
+    }
+    out.push('\n');
+
+    let _ = writeln!(out, "Removed ({}):", diff.removed.len());
+    if diff.removed.is_empty() {
+        out.push_str("  (none)\n");
+    }
+    for key in &diff.removed {
+        let _ = writeln!(out, "  - {}", key);
+    }
+    out.push('\n');
+
+    let _ = writeln!(out, "Changed ({}):", diff.changed.len());
+    if diff.changed.is_empty() {
+        out.push_str("  (none)\n");
+    }
+    for element in &diff.changed {
+        write_element_diff(&mut out, element, 2);
+    }
+    out.push('\n');
+
+    let _ = writeln!(
+        out,
+        "Summary: {} added, {} removed, {} changed",
+        diff.added.len(),
+        diff.removed.len(),
+        diff.changed.len()
+    );
+    out
+}
+
+/// Print the breaking changes of a model diff to stdout.
+pub fn print_breaking_changes(changes: &[ClassifiedChange]) {
+    print!("{}", format_breaking_changes(changes));
+}
+
+/// Format the breaking changes among `changes`, one per line with the affected object.
+pub fn format_breaking_changes(changes: &[ClassifiedChange]) -> String {
+    let breaking: Vec<&ClassifiedChange> = changes.iter().filter(|c| c.breaking).collect();
+    let mut out = String::new();
+    let _ = writeln!(out, "Breaking changes ({}):", breaking.len());
+    if breaking.is_empty() {
+        out.push_str("  (none)\n");
+    }
+    for change in breaking {
+        let _ = writeln!(out, "  ! {}: {}", change.key, change.description);
+    }
+    out
+}
+
+/// Print possible data loss warnings to stdout.
+pub fn print_data_loss_warnings(warnings: &[DataLossWarning]) {
+    print!("{}", format_data_loss_warnings(warnings));
+}
+
+/// Format possible data loss warnings, one per affected table or column.
+pub fn format_data_loss_warnings(warnings: &[DataLossWarning]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "Possible data loss ({}):", warnings.len());
+    if warnings.is_empty() {
+        out.push_str("  (none)\n");
+    }
+    for warning in warnings {
+        let _ = writeln!(out, "  ! {}: {}", warning.key, warning.reason);
+    }
+    out
+}
+
+fn write_element_diff(out: &mut String, diff: &ElementDiff, indent: usize) {
+    let pad = " ".repeat(indent);
+    let _ = writeln!(out, "{}~ {}", pad, diff.key);
+    let pad = " ".repeat(indent + 4);
+
+    for property in &diff.properties {
+        let _ = writeln!(out, "{}{}", pad, format_property_change(property));
+    }
+    for relationship in &diff.relationships {
+        let entries: Vec<String> = relationship
+            .added
+            .iter()
+            .map(|e| format!("+{}", format_rel_entry(e)))
+            .chain(
+                relationship
+                    .removed
+                    .iter()
+                    .map(|e| format!("-{}", format_rel_entry(e))),
+            )
+            .collect();
+        let _ = writeln!(out, "{}{}: {}", pad, relationship.name, entries.join(", "));
+    }
+    for key in &diff.added_children {
+        let _ = writeln!(out, "{}+ {}", pad, key);
+    }
+    for key in &diff.removed_children {
+        let _ = writeln!(out, "{}- {}", pad, key);
+    }
+    for child in &diff.changed_children {
+        write_element_diff(out, child, indent + 4);
+    }
+}
+
+fn format_property_change(change: &PropertyChange) -> String {
+    let is_long = |v: &Option<String>| {
+        v.as_ref()
+            .is_some_and(|v| v.contains('\n') || v.len() > MAX_VALUE_LENGTH)
+    };
+    if is_long(&change.old) || is_long(&change.new) {
+        return format!("{}: changed", change.name);
+    }
+    let value = |v: &Option<String>| v.clone().unwrap_or_else(|| "(none)".to_string());
+    format!(
+        "{}: {} -> {}",
+        change.name,
+        value(&change.old),
+        value(&change.new)
+    )
+}
+
+/// A reference by name (without its `@ExternalSource` suffix), or an inline element by its type
+fn format_rel_entry(entry: &RelEntry) -> String {
+    match entry {
+        RelEntry::Ref(name) => match name.rsplit_once('@') {
+            Some((target, source)) if target.ends_with(']') && !source.contains(']') => {
+                target.to_string()
+            }
+            _ => name.clone(),
+        },
+        RelEntry::Inline(fingerprint) => {
+            format!("({})", fingerprint.split('|').next().unwrap_or(fingerprint))
+        }
+    }
 }
@@ -0,0 +1,63 @@
+//! model.xml fixtures for the compare tests
+
+/// A model.xml document with `elements` in its `Model`
+pub fn model(elements: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<DataSchemaModel xmlns="http://schemas.microsoft.com/sqlserver/dac/Serialization/2012/02">
+  <Model>{}</Model>
+</DataSchemaModel>"#,
+        elements
+    )
+}
+
+/// A `<Property>` element
+pub fn property(name: &str, value: &str) -> String {
+    format!(r#"<Property Name="{}" Value="{}" />"#, name, value)
+}
+
+/// An `<Entry>` with an element of a built-in type (a column or parameter)
+///
+/// `properties` go on the element, `type_properties` (Length, Precision, ...) on its
+/// type specifier.
+pub fn typed(
+    element_type: &str,
+    name: &str,
+    type_name: &str,
+    type_properties: &str,
+    properties: &str,
+) -> String {
+    format!(
+        r#"<Entry><Element Type="{}" Name="{}">{}
+  <Relationship Name="TypeSpecifier"><Entry><Element Type="SqlTypeSpecifier">{}
+    <Relationship Name="Type"><Entry><References ExternalSource="BuiltIns" Name="[{}]" /></Entry></Relationship>
+  </Element></Entry></Relationship>
+</Element></Entry>"#,
+        element_type, name, properties, type_properties, type_name
+    )
+}
+
+/// An `<Entry>` with a column of a built-in type, as [`typed`]
+pub fn column(name: &str, type_name: &str, type_properties: &str, properties: &str) -> String {
+    typed(
+        "SqlSimpleColumn",
+        name,
+        type_name,
+        type_properties,
+        properties,
+    )
+}
+
+/// A table in the `[dbo]` schema with `columns` (from [`column`])
+pub fn table(name: &str, columns: &[String]) -> String {
+    format!(
+        r#"<Element Type="SqlTable" Name="{}">
+  <Property Name="IsAnsiNullsOn" Value="True" />
+  <Relationship Name="Columns">{}</Relationship>
+  <Relationship Name="Schema"><Entry><References ExternalSource="BuiltIns" Name="[dbo]" /></Entry></Relationship>
+  <AttachedAnnotation Disambiguator="3" />
+</Element>"#,
+        name,
+        columns.concat()
+    )
+}
@@ -2,6 +2,8 @@
 
 use std::fmt;
 
+use super::filter::KeyFilter;
+
 /// Key identifying a model element.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ElementKey {
@@ -29,6 +31,62 @@ impl fmt::Display for ElementKey {
     }
 }
 
+impl ElementKey {
+    /// The key as matched under `key_case`: with case-insensitive matching, names and
+    /// composite references are lowercased
+    pub fn normalized(&self, key_case: KeyCase) -> ElementKey {
+        if key_case == KeyCase::Sensitive {
+            return self.clone();
+        }
+        match self {
+            ElementKey::Named { element_type, name } => ElementKey::Named {
+                element_type: element_type.clone(),
+                name: name.to_lowercase(),
+            },
+            ElementKey::Composite {
+                element_type,
+                composite,
+            } => ElementKey::Composite {
+                element_type: element_type.clone(),
+                composite: composite.to_lowercase(),
+            },
+            ElementKey::Singleton { .. } => self.clone(),
+        }
+    }
+}
+
+/// How element names are matched when keying elements
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyCase {
+    /// Names that differ only by case are the same element, as under SQL Server's
+    /// default case-insensitive (`_CI_`) collations (default)
+    #[default]
+    Insensitive,
+    /// Names must match exactly, as under a case-sensitive (`_CS_`) collation
+    Sensitive,
+}
+
+impl std::str::FromStr for KeyCase {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "insensitive" | "ci" => Ok(KeyCase::Insensitive),
+            "sensitive" | "cs" => Ok(KeyCase::Sensitive),
+            _ => Err(format!("Unknown key case: {}", s)),
+        }
+    }
+}
+
+/// Options for [`super::compare_dacpacs_with_options`]
+#[derive(Debug, Clone, Default)]
+pub struct CompareOptions {
+    /// How element names are matched between and within the two models
+    pub key_case: KeyCase,
+    /// Model elements to compare; when set, files and the model header are skipped
+    pub filter: KeyFilter,
+}
+
 /// A relationship entry: either a reference or an inline element fingerprint.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum RelEntry {
@@ -85,6 +143,26 @@ pub struct ModelElementsResult {
     pub differences: Vec<(ElementKey, Vec<String>)>,
 }
 
+/// Elements of one model.xml whose keys match under the compare's [`KeyCase`]
+///
+/// Only the last of them is compared, so the others go unchecked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateKey {
+    /// The model the elements are in: "rust" or "dotnet"
+    pub model: String,
+    /// The elements in document order, at least two
+    pub elements: Vec<DuplicateElement>,
+}
+
+/// One of the elements sharing a [`DuplicateKey`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateElement {
+    /// The element's key as spelled in model.xml
+    pub key: ElementKey,
+    /// Line of the element in model.xml (1-based)
+    pub line: u32,
+}
+
 /// Overall result of comparing two dacpacs.
 #[derive(Debug)]
 pub struct CompareResult {
@@ -94,34 +172,53 @@ pub struct CompareResult {
     pub header_result: Option<HeaderResult>,
     /// Element comparison result (None if model.xml missing)
     pub elements_result: Option<ModelElementsResult>,
-    /// Duplicate key warnings: (source, keys)
-    pub duplicate_warnings: Vec<(String, Vec<ElementKey>)>,
+    /// Elements sharing a key, rust model first
+    pub duplicates: Vec<DuplicateKey>,
 }
 
 impl CompareResult {
     /// Returns true if any differences were found.
     pub fn has_differences(&self) -> bool {
-        for (label, status) in &self.file_results {
-            if label == "Origin.xml" {
-                continue;
-            }
-            if !status.is_ok() {
-                return true;
-            }
-        }
-        if let Some(header) = &self.header_result {
-            if !header.is_ok {
-                return true;
-            }
-        }
-        if let Some(elems) = &self.elements_result {
-            if !elems.missing_in_rust.is_empty()
-                || !elems.extra_in_rust.is_empty()
-                || !elems.differences.is_empty()
-            {
-                return true;
-            }
-        }
-        false
+        self.element_differences() + self.property_differences() > 0
+    }
+
+    /// Files and model elements present in only one dacpac
+    pub fn element_differences(&self) -> usize {
+        let files = self
+            .file_results
+            .iter()
+            .filter(|(_, status)| {
+                matches!(
+                    status,
+                    FileStatus::MissingInRust | FileStatus::MissingInDotnet
+                )
+            })
+            .count();
+        let elements = self
+            .elements_result
+            .as_ref()
+            .map_or(0, |e| e.missing_in_rust.len() + e.extra_in_rust.len());
+        files + elements
+    }
+
+    /// Files, header entries and model elements present in both dacpacs that differ
+    pub fn property_differences(&self) -> usize {
+        let files = self
+            .file_results
+            .iter()
+            .filter(|(label, status)| {
+                label != "Origin.xml" && matches!(status, FileStatus::Different(_))
+            })
+            .count();
+        let header = self
+            .header_result
+            .as_ref()
+            .filter(|h| !h.is_ok)
+            .map_or(0, |h| h.diffs.len().max(1));
+        let elements = self
+            .elements_result
+            .as_ref()
+            .map_or(0, |e| e.differences.len());
+        files + header + elements
     }
 }
@@ -0,0 +1,332 @@
+//! Pretty-printed XML and side-by-side views of model elements
+//!
+//! `compare --show-element '[dbo].[MyProc]'` prints the element from both dacpacs next
+//! to each other, lines aligned as by `diff -y`:
+//!
+//! ```text
+//! rust.dacpac                                  dotnet.dacpac
+//! <Element Type="SqlProcedure" Name="...">     <Element Type="SqlProcedure" Name="...">
+//!   <Property Name="IsAnsiNullsOn" ...       |   <Property Name="IsAnsiNullsOn" ...
+//!                                            >   <Relationship Name="BodyDependencies">
+//! ```
+//!
+//! `|` marks lines that differ, `<` and `>` lines found on one side only.
+
+use quick_xml::escape::{escape, partial_escape};
+use roxmltree::{Document, Node};
+
+use super::model_xml::element_key;
+use super::types::{ElementKey, KeyCase};
+use crate::model_dump::name_parts;
+
+/// Serialize `node` as indented XML, one element per line
+///
+/// Whitespace between elements is dropped and attributes keep their document order.
+/// Text containing markup or line breaks is written as CDATA, as in model.xml. The
+/// document element also gets its namespace declarations.
+pub fn pretty_xml(node: Node) -> String {
+    let mut out = String::new();
+    write_node(&mut out, node, 0);
+    out
+}
+
+fn write_node(out: &mut String, node: Node, depth: usize) {
+    let indent = "  ".repeat(depth);
+    if node.is_text() {
+        let text = node.text().unwrap_or_default().trim();
+        if !text.is_empty() {
+            out.push_str(&indent);
+            out.push_str(&text_content(text));
+            out.push('\n');
+        }
+        return;
+    }
+    if !node.is_element() {
+        return;
+    }
+
+    let tag = node.tag_name().name();
+    out.push_str(&indent);
+    out.push('<');
+    out.push_str(tag);
+    if node.parent().is_some_and(|p| p.is_root()) {
+        for ns in node.namespaces() {
+            match ns.name() {
+                Some(prefix) => out.push_str(&format!(" xmlns:{}=\"{}\"", prefix, ns.uri())),
+                None => out.push_str(&format!(" xmlns=\"{}\"", ns.uri())),
+            }
+        }
+    }
+    for attribute in node.attributes() {
+        out.push_str(&format!(
+            " {}=\"{}\"",
+            attribute.name(),
+            escape(attribute.value())
+        ));
+    }
+
+    let children: Vec<Node> = node
+        .children()
+        .filter(|c| {
+            c.is_element() || (c.is_text() && !c.text().unwrap_or_default().trim().is_empty())
+        })
+        .collect();
+    match children.as_slice() {
+        [] => out.push_str(" />\n"),
+        [text] if text.is_text() => {
+            out.push('>');
+            out.push_str(&text_content(text.text().unwrap_or_default()));
+            out.push_str(&format!("</{}>\n", tag));
+        }
+        _ => {
+            out.push_str(">\n");
+            for child in children {
+                write_node(out, child, depth + 1);
+            }
+            out.push_str(&format!("{}</{}>\n", indent, tag));
+        }
+    }
+}
+
+fn text_content(text: &str) -> String {
+    if text.contains(['<', '&', '\n']) && !text.contains("]]>") {
+        format!("<![CDATA[{}]]>", text)
+    } else {
+        partial_escape(text).into_owned()
+    }
+}
+
+/// Top-level model elements named `name`, pretty-printed
+///
+/// `name` matches the element's Name attribute (`[dbo].[MyProc]`) or its parts joined
+/// with dots (`dbo.MyProc`), compared according to `key_case`. Returns `None` when no
+/// element matches.
+pub fn element_xml(
+    model_xml: &str,
+    name: &str,
+    key_case: KeyCase,
+) -> anyhow::Result<Option<String>> {
+    let document = Document::parse(model_xml)?;
+    let model = document
+        .descendants()
+        .find(|n| n.has_tag_name("Model"))
+        .unwrap_or_else(|| document.root_element());
+
+    let same = |a: &str, b: &str| match key_case {
+        KeyCase::Insensitive => a.eq_ignore_ascii_case(b),
+        KeyCase::Sensitive => a == b,
+    };
+    let wanted = name.trim();
+    let matches: Vec<String> = model
+        .children()
+        .filter(|n| n.has_tag_name("Element"))
+        .filter(|n| {
+            n.attribute("Name").is_some_and(|element_name| {
+                same(element_name, wanted) || same(&name_parts(element_name).join("."), wanted)
+            })
+        })
+        .map(pretty_xml)
+        .collect();
+
+    Ok((!matches.is_empty()).then(|| matches.concat()))
+}
+
+/// Top-level model elements with `key`, pretty-printed, as [`element_xml`] finds them
+/// by name
+pub fn element_xml_by_key(
+    model_xml: &str,
+    key: &ElementKey,
+    key_case: KeyCase,
+) -> anyhow::Result<Option<String>> {
+    let document = Document::parse(model_xml)?;
+    let model = document
+        .descendants()
+        .find(|n| n.has_tag_name("Model"))
+        .unwrap_or_else(|| document.root_element());
+    let wanted = key.normalized(key_case);
+    let matches: Vec<String> = model
+        .children()
+        .filter(|n| n.has_tag_name("Element"))
+        .filter(|n| element_key(n).normalized(key_case) == wanted)
+        .map(pretty_xml)
+        .collect();
+    Ok((!matches.is_empty()).then(|| matches.concat()))
+}
+
+/// How a row of a side-by-side view differs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Row {
+    Same,
+    Changed,
+    LeftOnly,
+    RightOnly,
+}
+
+/// Pair up lines found on one side only as changed lines
+fn flush<'a>(
+    rows: &mut Vec<(Option<&'a str>, Row, Option<&'a str>)>,
+    pending_left: &mut Vec<&'a str>,
+    pending_right: &mut Vec<&'a str>,
+) {
+    for k in 0..pending_left.len().max(pending_right.len()) {
+        let row = match (pending_left.get(k), pending_right.get(k)) {
+            (Some(_), Some(_)) => Row::Changed,
+            (Some(_), None) => Row::LeftOnly,
+            _ => Row::RightOnly,
+        };
+        rows.push((
+            pending_left.get(k).copied(),
+            row,
+            pending_right.get(k).copied(),
+        ));
+    }
+    pending_left.clear();
+    pending_right.clear();
+}
+
+/// Lay out two texts in columns, aligning their common lines
+///
+/// Lines are matched by a longest common subsequence; unmatched lines between two
+/// matches are paired up as changed lines.
+pub fn side_by_side(left: &str, right: &str, left_title: &str, right_title: &str) -> String {
+    let left: Vec<String> = left.lines().map(|l| l.replace('\t', "    ")).collect();
+    let right: Vec<String> = right.lines().map(|l| l.replace('\t', "    ")).collect();
+
+    // lcs[i][j]: length of the longest common subsequence of left[i..] and right[j..]
+    let mut lcs = vec![vec![0usize; right.len() + 1]; left.len() + 1];
+    for i in (0..left.len()).rev() {
+        for j in (0..right.len()).rev() {
+            lcs[i][j] = if left[i] == right[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut rows: Vec<(Option<&str>, Row, Option<&str>)> = Vec::new();
+    let mut pending_left = Vec::new();
+    let mut pending_right = Vec::new();
+
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() || j < right.len() {
+        if i < left.len() && j < right.len() && left[i] == right[j] {
+            flush(&mut rows, &mut pending_left, &mut pending_right);
+            rows.push((Some(&left[i]), Row::Same, Some(&right[j])));
+            i += 1;
+            j += 1;
+        } else if j == right.len() || (i < left.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            pending_left.push(&left[i]);
+            i += 1;
+        } else {
+            pending_right.push(&right[j]);
+            j += 1;
+        }
+    }
+    flush(&mut rows, &mut pending_left, &mut pending_right);
+
+    let width = left
+        .iter()
+        .map(|l| l.chars().count())
+        .chain([left_title.chars().count()])
+        .max()
+        .unwrap_or(0);
+    let mut out = format!("{:<width$}   {}\n", left_title, right_title);
+    for (left_line, row, right_line) in rows {
+        let marker = match row {
+            Row::Same => ' ',
+            Row::Changed => '|',
+            Row::LeftOnly => '<',
+            Row::RightOnly => '>',
+        };
+        let line = format!(
+            "{:<width$} {} {}",
+            left_line.unwrap_or_default(),
+            marker,
+            right_line.unwrap_or_default()
+        );
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODEL: &str = r#"<DataSchemaModel xmlns="http://schemas.microsoft.com/sqlserver/dac/Serialization/2012/02">
+  <Model>
+    <Element Type="SqlSchema" Name="[sales]" />
+    <Element Type="SqlProcedure" Name="[dbo].[GetOrders]"><Property Name="BodyScript"><Value><![CDATA[SELECT 1
+WHERE 1 < 2]]></Value></Property>
+      <Relationship Name="Schema">
+        <Entry>
+          <References ExternalSource="BuiltIns" Name="[dbo]" />
+        </Entry>
+      </Relationship>
+    </Element>
+  </Model>
+</DataSchemaModel>"#;
+
+    #[test]
+    fn test_element_xml() {
+        let expected = r#"<Element Type="SqlProcedure" Name="[dbo].[GetOrders]">
+  <Property Name="BodyScript">
+    <Value><![CDATA[SELECT 1
+WHERE 1 < 2]]></Value>
+  </Property>
+  <Relationship Name="Schema">
+    <Entry>
+      <References ExternalSource="BuiltIns" Name="[dbo]" />
+    </Entry>
+  </Relationship>
+</Element>
+"#;
+        for name in ["[dbo].[GetOrders]", "dbo.getorders"] {
+            assert_eq!(
+                element_xml(MODEL, name, KeyCase::Insensitive)
+                    .unwrap()
+                    .as_deref(),
+                Some(expected)
+            );
+        }
+        assert_eq!(
+            element_xml(MODEL, "dbo.getorders", KeyCase::Sensitive).unwrap(),
+            None
+        );
+        assert_eq!(
+            element_xml(MODEL, "[dbo].[Missing]", KeyCase::Insensitive).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_pretty_xml_document() {
+        let document = Document::parse(r#"<a xmlns="urn:x"><b c="1 &amp; 2"/>text</a>"#).unwrap();
+        assert_eq!(
+            pretty_xml(document.root_element()),
+            "<a xmlns=\"urn:x\">\n  <b c=\"1 &amp; 2\" />\n  text\n</a>\n"
+        );
+    }
+
+    #[test]
+    fn test_side_by_side() {
+        let left = "<A>\n  <B />\n  <C />\n</A>\n";
+        let right = "<A>\n  <B x=\"1\" />\n  <C />\n  <D />\n</A>\n";
+        assert_eq!(
+            side_by_side(left, right, "rust", "dotnet"),
+            "rust      dotnet
+<A>       <A>
+  <B /> |   <B x=\"1\" />
+  <C />     <C />
+        >   <D />
+</A>      </A>
+"
+        );
+        assert_eq!(
+            side_by_side("<A />\n", "", "left", "right"),
+            "left    right\n<A /> <\n"
+        );
+    }
+}
@@ -0,0 +1,335 @@
+//! Workspace config file (`.sqlpackage.toml`) with defaults for the CLI
+//!
+//! A repo-level config saves every CI invocation from repeating the same flags. Flags
+//! given on the command line override the file's values:
+//!
+//! ```toml
+//! [build]
+//! target_platform = "Sql150"     # for --from-dir builds and compile
+//! output_dir = "artifacts"       # dacpacs are written as <output_dir>/<project>.dacpac
+//! output_mode = "relaxed"
+//! filters = ["exclude:schema=Scratch"]
+//! threads = 2                    # cap CPU use on shared agents
+//! unknown_elements = "error"     # fail on project elements the build does not apply
+//!
+//! [build.overrides]              # see crate::dacpac::ModelOverrides
+//! strip_annotations = ["SysCommentsObjectAnnotation"]
+//!
+//! [lint]
+//! ignore = ["not-in-build"]      # lint rules not to report
+//! ruleset = "lint-rules.toml"    # naming conventions
+//!
+//! [unused]
+//! entry_points = ["api.*"]       # objects called by applications
+//!
+//! [compare]
+//! fail_on = "breaking"
+//! ignore = ["SqlPermissionStatement"]  # element types left out of comparisons
+//! ignore_file = "compare-ignore.txt"   # known differences, one element per line
+//!
+//! [connections.staging]          # --connection-name staging
+//! connection_string = "Server=staging;Initial Catalog=Sales;User ID=deploy"
+//! password_env = "STAGING_DB_PASSWORD"
+//! ```
+//!
+//! The file is found in the current directory or the nearest ancestor with one. Relative
+//! paths in it are resolved against the file's directory.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::Result;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+
+use crate::compare::breaking::FailOn;
+use crate::compare::types::KeyCase;
+use crate::dacpac::ModelOverrides;
+use crate::error::SqlPackageError;
+use crate::model::{ElementOrder, ObjectFilter, OutputMode, ScriptFormat};
+use crate::parser::{LineEndings, ParseFallback};
+use crate::project::{SqlServerVersion, UnknownElementPolicy};
+
+/// File name searched for by [`WorkspaceConfig::discover`]
+pub const CONFIG_FILE_NAME: &str = ".sqlpackage.toml";
+
+/// Defaults read from a `.sqlpackage.toml`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorkspaceConfig {
+    pub build: BuildConfig,
+    pub lint: LintConfig,
+    pub unused: UnusedConfig,
+    pub compare: CompareConfig,
+    /// Named connections for `--connection-name`
+    pub connections: BTreeMap<String, ConnectionConfig>,
+}
+
+/// `[build]` defaults for `build` (and `target_platform` for `compile`)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BuildConfig {
+    #[serde(deserialize_with = "parse_option")]
+    pub target_platform: Option<SqlServerVersion>,
+    /// Directory for dacpacs built without `--output`
+    pub output_dir: Option<PathBuf>,
+    #[serde(deserialize_with = "parse_option")]
+    pub output_mode: Option<OutputMode>,
+    #[serde(deserialize_with = "parse_option")]
+    pub script_format: Option<ScriptFormat>,
+    #[serde(deserialize_with = "parse_option")]
+    pub element_order: Option<ElementOrder>,
+    #[serde(deserialize_with = "parse_option")]
+    pub line_endings: Option<LineEndings>,
+    /// What to do with statements no parser understands
+    #[serde(deserialize_with = "parse_option")]
+    pub parse_fallback: Option<ParseFallback>,
+    /// What to do with project file elements the build does not apply
+    #[serde(deserialize_with = "parse_option")]
+    pub unknown_elements: Option<UnknownElementPolicy>,
+    /// Filters applied before any given with `--filter`
+    #[serde(deserialize_with = "parse_list")]
+    pub filters: Vec<ObjectFilter>,
+    /// SQLCMD variables, overridden by `--variable` of the same name
+    pub variables: BTreeMap<String, String>,
+    /// MSBuild properties for item `Condition`s, overridden by `--property` of the same
+    /// name
+    pub properties: BTreeMap<String, String>,
+    /// Threads for parsing SQL files
+    pub threads: Option<NonZeroUsize>,
+    /// Properties and annotations forced on the emitted model.xml
+    pub overrides: ModelOverrides,
+    /// Embed a manifest of the source files in the dacpac
+    pub source_manifest: bool,
+}
+
+/// `[lint]` defaults
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LintConfig {
+    /// Rules not to report (see [`crate::project::LintIssue::rule`])
+    pub ignore: Vec<String>,
+    /// Ruleset file used without `--ruleset` (see [`crate::project::Ruleset`])
+    pub ruleset: Option<PathBuf>,
+}
+
+/// `[unused]` defaults
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UnusedConfig {
+    /// Entry-point globs, added to those given with `--entry-point`
+    pub entry_points: Vec<String>,
+}
+
+/// `[compare]` defaults
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CompareConfig {
+    #[serde(deserialize_with = "parse_option")]
+    pub fail_on: Option<FailOn>,
+    #[serde(deserialize_with = "parse_option")]
+    pub key_case: Option<KeyCase>,
+    pub max_differences: Option<usize>,
+    /// Element types left out of comparisons, added to those given with `--ignore`
+    pub ignore: Vec<String>,
+    /// Known differences used without `--ignore-file` (see
+    /// [`crate::compare::known::KnownDifferences`])
+    pub ignore_file: Option<PathBuf>,
+}
+
+/// `[connections.<name>]`: where to find a connection string and its secrets
+///
+/// The connection string itself must not hold a password; name the environment
+/// variables with the secrets instead.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConnectionConfig {
+    /// Connection string without secrets
+    pub connection_string: Option<String>,
+    /// Environment variable holding the whole connection string, used instead
+    pub connection_string_env: Option<String>,
+    /// Environment variable holding the password of the connection string's `User ID`
+    pub password_env: Option<String>,
+    /// Environment variable holding an Azure AD access token
+    pub access_token_env: Option<String>,
+}
+
+impl FromStr for WorkspaceConfig {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        toml::from_str(s).map_err(|e: toml::de::Error| e.to_string().trim_end().to_string())
+    }
+}
+
+impl WorkspaceConfig {
+    /// Read a config file, resolving its relative paths against the file's directory
+    pub fn load(path: &Path) -> Result<Self> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| SqlPackageError::ConfigReadError {
+                path: path.to_path_buf(),
+                source: e,
+            })?;
+        let mut config: Self =
+            content
+                .parse()
+                .map_err(|message| SqlPackageError::InvalidConfig {
+                    path: path.to_path_buf(),
+                    message,
+                })?;
+        let root = path.parent().unwrap_or(Path::new(""));
+        if let Some(dir) = &config.build.output_dir {
+            config.build.output_dir = Some(root.join(dir));
+        }
+        if let Some(ruleset) = &config.lint.ruleset {
+            config.lint.ruleset = Some(root.join(ruleset));
+        }
+        if let Some(ignore_file) = &config.compare.ignore_file {
+            config.compare.ignore_file = Some(root.join(ignore_file));
+        }
+        Ok(config)
+    }
+
+    /// The config file in `dir` or its nearest ancestor with one, if any
+    pub fn discover(dir: &Path) -> Result<Option<Self>> {
+        dir.ancestors()
+            .map(|ancestor| ancestor.join(CONFIG_FILE_NAME))
+            .find(|path| path.is_file())
+            .map(|path| Self::load(&path))
+            .transpose()
+    }
+}
+
+/// An optional string value parsed with `FromStr`
+pub(crate) fn parse_option<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|s| s.parse().map_err(D::Error::custom))
+        .transpose()
+}
+
+/// A list of string values parsed with `FromStr`
+fn parse_list<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|s| s.parse().map_err(D::Error::custom))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config: WorkspaceConfig = r#"
+[build]
+target_platform = "Sql150"
+output_dir = "artifacts"
+output_mode = "relaxed"
+script_format = "escaped"
+filters = ["exclude:schema=Scratch"]
+variables = { Env = "ci" }
+properties = { TargetEnv = "Azure" }
+threads = 2
+source_manifest = true
+unknown_elements = "ignore"
+
+[build.overrides]
+strip_annotations = ["*"]
+
+[[build.overrides.properties]]
+name = "IsQuotedIdentifierOn"
+
+[lint]
+ignore = ["not-in-build"]
+
+[unused]
+entry_points = ["api.*"]
+
+[compare]
+fail_on = "breaking"
+ignore = ["SqlPermissionStatement"]
+
+[connections.staging]
+connection_string = "Server=staging;User ID=deploy"
+password_env = "STAGING_DB_PASSWORD"
+"#
+        .parse()
+        .unwrap();
+        assert_eq!(config.build.target_platform, Some(SqlServerVersion::Sql150));
+        assert_eq!(config.build.output_mode, Some(OutputMode::Relaxed));
+        assert_eq!(config.build.script_format, Some(ScriptFormat::Escaped));
+        assert_eq!(config.build.element_order, None);
+        assert!(config.build.source_manifest);
+        assert_eq!(
+            config.build.unknown_elements,
+            Some(UnknownElementPolicy::Ignore)
+        );
+        assert_eq!(
+            config.build.overrides.strip_annotations,
+            vec!["*".to_string()]
+        );
+        assert_eq!(config.build.overrides.properties[0].value, None);
+        assert_eq!(config.build.filters.len(), 1);
+        assert_eq!(config.build.variables["Env"], "ci");
+        assert_eq!(config.build.properties["TargetEnv"], "Azure");
+        assert_eq!(config.build.threads, NonZeroUsize::new(2));
+        assert_eq!(config.lint.ignore, vec!["not-in-build"]);
+        assert_eq!(config.unused.entry_points, vec!["api.*"]);
+        assert_eq!(config.compare.fail_on, Some(FailOn::Breaking));
+        assert_eq!(config.compare.max_differences, None);
+        let staging = &config.connections["staging"];
+        assert_eq!(staging.password_env.as_deref(), Some("STAGING_DB_PASSWORD"));
+        assert_eq!(staging.access_token_env, None);
+
+        let empty: WorkspaceConfig = "".parse().unwrap();
+        assert!(empty.build.target_platform.is_none());
+    }
+
+    #[test]
+    fn test_parse_config_errors() {
+        let error = "[build]\ntarget_platform = \"Sql999\""
+            .parse::<WorkspaceConfig>()
+            .unwrap_err();
+        assert!(error.contains("Unknown SQL Server version"), "{}", error);
+        assert!("[build]\nouput_dir = \"x\""
+            .parse::<WorkspaceConfig>()
+            .is_err());
+        assert!("[deploy]".parse::<WorkspaceConfig>().is_err());
+        assert!("[build]\nthreads = 0".parse::<WorkspaceConfig>().is_err());
+    }
+
+    #[test]
+    fn test_discover_config() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let nested = dir.path().join("src").join("Tables");
+        std::fs::create_dir_all(&nested).unwrap();
+        assert!(WorkspaceConfig::discover(&nested).unwrap().is_none());
+
+        std::fs::write(
+            dir.path().join(CONFIG_FILE_NAME),
+            "[build]\noutput_dir = \"artifacts\"\n\n[lint]\nruleset = \"rules.toml\"\n\n[compare]\nignore_file = \"known.txt\"\n",
+        )
+        .unwrap();
+        let config = WorkspaceConfig::discover(&nested).unwrap().unwrap();
+        assert_eq!(config.build.output_dir, Some(dir.path().join("artifacts")));
+        assert_eq!(config.lint.ruleset, Some(dir.path().join("rules.toml")));
+        assert_eq!(
+            config.compare.ignore_file,
+            Some(dir.path().join("known.txt"))
+        );
+    }
+}
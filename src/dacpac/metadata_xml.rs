@@ -38,6 +38,30 @@ pub fn generate_metadata_xml<W: Write>(
         write_element(&mut xml_writer, "Description", description)?;
     }
 
+    // Deployment contributors - emit only if configured in sqlproj so managed tooling
+    // runs the same contributors when deploying this dacpac
+    if !project.deployment_contributors.is_empty() {
+        xml_writer.write_event(Event::Start(BytesStart::new("DeploymentContributors")))?;
+        for contributor in &project.deployment_contributors {
+            let elem =
+                BytesStart::new("Contributor").with_attributes([("Id", contributor.as_str())]);
+            xml_writer.write_event(Event::Empty(elem))?;
+        }
+        xml_writer.write_event(Event::End(BytesEnd::new("DeploymentContributors")))?;
+    }
+
+    if !project.contributor_arguments.is_empty() {
+        xml_writer.write_event(Event::Start(BytesStart::new("ContributorArguments")))?;
+        for argument in &project.contributor_arguments {
+            let elem = BytesStart::new("Argument").with_attributes([
+                ("Name", argument.name.as_str()),
+                ("Value", argument.value.as_str()),
+            ]);
+            xml_writer.write_event(Event::Empty(elem))?;
+        }
+        xml_writer.write_event(Event::End(BytesEnd::new("ContributorArguments")))?;
+    }
+
     // Close root
     xml_writer.write_event(Event::End(BytesEnd::new("DacType")))?;
 
@@ -3,12 +3,19 @@
 mod metadata_xml;
 mod model_xml;
 mod origin_xml;
+mod overrides;
 mod packager;
+mod source_manifest;
 
 pub use metadata_xml::generate_metadata_xml;
-pub use model_xml::generate_model_xml;
+pub use model_xml::{
+    column_lineage, generate_model_fragment, generate_model_xml, object_references,
+};
 pub use origin_xml::generate_origin_xml;
+pub use overrides::{ModelOverrides, PropertyOverride};
 pub use packager::create_dacpac;
+pub(crate) use packager::{create_dacpac_from_parts, PackageParts};
+pub use source_manifest::{verify_sources, SourceVerification, SOURCE_MANIFEST_PART};
 
 use crate::model::DatabaseModel;
 use crate::project::SqlServerVersion;
@@ -34,14 +41,26 @@ pub fn generate_model_xml_string(
         dacpac_references: vec![],
         package_references: vec![],
         sqlcmd_variables: vec![],
+        build_variables: Default::default(),
         project_dir: PathBuf::new(),
         pre_deploy_script: None,
         post_deploy_script: None,
+        resolve_deploy_includes: true,
+        header_descriptions: false,
+        column_descriptions: false,
         ansi_nulls: true,
         quoted_identifier: true,
         database_options: crate::project::DatabaseOptions::default(),
         dac_version: "1.0.0.0".to_string(),
         dac_description: None,
+        deployment_contributors: vec![],
+        contributor_arguments: vec![],
+        dacpac_profiles: vec![],
+        default_file_encoding: None,
+        objects_per_file: crate::project::ObjectsPerFile::Multiple,
+        skipped_files: Vec::new(),
+        unrecognized_elements: Vec::new(),
+        warnings: vec![],
     };
 
     let mut buffer = Vec::new();
@@ -64,14 +83,26 @@ pub fn generate_dac_metadata_xml(name: &str, version: &str) -> String {
         dacpac_references: vec![],
         package_references: vec![],
         sqlcmd_variables: vec![],
+        build_variables: Default::default(),
         project_dir: PathBuf::new(),
         pre_deploy_script: None,
         post_deploy_script: None,
+        resolve_deploy_includes: true,
+        header_descriptions: false,
+        column_descriptions: false,
         ansi_nulls: true,
         quoted_identifier: true,
         database_options: crate::project::DatabaseOptions::default(),
         dac_version: version.to_string(),
         dac_description: None,
+        deployment_contributors: vec![],
+        contributor_arguments: vec![],
+        dacpac_profiles: vec![],
+        default_file_encoding: None,
+        objects_per_file: crate::project::ObjectsPerFile::Multiple,
+        skipped_files: Vec::new(),
+        unrecognized_elements: Vec::new(),
+        warnings: vec![],
     };
 
     let mut buffer = Vec::new();
@@ -82,11 +113,16 @@ pub fn generate_dac_metadata_xml(name: &str, version: &str) -> String {
 /// Generate Origin.xml as a string (for testing)
 pub fn generate_origin_xml_string(checksum: &str) -> String {
     let mut buffer = Vec::new();
-    generate_origin_xml(&mut buffer, checksum).unwrap();
+    generate_origin_xml(
+        &mut buffer,
+        checksum,
+        &DatabaseModel::default().schema_version,
+    )
+    .unwrap();
     String::from_utf8(buffer).unwrap()
 }
 
 /// Generate [Content_Types].xml as a string (for testing)
 pub fn generate_content_types_xml() -> String {
-    packager::generate_content_types_xml(false)
+    packager::generate_content_types_xml(false, false)
 }
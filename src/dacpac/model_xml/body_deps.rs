@@ -9,16 +9,19 @@
 //! - `QualifiedName`: Parsed qualified name with 1-3 parts
 //! - `extract_body_dependencies`: Main function to extract all dependencies from SQL body
 
-use sqlparser::dialect::MsSqlDialect;
-use sqlparser::keywords::Keyword;
-use sqlparser::tokenizer::{Token, TokenWithSpan, Tokenizer};
 use std::collections::{HashMap, HashSet};
 
+use crate::model::DatabaseModel;
+use crate::parser::identifier_utils::escape_identifier;
+use crate::parser::lexer::{tokenize_with_location, Keyword, Token, TokenWithSpan, Whitespace};
+pub(crate) use crate::parser::token_parser_base::{compute_line_offsets, location_to_byte_offset};
+
+use super::xml_helpers::is_builtin_schema;
+
 /// Tokenize SQL text once, returning the token list.
 /// Used to avoid repeated tokenization of the same SQL body.
 fn tokenize_sql(sql: &str) -> Option<Vec<TokenWithSpan>> {
-    let dialect = MsSqlDialect {};
-    Tokenizer::new(&dialect, sql).tokenize_with_location().ok()
+    tokenize_with_location(sql).ok()
 }
 
 /// Represents dependencies extracted from a procedure/function body.
@@ -179,7 +182,7 @@ pub(crate) struct ApplySubqueryScope {
 /// Replaces TOKEN_RE regex with proper tokenization for handling whitespace, comments,
 /// and SQL syntax correctly.
 pub(crate) struct BodyDependencyTokenScanner {
-    tokens: Vec<sqlparser::tokenizer::TokenWithSpan>,
+    tokens: Vec<TokenWithSpan>,
     pos: usize,
 }
 
@@ -417,7 +420,7 @@ impl BodyDependencyTokenScanner {
     }
 
     /// Get current token without consuming
-    fn current_token(&self) -> Option<&sqlparser::tokenizer::TokenWithSpan> {
+    fn current_token(&self) -> Option<&TokenWithSpan> {
         self.tokens.get(self.pos)
     }
 
@@ -554,10 +557,19 @@ impl QualifiedName {
     pub fn to_bracketed(&self) -> String {
         match (&self.second, &self.third) {
             (Some(second), Some(third)) => {
-                format!("[{}].[{}].[{}]", self.first, second, third)
+                format!(
+                    "[{}].[{}].[{}]",
+                    escape_identifier(&self.first),
+                    escape_identifier(second),
+                    escape_identifier(third)
+                )
             }
-            (Some(second), None) => format!("[{}].[{}]", self.first, second),
-            (None, _) => format!("[{}]", self.first),
+            (Some(second), None) => format!(
+                "[{}].[{}]",
+                escape_identifier(&self.first),
+                escape_identifier(second)
+            ),
+            (None, _) => format!("[{}]", escape_identifier(&self.first)),
         }
     }
 }
@@ -642,8 +654,7 @@ pub(crate) struct BracketedIdentWithPos {
 /// Only single bracketed identifiers are returned; multi-part references like
 /// `[schema].[table]` are not included as individual components.
 pub(crate) fn extract_bracketed_identifiers_tokenized(sql: &str) -> Vec<BracketedIdentWithPos> {
-    let dialect = MsSqlDialect {};
-    let Ok(tokens) = Tokenizer::new(&dialect, sql).tokenize_with_location() else {
+    let Ok(tokens) = tokenize_with_location(sql) else {
         return Vec::new();
     };
 
@@ -714,6 +725,144 @@ pub(crate) fn extract_bracketed_identifiers_tokenized(sql: &str) -> Vec<Brackete
     results
 }
 
+/// A schema-qualified function call with its position in the source text.
+/// Used for extracting `[dbo].[CalcTotal](...)` references from SQL expressions.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FunctionCallWithPos {
+    pub schema: String,
+    pub name: String,
+    /// The byte position where the schema name starts
+    pub position: usize,
+}
+
+/// Extract calls to schema-qualified (user-defined) functions from SQL text.
+///
+/// Matches two-part names followed by an opening parenthesis, bracketed or not
+/// (`dbo.CalcTotal(`, `[dbo].[CalcTotal] (`). Built-in functions are never
+/// schema-qualified, so they are not returned. Method calls on xml, hierarchyid and
+/// spatial columns (`[Data].value(`) look the same, so a call is only taken for a
+/// method when its qualifier is not a schema of `model`.
+pub(crate) fn extract_function_calls_tokenized(
+    sql: &str,
+    model: &DatabaseModel,
+) -> Vec<FunctionCallWithPos> {
+    let Ok(tokens) = tokenize_with_location(sql) else {
+        return Vec::new();
+    };
+
+    let line_offsets = compute_line_offsets(sql);
+    let significant: Vec<&TokenWithSpan> = tokens
+        .iter()
+        .filter(|t| !matches!(t.token, Token::Whitespace(_)))
+        .collect();
+
+    let mut results = Vec::new();
+    for (i, window) in significant.windows(4).enumerate() {
+        let (Token::Word(schema), Token::Period, Token::Word(name), Token::LParen) = (
+            &window[0].token,
+            &window[1].token,
+            &window[2].token,
+            &window[3].token,
+        ) else {
+            continue;
+        };
+        // Skip the trailing parts of three-part names (database.schema.function)
+        if i > 0 && matches!(significant[i - 1].token, Token::Period) {
+            continue;
+        }
+        // Skip method calls on xml/hierarchyid/spatial columns (`[Data].value(...)`)
+        let is_schema = is_builtin_schema(&schema.value) || model.has_schema(&schema.value);
+        if !is_schema && is_data_type_method(&name.value) {
+            continue;
+        }
+        let location = &window[0].span.start;
+        results.push(FunctionCallWithPos {
+            schema: schema.value.clone(),
+            name: name.value.clone(),
+            position: location_to_byte_offset(&line_offsets, location.line, location.column),
+        });
+    }
+
+    results
+}
+
+/// Extract sequences referenced by `NEXT VALUE FOR <sequence>` from SQL text.
+///
+/// Returns `[schema].[sequence]` references in order of appearance, with their byte
+/// positions. Unqualified sequence names resolve to `default_schema`.
+pub(crate) fn extract_sequence_references_tokenized(
+    sql: &str,
+    default_schema: &str,
+) -> Vec<(usize, String)> {
+    let Ok(tokens) = tokenize_with_location(sql) else {
+        return Vec::new();
+    };
+
+    let line_offsets = compute_line_offsets(sql);
+    let significant: Vec<&TokenWithSpan> = tokens
+        .iter()
+        .filter(|t| !matches!(t.token, Token::Whitespace(_)))
+        .collect();
+    let is_word = |i: usize, word: &str| {
+        matches!(significant.get(i).map(|t| &t.token),
+            Some(Token::Word(w)) if w.quote_style.is_none() && w.value.eq_ignore_ascii_case(word))
+    };
+
+    let mut results = Vec::new();
+    for i in 0..significant.len() {
+        if !(is_word(i, "NEXT") && is_word(i + 1, "VALUE") && is_word(i + 2, "FOR")) {
+            continue;
+        }
+        let Some(Token::Word(first)) = significant.get(i + 3).map(|t| &t.token) else {
+            continue;
+        };
+        let reference = match (
+            significant.get(i + 4).map(|t| &t.token),
+            significant.get(i + 5).map(|t| &t.token),
+        ) {
+            (Some(Token::Period), Some(Token::Word(second))) => {
+                format!(
+                    "[{}].[{}]",
+                    escape_identifier(&first.value),
+                    escape_identifier(&second.value)
+                )
+            }
+            _ => format!(
+                "[{}].[{}]",
+                escape_identifier(default_schema),
+                escape_identifier(&first.value)
+            ),
+        };
+        let location = &significant[i].span.start;
+        results.push((
+            location_to_byte_offset(&line_offsets, location.line, location.column),
+            reference,
+        ));
+    }
+
+    results
+}
+
+/// Whether `name` is a method of a built-in CLR data type (xml, hierarchyid, geometry, geography)
+fn is_data_type_method(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    matches!(
+        lower.as_str(),
+        "value"
+            | "query"
+            | "exist"
+            | "nodes"
+            | "modify"
+            | "getancestor"
+            | "getdescendant"
+            | "getlevel"
+            | "getreparentedvalue"
+            | "isdescendantof"
+            | "tostring"
+            | "reparent"
+    ) || (lower.starts_with("st") && name.starts_with("ST"))
+}
+
 // =============================================================================
 // Table Reference Extraction (Phase 20.4.3)
 // =============================================================================
@@ -762,7 +911,11 @@ fn extract_table_refs_from_tokens(
                     if table_aliases.contains_key(&first_lower) {
                         continue;
                     }
-                    let table_ref = format!("[{}].[{}]", first, second);
+                    let table_ref = format!(
+                        "[{}].[{}]",
+                        escape_identifier(&first),
+                        escape_identifier(&second)
+                    );
                     if !table_refs.contains(&table_ref) {
                         table_refs.push(table_ref);
                     }
@@ -782,7 +935,11 @@ fn extract_table_refs_from_tokens(
                 if subquery_aliases.contains(&first.to_lowercase()) {
                     continue;
                 }
-                let table_ref = format!("[{}].[{}]", first, second);
+                let table_ref = format!(
+                    "[{}].[{}]",
+                    escape_identifier(&first),
+                    escape_identifier(&second)
+                );
                 if !table_refs.contains(&table_ref) {
                     table_refs.push(table_ref);
                 }
@@ -797,7 +954,11 @@ fn extract_table_refs_from_tokens(
                 if !table_aliases.contains_key(&alias_lower)
                     && !subquery_aliases.contains(&alias_lower)
                 {
-                    let table_ref = format!("[{}].[{}]", alias, column);
+                    let table_ref = format!(
+                        "[{}].[{}]",
+                        escape_identifier(&alias),
+                        escape_identifier(&column)
+                    );
                     if !table_refs.contains(&table_ref) {
                         table_refs.push(table_ref);
                     }
@@ -814,7 +975,11 @@ fn extract_table_refs_from_tokens(
                     if !table_aliases.contains_key(&alias_lower)
                         && !subquery_aliases.contains(&alias_lower)
                     {
-                        let table_ref = format!("[{}].[{}]", alias, column);
+                        let table_ref = format!(
+                            "[{}].[{}]",
+                            escape_identifier(&alias),
+                            escape_identifier(&column)
+                        );
                         if !table_refs.contains(&table_ref) {
                             table_refs.push(table_ref);
                         }
@@ -824,7 +989,11 @@ fn extract_table_refs_from_tokens(
             BodyDepToken::ThreePartBracketed { schema, table, .. } => {
                 // [schema].[table].[column] - extract the table part
                 if !schema.starts_with('@') && !table.starts_with('@') {
-                    let table_ref = format!("[{}].[{}]", schema, table);
+                    let table_ref = format!(
+                        "[{}].[{}]",
+                        escape_identifier(&schema),
+                        escape_identifier(&table)
+                    );
                     if !table_refs.contains(&table_ref) {
                         table_refs.push(table_ref);
                     }
@@ -900,7 +1069,12 @@ fn extract_function_call_refs_from_tokens(tokens: &[TokenWithSpan]) -> HashSet<S
                     if check_pos < tokens.len() && matches!(tokens[check_pos].token, Token::LParen)
                     {
                         // This is a function call - add to set
-                        let func_ref = format!("[{}].[{}]", schema, name).to_lowercase();
+                        let func_ref = format!(
+                            "[{}].[{}]",
+                            escape_identifier(&schema),
+                            escape_identifier(&name)
+                        )
+                        .to_lowercase();
                         function_refs.insert(func_ref);
                     }
                     pos = check_pos;
@@ -919,7 +1093,7 @@ fn extract_function_call_refs_from_tokens(tokens: &[TokenWithSpan]) -> HashSet<S
 /// Try to parse a qualified name (schema.name or [schema].[name]) from tokens.
 /// Returns (schema, name, next_position) if successful.
 fn try_parse_qualified_name_for_function(
-    tokens: &[sqlparser::tokenizer::TokenWithSpan],
+    tokens: &[TokenWithSpan],
     start_pos: usize,
 ) -> Option<(String, String, usize)> {
     let mut pos = start_pos;
@@ -1108,14 +1282,23 @@ pub(crate) fn extract_body_dependencies(
                     // Pattern 2: Three-part bracketed reference: [schema].[table].[column]
                     if !schema.starts_with('@') && !table.starts_with('@') {
                         // First emit the table reference if not seen (DotNet deduplicates tables)
-                        let table_ref = format!("[{}].[{}]", schema, table);
+                        let table_ref = format!(
+                            "[{}].[{}]",
+                            escape_identifier(&schema),
+                            escape_identifier(&table)
+                        );
                         if !seen_tables.contains(&table_ref) {
                             seen_tables.insert(table_ref.clone());
                             deps.push(BodyDependency::ObjectRef(table_ref));
                         }
 
                         // Direct three-part column refs ARE deduplicated by DotNet
-                        let col_ref = format!("[{}].[{}].[{}]", schema, table, column);
+                        let col_ref = format!(
+                            "[{}].[{}].[{}]",
+                            escape_identifier(&schema),
+                            escape_identifier(&table),
+                            escape_identifier(&column)
+                        );
                         if !seen_direct_columns.contains(&col_ref) {
                             seen_direct_columns.insert(col_ref.clone());
                             deps.push(BodyDependency::ObjectRef(col_ref));
@@ -1151,11 +1334,16 @@ pub(crate) fn extract_body_dependencies(
                         }
 
                         // Then emit the column reference (DotNet does NOT deduplicate columns)
-                        let col_ref = format!("{}.[{}]", resolved_table, second);
+                        let col_ref =
+                            format!("{}.[{}]", resolved_table, escape_identifier(&second));
                         deps.push(BodyDependency::ObjectRef(col_ref));
                     } else {
                         // Not an alias - treat as [schema].[table] (DotNet deduplicates tables)
-                        let table_ref = format!("[{}].[{}]", first, second);
+                        let table_ref = format!(
+                            "[{}].[{}]",
+                            escape_identifier(&first),
+                            escape_identifier(&second)
+                        );
                         if !seen_tables.contains(&table_ref) {
                             seen_tables.insert(table_ref.clone());
                             deps.push(BodyDependency::ObjectRef(table_ref));
@@ -1187,11 +1375,16 @@ pub(crate) fn extract_body_dependencies(
                         }
 
                         // Then emit the column reference (DotNet does NOT deduplicate columns)
-                        let col_ref = format!("{}.[{}]", resolved_table, column);
+                        let col_ref =
+                            format!("{}.[{}]", resolved_table, escape_identifier(&column));
                         deps.push(BodyDependency::ObjectRef(col_ref));
                     } else {
                         // Not a known alias - treat as [alias].[column] (might be schema.table)
-                        let table_ref = format!("[{}].[{}]", alias, column);
+                        let table_ref = format!(
+                            "[{}].[{}]",
+                            escape_identifier(&alias),
+                            escape_identifier(&column)
+                        );
                         if !seen_tables.contains(&table_ref) {
                             seen_tables.insert(table_ref.clone());
                             deps.push(BodyDependency::ObjectRef(table_ref));
@@ -1223,11 +1416,16 @@ pub(crate) fn extract_body_dependencies(
                         }
 
                         // Then emit the column reference (DotNet does NOT deduplicate columns)
-                        let col_ref = format!("{}.[{}]", resolved_table, column);
+                        let col_ref =
+                            format!("{}.[{}]", resolved_table, escape_identifier(&column));
                         deps.push(BodyDependency::ObjectRef(col_ref));
                     } else {
                         // Not a known alias - treat as [alias].[column] (might be schema.table)
-                        let table_ref = format!("[{}].[{}]", alias, column);
+                        let table_ref = format!(
+                            "[{}].[{}]",
+                            escape_identifier(&alias),
+                            escape_identifier(&column)
+                        );
                         if !seen_tables.contains(&table_ref) {
                             seen_tables.insert(table_ref.clone());
                             deps.push(BodyDependency::ObjectRef(table_ref));
@@ -1255,8 +1453,8 @@ pub(crate) fn extract_body_dependencies(
 
                     // Skip if this is part of a table reference (schema or table name)
                     let is_table_or_schema = table_refs.iter().any(|t| {
-                        t.ends_with(&format!("].[{}]", ident))
-                            || t.starts_with(&format!("[{}].", ident))
+                        t.ends_with(&format!("].[{}]", escape_identifier(&ident)))
+                            || t.starts_with(&format!("[{}].", escape_identifier(&ident)))
                     });
 
                     // If not a table/schema, treat as unqualified column -> resolve against scope table
@@ -1277,7 +1475,8 @@ pub(crate) fn extract_body_dependencies(
                             }
 
                             // Direct column refs (single bracketed) ARE deduplicated by DotNet
-                            let col_ref = format!("{}.[{}]", target_table, ident);
+                            let col_ref =
+                                format!("{}.[{}]", target_table, escape_identifier(&ident));
                             if !seen_direct_columns.contains(&col_ref) {
                                 seen_direct_columns.insert(col_ref.clone());
                                 deps.push(BodyDependency::ObjectRef(col_ref));
@@ -1316,11 +1515,16 @@ pub(crate) fn extract_body_dependencies(
                         }
 
                         // Then emit the column reference (DotNet does NOT deduplicate columns)
-                        let col_ref = format!("{}.[{}]", resolved_table, second);
+                        let col_ref =
+                            format!("{}.[{}]", resolved_table, escape_identifier(&second));
                         deps.push(BodyDependency::ObjectRef(col_ref));
                     } else {
                         // Not an alias - treat as schema.table (DotNet deduplicates tables)
-                        let table_ref = format!("[{}].[{}]", first, second);
+                        let table_ref = format!(
+                            "[{}].[{}]",
+                            escape_identifier(&first),
+                            escape_identifier(&second)
+                        );
                         if !seen_tables.contains(&table_ref) {
                             seen_tables.insert(table_ref.clone());
                             deps.push(BodyDependency::ObjectRef(table_ref));
@@ -1350,8 +1554,9 @@ pub(crate) fn extract_body_dependencies(
                     let is_table_or_schema = table_refs.iter().any(|t| {
                         // Check case-insensitive match for unbracketed identifiers
                         let t_lower = t.to_lowercase();
-                        t_lower.ends_with(&format!("].[{}]", ident_lower))
-                            || t_lower.starts_with(&format!("[{}].", ident_lower))
+                        t_lower.ends_with(&format!("].[{}]", escape_identifier(&ident_lower)))
+                            || t_lower
+                                .starts_with(&format!("[{}].", escape_identifier(&ident_lower)))
                     });
 
                     // If not a table/schema, treat as unqualified column -> resolve against scope table
@@ -1372,7 +1577,8 @@ pub(crate) fn extract_body_dependencies(
                             }
 
                             // Direct column refs (single unbracketed) ARE deduplicated by DotNet
-                            let col_ref = format!("{}.[{}]", target_table, ident);
+                            let col_ref =
+                                format!("{}.[{}]", target_table, escape_identifier(&ident));
                             if !seen_direct_columns.contains(&col_ref) {
                                 seen_direct_columns.insert(col_ref.clone());
                                 deps.push(BodyDependency::ObjectRef(col_ref));
@@ -1538,7 +1744,7 @@ fn resolve_alias_for_position<'a>(
 /// Token-based parser for extracting table aliases from SQL body text.
 /// Replaces 6 regex patterns with a single tokenizer-based implementation.
 pub(crate) struct TableAliasTokenParser {
-    tokens: Vec<sqlparser::tokenizer::TokenWithSpan>,
+    tokens: Vec<TokenWithSpan>,
     pos: usize,
     default_schema: String,
     /// Full name of the parent procedure/function (e.g., "[dbo].[GetOrdersByStatus]")
@@ -1685,7 +1891,11 @@ impl TableAliasTokenParser {
                         if !Self::is_alias_keyword(&alias_lower)
                             && !table_aliases.contains_key(&alias_lower)
                         {
-                            let table_ref = format!("[{}].[{}]", schema, table_name);
+                            let table_ref = format!(
+                                "[{}].[{}]",
+                                escape_identifier(&schema),
+                                escape_identifier(&table_name)
+                            );
                             table_aliases.insert(alias_lower, table_ref);
                         }
                     }
@@ -1722,7 +1932,11 @@ impl TableAliasTokenParser {
                             if !Self::is_alias_keyword(&alias_lower)
                                 && !table_aliases.contains_key(&alias_lower)
                             {
-                                let table_ref = format!("[{}].[{}]", schema, table_name);
+                                let table_ref = format!(
+                                    "[{}].[{}]",
+                                    escape_identifier(&schema),
+                                    escape_identifier(&table_name)
+                                );
                                 table_aliases.insert(alias_lower, table_ref);
                             }
                         }
@@ -1839,7 +2053,11 @@ impl TableAliasTokenParser {
                 self.advance();
                 self.skip_whitespace();
                 if let Some((schema, table_name, alias_opt)) = self.parse_table_name_with_alias() {
-                    let table_ref = format!("[{}].[{}]", schema, table_name);
+                    let table_ref = format!(
+                        "[{}].[{}]",
+                        escape_identifier(&schema),
+                        escape_identifier(&table_name)
+                    );
                     if !tables_in_scope.contains(&table_ref) {
                         tables_in_scope.push(table_ref.clone());
                     }
@@ -1864,7 +2082,11 @@ impl TableAliasTokenParser {
                     if let Some((schema, table_name, alias_opt)) =
                         self.parse_table_name_with_alias()
                     {
-                        let table_ref = format!("[{}].[{}]", schema, table_name);
+                        let table_ref = format!(
+                            "[{}].[{}]",
+                            escape_identifier(&schema),
+                            escape_identifier(&table_name)
+                        );
                         if !tables_in_scope.contains(&table_ref) {
                             tables_in_scope.push(table_ref.clone());
                         }
@@ -2003,7 +2225,11 @@ impl TableAliasTokenParser {
             None => return,
         };
 
-        let table_ref = format!("[{}].[{}]", schema, table_name);
+        let table_ref = format!(
+            "[{}].[{}]",
+            escape_identifier(&schema),
+            escape_identifier(&table_name)
+        );
 
         // Skip if this is a CTE name (not a real table)
         let table_name_lower = table_name.to_lowercase();
@@ -2116,8 +2342,11 @@ impl TableAliasTokenParser {
                                         // Parse the table name
                                         if let Some((schema, table_name)) = self.parse_table_name()
                                         {
-                                            found_table =
-                                                Some(format!("[{}].[{}]", schema, table_name));
+                                            found_table = Some(format!(
+                                                "[{}].[{}]",
+                                                escape_identifier(&schema),
+                                                escape_identifier(&table_name)
+                                            ));
                                         }
                                     } else {
                                         self.advance();
@@ -2210,10 +2439,14 @@ impl TableAliasTokenParser {
         let table_ref = if table_name.starts_with('@') && !self.full_name.is_empty() {
             // Table variable: use procedure-scoped reference
             // e.g., FROM @FilteredOrders -> [dbo].[GetOrdersByStatus].[@FilteredOrders]
-            format!("{}.[{}]", self.full_name, table_name)
+            format!("{}.[{}]", self.full_name, escape_identifier(&table_name))
         } else {
             // Regular table: use schema.table reference
-            format!("[{}].[{}]", schema, table_name)
+            format!(
+                "[{}].[{}]",
+                escape_identifier(&schema),
+                escape_identifier(&table_name)
+            )
         };
         let table_name_lower = table_name.to_lowercase();
 
@@ -2573,7 +2806,7 @@ impl TableAliasTokenParser {
     }
 
     /// Get current token without consuming
-    fn current_token(&self) -> Option<&sqlparser::tokenizer::TokenWithSpan> {
+    fn current_token(&self) -> Option<&TokenWithSpan> {
         self.tokens.get(self.pos)
     }
 
@@ -2616,87 +2849,54 @@ impl TableAliasTokenParser {
 // Helper Functions
 // =============================================================================
 
-/// Compute byte offsets for each line in the source text.
-/// Returns a vector where index i contains the byte offset where line (i+1) starts.
-pub(crate) fn compute_line_offsets(sql: &str) -> Vec<usize> {
-    let mut offsets = vec![0]; // Line 1 starts at offset 0
-    for (i, ch) in sql.char_indices() {
-        if ch == '\n' {
-            // Next line starts after this newline
-            offsets.push(i + 1);
-        }
-    }
-    offsets
-}
-
-/// Convert a (1-based line, 1-based column) Location to a byte offset.
-pub(crate) fn location_to_byte_offset(line_offsets: &[usize], line: u64, column: u64) -> usize {
-    if line == 0 || line as usize > line_offsets.len() {
-        return 0;
-    }
-    let line_start = line_offsets[(line - 1) as usize];
-    // Column is 1-based, so subtract 1 to get offset within line
-    line_start + (column.saturating_sub(1) as usize)
-}
-
 /// Strip SQL comments from body text for dependency extraction.
 /// Removes both line comments (-- ...) and block comments (/* ... */).
 /// This prevents words in comments from being treated as column/table references.
+///
+/// Comments are found by the tokenizer, so comment markers inside strings and bracketed
+/// identifiers are left alone and block comments nest as in T-SQL (`/* a /* b */ c */`).
+/// Line comments keep their newline and block comments become a space, preserving line
+/// structure and word boundaries. Text that cannot be tokenized (e.g. an unterminated
+/// comment) is returned unchanged.
 pub(crate) fn strip_sql_comments_for_body_deps(body: &str) -> String {
-    let mut result = String::with_capacity(body.len());
-    let mut chars = body.chars().peekable();
-    let mut in_string = false;
-    let mut string_delimiter = ' ';
-
-    while let Some(c) = chars.next() {
-        // Handle string literals - don't strip comments inside strings
-        if (c == '\'' || c == '"') && !in_string {
-            in_string = true;
-            string_delimiter = c;
-            result.push(c);
-            continue;
-        }
-        if c == string_delimiter && in_string {
-            in_string = false;
-            result.push(c);
-            continue;
-        }
-        if in_string {
-            result.push(c);
-            continue;
-        }
+    let Some(tokens) = tokenize_sql(body) else {
+        return body.to_string();
+    };
 
-        // Check for line comment: --
-        if c == '-' && chars.peek() == Some(&'-') {
-            chars.next(); // consume second -
-                          // Skip until end of line
-            while let Some(&ch) = chars.peek() {
-                chars.next();
-                if ch == '\n' {
-                    result.push('\n'); // preserve line structure
-                    break;
-                }
-            }
-            continue;
-        }
+    // Token locations count characters, not bytes
+    let line_offsets = compute_line_offsets(body);
+    let byte_offset = |token: &TokenWithSpan| {
+        let location = token.span.start;
+        let Some(&line_start) = line_offsets.get((location.line as usize).wrapping_sub(1)) else {
+            return body.len();
+        };
+        body[line_start..]
+            .char_indices()
+            .nth(location.column.saturating_sub(1) as usize)
+            .map_or(body.len(), |(i, _)| line_start + i)
+    };
 
-        // Check for block comment: /* ... */
-        if c == '/' && chars.peek() == Some(&'*') {
-            chars.next(); // consume *
-                          // Skip until */
-            while let Some(ch) = chars.next() {
-                if ch == '*' && chars.peek() == Some(&'/') {
-                    chars.next(); // consume /
-                    result.push(' '); // replace comment with space to preserve word boundaries
-                    break;
+    let mut result = String::with_capacity(body.len());
+    let mut copied = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        let replacement = match &token.token {
+            Token::Whitespace(Whitespace::SingleLineComment { comment, .. }) => {
+                if comment.ends_with('\n') {
+                    "\n"
+                } else {
+                    ""
                 }
             }
-            continue;
-        }
-
-        result.push(c);
+            Token::Whitespace(Whitespace::MultiLineComment(_)) => " ",
+            _ => continue,
+        };
+        let start = byte_offset(token);
+        let end = tokens.get(i + 1).map_or(body.len(), byte_offset);
+        result.push_str(&body[copied..start]);
+        result.push_str(replacement);
+        copied = end;
     }
-
+    result.push_str(&body[copied..]);
     result
 }
 
@@ -2806,8 +3006,7 @@ fn extract_column_aliases_from_tokens(tokens: &[TokenWithSpan]) -> Vec<String> {
 pub(crate) fn extract_declare_types_tokenized(sql: &str) -> Vec<String> {
     let mut results = Vec::new();
 
-    let dialect = MsSqlDialect {};
-    let Ok(tokens) = Tokenizer::new(&dialect, sql).tokenize_with_location() else {
+    let Ok(tokens) = tokenize_with_location(sql) else {
         return results;
     };
 
@@ -3232,8 +3431,7 @@ pub(crate) fn is_sql_keyword_not_column(word: &str) -> bool {
 /// # Returns
 /// Vector of CteDefinition structs, one per CTE found in the body
 pub(crate) fn extract_cte_definitions(sql: &str, default_schema: &str) -> Vec<CteDefinition> {
-    let dialect = MsSqlDialect {};
-    let tokens = match Tokenizer::new(&dialect, sql).tokenize_with_location() {
+    let tokens = match tokenize_with_location(sql) {
         Ok(t) => t,
         Err(_) => return Vec::new(),
     };
@@ -3412,7 +3610,7 @@ pub(crate) fn extract_cte_definitions(sql: &str, default_schema: &str) -> Vec<Ct
 /// Extract columns and their expression dependencies from CTE body tokens.
 /// Parses the SELECT clause to find column aliases/names and their source references.
 fn extract_cte_columns_from_tokens(
-    tokens: &[sqlparser::tokenizer::TokenWithSpan],
+    tokens: &[TokenWithSpan],
     table_aliases: &HashMap<String, String>,
     default_schema: &str,
 ) -> Vec<CteColumn> {
@@ -3514,7 +3712,7 @@ fn extract_cte_columns_from_tokens(
 /// Parse a single column expression from the SELECT clause.
 /// Returns (column_name, expression_dependencies, tokens_consumed)
 fn parse_cte_column_expression(
-    tokens: &[sqlparser::tokenizer::TokenWithSpan],
+    tokens: &[TokenWithSpan],
     table_aliases: &HashMap<String, String>,
     default_schema: &str,
 ) -> (String, Vec<String>, usize) {
@@ -3731,13 +3929,15 @@ fn resolve_cte_column_ref(
             // Check if first part is a known table alias
             if let Some(table_ref) = table_aliases.get(&alias_lower) {
                 // table_ref is like "[dbo].[Account]"
-                Some(format!("{}.[{}]", table_ref, column))
+                Some(format!("{}.[{}]", table_ref, escape_identifier(column)))
             } else {
                 // Assume it's schema.table (unlikely in CTE select) or just table.column
                 // Use default schema if it looks like an unqualified table
                 Some(format!(
                     "[{}].[{}].[{}]",
-                    default_schema, alias_or_table, column
+                    escape_identifier(default_schema),
+                    escape_identifier(alias_or_table),
+                    escape_identifier(column)
                 ))
             }
         }
@@ -3746,7 +3946,12 @@ fn resolve_cte_column_ref(
             let schema = &parts[0];
             let table = &parts[1];
             let column = &parts[2];
-            Some(format!("[{}].[{}].[{}]", schema, table, column))
+            Some(format!(
+                "[{}].[{}].[{}]",
+                escape_identifier(schema),
+                escape_identifier(table),
+                escape_identifier(column)
+            ))
         }
         _ => {
             // More than 3 parts - take last 3
@@ -3754,7 +3959,12 @@ fn resolve_cte_column_ref(
             let schema = &parts[len - 3];
             let table = &parts[len - 2];
             let column = &parts[len - 1];
-            Some(format!("[{}].[{}].[{}]", schema, table, column))
+            Some(format!(
+                "[{}].[{}].[{}]",
+                escape_identifier(schema),
+                escape_identifier(table),
+                escape_identifier(column)
+            ))
         }
     }
 }
@@ -3851,8 +4061,7 @@ fn is_sql_reserved_word(word: &str) -> bool {
 /// # Returns
 /// Vector of TempTableDefinition structs, one per temp table found in the body
 pub(crate) fn extract_temp_table_definitions(sql: &str) -> Vec<TempTableDefinition> {
-    let dialect = MsSqlDialect {};
-    let tokens = match Tokenizer::new(&dialect, sql).tokenize_with_location() {
+    let tokens = match tokenize_with_location(sql) {
         Ok(t) => t,
         Err(_) => return Vec::new(),
     };
@@ -3941,10 +4150,7 @@ pub(crate) fn extract_temp_table_definitions(sql: &str) -> Vec<TempTableDefiniti
 }
 
 /// Extract temp table name from tokens (handles #name and ##name patterns)
-fn extract_temp_table_name(
-    tokens: &[sqlparser::tokenizer::TokenWithSpan],
-    pos: &mut usize,
-) -> Option<String> {
+fn extract_temp_table_name(tokens: &[TokenWithSpan], pos: &mut usize) -> Option<String> {
     if *pos >= tokens.len() {
         return None;
     }
@@ -3962,9 +4168,7 @@ fn extract_temp_table_name(
 }
 
 /// Extract column definitions from temp table CREATE TABLE tokens
-fn extract_temp_table_columns(
-    tokens: &[sqlparser::tokenizer::TokenWithSpan],
-) -> Vec<TempTableColumn> {
+fn extract_temp_table_columns(tokens: &[TokenWithSpan]) -> Vec<TempTableColumn> {
     let mut columns = Vec::new();
     let mut pos = 0;
 
@@ -4132,10 +4336,7 @@ fn extract_temp_table_columns(
 }
 
 /// Extract data type from column definition tokens
-fn extract_column_data_type(
-    tokens: &[sqlparser::tokenizer::TokenWithSpan],
-    start_pos: usize,
-) -> (String, usize) {
+fn extract_column_data_type(tokens: &[TokenWithSpan], start_pos: usize) -> (String, usize) {
     let mut pos = start_pos;
     let mut type_parts = Vec::new();
 
@@ -4359,10 +4560,7 @@ fn extract_table_variable_definitions_from_tokens(
 }
 
 /// Extract table variable name from tokens (handles @name pattern)
-fn extract_table_variable_name(
-    tokens: &[sqlparser::tokenizer::TokenWithSpan],
-    pos: &mut usize,
-) -> Option<String> {
+fn extract_table_variable_name(tokens: &[TokenWithSpan], pos: &mut usize) -> Option<String> {
     if *pos >= tokens.len() {
         return None;
     }
@@ -4392,9 +4590,7 @@ fn extract_table_variable_name(
 
 /// Extract column definitions from table variable DECLARE TABLE tokens
 /// This follows the same pattern as extract_temp_table_columns
-fn extract_table_variable_columns(
-    tokens: &[sqlparser::tokenizer::TokenWithSpan],
-) -> Vec<TableVariableColumn> {
+fn extract_table_variable_columns(tokens: &[TokenWithSpan]) -> Vec<TableVariableColumn> {
     let mut columns = Vec::new();
     let mut pos = 0;
 
@@ -4619,6 +4815,8 @@ mod tests {
                 is_system_versioned: false,
                 history_table_schema: None,
                 history_table_name: None,
+                data_compression: None,
+                partition_compression: Vec::new(),
             }));
         }
         // Use "dbo" as default schema for test registry
@@ -5052,6 +5250,33 @@ mod tests {
         assert_eq!(result, "SELECT 'text -- not a comment'");
     }
 
+    #[test]
+    fn test_strip_nested_block_comment() {
+        let result =
+            strip_sql_comments_for_body_deps("SELECT /* a /* [Hidden] */ still hidden */ [Id]");
+        assert_eq!(result, "SELECT   [Id]");
+    }
+
+    #[test]
+    fn test_strip_comment_at_end_of_text() {
+        assert_eq!(
+            strip_sql_comments_for_body_deps("SELECT [Id] FROM [T] -- trailing"),
+            "SELECT [Id] FROM [T] "
+        );
+        assert_eq!(
+            strip_sql_comments_for_body_deps("SELECT [Id] FROM [T] /* trailing */"),
+            "SELECT [Id] FROM [T]  "
+        );
+    }
+
+    #[test]
+    fn test_strip_comments_keeps_identifiers_and_non_ascii() {
+        assert_eq!(
+            strip_sql_comments_for_body_deps("SELECT [a--b], N'café' /* é */ FROM [T/*x*/]"),
+            "SELECT [a--b], N'café'   FROM [T/*x*/]"
+        );
+    }
+
     // ============================================================================
     // CTE extraction tests (Phase 24.1.2)
     // ============================================================================
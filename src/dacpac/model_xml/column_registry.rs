@@ -7,6 +7,8 @@
 use std::collections::{HashMap, HashSet};
 
 use crate::model::{DatabaseModel, ModelElement};
+use crate::parser::extract_module_options;
+use crate::parser::identifier_utils::escape_identifier;
 
 use super::view_writer::{extract_view_columns_and_deps, extract_view_query, ViewColumn};
 
@@ -72,7 +74,12 @@ impl ColumnRegistry {
         // First pass: register all tables and build table_index for O(1) lookups
         for (idx, element) in model.elements.iter().enumerate() {
             if let ModelElement::Table(table) = element {
-                let table_key = format!("[{}].[{}]", table.schema, table.name).to_lowercase();
+                let table_key = format!(
+                    "[{}].[{}]",
+                    escape_identifier(&table.schema),
+                    escape_identifier(&table.name)
+                )
+                .to_lowercase();
 
                 let columns: HashSet<String> = table
                     .columns
@@ -89,7 +96,12 @@ impl ColumnRegistry {
         for element in &model.elements {
             match element {
                 ModelElement::View(view) => {
-                    let view_key = format!("[{}].[{}]", view.schema, view.name).to_lowercase();
+                    let view_key = format!(
+                        "[{}].[{}]",
+                        escape_identifier(&view.schema),
+                        escape_identifier(&view.name)
+                    )
+                    .to_lowercase();
 
                     // Extract the SELECT query from the view definition
                     let query_script = extract_view_query(&view.definition);
@@ -131,15 +143,16 @@ impl ColumnRegistry {
                     );
                 }
                 ModelElement::Raw(raw) if raw.sql_type == "SqlView" => {
-                    let view_key = format!("[{}].[{}]", raw.schema, raw.name).to_lowercase();
+                    let view_key = format!(
+                        "[{}].[{}]",
+                        escape_identifier(&raw.schema),
+                        escape_identifier(&raw.name)
+                    )
+                    .to_lowercase();
 
                     // Extract view properties from raw SQL text (same logic as write_raw_view)
-                    let is_schema_bound = {
-                        let def = &raw.definition;
-                        super::view_writer::contains_ci(def, "WITH SCHEMABINDING")
-                            || super::view_writer::contains_ci(def, ", SCHEMABINDING")
-                            || super::view_writer::contains_ci(def, ",SCHEMABINDING")
-                    };
+                    let is_schema_bound =
+                        extract_module_options(&raw.definition).has_option("SCHEMABINDING");
 
                     // Extract the SELECT query from the raw view definition
                     let query_script = extract_view_query(&raw.definition);
@@ -316,6 +329,8 @@ mod tests {
             is_system_versioned: false,
             history_table_schema: None,
             history_table_name: None,
+            data_compression: None,
+            partition_compression: Vec::new(),
         }
     }
 
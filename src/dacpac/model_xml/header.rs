@@ -9,6 +9,8 @@ use quick_xml::events::{BytesEnd, BytesStart, Event};
 use quick_xml::Writer;
 use std::io::Write;
 
+use crate::model::OutputMode;
+use crate::parser::identifier_utils::escape_identifier;
 use crate::project::SqlProject;
 
 use super::xml_helpers::write_property;
@@ -18,6 +20,7 @@ use super::xml_helpers::write_property;
 pub(crate) fn write_header<W: Write>(
     writer: &mut Writer<W>,
     project: &SqlProject,
+    mode: OutputMode,
 ) -> anyhow::Result<()> {
     writer.write_event(Event::Start(BytesStart::new("Header")))?;
 
@@ -57,7 +60,9 @@ pub(crate) fn write_header<W: Write>(
 
     // SQLCMD variables (all in one CustomData element)
     // Note: DotNet always emits this element, even when empty
-    write_sqlcmd_variables(writer, &project.sqlcmd_variables)?;
+    if mode == OutputMode::Parity || !project.sqlcmd_variables.is_empty() {
+        write_sqlcmd_variables(writer, &project.sqlcmd_variables)?;
+    }
 
     writer.write_event(Event::End(BytesEnd::new("Header")))?;
     Ok(())
@@ -97,7 +102,7 @@ fn write_package_reference<W: Write>(
     writer.write_event(Event::Empty(logical_name))?;
 
     // ExternalParts metadata - bracketed database name (matches DotNet DacFx output)
-    let external_parts = format!("[{}]", db_name);
+    let external_parts = format!("[{}]", escape_identifier(&db_name));
     let external = BytesStart::new("Metadata").with_attributes([
         ("Name", "ExternalParts"),
         ("Value", external_parts.as_str()),
@@ -207,6 +212,7 @@ fn write_custom_data<W: Write>(
 pub(crate) fn write_database_options<W: Write>(
     writer: &mut Writer<W>,
     project: &SqlProject,
+    mode: OutputMode,
 ) -> anyhow::Result<()> {
     // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
     let elem = BytesStart::new("Element").with_attributes([("Type", "SqlDatabaseOptions")]);
@@ -308,15 +314,17 @@ pub(crate) fn write_database_options<W: Write>(
         write_property(writer, "PageVerifyMode", mode_value)?;
     }
 
-    // DefaultLanguage (always emit, even if empty)
-    write_property(writer, "DefaultLanguage", &db_options.default_language)?;
-
-    // DefaultFullTextLanguage (always emit, even if empty)
-    write_property(
-        writer,
-        "DefaultFullTextLanguage",
-        &db_options.default_full_text_language,
-    )?;
+    // DefaultLanguage and DefaultFullTextLanguage (DotNet emits them even if empty)
+    if mode == OutputMode::Parity || !db_options.default_language.is_empty() {
+        write_property(writer, "DefaultLanguage", &db_options.default_language)?;
+    }
+    if mode == OutputMode::Parity || !db_options.default_full_text_language.is_empty() {
+        write_property(
+            writer,
+            "DefaultFullTextLanguage",
+            &db_options.default_full_text_language,
+        )?;
+    }
 
     // QueryStoreStaleQueryThreshold
     write_property(
@@ -332,7 +340,7 @@ pub(crate) fn write_database_options<W: Write>(
         ))?;
         writer.write_event(Event::Start(BytesStart::new("Entry")))?;
 
-        let filegroup_name = format!("[{}]", filegroup);
+        let filegroup_name = format!("[{}]", escape_identifier(filegroup));
         // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
         let refs = BytesStart::new("References").with_attributes([
             ("ExternalSource", "BuiltIns"),
@@ -378,14 +386,26 @@ mod tests {
             dacpac_references: Vec::new(),
             package_references: Vec::new(),
             sqlcmd_variables: Vec::new(),
+            build_variables: Default::default(),
             project_dir: PathBuf::new(),
             pre_deploy_script: None,
             post_deploy_script: None,
+            resolve_deploy_includes: true,
+            header_descriptions: false,
+            column_descriptions: false,
             ansi_nulls: true,
             quoted_identifier: true,
             database_options: DatabaseOptions::default(),
             dac_version: "1.0.0.0".to_string(),
             dac_description: None,
+            deployment_contributors: vec![],
+            contributor_arguments: vec![],
+            dacpac_profiles: vec![],
+            default_file_encoding: None,
+            objects_per_file: crate::project::ObjectsPerFile::Multiple,
+            skipped_files: Vec::new(),
+            unrecognized_elements: Vec::new(),
+            warnings: vec![],
         }
     }
 
@@ -466,7 +486,7 @@ mod tests {
     fn test_write_header() {
         let mut writer = create_test_writer();
         let project = create_test_project();
-        write_header(&mut writer, &project).unwrap();
+        write_header(&mut writer, &project, OutputMode::Parity).unwrap();
         let output = get_output(writer);
         assert!(output.contains("<Header>"));
         assert!(output.contains(r#"<CustomData Category="AnsiNulls">"#));
@@ -485,7 +505,7 @@ mod tests {
             name: "Microsoft.SqlServer.Dacpacs.Master".to_string(),
             version: "160.0.0".to_string(),
         }];
-        write_header(&mut writer, &project).unwrap();
+        write_header(&mut writer, &project, OutputMode::Parity).unwrap();
         let output = get_output(writer);
         assert!(output.contains(r#"<CustomData Category="Reference" Type="SqlSchema">"#));
         assert!(output.contains(r#"<Metadata Name="FileName" Value="master.dacpac"/>"#));
@@ -510,7 +530,7 @@ mod tests {
             query_store_stale_query_threshold: 367,
             default_filegroup: None,
         };
-        write_database_options(&mut writer, &project).unwrap();
+        write_database_options(&mut writer, &project, OutputMode::Parity).unwrap();
         let output = get_output(writer);
         assert!(output.contains(r#"<Element Type="SqlDatabaseOptions">"#));
         assert!(output.contains(r#"<Property Name="Collation" Value="Latin1_General_CI_AS"/>"#));
@@ -520,12 +540,30 @@ mod tests {
         assert!(output.contains("</Element>"));
     }
 
+    #[test]
+    fn test_relaxed_mode_skips_empty_header_and_options() {
+        let project = create_test_project();
+
+        let mut writer = create_test_writer();
+        write_header(&mut writer, &project, OutputMode::Relaxed).unwrap();
+        let output = get_output(writer);
+        assert!(output.contains(r#"<CustomData Category="CompatibilityMode">"#));
+        assert!(!output.contains("SqlCmdVariables"));
+
+        let mut writer = create_test_writer();
+        write_database_options(&mut writer, &project, OutputMode::Relaxed).unwrap();
+        let output = get_output(writer);
+        assert!(output.contains(r#"<Element Type="SqlDatabaseOptions">"#));
+        assert!(!output.contains("DefaultLanguage"));
+        assert!(!output.contains("DefaultFullTextLanguage"));
+    }
+
     #[test]
     fn test_write_database_options_with_filegroup() {
         let mut writer = create_test_writer();
         let mut project = create_test_project();
         project.database_options.default_filegroup = Some("PRIMARY".to_string());
-        write_database_options(&mut writer, &project).unwrap();
+        write_database_options(&mut writer, &project, OutputMode::Parity).unwrap();
         let output = get_output(writer);
         assert!(output.contains(r#"<Relationship Name="DefaultFilegroup">"#));
         assert!(output.contains(r#"ExternalSource="BuiltIns""#));
@@ -538,7 +576,7 @@ mod tests {
         let mut writer = create_test_writer();
         let mut project = create_test_project();
         project.database_options.page_verify = Some("NONE".to_string());
-        write_database_options(&mut writer, &project).unwrap();
+        write_database_options(&mut writer, &project, OutputMode::Parity).unwrap();
         let output = get_output(writer);
         assert!(output.contains(r#"<Property Name="PageVerifyMode" Value="0"/>"#));
 
@@ -546,7 +584,7 @@ mod tests {
         let mut writer = create_test_writer();
         let mut project = create_test_project();
         project.database_options.page_verify = Some("TORN_PAGE_DETECTION".to_string());
-        write_database_options(&mut writer, &project).unwrap();
+        write_database_options(&mut writer, &project, OutputMode::Parity).unwrap();
         let output = get_output(writer);
         assert!(output.contains(r#"<Property Name="PageVerifyMode" Value="1"/>"#));
     }
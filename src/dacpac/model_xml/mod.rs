@@ -11,25 +11,29 @@ mod xml_helpers;
 
 use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, Event};
 use quick_xml::Writer;
-use sqlparser::dialect::MsSqlDialect;
-use sqlparser::keywords::Keyword;
-use sqlparser::tokenizer::{Token, Tokenizer};
+use std::collections::{BTreeMap, HashMap};
 use std::io::Write;
 
+use crate::lineage::{ColumnLineage, LineageObjectType, ObjectLineage};
 use crate::model::{
-    ConstraintColumn, ConstraintElement, ConstraintType, DatabaseModel, ModelElement, RawElement,
-    ScalarTypeElement, SchemaElement, SortDirection, TableTypeColumnElement, TableTypeConstraint,
-    TriggerElement, UserDefinedTypeElement,
+    ConstraintColumn, ConstraintElement, ConstraintType, DatabaseModel, ModelElement, OutputMode,
+    RawElement, ScalarTypeElement, SchemaElement, SortDirection, SourceLocation,
+    TableTypeColumnElement, TableTypeConstraint, TriggerElement, UserDefinedTypeElement,
 };
-use crate::parser::identifier_utils::format_word;
+use crate::parser::identifier_utils::{escape_identifier, format_word};
+use crate::parser::lexer::{tokenize, tokenize_with_location, Keyword, Token, TokenWithSpan};
+use crate::parser::token_parser_base::location_to_offset;
 use crate::project::SqlProject;
 
 // Re-export XML helper functions for use within this module
 use xml_helpers::{
-    is_builtin_schema, write_property, write_relationship, write_schema_relationship,
-    write_script_property,
+    is_builtin_schema, write_filegroup_relationship, write_property, write_relationship,
+    write_schema_relationship, write_script_property, write_set_options,
+    write_sys_comments_annotation,
 };
 
+pub(crate) use xml_helpers::escape_cdata_sections;
+
 // Re-export header functions for use within this module
 use header::{write_database_options, write_header};
 
@@ -37,25 +41,30 @@ use header::{write_database_options, write_header};
 use table_writer::{write_table, write_table_type_column_with_annotation};
 
 // Re-export view writer functions for use within this module
-use view_writer::{write_raw_view, write_view};
+use view_writer::{extract_view_columns_and_deps, extract_view_query, write_raw_view, write_view};
 
 // Re-export programmability writer functions for use within this module
-use programmability_writer::{write_function, write_procedure};
+use programmability_writer::{
+    function_body_dependencies, inline_tvf_output_columns, procedure_body_dependencies,
+    write_function, write_procedure,
+};
 
 // Re-export other writers functions for use within this module
 use other_writers::{
-    write_columnstore_index, write_extended_property, write_filegroup, write_fulltext_catalog,
-    write_fulltext_index, write_index, write_partition_function, write_partition_scheme,
-    write_permission, write_role, write_role_membership, write_sequence, write_synonym, write_user,
+    write_columnstore_index, write_data_compression_options, write_extended_property,
+    write_filegroup, write_fulltext_catalog, write_fulltext_index, write_index,
+    write_partition_function, write_partition_scheme, write_permission, write_role,
+    write_role_membership, write_sequence, write_synonym, write_user,
 };
 
 // Re-export body dependency extraction functions and types
 use body_deps::{
     compute_line_offsets, extract_body_dependencies, extract_bracketed_identifiers_tokenized,
-    extract_cte_definitions, extract_table_variable_definitions, extract_temp_table_definitions,
-    is_sql_keyword, location_to_byte_offset, parse_qualified_name_tokenized, BodyDepToken,
-    BodyDependency, BodyDependencyTokenScanner, CteColumn, TableAliasTokenParser,
-    TableVariableColumn, TempTableColumn,
+    extract_cte_definitions, extract_function_calls_tokenized,
+    extract_sequence_references_tokenized, extract_table_variable_definitions,
+    extract_temp_table_definitions, is_sql_keyword, location_to_byte_offset,
+    parse_qualified_name_tokenized, BodyDepToken, BodyDependency, BodyDependencyTokenScanner,
+    CteColumn, TableAliasTokenParser, TableVariableColumn, TempTableColumn,
 };
 
 // Re-export column registry for schema-aware column resolution (Phase 49)
@@ -175,7 +184,7 @@ pub fn generate_model_xml<W: Write>(
     xml_writer.write_event(Event::Start(root))?;
 
     // Header element with CustomData entries
-    write_header(&mut xml_writer, project)?;
+    write_header(&mut xml_writer, project, model.output_mode)?;
 
     // Model element
     xml_writer.write_event(Event::Start(BytesStart::new("Model")))?;
@@ -203,13 +212,14 @@ pub fn generate_model_xml<W: Write>(
             let elem_name_lower = model.xml_name(idx).to_lowercase();
             let elem_type = element.type_name().to_lowercase();
             if db_options_sort_key <= (elem_name_lower.as_str(), elem_type.as_str()) {
-                write_database_options(&mut xml_writer, project)?;
+                write_database_options(&mut xml_writer, project, model.output_mode)?;
                 db_options_written = true;
             }
         }
         write_element(
             &mut xml_writer,
             element,
+            model.source(idx),
             model,
             &project.default_schema,
             &column_registry,
@@ -219,7 +229,7 @@ pub fn generate_model_xml<W: Write>(
     // Write SqlDatabaseOptions at the end if not yet written (happens when all elements
     // have empty Name and Type < "SqlDatabaseOptions", which is rare)
     if !db_options_written {
-        write_database_options(&mut xml_writer, project)?;
+        write_database_options(&mut xml_writer, project, model.output_mode)?;
     }
 
     // Close Model
@@ -231,32 +241,209 @@ pub fn generate_model_xml<W: Write>(
     Ok(())
 }
 
+/// Generate only the `<Model>` element and its child elements, without the XML declaration,
+/// `DataSchemaModel` root, `Header` or `SqlDatabaseOptions`. Used by single-file compile mode
+/// to show how individual objects serialize.
+pub fn generate_model_fragment<W: Write>(
+    writer: W,
+    model: &DatabaseModel,
+    project: &SqlProject,
+) -> anyhow::Result<()> {
+    let mut xml_writer = Writer::new_with_indent(writer, b' ', 2);
+    xml_writer
+        .config_mut()
+        .add_space_before_slash_in_empty_elements = true;
+
+    xml_writer.write_event(Event::Start(BytesStart::new("Model")))?;
+
+    let column_registry = ColumnRegistry::from_model(model, &project.default_schema);
+    for (idx, element) in model.elements.iter().enumerate() {
+        write_element(
+            &mut xml_writer,
+            element,
+            model.source(idx),
+            model,
+            &project.default_schema,
+            &column_registry,
+        )?;
+    }
+
+    xml_writer.write_event(Event::End(BytesEnd::new("Model")))?;
+
+    Ok(())
+}
+
+/// Collect the model objects each view, procedure and function depends on, keyed by the
+/// dependent object's full name (e.g. `[dbo].[GetUsers]` -> `[[dbo].[Users]]`).
+///
+/// Uses the same QueryDependencies/BodyDependencies extraction as model.xml, keeping only
+/// references that resolve to top-level objects in the model (columns, parameters and
+/// built-in types are dropped). Each dependency list is sorted and deduplicated.
+pub fn object_references(
+    model: &DatabaseModel,
+    default_schema: &str,
+) -> BTreeMap<String, Vec<String>> {
+    let column_registry = ColumnRegistry::from_model(model, default_schema);
+
+    // Lowercased full name -> canonical full name for objects that can be referenced
+    let objects: HashMap<String, String> = model
+        .elements
+        .iter()
+        .filter(|e| {
+            matches!(
+                e,
+                ModelElement::Table(_)
+                    | ModelElement::View(_)
+                    | ModelElement::Procedure(_)
+                    | ModelElement::Function(_)
+                    | ModelElement::Sequence(_)
+                    | ModelElement::Synonym(_)
+                    | ModelElement::UserDefinedType(_)
+            )
+        })
+        .map(|e| {
+            let name = e.full_name();
+            (name.to_lowercase(), name)
+        })
+        .collect();
+
+    let mut references = BTreeMap::new();
+    for element in &model.elements {
+        let full_name = element.full_name();
+        let refs: Vec<String> = match element {
+            ModelElement::View(v) => match column_registry.get_cached_view(&full_name) {
+                Some(cached) => cached.query_deps.clone(),
+                None => {
+                    let query = extract_view_query(&v.definition);
+                    extract_view_columns_and_deps(
+                        &query,
+                        default_schema,
+                        model,
+                        v.is_schema_bound,
+                        &column_registry,
+                    )
+                    .1
+                }
+            },
+            ModelElement::Procedure(p) => {
+                object_refs(procedure_body_dependencies(p, &column_registry))
+            }
+            ModelElement::Function(f) => {
+                object_refs(function_body_dependencies(f, &column_registry))
+            }
+            _ => continue,
+        };
+
+        let mut resolved: Vec<String> = refs
+            .iter()
+            .filter_map(|r| objects.get(&r.to_lowercase()))
+            .filter(|name| **name != full_name)
+            .cloned()
+            .collect();
+        resolved.sort();
+        resolved.dedup();
+        references.insert(full_name, resolved);
+    }
+    references
+}
+
+/// Collect the output columns of every view and inline table-valued function with the
+/// column each one is read from, using the same source resolution as the model.xml
+/// `Columns` relationship. Objects are returned in model order.
+pub fn column_lineage(model: &DatabaseModel, default_schema: &str) -> Vec<ObjectLineage> {
+    let column_registry = ColumnRegistry::from_model(model, default_schema);
+
+    model
+        .elements
+        .iter()
+        .filter_map(|element| {
+            let full_name = element.full_name();
+            let (object_type, columns) = match element {
+                ModelElement::View(v) => {
+                    let columns = match column_registry.get_cached_view(&full_name) {
+                        Some(cached) => cached.columns.clone(),
+                        None => {
+                            let query = extract_view_query(&v.definition);
+                            extract_view_columns_and_deps(
+                                &query,
+                                default_schema,
+                                model,
+                                v.is_schema_bound,
+                                &column_registry,
+                            )
+                            .0
+                        }
+                    };
+                    (LineageObjectType::View, columns)
+                }
+                ModelElement::Function(f) => (
+                    LineageObjectType::InlineTableValuedFunction,
+                    inline_tvf_output_columns(f, model, default_schema, &column_registry),
+                ),
+                _ => return None,
+            };
+            if columns.is_empty() {
+                return None;
+            }
+
+            Some(ObjectLineage {
+                name: full_name,
+                object_type,
+                defined_in: None,
+                header: None,
+                columns: columns
+                    .into_iter()
+                    .map(|c| ColumnLineage {
+                        name: c.name,
+                        source: c.source_ref,
+                        base_source: None,
+                    })
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+fn object_refs(deps: Vec<BodyDependency>) -> Vec<String> {
+    deps.into_iter()
+        .filter_map(|d| match d {
+            BodyDependency::ObjectRef(name) => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Write one element; `source` locates its script for the SysCommentsObjectAnnotation
+/// of scripted modules
 fn write_element<W: Write>(
     writer: &mut Writer<W>,
     element: &ModelElement,
+    source: Option<&SourceLocation>,
     model: &DatabaseModel,
     default_schema: &str,
     column_registry: &ColumnRegistry,
 ) -> anyhow::Result<()> {
     match element {
         ModelElement::Schema(s) => write_schema(writer, s),
-        ModelElement::Table(t) => write_table(writer, t),
-        ModelElement::View(v) => write_view(writer, v, model, default_schema, column_registry),
+        ModelElement::Table(t) => write_table(writer, t, model),
+        ModelElement::View(v) => {
+            write_view(writer, v, model, default_schema, column_registry, source)
+        }
         ModelElement::Procedure(p) => {
-            write_procedure(writer, p, model, default_schema, column_registry)
+            write_procedure(writer, p, model, default_schema, column_registry, source)
         }
         ModelElement::Function(f) => {
-            write_function(writer, f, model, default_schema, column_registry)
+            write_function(writer, f, model, default_schema, column_registry, source)
         }
         ModelElement::Index(i) => write_index(writer, i),
         ModelElement::FullTextIndex(f) => write_fulltext_index(writer, f),
         ModelElement::FullTextCatalog(c) => write_fulltext_catalog(writer, c),
-        ModelElement::Constraint(c) => write_constraint(writer, c),
+        ModelElement::Constraint(c) => write_constraint(writer, c, model, default_schema),
         ModelElement::Sequence(s) => write_sequence(writer, s),
         ModelElement::UserDefinedType(u) => write_user_defined_type(writer, u),
         ModelElement::ScalarType(s) => write_scalar_type(writer, s),
         ModelElement::ExtendedProperty(e) => write_extended_property(writer, e),
-        ModelElement::Trigger(t) => write_trigger(writer, t),
+        ModelElement::Trigger(t) => write_trigger(writer, t, model, source),
         ModelElement::Filegroup(f) => write_filegroup(writer, f),
         ModelElement::PartitionFunction(pf) => write_partition_function(writer, pf),
         ModelElement::PartitionScheme(ps) => write_partition_scheme(writer, ps),
@@ -266,7 +453,9 @@ fn write_element<W: Write>(
         ModelElement::Permission(p) => write_permission(writer, p),
         ModelElement::RoleMembership(rm) => write_role_membership(writer, rm),
         ModelElement::ColumnstoreIndex(ci) => write_columnstore_index(writer, ci),
-        ModelElement::Raw(r) => write_raw(writer, r, model, default_schema, column_registry),
+        ModelElement::Raw(r) => {
+            write_raw(writer, r, model, default_schema, column_registry, source)
+        }
     }
 }
 
@@ -278,7 +467,7 @@ fn write_schema<W: Write>(writer: &mut Writer<W>, schema: &SchemaElement) -> any
     }
 
     // Pre-compute schema name before attribute batching (Phase 16.3.3 optimization)
-    let schema_name = format!("[{}]", schema.name);
+    let schema_name = format!("[{}]", escape_identifier(&schema.name));
     let elem = BytesStart::new("Element")
         .with_attributes([("Type", "SqlSchema"), ("Name", schema_name.as_str())]);
     writer.write_event(Event::Start(elem))?;
@@ -302,7 +491,7 @@ fn write_authorizer_relationship<W: Write>(
 
     writer.write_event(Event::Start(BytesStart::new("Entry")))?;
 
-    let owner_ref = format!("[{}]", owner);
+    let owner_ref = format!("[{}]", escape_identifier(owner));
     // Conditional attribute - use with_attributes with appropriate attributes
     let refs = if is_builtin_schema(owner) {
         BytesStart::new("References")
@@ -402,8 +591,7 @@ fn parse_column_expression(
     let trimmed = expr.trim();
 
     // Tokenize the expression using sqlparser
-    let dialect = MsSqlDialect {};
-    let tokens = match Tokenizer::new(&dialect, trimmed).tokenize() {
+    let tokens = match tokenize(trimmed) {
         Ok(t) => t,
         Err(_) => {
             // Fallback: if tokenization fails, use simple extraction
@@ -492,14 +680,18 @@ fn token_to_sql(token: &Token) -> String {
     if let Token::Word(w) = token {
         return format_word(w);
     }
-    // For everything else, use the Display impl
-    token.to_string()
+    match token {
+        // Display doesn't re-escape embedded quotes
+        Token::SingleQuotedString(s) => format!("'{}'", s.replace('\'', "''")),
+        Token::NationalStringLiteral(s) => format!("N'{}'", s.replace('\'', "''")),
+        // For everything else, use the Display impl
+        _ => token.to_string(),
+    }
 }
 
 /// Check if an expression starts with a specific SQL keyword using tokenizer
 fn starts_with_keyword(expr: &str, keyword: Keyword) -> bool {
-    let dialect = MsSqlDialect {};
-    if let Ok(tokens) = Tokenizer::new(&dialect, expr).tokenize() {
+    if let Ok(tokens) = tokenize(expr) {
         for token in tokens {
             match token {
                 Token::Whitespace(_) => continue,
@@ -518,8 +710,7 @@ pub(crate) fn extract_expression_before_as(expr: &str) -> String {
     let trimmed = expr.trim();
 
     // Tokenize the expression using sqlparser
-    let dialect = MsSqlDialect {};
-    let tokens = match Tokenizer::new(&dialect, trimmed).tokenize() {
+    let tokens = match tokenize(trimmed) {
         Ok(t) => t,
         Err(_) => {
             // Fallback: if tokenization fails, return trimmed expression
@@ -587,21 +778,28 @@ fn extract_column_refs_tokenized(sql: &str) -> Vec<String> {
                     schema,
                     table,
                     column,
-                } => Some(format!("[{}].[{}].[{}]", schema, table, column)),
+                } => Some(format!(
+                    "[{}].[{}].[{}]",
+                    escape_identifier(&schema),
+                    escape_identifier(&table),
+                    escape_identifier(&column)
+                )),
 
                 // Two-part bracketed: [alias].[column] or [schema].[table]
-                BodyDepToken::TwoPartBracketed { first, second } => {
-                    Some(format!("[{}].[{}]", first, second))
-                }
+                BodyDepToken::TwoPartBracketed { first, second } => Some(format!(
+                    "[{}].[{}]",
+                    escape_identifier(&first),
+                    escape_identifier(&second)
+                )),
 
                 // alias.[column] - unbracketed alias with bracketed column
                 BodyDepToken::AliasDotBracketedColumn { alias, column } => {
-                    Some(format!("{}.[{}]", alias, column))
+                    Some(format!("{}.[{}]", alias, escape_identifier(&column)))
                 }
 
                 // [alias].column - bracketed alias with unbracketed column
                 BodyDepToken::BracketedAliasDotColumn { alias, column } => {
-                    Some(format!("[{}].{}", alias, column))
+                    Some(format!("[{}].{}", escape_identifier(&alias), column))
                 }
 
                 // schema.table - unbracketed two-part
@@ -717,8 +915,7 @@ fn extract_cast_expressions_tokenized(sql: &str) -> Vec<CastExprInfo> {
         return results;
     }
 
-    let dialect = MsSqlDialect {};
-    let Ok(tokens) = Tokenizer::new(&dialect, sql).tokenize_with_location() else {
+    let Ok(tokens) = tokenize_with_location(sql) else {
         return results;
     };
 
@@ -729,13 +926,12 @@ fn extract_cast_expressions_tokenized(sql: &str) -> Vec<CastExprInfo> {
     let mut i = 0;
 
     // Helper to skip whitespace tokens
-    let skip_whitespace =
-        |tokens: &[sqlparser::tokenizer::TokenWithSpan], mut idx: usize| -> usize {
-            while idx < tokens.len() && matches!(&tokens[idx].token, Token::Whitespace(_)) {
-                idx += 1;
-            }
-            idx
-        };
+    let skip_whitespace = |tokens: &[TokenWithSpan], mut idx: usize| -> usize {
+        while idx < tokens.len() && matches!(&tokens[idx].token, Token::Whitespace(_)) {
+            idx += 1;
+        }
+        idx
+    };
 
     while i < len {
         // Look for CAST keyword (unquoted word)
@@ -854,7 +1050,7 @@ fn resolve_column_reference(
                 return None;
             }
             if let Some((_, table_ref)) = table_aliases.first() {
-                return Some(format!("{}.[{}]", table_ref, col_name));
+                return Some(format!("{}.[{}]", table_ref, escape_identifier(col_name)));
             }
             None
         }
@@ -871,7 +1067,11 @@ fn resolve_column_reference(
             // Try to find matching alias
             for (alias, table_ref) in table_aliases {
                 if alias.eq_ignore_ascii_case(alias_or_schema) {
-                    return Some(format!("{}.[{}]", table_ref, col_or_table));
+                    return Some(format!(
+                        "{}.[{}]",
+                        table_ref,
+                        escape_identifier(col_or_table)
+                    ));
                 }
             }
 
@@ -887,7 +1087,12 @@ fn resolve_column_reference(
             if column == "*" {
                 return None;
             }
-            Some(format!("[{}].[{}].[{}]", schema, table, column))
+            Some(format!(
+                "[{}].[{}].[{}]",
+                escape_identifier(schema),
+                escape_identifier(table),
+                escape_identifier(column)
+            ))
         }
         _ => None,
     }
@@ -970,21 +1175,28 @@ fn extract_all_column_references(
                     schema,
                     table,
                     column,
-                } => Some(format!("[{}].[{}].[{}]", schema, table, column)),
+                } => Some(format!(
+                    "[{}].[{}].[{}]",
+                    escape_identifier(&schema),
+                    escape_identifier(&table),
+                    escape_identifier(&column)
+                )),
 
                 // Two-part bracketed: [alias].[column] or [schema].[table]
-                BodyDepToken::TwoPartBracketed { first, second } => {
-                    Some(format!("[{}].[{}]", first, second))
-                }
+                BodyDepToken::TwoPartBracketed { first, second } => Some(format!(
+                    "[{}].[{}]",
+                    escape_identifier(&first),
+                    escape_identifier(&second)
+                )),
 
                 // alias.[column] - unbracketed alias with bracketed column
                 BodyDepToken::AliasDotBracketedColumn { alias, column } => {
-                    Some(format!("{}.[{}]", alias, column))
+                    Some(format!("{}.[{}]", alias, escape_identifier(&column)))
                 }
 
                 // [alias].column - bracketed alias with unbracketed column
                 BodyDepToken::BracketedAliasDotColumn { alias, column } => {
-                    Some(format!("[{}].{}", alias, column))
+                    Some(format!("[{}].{}", escape_identifier(&alias), column))
                 }
 
                 // schema.table - unbracketed two-part
@@ -1004,7 +1216,7 @@ fn extract_all_column_references(
                     if is_alias {
                         None
                     } else {
-                        Some(ident)
+                        Some(format!("[{}]", escape_identifier(&ident)))
                     }
                 }
 
@@ -1033,15 +1245,15 @@ fn extract_all_column_references(
 pub(crate) fn normalize_type_name(type_name: &str) -> String {
     let trimmed = type_name.trim();
 
-    // Already in [schema].[name] format
-    if trimmed.starts_with('[') && trimmed.contains("].[") {
-        return trimmed.to_string();
-    }
-
-    // Use tokenized parsing to handle qualified names
+    // Use tokenized parsing to handle qualified names, re-quoting so that `]` in a
+    // name is escaped however the input was written
     if let Some(qn) = parse_qualified_name_tokenized(trimmed) {
         if let Some((schema, name)) = qn.schema_and_table() {
-            return format!("[{}].[{}]", schema, name);
+            return format!(
+                "[{}].[{}]",
+                escape_identifier(schema),
+                escape_identifier(name)
+            );
         }
     }
 
@@ -1092,8 +1304,7 @@ fn clean_data_type(dt: &str) -> String {
     }
 
     // Use tokenizer to find trailing keywords (READONLY, NULL, NOT NULL)
-    let dialect = MsSqlDialect {};
-    let tokens = match Tokenizer::new(&dialect, trimmed).tokenize() {
+    let tokens = match tokenize(trimmed) {
         Ok(t) => t,
         Err(_) => {
             // Fallback to original string if tokenization fails
@@ -1172,7 +1383,7 @@ fn clean_data_type(dt: &str) -> String {
         match token {
             Token::Word(w) => {
                 if w.quote_style == Some('[') {
-                    result.push_str(&format!("[{}]", w.value));
+                    result.push_str(&format!("[{}]", escape_identifier(&w.value)));
                 } else if w.quote_style == Some('"') {
                     result.push_str(&format!("\"{}\"", w.value));
                 } else {
@@ -1229,7 +1440,7 @@ pub(crate) struct InsertSelectStatement {
 /// Token-based parser for INSERT...SELECT statements in trigger bodies.
 /// Replaces INSERT_SELECT_RE and INSERT_SELECT_JOIN_RE regex patterns.
 pub(crate) struct InsertSelectTokenParser {
-    tokens: Vec<sqlparser::tokenizer::TokenWithSpan>,
+    tokens: Vec<TokenWithSpan>,
     pos: usize,
     source: String,           // Keep source for extracting raw text segments
     line_offsets: Vec<usize>, // For converting token locations to byte offsets
@@ -1238,10 +1449,7 @@ pub(crate) struct InsertSelectTokenParser {
 impl InsertSelectTokenParser {
     /// Create a new parser for SQL body text
     pub fn new(sql: &str) -> Option<Self> {
-        let dialect = MsSqlDialect {};
-        let tokens = Tokenizer::new(&dialect, sql)
-            .tokenize_with_location()
-            .ok()?;
+        let tokens = tokenize_with_location(sql).ok()?;
         let line_offsets = compute_line_offsets(sql);
         Some(Self {
             tokens,
@@ -1589,7 +1797,7 @@ impl InsertSelectTokenParser {
     }
 
     /// Get current token without consuming
-    fn current_token(&self) -> Option<&sqlparser::tokenizer::TokenWithSpan> {
+    fn current_token(&self) -> Option<&TokenWithSpan> {
         self.tokens.get(self.pos)
     }
 
@@ -1651,7 +1859,7 @@ pub(crate) struct UpdateStatement {
 /// Token-based parser for UPDATE...FROM...JOIN statements in trigger bodies.
 /// Replaces UPDATE_ALIAS_RE regex pattern.
 pub(crate) struct UpdateTokenParser {
-    tokens: Vec<sqlparser::tokenizer::TokenWithSpan>,
+    tokens: Vec<TokenWithSpan>,
     pos: usize,
     source: String,           // Keep source for extracting raw text segments
     line_offsets: Vec<usize>, // For converting token locations to byte offsets
@@ -1660,10 +1868,7 @@ pub(crate) struct UpdateTokenParser {
 impl UpdateTokenParser {
     /// Create a new parser for SQL body text
     pub fn new(sql: &str) -> Option<Self> {
-        let dialect = MsSqlDialect {};
-        let tokens = Tokenizer::new(&dialect, sql)
-            .tokenize_with_location()
-            .ok()?;
+        let tokens = tokenize_with_location(sql).ok()?;
         let line_offsets = compute_line_offsets(sql);
         Some(Self {
             tokens,
@@ -1978,7 +2183,7 @@ impl UpdateTokenParser {
     }
 
     /// Get current token without consuming
-    fn current_token(&self) -> Option<&sqlparser::tokenizer::TokenWithSpan> {
+    fn current_token(&self) -> Option<&TokenWithSpan> {
         self.tokens.get(self.pos)
     }
 
@@ -2017,15 +2222,73 @@ impl UpdateTokenParser {
 ///
 /// CHECK expressions reference columns by their unqualified names (e.g., `[Price] >= 0`).
 /// This function extracts those column names and returns them as fully-qualified references
-/// in the format `[schema].[table].[column]`.
+/// in the format `[schema].[table].[column]`. Calls to schema-qualified functions
+/// (e.g., `[dbo].[HasOverlap]([Start], [End]) = 0`) are included as function references.
 ///
 /// DotNet emits these as the `CheckExpressionDependencies` relationship.
 fn extract_check_expression_columns(
     expression: &str,
     table_schema: &str,
     table_name: &str,
+    model: &DatabaseModel,
 ) -> Vec<String> {
-    extract_expression_column_references(expression, table_schema, table_name)
+    extract_expression_column_references(expression, table_schema, table_name, model)
+}
+
+/// Extract object references from a DEFAULT constraint expression.
+///
+/// Defaults cannot reference columns, but can draw from sequences
+/// (`NEXT VALUE FOR [dbo].[OrderSeq]`) or call schema-qualified functions.
+/// Returns the references in order of appearance.
+///
+/// DotNet emits these as the `ExpressionDependencies` relationship.
+fn extract_default_expression_dependencies(
+    expression: &str,
+    model: &DatabaseModel,
+    default_schema: &str,
+) -> Vec<String> {
+    let mut position_refs = extract_sequence_references_tokenized(expression, default_schema);
+    position_refs.extend(
+        extract_function_calls_tokenized(expression, model)
+            .into_iter()
+            .map(|call| {
+                (
+                    call.position,
+                    format!(
+                        "[{}].[{}]",
+                        escape_identifier(&call.schema),
+                        escape_identifier(&call.name)
+                    ),
+                )
+            }),
+    );
+    position_refs.sort_by_key(|(pos, _)| *pos);
+
+    let mut refs: Vec<String> = Vec::new();
+    for (_, reference) in position_refs {
+        if !refs.contains(&reference) {
+            refs.push(reference);
+        }
+    }
+    refs
+}
+
+/// Write the ExpressionDependencies relationship for a DEFAULT constraint, if any
+fn write_default_expression_dependencies<W: Write>(
+    writer: &mut Writer<W>,
+    constraint: &ConstraintElement,
+    model: &DatabaseModel,
+    default_schema: &str,
+) -> anyhow::Result<()> {
+    let Some(ref definition) = constraint.definition else {
+        return Ok(());
+    };
+    let deps = extract_default_expression_dependencies(definition, model, default_schema);
+    if !deps.is_empty() {
+        let deps_str: Vec<&str> = deps.iter().map(|s| s.as_str()).collect();
+        write_relationship(writer, "ExpressionDependencies", &deps_str)?;
+    }
+    Ok(())
 }
 
 /// Extract column references from a filtered index predicate.
@@ -2052,7 +2315,7 @@ fn extract_filter_predicate_columns(predicate: &str, table_ref: &str) -> Vec<Str
 
         // Build fully-qualified column reference using provided table_ref
         // table_ref is in format "[schema].[table]"
-        let col_ref = format!("{}.[{}]", table_ref, ident.name);
+        let col_ref = format!("{}.[{}]", table_ref, escape_identifier(&ident.name));
 
         // Only add each column once, but preserve order of first appearance
         if !seen.contains(&col_ref) {
@@ -2069,14 +2332,16 @@ fn extract_filter_predicate_columns(predicate: &str, table_ref: &str) -> Vec<Str
 /// Computed column expressions reference columns by their unqualified names
 /// (e.g., `[Quantity] * [UnitPrice]`). This function extracts those column names
 /// and returns them as fully-qualified references in the format `[schema].[table].[column]`.
+/// Calls to schema-qualified functions are included as function references.
 ///
 /// DotNet emits these as the `ExpressionDependencies` relationship.
 pub(crate) fn extract_computed_expression_columns(
     expression: &str,
     table_schema: &str,
     table_name: &str,
+    model: &DatabaseModel,
 ) -> Vec<String> {
-    extract_expression_column_references(expression, table_schema, table_name)
+    extract_expression_column_references(expression, table_schema, table_name, model)
 }
 
 /// Extract column references and type references from an expression.
@@ -2085,7 +2350,8 @@ pub(crate) fn extract_computed_expression_columns(
 /// This function extracts those column names and returns them as fully-qualified references
 /// in the format `[schema].[table].[column]`.
 ///
-/// Additionally, CAST expressions emit type references (e.g., `[nvarchar]`) to match
+/// Additionally, CAST expressions emit type references (e.g., `[nvarchar]`) and calls to
+/// schema-qualified functions emit function references (e.g., `[dbo].[CalcTotal]`) to match
 /// DotNet DacFx behavior.
 ///
 /// Used by both CHECK constraints and computed columns.
@@ -2093,6 +2359,7 @@ fn extract_expression_column_references(
     expression: &str,
     table_schema: &str,
     table_name: &str,
+    model: &DatabaseModel,
 ) -> Vec<String> {
     use std::collections::HashSet;
     let mut refs = Vec::new();
@@ -2117,6 +2384,18 @@ fn extract_expression_column_references(
         ));
     }
 
+    // Calls to user-defined functions reference the function itself
+    for call in extract_function_calls_tokenized(expression, model) {
+        position_refs.push((
+            call.position,
+            format!(
+                "[{}].[{}]",
+                escape_identifier(&call.schema),
+                escape_identifier(&call.name)
+            ),
+        ));
+    }
+
     // Collect column references with their positions using token-based extraction
     // This replaces BRACKETED_IDENT_RE for better whitespace and comment handling
     for ident in extract_bracketed_identifiers_tokenized(expression) {
@@ -2128,7 +2407,12 @@ fn extract_expression_column_references(
         }
 
         // Build fully-qualified column reference
-        let col_ref = format!("[{}].[{}].[{}]", table_schema, table_name, ident.name);
+        let col_ref = format!(
+            "[{}].[{}].[{}]",
+            escape_identifier(table_schema),
+            escape_identifier(table_name),
+            escape_identifier(&ident.name)
+        );
         let pos = ident.position;
 
         // For columns inside a CAST, adjust position to appear after the type
@@ -2219,8 +2503,7 @@ pub(crate) fn write_body_dependencies<W: Write>(
 /// ON clauses terminate at: WHERE, GROUP, ORDER, HAVING, UNION, INNER, LEFT, RIGHT,
 /// OUTER, CROSS, JOIN, or semicolon.
 fn extract_on_clause_boundaries_tokenized(query: &str) -> Vec<(usize, usize)> {
-    let dialect = MsSqlDialect {};
-    let Ok(tokens) = Tokenizer::new(&dialect, query).tokenize_with_location() else {
+    let Ok(tokens) = tokenize_with_location(query) else {
         return Vec::new();
     };
 
@@ -2234,13 +2517,12 @@ fn extract_on_clause_boundaries_tokenized(query: &str) -> Vec<(usize, usize)> {
     let mut i = 0;
 
     // Helper to skip whitespace tokens
-    let skip_whitespace =
-        |tokens: &[sqlparser::tokenizer::TokenWithSpan], mut idx: usize| -> usize {
-            while idx < tokens.len() && matches!(&tokens[idx].token, Token::Whitespace(_)) {
-                idx += 1;
-            }
-            idx
-        };
+    let skip_whitespace = |tokens: &[TokenWithSpan], mut idx: usize| -> usize {
+        while idx < tokens.len() && matches!(&tokens[idx].token, Token::Whitespace(_)) {
+            idx += 1;
+        }
+        idx
+    };
 
     // Keywords that terminate an ON clause
     let terminator_keywords = [
@@ -2329,8 +2611,7 @@ fn extract_on_clause_boundaries_tokenized(query: &str) -> Vec<(usize, usize)> {
 /// # Terminating Keywords
 /// GROUP BY clauses terminate at: HAVING, ORDER, UNION, or semicolon.
 fn extract_group_by_clause_boundaries_tokenized(query: &str) -> Option<(usize, usize)> {
-    let dialect = MsSqlDialect {};
-    let Ok(tokens) = Tokenizer::new(&dialect, query).tokenize_with_location() else {
+    let Ok(tokens) = tokenize_with_location(query) else {
         return None;
     };
 
@@ -2343,13 +2624,12 @@ fn extract_group_by_clause_boundaries_tokenized(query: &str) -> Option<(usize, u
     let mut i = 0;
 
     // Helper to skip whitespace tokens
-    let skip_whitespace =
-        |tokens: &[sqlparser::tokenizer::TokenWithSpan], mut idx: usize| -> usize {
-            while idx < tokens.len() && matches!(&tokens[idx].token, Token::Whitespace(_)) {
-                idx += 1;
-            }
-            idx
-        };
+    let skip_whitespace = |tokens: &[TokenWithSpan], mut idx: usize| -> usize {
+        while idx < tokens.len() && matches!(&tokens[idx].token, Token::Whitespace(_)) {
+            idx += 1;
+        }
+        idx
+    };
 
     // Keywords that terminate a GROUP BY clause
     let terminator_keywords = ["HAVING", "ORDER", "UNION"];
@@ -2470,10 +2750,16 @@ pub(crate) fn parse_data_type(data_type: &str) -> (String, Option<i32>, Option<i
 fn write_constraint<W: Write>(
     writer: &mut Writer<W>,
     constraint: &ConstraintElement,
+    model: &DatabaseModel,
+    default_schema: &str,
 ) -> anyhow::Result<()> {
     // DotNet uses two-part names for constraints: [schema].[constraint_name]
     // But inline constraints (without CONSTRAINT keyword) have no Name attribute
-    let full_name = format!("[{}].[{}]", constraint.table_schema, constraint.name);
+    let full_name = format!(
+        "[{}].[{}]",
+        escape_identifier(&constraint.table_schema),
+        escape_identifier(&constraint.name)
+    );
 
     let type_name = match constraint.constraint_type {
         ConstraintType::PrimaryKey => "SqlPrimaryKeyConstraint",
@@ -2513,8 +2799,20 @@ fn write_constraint<W: Write>(
         }
     }
 
+    // Index options from WITH (...) on primary keys and unique constraints
+    if let Some(fill_factor) = constraint.fill_factor {
+        write_property(writer, "FillFactor", &fill_factor.to_string())?;
+    }
+    if constraint.is_padded {
+        write_property(writer, "IsPadded", "True")?;
+    }
+
     // Reference to table
-    let table_ref = format!("[{}].[{}]", constraint.table_schema, constraint.table_name);
+    let table_ref = format!(
+        "[{}].[{}]",
+        escape_identifier(&constraint.table_schema),
+        escape_identifier(&constraint.table_name)
+    );
 
     // Handle CHECK constraints with special ordering:
     // DotNet order for CHECK: CheckExpressionScript, CheckExpressionDependencies, DefiningTable
@@ -2528,6 +2826,7 @@ fn write_constraint<W: Write>(
                 definition,
                 &constraint.table_schema,
                 &constraint.table_name,
+                model,
             );
             if !col_refs.is_empty() {
                 let col_refs_str: Vec<&str> = col_refs.iter().map(|s| s.as_str()).collect();
@@ -2564,7 +2863,7 @@ fn write_constraint<W: Write>(
                         // is stored in the model for potential future use.
 
                         // Reference to the actual column
-                        let col_ref = format!("{}.[{}]", table_ref, col.name);
+                        let col_ref = format!("{}.[{}]", table_ref, escape_identifier(&col.name));
                         write_relationship(writer, "Column", &[&col_ref])?;
 
                         writer.write_event(Event::End(BytesEnd::new("Element")))?;
@@ -2573,15 +2872,26 @@ fn write_constraint<W: Write>(
 
                     writer.write_event(Event::End(BytesEnd::new("Relationship")))?;
 
+                    write_data_compression_options(
+                        writer,
+                        constraint.data_compression.as_ref(),
+                        &constraint.partition_compression,
+                    )?;
+
                     // DefiningTable comes after ColumnSpecifications
                     write_relationship(writer, "DefiningTable", &[&table_ref])?;
+
+                    // Filegroup from the ON clause; PRIMARY is a built-in filegroup
+                    if let Some(ref filegroup) = constraint.filegroup {
+                        write_filegroup_relationship(writer, filegroup)?;
+                    }
                 }
                 ConstraintType::ForeignKey => {
                     // Foreign keys: Columns, DefiningTable, ForeignColumns, ForeignTable (DotNet order)
                     let column_refs: Vec<String> = constraint
                         .columns
                         .iter()
-                        .map(|c| format!("{}.[{}]", table_ref, c.name))
+                        .map(|c| format!("{}.[{}]", table_ref, escape_identifier(&c.name)))
                         .collect();
                     let column_refs_str: Vec<&str> =
                         column_refs.iter().map(|s| s.as_str()).collect();
@@ -2596,7 +2906,9 @@ fn write_constraint<W: Write>(
                             if !foreign_columns.is_empty() {
                                 let foreign_col_refs: Vec<String> = foreign_columns
                                     .iter()
-                                    .map(|c| format!("{}.[{}]", foreign_table, c))
+                                    .map(|c| {
+                                        format!("{}.[{}]", foreign_table, escape_identifier(c))
+                                    })
                                     .collect();
                                 let foreign_col_refs_str: Vec<&str> =
                                     foreign_col_refs.iter().map(|s| s.as_str()).collect();
@@ -2611,16 +2923,27 @@ fn write_constraint<W: Write>(
                     }
                 }
                 ConstraintType::Default => {
-                    // Default constraints: DotNet order is DefaultExpressionScript, DefiningTable, ForColumn
+                    // Default constraints: DotNet order is DefaultExpressionScript, DefiningTable,
+                    // ExpressionDependencies, ForColumn
                     // Write DefaultExpressionScript property FIRST
                     if let Some(ref definition) = constraint.definition {
                         write_script_property(writer, "DefaultExpressionScript", definition)?;
                     }
                     // DefiningTable comes after property
                     write_relationship(writer, "DefiningTable", &[&table_ref])?;
+                    write_default_expression_dependencies(
+                        writer,
+                        constraint,
+                        model,
+                        default_schema,
+                    )?;
                     // ForColumn relationship to specify the target column
                     if !constraint.columns.is_empty() {
-                        let col_ref = format!("{}.[{}]", table_ref, constraint.columns[0].name);
+                        let col_ref = format!(
+                            "{}.[{}]",
+                            table_ref,
+                            escape_identifier(&constraint.columns[0].name)
+                        );
                         write_relationship(writer, "ForColumn", &[&col_ref])?;
                     }
                 }
@@ -2635,6 +2958,7 @@ fn write_constraint<W: Write>(
                 write_script_property(writer, "DefaultExpressionScript", definition)?;
             }
             write_relationship(writer, "DefiningTable", &[&table_ref])?;
+            write_default_expression_dependencies(writer, constraint, model, default_schema)?;
         } else {
             // No columns - still write DefiningTable for constraints that need it
             write_relationship(writer, "DefiningTable", &[&table_ref])?;
@@ -2675,7 +2999,11 @@ fn write_scalar_type<W: Write>(
     writer: &mut Writer<W>,
     scalar: &ScalarTypeElement,
 ) -> anyhow::Result<()> {
-    let full_name = format!("[{}].[{}]", scalar.schema, scalar.name);
+    let full_name = format!(
+        "[{}].[{}]",
+        escape_identifier(&scalar.schema),
+        escape_identifier(&scalar.name)
+    );
 
     // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
     let elem = BytesStart::new("Element").with_attributes([
@@ -2739,7 +3067,11 @@ fn write_user_defined_type<W: Write>(
     writer: &mut Writer<W>,
     udt: &UserDefinedTypeElement,
 ) -> anyhow::Result<()> {
-    let full_name = format!("[{}].[{}]", udt.schema, udt.name);
+    let full_name = format!(
+        "[{}].[{}]",
+        escape_identifier(&udt.schema),
+        escape_identifier(&udt.name)
+    );
 
     // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
     let elem = BytesStart::new("Element")
@@ -3069,7 +3401,7 @@ fn write_table_type_default_constraint<W: Write>(
     write_script_property(writer, "DefaultExpressionScript", default_value)?;
 
     // ForColumn relationship
-    let col_ref = format!("{}.[{}]", type_name, column_name);
+    let col_ref = format!("{}.[{}]", type_name, escape_identifier(column_name));
     // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
     let rel = BytesStart::new("Relationship").with_attributes([("Name", "ForColumn")]);
     writer.write_event(Event::Start(rel))?;
@@ -3108,7 +3440,7 @@ fn write_table_type_index<W: Write>(
     // Entry for this constraint (parent Constraints relationship is written by caller)
     writer.write_event(Event::Start(BytesStart::new("Entry")))?;
 
-    let idx_name = format!("{}.[{}]", type_name, name);
+    let idx_name = format!("{}.[{}]", type_name, escape_identifier(name));
     // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
     let elem = BytesStart::new("Element")
         .with_attributes([("Type", "SqlTableTypeIndex"), ("Name", idx_name.as_str())]);
@@ -3154,7 +3486,7 @@ fn write_table_type_index_with_annotation<W: Write>(
 ) -> anyhow::Result<()> {
     writer.write_event(Event::Start(BytesStart::new("Entry")))?;
 
-    let idx_name = format!("{}.[{}]", type_name, name);
+    let idx_name = format!("{}.[{}]", type_name, escape_identifier(name));
     // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
     let elem = BytesStart::new("Element")
         .with_attributes([("Type", "SqlTableTypeIndex"), ("Name", idx_name.as_str())]);
@@ -3219,7 +3551,7 @@ fn write_table_type_indexed_column_spec<W: Write>(
     }
 
     // Column relationship
-    let col_ref = format!("{}.[{}]", type_name, column_name);
+    let col_ref = format!("{}.[{}]", type_name, escape_identifier(column_name));
     write_relationship(writer, "Column", &[&col_ref])?;
 
     writer.write_event(Event::End(BytesEnd::new("Element")))?;
@@ -3231,8 +3563,17 @@ fn write_table_type_indexed_column_spec<W: Write>(
 /// DotNet format:
 /// - Properties: IsInsertTrigger, IsUpdateTrigger, IsDeleteTrigger, SqlTriggerType, BodyScript, IsAnsiNullsOn
 /// - Relationships: BodyDependencies, Parent (the table/view), no Schema relationship
-fn write_trigger<W: Write>(writer: &mut Writer<W>, trigger: &TriggerElement) -> anyhow::Result<()> {
-    let full_name = format!("[{}].[{}]", trigger.schema, trigger.name);
+fn write_trigger<W: Write>(
+    writer: &mut Writer<W>,
+    trigger: &TriggerElement,
+    model: &DatabaseModel,
+    source: Option<&SourceLocation>,
+) -> anyhow::Result<()> {
+    let full_name = format!(
+        "[{}].[{}]",
+        escape_identifier(&trigger.schema),
+        escape_identifier(&trigger.name)
+    );
 
     // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
     let elem = BytesStart::new("Element")
@@ -3262,11 +3603,25 @@ fn write_trigger<W: Write>(writer: &mut Writer<W>, trigger: &TriggerElement) ->
     let body_script = extract_trigger_body(&trigger.definition);
     write_script_property(writer, "BodyScript", &body_script)?;
 
-    // 6. IsAnsiNullsOn - always True for now (matches typical SQL Server defaults)
-    write_property(writer, "IsAnsiNullsOn", "True")?;
+    // 6. IsAnsiNullsOn / IsQuotedIdentifierOn - the project's SET options
+    write_set_options(writer, model.ansi_nulls, model.quoted_identifier)?;
+
+    // 7. IsSchemaBound (only if WITH SCHEMABINDING)
+    if trigger.is_schema_bound {
+        write_property(writer, "IsSchemaBound", "True")?;
+    }
+
+    // 8. IsEnabled (only if disabled by DISABLE TRIGGER)
+    if !trigger.is_enabled {
+        write_property(writer, "IsEnabled", "False")?;
+    }
 
     // Write BodyDependencies relationship (before Parent)
-    let parent_ref = format!("[{}].[{}]", trigger.parent_schema, trigger.parent_name);
+    let parent_ref = format!(
+        "[{}].[{}]",
+        escape_identifier(&trigger.parent_schema),
+        escape_identifier(&trigger.parent_name)
+    );
     let body_deps = extract_trigger_body_dependencies(&body_script, &parent_ref);
     write_body_dependencies(writer, &body_deps)?;
 
@@ -3275,33 +3630,46 @@ fn write_trigger<W: Write>(writer: &mut Writer<W>, trigger: &TriggerElement) ->
 
     // Note: DotNet does NOT emit a Schema relationship for triggers
 
+    // SysCommentsObjectAnnotation with the header up to AS (omitted in relaxed mode)
+    if model.output_mode == OutputMode::Parity {
+        if let Some(body_start) = find_trigger_body_start(&trigger.definition) {
+            write_sys_comments_annotation(
+                writer,
+                &trigger.definition,
+                &trigger.definition[..body_start],
+                "",
+                source,
+            )?;
+        }
+    }
+
     writer.write_event(Event::End(BytesEnd::new("Element")))?;
     Ok(())
 }
 
 /// Extract the trigger body (everything after AS keyword) from the full trigger definition
-/// Uses token-based parsing (Phase 15.8 J4/J5) to handle any whitespace around keywords
 fn extract_trigger_body(definition: &str) -> String {
+    match find_trigger_body_start(definition) {
+        Some(body_start) => definition[body_start..].trim_end().to_string(),
+        // Fallback: return the original definition if we can't find the pattern
+        None => definition.to_string(),
+    }
+}
+
+/// Byte offset just past the AS that starts a trigger's body, if found
+/// Uses token-based parsing (Phase 15.8 J4/J5) to handle any whitespace around keywords
+fn find_trigger_body_start(definition: &str) -> Option<usize> {
     // The pattern is: CREATE TRIGGER ... ON ... (FOR|AFTER|INSTEAD OF) ... AS <body>
     // We need to find the AS keyword that comes after FOR/AFTER/INSTEAD OF
-
-    // Tokenize the definition using sqlparser
-    let dialect = MsSqlDialect {};
-    let tokens = match Tokenizer::new(&dialect, definition).tokenize() {
-        Ok(t) => t,
-        Err(_) => {
-            // Fallback: return the original definition if tokenization fails
-            return definition.to_string();
-        }
-    };
+    let tokens = tokenize_with_location(definition).ok()?;
 
     // Find the position of FOR/AFTER keyword (or INSTEAD OF pair)
     // Then find the first AS keyword at top level after that position
     let mut found_trigger_action = false;
     let mut paren_depth: i32 = 0;
 
-    for (i, token) in tokens.iter().enumerate() {
-        match token {
+    for token in &tokens {
+        match &token.token {
             Token::LParen => paren_depth += 1,
             Token::RParen => paren_depth = paren_depth.saturating_sub(1),
             // Look for trigger action keywords: FOR, AFTER, or INSTEAD (followed by OF)
@@ -3317,118 +3685,186 @@ fn extract_trigger_body(definition: &str) -> String {
             Token::Word(w)
                 if w.keyword == Keyword::AS && paren_depth == 0 && found_trigger_action =>
             {
-                // Found the AS keyword - return everything after it
-                return reconstruct_tokens(&tokens[i + 1..]);
+                return Some(location_to_offset(definition, token.span.end));
             }
             _ => {}
         }
     }
-
-    // Fallback: return the original definition if we can't find the pattern
-    definition.to_string()
+    None
 }
 
 /// Extract body dependencies from a trigger body
 /// This handles the special "inserted" and "deleted" magic tables by resolving
 /// column references from them to the parent table/view.
 ///
-/// The dependencies are extracted in order of appearance and include:
+/// The body is processed one statement at a time, and dependencies are extracted in
+/// order of appearance:
 /// - Table references like [dbo].[Products]
 /// - Column references like [dbo].[Products].[Id]
 /// - Columns from INSERT column lists
 /// - Columns from SELECT/UPDATE referencing inserted/deleted resolved to parent
+///
+/// INSERT ... SELECT FROM inserted/deleted and UPDATE ... FROM ... JOIN inserted/deleted
+/// statements keep their DotNet-specific ordering and duplicates; any other references
+/// (extra joins, WHERE clauses, EXISTS subqueries, DELETE and MERGE statements) are then
+/// added once each by a generic scan of the statement.
 fn extract_trigger_body_dependencies(body: &str, parent_ref: &str) -> Vec<BodyDependency> {
     use std::collections::HashSet;
     let mut deps = Vec::new();
     let mut seen: HashSet<String> = HashSet::new();
 
-    // Track table aliases: maps alias (lowercase) -> table reference
-    // For triggers, "inserted" and "deleted" map to the parent table/view
-    let mut table_aliases: std::collections::HashMap<String, String> =
-        std::collections::HashMap::new();
-    table_aliases.insert("inserted".to_string(), parent_ref.to_string());
-    table_aliases.insert("deleted".to_string(), parent_ref.to_string());
-
-    // First pass: find all table aliases using token-based parsing (Phase 20.4.2)
-    // Pattern: FROM [schema].[table] alias or JOIN [schema].[table] alias
-    // Uses TableAliasTokenParser which handles whitespace, comments, and nested queries correctly
-    if let Some(mut parser) = TableAliasTokenParser::new(body) {
-        for (alias_or_name, table_ref) in parser.extract_aliases_with_table_names() {
-            let alias_lower = alias_or_name.to_lowercase();
-            // Don't overwrite inserted/deleted mappings
-            if alias_lower != "inserted" && alias_lower != "deleted" {
+    for statement in split_trigger_statements(body) {
+        // Track table aliases: maps alias (lowercase) -> table reference
+        // For triggers, "inserted" and "deleted" map to the parent table/view
+        let mut table_aliases: HashMap<String, String> = HashMap::new();
+        table_aliases.insert("inserted".to_string(), parent_ref.to_string());
+        table_aliases.insert("deleted".to_string(), parent_ref.to_string());
+        // Unqualified table names from FROM/JOIN, for unqualified table references
+        let mut table_names: HashMap<String, String> = HashMap::new();
+
+        // Find the statement's table aliases using token-based parsing (Phase 20.4.2)
+        // Pattern: FROM [schema].[table] alias or JOIN [schema].[table] alias
+        // Uses TableAliasTokenParser which handles whitespace, comments, and nested queries correctly
+        if let Some(mut parser) = TableAliasTokenParser::new(&statement) {
+            for (alias_or_name, table_ref) in parser.extract_aliases_with_table_names() {
+                let alias_lower = alias_or_name.to_lowercase();
+                let table_lower = table_ref.to_lowercase();
+                if alias_lower == "inserted" || alias_lower == "deleted" {
+                    continue;
+                }
+                // Aliases of inserted/deleted (FROM inserted i) resolve to the parent
+                if table_lower.ends_with(".[inserted]") || table_lower.ends_with(".[deleted]") {
+                    table_aliases.insert(alias_lower, parent_ref.to_string());
+                    continue;
+                }
+                if table_lower.ends_with(&format!(".[{}]", escape_identifier(&alias_lower))) {
+                    table_names.insert(alias_lower.clone(), table_ref.clone());
+                }
                 table_aliases.insert(alias_lower, table_ref);
             }
         }
-    }
 
-    // Process INSERT statements with SELECT FROM inserted/deleted
-    // Uses tokenized parsing (Phase 20.4.6) instead of INSERT_SELECT_RE and INSERT_SELECT_JOIN_RE regex
-    if let Some(mut parser) = InsertSelectTokenParser::new(body) {
-        for stmt in parser.extract_statements() {
-            let table_ref = format!("[{}].[{}]", stmt.schema, stmt.table);
+        let mut specialized = false;
+
+        // Process INSERT statements with SELECT FROM inserted/deleted
+        // Uses tokenized parsing (Phase 20.4.6) instead of INSERT_SELECT_RE and INSERT_SELECT_JOIN_RE regex
+        if let Some(mut parser) = InsertSelectTokenParser::new(&statement) {
+            for stmt in parser.extract_statements() {
+                specialized = true;
+                let table_ref = format!(
+                    "[{}].[{}]",
+                    escape_identifier(&stmt.schema),
+                    escape_identifier(&stmt.table)
+                );
 
-            if stmt.has_join {
-                // JOIN case: INSERT INTO ... SELECT ... FROM inserted alias JOIN deleted alias ON ...
+                if stmt.has_join {
+                    // JOIN case: INSERT INTO ... SELECT ... FROM inserted alias JOIN deleted alias ON ...
 
-                // Skip if already processed
-                if seen.contains(&table_ref) {
-                    continue;
-                }
+                    // Skip if already processed
+                    if seen.contains(&table_ref) {
+                        continue;
+                    }
 
-                // Emit table reference first
-                seen.insert(table_ref.clone());
-                deps.push(BodyDependency::ObjectRef(table_ref.clone()));
+                    // Emit table reference first
+                    seen.insert(table_ref.clone());
+                    deps.push(BodyDependency::ObjectRef(table_ref.clone()));
 
-                // Emit each column reference from the INSERT column list (no dedup - DotNet preserves order)
-                for col in extract_single_bracketed_identifiers(&stmt.column_list) {
-                    let col_ref = format!("{}.[{}]", table_ref, col);
-                    deps.push(BodyDependency::ObjectRef(col_ref));
-                }
+                    // Emit each column reference from the INSERT column list (no dedup - DotNet preserves order)
+                    for col in extract_single_bracketed_identifiers(&stmt.column_list) {
+                        let col_ref = format!("{}.[{}]", table_ref, escape_identifier(&col));
+                        deps.push(BodyDependency::ObjectRef(col_ref));
+                    }
 
-                // Add aliases for the JOIN tables (both map to parent)
-                if let Some(ref alias1) = stmt.alias1 {
-                    table_aliases.insert(alias1.to_lowercase(), parent_ref.to_string());
-                }
-                if let Some(ref alias2) = stmt.alias2 {
-                    table_aliases.insert(alias2.to_lowercase(), parent_ref.to_string());
-                }
+                    // Add aliases for the JOIN tables (both map to parent)
+                    if let Some(ref alias1) = stmt.alias1 {
+                        table_aliases.insert(alias1.to_lowercase(), parent_ref.to_string());
+                    }
+                    if let Some(ref alias2) = stmt.alias2 {
+                        table_aliases.insert(alias2.to_lowercase(), parent_ref.to_string());
+                    }
+
+                    // DotNet processes ON clause first, then SELECT columns (skipping duplicates)
+                    let mut emitted: std::collections::HashSet<(String, String)> =
+                        std::collections::HashSet::new();
 
-                // DotNet processes ON clause first, then SELECT columns (skipping duplicates)
-                let mut emitted: std::collections::HashSet<(String, String)> =
-                    std::collections::HashSet::new();
+                    // 1. Emit column references from ON clause first (no dedup within ON)
+                    if let Some(ref on_clause) = stmt.on_clause {
+                        for (alias, col) in extract_alias_column_refs_tokenized(on_clause) {
+                            let alias_lower = alias.to_lowercase();
 
-                // 1. Emit column references from ON clause first (no dedup within ON)
-                if let Some(ref on_clause) = stmt.on_clause {
-                    for (alias, col) in extract_alias_column_refs_tokenized(on_clause) {
+                            if let Some(resolved_table) = table_aliases.get(&alias_lower) {
+                                let col_ref =
+                                    format!("{}.[{}]", resolved_table, escape_identifier(&col));
+                                emitted.insert((alias_lower.clone(), col.to_lowercase()));
+                                deps.push(BodyDependency::ObjectRef(col_ref));
+                            }
+                        }
+                    }
+
+                    // 2. Emit column references from SELECT clause (skip if already in ON clause with same alias)
+                    for (alias, col) in extract_alias_column_refs_tokenized(&stmt.select_expr) {
                         let alias_lower = alias.to_lowercase();
+                        let key = (alias_lower.clone(), col.to_lowercase());
 
+                        // Skip if this exact alias.column was already emitted from ON clause
+                        if emitted.contains(&key) {
+                            continue;
+                        }
+
+                        // Resolve alias to table reference
                         if let Some(resolved_table) = table_aliases.get(&alias_lower) {
-                            let col_ref = format!("{}.[{}]", resolved_table, col);
-                            emitted.insert((alias_lower.clone(), col.to_lowercase()));
+                            let col_ref =
+                                format!("{}.[{}]", resolved_table, escape_identifier(&col));
                             deps.push(BodyDependency::ObjectRef(col_ref));
                         }
                     }
-                }
+                } else {
+                    // Simple case: INSERT INTO ... SELECT ... FROM inserted|deleted;
 
-                // 2. Emit column references from SELECT clause (skip if already in ON clause with same alias)
-                for (alias, col) in extract_alias_column_refs_tokenized(&stmt.select_expr) {
-                    let alias_lower = alias.to_lowercase();
-                    let key = (alias_lower.clone(), col.to_lowercase());
+                    // Emit table reference first
+                    if !seen.contains(&table_ref) {
+                        seen.insert(table_ref.clone());
+                        deps.push(BodyDependency::ObjectRef(table_ref.clone()));
+                    }
 
-                    // Skip if this exact alias.column was already emitted from ON clause
-                    if emitted.contains(&key) {
-                        continue;
+                    // Emit each column reference from the INSERT column list
+                    for col in extract_single_bracketed_identifiers(&stmt.column_list) {
+                        let col_ref = format!("{}.[{}]", table_ref, escape_identifier(&col));
+                        if !seen.contains(&col_ref) {
+                            seen.insert(col_ref.clone());
+                            deps.push(BodyDependency::ObjectRef(col_ref));
+                        }
                     }
 
-                    // Resolve alias to table reference
-                    if let Some(resolved_table) = table_aliases.get(&alias_lower) {
-                        let col_ref = format!("{}.[{}]", resolved_table, col);
-                        deps.push(BodyDependency::ObjectRef(col_ref));
+                    // Emit column references from SELECT clause - these come from inserted/deleted (parent)
+                    for col in extract_single_bracketed_identifiers(&stmt.select_expr) {
+                        // These columns come from inserted/deleted, resolve to parent
+                        let col_ref = format!("{}.[{}]", parent_ref, escape_identifier(&col));
+                        // Deduplicate - DotNet doesn't emit the same column twice from inserted/deleted
+                        if !seen.contains(&col_ref) {
+                            seen.insert(col_ref.clone());
+                            deps.push(BodyDependency::ObjectRef(col_ref));
+                        }
                     }
                 }
-            } else {
-                // Simple case: INSERT INTO ... SELECT ... FROM inserted|deleted;
+            }
+        }
+
+        // Process UPDATE with alias pattern: UPDATE alias SET ... FROM [schema].[table] alias JOIN inserted/deleted ON ...
+        // Uses tokenized parsing (Phase 20.4.7) instead of UPDATE_ALIAS_RE regex
+        if let Some(mut parser) = UpdateTokenParser::new(&statement) {
+            for stmt in parser.extract_statements() {
+                specialized = true;
+                let table_ref = format!(
+                    "[{}].[{}]",
+                    escape_identifier(&stmt.schema),
+                    escape_identifier(&stmt.table)
+                );
+
+                // Add aliases
+                table_aliases.insert(stmt.update_alias.to_lowercase(), table_ref.clone());
+                table_aliases.insert(stmt.table_alias.to_lowercase(), table_ref.clone());
+                table_aliases.insert(stmt.magic_alias.to_lowercase(), parent_ref.to_string());
 
                 // Emit table reference first
                 if !seen.contains(&table_ref) {
@@ -3436,73 +3872,246 @@ fn extract_trigger_body_dependencies(body: &str, parent_ref: &str) -> Vec<BodyDe
                     deps.push(BodyDependency::ObjectRef(table_ref.clone()));
                 }
 
-                // Emit each column reference from the INSERT column list
-                for col in extract_single_bracketed_identifiers(&stmt.column_list) {
-                    let col_ref = format!("{}.[{}]", table_ref, col);
-                    if !seen.contains(&col_ref) {
-                        seen.insert(col_ref.clone());
+                // Process ON clause FIRST - extract alias.[col] patterns (these can be duplicated)
+                // Use tokenized extraction instead of ALIAS_COL_RE regex
+                for (alias, col) in extract_alias_column_refs_tokenized(&stmt.on_clause) {
+                    let alias_lower = alias.to_lowercase();
+
+                    if let Some(resolved_table) = table_aliases.get(&alias_lower) {
+                        let col_ref = format!("{}.[{}]", resolved_table, escape_identifier(&col));
+                        // DotNet allows duplicates for columns in ON clause
                         deps.push(BodyDependency::ObjectRef(col_ref));
                     }
                 }
 
-                // Emit column references from SELECT clause - these come from inserted/deleted (parent)
-                for col in extract_single_bracketed_identifiers(&stmt.select_expr) {
-                    // These columns come from inserted/deleted, resolve to parent
-                    let col_ref = format!("{}.[{}]", parent_ref, col);
-                    // Deduplicate - DotNet doesn't emit the same column twice from inserted/deleted
-                    if !seen.contains(&col_ref) {
-                        seen.insert(col_ref.clone());
+                // Process SET clause - extract alias.[col] = patterns
+                // Use tokenized extraction instead of ALIAS_COL_RE regex
+                for (alias, col) in extract_alias_column_refs_tokenized(&stmt.set_clause) {
+                    let alias_lower = alias.to_lowercase();
+
+                    if let Some(resolved_table) = table_aliases.get(&alias_lower) {
+                        let col_ref = format!("{}.[{}]", resolved_table, escape_identifier(&col));
+                        // DotNet allows duplicates for SET clause columns too
                         deps.push(BodyDependency::ObjectRef(col_ref));
                     }
                 }
             }
         }
-    }
-
-    // Process UPDATE with alias pattern: UPDATE alias SET ... FROM [schema].[table] alias JOIN inserted/deleted ON ...
-    // Uses tokenized parsing (Phase 20.4.7) instead of UPDATE_ALIAS_RE regex
-    if let Some(mut parser) = UpdateTokenParser::new(body) {
-        for stmt in parser.extract_statements() {
-            let table_ref = format!("[{}].[{}]", stmt.schema, stmt.table);
 
-            // Add aliases
-            table_aliases.insert(stmt.update_alias.to_lowercase(), table_ref.clone());
-            table_aliases.insert(stmt.table_alias.to_lowercase(), table_ref.clone());
-            table_aliases.insert(stmt.magic_alias.to_lowercase(), parent_ref.to_string());
-
-            // Emit table reference first
-            if !seen.contains(&table_ref) {
-                seen.insert(table_ref.clone());
-                deps.push(BodyDependency::ObjectRef(table_ref.clone()));
+        let mut emitted: HashSet<String> = deps
+            .iter()
+            .filter_map(|dep| match dep {
+                BodyDependency::ObjectRef(r) => Some(r.clone()),
+                _ => None,
+            })
+            .collect();
+        let mut emit = |dep_ref: String, deps: &mut Vec<BodyDependency>| {
+            if emitted.insert(dep_ref.clone()) {
+                seen.insert(dep_ref.clone());
+                deps.push(BodyDependency::ObjectRef(dep_ref));
             }
+        };
 
-            // Process ON clause FIRST - extract alias.[col] patterns (these can be duplicated)
-            // Use tokenized extraction instead of ALIAS_COL_RE regex
-            for (alias, col) in extract_alias_column_refs_tokenized(&stmt.on_clause) {
-                let alias_lower = alias.to_lowercase();
+        // INSERT target and column list the specialized parser did not handle
+        if !specialized {
+            if let Some((table_ref, columns)) = insert_target_columns(&statement) {
+                emit(table_ref.clone(), &mut deps);
+                for col in columns {
+                    emit(
+                        format!("{}.[{}]", table_ref, escape_identifier(&col)),
+                        &mut deps,
+                    );
+                }
+            }
+        }
 
-                if let Some(resolved_table) = table_aliases.get(&alias_lower) {
-                    let col_ref = format!("{}.[{}]", resolved_table, col);
-                    // DotNet allows duplicates for columns in ON clause
-                    deps.push(BodyDependency::ObjectRef(col_ref));
+        // Generic scan: table references and alias-qualified columns in order of appearance
+        let Some(mut scanner) = BodyDependencyTokenScanner::new(&statement) else {
+            continue;
+        };
+        for token in scanner.scan() {
+            match token {
+                BodyDepToken::TwoPartBracketed { first, second }
+                | BodyDepToken::TwoPartUnbracketed { first, second }
+                | BodyDepToken::AliasDotBracketedColumn {
+                    alias: first,
+                    column: second,
                 }
+                | BodyDepToken::BracketedAliasDotColumn {
+                    alias: first,
+                    column: second,
+                } => {
+                    if first.starts_with('@') || first.starts_with('#') {
+                        continue;
+                    }
+                    match table_aliases.get(&first.to_lowercase()) {
+                        Some(resolved_table) => emit(
+                            format!("{}.[{}]", resolved_table, escape_identifier(&second)),
+                            &mut deps,
+                        ),
+                        None if !is_sql_keyword(&first.to_uppercase()) => emit(
+                            format!(
+                                "[{}].[{}]",
+                                escape_identifier(&first),
+                                escape_identifier(&second)
+                            ),
+                            &mut deps,
+                        ),
+                        None => {}
+                    }
+                }
+                BodyDepToken::ThreePartBracketed {
+                    schema,
+                    table,
+                    column,
+                } => {
+                    if !schema.starts_with('@') {
+                        let table_ref = format!(
+                            "[{}].[{}]",
+                            escape_identifier(&schema),
+                            escape_identifier(&table)
+                        );
+                        emit(table_ref.clone(), &mut deps);
+                        emit(
+                            format!("{}.[{}]", table_ref, escape_identifier(&column)),
+                            &mut deps,
+                        );
+                    }
+                }
+                BodyDepToken::SingleBracketed(name) | BodyDepToken::SingleUnbracketed(name) => {
+                    if let Some(table_ref) = table_names.get(&name.to_lowercase()) {
+                        emit(table_ref.clone(), &mut deps);
+                    }
+                }
+                BodyDepToken::Parameter(_) => {}
             }
+        }
+    }
+
+    deps
+}
+
+/// Split a trigger body into statements for dependency extraction
+///
+/// Statements end at semicolons and at statement keywords outside parentheses, so an
+/// `IF EXISTS (...)` condition and the statement it guards are scanned separately and
+/// each statement's aliases stay local to it. `UPDATE(column)` and `CASE ... END` do
+/// not start or end a statement.
+fn split_trigger_statements(body: &str) -> Vec<String> {
+    let Ok(tokens) = tokenize(body) else {
+        return vec![body.to_string()];
+    };
 
-            // Process SET clause - extract alias.[col] = patterns
-            // Use tokenized extraction instead of ALIAS_COL_RE regex
-            for (alias, col) in extract_alias_column_refs_tokenized(&stmt.set_clause) {
-                let alias_lower = alias.to_lowercase();
+    let mut statements = Vec::new();
+    let mut current: Vec<Token> = Vec::new();
+    let mut paren_depth: i32 = 0;
+    let mut case_depth: i32 = 0;
+
+    let mut flush = |current: &mut Vec<Token>| {
+        let statement = reconstruct_tokens(current);
+        if !statement.trim().is_empty() {
+            statements.push(statement.trim_start().to_string());
+        }
+        current.clear();
+    };
 
-                if let Some(resolved_table) = table_aliases.get(&alias_lower) {
-                    let col_ref = format!("{}.[{}]", resolved_table, col);
-                    // DotNet allows duplicates for SET clause columns too
-                    deps.push(BodyDependency::ObjectRef(col_ref));
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::LParen => paren_depth += 1,
+            Token::RParen => paren_depth = (paren_depth - 1).max(0),
+            Token::SemiColon if paren_depth == 0 => {
+                flush(&mut current);
+                continue;
+            }
+            Token::Word(w) if w.quote_style.is_none() && w.keyword == Keyword::CASE => {
+                case_depth += 1;
+            }
+            Token::Word(w)
+                if w.quote_style.is_none() && w.keyword == Keyword::END && case_depth > 0 =>
+            {
+                case_depth -= 1;
+            }
+            Token::Word(w) if w.quote_style.is_none() && paren_depth == 0 => {
+                let starts_statement = match w.value.to_uppercase().as_str() {
+                    "INSERT" | "DELETE" | "MERGE" | "IF" | "ELSE" | "WHILE" | "BEGIN" | "END"
+                    | "DECLARE" | "RETURN" | "EXEC" | "EXECUTE" | "RAISERROR" | "THROW"
+                    | "PRINT" => true,
+                    // UPDATE(column) in an IF is a function, not a statement
+                    "UPDATE" => !tokens[i + 1..]
+                        .iter()
+                        .find(|t| !matches!(t, Token::Whitespace(_)))
+                        .is_some_and(|t| matches!(t, Token::LParen)),
+                    _ => false,
+                };
+                if starts_statement {
+                    flush(&mut current);
                 }
             }
+            _ => {}
         }
+        current.push(token.clone());
     }
+    flush(&mut current);
 
-    deps
+    statements
+}
+
+/// Target table and column list of an `INSERT [INTO] table (columns)` statement
+fn insert_target_columns(statement: &str) -> Option<(String, Vec<String>)> {
+    let tokens: Vec<Token> = tokenize(statement)
+        .ok()?
+        .into_iter()
+        .filter(|t| !matches!(t, Token::Whitespace(_)))
+        .collect();
+    let mut iter = tokens.iter().peekable();
+
+    if !matches!(iter.next(), Some(Token::Word(w)) if w.keyword == Keyword::INSERT) {
+        return None;
+    }
+    if matches!(iter.peek(), Some(Token::Word(w)) if w.keyword == Keyword::INTO) {
+        iter.next();
+    }
+
+    let mut name_parts = Vec::new();
+    loop {
+        match iter.next()? {
+            Token::Word(w) => name_parts.push(w.value.clone()),
+            _ => return None,
+        }
+        if matches!(iter.peek(), Some(Token::Period)) {
+            iter.next();
+        } else {
+            break;
+        }
+    }
+    let table_ref = match name_parts.as_slice() {
+        [table] if !table.starts_with('#') && !table.starts_with('@') => {
+            format!("[dbo].[{}]", escape_identifier(table))
+        }
+        [.., schema, table] => format!(
+            "[{}].[{}]",
+            escape_identifier(schema),
+            escape_identifier(table)
+        ),
+        _ => return None,
+    };
+
+    if !matches!(iter.next(), Some(Token::LParen)) {
+        return None;
+    }
+    let mut columns = Vec::new();
+    loop {
+        match iter.next()? {
+            Token::Word(w) => columns.push(w.value.clone()),
+            _ => return None,
+        }
+        match iter.next()? {
+            Token::Comma => continue,
+            Token::RParen => break,
+            _ => return None,
+        }
+    }
+    Some((table_ref, columns))
 }
 
 fn write_raw<W: Write>(
@@ -3511,13 +4120,18 @@ fn write_raw<W: Write>(
     model: &DatabaseModel,
     default_schema: &str,
     column_registry: &ColumnRegistry,
+    source: Option<&SourceLocation>,
 ) -> anyhow::Result<()> {
     // Handle SqlView specially to get full property/relationship support
     if raw.sql_type == "SqlView" {
-        return write_raw_view(writer, raw, model, default_schema, column_registry);
+        return write_raw_view(writer, raw, model, default_schema, column_registry, source);
     }
 
-    let full_name = format!("[{}].[{}]", raw.schema, raw.name);
+    let full_name = format!(
+        "[{}].[{}]",
+        escape_identifier(&raw.schema),
+        escape_identifier(&raw.name)
+    );
 
     // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
     let elem = BytesStart::new("Element").with_attributes([
@@ -5017,6 +5631,96 @@ FROM [dbo].[Account] A
         assert_eq!(idents[1].name, "UnitPrice");
     }
 
+    #[test]
+    fn test_computed_expression_function_dependencies() {
+        let deps = extract_computed_expression_columns(
+            "[dbo].[CalcTotal]([Quantity], [Price]) + dbo.Tax([Price]) + [Data].value('x', 'int')",
+            "dbo",
+            "Lines",
+            &DatabaseModel::default(),
+        );
+        assert_eq!(
+            deps,
+            vec![
+                "[dbo].[CalcTotal]",
+                "[dbo].[Lines].[Quantity]",
+                "[dbo].[Lines].[Price]",
+                "[dbo].[Tax]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_function_named_like_data_type_method() {
+        use crate::model::SchemaElement;
+
+        let mut model = DatabaseModel::default();
+        model.add_element(ModelElement::Schema(SchemaElement {
+            name: "Sales".to_string(),
+            authorization: None,
+        }));
+        let deps = extract_computed_expression_columns(
+            "dbo.Value([Amount]) + [Sales].[STATUS_LABEL]([Status]) + [Doc].exist('/a')",
+            "dbo",
+            "Orders",
+            &model,
+        );
+        assert_eq!(
+            deps,
+            vec![
+                "[dbo].[Value]",
+                "[dbo].[Orders].[Amount]",
+                "[Sales].[STATUS_LABEL]",
+                "[dbo].[Orders].[Status]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_expression_function_dependencies() {
+        let deps = extract_check_expression_columns(
+            "[dbo].[OverlappingBookings]([RoomId], [StartDate], [EndDate]) = 0",
+            "dbo",
+            "Bookings",
+            &DatabaseModel::default(),
+        );
+        assert_eq!(
+            deps,
+            vec![
+                "[dbo].[OverlappingBookings]",
+                "[dbo].[Bookings].[RoomId]",
+                "[dbo].[Bookings].[StartDate]",
+                "[dbo].[Bookings].[EndDate]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_default_expression_dependencies() {
+        assert_eq!(
+            extract_default_expression_dependencies(
+                "(NEXT VALUE FOR [Sales].[OrderSeq])",
+                &DatabaseModel::default(),
+                "dbo"
+            ),
+            vec!["[Sales].[OrderSeq]"]
+        );
+        assert_eq!(
+            extract_default_expression_dependencies(
+                "(CONCAT(N'ORD-', NEXT VALUE FOR OrderSeq, [dbo].[Suffix]()))",
+                &DatabaseModel::default(),
+                "Sales"
+            ),
+            vec!["[Sales].[OrderSeq]", "[dbo].[Suffix]"]
+        );
+        assert!(extract_default_expression_dependencies(
+            "(GETDATE())",
+            &DatabaseModel::default(),
+            "dbo"
+        )
+        .is_empty());
+    }
+
     #[test]
     fn test_bracketed_idents_empty_input() {
         let idents = extract_bracketed_identifiers_tokenized("");
@@ -7674,4 +8378,104 @@ WHEN MATCHED THEN
             deps
         );
     }
+
+    // =============================================================================
+    // Trigger body dependency tests
+    // =============================================================================
+
+    fn object_refs(deps: &[BodyDependency]) -> Vec<&str> {
+        deps.iter()
+            .filter_map(|d| match d {
+                BodyDependency::ObjectRef(r) => Some(r.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_trigger_deps_left_join_with_real_table() {
+        let body = r#"
+BEGIN
+    INSERT INTO dbo.OrderAudit (OrderId, OldStatus, NewStatus)
+    SELECT i.Id, d.Status, i.Status
+    FROM inserted i
+    LEFT JOIN deleted d ON d.Id = i.Id
+    JOIN dbo.Customers c ON c.Id = i.CustomerId
+    WHERE c.IsActive = 1;
+END
+"#;
+        let deps = extract_trigger_body_dependencies(body, "[dbo].[Orders]");
+        assert_eq!(
+            object_refs(&deps),
+            vec![
+                "[dbo].[OrderAudit]",
+                "[dbo].[OrderAudit].[OrderId]",
+                "[dbo].[OrderAudit].[OldStatus]",
+                "[dbo].[OrderAudit].[NewStatus]",
+                "[dbo].[Orders].[Id]",
+                "[dbo].[Orders].[Status]",
+                "[dbo].[Customers]",
+                "[dbo].[Customers].[Id]",
+                "[dbo].[Orders].[CustomerId]",
+                "[dbo].[Customers].[IsActive]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trigger_deps_exists_subquery() {
+        let body = r#"
+IF EXISTS (SELECT 1 FROM inserted i JOIN [dbo].[Customers] c ON c.[Id] = i.[CustomerId] WHERE c.[IsBlocked] = 1)
+BEGIN
+    RAISERROR('Blocked customer', 16, 1);
+    ROLLBACK TRANSACTION;
+END
+"#;
+        let deps = extract_trigger_body_dependencies(body, "[dbo].[Orders]");
+        assert_eq!(
+            object_refs(&deps),
+            vec![
+                "[dbo].[Customers]",
+                "[dbo].[Customers].[Id]",
+                "[dbo].[Orders].[CustomerId]",
+                "[dbo].[Customers].[IsBlocked]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trigger_deps_aliases_are_per_statement() {
+        // "x" is a different table in each statement
+        let body = r#"
+IF UPDATE(Status)
+    UPDATE x SET x.LastChanged = GETDATE() FROM dbo.OrderState x JOIN inserted i ON i.Id = x.OrderId;
+DELETE x FROM dbo.OrderCache x WHERE EXISTS (SELECT 1 FROM deleted d WHERE d.Id = x.OrderId);
+"#;
+        let deps = extract_trigger_body_dependencies(body, "[dbo].[Orders]");
+        assert_eq!(
+            object_refs(&deps),
+            vec![
+                "[dbo].[OrderState]",
+                "[dbo].[OrderState].[LastChanged]",
+                "[dbo].[Orders].[Id]",
+                "[dbo].[OrderState].[OrderId]",
+                "[dbo].[OrderCache]",
+                "[dbo].[OrderCache].[OrderId]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_trigger_statements() {
+        let body = "IF UPDATE(Status) BEGIN UPDATE t SET t.A = CASE WHEN i.B = 1 THEN 1 END FROM dbo.T t JOIN inserted i ON i.Id = t.Id END";
+        assert_eq!(
+            split_trigger_statements(body),
+            vec![
+                "IF UPDATE(Status)",
+                "BEGIN",
+                "UPDATE t SET t.A = CASE WHEN i.B = 1 THEN 1 END FROM dbo.T t JOIN inserted i ON i.Id = t.Id",
+                "END",
+            ]
+        );
+    }
 }
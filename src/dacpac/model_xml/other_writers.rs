@@ -13,6 +13,7 @@ use crate::model::{
     PartitionSchemeElement, PermissionElement, RoleElement, RoleMembershipElement, SequenceElement,
     SynonymElement, UserElement,
 };
+use crate::parser::identifier_utils::escape_identifier;
 
 use super::body_deps::BodyDependency;
 use super::xml_helpers::{
@@ -28,7 +29,9 @@ pub(crate) fn write_index<W: Write>(
 ) -> anyhow::Result<()> {
     let full_name = format!(
         "[{}].[{}].[{}]",
-        index.table_schema, index.table_name, index.name
+        escape_identifier(&index.table_schema),
+        escape_identifier(&index.table_name),
+        escape_identifier(&index.name)
     );
 
     // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
@@ -60,7 +63,11 @@ pub(crate) fn write_index<W: Write>(
     }
 
     // Reference to table
-    let table_ref = format!("[{}].[{}]", index.table_schema, index.table_name);
+    let table_ref = format!(
+        "[{}].[{}]",
+        escape_identifier(&index.table_schema),
+        escape_identifier(&index.table_name)
+    );
 
     // Write BodyDependencies for filtered indexes (column references from filter predicate)
     // DotNet emits this before ColumnSpecifications
@@ -81,16 +88,18 @@ pub(crate) fn write_index<W: Write>(
     }
 
     // Write DataCompressionOptions relationship if index has compression
-    if let Some(ref compression) = index.data_compression {
-        write_data_compression_options(writer, compression)?;
-    }
+    write_data_compression_options(
+        writer,
+        index.data_compression.as_ref(),
+        &index.partition_compression,
+    )?;
 
     // Write IncludedColumns relationship if present
     if !index.include_columns.is_empty() {
         let include_refs: Vec<String> = index
             .include_columns
             .iter()
-            .map(|col| format!("{}.[{}]", table_ref, col))
+            .map(|col| format!("{}.[{}]", table_ref, escape_identifier(col)))
             .collect();
         let include_refs: Vec<&str> = include_refs.iter().map(|s| s.as_str()).collect();
         write_relationship(writer, "IncludedColumns", &include_refs)?;
@@ -128,7 +137,7 @@ fn write_index_column_specifications<W: Write>(
         }
 
         // Reference to the column
-        let col_ref = format!("{}.[{}]", table_ref, col.name);
+        let col_ref = format!("{}.[{}]", table_ref, escape_identifier(&col.name));
         write_relationship(writer, "Column", &[&col_ref])?;
 
         writer.write_event(Event::End(BytesEnd::new("Element")))?;
@@ -139,33 +148,56 @@ fn write_index_column_specifications<W: Write>(
     Ok(())
 }
 
-/// Write DataCompressionOptions relationship for indexes with data compression
-fn write_data_compression_options<W: Write>(
+/// Write DataCompressionOptions relationship for indexes and constraints with data compression.
+///
+/// Per-partition settings produce one SqlDataCompressionOption per partition. A `compression`
+/// for the whole index applies to partition 1 when there are none; alongside them it is
+/// written first without a PartitionNumber, covering the partitions not listed. Writes
+/// nothing if neither is set.
+pub(crate) fn write_data_compression_options<W: Write>(
     writer: &mut Writer<W>,
-    compression: &DataCompressionType,
+    compression: Option<&DataCompressionType>,
+    partitions: &[(u32, DataCompressionType)],
 ) -> anyhow::Result<()> {
+    let mut options: Vec<(Option<u32>, &DataCompressionType)> = partitions
+        .iter()
+        .map(|(partition, compression)| (Some(*partition), compression))
+        .collect();
+    options.sort_by_key(|(partition, _)| *partition);
+    options.dedup_by_key(|(partition, _)| *partition);
+    if let Some(compression) = compression {
+        let partition = if options.is_empty() { Some(1) } else { None };
+        options.insert(0, (partition, compression));
+    }
+    if options.is_empty() {
+        return Ok(());
+    }
+
     // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
     let rel = BytesStart::new("Relationship").with_attributes([("Name", "DataCompressionOptions")]);
     writer.write_event(Event::Start(rel))?;
 
-    writer.write_event(Event::Start(BytesStart::new("Entry")))?;
+    for (partition, compression) in options {
+        writer.write_event(Event::Start(BytesStart::new("Entry")))?;
 
-    // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
-    let elem = BytesStart::new("Element").with_attributes([("Type", "SqlDataCompressionOption")]);
-    writer.write_event(Event::Start(elem))?;
+        // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
+        let elem =
+            BytesStart::new("Element").with_attributes([("Type", "SqlDataCompressionOption")]);
+        writer.write_event(Event::Start(elem))?;
 
-    // Write CompressionLevel property
-    write_property(
-        writer,
-        "CompressionLevel",
-        &compression.compression_level().to_string(),
-    )?;
+        write_property(
+            writer,
+            "CompressionLevel",
+            &compression.compression_level().to_string(),
+        )?;
+        if let Some(partition) = partition {
+            write_property(writer, "PartitionNumber", &partition.to_string())?;
+        }
 
-    // Write PartitionNumber property (always 1 for single-partition indexes)
-    write_property(writer, "PartitionNumber", "1")?;
+        writer.write_event(Event::End(BytesEnd::new("Element")))?;
+        writer.write_event(Event::End(BytesEnd::new("Entry")))?;
+    }
 
-    writer.write_event(Event::End(BytesEnd::new("Element")))?;
-    writer.write_event(Event::End(BytesEnd::new("Entry")))?;
     writer.write_event(Event::End(BytesEnd::new("Relationship")))?;
     Ok(())
 }
@@ -176,7 +208,11 @@ pub(crate) fn write_fulltext_index<W: Write>(
     fulltext: &FullTextIndexElement,
 ) -> anyhow::Result<()> {
     // Full-text index name format: [schema].[table] (same as table name)
-    let full_name = format!("[{}].[{}]", fulltext.table_schema, fulltext.table_name);
+    let full_name = format!(
+        "[{}].[{}]",
+        escape_identifier(&fulltext.table_schema),
+        escape_identifier(&fulltext.table_name)
+    );
 
     // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
     // Conditional Disambiguator attribute requires separate handling
@@ -193,14 +229,30 @@ pub(crate) fn write_fulltext_index<W: Write>(
     };
     writer.write_event(Event::Start(elem))?;
 
+    // ChangeTracking property: AUTO is the default and is not emitted (MANUAL = 1, OFF = 2)
+    match fulltext.change_tracking.as_deref() {
+        Some("MANUAL") => write_property(writer, "ChangeTracking", "1")?,
+        Some("OFF") => write_property(writer, "ChangeTracking", "2")?,
+        _ => {}
+    }
+
+    // STOPLIST = OFF disables the stoplist; SYSTEM is the default
+    if fulltext.stoplist.as_deref() == Some("OFF") {
+        write_property(writer, "IsStopListOff", "True")?;
+    }
+
     // Reference to full-text catalog if specified
     if let Some(catalog) = &fulltext.catalog {
-        let catalog_ref = format!("[{}]", catalog);
+        let catalog_ref = format!("[{}]", escape_identifier(catalog));
         write_relationship(writer, "Catalog", &[&catalog_ref])?;
     }
 
     // Write Columns for full-text columns
-    let table_ref = format!("[{}].[{}]", fulltext.table_schema, fulltext.table_name);
+    let table_ref = format!(
+        "[{}].[{}]",
+        escape_identifier(&fulltext.table_schema),
+        escape_identifier(&fulltext.table_name)
+    );
     if !fulltext.columns.is_empty() {
         write_fulltext_column_specifications(writer, fulltext, &table_ref)?;
     }
@@ -210,9 +262,23 @@ pub(crate) fn write_fulltext_index<W: Write>(
 
     // Reference to the unique key index (KeyName)
     // Key reference format: [schema].[constraint_name]
-    let key_index_ref = format!("[{}].[{}]", fulltext.table_schema, fulltext.key_index);
+    let key_index_ref = format!(
+        "[{}].[{}]",
+        escape_identifier(&fulltext.table_schema),
+        escape_identifier(&fulltext.key_index)
+    );
     write_relationship(writer, "KeyName", &[&key_index_ref])?;
 
+    // Reference to a user-defined stoplist
+    if let Some(stoplist) = fulltext
+        .stoplist
+        .as_deref()
+        .filter(|s| *s != "OFF" && *s != "SYSTEM")
+    {
+        let stoplist_ref = format!("[{}]", escape_identifier(stoplist));
+        write_relationship(writer, "StopList", &[&stoplist_ref])?;
+    }
+
     writer.write_event(Event::End(BytesEnd::new("Element")))?;
     Ok(())
 }
@@ -242,9 +308,15 @@ fn write_fulltext_column_specifications<W: Write>(
         }
 
         // Reference to the column
-        let col_ref = format!("{}.[{}]", table_ref, col.name);
+        let col_ref = format!("{}.[{}]", table_ref, escape_identifier(&col.name));
         write_relationship(writer, "Column", &[&col_ref])?;
 
+        // Reference to the document type column for varbinary columns
+        if let Some(ref type_column) = col.type_column {
+            let type_col_ref = format!("{}.[{}]", table_ref, escape_identifier(type_column));
+            write_relationship(writer, "TypeColumn", &[&type_col_ref])?;
+        }
+
         writer.write_event(Event::End(BytesEnd::new("Element")))?;
         writer.write_event(Event::End(BytesEnd::new("Entry")))?;
     }
@@ -258,7 +330,7 @@ pub(crate) fn write_fulltext_catalog<W: Write>(
     writer: &mut Writer<W>,
     catalog: &FullTextCatalogElement,
 ) -> anyhow::Result<()> {
-    let full_name = format!("[{}]", catalog.name);
+    let full_name = format!("[{}]", escape_identifier(&catalog.name));
 
     // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
     let elem = BytesStart::new("Element")
@@ -282,7 +354,11 @@ pub(crate) fn write_sequence<W: Write>(
     writer: &mut Writer<W>,
     seq: &SequenceElement,
 ) -> anyhow::Result<()> {
-    let full_name = format!("[{}].[{}]", seq.schema, seq.name);
+    let full_name = format!(
+        "[{}].[{}]",
+        escape_identifier(&seq.schema),
+        escape_identifier(&seq.name)
+    );
 
     // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
     let elem = BytesStart::new("Element")
@@ -336,7 +412,7 @@ pub(crate) fn write_sequence<W: Write>(
 
     // TypeSpecifier relationship for data type
     if let Some(ref data_type) = seq.data_type {
-        let type_name = format!("[{}]", data_type.to_lowercase());
+        let type_name = format!("[{}]", escape_identifier(&data_type.to_lowercase()));
         write_type_specifier_builtin(writer, &type_name)?;
     }
 
@@ -399,7 +475,7 @@ pub(crate) fn write_filegroup<W: Write>(
     writer: &mut Writer<W>,
     filegroup: &FilegroupElement,
 ) -> anyhow::Result<()> {
-    let full_name = format!("[{}]", filegroup.name);
+    let full_name = format!("[{}]", escape_identifier(&filegroup.name));
 
     let elem = BytesStart::new("Element")
         .with_attributes([("Type", "SqlFilegroup"), ("Name", full_name.as_str())]);
@@ -433,7 +509,7 @@ pub(crate) fn write_partition_function<W: Write>(
     writer: &mut Writer<W>,
     partition_func: &PartitionFunctionElement,
 ) -> anyhow::Result<()> {
-    let full_name = format!("[{}]", partition_func.name);
+    let full_name = format!("[{}]", escape_identifier(&partition_func.name));
 
     let elem = BytesStart::new("Element").with_attributes([
         ("Type", "SqlPartitionFunction"),
@@ -475,7 +551,10 @@ pub(crate) fn write_partition_function<W: Write>(
     }
 
     // Write ParameterType relationship for the data type
-    let type_name = format!("[{}]", partition_func.data_type.to_lowercase());
+    let type_name = format!(
+        "[{}]",
+        escape_identifier(&partition_func.data_type.to_lowercase())
+    );
     write_type_specifier_builtin(writer, &type_name)?;
 
     writer.write_event(Event::End(BytesEnd::new("Element")))?;
@@ -500,7 +579,7 @@ pub(crate) fn write_partition_scheme<W: Write>(
     writer: &mut Writer<W>,
     partition_scheme: &PartitionSchemeElement,
 ) -> anyhow::Result<()> {
-    let full_name = format!("[{}]", partition_scheme.name);
+    let full_name = format!("[{}]", escape_identifier(&partition_scheme.name));
 
     let elem = BytesStart::new("Element")
         .with_attributes([("Type", "SqlPartitionScheme"), ("Name", full_name.as_str())]);
@@ -511,14 +590,17 @@ pub(crate) fn write_partition_scheme<W: Write>(
         let fg_refs: Vec<String> = partition_scheme
             .filegroups
             .iter()
-            .map(|fg| format!("[{}]", fg))
+            .map(|fg| format!("[{}]", escape_identifier(fg)))
             .collect();
         let fg_refs: Vec<&str> = fg_refs.iter().map(|s| s.as_str()).collect();
         write_relationship(writer, "FileGroups", &fg_refs)?;
     }
 
     // Write PartitionFunction relationship
-    let pf_ref = format!("[{}]", partition_scheme.partition_function);
+    let pf_ref = format!(
+        "[{}]",
+        escape_identifier(&partition_scheme.partition_function)
+    );
     write_relationship(writer, "PartitionFunction", &[&pf_ref])?;
 
     writer.write_event(Event::End(BytesEnd::new("Element")))?;
@@ -548,7 +630,11 @@ pub(crate) fn write_synonym<W: Write>(
     writer: &mut Writer<W>,
     synonym: &SynonymElement,
 ) -> anyhow::Result<()> {
-    let full_name = format!("[{}].[{}]", synonym.schema, synonym.name);
+    let full_name = format!(
+        "[{}].[{}]",
+        escape_identifier(&synonym.schema),
+        escape_identifier(&synonym.name)
+    );
 
     let elem = BytesStart::new("Element")
         .with_attributes([("Type", "SqlSynonym"), ("Name", full_name.as_str())]);
@@ -572,7 +658,11 @@ pub(crate) fn write_synonym<W: Write>(
         writer.write_event(Event::Empty(refs))?;
     } else {
         // Local reference: just [target_schema].[target_name]
-        let target_ref = format!("[{}].[{}]", synonym.target_schema, synonym.target_name);
+        let target_ref = format!(
+            "[{}].[{}]",
+            escape_identifier(&synonym.target_schema),
+            escape_identifier(&synonym.target_name)
+        );
         let refs = BytesStart::new("References").with_attributes([("Name", target_ref.as_str())]);
         writer.write_event(Event::Empty(refs))?;
     }
@@ -595,17 +685,26 @@ fn build_synonym_target_ref(synonym: &SynonymElement) -> String {
         (Some(server), Some(database)) => {
             format!(
                 "[{}].[{}].[{}].[{}]",
-                server, database, synonym.target_schema, synonym.target_name
+                escape_identifier(server),
+                escape_identifier(database),
+                escape_identifier(&synonym.target_schema),
+                escape_identifier(&synonym.target_name)
             )
         }
         (None, Some(database)) => {
             format!(
                 "[{}].[{}].[{}]",
-                database, synonym.target_schema, synonym.target_name
+                escape_identifier(database),
+                escape_identifier(&synonym.target_schema),
+                escape_identifier(&synonym.target_name)
             )
         }
         _ => {
-            format!("[{}].[{}]", synonym.target_schema, synonym.target_name)
+            format!(
+                "[{}].[{}]",
+                escape_identifier(&synonym.target_schema),
+                escape_identifier(&synonym.target_name)
+            )
         }
     }
 }
@@ -615,7 +714,7 @@ pub(crate) fn write_user<W: Write>(
     writer: &mut Writer<W>,
     user: &UserElement,
 ) -> anyhow::Result<()> {
-    let full_name = format!("[{}]", user.name);
+    let full_name = format!("[{}]", escape_identifier(&user.name));
 
     let elem = BytesStart::new("Element")
         .with_attributes([("Type", "SqlUser"), ("Name", full_name.as_str())]);
@@ -632,13 +731,13 @@ pub(crate) fn write_user<W: Write>(
 
     // DefaultSchema relationship
     if let Some(ref schema) = user.default_schema {
-        let schema_ref = format!("[{}]", schema);
+        let schema_ref = format!("[{}]", escape_identifier(schema));
         write_relationship(writer, "DefaultSchema", &[schema_ref.as_str()])?;
     }
 
     // Login relationship
     if let Some(ref login) = user.login {
-        let login_ref = format!("[{}]", login);
+        let login_ref = format!("[{}]", escape_identifier(login));
         let rel = BytesStart::new("Relationship").with_attributes([("Name", "Login")]);
         writer.write_event(Event::Start(rel))?;
         writer.write_event(Event::Start(BytesStart::new("Entry")))?;
@@ -658,7 +757,7 @@ pub(crate) fn write_role<W: Write>(
     writer: &mut Writer<W>,
     role: &RoleElement,
 ) -> anyhow::Result<()> {
-    let full_name = format!("[{}]", role.name);
+    let full_name = format!("[{}]", escape_identifier(&role.name));
 
     let elem = BytesStart::new("Element")
         .with_attributes([("Type", "SqlRole"), ("Name", full_name.as_str())]);
@@ -666,7 +765,7 @@ pub(crate) fn write_role<W: Write>(
 
     // Authorization relationship (owner)
     if let Some(ref owner) = role.owner {
-        let owner_ref = format!("[{}]", owner);
+        let owner_ref = format!("[{}]", escape_identifier(owner));
         write_relationship(writer, "Authorizer", &[owner_ref.as_str()])?;
     }
 
@@ -699,12 +798,16 @@ pub(crate) fn write_permission<W: Write>(
         "Object" => {
             let schema = perm.target_schema.as_deref().unwrap_or("dbo");
             let name = perm.target_name.as_deref().unwrap_or("");
-            let obj_ref = format!("[{}].[{}]", schema, name);
+            let obj_ref = format!(
+                "[{}].[{}]",
+                escape_identifier(schema),
+                escape_identifier(name)
+            );
             write_relationship(writer, "SecuredObject", &[obj_ref.as_str()])?;
         }
         "Schema" => {
             let schema_name = perm.target_schema.as_deref().unwrap_or("");
-            let schema_ref = format!("[{}]", schema_name);
+            let schema_ref = format!("[{}]", escape_identifier(schema_name));
             write_relationship(writer, "SecuredObject", &[schema_ref.as_str()])?;
         }
         _ => {
@@ -713,7 +816,7 @@ pub(crate) fn write_permission<W: Write>(
     }
 
     // Grantee relationship (the principal receiving the permission)
-    let principal_ref = format!("[{}]", perm.principal);
+    let principal_ref = format!("[{}]", escape_identifier(&perm.principal));
     write_relationship(writer, "Grantee", &[principal_ref.as_str()])?;
 
     writer.write_event(Event::End(BytesEnd::new("Element")))?;
@@ -732,11 +835,11 @@ pub(crate) fn write_role_membership<W: Write>(
     writer.write_event(Event::Start(elem))?;
 
     // Role relationship
-    let role_ref = format!("[{}]", rm.role);
+    let role_ref = format!("[{}]", escape_identifier(&rm.role));
     write_relationship(writer, "Role", &[role_ref.as_str()])?;
 
     // Member relationship
-    let member_ref = format!("[{}]", rm.member);
+    let member_ref = format!("[{}]", escape_identifier(&rm.member));
     write_relationship(writer, "Member", &[member_ref.as_str()])?;
 
     writer.write_event(Event::End(BytesEnd::new("Element")))?;
@@ -750,7 +853,9 @@ pub(crate) fn write_columnstore_index<W: Write>(
 ) -> anyhow::Result<()> {
     let full_name = format!(
         "[{}].[{}].[{}]",
-        index.table_schema, index.table_name, index.name
+        escape_identifier(&index.table_schema),
+        escape_identifier(&index.table_name),
+        escape_identifier(&index.name)
     );
 
     let elem = BytesStart::new("Element").with_attributes([
@@ -771,7 +876,11 @@ pub(crate) fn write_columnstore_index<W: Write>(
 
     // Write ColumnSpecifications for nonclustered columnstore index columns
     if !index.columns.is_empty() {
-        let table_ref = format!("[{}].[{}]", index.table_schema, index.table_name);
+        let table_ref = format!(
+            "[{}].[{}]",
+            escape_identifier(&index.table_schema),
+            escape_identifier(&index.table_name)
+        );
 
         let rel =
             BytesStart::new("Relationship").with_attributes([("Name", "ColumnSpecifications")]);
@@ -784,7 +893,7 @@ pub(crate) fn write_columnstore_index<W: Write>(
                 .with_attributes([("Type", "SqlIndexedColumnSpecification")]);
             writer.write_event(Event::Start(elem))?;
 
-            let col_ref = format!("{}.[{}]", table_ref, col);
+            let col_ref = format!("{}.[{}]", table_ref, escape_identifier(col));
             write_relationship(writer, "Column", &[&col_ref])?;
 
             writer.write_event(Event::End(BytesEnd::new("Element")))?;
@@ -795,12 +904,18 @@ pub(crate) fn write_columnstore_index<W: Write>(
     }
 
     // Write DataCompressionOptions if compression is specified
-    if let Some(ref compression) = index.data_compression {
-        write_data_compression_options(writer, compression)?;
-    }
+    write_data_compression_options(
+        writer,
+        index.data_compression.as_ref(),
+        &index.partition_compression,
+    )?;
 
     // IndexedObject relationship
-    let table_ref = format!("[{}].[{}]", index.table_schema, index.table_name);
+    let table_ref = format!(
+        "[{}].[{}]",
+        escape_identifier(&index.table_schema),
+        escape_identifier(&index.table_name)
+    );
     write_relationship(writer, "IndexedObject", &[&table_ref])?;
 
     writer.write_event(Event::End(BytesEnd::new("Element")))?;
@@ -828,6 +943,7 @@ mod tests {
             filter_predicate: None,
             fill_factor: None,
             data_compression: None,
+            partition_compression: Vec::new(),
             is_padded: false,
         };
 
@@ -857,6 +973,7 @@ mod tests {
             filter_predicate: None,
             fill_factor: None,
             data_compression: None,
+            partition_compression: Vec::new(),
             is_padded: false,
         };
 
@@ -883,6 +1000,7 @@ mod tests {
             filter_predicate: None,
             fill_factor: None,
             data_compression: None,
+            partition_compression: Vec::new(),
             is_padded: false,
         };
 
@@ -912,6 +1030,7 @@ mod tests {
             filter_predicate: None,
             fill_factor: None,
             data_compression: None,
+            partition_compression: Vec::new(),
             is_padded: false,
         };
 
@@ -927,6 +1046,40 @@ mod tests {
         assert_eq!(xml.matches("IsAscending").count(), 1);
     }
 
+    #[test]
+    fn test_write_index_with_partition_compression() {
+        let index = IndexElement {
+            name: "IX_Partitioned".to_string(),
+            table_schema: "dbo".to_string(),
+            table_name: "TestTable".to_string(),
+            columns: vec![IndexColumn::new("Col1".to_string())],
+            is_unique: false,
+            is_clustered: false,
+            include_columns: vec![],
+            filter_predicate: None,
+            fill_factor: None,
+            data_compression: None,
+            partition_compression: vec![
+                (3, DataCompressionType::Row),
+                (1, DataCompressionType::Page),
+            ],
+            is_padded: false,
+        };
+
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new(&mut buffer);
+        write_index(&mut writer, &index).unwrap();
+
+        let xml = String::from_utf8(buffer).unwrap();
+        assert_eq!(xml.matches("SqlDataCompressionOption").count(), 2);
+        let first = xml.find(r#"Name="PartitionNumber" Value="1""#).unwrap();
+        let second = xml.find(r#"Name="PartitionNumber" Value="3""#).unwrap();
+        assert!(first < second);
+        // PAGE = 2 for partition 1, ROW = 1 for partition 3
+        assert!(xml[..first].contains(r#"Name="CompressionLevel" Value="2""#));
+        assert!(xml[first..second].contains(r#"Name="CompressionLevel" Value="1""#));
+    }
+
     #[test]
     fn test_write_fulltext_catalog() {
         let catalog = FullTextCatalogElement {
@@ -952,10 +1105,12 @@ mod tests {
             columns: vec![FullTextColumnElement {
                 name: "Content".to_string(),
                 language_id: Some(1033),
+                type_column: None,
             }],
             key_index: "PK_TestTable".to_string(),
             catalog: Some("TestCatalog".to_string()),
             change_tracking: None,
+            stoplist: None,
             disambiguator: None,
         };
 
@@ -969,6 +1124,38 @@ mod tests {
         assert!(xml.contains(r#"Name="Catalog""#));
         assert!(xml.contains(r#"Name="IndexedObject""#));
         assert!(xml.contains(r#"Name="KeyName""#));
+        assert!(!xml.contains("ChangeTracking"));
+        assert!(!xml.contains("StopList"));
+    }
+
+    #[test]
+    fn test_write_fulltext_index_options() {
+        let fulltext = FullTextIndexElement {
+            table_schema: "dbo".to_string(),
+            table_name: "Documents".to_string(),
+            columns: vec![FullTextColumnElement {
+                name: "Body".to_string(),
+                language_id: None,
+                type_column: Some("Extension".to_string()),
+            }],
+            key_index: "PK_Documents".to_string(),
+            catalog: None,
+            change_tracking: Some("MANUAL".to_string()),
+            stoplist: Some("DocStopList".to_string()),
+            disambiguator: None,
+        };
+
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new(&mut buffer);
+        write_fulltext_index(&mut writer, &fulltext).unwrap();
+
+        let xml = String::from_utf8(buffer).unwrap();
+        assert!(xml.contains(r#"<Property Name="ChangeTracking" Value="1"/>"#));
+        assert!(xml.contains(r#"<References Name="[dbo].[Documents].[Extension]"/>"#));
+        let key_pos = xml.find(r#"Name="KeyName""#).unwrap();
+        let stoplist_pos = xml.find(r#"Name="StopList""#).unwrap();
+        assert!(key_pos < stoplist_pos);
+        assert!(xml.contains(r#"<References Name="[DocStopList]"/>"#));
     }
 
     #[test]
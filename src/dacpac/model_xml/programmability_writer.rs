@@ -7,27 +7,28 @@
 use quick_xml::events::{BytesEnd, BytesStart, Event};
 use quick_xml::Writer;
 use regex::Regex;
-use sqlparser::dialect::MsSqlDialect;
-use sqlparser::tokenizer::{Token, Tokenizer};
 use std::io::Write;
 use std::sync::LazyLock;
 
 use crate::model::{
-    DatabaseModel, FunctionElement, ModelElement, ProcedureElement, UserDefinedTypeElement,
+    DatabaseModel, FunctionElement, ModelElement, OutputMode, ProcedureElement, SourceLocation,
+    UserDefinedTypeElement,
 };
+use crate::parser::identifier_utils::escape_identifier;
+use crate::parser::lexer::{tokenize, tokenize_with_location, Token, TokenWithSpan};
+use crate::parser::token_parser_base::location_to_offset;
 use crate::parser::{extract_function_parameters_tokens, extract_procedure_parameters_tokens};
 
 use super::column_registry::ColumnRegistry;
 use super::table_writer::{write_column_type_specifier, write_table_type_relationship};
 use super::view_writer::{extract_view_columns_and_deps, write_view_columns, ViewColumn};
 use super::xml_helpers::{
-    escape_newlines_for_attr, write_property, write_property_raw, write_schema_relationship,
-    write_script_property,
+    write_property, write_schema_relationship, write_script_property, write_set_options,
+    write_sys_comments_annotation,
 };
 use super::{
-    compute_line_offsets, extract_body_dependencies, extract_expression_before_as,
-    extract_select_columns, location_to_byte_offset, normalize_type_name, parse_data_type,
-    write_body_dependencies, BodyDependency,
+    extract_body_dependencies, extract_expression_before_as, extract_select_columns,
+    normalize_type_name, parse_data_type, write_body_dependencies, BodyDependency,
 };
 
 /// Multi-statement TVF detection: RETURNS @var TABLE (
@@ -44,8 +45,13 @@ pub(crate) fn write_procedure<W: Write>(
     model: &DatabaseModel,
     default_schema: &str,
     column_registry: &ColumnRegistry,
+    source: Option<&SourceLocation>,
 ) -> anyhow::Result<()> {
-    let full_name = format!("[{}].[{}]", proc.schema, proc.name);
+    let full_name = format!(
+        "[{}].[{}]",
+        escape_identifier(&proc.schema),
+        escape_identifier(&proc.name)
+    );
 
     // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
     let elem = BytesStart::new("Element")
@@ -79,14 +85,19 @@ pub(crate) fn write_procedure<W: Write>(
     // Write BodyScript property first
     write_script_property(writer, "BodyScript", &body)?;
 
-    // Write IsAnsiNullsOn property (always true for procedures)
-    write_property(writer, "IsAnsiNullsOn", "True")?;
+    // Write IsAnsiNullsOn / IsQuotedIdentifierOn from the project's SET options
+    write_set_options(writer, model.ansi_nulls, model.quoted_identifier)?;
 
     // Write IsNativelyCompiled property if true
     if proc.is_natively_compiled {
         write_property(writer, "IsNativelyCompiled", "True")?;
     }
 
+    // Write IsSchemaBound property if true (natively compiled procedures require SCHEMABINDING)
+    if proc.is_schema_bound {
+        write_property(writer, "IsSchemaBound", "True")?;
+    }
+
     // Extract and write BodyDependencies
     // For procedures with TVPs, we need special handling for TVP column references
     // For all procedures, we still need regular body dependencies (table refs, param refs, etc.)
@@ -128,7 +139,7 @@ pub(crate) fn write_procedure<W: Write>(
             } else {
                 format!("@{}", param.name)
             };
-            let param_name = format!("{}.[{}]", full_name, param_name_with_at);
+            let param_name = format!("{}.[{}]", full_name, escape_identifier(&param_name_with_at));
 
             // Check if this is a TVP parameter
             let tvp_idx = tvp_params.iter().position(|(p, _)| std::ptr::eq(*p, param));
@@ -167,6 +178,11 @@ pub(crate) fn write_procedure<W: Write>(
                 write_property(writer, "IsReadOnly", "True")?;
             }
 
+            // Varying property for CURSOR VARYING OUTPUT parameters
+            if param.is_varying {
+                write_property(writer, "Varying", "True")?;
+            }
+
             // Data type relationship - different handling for TVPs vs built-in types
             if is_tvp {
                 write_table_type_relationship(writer, &param.data_type)?;
@@ -183,8 +199,14 @@ pub(crate) fn write_procedure<W: Write>(
 
     write_schema_relationship(writer, &proc.schema)?;
 
-    // Write SysCommentsObjectAnnotation with header contents (matching DotNet format)
-    write_procedure_annotation(writer, &proc.definition)?;
+    // Write SysCommentsObjectAnnotation with header contents (matching DotNet format);
+    // skipped if the header cannot be found
+    if model.output_mode == OutputMode::Parity {
+        let header = extract_procedure_header(&proc.definition);
+        if !header.is_empty() {
+            write_sys_comments_annotation(writer, &proc.definition, &header, "", source)?;
+        }
+    }
 
     writer.write_event(Event::End(BytesEnd::new("Element")))?;
     Ok(())
@@ -195,54 +217,71 @@ pub(crate) fn write_procedure<W: Write>(
 fn extract_procedure_header(definition: &str) -> String {
     // Use tokenized parsing to find the AS keyword
     if let Some((_as_start, as_end)) = find_procedure_body_separator_as_tokenized(definition) {
-        // Return everything up to the end position (after AS, including trailing whitespace)
-        // Only trim leading whitespace - trailing whitespace is needed for proper reconstruction
-        return definition[..as_end].trim_start().to_string();
+        // Return everything up to the end position (after AS, including trailing whitespace,
+        // which is needed for proper reconstruction)
+        return definition[..as_end].to_string();
     }
     String::new()
 }
 
-/// Find the byte offset of CREATE within the definition.
-/// This is used for the CreateOffset property in SysCommentsObjectAnnotation.
-fn find_create_offset(definition: &str) -> usize {
-    let def_upper = definition.to_uppercase();
-    def_upper.find("CREATE").unwrap_or(0)
+/// Extract the BodyDependencies of a procedure without writing XML.
+/// Uses the same extraction as `write_procedure`, minus TVP column resolution.
+pub(crate) fn procedure_body_dependencies(
+    proc: &ProcedureElement,
+    column_registry: &ColumnRegistry,
+) -> Vec<BodyDependency> {
+    let full_name = format!(
+        "[{}].[{}]",
+        escape_identifier(&proc.schema),
+        escape_identifier(&proc.name)
+    );
+    let body = extract_procedure_body_only(&proc.definition);
+    let param_names: Vec<String> = extract_procedure_parameters(&proc.definition)
+        .into_iter()
+        .map(|p| p.name)
+        .collect();
+    extract_body_dependencies(&body, &full_name, &param_names, column_registry)
 }
 
-/// Write SysCommentsObjectAnnotation for a procedure.
-/// DotNet emits this annotation with CreateOffset, Length, StartLine, StartColumn, and HeaderContents.
-fn write_procedure_annotation<W: Write>(
-    writer: &mut Writer<W>,
-    definition: &str,
-) -> anyhow::Result<()> {
-    // Extract header (CREATE PROCEDURE ... AS)
-    let header = extract_procedure_header(definition);
-    if header.is_empty() {
-        // If we can't extract the header, skip the annotation
-        return Ok(());
-    }
-
-    // Calculate properties
-    let total_length = definition.len();
-    let create_offset = find_create_offset(definition);
-
-    // Write the annotation
-    let annotation =
-        BytesStart::new("Annotation").with_attributes([("Type", "SysCommentsObjectAnnotation")]);
-    writer.write_event(Event::Start(annotation))?;
-
-    write_property(writer, "CreateOffset", &create_offset.to_string())?;
-    write_property(writer, "Length", &total_length.to_string())?;
-    write_property(writer, "StartLine", "1")?;
-    write_property(writer, "StartColumn", "1")?;
-    // Escape newlines for XML attribute value (DotNet uses &#xA; for newlines)
-    // Use write_property_raw to avoid double-escaping the & in &#xA;
-    let escaped_header = escape_newlines_for_attr(&header);
-    write_property_raw(writer, "HeaderContents", &escaped_header)?;
-
-    writer.write_event(Event::End(BytesEnd::new("Annotation")))?;
+/// Extract the BodyDependencies of a function without writing XML.
+pub(crate) fn function_body_dependencies(
+    func: &FunctionElement,
+    column_registry: &ColumnRegistry,
+) -> Vec<BodyDependency> {
+    let full_name = format!(
+        "[{}].[{}]",
+        escape_identifier(&func.schema),
+        escape_identifier(&func.name)
+    );
+    let body = extract_function_body(&func.definition);
+    let param_names: Vec<String> = extract_function_parameters(&func.definition)
+        .into_iter()
+        .map(|p| p.name)
+        .collect();
+    extract_body_dependencies(&body, &full_name, &param_names, column_registry)
+}
 
-    Ok(())
+/// Extract the output columns of an inline table-valued function without writing XML.
+/// Returns an empty list for scalar and multi-statement functions.
+pub(crate) fn inline_tvf_output_columns(
+    func: &FunctionElement,
+    model: &DatabaseModel,
+    default_schema: &str,
+    column_registry: &ColumnRegistry,
+) -> Vec<ViewColumn> {
+    if !matches!(
+        func.function_type,
+        crate::model::FunctionType::InlineTableValued
+    ) {
+        return Vec::new();
+    }
+    let full_name = format!(
+        "[{}].[{}]",
+        escape_identifier(&func.schema),
+        escape_identifier(&func.name)
+    );
+    let body = extract_function_body(&func.definition);
+    extract_inline_tvf_columns(&body, &full_name, default_schema, model, column_registry)
 }
 
 // =============================================================================
@@ -255,8 +294,13 @@ pub(crate) fn write_function<W: Write>(
     model: &DatabaseModel,
     default_schema: &str,
     column_registry: &ColumnRegistry,
+    source: Option<&SourceLocation>,
 ) -> anyhow::Result<()> {
-    let full_name = format!("[{}].[{}]", func.schema, func.name);
+    let full_name = format!(
+        "[{}].[{}]",
+        escape_identifier(&func.schema),
+        escape_identifier(&func.name)
+    );
     let type_name = match func.function_type {
         crate::model::FunctionType::Scalar => "SqlScalarFunction",
         crate::model::FunctionType::TableValued => "SqlMultiStatementTableValuedFunction",
@@ -268,17 +312,39 @@ pub(crate) fn write_function<W: Write>(
         .with_attributes([("Type", type_name), ("Name", full_name.as_str())]);
     writer.write_event(Event::Start(elem))?;
 
-    // Write IsAnsiNullsOn property (always true for functions)
-    write_property(writer, "IsAnsiNullsOn", "True")?;
+    // Function options, in DotNet's (alphabetical) property order
+    if func.returns_null_on_null_input {
+        write_property(writer, "DoReturnNullForNullInput", "True")?;
+    }
+
+    // Write IsAnsiNullsOn / IsQuotedIdentifierOn from the project's SET options
+    write_set_options(writer, model.ansi_nulls, model.quoted_identifier)?;
+
+    let execute_as = func.execute_as.as_deref().map(str::to_ascii_uppercase);
+    if execute_as.as_deref() == Some("CALLER") {
+        write_property(writer, "IsCaller", "True")?;
+    }
 
     // Write IsNativelyCompiled property if true
     if func.is_natively_compiled {
         write_property(writer, "IsNativelyCompiled", "True")?;
     }
 
+    if execute_as.as_deref() == Some("OWNER") {
+        write_property(writer, "IsOwner", "True")?;
+    }
+    if func.is_schema_bound {
+        write_property(writer, "IsSchemaBound", "True")?;
+    }
+    if execute_as.as_deref() == Some("SELF") {
+        write_property(writer, "IsSelf", "True")?;
+    }
+
     // Extract function body for dependency analysis
     let body = extract_function_body(&func.definition);
-    let header = extract_function_header(&func.definition);
+    // The header is only used by SysCommentsObjectAnnotation, which relaxed mode omits
+    let header = (model.output_mode == OutputMode::Parity)
+        .then(|| extract_function_header(&func.definition));
 
     // Extract function parameters for dependency analysis
     let func_params = extract_function_parameters(&func.definition);
@@ -320,7 +386,13 @@ pub(crate) fn write_function<W: Write>(
 
     // Write FunctionBody relationship with SqlScriptFunctionImplementation
     // BodyScript contains only the function body (BEGIN...END), not the header
-    write_function_body_with_annotation(writer, &body, &header)?;
+    write_function_body_with_annotation(
+        writer,
+        &body,
+        header
+            .as_deref()
+            .map(|header| (&*func.definition, header, source)),
+    )?;
 
     // Write Parameters relationship for function parameters
     write_function_parameters(writer, &func_params, &full_name)?;
@@ -350,6 +422,8 @@ pub(crate) struct ProcedureParameter {
     pub is_output: bool,
     /// Whether this is a READONLY table-valued parameter
     pub is_readonly: bool,
+    /// Whether this is a CURSOR VARYING parameter
+    pub is_varying: bool,
     #[allow(dead_code)] // Captured for potential future use
     pub default_value: Option<String>,
 }
@@ -372,6 +446,7 @@ fn extract_procedure_parameters(definition: &str) -> Vec<ProcedureParameter> {
             data_type: p.data_type,
             is_output: p.is_output,
             is_readonly: p.is_readonly,
+            is_varying: p.is_varying,
             default_value: p.default_value,
         })
         .collect()
@@ -431,7 +506,7 @@ fn write_function_parameters<W: Write>(
         } else {
             format!("@{}", param.name)
         };
-        let param_name = format!("{}.[{}]", full_name, param_name_with_at);
+        let param_name = format!("{}.[{}]", full_name, escape_identifier(&param_name_with_at));
         // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
         let param_elem = BytesStart::new("Element").with_attributes([
             ("Type", "SqlSubroutineParameter"),
@@ -470,7 +545,11 @@ fn find_table_type_for_parameter<'a>(
 
     for element in &model.elements {
         if let ModelElement::UserDefinedType(udt) = element {
-            let type_full_name = format!("[{}].[{}]", udt.schema, udt.name);
+            let type_full_name = format!(
+                "[{}].[{}]",
+                escape_identifier(&udt.schema),
+                escape_identifier(&udt.name)
+            );
             if type_full_name.eq_ignore_ascii_case(&normalized) {
                 return Some(udt);
             }
@@ -496,7 +575,7 @@ fn write_dynamic_columns<W: Write>(
     for col in &table_type.columns {
         writer.write_event(Event::Start(BytesStart::new("Entry")))?;
 
-        let col_full_name = format!("{}.[{}]", dynamic_source_name, col.name);
+        let col_full_name = format!("{}.[{}]", dynamic_source_name, escape_identifier(&col.name));
         // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
         let col_elem = BytesStart::new("Element").with_attributes([
             ("Type", "SqlSimpleColumn"),
@@ -544,7 +623,7 @@ fn write_cte_columns<W: Write>(
     for col in columns {
         writer.write_event(Event::Start(BytesStart::new("Entry")))?;
 
-        let col_full_name = format!("{}.[{}]", cte_source_name, col.name);
+        let col_full_name = format!("{}.[{}]", cte_source_name, escape_identifier(&col.name));
         let col_elem = BytesStart::new("Element").with_attributes([
             ("Type", "SqlComputedColumn"),
             ("Name", col_full_name.as_str()),
@@ -600,7 +679,11 @@ fn write_temp_table_columns<W: Write>(
     for col in columns {
         writer.write_event(Event::Start(BytesStart::new("Entry")))?;
 
-        let col_full_name = format!("{}.[{}]", temp_table_source_name, col.name);
+        let col_full_name = format!(
+            "{}.[{}]",
+            temp_table_source_name,
+            escape_identifier(&col.name)
+        );
         let col_elem = BytesStart::new("Element").with_attributes([
             ("Type", "SqlSimpleColumn"),
             ("Name", col_full_name.as_str()),
@@ -643,7 +726,7 @@ fn write_temp_table_column_type_specifier<W: Write>(
     writer.write_event(Event::Start(type_ref_rel))?;
     writer.write_event(Event::Start(BytesStart::new("Entry")))?;
 
-    let type_ref = format!("[{}]", base_type.to_lowercase());
+    let type_ref = format!("[{}]", escape_identifier(&base_type.to_lowercase()));
     let refs_elem = BytesStart::new("References")
         .with_attributes([("ExternalSource", "BuiltIns"), ("Name", type_ref.as_str())]);
     writer.write_event(Event::Empty(refs_elem))?;
@@ -721,7 +804,11 @@ fn write_table_variable_columns<W: Write>(
     for col in columns {
         writer.write_event(Event::Start(BytesStart::new("Entry")))?;
 
-        let col_full_name = format!("{}.[{}]", table_var_source_name, col.name);
+        let col_full_name = format!(
+            "{}.[{}]",
+            table_var_source_name,
+            escape_identifier(&col.name)
+        );
         let col_elem = BytesStart::new("Element").with_attributes([
             ("Type", "SqlSimpleColumn"),
             ("Name", col_full_name.as_str()),
@@ -784,7 +871,8 @@ pub(crate) fn write_all_dynamic_objects<W: Write>(
         } else {
             format!("@{}", param.name)
         };
-        let dynamic_source_name = format!("{}.[{}]", full_name, param_name_with_at);
+        let dynamic_source_name =
+            format!("{}.[{}]", full_name, escape_identifier(&param_name_with_at));
 
         let elem = BytesStart::new("Element").with_attributes([
             ("Type", "SqlDynamicColumnSource"),
@@ -805,7 +893,12 @@ pub(crate) fn write_all_dynamic_objects<W: Write>(
     for cte in &cte_defs {
         writer.write_event(Event::Start(BytesStart::new("Entry")))?;
 
-        let cte_source_name = format!("{}.[CTE{}].[{}]", full_name, cte.cte_number, cte.name);
+        let cte_source_name = format!(
+            "{}.[CTE{}].[{}]",
+            full_name,
+            cte.cte_number,
+            escape_identifier(&cte.name)
+        );
 
         let elem = BytesStart::new("Element").with_attributes([
             ("Type", "SqlDynamicColumnSource"),
@@ -828,7 +921,9 @@ pub(crate) fn write_all_dynamic_objects<W: Write>(
         // Format: [schema].[proc].[TempTable1].[#TempName]
         let temp_table_source_name = format!(
             "{}.[TempTable{}].[{}]",
-            full_name, temp_table.temp_table_number, temp_table.name
+            full_name,
+            temp_table.temp_table_number,
+            escape_identifier(&temp_table.name)
         );
 
         let elem = BytesStart::new("Element").with_attributes([
@@ -850,7 +945,8 @@ pub(crate) fn write_all_dynamic_objects<W: Write>(
         writer.write_event(Event::Start(BytesStart::new("Entry")))?;
 
         // Format: [schema].[proc].[@VarName] - matches DacFx (no [TableVariable#] intermediate)
-        let table_var_source_name = format!("{}.[{}]", full_name, table_var.name);
+        let table_var_source_name =
+            format!("{}.[{}]", full_name, escape_identifier(&table_var.name));
 
         let elem = BytesStart::new("Element").with_attributes([
             ("Type", "SqlDynamicColumnSource"),
@@ -925,7 +1021,7 @@ fn extract_body_dependencies_with_tvp(
                         "{}.[@{}].[{}]",
                         full_name,
                         tvp_param_name.trim_start_matches('@'),
-                        col_name
+                        escape_identifier(col_name)
                     );
                     if !seen.contains(&col_ref) {
                         seen.insert(col_ref.clone());
@@ -1006,9 +1102,9 @@ fn extract_function_header(definition: &str) -> String {
         // Use token-based parsing to find the AS keyword
         if let Some((_as_start, as_end)) = find_function_body_as_tokenized(definition, returns_pos)
         {
-            // Return everything up to and including AS with trailing whitespace
-            // Use trim_start() to only remove leading whitespace, preserving trailing newline
-            return definition[..as_end].trim_start().to_string();
+            // Return everything up to and including AS with trailing whitespace; leading
+            // text is kept byte for byte so the header and Length agree with DotNet
+            return definition[..as_end].to_string();
         }
     }
 
@@ -1036,8 +1132,7 @@ fn find_function_body_as_tokenized(
 ) -> Option<(usize, usize)> {
     let after_returns = &definition[after_returns_start..];
 
-    let dialect = MsSqlDialect {};
-    let Ok(tokens) = Tokenizer::new(&dialect, after_returns).tokenize_with_location() else {
+    let Ok(tokens) = tokenize_with_location(after_returns) else {
         return None;
     };
 
@@ -1045,20 +1140,16 @@ fn find_function_body_as_tokenized(
         return None;
     }
 
-    // Build line offset map for byte position calculation
-    let line_offsets = compute_line_offsets(after_returns);
-
     let len = tokens.len();
     let mut i = 0;
 
     // Helper to skip whitespace tokens, returns the next non-whitespace index
-    let skip_whitespace =
-        |tokens: &[sqlparser::tokenizer::TokenWithSpan], mut idx: usize| -> usize {
-            while idx < tokens.len() && matches!(&tokens[idx].token, Token::Whitespace(_)) {
-                idx += 1;
-            }
-            idx
-        };
+    let skip_whitespace = |tokens: &[TokenWithSpan], mut idx: usize| -> usize {
+        while idx < tokens.len() && matches!(&tokens[idx].token, Token::Whitespace(_)) {
+            idx += 1;
+        }
+        idx
+    };
 
     // Keywords that can start a function body after AS
     let body_start_keywords = [
@@ -1071,22 +1162,14 @@ fn find_function_body_as_tokenized(
         if let Token::Word(w) = &tokens[i].token {
             if w.quote_style.is_none() && w.value.eq_ignore_ascii_case("AS") {
                 // Calculate byte position of AS keyword
-                let as_byte_start = location_to_byte_offset(
-                    &line_offsets,
-                    tokens[i].span.start.line,
-                    tokens[i].span.start.column,
-                );
+                let as_byte_start = location_to_offset(after_returns, tokens[i].span.start);
 
                 // Look at what comes after AS
                 let j = skip_whitespace(&tokens, i + 1);
 
                 // Calculate end position (after AS and any whitespace)
                 let as_byte_end = if j < len {
-                    location_to_byte_offset(
-                        &line_offsets,
-                        tokens[j].span.start.line,
-                        tokens[j].span.start.column,
-                    )
+                    location_to_offset(after_returns, tokens[j].span.start)
                 } else {
                     // AS is at the end, end is after "AS" (2 chars)
                     as_byte_start + 2
@@ -1150,8 +1233,7 @@ fn extract_procedure_body_only_impl(definition: &str) -> String {
 ///   where `as_end` includes any trailing whitespace after AS
 /// * `None` - If no valid body separator AS was found
 fn find_procedure_body_separator_as_tokenized(definition: &str) -> Option<(usize, usize)> {
-    let dialect = MsSqlDialect {};
-    let Ok(tokens) = Tokenizer::new(&dialect, definition).tokenize_with_location() else {
+    let Ok(tokens) = tokenize_with_location(definition) else {
         return None;
     };
 
@@ -1159,20 +1241,16 @@ fn find_procedure_body_separator_as_tokenized(definition: &str) -> Option<(usize
         return None;
     }
 
-    // Build line offset map for byte position calculation
-    let line_offsets = compute_line_offsets(definition);
-
     let len = tokens.len();
     let mut i = 0;
 
     // Helper to skip whitespace tokens, returns the next non-whitespace index
-    let skip_whitespace =
-        |tokens: &[sqlparser::tokenizer::TokenWithSpan], mut idx: usize| -> usize {
-            while idx < tokens.len() && matches!(&tokens[idx].token, Token::Whitespace(_)) {
-                idx += 1;
-            }
-            idx
-        };
+    let skip_whitespace = |tokens: &[TokenWithSpan], mut idx: usize| -> usize {
+        while idx < tokens.len() && matches!(&tokens[idx].token, Token::Whitespace(_)) {
+            idx += 1;
+        }
+        idx
+    };
 
     // Keywords that can start a procedure body after AS
     let body_start_keywords = [
@@ -1185,22 +1263,14 @@ fn find_procedure_body_separator_as_tokenized(definition: &str) -> Option<(usize
         if let Token::Word(w) = &tokens[i].token {
             if w.quote_style.is_none() && w.value.eq_ignore_ascii_case("AS") {
                 // Calculate byte position of AS keyword
-                let as_byte_start = location_to_byte_offset(
-                    &line_offsets,
-                    tokens[i].span.start.line,
-                    tokens[i].span.start.column,
-                );
+                let as_byte_start = location_to_offset(definition, tokens[i].span.start);
 
                 // Look at what comes after AS
                 let j = skip_whitespace(&tokens, i + 1);
 
                 // Calculate end position (after AS and any whitespace)
                 let as_byte_end = if j < len {
-                    location_to_byte_offset(
-                        &line_offsets,
-                        tokens[j].span.start.line,
-                        tokens[j].span.start.column,
-                    )
+                    location_to_offset(definition, tokens[j].span.start)
                 } else {
                     // AS is at the end, end is after "AS" (2 chars)
                     as_byte_start + 2
@@ -1231,10 +1301,11 @@ fn find_procedure_body_separator_as_tokenized(definition: &str) -> Option<(usize
 
 /// Write FunctionBody relationship for functions with nested SqlScriptFunctionImplementation
 /// Includes SysCommentsObjectAnnotation with HeaderContents for DacFx compatibility
+/// when `annotation` (the definition, its header and where it was defined) is given
 fn write_function_body_with_annotation<W: Write>(
     writer: &mut Writer<W>,
     body: &str,
-    header: &str,
+    annotation: Option<(&str, &str, Option<&SourceLocation>)>,
 ) -> anyhow::Result<()> {
     // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
     let rel = BytesStart::new("Relationship").with_attributes([("Name", "FunctionBody")]);
@@ -1251,23 +1322,9 @@ fn write_function_body_with_annotation<W: Write>(
     write_script_property(writer, "BodyScript", body)?;
 
     // Write SysCommentsObjectAnnotation with HeaderContents
-    // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
-    let annotation =
-        BytesStart::new("Annotation").with_attributes([("Type", "SysCommentsObjectAnnotation")]);
-    writer.write_event(Event::Start(annotation))?;
-
-    // Calculate length (header + body)
-    let total_length = header.len() + body.len();
-    write_property(writer, "Length", &total_length.to_string())?;
-    write_property(writer, "StartLine", "1")?;
-    write_property(writer, "StartColumn", "1")?;
-
-    // Write HeaderContents with XML-escaped header (newlines encoded as &#xA;)
-    // Use write_property_raw to avoid double-escaping the & in &#xA;
-    let escaped_header = escape_newlines_for_attr(header);
-    write_property_raw(writer, "HeaderContents", &escaped_header)?;
-
-    writer.write_event(Event::End(BytesEnd::new("Annotation")))?;
+    if let Some((definition, header, source)) = annotation {
+        write_sys_comments_annotation(writer, definition, header, "", source)?;
+    }
 
     writer.write_event(Event::End(BytesEnd::new("Element")))?;
     writer.write_event(Event::End(BytesEnd::new("Entry")))?;
@@ -1286,7 +1343,7 @@ fn write_function_return_type<W: Write>(
 ) -> anyhow::Result<()> {
     // Extract base type name (e.g., "INT" -> "int", "DECIMAL(18,2)" -> "decimal")
     let base_type = extract_base_type_name(return_type);
-    let type_ref = format!("[{}]", base_type.to_lowercase());
+    let type_ref = format!("[{}]", escape_identifier(&base_type.to_lowercase()));
 
     // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
     let rel = BytesStart::new("Relationship").with_attributes([("Name", "Type")]);
@@ -1394,7 +1451,11 @@ fn extract_inline_tvf_columns(
                 if expr_part.starts_with('@') && !expr_part.contains('(') {
                     let param_name = expr_part.trim_matches(|c| c == '[' || c == ']');
                     // DotNet format: [schema].[FuncName].[@ParamName] (brackets around the @param)
-                    columns[idx].source_ref = Some(format!("{}.[{}]", func_full_name, param_name));
+                    columns[idx].source_ref = Some(format!(
+                        "{}.[{}]",
+                        func_full_name,
+                        escape_identifier(param_name)
+                    ));
                 }
             }
         }
@@ -1578,8 +1639,7 @@ fn parse_tvf_column_type_tokenized(type_str: &str) -> Option<TvfColumnTypeInfo>
         return None;
     }
 
-    let dialect = MsSqlDialect {};
-    let tokens = Tokenizer::new(&dialect, type_str).tokenize().ok()?;
+    let tokens = tokenize(type_str).ok()?;
     if tokens.is_empty() {
         return None;
     }
@@ -1692,7 +1752,7 @@ fn write_tvf_columns<W: Write>(
     for col in columns {
         writer.write_event(Event::Start(BytesStart::new("Entry")))?;
 
-        let col_full_name = format!("{}.[{}]", func_full_name, col.name);
+        let col_full_name = format!("{}.[{}]", func_full_name, escape_identifier(&col.name));
         // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
         let elem = BytesStart::new("Element").with_attributes([
             ("Type", "SqlSimpleColumn"),
@@ -1798,7 +1858,7 @@ fn write_data_type_relationship<W: Write>(
     }
 
     // Write the base type as a reference
-    let type_ref = format!("[{}]", base_type.to_lowercase());
+    let type_ref = format!("[{}]", escape_identifier(&base_type.to_lowercase()));
     // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
     let type_rel = BytesStart::new("Relationship").with_attributes([("Name", "Type")]);
     writer.write_event(Event::Start(type_rel))?;
@@ -8,9 +8,10 @@ use quick_xml::events::{BytesEnd, BytesStart, Event};
 use quick_xml::Writer;
 use std::io::Write;
 
-use crate::model::{ColumnElement, TableElement, TableTypeColumnElement};
-use crate::parser::identifier_utils::normalize_identifier;
+use crate::model::{ColumnElement, DatabaseModel, TableElement, TableTypeColumnElement};
+use crate::parser::identifier_utils::{escape_identifier, normalize_identifier, split_name_parts};
 
+use super::other_writers::write_data_compression_options;
 use super::xml_helpers::{
     write_builtin_type_relationship, write_property, write_schema_relationship,
     write_script_property,
@@ -23,16 +24,23 @@ use super::{extract_computed_expression_columns, parse_data_type, parse_qualifie
 pub(crate) fn write_table<W: Write>(
     writer: &mut Writer<W>,
     table: &TableElement,
+    model: &DatabaseModel,
 ) -> anyhow::Result<()> {
-    let full_name = format!("[{}].[{}]", table.schema, table.name);
+    let full_name = format!(
+        "[{}].[{}]",
+        escape_identifier(&table.schema),
+        escape_identifier(&table.name)
+    );
 
     // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
     let elem = BytesStart::new("Element")
         .with_attributes([("Type", "SqlTable"), ("Name", full_name.as_str())]);
     writer.write_event(Event::Start(elem))?;
 
-    // Write IsAnsiNullsOn property (always true for tables - ANSI_NULLS ON is default)
-    write_property(writer, "IsAnsiNullsOn", "True")?;
+    // Write IsAnsiNullsOn property (tables record ANSI_NULLS but not QUOTED_IDENTIFIER)
+    if model.ansi_nulls {
+        write_property(writer, "IsAnsiNullsOn", "True")?;
+    }
 
     // Write temporal table property: IsSystemVersioningOn
     if table.is_system_versioned {
@@ -45,18 +53,25 @@ pub(crate) fn write_table<W: Write>(
         writer.write_event(Event::Start(rel))?;
 
         for col in &table.columns {
-            write_column(writer, col, &full_name)?;
+            write_column(writer, col, &full_name, model)?;
         }
 
         writer.write_event(Event::End(BytesEnd::new("Relationship")))?;
     }
 
+    // Table-level DATA_COMPRESSION (comes between Columns and Schema in DotNet output)
+    write_data_compression_options(
+        writer,
+        table.data_compression.as_ref(),
+        &table.partition_compression,
+    )?;
+
     // Relationship to schema (comes after Columns in DotNet output)
     write_schema_relationship(writer, &table.schema)?;
 
     // Temporal table relationships: SystemTimePeriodStartColumn, SystemTimePeriodEndColumn
     if let Some(ref start_col) = table.system_time_start_column {
-        let col_ref = format!("{}.[{}]", full_name, start_col);
+        let col_ref = format!("{}.[{}]", full_name, escape_identifier(start_col));
         let rel = BytesStart::new("Relationship")
             .with_attributes([("Name", "SystemTimePeriodStartColumn")]);
         writer.write_event(Event::Start(rel))?;
@@ -68,7 +83,7 @@ pub(crate) fn write_table<W: Write>(
     }
 
     if let Some(ref end_col) = table.system_time_end_column {
-        let col_ref = format!("{}.[{}]", full_name, end_col);
+        let col_ref = format!("{}.[{}]", full_name, escape_identifier(end_col));
         let rel = BytesStart::new("Relationship")
             .with_attributes([("Name", "SystemTimePeriodEndColumn")]);
         writer.write_event(Event::Start(rel))?;
@@ -83,7 +98,11 @@ pub(crate) fn write_table<W: Write>(
     if let (Some(ref hist_schema), Some(ref hist_name)) =
         (&table.history_table_schema, &table.history_table_name)
     {
-        let hist_ref = format!("[{}].[{}]", hist_schema, hist_name);
+        let hist_ref = format!(
+            "[{}].[{}]",
+            escape_identifier(hist_schema),
+            escape_identifier(hist_name)
+        );
         let rel = BytesStart::new("Relationship").with_attributes([("Name", "HistoryTable")]);
         writer.write_event(Event::Start(rel))?;
         writer.write_event(Event::Start(BytesStart::new("Entry")))?;
@@ -131,10 +150,11 @@ pub(crate) fn write_column<W: Write>(
     writer: &mut Writer<W>,
     column: &ColumnElement,
     table_name: &str,
+    model: &DatabaseModel,
 ) -> anyhow::Result<()> {
     // Check if this is a computed column
     if column.computed_expression.is_some() {
-        write_computed_column(writer, column, table_name)
+        write_computed_column(writer, column, table_name, model)
     } else {
         write_column_with_type(writer, column, table_name, "SqlSimpleColumn")
     }
@@ -145,8 +165,9 @@ fn write_computed_column<W: Write>(
     writer: &mut Writer<W>,
     column: &ColumnElement,
     table_name: &str,
+    model: &DatabaseModel,
 ) -> anyhow::Result<()> {
-    let col_name = format!("{}.[{}]", table_name, column.name);
+    let col_name = format!("{}.[{}]", table_name, escape_identifier(&column.name));
 
     writer.write_event(Event::Start(BytesStart::new("Entry")))?;
 
@@ -171,7 +192,7 @@ fn write_computed_column<W: Write>(
     if let Some(ref expr) = column.computed_expression {
         // Parse schema and table name from qualified table_name like "[dbo].[Employees]"
         if let Some((schema, tbl)) = parse_qualified_table_name(table_name) {
-            let deps = extract_computed_expression_columns(expr, &schema, &tbl);
+            let deps = extract_computed_expression_columns(expr, &schema, &tbl, model);
             if !deps.is_empty() {
                 write_expression_dependencies(writer, &deps)?;
             }
@@ -285,7 +306,7 @@ pub(crate) fn write_table_type_column_with_annotation<W: Write>(
     type_name: &str,
     disambiguator: Option<u32>,
 ) -> anyhow::Result<()> {
-    let col_name = format!("{}.[{}]", type_name, column.name);
+    let col_name = format!("{}.[{}]", type_name, escape_identifier(&column.name));
 
     writer.write_event(Event::Start(BytesStart::new("Entry")))?;
 
@@ -333,7 +354,7 @@ pub(crate) fn write_column_with_type<W: Write>(
     parent_name: &str,
     column_type: &str,
 ) -> anyhow::Result<()> {
-    let col_name = format!("{}.[{}]", parent_name, column.name);
+    let col_name = format!("{}.[{}]", parent_name, escape_identifier(&column.name));
 
     writer.write_event(Event::Start(BytesStart::new("Entry")))?;
 
@@ -522,11 +543,10 @@ fn is_user_defined_type(data_type: &str) -> bool {
     // Extract base type (before any parenthesis for parameters)
     let base_type = trimmed.split('(').next().unwrap_or(trimmed).trim();
 
-    // Check for qualified name patterns:
-    // 1. [schema].[name] format
-    // 2. schema.name format (with or without brackets)
-    if base_type.contains("].[") {
-        return true;
+    // Check for qualified name patterns ([schema].[name] or schema.name); a dot
+    // inside brackets, as in [A.B], does not separate parts
+    if let Some(parts) = split_name_parts(base_type) {
+        return parts.len() >= 2;
     }
 
     // Check for unbracketed qualified name (contains a dot but not just "[type]")
@@ -602,7 +622,7 @@ pub(crate) fn write_column_type_specifier<W: Write>(
 
     // Write Type relationship
     let (base_type, _, _, _) = parse_data_type(data_type);
-    let type_ref = format!("[{}]", base_type.to_lowercase());
+    let type_ref = format!("[{}]", escape_identifier(&base_type.to_lowercase()));
 
     // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
     let type_rel = BytesStart::new("Relationship").with_attributes([("Name", "Type")]);
@@ -827,9 +847,11 @@ mod tests {
             is_system_versioned: false,
             history_table_schema: None,
             history_table_name: None,
+            data_compression: None,
+            partition_compression: Vec::new(),
         };
         let mut writer = create_test_writer();
-        write_table(&mut writer, &table).unwrap();
+        write_table(&mut writer, &table, &DatabaseModel::default()).unwrap();
         let output = get_output(writer);
         assert!(output.contains(r#"<Element Type="SqlTable" Name="[dbo].[TestTable]">"#));
         assert!(output.contains(r#"<Property Name="IsAnsiNullsOn" Value="True"/>"#));
@@ -853,9 +875,11 @@ mod tests {
             is_system_versioned: false,
             history_table_schema: None,
             history_table_name: None,
+            data_compression: None,
+            partition_compression: Vec::new(),
         };
         let mut writer = create_test_writer();
-        write_table(&mut writer, &table).unwrap();
+        write_table(&mut writer, &table, &DatabaseModel::default()).unwrap();
         let output = get_output(writer);
         assert!(output
             .contains(r#"<Annotation Type="SqlInlineConstraintAnnotation" Disambiguator="1"/>"#));
@@ -5,23 +5,26 @@
 
 use quick_xml::events::{BytesEnd, BytesStart, Event};
 use quick_xml::Writer;
-use sqlparser::dialect::MsSqlDialect;
-use sqlparser::keywords::Keyword;
-use sqlparser::tokenizer::{Token, Tokenizer};
 use std::collections::HashSet;
 use std::io::Write;
 
-use crate::model::{DatabaseModel, ModelElement, RawElement, ViewElement};
+use crate::model::{
+    DatabaseModel, ModelElement, OutputMode, RawElement, SourceLocation, ViewElement,
+};
+use crate::parser::extract_module_options;
+use crate::parser::identifier_utils::escape_identifier;
+use crate::parser::lexer::{tokenize_with_location, Keyword, Token};
+use crate::parser::token_parser_base::location_to_offset;
 pub(crate) use crate::util::contains_ci;
 
 use super::xml_helpers::{
-    escape_newlines_for_attr, normalize_script_content, write_property, write_property_raw,
-    write_schema_relationship, write_script_property,
+    write_property, write_schema_relationship, write_script_property, write_set_options,
+    write_sys_comments_annotation,
 };
 use super::{
     extract_all_column_references, extract_cte_definitions, extract_group_by_columns,
     extract_join_on_columns, extract_select_columns, extract_table_aliases,
-    parse_column_expression, reconstruct_tokens, CteColumn,
+    parse_column_expression, CteColumn,
 };
 
 /// Represents a view column with its name and optional source dependency
@@ -47,8 +50,13 @@ pub(crate) fn write_view<W: Write>(
     model: &DatabaseModel,
     default_schema: &str,
     column_registry: &ColumnRegistry,
+    source: Option<&SourceLocation>,
 ) -> anyhow::Result<()> {
-    let full_name = format!("[{}].[{}]", view.schema, view.name);
+    let full_name = format!(
+        "[{}].[{}]",
+        escape_identifier(&view.schema),
+        escape_identifier(&view.name)
+    );
 
     // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
     let elem = BytesStart::new("Element")
@@ -86,9 +94,9 @@ pub(crate) fn write_view<W: Write>(
         write_property(writer, "IsWithCheckOption", "True")?;
     }
 
-    // 5. IsAnsiNullsOn - always emit for views (current DotNet behavior)
-    // Modern .NET DacFx emits this property for all views
-    write_property(writer, "IsAnsiNullsOn", "True")?;
+    // 5. IsAnsiNullsOn / IsQuotedIdentifierOn - the project's SET options
+    // (with ANSI_NULLS ON, the default, DotNet emits IsAnsiNullsOn for every view)
+    write_set_options(writer, model.ansi_nulls, model.quoted_identifier)?;
 
     // Extract view columns and dependencies from the query
     // DotNet emits Columns and QueryDependencies for ALL views
@@ -127,8 +135,10 @@ pub(crate) fn write_view<W: Write>(
     // 9. Schema relationship
     write_schema_relationship(writer, &view.schema)?;
 
-    // 10. SysCommentsObjectAnnotation with header/footer contents
-    write_view_annotation(writer, &view.definition)?;
+    // 10. SysCommentsObjectAnnotation with header/footer contents (omitted in relaxed mode)
+    if model.output_mode == OutputMode::Parity {
+        write_view_annotation(writer, &view.definition, source)?;
+    }
 
     writer.write_event(Event::End(BytesEnd::new("Element")))?;
     Ok(())
@@ -142,26 +152,28 @@ pub(crate) fn write_raw_view<W: Write>(
     model: &DatabaseModel,
     default_schema: &str,
     column_registry: &ColumnRegistry,
+    source: Option<&SourceLocation>,
 ) -> anyhow::Result<()> {
-    let full_name = format!("[{}].[{}]", raw.schema, raw.name);
+    let full_name = format!(
+        "[{}].[{}]",
+        escape_identifier(&raw.schema),
+        escape_identifier(&raw.name)
+    );
 
     // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
     let elem = BytesStart::new("Element")
         .with_attributes([("Type", "SqlView"), ("Name", full_name.as_str())]);
     writer.write_event(Event::Start(elem))?;
 
-    // Extract view options from raw SQL text (case-insensitive, no allocation)
-    // WITH SCHEMABINDING appears before AS in the view definition
-    let is_schema_bound = contains_ci(&raw.definition, "WITH SCHEMABINDING")
-        || contains_ci(&raw.definition, ", SCHEMABINDING")
-        || contains_ci(&raw.definition, ",SCHEMABINDING");
+    // Extract view options from raw SQL text
+    // SCHEMABINDING and VIEW_METADATA appear in the WITH clause before AS
+    let options = extract_module_options(&raw.definition);
+    let is_schema_bound = options.has_option("SCHEMABINDING");
+    let is_metadata_reported = options.has_option("VIEW_METADATA");
 
     // WITH CHECK OPTION appears at the end of the view definition
     let is_with_check_option = contains_ci(&raw.definition, "WITH CHECK OPTION");
 
-    // VIEW_METADATA appears in WITH clause before AS
-    let is_metadata_reported = contains_ci(&raw.definition, "VIEW_METADATA");
-
     // Write properties in DotNet order:
     // 1. IsSchemaBound (if true)
     if is_schema_bound {
@@ -191,9 +203,9 @@ pub(crate) fn write_raw_view<W: Write>(
         write_property(writer, "IsWithCheckOption", "True")?;
     }
 
-    // 5. IsAnsiNullsOn - always emit for views (current DotNet behavior)
-    // Modern .NET DacFx emits this property for all views
-    write_property(writer, "IsAnsiNullsOn", "True")?;
+    // 5. IsAnsiNullsOn / IsQuotedIdentifierOn - the project's SET options
+    // (with ANSI_NULLS ON, the default, DotNet emits IsAnsiNullsOn for every view)
+    write_set_options(writer, model.ansi_nulls, model.quoted_identifier)?;
 
     // Extract view columns and dependencies from the query
     // DotNet emits Columns and QueryDependencies for ALL views
@@ -232,8 +244,10 @@ pub(crate) fn write_raw_view<W: Write>(
     // 9. Schema relationship
     write_schema_relationship(writer, &raw.schema)?;
 
-    // 10. SysCommentsObjectAnnotation with header/footer contents
-    write_view_annotation(writer, &raw.definition)?;
+    // 10. SysCommentsObjectAnnotation with header/footer contents (omitted in relaxed mode)
+    if model.output_mode == OutputMode::Parity {
+        write_view_annotation(writer, &raw.definition, source)?;
+    }
 
     writer.write_event(Event::End(BytesEnd::new("Element")))?;
     Ok(())
@@ -245,8 +259,7 @@ pub(crate) fn write_raw_view<W: Write>(
 /// Uses token-based parsing to handle any whitespace (tabs, multiple spaces, newlines)
 pub(crate) fn extract_view_query(definition: &str) -> String {
     // Tokenize the definition using sqlparser
-    let dialect = MsSqlDialect {};
-    let tokens = match Tokenizer::new(&dialect, definition).tokenize() {
+    let tokens = match tokenize_with_location(definition) {
         Ok(t) => t,
         Err(_) => {
             // Fallback: return the original definition if tokenization fails
@@ -259,16 +272,16 @@ pub(crate) fn extract_view_query(definition: &str) -> String {
     let mut paren_depth: i32 = 0;
     let mut found_view = false;
 
-    for (i, token) in tokens.iter().enumerate() {
-        match token {
+    for token in &tokens {
+        match &token.token {
             Token::LParen => paren_depth += 1,
             Token::RParen => paren_depth = paren_depth.saturating_sub(1),
             Token::Word(w) if w.keyword == Keyword::VIEW => {
                 found_view = true;
             }
             Token::Word(w) if w.keyword == Keyword::AS && paren_depth == 0 && found_view => {
-                // Found the AS keyword - return everything after it
-                let query = reconstruct_tokens(&tokens[i + 1..]);
+                // Found the AS keyword - return the source text after it
+                let query = &definition[location_to_offset(definition, token.span.end)..];
                 // Strip trailing semicolon to match DotNet behavior
                 return query.trim_end().trim_end_matches(';').to_string();
             }
@@ -285,8 +298,7 @@ pub(crate) fn extract_view_query(definition: &str) -> String {
 /// Uses token-based parsing to handle any whitespace variations.
 fn extract_view_header(definition: &str) -> String {
     // Tokenize the definition using sqlparser
-    let dialect = MsSqlDialect {};
-    let tokens = match Tokenizer::new(&dialect, definition).tokenize() {
+    let tokens = match tokenize_with_location(definition) {
         Ok(t) => t,
         Err(_) => {
             // Fallback: return empty string if tokenization fails
@@ -298,16 +310,16 @@ fn extract_view_header(definition: &str) -> String {
     let mut paren_depth: i32 = 0;
     let mut found_view = false;
 
-    for (i, token) in tokens.iter().enumerate() {
-        match token {
+    for token in &tokens {
+        match &token.token {
             Token::LParen => paren_depth += 1,
             Token::RParen => paren_depth = paren_depth.saturating_sub(1),
             Token::Word(w) if w.keyword == Keyword::VIEW => {
                 found_view = true;
             }
             Token::Word(w) if w.keyword == Keyword::AS && paren_depth == 0 && found_view => {
-                // Found the AS keyword - return everything up to and including it
-                return reconstruct_tokens(&tokens[..=i]);
+                // Found the AS keyword - return the source text up to and including it
+                return definition[..location_to_offset(definition, token.span.end)].to_string();
             }
             _ => {}
         }
@@ -319,46 +331,26 @@ fn extract_view_header(definition: &str) -> String {
 
 /// Write SysCommentsObjectAnnotation for a view.
 /// DotNet emits this annotation with Length, StartLine, StartColumn, HeaderContents, and FooterContents.
-fn write_view_annotation<W: Write>(writer: &mut Writer<W>, definition: &str) -> anyhow::Result<()> {
-    // Normalize the definition to have consistent line endings
-    let normalized_def = normalize_script_content(definition);
-
+fn write_view_annotation<W: Write>(
+    writer: &mut Writer<W>,
+    definition: &str,
+    source: Option<&SourceLocation>,
+) -> anyhow::Result<()> {
     // Extract header (CREATE VIEW ... AS)
-    let header = extract_view_header(&normalized_def);
+    let header = extract_view_header(definition);
     if header.is_empty() {
         // If we can't extract the header, skip the annotation
         return Ok(());
     }
 
-    // Calculate total length
-    let total_length = normalized_def.len();
-
     // Detect trailing semicolon for FooterContents
-    let footer = if normalized_def.trim_end().ends_with(';') {
+    let footer = if definition.trim_end().ends_with(';') {
         ";"
     } else {
         ""
     };
 
-    // Write the annotation
-    let annotation =
-        BytesStart::new("Annotation").with_attributes([("Type", "SysCommentsObjectAnnotation")]);
-    writer.write_event(Event::Start(annotation))?;
-
-    write_property(writer, "Length", &total_length.to_string())?;
-    write_property(writer, "StartLine", "1")?;
-    write_property(writer, "StartColumn", "1")?;
-    // Escape newlines for XML attribute value (DotNet uses &#xA; for newlines)
-    // Use write_property_raw to avoid double-escaping the & in &#xA;
-    let escaped_header = escape_newlines_for_attr(&header);
-    write_property_raw(writer, "HeaderContents", &escaped_header)?;
-    if !footer.is_empty() {
-        write_property(writer, "FooterContents", footer)?;
-    }
-
-    writer.write_event(Event::End(BytesEnd::new("Annotation")))?;
-
-    Ok(())
+    write_sys_comments_annotation(writer, definition, &header, footer, source)
 }
 
 /// Expand SELECT * to actual table columns using the database model
@@ -380,7 +372,7 @@ fn expand_select_star(
             if let Some(ModelElement::Table(table)) = model.elements.get(idx) {
                 // Add each column from the table
                 for col in &table.columns {
-                    let col_ref = format!("{}.[{}]", table_ref, col.name);
+                    let col_ref = format!("{}.[{}]", table_ref, escape_identifier(&col.name));
                     columns.push(ViewColumn {
                         name: col.name.clone(),
                         source_ref: Some(col_ref),
@@ -543,7 +535,7 @@ pub(crate) fn write_view_columns<W: Write>(
     for col in columns {
         writer.write_event(Event::Start(BytesStart::new("Entry")))?;
 
-        let col_full_name = format!("{}.[{}]", view_full_name, col.name);
+        let col_full_name = format!("{}.[{}]", view_full_name, escape_identifier(&col.name));
         // Use with_attributes for batched attribute setting (Phase 16.3.3 optimization)
         let elem = BytesStart::new("Element").with_attributes([
             ("Type", "SqlComputedColumn"),
@@ -626,7 +618,12 @@ fn write_view_cte_dynamic_objects<W: Write>(
         writer.write_event(Event::Start(BytesStart::new("Entry")))?;
 
         // Element name: [schema].[view].[CTEn].[cte_name]
-        let cte_source_name = format!("{}.[CTE{}].[{}]", full_name, cte.cte_number, cte.name);
+        let cte_source_name = format!(
+            "{}.[CTE{}].[{}]",
+            full_name,
+            cte.cte_number,
+            escape_identifier(&cte.name)
+        );
 
         let elem = BytesStart::new("Element").with_attributes([
             ("Type", "SqlDynamicColumnSource"),
@@ -659,7 +656,7 @@ fn write_cte_columns_for_view<W: Write>(
     for col in columns {
         writer.write_event(Event::Start(BytesStart::new("Entry")))?;
 
-        let col_full_name = format!("{}.[{}]", cte_source_name, col.name);
+        let col_full_name = format!("{}.[{}]", cte_source_name, escape_identifier(&col.name));
         let col_elem = BytesStart::new("Element").with_attributes([
             ("Type", "SqlComputedColumn"),
             ("Name", col_full_name.as_str()),
@@ -739,6 +736,15 @@ mod tests {
         assert!(result.contains("[dbo].[Table1]"));
     }
 
+    #[test]
+    fn test_extract_view_query_keeps_string_literals() {
+        let definition = "CREATE VIEW [dbo].[V] AS SELECT N'it''s' AS [A], 'x''y' AS [B];";
+        assert_eq!(
+            extract_view_query(definition),
+            " SELECT N'it''s' AS [A], 'x''y' AS [B]"
+        );
+    }
+
     #[test]
     fn test_write_view_columns_single() {
         let mut writer = create_test_writer();
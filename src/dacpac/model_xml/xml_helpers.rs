@@ -11,6 +11,11 @@ use quick_xml::Writer;
 use std::borrow::Cow;
 use std::io::Write;
 
+use crate::model::SourceLocation;
+use crate::parser::identifier_utils::escape_identifier;
+use crate::parser::lexer::{tokenize_with_location, Token};
+use crate::parser::token_parser_base::location_to_offset;
+
 /// Built-in schemas that exist by default in SQL Server
 pub(crate) const BUILTIN_SCHEMAS: &[&str] = &[
     "dbo",
@@ -49,13 +54,83 @@ pub(crate) fn write_property<W: Write>(
     Ok(())
 }
 
-/// Normalize script content for consistent output.
+/// Write the SET options a module (view, procedure, function or trigger) was created with.
 ///
-/// DotNet DacFx normalizes line endings in script content to LF (Unix-style).
-/// This ensures consistent output regardless of the source file's line endings.
-pub(crate) fn normalize_script_content(script: &str) -> String {
-    // Convert CRLF to LF for consistent line endings
-    script.replace("\r\n", "\n")
+/// DacFx defaults IsAnsiNullsOn to False and IsQuotedIdentifierOn to True, so each is only
+/// written when the setting differs: `IsAnsiNullsOn="True"` and `IsQuotedIdentifierOn="False"`.
+pub(crate) fn write_set_options<W: Write>(
+    writer: &mut Writer<W>,
+    ansi_nulls: bool,
+    quoted_identifier: bool,
+) -> anyhow::Result<()> {
+    if ansi_nulls {
+        write_property(writer, "IsAnsiNullsOn", "True")?;
+    }
+    if !quoted_identifier {
+        write_property(writer, "IsQuotedIdentifierOn", "False")?;
+    }
+    Ok(())
+}
+
+/// Write the SysCommentsObjectAnnotation DacFx adds to scripted modules (views,
+/// procedures, functions and triggers), locating the module's script in its source file.
+///
+/// `definition` is the whole script, `header` the text before the module's body and
+/// `footer` the text after it (a view's trailing `;`), both written as given. Like
+/// DotNet, the script starts where its batch does: `source` supplies the blank lines
+/// before the definition (see [`SourceLocation::leading_whitespace`]), which are part of
+/// the header and Length, and the line they start on. Length and CreateOffset (the
+/// offset of CREATE past leading blank lines and comments, only written when there are
+/// some) count UTF-16 code units, as .NET does.
+pub(crate) fn write_sys_comments_annotation<W: Write>(
+    writer: &mut Writer<W>,
+    definition: &str,
+    header: &str,
+    footer: &str,
+    source: Option<&SourceLocation>,
+) -> anyhow::Result<()> {
+    let leading = source.map_or("", |source| source.leading_whitespace.as_str());
+    let start_line = source.map_or(1, |source| {
+        source.start_line - leading.matches('\n').count()
+    });
+    let leading_length = leading.encode_utf16().count();
+
+    let annotation =
+        BytesStart::new("Annotation").with_attributes([("Type", "SysCommentsObjectAnnotation")]);
+    writer.write_event(Event::Start(annotation))?;
+
+    let create_offset = leading_length
+        + definition[..find_create_offset(definition)]
+            .encode_utf16()
+            .count();
+    if create_offset > 0 {
+        write_property(writer, "CreateOffset", &create_offset.to_string())?;
+    }
+    let length = leading_length + definition.encode_utf16().count();
+    write_property(writer, "Length", &length.to_string())?;
+    write_property(writer, "StartLine", &start_line.to_string())?;
+    write_property(writer, "StartColumn", "1")?;
+    // Use write_property_raw to avoid double-escaping the & in &#xA;
+    let header = format!("{}{}", leading, header);
+    write_property_raw(writer, "HeaderContents", &escape_newlines_for_attr(&header))?;
+    if !footer.is_empty() {
+        write_property_raw(writer, "FooterContents", &escape_newlines_for_attr(footer))?;
+    }
+
+    writer.write_event(Event::End(BytesEnd::new("Annotation")))?;
+    Ok(())
+}
+
+/// Byte offset of the first token of `definition` that is not a comment (its CREATE or
+/// ALTER), or 0 if it cannot be tokenized
+fn find_create_offset(definition: &str) -> usize {
+    let Ok(tokens) = tokenize_with_location(definition) else {
+        return 0;
+    };
+    tokens
+        .iter()
+        .find(|token| !matches!(token.token, Token::Whitespace(_)))
+        .map_or(0, |token| location_to_offset(definition, token.span.start))
 }
 
 /// Escape a string for use in XML attribute values, including newlines.
@@ -67,6 +142,7 @@ pub(crate) fn normalize_script_content(script: &str) -> String {
 /// - `"` becomes `&quot;`
 /// - LF (\\n, 0x0A) becomes `&#xA;`
 /// - CR (\\r, 0x0D) becomes `&#xD;`
+/// - Tab (0x09) becomes `&#x9;`, which XML readers would otherwise normalize to a space
 ///
 /// DotNet DacFx uses XML numeric character references for newlines in attribute values.
 /// This function is used with `write_property_raw` to write pre-escaped values.
@@ -78,7 +154,10 @@ pub(crate) fn escape_newlines_for_attr(s: &str) -> String {
         .replace('>', "&gt;")
         .replace('"', "&quot;");
     // Then escape newlines
-    escaped.replace('\r', "&#xD;").replace('\n', "&#xA;")
+    escaped
+        .replace('\r', "&#xD;")
+        .replace('\n', "&#xA;")
+        .replace('\t', "&#x9;")
 }
 
 /// Write a Property element with a pre-escaped (raw) value that won't be double-escaped.
@@ -106,6 +185,9 @@ pub(crate) fn write_property_raw<W: Write>(
 
 /// Write a property with a CDATA value (for script content like QueryScript, BodyScript).
 ///
+/// The script is written as given: line endings are settled when the source is read
+/// (see [`crate::parser::LineEndings`]), so CDATA and header properties agree.
+///
 /// Generates:
 /// ```xml
 /// <Property Name="name">
@@ -121,18 +203,51 @@ pub(crate) fn write_script_property<W: Write>(
     let prop = BytesStart::new("Property").with_attributes([("Name", name)]);
     writer.write_event(Event::Start(prop))?;
 
-    // Normalize line endings before writing
-    let normalized_script = normalize_script_content(script);
-
     // Write Value element with CDATA content
     writer.write_event(Event::Start(BytesStart::new("Value")))?;
-    writer.write_event(Event::CData(BytesCData::new(&normalized_script)))?;
+    writer.write_event(Event::CData(BytesCData::new(script)))?;
     writer.write_event(Event::End(BytesEnd::new("Value")))?;
 
     writer.write_event(Event::End(BytesEnd::new("Property")))?;
     Ok(())
 }
 
+/// `xml` with its CDATA sections turned into escaped text, for
+/// [`crate::model::ScriptFormat::Escaped`]
+///
+/// Only script properties are written as CDATA, so this changes nothing else. `&`, `<`
+/// and `>` are escaped, and carriage returns become `&#xD;`, which XML readers would
+/// otherwise normalize away.
+pub(crate) fn escape_cdata_sections(xml: &[u8]) -> Vec<u8> {
+    const OPEN: &[u8] = b"<![CDATA[";
+    const CLOSE: &[u8] = b"]]>";
+    let find = |haystack: &[u8], needle: &[u8]| {
+        haystack
+            .windows(needle.len())
+            .position(|window| window == needle)
+    };
+
+    let mut output = Vec::with_capacity(xml.len());
+    let mut rest = xml;
+    while let Some(start) = find(rest, OPEN) {
+        output.extend_from_slice(&rest[..start]);
+        let content = &rest[start + OPEN.len()..];
+        let end = find(content, CLOSE).unwrap_or(content.len());
+        for &byte in &content[..end] {
+            match byte {
+                b'&' => output.extend_from_slice(b"&amp;"),
+                b'<' => output.extend_from_slice(b"&lt;"),
+                b'>' => output.extend_from_slice(b"&gt;"),
+                b'\r' => output.extend_from_slice(b"&#xD;"),
+                _ => output.push(byte),
+            }
+        }
+        rest = &content[(end + CLOSE.len()).min(content.len())..];
+    }
+    output.extend_from_slice(rest);
+    output
+}
+
 /// Write a Relationship element with multiple entries.
 ///
 /// Generates:
@@ -222,7 +337,7 @@ pub(crate) fn write_schema_relationship<W: Write>(
 
     writer.write_event(Event::Start(BytesStart::new("Entry")))?;
 
-    let schema_ref = format!("[{}]", schema);
+    let schema_ref = format!("[{}]", escape_identifier(schema));
     // Conditional attribute - use with_attributes with appropriate attributes
     let refs = if is_builtin_schema(schema) {
         BytesStart::new("References").with_attributes([
@@ -240,6 +355,33 @@ pub(crate) fn write_schema_relationship<W: Write>(
     Ok(())
 }
 
+/// Write a Filegroup relationship, using ExternalSource="BuiltIns" for the PRIMARY filegroup.
+pub(crate) fn write_filegroup_relationship<W: Write>(
+    writer: &mut Writer<W>,
+    filegroup: &str,
+) -> anyhow::Result<()> {
+    let rel = BytesStart::new("Relationship").with_attributes([("Name", "Filegroup")]);
+    writer.write_event(Event::Start(rel))?;
+
+    writer.write_event(Event::Start(BytesStart::new("Entry")))?;
+
+    let filegroup_ref = format!("[{}]", escape_identifier(filegroup));
+    let refs = if filegroup.eq_ignore_ascii_case("PRIMARY") {
+        BytesStart::new("References").with_attributes([
+            ("ExternalSource", "BuiltIns"),
+            ("Name", filegroup_ref.as_str()),
+        ])
+    } else {
+        BytesStart::new("References").with_attributes([("Name", filegroup_ref.as_str())])
+    };
+    writer.write_event(Event::Empty(refs))?;
+
+    writer.write_event(Event::End(BytesEnd::new("Entry")))?;
+
+    writer.write_event(Event::End(BytesEnd::new("Relationship")))?;
+    Ok(())
+}
+
 /// Write TypeSpecifier relationship for sequences referencing a built-in type.
 ///
 /// Generates:
@@ -346,13 +488,6 @@ mod tests {
         assert_eq!(output, r#"<Property Name="TestName" Value="TestValue"/>"#);
     }
 
-    #[test]
-    fn test_normalize_script_content() {
-        assert_eq!(normalize_script_content("line1\r\nline2"), "line1\nline2");
-        assert_eq!(normalize_script_content("line1\nline2"), "line1\nline2");
-        assert_eq!(normalize_script_content("a\r\nb\r\nc"), "a\nb\nc");
-    }
-
     #[test]
     fn test_write_script_property() {
         let mut writer = create_test_writer();
@@ -365,6 +500,23 @@ mod tests {
         assert!(output.contains("</Property>"));
     }
 
+    #[test]
+    fn test_write_script_property_keeps_line_endings() {
+        let mut writer = create_test_writer();
+        write_script_property(&mut writer, "BodyScript", "SELECT 1\r\nSELECT 2").unwrap();
+        let output = get_output(writer);
+        assert!(output.contains("<![CDATA[SELECT 1\r\nSELECT 2]]>"));
+    }
+
+    #[test]
+    fn test_escape_cdata_sections() {
+        let xml = "<Property Name=\"BodyScript\">\n  <Value><![CDATA[IF @a < 1 AND @b > 2\r\n  SELECT 'x&y';]]></Value>\n</Property>\n<Property Name=\"IsAnsiNullsOn\" Value=\"True\" />";
+        assert_eq!(
+            String::from_utf8(escape_cdata_sections(xml.as_bytes())).unwrap(),
+            "<Property Name=\"BodyScript\">\n  <Value>IF @a &lt; 1 AND @b &gt; 2&#xD;\n  SELECT 'x&amp;y';</Value>\n</Property>\n<Property Name=\"IsAnsiNullsOn\" Value=\"True\" />"
+        );
+    }
+
     #[test]
     fn test_write_relationship() {
         let mut writer = create_test_writer();
@@ -15,7 +15,11 @@ const PRODUCT_NAME: &str = "rust-sqlpackage";
 /// Product version for rust-sqlpackage
 const PRODUCT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-pub fn generate_origin_xml<W: Write>(writer: W, model_xml_checksum: &str) -> anyhow::Result<()> {
+pub fn generate_origin_xml<W: Write>(
+    writer: W,
+    model_xml_checksum: &str,
+    model_schema_version: &str,
+) -> anyhow::Result<()> {
     let mut xml_writer = Writer::new_with_indent(writer, b' ', 2);
     // Add space before /> in self-closing tags to match DotNet DacFx output
     xml_writer
@@ -82,7 +86,7 @@ pub fn generate_origin_xml<W: Write>(writer: W, model_xml_checksum: &str) -> any
     xml_writer.write_event(Event::End(BytesEnd::new("Checksums")))?;
 
     // ModelSchemaVersion (after Checksums, matches DotNet behavior)
-    write_element(&mut xml_writer, "ModelSchemaVersion", "2.9")?;
+    write_element(&mut xml_writer, "ModelSchemaVersion", model_schema_version)?;
 
     // Close root
     xml_writer.write_event(Event::End(BytesEnd::new("DacOrigin")))?;
@@ -0,0 +1,290 @@
+//! Organization-wide overrides of the emitted model.xml
+//!
+//! Some downstream tools choke on details of DacFx's output that a project cannot
+//! change, such as a property DacFx leaves out when it has its default value or an
+//! annotation they do not understand. Overrides force such details on every build, from
+//! the `[build.overrides]` table of `.sqlpackage.toml`:
+//!
+//! ```toml
+//! [build.overrides]
+//! strip_annotations = ["SysCommentsObjectAnnotation"]  # "*" strips every annotation
+//!
+//! [[build.overrides.properties]]
+//! element_type = "SqlProcedure"   # "*" or left out: every element
+//! name = "IsQuotedIdentifierOn"
+//! value = "True"                  # left out: the property is removed
+//! ```
+//!
+//! Overrides are applied to the rendered model.xml, after DacFx parity output, so the
+//! model checksum in Origin.xml covers them.
+
+use serde::Deserialize;
+
+use crate::error::SqlPackageError;
+
+/// Changes forced on the emitted model.xml
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ModelOverrides {
+    /// Annotation types to leave out (`"*"` for all), with the attached annotations
+    /// pointing at them
+    pub strip_annotations: Vec<String>,
+    /// Properties set on (or removed from) elements, later entries winning
+    pub properties: Vec<PropertyOverride>,
+}
+
+/// A property forced on the elements of a type
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PropertyOverride {
+    /// Element type, e.g. `SqlProcedure`; `None` or `"*"` for every element
+    pub element_type: Option<String>,
+    /// Property name, e.g. `IsQuotedIdentifierOn`
+    pub name: String,
+    /// Value to set; `None` removes the property
+    pub value: Option<String>,
+}
+
+impl PropertyOverride {
+    fn applies_to(&self, element_type: &str) -> bool {
+        self.element_type
+            .as_deref()
+            .is_none_or(|t| t == "*" || t == element_type)
+    }
+}
+
+impl ModelOverrides {
+    /// Whether the overrides leave model.xml as generated
+    pub fn is_empty(&self) -> bool {
+        self.strip_annotations.is_empty() && self.properties.is_empty()
+    }
+
+    fn strips(&self, annotation_type: Option<&str>) -> bool {
+        self.strip_annotations
+            .iter()
+            .any(|t| t == "*" || Some(t.as_str()) == annotation_type)
+    }
+
+    /// `xml` with the overrides applied
+    pub fn apply(&self, xml: &[u8]) -> Result<Vec<u8>, SqlPackageError> {
+        if self.is_empty() {
+            return Ok(xml.to_vec());
+        }
+        let invalid = |message: String| SqlPackageError::DacpacCreationError {
+            message: format!("cannot apply model overrides: {}", message),
+        };
+        let text = std::str::from_utf8(xml).map_err(|e| invalid(e.to_string()))?;
+        let document = roxmltree::Document::parse(text).map_err(|e| invalid(e.to_string()))?;
+
+        // (start, end, replacement) in the original text
+        let mut edits: Vec<(usize, usize, String)> = Vec::new();
+
+        let stripped: Vec<&str> = document
+            .descendants()
+            .filter(|n| n.has_tag_name("Annotation") && self.strips(n.attribute("Type")))
+            .filter_map(|n| n.attribute("Disambiguator"))
+            .collect();
+        for node in document.descendants() {
+            let strip = (node.has_tag_name("Annotation") && self.strips(node.attribute("Type")))
+                || (node.has_tag_name("AttachedAnnotation")
+                    && (self.strips(None)
+                        || node
+                            .attribute("Disambiguator")
+                            .is_some_and(|d| stripped.contains(&d))));
+            if strip {
+                edits.push((
+                    line_start(text, node.range().start),
+                    node.range().end,
+                    String::new(),
+                ));
+            }
+        }
+
+        for element in document.descendants().filter(|n| n.has_tag_name("Element")) {
+            let element_type = element.attribute("Type").unwrap_or_default();
+            // Later overrides of a property win
+            let mut forced: Vec<(&str, Option<&str>)> = Vec::new();
+            for property in self
+                .properties
+                .iter()
+                .filter(|p| p.applies_to(element_type))
+            {
+                forced.retain(|(name, _)| *name != property.name);
+                forced.push((&property.name, property.value.as_deref()));
+            }
+            if forced.is_empty() {
+                continue;
+            }
+
+            let indent = indentation(text, element.range().start);
+            let children: Vec<_> = element.children().filter(|n| n.is_element()).collect();
+            let mut added = String::new();
+            for (name, value) in forced {
+                let existing = children
+                    .iter()
+                    .find(|n| n.has_tag_name("Property") && n.attribute("Name") == Some(name));
+                match (existing, value) {
+                    (Some(node), Some(value)) => {
+                        edits.push((node.range().start, node.range().end, property(name, value)))
+                    }
+                    (Some(node), None) => edits.push((
+                        line_start(text, node.range().start),
+                        node.range().end,
+                        String::new(),
+                    )),
+                    (None, Some(value)) => {
+                        added.push_str(&format!("\n{}  {}", indent, property(name, value)))
+                    }
+                    (None, None) => {}
+                }
+            }
+            if added.is_empty() {
+                continue;
+            }
+
+            // New properties go after the existing ones, or first if there are none
+            let last_property = children.iter().rev().find(|n| n.has_tag_name("Property"));
+            let range = element.range();
+            if let Some(node) = last_property.or(children.first()) {
+                let at = if last_property.is_some() {
+                    node.range().end
+                } else {
+                    line_start(text, node.range().start)
+                };
+                edits.push((at, at, added));
+            } else if text[range.clone()].ends_with("/>") {
+                let open_end = range.start + text[range.start..range.end - 2].trim_end().len();
+                edits.push((
+                    open_end,
+                    range.end,
+                    format!(">{}\n{}</Element>", added, indent),
+                ));
+            } else {
+                let at = range.start + text[range.clone()].rfind("</").unwrap_or(0);
+                edits.push((at, at, format!("{}\n{}", added, indent)));
+            }
+        }
+
+        // Apply from the end so earlier offsets stay valid; a removal starting where an
+        // insertion does is applied first, keeping the insertion
+        edits.sort_by_key(|(start, end, _)| (*start, *end));
+        let mut output = text.to_string();
+        for (start, end, replacement) in edits.into_iter().rev() {
+            output.replace_range(start..end, &replacement);
+        }
+        Ok(output.into_bytes())
+    }
+}
+
+/// `<Property Name=".." Value=".." />` as the model.xml writer formats it
+fn property(name: &str, value: &str) -> String {
+    format!(
+        r#"<Property Name="{}" Value="{}" />"#,
+        quick_xml::escape::escape(name),
+        quick_xml::escape::escape(value)
+    )
+}
+
+/// Whitespace before the node starting at `pos` on its line
+fn indentation(text: &str, pos: usize) -> &str {
+    let start = text[..pos].rfind('\n').map_or(0, |i| i + 1);
+    &text[start..pos]
+}
+
+/// Where to start removing the node at `pos` so its line goes with it: the newline
+/// before it when only whitespace separates them, `pos` otherwise
+fn line_start(text: &str, pos: usize) -> usize {
+    match text[..pos].rfind('\n') {
+        Some(newline) if text[newline + 1..pos].trim().is_empty() => newline,
+        _ => pos,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODEL: &str = r#"<Model>
+  <Element Type="SqlPrimaryKeyConstraint">
+    <Annotation Type="SqlInlineConstraintAnnotation" Disambiguator="3" />
+  </Element>
+  <Element Type="SqlProcedure" Name="[dbo].[P]">
+    <Property Name="BodyScript">
+      <Value><![CDATA[SELECT 1;]]></Value>
+    </Property>
+    <Property Name="IsAnsiNullsOn" Value="True" />
+    <Annotation Type="SysCommentsObjectAnnotation">
+      <Property Name="Length" Value="35" />
+    </Annotation>
+  </Element>
+  <Element Type="SqlTable" Name="[dbo].[T]">
+    <Relationship Name="Columns">
+      <Entry>
+        <Element Type="SqlSimpleColumn" Name="[dbo].[T].[Id]">
+          <AttachedAnnotation Disambiguator="3" />
+        </Element>
+      </Entry>
+    </Relationship>
+  </Element>
+  <Element Type="SqlSchema" Name="[Sales]" />
+</Model>"#;
+
+    #[test]
+    fn test_apply_overrides() {
+        let overrides = ModelOverrides {
+            strip_annotations: vec!["SysCommentsObjectAnnotation".to_string()],
+            properties: vec![
+                PropertyOverride {
+                    element_type: Some("SqlProcedure".to_string()),
+                    name: "IsQuotedIdentifierOn".to_string(),
+                    value: Some("True".to_string()),
+                },
+                PropertyOverride {
+                    element_type: Some("SqlProcedure".to_string()),
+                    name: "IsAnsiNullsOn".to_string(),
+                    value: None,
+                },
+                PropertyOverride {
+                    element_type: Some("SqlSchema".to_string()),
+                    name: "Owner".to_string(),
+                    value: Some("a&b".to_string()),
+                },
+            ],
+        };
+        let output = String::from_utf8(overrides.apply(MODEL.as_bytes()).unwrap()).unwrap();
+        assert_eq!(
+            output,
+            r#"<Model>
+  <Element Type="SqlPrimaryKeyConstraint">
+    <Annotation Type="SqlInlineConstraintAnnotation" Disambiguator="3" />
+  </Element>
+  <Element Type="SqlProcedure" Name="[dbo].[P]">
+    <Property Name="BodyScript">
+      <Value><![CDATA[SELECT 1;]]></Value>
+    </Property>
+    <Property Name="IsQuotedIdentifierOn" Value="True" />
+  </Element>
+  <Element Type="SqlTable" Name="[dbo].[T]">
+    <Relationship Name="Columns">
+      <Entry>
+        <Element Type="SqlSimpleColumn" Name="[dbo].[T].[Id]">
+          <AttachedAnnotation Disambiguator="3" />
+        </Element>
+      </Entry>
+    </Relationship>
+  </Element>
+  <Element Type="SqlSchema" Name="[Sales]">
+    <Property Name="Owner" Value="a&amp;b" />
+  </Element>
+</Model>"#
+        );
+
+        let strip_all = ModelOverrides {
+            strip_annotations: vec!["*".to_string()],
+            ..Default::default()
+        };
+        let output = String::from_utf8(strip_all.apply(MODEL.as_bytes()).unwrap()).unwrap();
+        assert!(!output.contains("Annotation"));
+        assert!(output.contains("<Element Type=\"SqlPrimaryKeyConstraint\">\n  </Element>"));
+    }
+}
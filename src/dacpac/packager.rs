@@ -1,8 +1,9 @@
 //! Create dacpac ZIP package
 
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::{Cursor, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use sha2::{Digest, Sha256};
@@ -10,118 +11,222 @@ use zip::write::SimpleFileOptions;
 use zip::ZipWriter;
 
 use crate::error::SqlPackageError;
-use crate::model::DatabaseModel;
-use crate::parser::expand_includes;
+use crate::model::{DatabaseModel, ScriptFormat};
+use crate::parser::{apply_conditionals, decode_source, expand_includes_with_hashes, source_hash};
 use crate::project::SqlProject;
+use crate::util::long_path;
 
+use super::source_manifest::{generate_source_manifest, SOURCE_MANIFEST_PART};
 use super::{metadata_xml, model_xml, origin_xml};
 
 /// Create a dacpac file from the database model
+///
+/// The package is written to a temporary file next to `output_path` and renamed over it
+/// once complete, so a failed or killed build never leaves a truncated dacpac behind (an
+/// existing one is kept). The temporary file is removed when writing fails.
 pub fn create_dacpac(
     model: &DatabaseModel,
     project: &SqlProject,
     output_path: &Path,
 ) -> Result<()> {
+    write_atomically(output_path, |file| write_package(file, model, project))
+}
+
+/// The parts of a package whose model and metadata are already rendered
+pub(crate) struct PackageParts<'a> {
+    pub model_xml: &'a [u8],
+    pub metadata_xml: &'a [u8],
+    /// Model schema version, recorded in Origin.xml
+    pub schema_version: &'a str,
+    pub predeploy: Option<&'a str>,
+    pub postdeploy: Option<&'a str>,
+    /// SourceManifest.json, when the sources are recorded
+    pub source_manifest: Option<&'a [u8]>,
+}
+
+/// Create a dacpac from rendered parts, as [`create_dacpac`] does from a model
+pub(crate) fn create_dacpac_from_parts(parts: &PackageParts, output_path: &Path) -> Result<()> {
+    write_atomically(output_path, |file| write_parts(file, parts))
+}
+
+/// Write a package through a temporary file renamed over `output_path` once complete
+fn write_atomically(
+    output_path: &Path,
+    write_package: impl FnOnce(File) -> Result<File>,
+) -> Result<()> {
+    let write_error = |e| SqlPackageError::DacpacWriteError {
+        path: output_path.to_path_buf(),
+        source: e,
+    };
+
     // Ensure output directory exists
     if let Some(parent) = output_path.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| SqlPackageError::DacpacWriteError {
-            path: output_path.to_path_buf(),
-            source: e,
-        })?;
+        std::fs::create_dir_all(long_path(parent)).map_err(write_error)?;
     }
 
-    let file = File::create(output_path).map_err(|e| SqlPackageError::DacpacWriteError {
-        path: output_path.to_path_buf(),
-        source: e,
-    })?;
+    let temp_path = temp_path(output_path);
+    let write = || -> Result<()> {
+        let file = File::create(long_path(&temp_path)).map_err(write_error)?;
+        let file = write_package(file)?;
+        file.sync_all().map_err(write_error)?;
+        std::fs::rename(long_path(&temp_path), long_path(output_path)).map_err(write_error)?;
+        Ok(())
+    };
+    let result = write();
+    if result.is_err() {
+        let _ = std::fs::remove_file(long_path(&temp_path));
+    }
+    result
+}
+
+/// Hidden temporary file in the output directory, unique to this process
+fn temp_path(output_path: &Path) -> PathBuf {
+    let file_name = output_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    output_path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()))
+}
+
+/// Write the package parts to `file`, returning it once the ZIP is finished
+fn write_package(file: File, model: &DatabaseModel, project: &SqlProject) -> Result<File> {
+    let mut model_buffer = Cursor::new(Vec::with_capacity(model.elements.len() * 2000));
+    model_xml::generate_model_xml(&mut model_buffer, model, project)?;
+    let mut model_xml = model.overrides.apply(model_buffer.get_ref())?;
+    if model.script_format == ScriptFormat::Escaped {
+        model_xml = model_xml::escape_cdata_sections(&model_xml);
+    }
 
+    let mut metadata_buffer = Cursor::new(Vec::with_capacity(4096));
+    metadata_xml::generate_metadata_xml(&mut metadata_buffer, project, &project.dac_version)?;
+
+    // The manifest records the bytes that went into the package, as they were read
+    let mut source_hashes = model.source_hashes.clone();
+    let predeploy = project
+        .pre_deploy_script
+        .as_deref()
+        .map(|path| prepare_deploy_script(path, project, &mut source_hashes))
+        .transpose()?;
+    let postdeploy = project
+        .post_deploy_script
+        .as_deref()
+        .map(|path| prepare_deploy_script(path, project, &mut source_hashes))
+        .transpose()?;
+    let source_manifest = if model.source_manifest {
+        Some(generate_source_manifest(
+            &source_hashes,
+            &project.project_dir,
+        )?)
+    } else {
+        None
+    };
+
+    let parts = PackageParts {
+        model_xml: &model_xml,
+        metadata_xml: metadata_buffer.get_ref(),
+        schema_version: &model.schema_version,
+        predeploy: predeploy.as_deref(),
+        postdeploy: postdeploy.as_deref(),
+        source_manifest: source_manifest.as_deref(),
+    };
+    write_parts(file, &parts)
+}
+
+/// Write rendered parts to `file`, adding Origin.xml and [Content_Types].xml
+fn write_parts(file: File, parts: &PackageParts) -> Result<File> {
     let mut zip = ZipWriter::new(file);
     let options = SimpleFileOptions::default()
         .compression_method(zip::CompressionMethod::Deflated)
         .compression_level(Some(1));
 
     // Write model.xml
-    let mut model_buffer = Cursor::new(Vec::with_capacity(model.elements.len() * 2000));
-    model_xml::generate_model_xml(&mut model_buffer, model, project)?;
     zip.start_file("model.xml", options)?;
-    zip.write_all(model_buffer.get_ref())?;
+    zip.write_all(parts.model_xml)?;
 
     // Write DacMetadata.xml
-    let mut metadata_buffer = Cursor::new(Vec::with_capacity(4096));
-    metadata_xml::generate_metadata_xml(&mut metadata_buffer, project, &project.dac_version)?;
     zip.start_file("DacMetadata.xml", options)?;
-    zip.write_all(metadata_buffer.get_ref())?;
+    zip.write_all(parts.metadata_xml)?;
 
     // Compute SHA256 checksum of model.xml for Origin.xml
     let mut hasher = Sha256::new();
-    hasher.update(model_buffer.get_ref());
+    hasher.update(parts.model_xml);
     let model_checksum = format!("{:X}", hasher.finalize());
 
     // Write Origin.xml
     let mut origin_buffer = Cursor::new(Vec::with_capacity(4096));
-    origin_xml::generate_origin_xml(&mut origin_buffer, &model_checksum)?;
+    origin_xml::generate_origin_xml(&mut origin_buffer, &model_checksum, parts.schema_version)?;
     zip.start_file("Origin.xml", options)?;
     zip.write_all(origin_buffer.get_ref())?;
 
     // Write [Content_Types].xml (required for package format)
-    let has_deploy_scripts =
-        project.pre_deploy_script.is_some() || project.post_deploy_script.is_some();
-    let content_types = generate_content_types_xml(has_deploy_scripts);
+    let has_deploy_scripts = parts.predeploy.is_some() || parts.postdeploy.is_some();
+    let content_types =
+        generate_content_types_xml(has_deploy_scripts, parts.source_manifest.is_some());
     zip.start_file("[Content_Types].xml", options)?;
     zip.write_all(content_types.as_bytes())?;
 
-    // Write predeploy.sql (if present)
-    // Expands SQLCMD :r include directives to inline referenced files
-    // DotNet ensures deploy scripts end with a GO statement
-    if let Some(pre_deploy_path) = &project.pre_deploy_script {
-        let content = std::fs::read_to_string(pre_deploy_path).map_err(|e| {
-            SqlPackageError::SqlFileReadError {
-                path: pre_deploy_path.clone(),
-                source: e,
-            }
-        })?;
-        let expanded = expand_includes(&content, pre_deploy_path)?;
-        let normalized = ensure_trailing_go(&expanded);
-        zip.start_file("predeploy.sql", options)?;
-        zip.write_all(normalized.as_bytes())?;
+    // Write predeploy.sql and postdeploy.sql (if present)
+    for (script, name) in [
+        (parts.predeploy, "predeploy.sql"),
+        (parts.postdeploy, "postdeploy.sql"),
+    ] {
+        if let Some(content) = script {
+            zip.start_file(name, options)?;
+            zip.write_all(content.as_bytes())?;
+        }
     }
 
-    // Write postdeploy.sql (if present)
-    // Expands SQLCMD :r include directives to inline referenced files
-    // DotNet ensures deploy scripts end with a GO statement
-    if let Some(post_deploy_path) = &project.post_deploy_script {
-        let content = std::fs::read_to_string(post_deploy_path).map_err(|e| {
-            SqlPackageError::SqlFileReadError {
-                path: post_deploy_path.clone(),
-                source: e,
-            }
-        })?;
-        let expanded = expand_includes(&content, post_deploy_path)?;
-        let normalized = ensure_trailing_go(&expanded);
-        zip.start_file("postdeploy.sql", options)?;
-        zip.write_all(normalized.as_bytes())?;
+    if let Some(manifest) = parts.source_manifest {
+        zip.start_file(SOURCE_MANIFEST_PART, options)?;
+        zip.write_all(manifest)?;
     }
 
-    zip.finish()?;
-
-    Ok(())
+    Ok(zip.finish()?)
 }
 
-pub(crate) fn generate_content_types_xml(include_sql: bool) -> String {
-    if include_sql {
-        r#"<?xml version="1.0" encoding="utf-8"?>
-<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
-  <Default Extension="xml" ContentType="text/xml" />
-  <Default Extension="sql" ContentType="text/plain" />
-</Types>"#
-            .to_string()
+/// Read a deploy script for packaging
+///
+/// Expands SQLCMD `:r` include directives to inline referenced files (unless the project
+/// keeps them for deploy time), then evaluates `:IF` blocks. DotNet ensures deploy
+/// scripts end with a GO statement. The hashes of the script and the files it includes
+/// are added to `source_hashes`.
+fn prepare_deploy_script(
+    path: &Path,
+    project: &SqlProject,
+    source_hashes: &mut BTreeMap<PathBuf, String>,
+) -> Result<String> {
+    let read_error = |e| SqlPackageError::SqlFileReadError {
+        path: path.to_path_buf(),
+        source: e,
+    };
+    let bytes = std::fs::read(long_path(path)).map_err(read_error)?;
+    source_hashes.insert(path.to_path_buf(), source_hash(&bytes));
+    let content = decode_source(&bytes, project.default_file_encoding).map_err(read_error)?;
+    let expanded = if project.resolve_deploy_includes {
+        let (expanded, included) = expand_includes_with_hashes(&content, path)?;
+        source_hashes.extend(included);
+        expanded
     } else {
-        r#"<?xml version="1.0" encoding="utf-8"?>
+        content
+    };
+    let expanded = apply_conditionals(&expanded, &project.build_variables, path)?;
+    Ok(ensure_trailing_go(&expanded))
+}
+
+pub(crate) fn generate_content_types_xml(include_sql: bool, include_json: bool) -> String {
+    let mut xml = r#"<?xml version="1.0" encoding="utf-8"?>
 <Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
   <Default Extension="xml" ContentType="text/xml" />
-</Types>"#
-            .to_string()
+"#
+    .to_string();
+    if include_sql {
+        xml.push_str("  <Default Extension=\"sql\" ContentType=\"text/plain\" />\n");
+    }
+    if include_json {
+        xml.push_str("  <Default Extension=\"json\" ContentType=\"application/json\" />\n");
     }
+    xml.push_str("</Types>");
+    xml
 }
 
 /// Ensure deploy script content ends with a GO statement (matches DotNet behavior).
@@ -0,0 +1,105 @@
+//! Manifest of the source files a dacpac was built from
+//!
+//! With `--source-manifest`, the package gets a `SourceManifest.json` part mapping each
+//! SQL file and deploy script of the project, and each file a deploy script includes with
+//! `:r`, relative to the project directory, to the SHA256 of its content as it was read
+//! for the build. `verify-sources` checks a working tree against it, so an
+//! auditor can tell exactly which sources produced an artifact.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use crate::compare::reader::DacpacContents;
+use crate::error::SqlPackageError;
+use crate::parser::source_hash;
+use crate::util::long_path;
+
+/// Name of the manifest part in the package
+pub const SOURCE_MANIFEST_PART: &str = "SourceManifest.json";
+
+/// The manifest of the sources with `hashes` (SHA256, uppercase hex, from
+/// [`crate::parser::source_hash`]) as JSON, keyed by path relative to `project_dir`
+/// with `/` separators
+pub(crate) fn generate_source_manifest(
+    hashes: &BTreeMap<PathBuf, String>,
+    project_dir: &Path,
+) -> Result<Vec<u8>> {
+    let manifest: BTreeMap<String, &String> = hashes
+        .iter()
+        .map(|(path, hash)| (relative_name(path, project_dir), hash))
+        .collect();
+    Ok(serde_json::to_vec_pretty(&manifest)?)
+}
+
+/// Sources of a dacpac checked against a working tree
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceVerification {
+    /// Files whose content matches the manifest
+    pub verified: usize,
+    /// Files whose content differs from the manifest
+    pub changed: Vec<String>,
+    /// Files in the manifest missing from the working tree
+    pub missing: Vec<String>,
+}
+
+impl SourceVerification {
+    /// Whether every file in the manifest matches
+    pub fn is_ok(&self) -> bool {
+        self.changed.is_empty() && self.missing.is_empty()
+    }
+}
+
+/// Check the files under `project_dir` against the source manifest of the dacpac at
+/// `dacpac_path`
+pub fn verify_sources(dacpac_path: &Path, project_dir: &Path) -> Result<SourceVerification> {
+    let contents = DacpacContents::from_path(dacpac_path)?;
+    let invalid = |message: String| SqlPackageError::InvalidDacpac {
+        path: dacpac_path.to_path_buf(),
+        message,
+    };
+    let manifest = contents.get_string(SOURCE_MANIFEST_PART).ok_or_else(|| {
+        invalid(format!(
+            "no {} (build with --source-manifest)",
+            SOURCE_MANIFEST_PART
+        ))
+    })?;
+    let manifest: BTreeMap<String, String> = serde_json::from_str(&manifest)
+        .map_err(|e| invalid(format!("bad {}: {}", SOURCE_MANIFEST_PART, e)))?;
+
+    let mut verification = SourceVerification::default();
+    for (name, hash) in manifest {
+        let path: PathBuf = project_dir.join(&name);
+        if !long_path(&path).is_file() {
+            verification.missing.push(name);
+        } else if !file_hash(&path)?.eq_ignore_ascii_case(&hash) {
+            verification.changed.push(name);
+        } else {
+            verification.verified += 1;
+        }
+    }
+    Ok(verification)
+}
+
+/// `path` relative to `project_dir`, with `/` separators
+fn relative_name(path: &Path, project_dir: &Path) -> String {
+    // Included files are known by their canonical path
+    let canonical_dir = long_path(project_dir).canonicalize().ok();
+    path.strip_prefix(project_dir)
+        .ok()
+        .or_else(|| path.strip_prefix(canonical_dir.as_ref()?).ok())
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn file_hash(path: &Path) -> Result<String> {
+    let bytes = std::fs::read(long_path(path)).map_err(|e| SqlPackageError::SqlFileReadError {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+    Ok(source_hash(&bytes))
+}
@@ -0,0 +1,715 @@
+//! Schema documentation generator
+//!
+//! Renders a built `DatabaseModel` as Markdown or HTML: tables with their columns, types
+//! and constraints, views, procedures and functions with their parameters, and
+//! cross-reference links. Links come from foreign keys and from the same
+//! QueryDependencies/BodyDependencies extraction used for model.xml.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::dacpac::object_references;
+use crate::model::{
+    ConstraintElement, ConstraintType, DatabaseModel, FunctionElement, FunctionType, IndexElement,
+    ModelElement, ProcedureElement, TableElement, ViewElement,
+};
+use crate::model_dump::name_parts;
+use crate::parser::{
+    extract_function_parameters_tokens, extract_procedure_parameters_tokens, header_description,
+};
+use crate::project::SqlProject;
+
+/// Output format for schema documentation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DocsFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+impl std::str::FromStr for DocsFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "markdown" | "md" => Ok(DocsFormat::Markdown),
+            "html" => Ok(DocsFormat::Html),
+            _ => Err(format!("Unknown docs format: {}", s)),
+        }
+    }
+}
+
+impl DocsFormat {
+    /// File extension for documents in this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            DocsFormat::Markdown => "md",
+            DocsFormat::Html => "html",
+        }
+    }
+}
+
+/// Render schema documentation for `model` in the requested format
+pub fn render_docs(model: &DatabaseModel, project: &SqlProject, format: DocsFormat) -> String {
+    let index = DocsIndex::new(model, &project.default_schema);
+    match format {
+        DocsFormat::Markdown => {
+            let mut renderer = MarkdownRenderer::default();
+            render(&index, &project.name, &mut renderer);
+            renderer.out
+        }
+        DocsFormat::Html => {
+            let mut renderer = HtmlRenderer::default();
+            render(&index, &project.name, &mut renderer);
+            renderer.out
+        }
+    }
+}
+
+// =============================================================================
+// Model Index
+// =============================================================================
+
+/// Model elements grouped for documentation, with precomputed cross references
+struct DocsIndex<'a> {
+    tables: Vec<&'a TableElement>,
+    views: Vec<&'a ViewElement>,
+    procedures: Vec<&'a ProcedureElement>,
+    functions: Vec<&'a FunctionElement>,
+    /// Constraints keyed by owning table full name
+    constraints: HashMap<String, Vec<&'a ConstraintElement>>,
+    /// Indexes keyed by owning table full name
+    indexes: HashMap<String, Vec<&'a IndexElement>>,
+    /// Objects referenced by each view/procedure/function
+    references: BTreeMap<String, Vec<String>>,
+    /// Objects that reference each object (foreign keys and code dependencies)
+    referenced_by: HashMap<String, BTreeSet<String>>,
+    /// Full names of objects that get a section (and therefore an anchor)
+    documented: BTreeSet<String>,
+    /// Object descriptions: the MS_Description extended property, or else the header
+    /// comment of the object's file
+    descriptions: HashMap<String, String>,
+}
+
+impl<'a> DocsIndex<'a> {
+    fn new(model: &'a DatabaseModel, default_schema: &str) -> Self {
+        let mut index = DocsIndex {
+            tables: Vec::new(),
+            views: Vec::new(),
+            procedures: Vec::new(),
+            functions: Vec::new(),
+            constraints: HashMap::new(),
+            indexes: HashMap::new(),
+            references: object_references(model, default_schema),
+            referenced_by: HashMap::new(),
+            documented: BTreeSet::new(),
+            descriptions: HashMap::new(),
+        };
+
+        let mut properties = Vec::new();
+        for (position, element) in model.elements.iter().enumerate() {
+            match element {
+                ModelElement::Table(t) => index.tables.push(t),
+                ModelElement::View(v) => index.views.push(v),
+                ModelElement::Procedure(p) => index.procedures.push(p),
+                ModelElement::Function(f) => index.functions.push(f),
+                ModelElement::Constraint(c) => {
+                    let table = format!("[{}].[{}]", c.table_schema, c.table_name);
+                    if let (ConstraintType::ForeignKey, Some(target)) =
+                        (c.constraint_type, &c.referenced_table)
+                    {
+                        index
+                            .referenced_by
+                            .entry(target.clone())
+                            .or_default()
+                            .insert(table.clone());
+                    }
+                    index.constraints.entry(table).or_default().push(c);
+                    continue;
+                }
+                ModelElement::Index(i) => {
+                    let table = format!("[{}].[{}]", i.table_schema, i.table_name);
+                    index.indexes.entry(table).or_default().push(i);
+                    continue;
+                }
+                ModelElement::ExtendedProperty(e)
+                    if e.target_column.is_none()
+                        && e.property_name.eq_ignore_ascii_case("MS_Description") =>
+                {
+                    properties.push(e);
+                    continue;
+                }
+                _ => continue,
+            }
+            index.documented.insert(element.full_name());
+            if let (Some(header), Some((schema, name))) =
+                (model.header(position), element.schema_and_name())
+            {
+                index.descriptions.insert(
+                    format!("[{}].[{}]", schema, name),
+                    header_description(header),
+                );
+            }
+        }
+        for property in properties {
+            index.descriptions.insert(
+                format!("[{}].[{}]", property.target_schema, property.target_object),
+                property.property_value.clone(),
+            );
+        }
+
+        for (source, targets) in &index.references {
+            for target in targets {
+                index
+                    .referenced_by
+                    .entry(target.clone())
+                    .or_default()
+                    .insert(source.clone());
+            }
+        }
+
+        index
+            .tables
+            .sort_by_key(|t| (t.schema.clone(), t.name.clone()));
+        index
+            .views
+            .sort_by_key(|v| (v.schema.clone(), v.name.clone()));
+        index
+            .procedures
+            .sort_by_key(|p| (p.schema.clone(), p.name.clone()));
+        index
+            .functions
+            .sort_by_key(|f| (f.schema.clone(), f.name.clone()));
+        index
+    }
+}
+
+// =============================================================================
+// Renderers
+// =============================================================================
+
+/// Output primitives shared by the Markdown and HTML renderers.
+/// Cell, list and paragraph text is expected to be escaped/formatted already.
+trait DocRenderer {
+    fn begin(&mut self, title: &str);
+    fn end(&mut self);
+    fn heading(&mut self, level: usize, text: &str, anchor: Option<&str>);
+    fn paragraph(&mut self, text: &str);
+    fn table(&mut self, headers: &[&str], rows: &[Vec<String>]);
+    fn list(&mut self, items: &[String]);
+    fn escape(&self, text: &str) -> String;
+    fn code(&self, text: &str) -> String;
+    fn link(&self, text: &str, anchor: &str) -> String;
+}
+
+#[derive(Default)]
+struct MarkdownRenderer {
+    out: String,
+}
+
+impl DocRenderer for MarkdownRenderer {
+    fn begin(&mut self, title: &str) {
+        self.out.push_str(&format!("# {}\n\n", self.escape(title)));
+    }
+
+    fn end(&mut self) {}
+
+    fn heading(&mut self, level: usize, text: &str, anchor: Option<&str>) {
+        if let Some(anchor) = anchor {
+            self.out.push_str(&format!("<a id=\"{}\"></a>\n\n", anchor));
+        }
+        self.out
+            .push_str(&format!("{} {}\n\n", "#".repeat(level), self.escape(text)));
+    }
+
+    fn paragraph(&mut self, text: &str) {
+        self.out.push_str(&format!("{}\n\n", text));
+    }
+
+    fn table(&mut self, headers: &[&str], rows: &[Vec<String>]) {
+        self.out.push_str(&format!("| {} |\n", headers.join(" | ")));
+        self.out
+            .push_str(&format!("|{}\n", " --- |".repeat(headers.len())));
+        for row in rows {
+            self.out.push_str(&format!("| {} |\n", row.join(" | ")));
+        }
+        self.out.push('\n');
+    }
+
+    fn list(&mut self, items: &[String]) {
+        for item in items {
+            self.out.push_str(&format!("- {}\n", item));
+        }
+        self.out.push('\n');
+    }
+
+    fn escape(&self, text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '\\' | '|' | '*' | '`' | '[' | ']' | '<' | '>' => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                '\r' => {}
+                '\n' => escaped.push(' '),
+                _ => escaped.push(c),
+            }
+        }
+        escaped
+    }
+
+    fn code(&self, text: &str) -> String {
+        let text = text.replace(['\r', '\n'], " ").replace('|', "\\|");
+        if text.contains('`') {
+            format!("`` {} ``", text)
+        } else {
+            format!("`{}`", text)
+        }
+    }
+
+    fn link(&self, text: &str, anchor: &str) -> String {
+        format!("[{}](#{})", self.escape(text), anchor)
+    }
+}
+
+#[derive(Default)]
+struct HtmlRenderer {
+    out: String,
+}
+
+impl DocRenderer for HtmlRenderer {
+    fn begin(&mut self, title: &str) {
+        let title = self.escape(title);
+        self.out
+            .push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        self.out.push_str(&format!("<title>{}</title>\n", title));
+        self.out.push_str(
+            "<style>body{font-family:sans-serif;margin:2em}table{border-collapse:collapse}\
+             th,td{border:1px solid #ccc;padding:4px 8px;text-align:left}</style>\n",
+        );
+        self.out.push_str("</head>\n<body>\n");
+        self.out.push_str(&format!("<h1>{}</h1>\n", title));
+    }
+
+    fn end(&mut self) {
+        self.out.push_str("</body>\n</html>\n");
+    }
+
+    fn heading(&mut self, level: usize, text: &str, anchor: Option<&str>) {
+        let id = anchor.map(|a| format!(" id=\"{}\"", a)).unwrap_or_default();
+        self.out
+            .push_str(&format!("<h{level}{id}>{}</h{level}>\n", self.escape(text)));
+    }
+
+    fn paragraph(&mut self, text: &str) {
+        self.out.push_str(&format!("<p>{}</p>\n", text));
+    }
+
+    fn table(&mut self, headers: &[&str], rows: &[Vec<String>]) {
+        self.out.push_str("<table>\n<tr>");
+        for header in headers {
+            self.out.push_str(&format!("<th>{}</th>", header));
+        }
+        self.out.push_str("</tr>\n");
+        for row in rows {
+            self.out.push_str("<tr>");
+            for cell in row {
+                self.out.push_str(&format!("<td>{}</td>", cell));
+            }
+            self.out.push_str("</tr>\n");
+        }
+        self.out.push_str("</table>\n");
+    }
+
+    fn list(&mut self, items: &[String]) {
+        self.out.push_str("<ul>\n");
+        for item in items {
+            self.out.push_str(&format!("<li>{}</li>\n", item));
+        }
+        self.out.push_str("</ul>\n");
+    }
+
+    fn escape(&self, text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+
+    fn code(&self, text: &str) -> String {
+        format!("<code>{}</code>", self.escape(text))
+    }
+
+    fn link(&self, text: &str, anchor: &str) -> String {
+        format!("<a href=\"#{}\">{}</a>", anchor, self.escape(text))
+    }
+}
+
+// =============================================================================
+// Document Layout
+// =============================================================================
+
+fn render<R: DocRenderer>(index: &DocsIndex, title: &str, r: &mut R) {
+    r.begin(title);
+
+    if !index.tables.is_empty() {
+        r.heading(2, "Tables", None);
+        for table in &index.tables {
+            render_table(index, table, r);
+        }
+    }
+
+    if !index.views.is_empty() {
+        r.heading(2, "Views", None);
+        for view in &index.views {
+            let full_name = format!("[{}].[{}]", view.schema, view.name);
+            r.heading(3, &display_name(&full_name), Some(&anchor(&full_name)));
+            render_description(index, &full_name, r);
+            render_cross_references(index, &full_name, r);
+        }
+    }
+
+    if !index.procedures.is_empty() {
+        r.heading(2, "Stored Procedures", None);
+        for proc in &index.procedures {
+            render_procedure(index, proc, r);
+        }
+    }
+
+    if !index.functions.is_empty() {
+        r.heading(2, "Functions", None);
+        for func in &index.functions {
+            render_function(index, func, r);
+        }
+    }
+
+    r.end();
+}
+
+fn render_table<R: DocRenderer>(index: &DocsIndex, table: &TableElement, r: &mut R) {
+    let full_name = format!("[{}].[{}]", table.schema, table.name);
+    r.heading(3, &display_name(&full_name), Some(&anchor(&full_name)));
+    render_description(index, &full_name, r);
+
+    let constraints = index
+        .constraints
+        .get(&full_name)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let constraint_columns = |kind: ConstraintType| -> BTreeSet<String> {
+        constraints
+            .iter()
+            .filter(|c| c.constraint_type == kind)
+            .flat_map(|c| c.columns.iter().map(|col| col.name.to_lowercase()))
+            .collect()
+    };
+    let pk_columns = constraint_columns(ConstraintType::PrimaryKey);
+
+    let rows: Vec<Vec<String>> = table
+        .columns
+        .iter()
+        .map(|col| {
+            let is_pk = pk_columns.contains(&col.name.to_lowercase());
+            let default = col.default_value.clone().or_else(|| {
+                constraints
+                    .iter()
+                    .find(|c| {
+                        c.constraint_type == ConstraintType::Default
+                            && c.columns
+                                .first()
+                                .is_some_and(|first| first.name.eq_ignore_ascii_case(&col.name))
+                    })
+                    .and_then(|c| c.definition.clone())
+            });
+
+            let mut notes = Vec::new();
+            if is_pk {
+                notes.push("Primary key".to_string());
+            }
+            if col.is_identity {
+                notes.push("Identity".to_string());
+            }
+            for fk in constraints.iter().filter(|c| {
+                c.constraint_type == ConstraintType::ForeignKey
+                    && c.columns
+                        .iter()
+                        .any(|fc| fc.name.eq_ignore_ascii_case(&col.name))
+            }) {
+                if let Some(target) = &fk.referenced_table {
+                    notes.push(format!("References {}", object_link(index, target, r)));
+                }
+            }
+            if let Some(expr) = &col.computed_expression {
+                let persisted = if col.is_persisted { " (persisted)" } else { "" };
+                notes.push(format!("Computed: {}{}", r.code(expr), persisted));
+            }
+            if col.is_sparse {
+                notes.push("Sparse".to_string());
+            }
+            if col.is_rowguidcol {
+                notes.push("ROWGUIDCOL".to_string());
+            }
+            if let Some(mask) = &col.masking_function {
+                notes.push(format!("Masked: {}", r.code(mask)));
+            }
+
+            let nullable = if is_pk || col.nullability == Some(false) {
+                "No"
+            } else {
+                "Yes"
+            };
+            vec![
+                r.escape(&col.name),
+                if col.computed_expression.is_some() {
+                    String::new()
+                } else {
+                    r.code(&col.data_type)
+                },
+                nullable.to_string(),
+                default.map(|d| r.code(&d)).unwrap_or_default(),
+                notes.join("; "),
+            ]
+        })
+        .collect();
+    r.table(&["Column", "Type", "Nullable", "Default", "Notes"], &rows);
+
+    let constraint_items: Vec<String> = constraints
+        .iter()
+        .filter(|c| c.constraint_type != ConstraintType::Default)
+        .map(|c| describe_constraint(index, c, r))
+        .collect();
+    if !constraint_items.is_empty() {
+        r.heading(4, "Constraints", None);
+        r.list(&constraint_items);
+    }
+
+    if let Some(indexes) = index.indexes.get(&full_name) {
+        let items: Vec<String> = indexes
+            .iter()
+            .map(|i| {
+                let mut kind = Vec::new();
+                if i.is_unique {
+                    kind.push("unique");
+                }
+                kind.push(if i.is_clustered {
+                    "clustered"
+                } else {
+                    "nonclustered"
+                });
+                let columns: Vec<&str> = i.columns.iter().map(|c| c.name.as_str()).collect();
+                format!(
+                    "{} ({}) on {}",
+                    r.escape(&i.name),
+                    kind.join(", "),
+                    r.escape(&columns.join(", "))
+                )
+            })
+            .collect();
+        r.heading(4, "Indexes", None);
+        r.list(&items);
+    }
+
+    render_cross_references(index, &full_name, r);
+}
+
+fn describe_constraint<R: DocRenderer>(
+    index: &DocsIndex,
+    constraint: &ConstraintElement,
+    r: &R,
+) -> String {
+    let columns: Vec<&str> = constraint.columns.iter().map(|c| c.name.as_str()).collect();
+    let columns = r.escape(&columns.join(", "));
+    let body = match constraint.constraint_type {
+        ConstraintType::PrimaryKey => format!("PRIMARY KEY ({})", columns),
+        ConstraintType::Unique => format!("UNIQUE ({})", columns),
+        ConstraintType::ForeignKey => {
+            let target = constraint
+                .referenced_table
+                .as_deref()
+                .map(|t| object_link(index, t, r))
+                .unwrap_or_default();
+            let referenced = constraint
+                .referenced_columns
+                .as_ref()
+                .map(|cols| r.escape(&cols.join(", ")))
+                .unwrap_or_default();
+            format!(
+                "FOREIGN KEY ({}) REFERENCES {} ({})",
+                columns, target, referenced
+            )
+        }
+        ConstraintType::Check => format!(
+            "CHECK {}",
+            r.code(constraint.definition.as_deref().unwrap_or_default())
+        ),
+        ConstraintType::Default => format!(
+            "DEFAULT {}",
+            r.code(constraint.definition.as_deref().unwrap_or_default())
+        ),
+    };
+
+    if constraint.emit_name {
+        format!("{}: {}", r.escape(&constraint.name), body)
+    } else {
+        body
+    }
+}
+
+fn render_procedure<R: DocRenderer>(index: &DocsIndex, proc: &ProcedureElement, r: &mut R) {
+    let full_name = format!("[{}].[{}]", proc.schema, proc.name);
+    r.heading(3, &display_name(&full_name), Some(&anchor(&full_name)));
+    render_description(index, &full_name, r);
+
+    if proc.is_natively_compiled {
+        let atomic: Vec<String> = [
+            proc.atomic_isolation_level
+                .as_deref()
+                .map(|level| format!("{} isolation", level)),
+            proc.atomic_language
+                .as_deref()
+                .map(|language| format!("language {}", language)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if atomic.is_empty() {
+            r.paragraph("Natively compiled.");
+        } else {
+            r.paragraph(&format!(
+                "Natively compiled ({}).",
+                r.escape(&atomic.join(", "))
+            ));
+        }
+    }
+
+    let rows: Vec<Vec<String>> = extract_procedure_parameters_tokens(&proc.definition)
+        .iter()
+        .map(|p| {
+            vec![
+                r.escape(&format!("@{}", p.name)),
+                r.code(&p.data_type),
+                if p.is_output { "Output" } else { "Input" }.to_string(),
+                p.default_value
+                    .as_deref()
+                    .map(|d| r.code(d))
+                    .unwrap_or_default(),
+            ]
+        })
+        .collect();
+    if rows.is_empty() {
+        r.paragraph("No parameters.");
+    } else {
+        r.table(&["Parameter", "Type", "Direction", "Default"], &rows);
+    }
+
+    render_cross_references(index, &full_name, r);
+}
+
+fn render_function<R: DocRenderer>(index: &DocsIndex, func: &FunctionElement, r: &mut R) {
+    let full_name = format!("[{}].[{}]", func.schema, func.name);
+    r.heading(3, &display_name(&full_name), Some(&anchor(&full_name)));
+    render_description(index, &full_name, r);
+
+    let returns = match (&func.function_type, &func.return_type) {
+        (FunctionType::Scalar, Some(return_type)) => r.code(return_type),
+        (FunctionType::Scalar, None) => "scalar".to_string(),
+        (FunctionType::TableValued, _) => "table (multi-statement)".to_string(),
+        (FunctionType::InlineTableValued, _) => "table (inline)".to_string(),
+    };
+    r.paragraph(&format!("Returns: {}", returns));
+
+    let rows: Vec<Vec<String>> = extract_function_parameters_tokens(&func.definition)
+        .iter()
+        .map(|p| {
+            let name = if p.name.starts_with('@') {
+                p.name.clone()
+            } else {
+                format!("@{}", p.name)
+            };
+            vec![r.escape(&name), r.code(&p.data_type)]
+        })
+        .collect();
+    if rows.is_empty() {
+        r.paragraph("No parameters.");
+    } else {
+        r.table(&["Parameter", "Type"], &rows);
+    }
+
+    render_cross_references(index, &full_name, r);
+}
+
+/// Render an object's description, if it has one
+fn render_description<R: DocRenderer>(index: &DocsIndex, full_name: &str, r: &mut R) {
+    if let Some(description) = index.descriptions.get(full_name) {
+        r.paragraph(&r.escape(description));
+    }
+}
+
+/// Render "References" and "Referenced by" lists for an object
+fn render_cross_references<R: DocRenderer>(index: &DocsIndex, full_name: &str, r: &mut R) {
+    if let Some(targets) = index.references.get(full_name) {
+        if !targets.is_empty() {
+            let items: Vec<String> = targets.iter().map(|t| object_link(index, t, r)).collect();
+            r.heading(4, "References", None);
+            r.list(&items);
+        }
+    }
+
+    if let Some(sources) = index.referenced_by.get(full_name) {
+        let items: Vec<String> = sources
+            .iter()
+            .filter(|s| s.as_str() != full_name)
+            .map(|s| object_link(index, s, r))
+            .collect();
+        if !items.is_empty() {
+            r.heading(4, "Referenced By", None);
+            r.list(&items);
+        }
+    }
+}
+
+/// Link to an object's section, or plain text if the object is not documented
+/// (e.g. a table in a referenced dacpac)
+fn object_link<R: DocRenderer>(index: &DocsIndex, full_name: &str, r: &R) -> String {
+    if index.documented.contains(full_name) {
+        r.link(&display_name(full_name), &anchor(full_name))
+    } else {
+        r.escape(&display_name(full_name))
+    }
+}
+
+/// `[dbo].[Users]` -> `dbo.Users`
+fn display_name(full_name: &str) -> String {
+    name_parts(full_name).join(".")
+}
+
+/// `[dbo].[Users]` -> `dbo-users`
+fn anchor(full_name: &str) -> String {
+    let mut slug = String::with_capacity(full_name.len());
+    for c in display_name(full_name).chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_docs_format_from_str() {
+        assert_eq!("md".parse::<DocsFormat>().unwrap(), DocsFormat::Markdown);
+        assert_eq!("HTML".parse::<DocsFormat>().unwrap(), DocsFormat::Html);
+        assert!("pdf".parse::<DocsFormat>().is_err());
+    }
+
+    #[test]
+    fn test_display_name_and_anchor() {
+        assert_eq!(display_name("[dbo].[Order Items]"), "dbo.Order Items");
+        assert_eq!(anchor("[dbo].[Order Items]"), "dbo-order-items");
+    }
+}
@@ -0,0 +1,323 @@
+//! Entity-relationship diagram export
+//!
+//! Renders tables, their columns and foreign key relationships as a mermaid `erDiagram`
+//! or a PlantUML entity diagram, optionally limited to a set of schemas.
+
+use std::collections::BTreeSet;
+
+use crate::model::{ConstraintElement, ConstraintType, DatabaseModel, ModelElement, TableElement};
+use crate::parser::identifier_utils::{quote_name_parts, split_name_parts};
+
+/// Output format for ERD export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErdFormat {
+    #[default]
+    Mermaid,
+    PlantUml,
+}
+
+impl std::str::FromStr for ErdFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mermaid" | "mmd" => Ok(ErdFormat::Mermaid),
+            "plantuml" | "puml" => Ok(ErdFormat::PlantUml),
+            _ => Err(format!("Unknown ERD format: {}", s)),
+        }
+    }
+}
+
+impl ErdFormat {
+    /// File extension for diagrams in this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ErdFormat::Mermaid => "mmd",
+            ErdFormat::PlantUml => "puml",
+        }
+    }
+}
+
+/// Key markers for a column in the diagram
+#[derive(Default)]
+struct ColumnKeys {
+    primary: bool,
+    foreign: bool,
+    unique: bool,
+}
+
+/// A foreign key relationship between two rendered tables
+struct Relationship {
+    /// Referenced (parent) table full name
+    parent: String,
+    /// Referencing (child) table full name
+    child: String,
+    name: String,
+    /// Whether every referencing column is NOT NULL (child rows always have a parent)
+    required: bool,
+}
+
+/// Render an ERD for the tables in `model`.
+///
+/// When `schemas` is non-empty only tables in those schemas (case-insensitive) are included,
+/// and foreign keys to tables outside the scope are omitted.
+pub fn render_erd(model: &DatabaseModel, format: ErdFormat, schemas: &[String]) -> String {
+    let in_scope =
+        |schema: &str| schemas.is_empty() || schemas.iter().any(|s| s.eq_ignore_ascii_case(schema));
+
+    let mut tables: Vec<&TableElement> = model
+        .elements
+        .iter()
+        .filter_map(|e| match e {
+            ModelElement::Table(t) if in_scope(&t.schema) => Some(t),
+            _ => None,
+        })
+        .collect();
+    tables.sort_by_key(|t| (t.schema.clone(), t.name.clone()));
+
+    let rendered: BTreeSet<String> = tables.iter().map(|t| table_key(t)).collect();
+    let constraints: Vec<&ConstraintElement> = model
+        .elements
+        .iter()
+        .filter_map(|e| match e {
+            ModelElement::Constraint(c) => Some(c),
+            _ => None,
+        })
+        .collect();
+
+    let mut relationships = Vec::new();
+    for c in &constraints {
+        let (ConstraintType::ForeignKey, Some(parent)) = (c.constraint_type, &c.referenced_table)
+        else {
+            continue;
+        };
+        let child = quote_name_parts(&[&c.table_schema, &c.table_name]);
+        if !rendered.contains(&child.to_lowercase()) || !rendered.contains(&parent.to_lowercase()) {
+            continue;
+        }
+        let child_table = tables.iter().find(|t| table_key(t) == child.to_lowercase());
+        let required = child_table.is_some_and(|t| {
+            c.columns.iter().all(|fc| {
+                t.columns
+                    .iter()
+                    .find(|col| col.name.eq_ignore_ascii_case(&fc.name))
+                    .is_some_and(|col| col.nullability == Some(false))
+            })
+        });
+        relationships.push(Relationship {
+            parent: parent.clone(),
+            child,
+            name: c.name.clone(),
+            required,
+        });
+    }
+    relationships.sort_by(|a, b| (&a.child, &a.name).cmp(&(&b.child, &b.name)));
+
+    let keys_for = |table: &TableElement, column: &str| -> ColumnKeys {
+        let mut keys = ColumnKeys::default();
+        for c in constraints.iter().filter(|c| {
+            c.table_schema.eq_ignore_ascii_case(&table.schema)
+                && c.table_name.eq_ignore_ascii_case(&table.name)
+                && c.columns
+                    .iter()
+                    .any(|cc| cc.name.eq_ignore_ascii_case(column))
+        }) {
+            match c.constraint_type {
+                ConstraintType::PrimaryKey => keys.primary = true,
+                ConstraintType::ForeignKey => keys.foreign = true,
+                ConstraintType::Unique => keys.unique = true,
+                _ => {}
+            }
+        }
+        keys
+    };
+
+    match format {
+        ErdFormat::Mermaid => render_mermaid(&tables, &relationships, keys_for),
+        ErdFormat::PlantUml => render_plantuml(&tables, &relationships, keys_for),
+    }
+}
+
+fn render_mermaid(
+    tables: &[&TableElement],
+    relationships: &[Relationship],
+    keys_for: impl Fn(&TableElement, &str) -> ColumnKeys,
+) -> String {
+    let mut out = String::from("erDiagram\n");
+
+    for table in tables {
+        out.push_str(&format!(
+            "    {}[\"{}.{}\"] {{\n",
+            entity_id(&table.schema, &table.name),
+            table.schema,
+            table.name
+        ));
+        for col in &table.columns {
+            let keys = keys_for(table, &col.name);
+            let mut markers = Vec::new();
+            if keys.primary {
+                markers.push("PK");
+            }
+            if keys.foreign {
+                markers.push("FK");
+            }
+            if keys.unique {
+                markers.push("UK");
+            }
+            let data_type = if col.computed_expression.is_some() {
+                "computed"
+            } else {
+                &col.data_type
+            };
+            out.push_str(&format!(
+                "        {} {}",
+                mermaid_token(data_type),
+                mermaid_token(&col.name)
+            ));
+            if !markers.is_empty() {
+                out.push_str(&format!(" {}", markers.join(", ")));
+            }
+            out.push('\n');
+        }
+        out.push_str("    }\n");
+    }
+
+    for rel in relationships {
+        let parent_cardinality = if rel.required { "||" } else { "|o" };
+        out.push_str(&format!(
+            "    {} {}--o{{ {} : \"{}\"\n",
+            entity_id_from_full_name(&rel.parent),
+            parent_cardinality,
+            entity_id_from_full_name(&rel.child),
+            rel.name.replace('"', "'")
+        ));
+    }
+
+    out
+}
+
+fn render_plantuml(
+    tables: &[&TableElement],
+    relationships: &[Relationship],
+    keys_for: impl Fn(&TableElement, &str) -> ColumnKeys,
+) -> String {
+    let mut out = String::from("@startuml\nhide circle\nskinparam linetype ortho\n\n");
+
+    for table in tables {
+        out.push_str(&format!(
+            "entity \"{}.{}\" as {} {{\n",
+            table.schema,
+            table.name,
+            entity_id(&table.schema, &table.name)
+        ));
+
+        // Primary key columns go above the separator, as in PlantUML's IE notation
+        let (key_columns, other_columns): (Vec<_>, Vec<_>) = table
+            .columns
+            .iter()
+            .partition(|col| keys_for(table, &col.name).primary);
+        for (i, columns) in [key_columns, other_columns].iter().enumerate() {
+            if i == 1 && !columns.is_empty() {
+                out.push_str("  --\n");
+            }
+            for col in columns {
+                let keys = keys_for(table, &col.name);
+                let mandatory = if keys.primary || col.nullability == Some(false) {
+                    "* "
+                } else {
+                    ""
+                };
+                let data_type = if col.computed_expression.is_some() {
+                    "computed"
+                } else {
+                    &col.data_type
+                };
+                let mut stereotypes = String::new();
+                if keys.primary {
+                    stereotypes.push_str(" <<PK>>");
+                }
+                if keys.foreign {
+                    stereotypes.push_str(" <<FK>>");
+                }
+                if keys.unique {
+                    stereotypes.push_str(" <<UK>>");
+                }
+                out.push_str(&format!(
+                    "  {}{} : {}{}\n",
+                    mandatory, col.name, data_type, stereotypes
+                ));
+            }
+        }
+        out.push_str("}\n\n");
+    }
+
+    for rel in relationships {
+        let parent_cardinality = if rel.required { "||" } else { "|o" };
+        out.push_str(&format!(
+            "{} {}--o{{ {} : {}\n",
+            entity_id_from_full_name(&rel.parent),
+            parent_cardinality,
+            entity_id_from_full_name(&rel.child),
+            rel.name
+        ));
+    }
+
+    out.push_str("@enduml\n");
+    out
+}
+
+/// Lowercased `[schema].[name]` key used to match FK targets against rendered tables
+fn table_key(table: &TableElement) -> String {
+    quote_name_parts(&[&table.schema, &table.name]).to_lowercase()
+}
+
+/// Diagram identifier for a table (`dbo_Products`)
+fn entity_id(schema: &str, name: &str) -> String {
+    mermaid_token(&format!("{}_{}", schema, name))
+}
+
+/// Diagram identifier for a `[schema].[name]` reference
+fn entity_id_from_full_name(full_name: &str) -> String {
+    match split_name_parts(full_name).as_deref() {
+        Some([schema, name]) => entity_id(schema, name),
+        _ => mermaid_token(full_name.trim_start_matches('[').trim_end_matches(']')),
+    }
+}
+
+/// Restrict a name or type to the characters mermaid accepts in identifiers
+/// (letters, digits, `_`, `-`, parentheses). `DECIMAL(18, 2)` becomes `DECIMAL(18-2)`.
+fn mermaid_token(text: &str) -> String {
+    let mut token: String = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            ',' => '-',
+            c if c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '(' | ')') => c,
+            _ => '_',
+        })
+        .collect();
+    if !token.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        token.insert(0, '_');
+    }
+    token
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_erd_format_from_str() {
+        assert_eq!("Mermaid".parse::<ErdFormat>().unwrap(), ErdFormat::Mermaid);
+        assert_eq!("puml".parse::<ErdFormat>().unwrap(), ErdFormat::PlantUml);
+        assert!("dot".parse::<ErdFormat>().is_err());
+    }
+
+    #[test]
+    fn test_mermaid_token() {
+        assert_eq!(mermaid_token("DECIMAL(18, 2)"), "DECIMAL(18-2)");
+        assert_eq!(mermaid_token("Order Date"), "OrderDate");
+        assert_eq!(mermaid_token("1stColumn"), "_1stColumn");
+        assert_eq!(entity_id_from_full_name("[Sales].[Orders]"), "Sales_Orders");
+    }
+}
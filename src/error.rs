@@ -20,9 +20,25 @@ pub enum SqlPackageError {
         source: roxmltree::Error,
     },
 
+    #[error(
+        "Project file elements the build does not apply (see --unknown-elements):\n  {}",
+        .elements.join("\n  ")
+    )]
+    UnrecognizedProjectElements { elements: Vec<String> },
+
     #[error("Invalid project file format: {message}")]
     InvalidProjectFormat { message: String },
 
+    #[error("Failed to read config file: {path}")]
+    ConfigReadError {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Invalid config file {path}: {message}")]
+    InvalidConfig { path: PathBuf, message: String },
+
     #[error("Failed to read SQL file: {path}")]
     SqlFileReadError {
         path: PathBuf,
@@ -37,6 +53,25 @@ pub enum SqlPackageError {
         message: String,
     },
 
+    #[error(
+        "{statement} in {path} at line {line} is not supported in a database project: {suggestion}"
+    )]
+    ImperativeStatement {
+        path: PathBuf,
+        line: usize,
+        statement: String,
+        suggestion: String,
+    },
+
+    #[error("Build limit exceeded: {message}")]
+    LimitExceeded { message: String },
+
+    #[error("Build cancelled")]
+    Cancelled,
+
+    #[error("Model validation failed:\n  {}", .errors.join("\n  "))]
+    ModelValidationError { errors: Vec<String> },
+
     #[error("Unsupported SQL statement: {statement_type}")]
     UnsupportedStatement { statement_type: String },
 
@@ -50,6 +85,23 @@ pub enum SqlPackageError {
         source: std::io::Error,
     },
 
+    #[error("Failed to write build summary to {path}")]
+    SummaryWriteError {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Failed to write output file {path}")]
+    OutputWriteError {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Unsupported model schema version {version}: {message}")]
+    UnsupportedSchemaVersion { version: String, message: String },
+
     #[error("XML generation error: {message}")]
     XmlGenerationError { message: String },
 
@@ -60,14 +112,69 @@ pub enum SqlPackageError {
         source: std::io::Error,
     },
 
+    #[error("Self-update failed: {message}")]
+    SelfUpdateError { message: String },
+
+    #[error("Baseline build failed: {message}")]
+    BaselineError { message: String },
+
+    #[error("Invalid dacpac {path}: {message}")]
+    InvalidDacpac { path: PathBuf, message: String },
+
+    #[error("Invalid compare manifest {path}: {message}")]
+    InvalidCompareManifest { path: PathBuf, message: String },
+
+    #[error(
+        "Files define more than one object, which ObjectsPerFile=Single forbids:\n  {}",
+        .files.join("\n  ")
+    )]
+    MultipleObjectsPerFile { files: Vec<String> },
+
+    #[error("Cannot merge dacpacs:\n  {}", .conflicts.join("\n  "))]
+    MergeConflict { conflicts: Vec<String> },
+
     #[error("ZIP creation error: {message}")]
     ZipError { message: String },
 
+    #[error("Failed to connect to {server}: {message}")]
+    ConnectionError {
+        server: String,
+        message: String,
+        /// SQL Server error number, when the server rejected the connection
+        number: Option<u32>,
+    },
+
+    #[error("Invalid connection string: {message}")]
+    InvalidConnectionString { message: String },
+
+    #[error("Command timed out after {seconds}s")]
+    CommandTimeout { seconds: u64 },
+
+    #[error("Query failed: {message}")]
+    QueryError {
+        message: String,
+        /// SQL Server error number, when the server reported the failure
+        number: Option<u32>,
+    },
+
+    #[error("Deployment failed in batch {batch}: {message}")]
+    DeploymentError { batch: usize, message: String },
+
+    #[error("Database {database} is not registered as a data-tier application")]
+    DacNotRegistered { database: String },
+
     #[error("SQLCMD include file not found: {path} (referenced from {source_file})")]
     SqlcmdIncludeNotFound { path: PathBuf, source_file: PathBuf },
 
     #[error("Circular SQLCMD include detected: {path} (include chain: {chain})")]
     SqlcmdCircularInclude { path: PathBuf, chain: String },
+
+    #[error("Invalid SQLCMD conditional in {path} at line {line}: {message}")]
+    SqlcmdConditionalError {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
 }
 
 impl From<zip::result::ZipError> for SqlPackageError {
@@ -3,86 +3,642 @@
 //! This library compiles .sqlproj files into .dacpac packages,
 //! providing a faster alternative to the .NET DacFx toolchain.
 
+#[cfg(feature = "async")]
+pub mod async_build;
+pub mod baseline;
+#[cfg(feature = "client")]
+pub mod client;
 pub mod compare;
+pub mod config;
 pub mod dacpac;
+pub mod docs;
+pub mod erd;
 pub mod error;
+pub mod lineage;
+pub mod merge;
 pub mod model;
+pub mod model_dump;
 pub mod parser;
+pub mod progress;
 pub mod project;
+pub mod self_update;
+pub mod slice;
+pub mod summary;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod unused;
 pub mod util;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
 use anyhow::Result;
 
+#[cfg(feature = "async")]
+pub use async_build::{build_dacpac_async, BuildHandle};
+pub use docs::DocsFormat;
+pub use erd::ErdFormat;
 pub use error::SqlPackageError;
+pub use lineage::LineageReport;
+pub use merge::{merge_dacpacs, MergeOptions, MergeSummary};
+pub use model_dump::DumpFilter;
+pub use progress::{BuildProgress, ProgressCallback};
+pub use slice::{slice_dacpac, SliceOptions, SliceSummary};
+pub use summary::{BuildSummary, SummaryFormat};
+pub use unused::EntryPoints;
+pub use util::CancellationToken;
 
 /// Options for building a dacpac
 #[derive(Debug, Clone)]
 pub struct BuildOptions {
     /// Path to the .sqlproj file
     pub project_path: PathBuf,
+    /// Build every .sql file under this folder instead of a .sqlproj (`project_path` is
+    /// then ignored), see [`project::project_from_dir`]
+    pub from_dir: Option<PathBuf>,
+    /// Database name for a `from_dir` build (defaults to the folder's name)
+    pub name: Option<String>,
     /// Output path for the .dacpac file
     pub output_path: Option<PathBuf>,
-    /// Target SQL Server platform (e.g., "Sql160")
+    /// Directory for `<project>.dacpac` when `output_path` is not set (defaults to
+    /// `bin/Debug` under the project directory)
+    pub output_dir: Option<PathBuf>,
+    /// Target SQL Server platform (e.g., "Sql160") of a `from_dir` build; a .sqlproj
+    /// sets its own through `<DSP>`
     pub target_platform: String,
     /// Enable verbose output
     pub verbose: bool,
+    /// Write a machine-readable build summary in this format
+    pub summary_format: Option<SummaryFormat>,
+    /// Path for the build summary (defaults to `<output>.summary.<ext>` next to the dacpac)
+    pub summary_path: Option<PathBuf>,
+    /// Write a JSON map of each model element to a hash of its content
+    pub object_hashes: bool,
+    /// Path for the object hashes (defaults to `<output>.hashes.json` next to the dacpac)
+    pub object_hashes_path: Option<PathBuf>,
+    /// Include/exclude filters applied to the model before packaging
+    pub filters: Vec<model::ObjectFilter>,
+    /// JSON file of extra filtered dacpacs to build, `{"Name": ["filter", ...]}`, added
+    /// to the project's `<DacpacProfile>` items (a profile here replaces one of the same name)
+    pub profiles_path: Option<PathBuf>,
+    /// SQLCMD variable values for `:IF` blocks, overriding the project's defaults
+    pub variables: Vec<(String, String)>,
+    /// MSBuild properties for the project's item `Condition`s, overriding the project's
+    /// properties and the environment (the last value of a name wins)
+    pub properties: Vec<(String, String)>,
+    /// DacFx parity (default) or relaxed model.xml output
+    pub output_mode: model::OutputMode,
+    /// CDATA (default, as DacFx) or escaped text for script properties in model.xml
+    pub script_format: model::ScriptFormat,
+    /// Order of model.xml elements: DacFx's (default) or grouped by schema, which needs
+    /// relaxed output
+    pub element_order: model::ElementOrder,
+    /// Model schema version to emit instead of the default (e.g. "2.4" for older DacFx)
+    pub schema_version: Option<String>,
+    /// Keep `:r` includes in pre/post-deploy scripts for SQLCMD to resolve at deploy time
+    /// instead of inlining them
+    pub keep_deploy_includes: bool,
+    /// Add MS_Description extended properties to tables, views, procedures and functions
+    /// from the comment block their batch starts with, unless they have one already
+    pub header_descriptions: bool,
+    /// Add MS_Description extended properties to columns from trailing
+    /// `-- description: ...` comments in CREATE TABLE, unless they have one already
+    pub column_descriptions: bool,
+    /// Line endings of script properties: preserved from the source files (default, as
+    /// DacFx does) or normalized to LF or CRLF
+    pub line_endings: parser::LineEndings,
+    /// Fail when the model has more elements than this
+    pub max_elements: Option<usize>,
+    /// Fail on SQL files larger than this many bytes
+    pub max_file_size: Option<u64>,
+    /// Fail on batches (the text between `GO`s) longer than this many bytes
+    pub max_statement_length: Option<usize>,
+    /// Threads for parsing SQL files (defaults to one per CPU, or `RAYON_NUM_THREADS`)
+    pub threads: Option<usize>,
+    /// What to do with statements neither sqlparser nor the fallback parsers understand:
+    /// fail (default), keep tables and views as raw elements, or also skip the rest
+    pub parse_fallback: parser::ParseFallback,
+    /// What to do with project file elements the build does not apply, such as
+    /// `BuildExtensionConfiguration` items: warn (default), ignore or fail
+    pub unknown_elements: project::UnknownElementPolicy,
+    /// Properties and annotations forced on the emitted model.xml
+    pub model_overrides: dacpac::ModelOverrides,
+    /// Embed a manifest of the project's source files and their SHA256 in the dacpac,
+    /// for `verify-sources`
+    pub source_manifest: bool,
+    /// Build the model but write nothing, printing the files that would be written
+    pub dry_run: bool,
+    /// Stop the build between files and stages once cancelled
+    pub cancellation: Option<CancellationToken>,
+    /// Called as each stage of the build finishes
+    pub on_progress: Option<ProgressCallback>,
+}
+
+impl Default for BuildOptions {
+    fn default() -> Self {
+        Self {
+            project_path: PathBuf::new(),
+            from_dir: None,
+            name: None,
+            output_path: None,
+            output_dir: None,
+            target_platform: "Sql160".to_string(),
+            verbose: false,
+            summary_format: None,
+            summary_path: None,
+            object_hashes: false,
+            object_hashes_path: None,
+            filters: Vec::new(),
+            profiles_path: None,
+            variables: Vec::new(),
+            properties: Vec::new(),
+            output_mode: model::OutputMode::default(),
+            script_format: model::ScriptFormat::default(),
+            element_order: model::ElementOrder::default(),
+            schema_version: None,
+            keep_deploy_includes: false,
+            header_descriptions: false,
+            column_descriptions: false,
+            line_endings: parser::LineEndings::default(),
+            max_elements: None,
+            max_file_size: None,
+            max_statement_length: None,
+            threads: None,
+            parse_fallback: parser::ParseFallback::default(),
+            unknown_elements: project::UnknownElementPolicy::default(),
+            model_overrides: dacpac::ModelOverrides::default(),
+            source_manifest: false,
+            dry_run: false,
+            cancellation: None,
+            on_progress: None,
+        }
+    }
 }
 
 /// Build a dacpac from a sqlproj file
+///
+/// Returns the dacpac's path; with `dry_run`, the path it would have been written to.
+/// Fails with [`SqlPackageError::Cancelled`] if `cancellation` is cancelled first.
 pub fn build_dacpac(options: BuildOptions) -> Result<PathBuf> {
+    let cancelled = || util::check_cancelled(options.cancellation.as_ref());
+    let report = |progress: BuildProgress| {
+        if let Some(callback) = &options.on_progress {
+            callback.report(&progress);
+        }
+    };
+    let source = options.from_dir.as_ref().unwrap_or(&options.project_path);
     if options.verbose {
-        println!("Building project: {}", options.project_path.display());
+        println!("Building project: {}", source.display());
     }
 
-    // Step 1: Parse the sqlproj file
-    let project = project::parse_sqlproj(&options.project_path)?;
+    let build_start = Instant::now();
+    let mut durations = summary::PhaseDurations::default();
+
+    // Step 1: Parse the sqlproj file (or collect the folder's SQL files)
+    let phase_start = Instant::now();
+    let mut project = match &options.from_dir {
+        Some(dir) => {
+            let target_platform = options
+                .target_platform
+                .parse()
+                .map_err(anyhow::Error::msg)?;
+            project::project_from_dir(dir, options.name.as_deref(), target_platform)?
+        }
+        None => project::parse_sqlproj_with_properties(&options.project_path, &options.properties)?,
+    };
+    project.build_variables.extend(
+        options
+            .variables
+            .iter()
+            .map(|(name, value)| (parser::variable_key(name), value.clone())),
+    );
+    project.resolve_deploy_includes = !options.keep_deploy_includes;
+    project.header_descriptions = options.header_descriptions;
+    project.column_descriptions = options.column_descriptions;
+    apply_unknown_element_policy(&mut project, options.unknown_elements)?;
+    durations.parse_project_ms = summary::PhaseDurations::millis(phase_start.elapsed());
 
+    report(BuildProgress::ProjectLoaded {
+        sql_files: project.sql_files.len(),
+    });
     if options.verbose {
         println!("Found {} SQL files", project.sql_files.len());
+        if !project.skipped_files.is_empty() {
+            println!("Skipped {} files:", project.skipped_files.len());
+            for skipped in &project.skipped_files {
+                println!("  {} ({} item)", skipped.path.display(), skipped.item);
+            }
+        }
     }
 
-    // Step 2: Parse all SQL files
-    let statements = parser::parse_sql_files(&project.sql_files)?;
+    let parse_options = parser::ParseOptions {
+        variables: project.build_variables.clone(),
+        line_endings: options.line_endings,
+        default_encoding: project.default_file_encoding,
+        max_file_size: options.max_file_size,
+        max_statement_length: options.max_statement_length,
+        cancellation: options.cancellation.clone(),
+        fallback: options.parse_fallback,
+    };
+    // Steps 2 and 3 run again after ObjectsPerFile=Split has moved objects to new files,
+    // so the dacpac describes the files as they are on disk
+    let (statements, mut database_model, phase_start) = loop {
+        // Step 2: Parse all SQL files
+        cancelled()?;
+        let phase_start = Instant::now();
+        let parsed = match options.threads {
+            Some(threads) => rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()?
+                .install(|| {
+                    parser::parse_sql_files_with_hashes(&project.sql_files, &parse_options)
+                })?,
+            None => parser::parse_sql_files_with_hashes(&project.sql_files, &parse_options)?,
+        };
+        let statements = parsed.statements;
+        durations.parse_sql_ms += summary::PhaseDurations::millis(phase_start.elapsed());
 
-    if options.verbose {
-        println!("Parsed {} SQL statements", statements.len());
-    }
+        report(BuildProgress::SqlParsed {
+            statements: statements.len(),
+        });
+        if options.verbose {
+            println!("Parsed {} SQL statements", statements.len());
+        }
 
-    // Step 3: Build the database model
-    let database_model = model::build_model(&statements, &project)?;
+        // Step 3: Build the database model
+        cancelled()?;
+        let phase_start = Instant::now();
+        let mut database_model = model::build_model(&statements, &project)?;
+        database_model.source_hashes = parsed.source_hashes;
+        project::enforce_single_object_files(&database_model, &project)?;
+        let split = project::split_multi_object_files(&database_model, &project, options.dry_run)?;
+        let verb = if options.dry_run {
+            "Would split"
+        } else {
+            "Split"
+        };
+        for (file, created) in &split {
+            println!(
+                "{} {} into {}",
+                verb,
+                file.display(),
+                created
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        if split.is_empty() || options.dry_run {
+            break (statements, database_model, phase_start);
+        }
+        for (_, created) in split {
+            if options.from_dir.is_none() {
+                project::add_sql_files(&options.project_path, &created)?;
+            }
+            project.sql_files.extend(created);
+        }
+    };
+    if let Some(limit) = options
+        .max_elements
+        .filter(|limit| database_model.elements.len() > *limit)
+    {
+        return Err(SqlPackageError::LimitExceeded {
+            message: format!(
+                "the model has {} elements, more than the maximum of {}",
+                database_model.elements.len(),
+                limit
+            ),
+        }
+        .into());
+    }
+    // What each object depends on, to report references the filters leave dangling
+    let filters_apply = !options.filters.is_empty()
+        || !project.dacpac_profiles.is_empty()
+        || options.profiles_path.is_some();
+    let dependencies = if !filters_apply {
+        Default::default()
+    } else {
+        model::filter::object_dependencies(&database_model, &project.default_schema)
+    };
+    let filtered_out = model::apply_filters(&mut database_model, &options.filters);
+    for dangling in model::filter::dangling_references(&database_model, &dependencies) {
+        eprintln!("Warning: {}", dangling);
+        database_model.warnings.push(dangling.to_string());
+    }
+    database_model.output_mode = options.output_mode;
+    database_model.script_format = options.script_format;
+    database_model.overrides = options.model_overrides.clone();
+    database_model.source_manifest = options.source_manifest;
+    if options.element_order != model::ElementOrder::DotNet {
+        if options.output_mode == model::OutputMode::Parity {
+            return Err(SqlPackageError::DacpacCreationError {
+                message: "logical element order needs relaxed output mode, as DacFx expects \
+                          elements in its own order"
+                    .to_string(),
+            }
+            .into());
+        }
+        database_model.sort_elements(options.element_order);
+    }
+    if let Some(version) = &options.schema_version {
+        database_model.set_schema_version(version, project.target_platform)?;
+    }
+    durations.build_model_ms = summary::PhaseDurations::millis(phase_start.elapsed());
+    report(BuildProgress::ModelBuilt {
+        elements: database_model.elements.len(),
+    });
 
     if options.verbose {
-        println!(
-            "Built model with {} elements",
-            database_model.elements.len()
-        );
+        print!("{}", summary::format_model_breakdown(&database_model));
+        if filtered_out > 0 {
+            println!("Filtered out {} elements", filtered_out);
+        }
     }
 
     // Step 4: Determine output path
     let output_path = options.output_path.unwrap_or_else(|| {
-        let project_dir = options
-            .project_path
-            .parent()
-            .unwrap_or(std::path::Path::new("."));
-        let project_name = options
-            .project_path
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("output");
-        project_dir
-            .join("bin")
-            .join("Debug")
-            .join(format!("{}.dacpac", project_name))
+        options
+            .output_dir
+            .unwrap_or_else(|| project.project_dir.join("bin").join("Debug"))
+            .join(format!("{}.dacpac", project.name))
     });
 
+    // Dacpac profiles from the project and the profiles file, written next to the output
+    let mut profiles = project.dacpac_profiles.clone();
+    if let Some(path) = &options.profiles_path {
+        for profile in project::read_profiles_file(path)? {
+            profiles.retain(|p| !p.name.eq_ignore_ascii_case(&profile.name));
+            profiles.push(profile);
+        }
+    }
+    let output_dir = output_path.parent().unwrap_or(Path::new(""));
+    let profiles = profiles
+        .into_iter()
+        .map(|profile| {
+            let profile_path = output_dir.join(format!("{}.dacpac", profile.name));
+            if profile_path == output_path {
+                return Err(SqlPackageError::InvalidProjectFormat {
+                    message: format!(
+                        "dacpac profile '{}' would overwrite {}",
+                        profile.name,
+                        output_path.display()
+                    ),
+                });
+            }
+            Ok((profile, profile_path))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if options.dry_run {
+        println!("Would write dacpac: {}", output_path.display());
+        for (profile, profile_path) in &profiles {
+            println!(
+                "Would write dacpac: {} (profile {})",
+                profile_path.display(),
+                profile.name
+            );
+        }
+        if let Some(format) = options.summary_format {
+            let summary_path = options
+                .summary_path
+                .unwrap_or_else(|| summary::default_summary_path(&output_path, format));
+            println!("Would write build summary: {}", summary_path.display());
+        }
+        if options.object_hashes {
+            let hashes_path = options
+                .object_hashes_path
+                .unwrap_or_else(|| summary::default_object_hashes_path(&output_path));
+            println!("Would write object hashes: {}", hashes_path.display());
+        }
+        return Ok(output_path);
+    }
+
     // Step 5: Generate the dacpac
+    cancelled()?;
+    let phase_start = Instant::now();
     dacpac::create_dacpac(&database_model, &project, &output_path)?;
+    durations.write_dacpac_ms = summary::PhaseDurations::millis(phase_start.elapsed());
+    report(BuildProgress::DacpacWritten {
+        path: output_path.clone(),
+    });
 
     if options.verbose {
         println!("Created dacpac: {}", output_path.display());
     }
 
+    // Step 5b: Generate a filtered dacpac per profile, next to the main one
+    for (profile, profile_path) in &profiles {
+        cancelled()?;
+        let mut profile_model = database_model.clone();
+        let profile_filtered_out = model::apply_filters(&mut profile_model, &profile.filters);
+        for dangling in model::filter::dangling_references(&profile_model, &dependencies) {
+            if !database_model.warnings.contains(&dangling.to_string()) {
+                eprintln!("Warning: profile {}: {}", profile.name, dangling);
+            }
+        }
+        dacpac::create_dacpac(&profile_model, &project, profile_path)?;
+        report(BuildProgress::DacpacWritten {
+            path: profile_path.clone(),
+        });
+
+        if options.verbose {
+            println!(
+                "Created dacpac: {} (profile {}, filtered out {} elements)",
+                profile_path.display(),
+                profile.name,
+                profile_filtered_out
+            );
+        }
+    }
+
+    // Step 6: Write the machine-readable build summary (if requested)
+    if let Some(format) = options.summary_format {
+        durations.total_ms = summary::PhaseDurations::millis(build_start.elapsed());
+        let summary_path = options
+            .summary_path
+            .unwrap_or_else(|| summary::default_summary_path(&output_path, format));
+        let build_summary = BuildSummary {
+            project: project.name.clone(),
+            output_path: output_path.clone(),
+            output_sha256: summary::file_sha256(&output_path)?,
+            source_files: project.sql_files.len(),
+            statements: statements.len(),
+            total_elements: database_model.elements.len(),
+            elements_by_type: BuildSummary::count_elements_by_type(&database_model),
+            warnings: project
+                .warnings
+                .iter()
+                .chain(&database_model.warnings)
+                .cloned()
+                .collect(),
+            durations,
+        };
+        build_summary.write(&summary_path, format)?;
+
+        if options.verbose {
+            println!("Wrote build summary: {}", summary_path.display());
+        }
+    }
+
+    // Step 7: Write the per-object content hashes (if requested)
+    if options.object_hashes {
+        let hashes_path = options
+            .object_hashes_path
+            .unwrap_or_else(|| summary::default_object_hashes_path(&output_path));
+        summary::write_object_hashes(&output_path, &hashes_path)?;
+
+        if options.verbose {
+            println!("Wrote object hashes: {}", hashes_path.display());
+        }
+    }
+
     Ok(output_path)
 }
+
+/// Warn about (recording them as project warnings), ignore or fail on the project file
+/// elements the build does not apply
+fn apply_unknown_element_policy(
+    project: &mut project::SqlProject,
+    policy: project::UnknownElementPolicy,
+) -> Result<()> {
+    let elements: Vec<String> = project
+        .unrecognized_elements
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    match policy {
+        project::UnknownElementPolicy::Ignore => {}
+        project::UnknownElementPolicy::Warn => {
+            for element in elements {
+                eprintln!("Warning: {}", element);
+                project.warnings.push(element);
+            }
+        }
+        project::UnknownElementPolicy::Error if !elements.is_empty() => {
+            return Err(SqlPackageError::UnrecognizedProjectElements { elements }.into());
+        }
+        project::UnknownElementPolicy::Error => {}
+    }
+    Ok(())
+}
+
+/// Compile a single SQL script against an implicit empty project and return the
+/// generated model.xml `<Model>` fragment.
+///
+/// `source_path` labels statements and parse errors (use e.g. `<stdin>` for piped input).
+pub fn compile_sql(
+    sql: &str,
+    source_path: &Path,
+    target_platform: project::SqlServerVersion,
+) -> Result<String> {
+    let project = project::SqlProject {
+        target_platform,
+        sql_files: vec![source_path.to_path_buf()],
+        ..Default::default()
+    };
+
+    let statements = parser::parse_sql_content(sql, source_path)?;
+    let database_model = model::build_model(&statements, &project)?;
+
+    let mut buffer = Vec::new();
+    dacpac::generate_model_fragment(&mut buffer, &database_model, &project)?;
+    Ok(String::from_utf8(buffer)?)
+}
+
+/// Parse a sqlproj and its SQL files and build the database model, without writing a dacpac.
+/// Used by commands that inspect the model (e.g. documentation generation).
+pub fn load_model(project_path: &Path) -> Result<(project::SqlProject, model::DatabaseModel)> {
+    let project = project::parse_sqlproj(project_path)?;
+    let parse_options = parser::ParseOptions {
+        variables: project.build_variables.clone(),
+        default_encoding: project.default_file_encoding,
+        ..Default::default()
+    };
+    let statements = parser::parse_sql_files_with_options(&project.sql_files, &parse_options)?;
+    let database_model = model::build_model(&statements, &project)?;
+    Ok((project, database_model))
+}
+
+/// Generate schema documentation (Markdown or HTML) for a sqlproj
+pub fn generate_docs(project_path: &Path, format: DocsFormat) -> Result<String> {
+    let (project, database_model) = load_model(project_path)?;
+    Ok(docs::render_docs(&database_model, &project, format))
+}
+
+/// Generate an entity-relationship diagram for a sqlproj, optionally limited to `schemas`
+pub fn generate_erd(project_path: &Path, format: ErdFormat, schemas: &[String]) -> Result<String> {
+    let (_, database_model) = load_model(project_path)?;
+    Ok(erd::render_erd(&database_model, format, schemas))
+}
+
+/// Generate the column-level lineage report (JSON) for a sqlproj
+pub fn generate_lineage(project_path: &Path) -> Result<String> {
+    let (project, database_model) = load_model(project_path)?;
+    let json = LineageReport::from_model(&database_model, &project).to_json()?;
+    Ok(format!("{}\n", json))
+}
+
+/// Find the views, procedures and functions of a sqlproj that nothing references and
+/// that match none of `entry_points`
+pub fn find_unused(
+    project_path: &Path,
+    entry_points: &EntryPoints,
+) -> Result<Vec<unused::UnusedObject>> {
+    let (project, database_model) = load_model(project_path)?;
+    Ok(unused::find_unused_objects(
+        &database_model,
+        &project,
+        entry_points,
+    ))
+}
+
+/// Build the model for a sqlproj and render it as a human-readable tree of elements,
+/// properties and relationships, limited to the elements selected by `filter`; each
+/// element notes the file and lines that define it
+pub fn dump_model(project_path: &Path, filter: &DumpFilter) -> Result<String> {
+    let (project, database_model) = load_model(project_path)?;
+    let mut buffer = Vec::new();
+    dacpac::generate_model_fragment(&mut buffer, &database_model, &project)?;
+    let sources = (0..database_model.elements.len())
+        .filter_map(|i| {
+            let source = database_model.source(i)?;
+            Some((
+                (
+                    database_model.elements[i].type_name().to_string(),
+                    database_model.xml_name(i).to_string(),
+                ),
+                source.display_relative_to(&project.project_dir),
+            ))
+        })
+        .collect();
+    model_dump::render_model_dump_with_sources(&String::from_utf8(buffer)?, filter, &sources)
+}
+
+/// Read one part of a dacpac (e.g. `model.xml` or `origin`), see
+/// [`compare::reader::DacpacContents::find_part`]
+///
+/// With `pretty`, XML parts are re-indented one element per line; other parts are
+/// returned as stored.
+pub fn read_dacpac_part(dacpac_path: &Path, part: &str, pretty: bool) -> Result<Vec<u8>> {
+    let contents = compare::reader::DacpacContents::from_path(dacpac_path)?;
+    let (name, data) = contents.find_part(part).ok_or_else(|| {
+        let mut names: Vec<&str> = contents.file_names().collect();
+        names.sort_unstable();
+        SqlPackageError::InvalidDacpac {
+            path: dacpac_path.to_path_buf(),
+            message: format!("no part named {} (parts: {})", part, names.join(", ")),
+        }
+    })?;
+
+    if !pretty || !name.to_lowercase().ends_with(".xml") {
+        return Ok(data.to_vec());
+    }
+    let text = std::str::from_utf8(data)?;
+    let document = roxmltree::Document::parse(text.trim_start_matches('\u{feff}'))?;
+    let xml = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n{}",
+        compare::xml_view::pretty_xml(document.root_element())
+    );
+    Ok(xml.into_bytes())
+}
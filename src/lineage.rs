@@ -0,0 +1,164 @@
+//! Column-level lineage export
+//!
+//! Maps the output columns of views and inline table-valued functions back to the columns
+//! they are read from, following views-over-views down to base table columns. The report is
+//! written as JSON for data-governance tooling.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::dacpac::column_lineage;
+use crate::model::DatabaseModel;
+use crate::project::SqlProject;
+
+/// Kind of object whose columns are traced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum LineageObjectType {
+    View,
+    InlineTableValuedFunction,
+}
+
+/// Lineage of a single output column
+#[derive(Debug, Clone, Serialize)]
+pub struct ColumnLineage {
+    /// Output column name
+    pub name: String,
+    /// Column (or function parameter) the value is read from directly,
+    /// e.g. `[dbo].[Products].[Id]`. `None` for computed expressions.
+    pub source: Option<String>,
+    /// `source` followed through intermediate views/functions to a base table column
+    pub base_source: Option<String>,
+}
+
+/// Output columns of a view or inline table-valued function
+#[derive(Debug, Clone, Serialize)]
+pub struct ObjectLineage {
+    /// Object full name (e.g. `[dbo].[ActiveProducts]`)
+    pub name: String,
+    #[serde(rename = "type")]
+    pub object_type: LineageObjectType,
+    /// File and lines defining the object, relative to the project
+    /// (e.g. `Views/ActiveProducts.sql:1-6`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub defined_in: Option<String>,
+    /// Header comment of the object's file (see [`crate::parser::extract_header_comment`])
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header: Option<String>,
+    pub columns: Vec<ColumnLineage>,
+}
+
+/// Column lineage for a whole project
+#[derive(Debug, Clone, Serialize)]
+pub struct LineageReport {
+    pub project: String,
+    pub objects: Vec<ObjectLineage>,
+}
+
+impl LineageReport {
+    /// Trace view and inline TVF columns in `model`
+    pub fn from_model(model: &DatabaseModel, project: &SqlProject) -> Self {
+        let mut objects = column_lineage(model, &project.default_schema);
+        resolve_base_sources(&mut objects);
+
+        let positions: HashMap<String, usize> = (0..model.elements.len())
+            .map(|i| (model.full_name(i).to_lowercase(), i))
+            .collect();
+        for object in &mut objects {
+            let Some(&i) = positions.get(&object.name.to_lowercase()) else {
+                continue;
+            };
+            object.defined_in = model
+                .source(i)
+                .map(|source| source.display_relative_to(&project.project_dir));
+            object.header = model.header(i).map(str::to_string);
+        }
+        Self {
+            project: project.name.clone(),
+            objects,
+        }
+    }
+
+    /// Render the report as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Fill `base_source` by following each column's source through other traced objects
+/// until it reaches a column that is not itself a view/TVF output (i.e. a table column).
+fn resolve_base_sources(objects: &mut [ObjectLineage]) {
+    // Lowercased `[schema].[object].[column]` -> direct source
+    let sources: HashMap<String, Option<String>> = objects
+        .iter()
+        .flat_map(|o| {
+            o.columns.iter().map(move |c| {
+                (
+                    format!("{}.[{}]", o.name, c.name).to_lowercase(),
+                    c.source.clone(),
+                )
+            })
+        })
+        .collect();
+
+    for column in objects.iter_mut().flat_map(|o| o.columns.iter_mut()) {
+        let mut current = column.source.clone();
+        // Bound the walk so a cycle between views cannot loop forever
+        for _ in 0..=sources.len() {
+            match current.as_ref().map(|c| sources.get(&c.to_lowercase())) {
+                Some(Some(next)) => current = next.clone(),
+                _ => break,
+            }
+        }
+        column.base_source = current;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, source: Option<&str>) -> ColumnLineage {
+        ColumnLineage {
+            name: name.to_string(),
+            source: source.map(str::to_string),
+            base_source: None,
+        }
+    }
+
+    #[test]
+    fn test_resolve_base_sources_follows_views() {
+        let mut objects = vec![
+            ObjectLineage {
+                name: "[dbo].[Outer]".to_string(),
+                object_type: LineageObjectType::View,
+                defined_in: None,
+                header: None,
+                columns: vec![
+                    column("Id", Some("[dbo].[Inner].[Id]")),
+                    column("Total", None),
+                ],
+            },
+            ObjectLineage {
+                name: "[dbo].[Inner]".to_string(),
+                object_type: LineageObjectType::View,
+                defined_in: None,
+                header: None,
+                columns: vec![column("Id", Some("[dbo].[Products].[Id]"))],
+            },
+        ];
+
+        resolve_base_sources(&mut objects);
+
+        assert_eq!(
+            objects[0].columns[0].base_source.as_deref(),
+            Some("[dbo].[Products].[Id]")
+        );
+        assert_eq!(objects[0].columns[1].base_source, None);
+        assert_eq!(
+            objects[1].columns[0].base_source.as_deref(),
+            Some("[dbo].[Products].[Id]")
+        );
+    }
+}
@@ -1,20 +1,72 @@
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use glob::Pattern;
+use std::io::{Read, Write};
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::process;
 
-use rust_sqlpackage::{build_dacpac, BuildOptions};
+use rust_sqlpackage::baseline;
+use rust_sqlpackage::compare::batch::{
+    compare_batch, format_batch_json_report, format_batch_report, load_manifest, BatchOptions,
+};
+use rust_sqlpackage::compare::breaking::{classify_changes, FailOn};
+use rust_sqlpackage::compare::data_loss::data_loss_warnings;
+use rust_sqlpackage::compare::filter::KeyFilter;
+use rust_sqlpackage::compare::known::KnownDifferences;
+use rust_sqlpackage::compare::report::ReportFormat;
+use rust_sqlpackage::compare::types::{CompareOptions, CompareResult, KeyCase};
+use rust_sqlpackage::config::WorkspaceConfig;
+use rust_sqlpackage::model::{ElementOrder, ModelElement, ObjectFilter, OutputMode, ScriptFormat};
+use rust_sqlpackage::parser::{self, lexer, LineEndings, ParseFallback};
+use rust_sqlpackage::project::{
+    add_sql_files, format_sarif, lint_model, lint_project, sql_files_to_add, LintFormat, LintIssue,
+    Ruleset, SqlServerVersion, UnknownElementPolicy,
+};
+use rust_sqlpackage::self_update;
+use rust_sqlpackage::unused::format_unused_objects;
+use rust_sqlpackage::util::long_path;
+use rust_sqlpackage::{
+    build_dacpac, compile_sql, dump_model, find_unused, generate_docs, generate_erd,
+    generate_lineage, load_model, read_dacpac_part, BuildOptions, DocsFormat, DumpFilter,
+    EntryPoints, ErdFormat, SqlPackageError, SummaryFormat,
+};
 
 #[derive(Parser)]
 #[command(name = "rust-sqlpackage")]
 #[command(
     author,
     version,
-    about = "Fast Rust compiler for SQL Server database projects"
+    about = "Fast Rust compiler for SQL Server database projects",
+    disable_version_flag = true,
+    arg_required_else_help = true
 )]
 struct Cli {
     #[command(subcommand)]
-    command: Commands,
+    command: Option<Commands>,
+
+    /// Print version
+    #[arg(short = 'V', long)]
+    version: bool,
+
+    /// With --version: print the version, platform and features as JSON
+    #[arg(long, requires = "version")]
+    json: bool,
+
+    /// Print the man page, or write one per command to DIR
+    #[arg(long, value_name = "DIR")]
+    generate_manpage: Option<Option<PathBuf>>,
+
+    /// Config file with defaults for command-line options (defaults to the nearest
+    /// .sqlpackage.toml in the current directory or its ancestors)
+    #[arg(long, global = true, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Print the files a command would write (or the changes it would make) without
+    /// writing anything
+    #[arg(long, global = true)]
+    dry_run: bool,
 }
 
 #[derive(Subcommand)]
@@ -22,77 +74,1458 @@ enum Commands {
     /// Build a .sqlproj file into a .dacpac package
     Build {
         /// Path to the .sqlproj file
-        #[arg(short, long)]
-        project: PathBuf,
+        #[arg(short, long, required_unless_present = "from_dir")]
+        project: Option<PathBuf>,
+
+        /// Build every .sql file under this folder, without a .sqlproj
+        #[arg(long, value_name = "DIR", conflicts_with = "project")]
+        from_dir: Option<PathBuf>,
+
+        /// Database name for --from-dir (defaults to the folder's name)
+        #[arg(long, requires = "from_dir")]
+        name: Option<String>,
 
-        /// Output path for the .dacpac file (defaults to bin/Debug/<project>.dacpac)
+        /// Output path for the .dacpac file (defaults to bin/Debug/<project>.dacpac, or
+        /// <build.output_dir>/<project>.dacpac when the config file sets one)
         #[arg(short, long)]
         output: Option<PathBuf>,
 
-        /// Target SQL Server platform (Sql130, Sql140, Sql150, Sql160)
-        #[arg(short, long, default_value = "Sql160")]
-        target_platform: String,
+        /// Target SQL Server platform for --from-dir (Sql130, Sql140, Sql150, Sql160;
+        /// default Sql160)
+        #[arg(short, long)]
+        target_platform: Option<SqlServerVersion>,
 
         /// Enable verbose output
         #[arg(short, long)]
         verbose: bool,
+
+        /// Write a machine-readable build summary (json)
+        #[arg(long)]
+        summary_format: Option<SummaryFormat>,
+
+        /// Path for the build summary (defaults to <output>.summary.json next to the dacpac)
+        #[arg(long, requires = "summary_format")]
+        summary_path: Option<PathBuf>,
+
+        /// Write a JSON map of each model element to a hash of its content
+        #[arg(long)]
+        object_hashes: bool,
+
+        /// Path for the object hashes (defaults to <output>.hashes.json next to the dacpac)
+        #[arg(long, requires = "object_hashes")]
+        object_hashes_path: Option<PathBuf>,
+
+        /// Include or exclude objects: <include|exclude>:<schema|type|name>=<glob> (can be repeated)
+        #[arg(long = "filter")]
+        filters: Vec<ObjectFilter>,
+
+        /// JSON file of extra dacpacs to build next to the output: {"Name": ["<filter>", ...]}
+        #[arg(long, value_name = "FILE")]
+        profiles: Option<PathBuf>,
+
+        /// Set a SQLCMD variable for :IF blocks: Name=Value (can be repeated)
+        #[arg(long = "variable", value_parser = parse_variable)]
+        variables: Vec<(String, String)>,
+
+        /// Set an MSBuild property for Condition attributes on items: Name=Value (can be
+        /// repeated); overrides the project's value and the environment
+        #[arg(long = "property", value_name = "NAME=VALUE", value_parser = parse_property)]
+        properties: Vec<(String, String)>,
+
+        /// model.xml output: parity (default, byte-identical to DacFx) or relaxed (skips
+        /// DacFx-only details)
+        #[arg(long)]
+        output_mode: Option<OutputMode>,
+
+        /// Script properties in model.xml: cdata (default, as DacFx) or escaped (escaped
+        /// text, for XML consumers that do not handle CDATA)
+        #[arg(long)]
+        script_format: Option<ScriptFormat>,
+
+        /// model.xml element order: dotnet (default, as DacFx) or logical (grouped by
+        /// schema, each object followed by its constraints and indexes; needs
+        /// --output-mode relaxed)
+        #[arg(long)]
+        element_order: Option<ElementOrder>,
+
+        /// Model schema version for older DacFx consumers (2.4-2.9, default 2.9)
+        #[arg(long)]
+        schema_version: Option<String>,
+
+        /// Keep SQLCMD :r includes in pre/post-deploy scripts for resolution at deploy time
+        #[arg(long)]
+        keep_includes: bool,
+
+        /// Add MS_Description extended properties from the header comments of tables,
+        /// views, procedures and functions that have none
+        #[arg(long)]
+        header_descriptions: bool,
+
+        /// Add MS_Description extended properties to columns from trailing
+        /// `-- description: ...` comments
+        #[arg(long)]
+        column_descriptions: bool,
+
+        /// Line endings of script properties: preserve (default, as DacFx), lf or crlf
+        #[arg(long)]
+        line_endings: Option<LineEndings>,
+
+        /// Fail when the model has more than this many elements
+        #[arg(long, value_name = "COUNT")]
+        max_elements: Option<usize>,
+
+        /// Fail on SQL files larger than this many bytes
+        #[arg(long, value_name = "BYTES")]
+        max_file_size: Option<u64>,
+
+        /// Fail on batches (text between GOs) longer than this many bytes
+        #[arg(long, value_name = "BYTES")]
+        max_statement_length: Option<usize>,
+
+        /// Threads for parsing SQL files (default: one per CPU)
+        #[arg(long, value_name = "N")]
+        threads: Option<NonZeroUsize>,
+
+        /// Statements no parser understands: fail (default), raw (keep tables and views
+        /// as written, with a warning) or skip (also skip anything else, with a warning)
+        #[arg(long)]
+        parse_fallback: Option<ParseFallback>,
+
+        /// Project file elements the build does not apply (BuildExtensionConfiguration
+        /// items, project references, custom targets, unknown items): warn (default),
+        /// ignore or error
+        #[arg(long, value_name = "POLICY")]
+        unknown_elements: Option<UnknownElementPolicy>,
+
+        /// Embed a manifest of the source files and their SHA256 in the dacpac, for
+        /// verify-sources
+        #[arg(long)]
+        source_manifest: bool,
+    },
+
+    /// Check a .sqlproj for SQL files left out of the build, Build items pointing at
+    /// missing files, files included more than once and elements the build does not
+    /// apply, and its objects for deprecated
+    /// features, security risks and, with a ruleset, names (exits with code 1 on any issue)
+    Lint {
+        /// Path to the .sqlproj file
+        #[arg(short, long)]
+        project: PathBuf,
+
+        /// Don't report issues of this rule: not-in-build, missing-file,
+        /// duplicate-include, naming, deprecated-type, old-style-join, set-rowcount,
+        /// broad-grant, dynamic-sql, execute-as-owner or multiple-objects-per-file (can be
+        /// repeated)
+        #[arg(long = "ignore", value_name = "RULE")]
+        ignore: Vec<String>,
+
+        /// Ruleset file (TOML) with naming patterns and security settings
+        #[arg(long, value_name = "FILE")]
+        ruleset: Option<PathBuf>,
+
+        /// Output format: text (default) or sarif
+        #[arg(long, default_value = "text")]
+        format: LintFormat,
+    },
+
+    /// Add SQL files to a .sqlproj as Build items, skipping files it already builds
+    Add {
+        /// Path to the .sqlproj file
+        #[arg(short, long)]
+        project: PathBuf,
+
+        /// SQL files to add
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+
+    /// Compile a single .sql file (or stdin) and print the generated model.xml fragment
+    Compile {
+        /// Path to the .sql file
+        #[arg(required_unless_present = "stdin")]
+        file: Option<PathBuf>,
+
+        /// Read SQL from stdin instead of a file
+        #[arg(long, conflicts_with = "file")]
+        stdin: bool,
+
+        /// Target SQL Server platform (Sql130, Sql140, Sql150, Sql160; default Sql160)
+        #[arg(short, long)]
+        target_platform: Option<SqlServerVersion>,
+
+        /// Print the file's token stream, as the SQL parser tokenizes it, instead of the
+        /// model.xml fragment (to compare tokenizer behavior across parser versions)
+        #[arg(long)]
+        parser_compat: bool,
+    },
+
+    /// Generate schema documentation (Markdown or HTML) from a .sqlproj
+    Docs {
+        /// Path to the .sqlproj file
+        #[arg(short, long)]
+        project: PathBuf,
+
+        /// Output format (markdown, html)
+        #[arg(short, long, default_value = "markdown")]
+        format: DocsFormat,
+
+        /// Output file (prints to stdout when omitted)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Export an entity-relationship diagram (mermaid or PlantUML) of tables and foreign keys
+    Erd {
+        /// Path to the .sqlproj file
+        #[arg(short, long)]
+        project: PathBuf,
+
+        /// Output format (mermaid, plantuml)
+        #[arg(short, long, default_value = "mermaid")]
+        format: ErdFormat,
+
+        /// Only include tables in this schema (can be repeated)
+        #[arg(short, long = "schema")]
+        schemas: Vec<String>,
+
+        /// Output file (prints to stdout when omitted)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Export column-level lineage (JSON) mapping view and inline TVF columns to table columns
+    Lineage {
+        /// Path to the .sqlproj file
+        #[arg(short, long)]
+        project: PathBuf,
+
+        /// Output file (prints to stdout when omitted)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// List views, procedures and functions that nothing in the project references
+    Unused {
+        /// Path to the .sqlproj file
+        #[arg(short, long)]
+        project: PathBuf,
+
+        /// Objects called from outside the database, never reported, e.g. api.* or usp_*
+        /// (glob, can be repeated)
+        #[arg(long = "entry-point", value_name = "PATTERN")]
+        entry_points: Vec<String>,
+
+        /// Output file (prints to stdout when omitted)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Print the built model as a tree of elements, properties and relationships (for debugging)
+    DumpModel {
+        /// Path to the .sqlproj file
+        #[arg(short, long)]
+        project: PathBuf,
+
+        /// Only dump elements of this type, e.g. SqlTable or Table (glob, can be repeated)
+        #[arg(long = "type")]
+        types: Vec<String>,
+
+        /// Only dump elements with this name, e.g. dbo.Orders or Order* (glob, can be repeated)
+        #[arg(long = "name")]
+        names: Vec<String>,
+
+        /// Output file (prints to stdout when omitted)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Write a Markdown changelog of schema changes between two builds (.dacpac or .sqlproj)
+    Changelog {
+        /// Path to the old build (.dacpac or .sqlproj)
+        old: PathBuf,
+
+        /// Path to the new build (.dacpac or .sqlproj)
+        new: PathBuf,
+
+        /// Output file (prints to stdout when omitted)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Merge several dacpacs into one, failing if they define an object differently
+    Merge {
+        /// Dacpacs to merge; the first one's header settings and version are kept
+        #[arg(required = true, num_args = 2..)]
+        inputs: Vec<PathBuf>,
+
+        /// Path of the merged dacpac
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// Name in DacMetadata.xml (defaults to the first dacpac's)
+        #[arg(long)]
+        name: Option<String>,
+    },
+
+    /// Extract the objects matching filters, and everything they depend on, into a new dacpac
+    Slice {
+        /// Path to the .dacpac file
+        dacpac: PathBuf,
+
+        /// Path of the sliced dacpac
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// Include or exclude objects: <include|exclude>:<schema|type|name>=<glob> (can be repeated)
+        #[arg(long = "filter", required = true)]
+        filters: Vec<ObjectFilter>,
+    },
+
+    /// Check source files against the manifest a dacpac was built with (--source-manifest);
+    /// exits with code 1 if any file changed or is missing
+    VerifySources {
+        /// Path to the .dacpac file
+        dacpac: PathBuf,
+
+        /// Directory the manifest's paths are relative to: the project's directory
+        #[arg(long, value_name = "DIR", default_value = ".")]
+        project_dir: PathBuf,
+    },
+
+    /// Replace this binary with a release from a mirror folder, for build agents without
+    /// internet access
+    SelfUpdate {
+        /// Mirror folder or file:// URL (defaults to $SQLPACKAGE_UPDATE_MIRROR)
+        #[arg(long)]
+        mirror: Option<String>,
+
+        /// Version to install (defaults to the one in the mirror's `latest` file)
+        #[arg(long, value_name = "VERSION")]
+        to: Option<String>,
+    },
+
+    /// Print a shell completion script (bash, zsh, fish, powershell or elvish)
+    Completions {
+        /// Shell to complete in
+        shell: Shell,
+    },
+
+    /// Print one part of a dacpac (model.xml, Origin.xml, DacMetadata.xml, ...) to stdout
+    Show {
+        /// Path to the .dacpac file
+        dacpac: PathBuf,
+
+        /// Part to print: a file name in the dacpac or model, origin, metadata,
+        /// content-types, predeploy or postdeploy
+        #[arg(long, default_value = "model.xml")]
+        part: String,
+
+        /// Re-indent XML parts one element per line
+        #[arg(long)]
+        pretty: bool,
+
+        /// List the parts in the dacpac instead of printing one
+        #[arg(long, conflicts_with_all = ["part", "pretty"])]
+        list: bool,
     },
 
     /// Compare two dacpac files and report differences
     Compare {
-        /// Path to the rust-generated dacpac
+        /// Path to the rust-generated dacpac (with --objects: the old build, .dacpac or .sqlproj)
         rust_dacpac: PathBuf,
 
-        /// Path to the dotnet-generated dacpac
+        /// Path to the dotnet-generated dacpac (with --objects: the new build, .dacpac or .sqlproj)
         dotnet_dacpac: PathBuf,
+
+        /// Summarize objects added, removed and changed between two builds instead of
+        /// checking that the two model.xml files are identical
+        #[arg(long)]
+        objects: bool,
+
+        /// Exit with code 1 on any difference, only on elements present on one side
+        /// (element), only on changes to elements on both sides (property), or with
+        /// --objects only on breaking changes (dropped objects, columns or parameters,
+        /// narrowed types, columns made NOT NULL) or changes that can lose data
+        /// (any, element, property, breaking, data-loss; default any)
+        #[arg(long)]
+        fail_on: Option<FailOn>,
+
+        /// Exit with code 0 while the differences selected by --fail-on number at most N
+        /// (default 0)
+        #[arg(long, value_name = "N")]
+        max_differences: Option<usize>,
+
+        /// Print nothing; report the result through the exit code only
+        #[arg(short, long)]
+        quiet: bool,
+
+        /// How element names are matched: insensitive (default, as SQL Server's default
+        /// collations) or sensitive (for case-sensitive collations)
+        #[arg(long, conflicts_with = "objects")]
+        key_case: Option<KeyCase>,
+
+        /// Compare only elements of this type, with or without the Sql prefix, e.g.
+        /// SqlProcedure or View (can be repeated); skips the file and header comparison
+        #[arg(long = "only", value_name = "TYPE")]
+        only: Vec<String>,
+
+        /// Compare only elements whose name matches this glob, bare or schema-qualified
+        /// (e.g. 'dbo.usp_*'); skips the file and header comparison
+        #[arg(long, value_name = "GLOB")]
+        name: Option<Pattern>,
+
+        /// Leave out elements of this type, with or without the Sql prefix (can be
+        /// repeated); skips the file and header comparison
+        #[arg(long = "ignore", value_name = "TYPE")]
+        ignore: Vec<String>,
+
+        /// Print the model element with this name (e.g. '[dbo].[MyProc]' or dbo.MyProc)
+        /// from both dacpacs, pretty-printed side by side, instead of comparing them
+        #[arg(long, value_name = "NAME", conflicts_with = "objects")]
+        show_element: Option<String>,
+
+        /// Report format: text (default) or json
+        #[arg(long, conflicts_with = "objects")]
+        format: Option<ReportFormat>,
+
+        /// Leave out the known differences listed in this file, one element per line
+        /// (e.g. 'SqlTable [dbo].[Orders]')
+        #[arg(long, value_name = "FILE")]
+        ignore_file: Option<PathBuf>,
+
+        /// Browse the differing elements in a terminal UI, marking known differences
+        /// into --ignore-file (needs the tui feature)
+        #[arg(long, conflicts_with_all = ["objects", "quiet", "format"])]
+        interactive: bool,
+    },
+
+    /// Compare the pairs of dacpacs listed in a manifest in parallel, reporting which
+    /// pairs pass
+    CompareBatch {
+        /// JSON manifest: an array of {"name", "rust", "dotnet"} pairs, with paths
+        /// relative to the manifest (an optional "max_differences" overrides
+        /// --max-differences for a pair)
+        #[arg(long, value_name = "FILE")]
+        pairs: PathBuf,
+
+        /// Fail a pair on any difference, only on elements present on one side
+        /// (element), or only on changes to elements on both sides (property)
+        #[arg(long)]
+        fail_on: Option<FailOn>,
+
+        /// Pass a pair while its differences number at most N (default 0)
+        #[arg(long, value_name = "N")]
+        max_differences: Option<usize>,
+
+        /// Print nothing; exit with code 1 when any pair fails
+        #[arg(short, long)]
+        quiet: bool,
+
+        /// How element names are matched: insensitive (default) or sensitive
+        #[arg(long)]
+        key_case: Option<KeyCase>,
+
+        /// Compare only elements of this type (can be repeated)
+        #[arg(long = "only", value_name = "TYPE")]
+        only: Vec<String>,
+
+        /// Compare only elements whose name matches this glob
+        #[arg(long, value_name = "GLOB")]
+        name: Option<Pattern>,
+
+        /// Leave out elements of this type (can be repeated)
+        #[arg(long = "ignore", value_name = "TYPE")]
+        ignore: Vec<String>,
+
+        /// Leave out the known differences listed in this file, in every pair
+        #[arg(long, value_name = "FILE")]
+        ignore_file: Option<PathBuf>,
+
+        /// Report format: text (default) or json, which includes each pair's full report
+        #[arg(long)]
+        format: Option<ReportFormat>,
+
+        /// Write the report to this file instead of stdout
+        #[arg(short, long, conflicts_with = "quiet")]
+        output: Option<PathBuf>,
+
+        /// Pairs compared at once (default: one per CPU)
+        #[arg(long, value_name = "N")]
+        jobs: Option<NonZeroUsize>,
+    },
+
+    /// Build a project with dotnet build (DacFx) and store the dacpac as the baseline to
+    /// compare rust-sqlpackage's output against, recording the tool versions used
+    Baseline {
+        /// Path to the .sqlproj file
+        #[arg(short, long)]
+        project: PathBuf,
+
+        /// Where to store the baseline dacpac (defaults to baseline/<project>.dacpac in
+        /// the project directory); the record is written next to it as
+        /// <name>.baseline.json
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// MSBuild configuration to build
+        #[arg(long, default_value = "Debug")]
+        configuration: String,
+
+        /// The dotnet executable
+        #[arg(long, value_name = "PATH", default_value = "dotnet")]
+        dotnet: PathBuf,
+    },
+
+    /// Register a dacpac as the data-tier application deployed to a database
+    #[cfg(feature = "client")]
+    Register {
+        /// Path to the deployed dacpac
+        dacpac: PathBuf,
+
+        /// ADO.NET connection string of the target database (defaults to
+        /// $SQLPACKAGE_CONNECTION_STRING; a missing password is read from $SQLPACKAGE_PASSWORD)
+        #[arg(long)]
+        connection_string: Option<String>,
+
+        /// Connection from the config file's [connections.<NAME>] section
+        #[arg(long, value_name = "NAME", conflicts_with = "connection_string")]
+        connection_name: Option<String>,
+
+        /// Azure AD access token, used instead of the connection string's credentials
+        /// (defaults to $SQLPACKAGE_ACCESS_TOKEN)
+        #[arg(long)]
+        access_token: Option<String>,
+
+        /// Print the server and database connected to (passwords and tokens are masked)
+        #[arg(short, long)]
+        verbose: bool,
+
+        /// Description stored with the registration
+        #[arg(long, default_value = "")]
+        description: String,
+    },
+
+    /// Report objects added, removed or modified since the database was registered
+    /// (DriftReport XML, as written by SqlPackage)
+    #[cfg(feature = "client")]
+    DriftReport {
+        /// ADO.NET connection string of the registered database (defaults to
+        /// $SQLPACKAGE_CONNECTION_STRING; a missing password is read from $SQLPACKAGE_PASSWORD)
+        #[arg(long)]
+        connection_string: Option<String>,
+
+        /// Connection from the config file's [connections.<NAME>] section
+        #[arg(long, value_name = "NAME", conflicts_with = "connection_string")]
+        connection_name: Option<String>,
+
+        /// Azure AD access token, used instead of the connection string's credentials
+        /// (defaults to $SQLPACKAGE_ACCESS_TOKEN)
+        #[arg(long)]
+        access_token: Option<String>,
+
+        /// Print the server and database connected to (passwords and tokens are masked)
+        #[arg(short, long)]
+        verbose: bool,
+
+        /// Output file (prints to stdout when omitted)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
     },
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
+    if cli.version {
+        print_version(cli.json)?;
+        return Ok(());
+    }
+    if let Some(dir) = cli.generate_manpage {
+        return generate_manpage(dir, cli.dry_run);
+    }
+    let Some(command) = cli.command else {
+        Cli::command()
+            .error(
+                clap::error::ErrorKind::MissingSubcommand,
+                "a command is required",
+            )
+            .exit();
+    };
+    let config = match &cli.config {
+        Some(path) => WorkspaceConfig::load(path)?,
+        None => WorkspaceConfig::discover(&std::env::current_dir()?)?.unwrap_or_default(),
+    };
 
-    match cli.command {
+    match command {
         Commands::Build {
             project,
+            from_dir,
+            name,
             output,
             target_platform,
             verbose,
+            summary_format,
+            summary_path,
+            object_hashes,
+            object_hashes_path,
+            filters,
+            profiles,
+            variables,
+            properties,
+            output_mode,
+            script_format,
+            element_order,
+            schema_version,
+            keep_includes,
+            header_descriptions,
+            column_descriptions,
+            line_endings,
+            max_elements,
+            max_file_size,
+            max_statement_length,
+            threads,
+            parse_fallback,
+            unknown_elements,
+            source_manifest,
         } => {
+            let defaults = config.build;
             let options = BuildOptions {
-                project_path: project,
+                project_path: project.unwrap_or_default(),
+                from_dir,
+                name,
                 output_path: output,
-                target_platform,
+                output_dir: defaults.output_dir,
+                target_platform: target_platform
+                    .or(defaults.target_platform)
+                    .unwrap_or_default()
+                    .to_string(),
                 verbose,
+                summary_format,
+                summary_path,
+                object_hashes,
+                object_hashes_path,
+                filters: defaults.filters.into_iter().chain(filters).collect(),
+                profiles_path: profiles,
+                variables: defaults.variables.into_iter().chain(variables).collect(),
+                properties: defaults.properties.into_iter().chain(properties).collect(),
+                output_mode: output_mode.or(defaults.output_mode).unwrap_or_default(),
+                script_format: script_format.or(defaults.script_format).unwrap_or_default(),
+                element_order: element_order.or(defaults.element_order).unwrap_or_default(),
+                schema_version,
+                keep_deploy_includes: keep_includes,
+                header_descriptions,
+                column_descriptions,
+                line_endings: line_endings.or(defaults.line_endings).unwrap_or_default(),
+                max_elements,
+                max_file_size,
+                max_statement_length,
+                threads: threads.or(defaults.threads).map(NonZeroUsize::get),
+                parse_fallback: parse_fallback
+                    .or(defaults.parse_fallback)
+                    .unwrap_or_default(),
+                unknown_elements: unknown_elements
+                    .or(defaults.unknown_elements)
+                    .unwrap_or_default(),
+                model_overrides: defaults.overrides,
+                source_manifest: source_manifest || defaults.source_manifest,
+                dry_run: cli.dry_run,
+                cancellation: None,
+                on_progress: None,
             };
 
             build_dacpac(options)?;
         }
 
+        Commands::Lint {
+            project,
+            ignore,
+            ruleset,
+            format,
+        } => {
+            let ignore: Vec<String> = config.lint.ignore.into_iter().chain(ignore).collect();
+            if let Some(rule) = ignore
+                .iter()
+                .find(|rule| !LintIssue::RULES.contains(&rule.as_str()))
+            {
+                return Err(anyhow::anyhow!(
+                    "Unknown lint rule: {} (expected {})",
+                    rule,
+                    LintIssue::RULES.join(", ")
+                ));
+            }
+            let mut issues = lint_project(&project)?;
+            let ruleset = match ruleset.or(config.lint.ruleset) {
+                Some(path) => Ruleset::load(&path)?,
+                None => Ruleset::default(),
+            };
+            // A project that doesn't build still gets its file checks
+            match load_model(&project) {
+                Ok((sql_project, model)) => {
+                    issues.extend(lint_model(&model, &sql_project, &ruleset))
+                }
+                Err(e) => eprintln!(
+                    "Warning: objects not checked, the project does not build: {}",
+                    e
+                ),
+            }
+            issues.retain(|issue| !ignore.iter().any(|rule| rule == issue.rule()));
+            match format {
+                LintFormat::Text => {
+                    for issue in &issues {
+                        println!("{}", issue);
+                    }
+                }
+                LintFormat::Sarif => print!("{}", format_sarif(&issues, &project)),
+            }
+            if !issues.is_empty() {
+                eprintln!("{} issue(s) in {}", issues.len(), project.display());
+                process::exit(1);
+            }
+        }
+
+        Commands::Add { project, files } => {
+            let added = if cli.dry_run {
+                sql_files_to_add(&project, &files)?
+            } else {
+                add_sql_files(&project, &files)?
+            };
+            let verb = if cli.dry_run { "Would add" } else { "Added" };
+            for include in &added {
+                println!("{} {}", verb, include);
+            }
+            if added.len() < files.len() {
+                println!("{} file(s) already in the build", files.len() - added.len());
+            }
+        }
+
+        Commands::Compile {
+            file,
+            stdin,
+            target_platform,
+            parser_compat,
+        } => {
+            let version = target_platform
+                .or(config.build.target_platform)
+                .unwrap_or_default();
+            let (sql, source_path) = if stdin {
+                let mut sql = String::new();
+                std::io::stdin().read_to_string(&mut sql)?;
+                (sql, PathBuf::from("<stdin>"))
+            } else {
+                // clap requires a file unless --stdin is given
+                let path = file.expect("file is required without --stdin");
+                // Decoded the same way as the files of a project
+                let sql = std::fs::read(long_path(&path))
+                    .and_then(|bytes| parser::decode_source(&bytes, None))
+                    .map_err(|e| SqlPackageError::SqlFileReadError {
+                        path: path.clone(),
+                        source: e,
+                    })?;
+                (sql, path)
+            };
+
+            if parser_compat {
+                let tokens =
+                    lexer::dump_tokens(&sql).map_err(|e| SqlPackageError::SqlParseError {
+                        path: source_path.clone(),
+                        line: e.location.line as usize,
+                        message: e.message,
+                    })?;
+                print!("{}", tokens);
+            } else {
+                let fragment = compile_sql(&sql, &source_path, version)?;
+                println!("{}", fragment);
+            }
+        }
+
+        Commands::Docs {
+            project,
+            format,
+            output,
+        } => {
+            let docs = generate_docs(&project, format)?;
+            write_output(output, &docs, cli.dry_run)?;
+        }
+
+        Commands::Erd {
+            project,
+            format,
+            schemas,
+            output,
+        } => {
+            let diagram = generate_erd(&project, format, &schemas)?;
+            write_output(output, &diagram, cli.dry_run)?;
+        }
+
+        Commands::Lineage { project, output } => {
+            let lineage = generate_lineage(&project)?;
+            write_output(output, &lineage, cli.dry_run)?;
+        }
+
+        Commands::Unused {
+            project,
+            entry_points,
+            output,
+        } => {
+            let entry_points = EntryPoints::new(
+                &config
+                    .unused
+                    .entry_points
+                    .into_iter()
+                    .chain(entry_points)
+                    .collect::<Vec<_>>(),
+            )?;
+            let objects = find_unused(&project, &entry_points)?;
+            write_output(output, &format_unused_objects(&objects), cli.dry_run)?;
+            eprintln!(
+                "{} unused object(s) in {}",
+                objects.len(),
+                project.display()
+            );
+        }
+
+        Commands::DumpModel {
+            project,
+            types,
+            names,
+            output,
+        } => {
+            let filter = DumpFilter::new(&types, &names)?;
+            let dump = dump_model(&project, &filter)?;
+            write_output(output, &dump, cli.dry_run)?;
+        }
+
+        Commands::Changelog { old, new, output } => {
+            let diff = rust_sqlpackage::compare::diff_builds(&old, &new)?;
+            let label = |path: &PathBuf| {
+                path.file_name().map_or_else(
+                    || path.display().to_string(),
+                    |name| name.to_string_lossy().into_owned(),
+                )
+            };
+            let changelog = rust_sqlpackage::compare::changelog::render_changelog(
+                &diff,
+                &label(&old),
+                &label(&new),
+            );
+            write_output(output, &changelog, cli.dry_run)?;
+        }
+
+        Commands::Merge {
+            inputs,
+            output,
+            name,
+        } => {
+            let count = inputs.len();
+            let summary = rust_sqlpackage::merge_dacpacs(&rust_sqlpackage::MergeOptions {
+                inputs,
+                output_path: output.clone(),
+                name,
+                dry_run: cli.dry_run,
+            })?;
+            let verb = if cli.dry_run { "Would merge" } else { "Merged" };
+            println!(
+                "{} {} dacpacs into {} ({} elements, {} shared)",
+                verb,
+                count,
+                output.display(),
+                summary.elements,
+                summary.shared
+            );
+        }
+
+        Commands::Slice {
+            dacpac,
+            output,
+            filters,
+        } => {
+            let summary = rust_sqlpackage::slice_dacpac(&rust_sqlpackage::SliceOptions {
+                input: dacpac,
+                output_path: output.clone(),
+                filters,
+                dry_run: cli.dry_run,
+            })?;
+            let verb = if cli.dry_run { "Would write" } else { "Wrote" };
+            println!(
+                "{} {} of {} elements to {}",
+                verb,
+                summary.elements,
+                summary.total,
+                output.display()
+            );
+            if !summary.dependencies.is_empty() {
+                println!("Included as dependencies:");
+                for dependency in &summary.dependencies {
+                    println!("  {}", dependency);
+                }
+            }
+        }
+
+        Commands::VerifySources {
+            dacpac,
+            project_dir,
+        } => {
+            let verification = rust_sqlpackage::dacpac::verify_sources(&dacpac, &project_dir)?;
+            for name in &verification.changed {
+                println!("changed: {}", name);
+            }
+            for name in &verification.missing {
+                println!("missing: {}", name);
+            }
+            println!(
+                "{} verified, {} changed, {} missing",
+                verification.verified,
+                verification.changed.len(),
+                verification.missing.len()
+            );
+            if !verification.is_ok() {
+                process::exit(1);
+            }
+        }
+
+        Commands::SelfUpdate { mirror, to } => {
+            let mirror = mirror
+                .or_else(|| std::env::var(UPDATE_MIRROR_ENV).ok())
+                .ok_or_else(|| SqlPackageError::SelfUpdateError {
+                    message: format!("no mirror given (--mirror or ${})", UPDATE_MIRROR_ENV),
+                })?;
+            let release =
+                self_update::find_release(&self_update::mirror_dir(&mirror)?, to.as_deref())?;
+            if release.version == self_update::VERSION {
+                println!("Already at {}", release.version);
+            } else if cli.dry_run {
+                println!(
+                    "Would update {} to {} ({})",
+                    self_update::VERSION,
+                    release.version,
+                    release.binary.display()
+                );
+            } else {
+                self_update::install(&release, &std::env::current_exe()?)?;
+                println!("Updated {} to {}", self_update::VERSION, release.version);
+            }
+        }
+
+        Commands::Completions { shell } => {
+            clap_complete::generate(
+                shell,
+                &mut completion_command(),
+                "rust-sqlpackage",
+                &mut std::io::stdout(),
+            );
+        }
+
+        Commands::Show {
+            dacpac, list: true, ..
+        } => {
+            let contents = rust_sqlpackage::compare::reader::DacpacContents::from_path(&dacpac)?;
+            let mut names: Vec<&str> = contents.file_names().collect();
+            names.sort_unstable();
+            for name in names {
+                let size = contents.get_bytes(name).map_or(0, <[u8]>::len);
+                println!("{:>10}  {}", size, name);
+            }
+        }
+
+        Commands::Show {
+            dacpac,
+            part,
+            pretty,
+            list: false,
+        } => {
+            let data = read_dacpac_part(&dacpac, &part, pretty)?;
+            std::io::stdout().lock().write_all(&data)?;
+        }
+
         Commands::Compare {
             rust_dacpac,
             dotnet_dacpac,
+            key_case,
+            show_element: Some(element_name),
+            ..
         } => {
-            let result = rust_sqlpackage::compare::compare_dacpacs(&rust_dacpac, &dotnet_dacpac)?;
+            let key_case = key_case.or(config.compare.key_case).unwrap_or_default();
+            print!(
+                "{}",
+                rust_sqlpackage::compare::show_element(
+                    &rust_dacpac,
+                    &dotnet_dacpac,
+                    &element_name,
+                    key_case
+                )?
+            );
+        }
 
-            // Print duplicate warnings to stderr
-            for (source, keys) in &result.duplicate_warnings {
-                eprintln!(
-                    "WARNING: {} duplicate keys in {} model.xml",
-                    keys.len(),
-                    source
-                );
-                for key in keys.iter().take(5) {
-                    eprintln!("  {}", key);
+        Commands::Compare {
+            rust_dacpac,
+            dotnet_dacpac,
+            objects: true,
+            fail_on,
+            max_differences,
+            quiet,
+            only,
+            name,
+            ignore,
+            ignore_file,
+            ..
+        } => {
+            let defaults = config.compare;
+            let fail_on = fail_on.or(defaults.fail_on).unwrap_or_default();
+            let max_differences = max_differences.or(defaults.max_differences).unwrap_or(0);
+            let known = load_known_differences(ignore_file.or(defaults.ignore_file))?;
+            let mut diff = rust_sqlpackage::compare::diff_builds(&rust_dacpac, &dotnet_dacpac)?;
+            diff.retain(&KeyFilter {
+                element_types: only,
+                name,
+                ignored_types: defaults.ignore.into_iter().chain(ignore).collect(),
+            });
+            report_known_differences(&known, known.apply_to_diff(&mut diff), quiet);
+            let changes = classify_changes(&diff);
+            let warnings = data_loss_warnings(&diff);
+            if !quiet {
+                rust_sqlpackage::compare::report::print_model_diff(&diff);
+                println!();
+                rust_sqlpackage::compare::report::print_breaking_changes(&changes);
+                println!();
+                rust_sqlpackage::compare::report::print_data_loss_warnings(&warnings);
+            }
+
+            let differences = match fail_on {
+                FailOn::Any => diff.element_differences() + diff.property_differences(),
+                FailOn::Element => diff.element_differences(),
+                FailOn::Property => diff.property_differences(),
+                FailOn::Breaking => changes.iter().filter(|c| c.breaking).count(),
+                FailOn::DataLoss => warnings.len(),
+            };
+            exit_on_differences(differences, max_differences, quiet);
+        }
+
+        Commands::Compare {
+            rust_dacpac,
+            dotnet_dacpac,
+            objects: false,
+            fail_on,
+            max_differences,
+            quiet,
+            format,
+            key_case,
+            only,
+            name,
+            ignore,
+            show_element: None,
+            ignore_file,
+            interactive,
+        } => {
+            let defaults = config.compare;
+            let fail_on = fail_on.or(defaults.fail_on).unwrap_or_default();
+            let max_differences = max_differences.or(defaults.max_differences).unwrap_or(0);
+            let key_case = key_case.or(defaults.key_case).unwrap_or_default();
+            let differences_of: fn(&CompareResult) -> usize = match fail_on {
+                FailOn::Any => |r| r.element_differences() + r.property_differences(),
+                FailOn::Element => CompareResult::element_differences,
+                FailOn::Property => CompareResult::property_differences,
+                FailOn::Breaking | FailOn::DataLoss => {
+                    return Err(anyhow::anyhow!(
+                        "--fail-on breaking and --fail-on data-loss require --objects"
+                    ));
                 }
+            };
+
+            let options = CompareOptions {
+                key_case,
+                filter: KeyFilter {
+                    element_types: only,
+                    name,
+                    ignored_types: defaults.ignore.into_iter().chain(ignore).collect(),
+                },
+            };
+            let mut result = rust_sqlpackage::compare::compare_dacpacs_with_options(
+                &rust_dacpac,
+                &dotnet_dacpac,
+                &options,
+            )?;
+            let known = load_known_differences(ignore_file.or(defaults.ignore_file))?;
+            if interactive {
+                return browse_differences(&result, &rust_dacpac, &dotnet_dacpac, key_case, known);
             }
+            report_known_differences(&known, known.apply(&mut result), quiet);
 
-            rust_sqlpackage::compare::report::print_report(&result);
+            if !quiet {
+                match format.unwrap_or_default() {
+                    ReportFormat::Text => rust_sqlpackage::compare::report::print_report(&result),
+                    ReportFormat::Json => {
+                        print!(
+                            "{}",
+                            rust_sqlpackage::compare::report::format_json_report(&result)
+                        )
+                    }
+                }
+            }
+
+            exit_on_differences(differences_of(&result), max_differences, quiet);
+        }
 
-            if result.has_differences() {
+        Commands::CompareBatch {
+            pairs,
+            fail_on,
+            max_differences,
+            quiet,
+            key_case,
+            only,
+            name,
+            ignore,
+            ignore_file,
+            format,
+            output,
+            jobs,
+        } => {
+            let defaults = config.compare;
+            let pairs = load_manifest(&pairs)?;
+            let options = BatchOptions {
+                compare: CompareOptions {
+                    key_case: key_case.or(defaults.key_case).unwrap_or_default(),
+                    filter: KeyFilter {
+                        element_types: only,
+                        name,
+                        ignored_types: defaults.ignore.into_iter().chain(ignore).collect(),
+                    },
+                },
+                known: load_known_differences(ignore_file.or(defaults.ignore_file))?,
+                fail_on: fail_on.or(defaults.fail_on).unwrap_or_default(),
+                max_differences: max_differences.or(defaults.max_differences).unwrap_or(0),
+            };
+            let report = match jobs {
+                Some(jobs) => rayon::ThreadPoolBuilder::new()
+                    .num_threads(jobs.get())
+                    .build()?
+                    .install(|| compare_batch(pairs, &options))?,
+                None => compare_batch(pairs, &options)?,
+            };
+            if !quiet {
+                let content = match format.unwrap_or_default() {
+                    ReportFormat::Text => format_batch_report(&report),
+                    ReportFormat::Json => format_batch_json_report(&report),
+                };
+                write_output(output, &content, cli.dry_run)?;
+            }
+            if !report.is_ok() {
                 process::exit(1);
             }
         }
+
+        Commands::Baseline {
+            project,
+            output,
+            configuration,
+            dotnet,
+        } => {
+            let output = match output {
+                Some(output) => output,
+                None => baseline::default_output_path(&project)?,
+            };
+            if cli.dry_run {
+                println!(
+                    "Would build {} with {} build ({}) and write {} and {}",
+                    project.display(),
+                    dotnet.display(),
+                    configuration,
+                    output.display(),
+                    baseline::record_path(&output).display()
+                );
+                return Ok(());
+            }
+            let options = baseline::BaselineOptions {
+                configuration,
+                dotnet,
+            };
+            let record = baseline::create_baseline(&project, &output, &options)?;
+            println!("Baseline written to {}", output.display());
+            println!("  .NET SDK:  {}", record.dotnet_sdk);
+            if let Some(sdk) = &record.build_sdk {
+                println!("  Build SDK: {}", sdk);
+            }
+            if let Some(dacfx) = &record.dacfx {
+                println!("  DacFx:     {}", dacfx);
+            }
+        }
+
+        #[cfg(feature = "client")]
+        Commands::Register {
+            dacpac,
+            connection_string,
+            connection_name,
+            access_token,
+            verbose,
+            description,
+        } => {
+            let options = connection_options(
+                &config,
+                connection_string,
+                connection_name,
+                access_token,
+                verbose,
+            )?;
+            let bytes = std::fs::read(&dacpac).map_err(|e| SqlPackageError::InvalidDacpac {
+                path: dacpac.clone(),
+                message: e.to_string(),
+            })?;
+            if cli.dry_run {
+                println!("Would register {} in {}", dacpac.display(), options);
+                return Ok(());
+            }
+            tokio::runtime::Runtime::new()?.block_on(rust_sqlpackage::client::register_dacpac(
+                &options,
+                &bytes,
+                &description,
+            ))?;
+        }
+
+        #[cfg(feature = "client")]
+        Commands::DriftReport {
+            connection_string,
+            connection_name,
+            access_token,
+            verbose,
+            output,
+        } => {
+            let options = connection_options(
+                &config,
+                connection_string,
+                connection_name,
+                access_token,
+                verbose,
+            )?;
+            let report = tokio::runtime::Runtime::new()?
+                .block_on(rust_sqlpackage::client::drift_report(&options))?;
+            write_output(output, &report.to_xml(), cli.dry_run)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Connection options from the command line, a named connection or the environment
+#[cfg(feature = "client")]
+fn connection_options(
+    config: &WorkspaceConfig,
+    connection_string: Option<String>,
+    connection_name: Option<String>,
+    access_token: Option<String>,
+    verbose: bool,
+) -> Result<rust_sqlpackage::client::ConnectionOptions> {
+    let connection = connection_name
+        .map(|name| {
+            config
+                .connections
+                .get(&name)
+                .ok_or_else(|| anyhow::anyhow!("No connection named '{}' in the config file", name))
+        })
+        .transpose()?;
+    let options = rust_sqlpackage::client::resolve_connection(
+        connection_string.as_deref(),
+        connection,
+        access_token,
+    )?;
+    if verbose {
+        eprintln!("Connecting: {}", options);
+    }
+    Ok(options)
+}
+
+/// Parse a `Name=Value` SQLCMD variable assignment
+fn parse_variable(s: &str) -> Result<(String, String), String> {
+    parse_assignment(s, "variable")
+}
+
+/// Parse a `Name=Value` MSBuild property assignment
+fn parse_property(s: &str) -> Result<(String, String), String> {
+    parse_assignment(s, "property")
+}
+
+fn parse_assignment(s: &str, kind: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((name, value)) if !name.trim().is_empty() => {
+            Ok((name.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!("Invalid {} '{}': expected Name=Value", kind, s)),
+    }
+}
+
+/// Write command output to a file, or to stdout when no path is given
+///
+/// With `dry_run`, only reports the file that would be written.
+fn write_output(output: Option<PathBuf>, content: &str, dry_run: bool) -> Result<()> {
+    match output {
+        Some(path) if dry_run => {
+            println!("Would write {} ({} bytes)", path.display(), content.len());
+        }
+        Some(path) => {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent).map_err(|e| SqlPackageError::OutputWriteError {
+                    path: path.clone(),
+                    source: e,
+                })?;
+            }
+            std::fs::write(&path, content)
+                .map_err(|e| SqlPackageError::OutputWriteError { path, source: e })?;
+        }
+        None => print!("{}", content),
     }
+    Ok(())
+}
+
+/// Environment variable with the default `self-update` mirror
+const UPDATE_MIRROR_ENV: &str = "SQLPACKAGE_UPDATE_MIRROR";
 
+/// Print `--version`, as clap does or as JSON
+fn print_version(json: bool) -> Result<()> {
+    let info = self_update::VersionInfo::current();
+    if json {
+        println!("{}", serde_json::to_string_pretty(&info)?);
+    } else {
+        println!("{} {}", info.name, info.version);
+    }
     Ok(())
 }
+
+/// Values offered for arguments parsed with `FromStr`, which clap cannot list itself, as
+/// (command, argument, values); `*` matches every command with the argument
+const COMPLETION_VALUES: &[(&str, &str, &[&str])] = &[
+    (
+        "*",
+        "target_platform",
+        &["Sql130", "Sql140", "Sql150", "Sql160"],
+    ),
+    ("*", "summary_format", &["json"]),
+    ("*", "output_mode", &["parity", "relaxed"]),
+    ("*", "script_format", &["cdata", "escaped"]),
+    ("*", "element_order", &["dotnet", "logical"]),
+    ("*", "line_endings", &["preserve", "lf", "crlf"]),
+    ("*", "parse_fallback", &["fail", "raw", "skip"]),
+    ("*", "unknown_elements", &["warn", "ignore", "error"]),
+    (
+        "*",
+        "fail_on",
+        &["any", "element", "property", "breaking", "data-loss"],
+    ),
+    ("*", "key_case", &["insensitive", "sensitive"]),
+    ("lint", "format", &["text", "sarif"]),
+    ("docs", "format", &["markdown", "html"]),
+    ("erd", "format", &["mermaid", "plantuml"]),
+    ("compare", "format", &["text", "json"]),
+    ("compare-batch", "format", &["text", "json"]),
+    ("compare-batch", "fail_on", &["any", "element", "property"]),
+    ("dump-model", "types", ModelElement::TYPE_NAMES),
+    ("compare", "only", ModelElement::TYPE_NAMES),
+    ("compare", "ignore", ModelElement::TYPE_NAMES),
+    ("compare-batch", "only", ModelElement::TYPE_NAMES),
+    ("compare-batch", "ignore", ModelElement::TYPE_NAMES),
+];
+
+/// The CLI with [`COMPLETION_VALUES`] set as possible values, for completion scripts
+/// and man pages (parsing still goes through `FromStr`, accepting aliases too)
+fn completion_command() -> clap::Command {
+    let mut command = Cli::command();
+    let names: Vec<String> = command
+        .get_subcommands()
+        .map(|subcommand| subcommand.get_name().to_string())
+        .collect();
+    for name in names {
+        command = command.mut_subcommand(&name, |mut subcommand| {
+            for (target, id, values) in COMPLETION_VALUES {
+                if (*target == "*" || *target == name)
+                    && subcommand.get_arguments().any(|arg| arg.get_id() == *id)
+                {
+                    subcommand = subcommand.mut_arg(*id, |arg| {
+                        arg.value_parser(clap::builder::PossibleValuesParser::new(*values))
+                    });
+                }
+            }
+            subcommand
+        });
+    }
+    command
+}
+
+/// Print the man page to stdout, or write one per command to `dir`
+fn generate_manpage(dir: Option<PathBuf>, dry_run: bool) -> Result<()> {
+    let command = completion_command();
+    let Some(dir) = dir else {
+        clap_mangen::Man::new(command).render(&mut std::io::stdout())?;
+        return Ok(());
+    };
+    if dry_run {
+        println!("Would write man pages to {}", dir.display());
+        return Ok(());
+    }
+    let write_error = |e| SqlPackageError::OutputWriteError {
+        path: dir.clone(),
+        source: e,
+    };
+    std::fs::create_dir_all(&dir).map_err(write_error)?;
+    clap_mangen::generate_to(command, &dir).map_err(write_error)?;
+    Ok(())
+}
+
+/// Known differences from `compare --ignore-file`, none without one
+fn load_known_differences(path: Option<PathBuf>) -> Result<KnownDifferences> {
+    path.map_or_else(
+        || Ok(KnownDifferences::default()),
+        |path| KnownDifferences::load(&path),
+    )
+}
+
+/// Note how many known differences `compare` left out
+fn report_known_differences(known: &KnownDifferences, left_out: usize, quiet: bool) {
+    if let (Some(path), false) = (&known.path, quiet) {
+        if left_out > 0 {
+            eprintln!(
+                "{} known difference(s) left out ({})",
+                left_out,
+                path.display()
+            );
+        }
+    }
+}
+
+#[cfg(feature = "tui")]
+fn browse_differences(
+    result: &CompareResult,
+    rust_dacpac: &std::path::Path,
+    dotnet_dacpac: &std::path::Path,
+    key_case: KeyCase,
+    known: KnownDifferences,
+) -> Result<()> {
+    rust_sqlpackage::compare::interactive::browse(
+        result,
+        rust_dacpac,
+        dotnet_dacpac,
+        key_case,
+        known,
+    )
+}
+
+#[cfg(not(feature = "tui"))]
+fn browse_differences(
+    _result: &CompareResult,
+    _rust_dacpac: &std::path::Path,
+    _dotnet_dacpac: &std::path::Path,
+    _key_case: KeyCase,
+    _known: KnownDifferences,
+) -> Result<()> {
+    Err(anyhow::anyhow!(
+        "compare --interactive needs rust-sqlpackage built with the tui feature"
+    ))
+}
+
+/// Exit with code 1 when `compare` found more than `max_differences` differences
+fn exit_on_differences(differences: usize, max_differences: usize, quiet: bool) {
+    if differences > max_differences {
+        process::exit(1);
+    }
+    if differences > 0 && !quiet {
+        eprintln!(
+            "{} difference(s), within --max-differences {}",
+            differences, max_differences
+        );
+    }
+}
@@ -0,0 +1,480 @@
+//! Merge several dacpacs into one deployable package
+//!
+//! The inputs' model elements are combined into a single model.xml. An element defined
+//! by more than one input (same type and name) must be identical in each, and is then
+//! written once; any that differ are reported as conflicts and nothing is written. So
+//! are inputs built for different platforms, collations or header settings.
+//!
+//! Inline constraint disambiguators are renumbered so they stay unique, references
+//! from one input to another become references within the merged model, and the
+//! inputs' pre- and post-deploy scripts are concatenated in input order.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use anyhow::Result;
+use regex::{Captures, Regex};
+use roxmltree::{Document, Node};
+
+use crate::compare::reader::DacpacContents;
+use crate::dacpac::{create_dacpac_from_parts, generate_metadata_xml, PackageParts};
+use crate::error::SqlPackageError;
+use crate::project::SqlProject;
+
+static DISAMBIGUATOR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"Disambiguator="(\d+)""#).unwrap());
+
+static EXTERNAL_SOURCE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#" ExternalSource="([^"]*)""#).unwrap());
+
+/// Root attributes every input must agree on
+const MODEL_ATTRIBUTES: &[&str] = &[
+    "SchemaVersion",
+    "DspName",
+    "CollationLcid",
+    "CollationCaseSensitive",
+];
+
+/// Options for merging dacpacs
+#[derive(Debug, Clone, Default)]
+pub struct MergeOptions {
+    /// Dacpacs to merge; the first one's header settings and version are kept
+    pub inputs: Vec<PathBuf>,
+    /// Path of the merged dacpac
+    pub output_path: PathBuf,
+    /// Name in DacMetadata.xml (defaults to the first input's)
+    pub name: Option<String>,
+    /// Check the inputs for conflicts but write nothing
+    pub dry_run: bool,
+}
+
+/// What a merge wrote
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MergeSummary {
+    /// Elements in the merged model
+    pub elements: usize,
+    /// Elements defined identically by more than one input, written once
+    pub shared: usize,
+}
+
+/// A dacpac being merged
+struct Input {
+    /// File name, as other dacpacs reference it
+    file_name: String,
+    contents: DacpacContents,
+    model_xml: String,
+}
+
+impl Input {
+    fn load(path: &Path) -> Result<Self> {
+        let contents = DacpacContents::from_path(path)?;
+        let model_xml =
+            contents
+                .get_string("model.xml")
+                .ok_or_else(|| SqlPackageError::InvalidDacpac {
+                    path: path.to_path_buf(),
+                    message: "no model.xml".to_string(),
+                })?;
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Ok(Self {
+            file_name,
+            contents,
+            model_xml,
+        })
+    }
+}
+
+/// A header CustomData element such as AnsiNulls
+struct HeaderSetting<'a> {
+    category: &'a str,
+    /// `(Name, Value)` of its Metadata children
+    values: Vec<(&'a str, &'a str)>,
+    text: &'a str,
+}
+
+/// An element of the merged model
+struct Element {
+    type_name: String,
+    name: String,
+    /// DefiningTable of an unnamed element such as an inline constraint
+    owner: String,
+    text: String,
+}
+
+/// Merge the input dacpacs into `output_path`
+///
+/// Fails with [`SqlPackageError::MergeConflict`] listing every conflict found.
+pub fn merge_dacpacs(options: &MergeOptions) -> Result<MergeSummary> {
+    let inputs = options
+        .inputs
+        .iter()
+        .map(|path| Input::load(path))
+        .collect::<Result<Vec<_>>>()?;
+    let documents = inputs
+        .iter()
+        .zip(&options.inputs)
+        .map(|(input, path)| {
+            Document::parse(&input.model_xml).map_err(|e| {
+                SqlPackageError::InvalidDacpac {
+                    path: path.clone(),
+                    message: format!("model.xml is not valid XML: {}", e),
+                }
+                .into()
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let Some(first) = documents.first() else {
+        anyhow::bail!("No dacpacs to merge");
+    };
+    let merged_names: Vec<&str> = inputs.iter().map(|i| i.file_name.as_str()).collect();
+    let mut conflicts = Vec::new();
+
+    // Platform and collation
+    let first_root = first.root_element();
+    for (input, document) in inputs.iter().zip(&documents).skip(1) {
+        let root = document.root_element();
+        for attribute in MODEL_ATTRIBUTES {
+            let (expected, actual) = (first_root.attribute(*attribute), root.attribute(*attribute));
+            if expected != actual {
+                conflicts.push(format!(
+                    "{} is {} in {} but {} in {}",
+                    attribute,
+                    expected.unwrap_or("unset"),
+                    inputs[0].file_name,
+                    actual.unwrap_or("unset"),
+                    input.file_name
+                ));
+            }
+        }
+    }
+
+    // Header: the first input's settings, every input's references and SQLCMD variables
+    let mut settings: Vec<HeaderSetting> = Vec::new();
+    let mut references: Vec<&str> = Vec::new();
+    let mut variables: Vec<&str> = Vec::new();
+    for (index, (input, document)) in inputs.iter().zip(&documents).enumerate() {
+        let Some(header) = child(document.root_element(), "Header") else {
+            continue;
+        };
+        for data in header.children().filter(|n| n.has_tag_name("CustomData")) {
+            let category = data.attribute("Category").unwrap_or_default();
+            let values = metadata_values(data);
+            match category {
+                "Reference" => {
+                    let file_name = values
+                        .iter()
+                        .find(|(name, _)| *name == "FileName")
+                        .map_or("", |(_, value)| value);
+                    let text = &input.model_xml[data.range()];
+                    if !is_merged(file_name, &merged_names) && !references.contains(&text) {
+                        references.push(text);
+                    }
+                }
+                "SqlCmdVariables" => {
+                    for (name, _) in values {
+                        if !variables.iter().any(|v| v.eq_ignore_ascii_case(name)) {
+                            variables.push(name);
+                        }
+                    }
+                }
+                _ if index == 0 => {
+                    settings.push(HeaderSetting {
+                        category,
+                        values,
+                        text: &input.model_xml[data.range()],
+                    });
+                }
+                _ => match settings.iter().find(|s| s.category == category) {
+                    Some(expected) if expected.values != values => {
+                        conflicts.push(format!(
+                            "{} is {} in {} but {} in {}",
+                            category,
+                            format_values(&expected.values),
+                            inputs[0].file_name,
+                            format_values(&values),
+                            input.file_name
+                        ));
+                    }
+                    Some(_) => {}
+                    None => conflicts.push(format!(
+                        "{} is set in {} but not in {}",
+                        category, input.file_name, inputs[0].file_name
+                    )),
+                },
+            }
+        }
+    }
+
+    // Elements, keyed by type and name, or by type and defining table when unnamed
+    let mut elements: Vec<Element> = Vec::new();
+    let mut named: HashMap<(String, String), (usize, String)> = HashMap::new();
+    let mut unnamed: HashMap<(String, String), Vec<(usize, String)>> = HashMap::new();
+    let mut shared = 0;
+    let mut offset = 0;
+    for (index, (input, document)) in inputs.iter().zip(&documents).enumerate() {
+        let Some(model) = child(document.root_element(), "Model") else {
+            continue;
+        };
+        let mut max_disambiguator = 0;
+        for node in model.children().filter(|n| n.has_tag_name("Element")) {
+            let type_name = node.attribute("Type").unwrap_or_default().to_string();
+            let name = node.attribute("Name").unwrap_or_default().to_string();
+            let text =
+                EXTERNAL_SOURCE_RE.replace_all(&input.model_xml[node.range()], |c: &Captures| {
+                    if is_merged(&c[1], &merged_names) {
+                        String::new()
+                    } else {
+                        c[0].to_string()
+                    }
+                });
+            for captures in DISAMBIGUATOR_RE.captures_iter(&text) {
+                max_disambiguator = max_disambiguator.max(captures[1].parse().unwrap_or(0));
+            }
+            let normalized = DISAMBIGUATOR_RE
+                .replace_all(&text, r#"Disambiguator="""#)
+                .into_owned();
+
+            let owner = if name.is_empty() {
+                defining_table(node).unwrap_or_default().to_string()
+            } else {
+                String::new()
+            };
+            if !name.is_empty() || type_name == "SqlDatabaseOptions" {
+                let key = (type_name.clone(), name.to_lowercase());
+                if let Some((other, existing)) = named.get(&key) {
+                    if *existing == normalized {
+                        shared += 1;
+                    } else {
+                        conflicts.push(format!(
+                            "{} {} differs between {} and {}",
+                            type_name, name, inputs[*other].file_name, input.file_name
+                        ));
+                    }
+                    continue;
+                }
+                named.insert(key, (index, normalized));
+            } else {
+                let group = unnamed
+                    .entry((type_name.clone(), owner.to_lowercase()))
+                    .or_default();
+                if group.iter().any(|(i, t)| *i != index && *t == normalized) {
+                    shared += 1;
+                    continue;
+                }
+                if let Some((other, _)) = group.iter().find(|(i, _)| *i != index) {
+                    conflicts.push(format!(
+                        "{} on {} differs between {} and {}",
+                        type_name, owner, inputs[*other].file_name, input.file_name
+                    ));
+                    continue;
+                }
+                group.push((index, normalized));
+            }
+
+            let text = if offset == 0 {
+                text.into_owned()
+            } else {
+                DISAMBIGUATOR_RE
+                    .replace_all(&text, |c: &Captures| {
+                        let value: usize = c[1].parse().unwrap_or(0);
+                        format!(r#"Disambiguator="{}""#, value + offset)
+                    })
+                    .into_owned()
+            };
+            elements.push(Element {
+                type_name,
+                name,
+                owner,
+                text,
+            });
+        }
+        offset += max_disambiguator;
+    }
+
+    if !conflicts.is_empty() {
+        return Err(SqlPackageError::MergeConflict { conflicts }.into());
+    }
+
+    // DacFx order: name, type, then defining table descending for unnamed elements
+    elements.sort_by_cached_key(|e| {
+        let name = e.name.to_lowercase();
+        let owner =
+            (name.is_empty() && !e.owner.is_empty()).then(|| Reverse(e.owner.to_lowercase()));
+        (name, e.type_name.to_lowercase(), owner)
+    });
+
+    let model_xml = write_model_xml(
+        first,
+        &inputs[0].model_xml,
+        &settings,
+        &references,
+        &variables,
+        &elements,
+    );
+
+    let metadata = first_metadata(&inputs[0]);
+    let project = SqlProject {
+        name: options.name.clone().unwrap_or(metadata.0),
+        ..Default::default()
+    };
+    let mut metadata_xml = Cursor::new(Vec::new());
+    generate_metadata_xml(&mut metadata_xml, &project, &metadata.1)?;
+
+    let predeploy = concat_scripts(&inputs, "predeploy.sql");
+    let postdeploy = concat_scripts(&inputs, "postdeploy.sql");
+    let parts = PackageParts {
+        model_xml: model_xml.as_bytes(),
+        metadata_xml: metadata_xml.get_ref(),
+        schema_version: first_root.attribute("SchemaVersion").unwrap_or("2.9"),
+        predeploy: predeploy.as_deref(),
+        postdeploy: postdeploy.as_deref(),
+        source_manifest: None,
+    };
+    if !options.dry_run {
+        create_dacpac_from_parts(&parts, &options.output_path)?;
+    }
+
+    Ok(MergeSummary {
+        elements: elements.len(),
+        shared,
+    })
+}
+
+/// Render the merged model.xml: the first input's declaration and root element, the
+/// merged header and the merged elements
+fn write_model_xml(
+    first: &Document,
+    first_xml: &str,
+    settings: &[HeaderSetting],
+    references: &[&str],
+    variables: &[&str],
+    elements: &[Element],
+) -> String {
+    let root = first.root_element();
+    let start = root
+        .first_element_child()
+        .map_or(first_xml.len(), |n| n.range().start);
+    let mut xml = first_xml[..start].trim_end().to_string();
+
+    xml.push_str("\n  <Header>");
+    for setting in settings {
+        xml.push_str("\n    ");
+        xml.push_str(setting.text);
+    }
+    for text in references {
+        xml.push_str("\n    ");
+        xml.push_str(text);
+    }
+    xml.push_str("\n    <CustomData Category=\"SqlCmdVariables\" Type=\"SqlCmdVariable\"");
+    if variables.is_empty() {
+        xml.push_str(" />");
+    } else {
+        xml.push('>');
+        for name in variables {
+            xml.push_str(&format!(
+                "\n      <Metadata Name=\"{}\" Value=\"\" />",
+                quick_xml::escape::escape(*name)
+            ));
+        }
+        xml.push_str("\n    </CustomData>");
+    }
+    xml.push_str("\n  </Header>\n  <Model>");
+    for element in elements {
+        xml.push_str("\n    ");
+        xml.push_str(&element.text);
+    }
+    xml.push_str("\n  </Model>\n</DataSchemaModel>");
+    xml
+}
+
+/// `(name, version)` from the first input's DacMetadata.xml
+fn first_metadata(input: &Input) -> (String, String) {
+    let metadata = input
+        .contents
+        .get_string("DacMetadata.xml")
+        .unwrap_or_default();
+    let document = Document::parse(&metadata).ok();
+    let field = |tag: &str| {
+        document
+            .as_ref()
+            .and_then(|d| child(d.root_element(), tag))
+            .and_then(|n| n.text())
+            .map(str::to_string)
+    };
+    let name = field("Name").unwrap_or_else(|| {
+        Path::new(&input.file_name)
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+    let version = field("Version").unwrap_or_else(|| "1.0.0.0".to_string());
+    (name, version)
+}
+
+/// The inputs' copies of a deploy script, each headed by the dacpac it came from
+fn concat_scripts(inputs: &[Input], part: &str) -> Option<String> {
+    let scripts: Vec<String> = inputs
+        .iter()
+        .filter_map(|input| {
+            let script = input.contents.get_string(part)?;
+            Some(format!(
+                "-- From {}\n{}",
+                input.file_name,
+                script.trim_end()
+            ))
+        })
+        .collect();
+    (!scripts.is_empty()).then(|| scripts.join("\n\n") + "\n")
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, tag: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|n| n.has_tag_name(tag))
+}
+
+/// `(Name, Value)` of a CustomData element's Metadata children
+fn metadata_values<'a>(data: Node<'a, '_>) -> Vec<(&'a str, &'a str)> {
+    data.children()
+        .filter(|n| n.has_tag_name("Metadata"))
+        .map(|n| {
+            (
+                n.attribute("Name").unwrap_or_default(),
+                n.attribute("Value").unwrap_or_default(),
+            )
+        })
+        .collect()
+}
+
+fn format_values(values: &[(&str, &str)]) -> String {
+    values
+        .iter()
+        .map(|(_, value)| *value)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The table an unnamed element such as an inline constraint belongs to
+fn defining_table<'a>(element: Node<'a, '_>) -> Option<&'a str> {
+    element
+        .children()
+        .find(|n| n.has_tag_name("Relationship") && n.attribute("Name") == Some("DefiningTable"))?
+        .descendants()
+        .find(|n| n.has_tag_name("References"))?
+        .attribute("Name")
+}
+
+/// Whether a referenced dacpac is one of the inputs being merged
+fn is_merged(file_name: &str, merged_names: &[&str]) -> bool {
+    let file_name = Path::new(file_name)
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    merged_names
+        .iter()
+        .any(|name| name.eq_ignore_ascii_case(&file_name))
+}
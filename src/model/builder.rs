@@ -5,36 +5,39 @@
 //! converted to owned Strings at the end when creating SchemaElements.
 
 use std::borrow::Cow;
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::sync::LazyLock;
 
 use anyhow::Result;
 use regex::Regex;
 use sqlparser::ast::{
-    Action, AlterRoleOperation, BinaryOperator, ColumnDef, ColumnOption, DataType, Expr,
-    GrantObjects, ObjectName, Privileges, SchemaName, Statement, TableConstraint,
+    Action, AlterRoleOperation, AlterTableOperation, BinaryOperator, ColumnDef, ColumnOption,
+    DataType, Expr, GrantObjects, ObjectName, Privileges, SchemaName, Statement, TableConstraint,
 };
 
+use crate::error::SqlPackageError;
 use crate::parser::{
-    ident_extract,
-    identifier_utils::normalize_identifier,
+    extract_column_descriptions, extract_header_comment, extract_module_options,
+    extract_table_scripts, header_description, ident_extract,
+    identifier_utils::{escape_identifier, normalize_identifier},
     index_parser::{extract_index_filter_predicate_tokenized, extract_index_is_padded},
-    ExtractedExtendedProperty, ExtractedFullTextColumn, ExtractedFunctionParameter,
-    ExtractedTableColumn, ExtractedTableConstraint, ExtractedTableTypeColumn,
-    ExtractedTableTypeConstraint, FallbackFunctionType, FallbackStatementType, ParsedStatement,
+    parse_table_compression_tokens, ConstraintIndexOptions, ExtractedExtendedProperty,
+    ExtractedFullTextColumn, ExtractedFunctionParameter, ExtractedTableColumn,
+    ExtractedTableConstraint, ExtractedTableTypeColumn, ExtractedTableTypeConstraint,
+    FallbackFunctionType, FallbackStatementType, ParsedStatement, TableScripts,
     BINARY_MAX_SENTINEL,
 };
 use crate::project::SqlProject;
 
 use super::{
     ColumnElement, ColumnstoreIndexElement, ConstraintColumn, ConstraintElement, ConstraintType,
-    DataCompressionType, DatabaseModel, ExtendedPropertyElement, FilegroupElement,
+    DataCompressionType, DatabaseModel, ElementOrder, ExtendedPropertyElement, FilegroupElement,
     FullTextCatalogElement, FullTextColumnElement, FullTextIndexElement, FunctionElement,
     FunctionType, IndexColumn, IndexElement, ModelElement, ParameterElement,
     PartitionFunctionElement, PartitionSchemeElement, PermissionElement, ProcedureElement,
     RawElement, RoleElement, RoleMembershipElement, ScalarTypeElement, SchemaElement,
-    SequenceElement, SynonymElement, TableElement, TableTypeColumnElement, TableTypeConstraint,
-    TriggerElement, UserDefinedTypeElement, UserElement, ViewElement,
+    SequenceElement, SourceLocation, SynonymElement, TableElement, TableTypeColumnElement,
+    TableTypeConstraint, TriggerElement, UserDefinedTypeElement, UserElement, ViewElement,
 };
 
 use crate::util::{contains_ci, find_ci, starts_with_ci};
@@ -59,6 +62,14 @@ fn parse_data_compression(s: &str) -> Option<DataCompressionType> {
     }
 }
 
+/// Parse per-partition `(partition number, compression)` pairs, dropping unknown compression types.
+fn parse_partition_compression(partitions: &[(u32, String)]) -> Vec<(u32, DataCompressionType)> {
+    partitions
+        .iter()
+        .filter_map(|(partition, s)| Some((*partition, parse_data_compression(s)?)))
+        .collect()
+}
+
 // Cached regex patterns (Phase 63) — compiled once, reused on every call
 static TYPE_PARAMS_RE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"\((\d+)(?:\s*,\s*(\d+))?\)").unwrap());
@@ -99,6 +110,11 @@ struct ConstraintBuilder {
     referenced_table: Option<String>,
     referenced_columns: Option<Vec<String>>,
     is_clustered: Option<bool>,
+    fill_factor: Option<u8>,
+    is_padded: bool,
+    data_compression: Option<DataCompressionType>,
+    partition_compression: Vec<(u32, DataCompressionType)>,
+    filegroup: Option<String>,
     is_inline: bool,
     emit_name: bool,
     source_order: u32,
@@ -123,6 +139,11 @@ impl ConstraintBuilder {
             referenced_table: None,
             referenced_columns: None,
             is_clustered: None,
+            fill_factor: None,
+            is_padded: false,
+            data_compression: None,
+            partition_compression: Vec::new(),
+            filegroup: None,
             is_inline: false,
             emit_name: true, // Default for table-level constraints
             source_order: 0, // Set by caller
@@ -167,6 +188,19 @@ impl ConstraintBuilder {
         self
     }
 
+    /// Set index options from WITH and ON clauses (for PRIMARY KEY and UNIQUE).
+    fn index_options(mut self, options: &ConstraintIndexOptions) -> Self {
+        self.fill_factor = options.fill_factor;
+        self.is_padded = options.is_padded;
+        self.data_compression = options
+            .data_compression
+            .as_deref()
+            .and_then(parse_data_compression);
+        self.partition_compression = parse_partition_compression(&options.partition_compression);
+        self.filegroup = options.filegroup.clone();
+        self
+    }
+
     /// Build the final `ConstraintElement`.
     fn build(self) -> ConstraintElement {
         ConstraintElement {
@@ -179,6 +213,11 @@ impl ConstraintBuilder {
             referenced_table: self.referenced_table,
             referenced_columns: self.referenced_columns,
             is_clustered: self.is_clustered,
+            fill_factor: self.fill_factor,
+            is_padded: self.is_padded,
+            data_compression: self.data_compression,
+            partition_compression: self.partition_compression,
+            filegroup: self.filegroup,
             is_inline: self.is_inline,
             inline_constraint_disambiguator: None, // Set by assign_inline_constraint_disambiguators
             uses_annotation: false,                // Set by assign_inline_constraint_disambiguators
@@ -212,25 +251,79 @@ fn track_schema(schemas: &mut BTreeSet<Cow<'static, str>>, schema: &str) -> Stri
 /// Build a database model from parsed statements
 pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Result<DatabaseModel> {
     let mut model = DatabaseModel::new();
+    model.ansi_nulls = project.ansi_nulls;
+    model.quoted_identifier = project.quoted_identifier;
     // Use Cow<str> to avoid cloning for common schema patterns
     let mut schemas: BTreeSet<Cow<'static, str>> = BTreeSet::new();
 
     // Always include dbo schema - use borrowed static string
     schemas.insert(Cow::Borrowed(DBO_SCHEMA));
 
+    // ENABLE/DISABLE TRIGGER statements, applied once all triggers have been added
+    let mut trigger_states: Vec<TriggerStateChange> = Vec::new();
+
+    // Source of the statement being processed, recorded for each element it adds
+    let mut statement_source: Option<SourceLocation> = None;
+    // Header comment of the statement being processed, recorded for the first element
+    let mut statement_header: Option<String> = None;
+    // `-- description:` column comments, with the index of their table
+    let mut column_comments: Vec<(usize, Vec<(String, String)>)> = Vec::new();
+
     for parsed in statements {
+        if let Some(warning) = &parsed.warning {
+            eprintln!("Warning: {}", warning);
+            model.warnings.push(warning.clone());
+        }
+        model
+            .sources
+            .resize(model.elements.len(), statement_source.take());
+        record_header(&mut model, statement_header.take());
+        statement_header = extract_header_comment(&parsed.sql_text);
+        let (start_line, end_line) = parsed.line_range();
+        statement_source = Some(SourceLocation {
+            file: parsed.source_file.clone(),
+            start_line,
+            end_line,
+            leading_whitespace: parsed.leading_whitespace.clone(),
+        });
+
         // Handle fallback-parsed statements (procedures and functions with T-SQL syntax)
         if let Some(fallback) = &parsed.fallback_type {
             match fallback {
-                FallbackStatementType::Procedure { schema, name } => {
+                FallbackStatementType::Procedure {
+                    schema,
+                    name,
+                    number,
+                } => {
+                    // Numbered procedures beyond the first share the group's name, which
+                    // the model cannot represent; only `;1` (or no number) is kept.
+                    if let Some(number) = number.filter(|n| *n > 1) {
+                        let warning = format!(
+                            "Numbered procedure [{}].[{}];{} is not supported and was skipped ({}:{})",
+                            escape_identifier(schema),
+                            escape_identifier(name),
+                            number,
+                            parsed.source_file.display(),
+                            start_line
+                        );
+                        eprintln!("Warning: {}", warning);
+                        model.warnings.push(warning);
+                        continue;
+                    }
                     let schema_owned = track_schema(&mut schemas, schema);
                     let is_natively_compiled = is_natively_compiled(&parsed.sql_text);
+                    let options = extract_module_options(&parsed.sql_text);
                     model.add_element(ModelElement::Procedure(ProcedureElement {
                         schema: schema_owned,
                         name: name.clone(),
                         definition: parsed.sql_text.clone(),
                         parameters: vec![], // T-SQL params not extracted - stored in definition
                         is_natively_compiled,
+                        is_schema_bound: options.has_option("SCHEMABINDING"),
+                        atomic_isolation_level: options
+                            .atomic_option("TRANSACTION ISOLATION LEVEL")
+                            .map(str::to_string),
+                        atomic_language: options.atomic_option("LANGUAGE").map(str::to_string),
                         dynamic_sources: Vec::new(),
                     }));
                 }
@@ -248,6 +341,7 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
                         FallbackFunctionType::InlineTableValued => FunctionType::InlineTableValued,
                     };
                     let is_natively_compiled = is_natively_compiled(&parsed.sql_text);
+                    let options = extract_module_options(&parsed.sql_text);
                     let param_elements = parameters.iter().map(param_from_extracted).collect();
                     model.add_element(ModelElement::Function(FunctionElement {
                         schema: schema_owned,
@@ -257,6 +351,10 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
                         parameters: param_elements,
                         return_type: return_type.clone(),
                         is_natively_compiled,
+                        is_schema_bound: options.has_option("SCHEMABINDING"),
+                        returns_null_on_null_input: options
+                            .has_option("RETURNS NULL ON NULL INPUT"),
+                        execute_as: options.execute_as().map(str::to_string),
                         dynamic_sources: Vec::new(),
                     }));
                 }
@@ -271,6 +369,7 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
                     fill_factor,
                     filter_predicate,
                     data_compression,
+                    partition_compression,
                     is_padded,
                 } => {
                     // Convert string data_compression to DataCompressionType
@@ -293,6 +392,7 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
                         fill_factor: *fill_factor,
                         filter_predicate: filter_predicate.clone(),
                         data_compression: compression_type,
+                        partition_compression: parse_partition_compression(partition_compression),
                         is_padded: *is_padded,
                     }));
                 }
@@ -303,6 +403,7 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
                     is_clustered,
                     columns,
                     data_compression,
+                    partition_compression,
                     filter_predicate,
                 } => {
                     let compression_type = data_compression
@@ -315,6 +416,7 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
                         is_clustered: *is_clustered,
                         columns: columns.clone(),
                         data_compression: compression_type,
+                        partition_compression: parse_partition_compression(partition_compression),
                         filter_predicate: filter_predicate.clone(),
                     }));
                 }
@@ -325,6 +427,7 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
                     key_index,
                     catalog,
                     change_tracking,
+                    stoplist,
                 } => {
                     let column_elements: Vec<FullTextColumnElement> =
                         columns.iter().map(fulltext_column_from_extracted).collect();
@@ -335,6 +438,7 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
                         key_index: key_index.clone(),
                         catalog: catalog.clone(),
                         change_tracking: change_tracking.clone(),
+                        stoplist: stoplist.clone(),
                         disambiguator: None, // Set during post-processing
                     }));
                 }
@@ -428,6 +532,9 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
                     is_system_versioned,
                     history_table_schema,
                     history_table_name,
+                    indexes,
+                    data_compression,
+                    partition_compression,
                 } => {
                     let schema_owned = track_schema(&mut schemas, schema);
 
@@ -452,7 +559,17 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
                         is_system_versioned: *is_system_versioned,
                         history_table_schema: history_table_schema.clone(),
                         history_table_name: history_table_name.clone(),
+                        data_compression: data_compression
+                            .as_deref()
+                            .and_then(parse_data_compression),
+                        partition_compression: parse_partition_compression(partition_compression),
                     }));
+                    if project.column_descriptions {
+                        column_comments.push((
+                            model.elements.len() - 1,
+                            extract_column_descriptions(&parsed.sql_text),
+                        ));
+                    }
 
                     // Add constraints as separate elements, tracking source order
                     let mut constraint_order: u32 = 0;
@@ -516,6 +633,35 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
                             constraint_order += 1;
                         }
                     }
+
+                    // Add indexes declared inline in the table body
+                    for index in indexes {
+                        model.add_element(ModelElement::Index(IndexElement {
+                            name: index.name.clone(),
+                            table_schema: schema_owned.clone(),
+                            table_name: name.clone(),
+                            columns: index
+                                .columns
+                                .iter()
+                                .map(|c| {
+                                    IndexColumn::with_direction(c.name.clone(), c.is_descending)
+                                })
+                                .collect(),
+                            include_columns: index.include_columns.clone(),
+                            is_unique: index.is_unique,
+                            is_clustered: index.is_clustered,
+                            fill_factor: index.fill_factor,
+                            filter_predicate: index.filter_predicate.clone(),
+                            data_compression: index
+                                .data_compression
+                                .as_ref()
+                                .and_then(|s| parse_data_compression(s)),
+                            partition_compression: parse_partition_compression(
+                                &index.partition_compression,
+                            ),
+                            is_padded: index.is_padded,
+                        }));
+                    }
                 }
                 FallbackStatementType::AlterTableAddConstraint {
                     table_schema,
@@ -583,8 +729,24 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
                         is_update_trigger: *is_update,
                         is_delete_trigger: *is_delete,
                         trigger_type: *trigger_type,
+                        is_schema_bound: extract_module_options(&parsed.sql_text)
+                            .has_option("SCHEMABINDING"),
+                        is_enabled: true,
                     }));
                 }
+                FallbackStatementType::TriggerState {
+                    trigger_names,
+                    parent_schema,
+                    parent_name,
+                    is_enabled,
+                } => {
+                    trigger_states.push(TriggerStateChange {
+                        trigger_names: trigger_names.clone(),
+                        parent_schema: parent_schema.clone(),
+                        parent_name: parent_name.clone(),
+                        is_enabled: *is_enabled,
+                    });
+                }
                 FallbackStatementType::Filegroup {
                     name,
                     contains_memory_optimized_data,
@@ -718,11 +880,15 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
                 // PERIOD FOR SYSTEM_TIME or SYSTEM_VERSIONING in its AST, so we
                 // extract these from the original SQL text.
                 let temporal = extract_temporal_metadata_from_sql(&parsed.sql_text);
+                let compression = parse_table_compression_tokens(&parsed.sql_text);
+                // Expression scripts come from the source text rather than the AST, which
+                // re-renders them
+                let scripts = extract_table_scripts(&parsed.sql_text);
 
                 let mut columns: Vec<ColumnElement> = create_table
                     .columns
                     .iter()
-                    .map(|c| column_from_def(c, &schema, &name))
+                    .map(|c| column_from_def(c, &scripts))
                     .collect();
 
                 // Apply temporal column attributes from raw SQL extraction
@@ -764,7 +930,20 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
                     is_system_versioned: temporal.is_system_versioned,
                     history_table_schema: temporal.history_table_schema,
                     history_table_name: temporal.history_table_name,
+                    data_compression: compression
+                        .data_compression
+                        .as_deref()
+                        .and_then(parse_data_compression),
+                    partition_compression: parse_partition_compression(
+                        &compression.partition_compression,
+                    ),
                 }));
+                if project.column_descriptions {
+                    column_comments.push((
+                        model.elements.len() - 1,
+                        extract_column_descriptions(&parsed.sql_text),
+                    ));
+                }
 
                 // Extract constraints from table definition (table-level constraints)
                 // Track source order for disambiguator assignment
@@ -776,6 +955,13 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
                         &project.default_schema,
                         &parsed.sql_text,
                     ) {
+                        if let TableConstraint::Check { name, expr } = constraint {
+                            let name = name.as_ref().map(|n| n.value.as_str());
+                            if let Some(script) = scripts.check_expression(name, &expr.to_string())
+                            {
+                                constraint_element.definition = Some(script.to_string());
+                            }
+                        }
                         constraint_element.source_order = constraint_order;
                         constraint_order += 1;
                         model.add_element(ModelElement::Constraint(constraint_element));
@@ -886,7 +1072,11 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
                                     vec![ConstraintColumn::new(col.name.value.clone())],
                                 )
                                 .inline(has_explicit_name)
-                                .definition(expr.to_string())
+                                .definition(
+                                    scripts
+                                        .default_value(&col.name.value)
+                                        .map_or_else(|| expr.to_string(), str::to_string),
+                                )
                                 .source_order(constraint_order)
                                 .build(),
                             ));
@@ -913,7 +1103,15 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
                                     vec![ConstraintColumn::new(col.name.value.clone())],
                                 )
                                 .inline(has_explicit_name)
-                                .definition(expr.to_string())
+                                .definition({
+                                    let rendered = expr.to_string();
+                                    scripts
+                                        .check_expression(
+                                            option.name.as_ref().map(|n| n.value.as_str()),
+                                            &rendered,
+                                        )
+                                        .map_or(rendered, str::to_string)
+                                })
                                 .source_order(constraint_order)
                                 .build(),
                             ));
@@ -1013,6 +1211,7 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
                     fill_factor,
                     filter_predicate,
                     data_compression,
+                    partition_compression: Vec::new(),
                     is_padded,
                 }));
             }
@@ -1022,6 +1221,7 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
                 let (schema, proc_name) = extract_schema_and_name(name, &project.default_schema);
                 let schema = track_schema(&mut schemas, &schema);
                 let is_native = is_natively_compiled(&parsed.sql_text);
+                let options = extract_module_options(&parsed.sql_text);
 
                 model.add_element(ModelElement::Procedure(ProcedureElement {
                     schema,
@@ -1029,6 +1229,11 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
                     definition: parsed.sql_text.clone(),
                     parameters: vec![], // Parameters stored in definition
                     is_natively_compiled: is_native,
+                    is_schema_bound: options.has_option("SCHEMABINDING"),
+                    atomic_isolation_level: options
+                        .atomic_option("TRANSACTION ISOLATION LEVEL")
+                        .map(str::to_string),
+                    atomic_language: options.atomic_option("LANGUAGE").map(str::to_string),
                     dynamic_sources: Vec::new(),
                 }));
             }
@@ -1059,6 +1264,7 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
                 };
 
                 let is_native = is_natively_compiled(&parsed.sql_text);
+                let options = extract_module_options(&parsed.sql_text);
 
                 model.add_element(ModelElement::Function(FunctionElement {
                     schema,
@@ -1068,6 +1274,9 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
                     parameters: vec![], // Parameters stored in definition
                     return_type: create_func.return_type.as_ref().map(|t| t.to_string()),
                     is_natively_compiled: is_native,
+                    is_schema_bound: options.has_option("SCHEMABINDING"),
+                    returns_null_on_null_input: options.has_option("RETURNS NULL ON NULL INPUT"),
+                    execute_as: options.execute_as().map(str::to_string),
                     dynamic_sources: Vec::new(),
                 }));
             }
@@ -1218,11 +1427,49 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
                 }
             }
 
+            // ALTER TABLE ... ENABLE/DISABLE TRIGGER
+            Statement::AlterTable {
+                name, operations, ..
+            } => {
+                let (parent_schema, parent_name) =
+                    extract_schema_and_name(name, &project.default_schema);
+                for operation in operations {
+                    let (trigger, is_enabled) = match operation {
+                        AlterTableOperation::EnableTrigger { name } => (name, true),
+                        AlterTableOperation::DisableTrigger { name } => (name, false),
+                        _ => continue,
+                    };
+                    // `ALL` keeps its text when parsed as a trigger name
+                    let trigger_names = if trigger.quote_style.is_none()
+                        && trigger.value.eq_ignore_ascii_case("ALL")
+                    {
+                        Vec::new()
+                    } else {
+                        vec![trigger.value.clone()]
+                    };
+                    trigger_states.push(TriggerStateChange {
+                        trigger_names,
+                        parent_schema: parent_schema.clone(),
+                        parent_name: parent_name.clone(),
+                        is_enabled,
+                    });
+                }
+            }
+
             // Ignore other statements (DML, etc.)
             _ => {}
         }
     }
 
+    model
+        .sources
+        .resize(model.elements.len(), statement_source.take());
+    record_header(&mut model, statement_header.take());
+
+    add_comment_descriptions(&mut model, project.header_descriptions, &column_comments);
+
+    apply_trigger_states(&mut model.elements, &trigger_states);
+
     // Add schema elements for any schemas we discovered
     // (Built-in schemas like dbo are included in the model but will be filtered
     // during XML generation - they're written as ExternalSource="BuiltIns" references)
@@ -1240,18 +1487,25 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
         }
     }
 
+    // Schemas only referenced by other objects have no source of their own
+    model.sources.resize(model.elements.len(), None);
+    model.headers.resize(model.elements.len(), None);
+
     // Resolve UDT nullability for columns
     // Columns that use a user-defined scalar type and don't have explicit NULL/NOT NULL
     // inherit the nullability from the UDT definition (matching DotNet behavior)
     resolve_udt_nullability(&mut model.elements);
 
+    // Reference trigger parents by their declared names, as DotNet does
+    resolve_trigger_parents(&mut model.elements);
+
     // Pre-compute and cache element names before sorting.
     // This avoids repeated format!() allocations in full_name()/xml_name_attr()
     // during sorting and later during XML generation.
     model.cache_element_names();
 
-    // Sort elements by type (following DotNet order) then by name for deterministic output
-    sort_model(&mut model);
+    // Sort elements by name then type (following DotNet order) for deterministic output
+    model.sort_elements(ElementOrder::DotNet);
 
     // Assign disambiguators to inline constraints and link to columns/tables
     // This must happen after sorting because DotNet assigns disambiguators in sorted order.
@@ -1262,65 +1516,96 @@ pub fn build_model(statements: &[ParsedStatement], project: &SqlProject) -> Resu
     // (disambiguation doesn't change names, but re-caching is cheap insurance)
     model.cache_element_names();
 
+    // Report definitions deployment would reject, such as mismatched foreign keys, and
+    // fail on references to columns that don't exist
+    let report = super::validation::validate_model(&model);
+    if !report.errors.is_empty() {
+        return Err(SqlPackageError::ModelValidationError {
+            errors: report.errors,
+        }
+        .into());
+    }
+    for warning in report.warnings {
+        eprintln!("Warning: {}", warning);
+        model.warnings.push(warning);
+    }
+
     Ok(model)
 }
 
-/// Sort the model's elements and their cached names together by (Name, Type, SecondaryKey)
-/// to match DotNet DacFx ordering.
-///
-/// DotNet sorts elements alphabetically (case-insensitive) by:
-/// 1. Name attribute value (empty string for elements without Name attribute)
-/// 2. Type attribute value (e.g., "SqlCheckConstraint", "SqlTable")
-/// 3. Secondary key for disambiguation (DefiningTable reference for inline constraints)
-///
-/// Uses pre-computed cached_xml_names from `model.cache_element_names()` to avoid
-/// allocating new Strings during sort key computation. Sort keys reference the cached
-/// names via index, so no per-element allocation occurs during sorting.
-fn sort_model(model: &mut DatabaseModel) {
-    use std::cmp::Reverse;
-
-    let n = model.elements.len();
-
-    // Build sort keys from cached names (no new allocations — just lowercase + references)
-    let sort_keys: Vec<_> = (0..n)
-        .map(|i| {
-            let name = model.cached_xml_names[i].to_lowercase();
-            let type_name = model.elements[i].type_name().to_lowercase();
-            let secondary = model.elements[i].secondary_sort_key().to_lowercase();
-
-            let secondary_desc = if name.is_empty() && !secondary.is_empty() {
-                Some(Reverse(secondary))
-            } else {
-                None
+/// An ENABLE/DISABLE TRIGGER statement. Applied after all statements are processed because
+/// it may appear in a different file than the trigger it targets.
+struct TriggerStateChange {
+    /// Affected trigger names; empty for `ALL`
+    trigger_names: Vec<String>,
+    parent_schema: String,
+    parent_name: String,
+    is_enabled: bool,
+}
+
+/// Set `is_enabled` on the triggers targeted by ENABLE/DISABLE TRIGGER statements,
+/// in statement order so the last statement for a trigger wins.
+fn apply_trigger_states(elements: &mut [ModelElement], changes: &[TriggerStateChange]) {
+    for change in changes {
+        for element in elements.iter_mut() {
+            let ModelElement::Trigger(trigger) = element else {
+                continue;
             };
+            let on_parent = trigger
+                .parent_schema
+                .eq_ignore_ascii_case(&change.parent_schema)
+                && trigger
+                    .parent_name
+                    .eq_ignore_ascii_case(&change.parent_name);
+            let named = change.trigger_names.is_empty()
+                || change
+                    .trigger_names
+                    .iter()
+                    .any(|n| n.eq_ignore_ascii_case(&trigger.name));
+            if on_parent && named {
+                trigger.is_enabled = change.is_enabled;
+            }
+        }
+    }
+}
 
-            (name, type_name, secondary_desc)
+/// Point each trigger's parent at the table or view as declared, so `ON dbo.orders`
+/// references `[dbo].[Orders]`. A trigger named without a schema took its schema from the
+/// `ON` clause and follows the declared casing too.
+fn resolve_trigger_parents(elements: &mut [ModelElement]) {
+    let declared: HashMap<(String, String), (String, String)> = elements
+        .iter()
+        .filter_map(|element| match element {
+            ModelElement::Table(t) => Some((&t.schema, &t.name)),
+            ModelElement::View(v) => Some((&v.schema, &v.name)),
+            ModelElement::Raw(r) if r.sql_type == "SqlTable" || r.sql_type == "SqlView" => {
+                Some((&r.schema, &r.name))
+            }
+            _ => None,
+        })
+        .map(|(schema, name)| {
+            (
+                (schema.to_lowercase(), name.to_lowercase()),
+                (schema.clone(), name.clone()),
+            )
         })
         .collect();
 
-    // Sort indices by the pre-computed keys
-    let mut indices: Vec<usize> = (0..n).collect();
-    indices.sort_by(|&a, &b| sort_keys[a].cmp(&sort_keys[b]));
-
-    // Apply permutation by rebuilding vecs in sorted order.
-    // This is O(n) extra memory but avoids the complexity of in-place cycle permutation.
-    apply_permutation(&mut model.elements, &indices);
-    apply_permutation(&mut model.cached_full_names, &indices);
-    apply_permutation(&mut model.cached_xml_names, &indices);
-}
-
-/// Apply a permutation to a vec by rebuilding it in the new order.
-/// After this call, `vec[i] = original_vec[indices[i]]` for all i.
-fn apply_permutation<T>(vec: &mut Vec<T>, indices: &[usize]) {
-    let n = vec.len();
-    debug_assert_eq!(n, indices.len());
-
-    // Move elements out of vec into Options for safe arbitrary-order access
-    let mut opts: Vec<Option<T>> = vec.drain(..).map(Some).collect();
-
-    // Rebuild vec in sorted order using the index permutation
-    for &idx in indices {
-        vec.push(opts[idx].take().expect("index used twice in permutation"));
+    for element in elements.iter_mut() {
+        let ModelElement::Trigger(trigger) = element else {
+            continue;
+        };
+        let key = (
+            trigger.parent_schema.to_lowercase(),
+            trigger.parent_name.to_lowercase(),
+        );
+        if let Some((schema, name)) = declared.get(&key) {
+            if trigger.schema.eq_ignore_ascii_case(schema) {
+                trigger.schema = schema.clone();
+            }
+            trigger.parent_schema = schema.clone();
+            trigger.parent_name = name.clone();
+        }
     }
 }
 
@@ -1353,13 +1638,26 @@ fn resolve_udt_nullability(elements: &mut [ModelElement]) {
 
             // Store all possible reference formats
             // Format: [schema].[name]
-            udt_nullability.insert(format!("[{}].[{}]", schema, name), is_nullable);
+            udt_nullability.insert(
+                format!(
+                    "[{}].[{}]",
+                    escape_identifier(schema),
+                    escape_identifier(name)
+                ),
+                is_nullable,
+            );
             // Format: schema.name
             udt_nullability.insert(format!("{}.{}", schema, name), is_nullable);
             // Format: [schema].name
-            udt_nullability.insert(format!("[{}].{}", schema, name), is_nullable);
+            udt_nullability.insert(
+                format!("[{}].{}", escape_identifier(schema), name),
+                is_nullable,
+            );
             // Format: schema.[name]
-            udt_nullability.insert(format!("{}.[{}]", schema, name), is_nullable);
+            udt_nullability.insert(
+                format!("{}.[{}]", schema, escape_identifier(name)),
+                is_nullable,
+            );
         }
     }
 
@@ -1839,7 +2137,7 @@ fn extract_schema_and_name(name: &ObjectName, default_schema: &str) -> (String,
     }
 }
 
-fn column_from_def(col: &ColumnDef, _schema: &str, _table_name: &str) -> ColumnElement {
+fn column_from_def(col: &ColumnDef, scripts: &TableScripts) -> ColumnElement {
     // Track explicit nullability: None = not specified, Some(true) = explicit NULL, Some(false) = explicit NOT NULL
     let mut nullability: Option<bool> = None;
     let mut is_identity = false;
@@ -1852,7 +2150,11 @@ fn column_from_def(col: &ColumnDef, _schema: &str, _table_name: &str) -> ColumnE
             ColumnOption::NotNull => nullability = Some(false),
             ColumnOption::Null => nullability = Some(true),
             ColumnOption::Default(expr) => {
-                default_value = Some(expr.to_string());
+                default_value = Some(
+                    scripts
+                        .default_value(&col.name.value)
+                        .map_or_else(|| expr.to_string(), str::to_string),
+                );
             }
             ColumnOption::Identity(_) => is_identity = true,
             ColumnOption::Generated {
@@ -1862,7 +2164,11 @@ fn column_from_def(col: &ColumnDef, _schema: &str, _table_name: &str) -> ColumnE
             } => {
                 // This is a computed column
                 if let Some(expr) = generation_expr {
-                    computed_expression = Some(format!("({})", expr));
+                    computed_expression = Some(
+                        scripts
+                            .computed_expression(&col.name.value)
+                            .map_or_else(|| format!("({})", expr), str::to_string),
+                    );
                 }
                 // Check if PERSISTED (STORED in ANSI SQL)
                 if let Some(mode) = generation_expr_mode {
@@ -1982,6 +2288,7 @@ fn fulltext_column_from_extracted(col: &ExtractedFullTextColumn) -> FullTextColu
     FullTextColumnElement {
         name: col.name.clone(),
         language_id: col.language_id,
+        type_column: col.type_column.clone(),
     }
 }
 
@@ -2107,6 +2414,7 @@ fn constraint_from_extracted(
             name,
             columns,
             is_clustered,
+            options,
         } => Some(
             ConstraintBuilder::new(
                 name.clone(),
@@ -2119,6 +2427,7 @@ fn constraint_from_extracted(
                     .collect(),
             )
             .clustered(*is_clustered)
+            .index_options(options)
             .build(),
         ),
         ExtractedTableConstraint::ForeignKey {
@@ -2144,6 +2453,7 @@ fn constraint_from_extracted(
             name,
             columns,
             is_clustered,
+            options,
         } => Some(
             ConstraintBuilder::new(
                 name.clone(),
@@ -2156,6 +2466,7 @@ fn constraint_from_extracted(
                     .collect(),
             )
             .clustered(*is_clustered)
+            .index_options(options)
             .build(),
         ),
         ExtractedTableConstraint::Check { name, expression } => Some(
@@ -2188,7 +2499,7 @@ fn extract_constraint_clustering(
 ) -> Option<bool> {
     // Try to find the constraint definition in the SQL
     // First, try to find a named constraint
-    let constraint_pattern = format!("CONSTRAINT [{}]", constraint_name);
+    let constraint_pattern = format!("CONSTRAINT [{}]", escape_identifier(constraint_name));
     let constraint_pattern_bare = format!("CONSTRAINT {}", constraint_name);
 
     // Find the position of the constraint in the SQL (case-insensitive)
@@ -2294,7 +2605,11 @@ fn constraint_from_table_constraint(
             // Format the foreign table reference with brackets: [schema].[table]
             let (foreign_schema, foreign_table_name) =
                 extract_schema_and_name(foreign_table, default_schema);
-            let formatted_foreign_table = format!("[{}].[{}]", foreign_schema, foreign_table_name);
+            let formatted_foreign_table = format!(
+                "[{}].[{}]",
+                escape_identifier(&foreign_schema),
+                escape_identifier(&foreign_table_name)
+            );
 
             Some(
                 ConstraintBuilder::new(
@@ -2411,6 +2726,122 @@ fn extract_data_compression(with_options: &[Expr]) -> Option<DataCompressionType
 // Phase 20.6.2: Removed extract_filter_predicate_from_sql() - replaced with token-based
 // extract_index_filter_predicate_tokenized() from index_parser.rs
 
+/// Give the first element a statement added its header comment; the constraints, indexes
+/// and properties declared with it get none
+fn record_header(model: &mut DatabaseModel, header: Option<String>) {
+    if model.headers.len() < model.elements.len() {
+        model.headers.push(header);
+        model.headers.resize(model.elements.len(), None);
+    }
+}
+
+/// Add MS_Description extended properties from comments, unless the object or column has
+/// one already: with `headers`, to each table, view, procedure and function from its header
+/// comment, and to the columns in `column_comments` (table index, column, description)
+fn add_comment_descriptions(
+    model: &mut DatabaseModel,
+    headers: bool,
+    column_comments: &[(usize, Vec<(String, String)>)],
+) {
+    // (schema, object, column) of the descriptions already declared
+    let mut described: HashSet<(String, String, Option<String>)> = model
+        .elements
+        .iter()
+        .filter_map(|element| match element {
+            ModelElement::ExtendedProperty(e)
+                if e.property_name.eq_ignore_ascii_case("MS_Description") =>
+            {
+                Some((
+                    e.target_schema.to_lowercase(),
+                    e.target_object.to_lowercase(),
+                    e.target_column.as_ref().map(|c| c.to_lowercase()),
+                ))
+            }
+            _ => None,
+        })
+        .collect();
+
+    let mut properties: Vec<(usize, ExtendedPropertyElement)> = Vec::new();
+    let mut describe = |index: usize,
+                        (schema, name): (&str, &str),
+                        level1type: &str,
+                        column: Option<&str>,
+                        description: String| {
+        let key = (
+            schema.to_lowercase(),
+            name.to_lowercase(),
+            column.map(str::to_lowercase),
+        );
+        if !described.insert(key) {
+            return;
+        }
+        properties.push((
+            index,
+            ExtendedPropertyElement {
+                property_name: "MS_Description".to_string(),
+                property_value: description,
+                target_schema: schema.to_string(),
+                target_object: name.to_string(),
+                target_column: column.map(str::to_string),
+                level1type: Some(level1type.to_string()),
+                level2type: column.map(|_| "COLUMN".to_string()),
+            },
+        ));
+    };
+
+    if headers {
+        for (index, header) in model.headers.iter().enumerate() {
+            let Some(header) = header else {
+                continue;
+            };
+            let (level1type, schema, name) = match &model.elements[index] {
+                ModelElement::Table(t) => ("TABLE", &t.schema, &t.name),
+                ModelElement::View(v) => ("VIEW", &v.schema, &v.name),
+                ModelElement::Procedure(p) => ("PROCEDURE", &p.schema, &p.name),
+                ModelElement::Function(f) => ("FUNCTION", &f.schema, &f.name),
+                _ => continue,
+            };
+            describe(
+                index,
+                (schema, name),
+                level1type,
+                None,
+                header_description(header),
+            );
+        }
+    }
+
+    for (index, comments) in column_comments {
+        let ModelElement::Table(table) = &model.elements[*index] else {
+            continue;
+        };
+        for (column, description) in comments {
+            // Comments after a column the table does not have, e.g. a table constraint
+            let Some(column) = table
+                .columns
+                .iter()
+                .find(|c| c.name.eq_ignore_ascii_case(column))
+            else {
+                continue;
+            };
+            describe(
+                *index,
+                (&table.schema, &table.name),
+                "TABLE",
+                Some(&column.name),
+                description.clone(),
+            );
+        }
+    }
+
+    for (index, property) in properties {
+        let source = model.sources[index].clone();
+        model.add_element(ModelElement::ExtendedProperty(property));
+        model.sources.push(source);
+        model.headers.push(None);
+    }
+}
+
 /// Convert an extracted extended property to a model ExtendedPropertyElement
 fn extended_property_from_extracted(
     property: &ExtractedExtendedProperty,
@@ -2429,17 +2860,14 @@ fn extended_property_from_extracted(
 /// Extract view options (SCHEMABINDING, WITH CHECK OPTION, VIEW_METADATA) from SQL text
 /// Returns (is_schema_bound, is_with_check_option, is_metadata_reported)
 fn extract_view_options(sql: &str) -> (bool, bool, bool) {
-    // WITH SCHEMABINDING appears before AS in the view definition
-    let is_schema_bound = contains_ci(sql, "WITH SCHEMABINDING")
-        || contains_ci(sql, ", SCHEMABINDING")
-        || contains_ci(sql, ",SCHEMABINDING");
+    // SCHEMABINDING and VIEW_METADATA appear in the WITH clause before AS
+    let options = extract_module_options(sql);
+    let is_schema_bound = options.has_option("SCHEMABINDING");
+    let is_metadata_reported = options.has_option("VIEW_METADATA");
 
     // WITH CHECK OPTION appears at the end of the view definition
     let is_with_check_option = contains_ci(sql, "WITH CHECK OPTION");
 
-    // VIEW_METADATA appears in WITH clause before AS
-    let is_metadata_reported = contains_ci(sql, "VIEW_METADATA");
-
     (is_schema_bound, is_with_check_option, is_metadata_reported)
 }
 
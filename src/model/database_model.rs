@@ -1,6 +1,134 @@
 //! Database model representation
 
-use super::ModelElement;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::filter::{element_schema, parent_object};
+use super::{
+    ConstraintElement, FunctionElement, IndexElement, ModelElement, ProcedureElement,
+    SchemaElement, SequenceElement, SynonymElement, TableElement, TriggerElement,
+    UserDefinedTypeElement, ViewElement,
+};
+use crate::error::SqlPackageError;
+use crate::project::SqlServerVersion;
+
+/// Model serialization versions older DacFx consumers understand:
+/// `(SchemaVersion, FileFormatVersion, newest target platform the version can describe)`
+const SCHEMA_VERSIONS: &[(&str, &str, SqlServerVersion)] = &[
+    ("2.4", "1.2", SqlServerVersion::Sql140),
+    ("2.5", "1.2", SqlServerVersion::Sql140),
+    ("2.6", "1.2", SqlServerVersion::Sql150),
+    ("2.7", "1.2", SqlServerVersion::Sql150),
+    ("2.8", "1.2", SqlServerVersion::Sql160),
+    ("2.9", "1.2", SqlServerVersion::Sql160),
+];
+
+/// How closely model.xml follows DotNet DacFx output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputMode {
+    /// Byte-for-byte parity with DotNet DacFx (default)
+    #[default]
+    Parity,
+    /// Skip DacFx-only details (SysCommentsObjectAnnotation, empty header and
+    /// database option properties) for dacpacs that are only read by rust-sqlpackage
+    Relaxed,
+}
+
+impl std::str::FromStr for OutputMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "parity" | "strict" => Ok(OutputMode::Parity),
+            "relaxed" | "fast" => Ok(OutputMode::Relaxed),
+            _ => Err(format!("Unknown output mode: {}", s)),
+        }
+    }
+}
+
+/// Order of elements in model.xml
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ElementOrder {
+    /// DacFx order: by name, then type (default, required for parity output)
+    #[default]
+    DotNet,
+    /// Grouped by schema, each object followed by its constraints, indexes, triggers and
+    /// extended properties; database-level elements first. For relaxed output only.
+    Logical,
+}
+
+impl std::str::FromStr for ElementOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "dotnet" | "dacfx" => Ok(ElementOrder::DotNet),
+            "logical" | "schema" => Ok(ElementOrder::Logical),
+            _ => Err(format!("Unknown element order: {}", s)),
+        }
+    }
+}
+
+/// How script properties (BodyScript, QueryScript, ...) hold their text in model.xml
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScriptFormat {
+    /// A CDATA section, as DotNet DacFx writes them (default)
+    #[default]
+    Cdata,
+    /// Escaped text, for XML consumers that do not handle CDATA; carriage returns are
+    /// written as `&#xD;` so line endings survive reading
+    Escaped,
+}
+
+impl std::str::FromStr for ScriptFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cdata" => Ok(ScriptFormat::Cdata),
+            "escaped" | "text" => Ok(ScriptFormat::Escaped),
+            _ => Err(format!("Unknown script format: {}", s)),
+        }
+    }
+}
+
+/// Where an element was defined: the batch of the statement that created it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    /// SQL file, as listed in the project
+    pub file: PathBuf,
+    /// First line (1-based)
+    pub start_line: usize,
+    /// Last line (1-based, inclusive)
+    pub end_line: usize,
+    /// Blank lines (and indentation) between the start of the batch and `start_line`,
+    /// kept so module annotations can cover the batch byte for byte as DotNet does
+    pub leading_whitespace: String,
+}
+
+impl SourceLocation {
+    /// `file` relative to `dir` when it is under it, as shown in messages
+    pub fn display_relative_to(&self, dir: &Path) -> String {
+        let file = self.file.strip_prefix(dir).unwrap_or(&self.file);
+        let location = SourceLocation {
+            file: file.to_path_buf(),
+            ..self.clone()
+        };
+        location.to_string()
+    }
+}
+
+/// `Tables/Orders.sql:3-12`, or `Tables/Orders.sql:3` for a single line
+impl std::fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.file.display(), self.start_line)?;
+        if self.end_line > self.start_line {
+            write!(f, "-{}", self.end_line)?;
+        }
+        Ok(())
+    }
+}
 
 /// The complete database model
 #[derive(Debug, Clone)]
@@ -15,10 +143,32 @@ pub struct DatabaseModel {
     /// For inline constraints without emit_name, this is empty string.
     /// For all other elements, this equals the cached full name.
     pub cached_xml_names: Vec<String>,
+    /// Source location of each element (parallel to `elements` vec); `None` for elements
+    /// the builder adds itself, such as schemas only referenced by other objects
+    pub sources: Vec<Option<SourceLocation>>,
+    /// Header comment of the batch defining each element (parallel to `elements` vec),
+    /// kept for the first element a batch defines; see [`crate::parser::extract_header_comment`]
+    pub headers: Vec<Option<String>>,
     /// Model version
     pub schema_version: String,
     /// File format version
     pub file_format_version: String,
+    /// DacFx parity or relaxed serialization
+    pub output_mode: OutputMode,
+    /// CDATA (default) or escaped text for script properties
+    pub script_format: ScriptFormat,
+    /// Changes forced on the emitted model.xml (`[build.overrides]`)
+    pub overrides: crate::dacpac::ModelOverrides,
+    /// Record the project's source files and their hashes in the package
+    pub source_manifest: bool,
+    /// SHA256 of each SQL file's bytes as they were parsed, for the source manifest
+    pub source_hashes: BTreeMap<PathBuf, String>,
+    /// ANSI_NULLS setting objects are created with (the project's `<AnsiNulls>`)
+    pub ansi_nulls: bool,
+    /// QUOTED_IDENTIFIER setting modules are created with (the project's `<QuotedIdentifier>`)
+    pub quoted_identifier: bool,
+    /// Non-fatal warnings raised while building the model
+    pub warnings: Vec<String>,
 }
 
 impl Default for DatabaseModel {
@@ -27,8 +177,18 @@ impl Default for DatabaseModel {
             elements: Vec::new(),
             cached_full_names: Vec::new(),
             cached_xml_names: Vec::new(),
+            sources: Vec::new(),
+            headers: Vec::new(),
             schema_version: "2.9".to_string(),
             file_format_version: "1.2".to_string(),
+            output_mode: OutputMode::default(),
+            script_format: ScriptFormat::default(),
+            overrides: Default::default(),
+            source_manifest: false,
+            source_hashes: BTreeMap::new(),
+            ansi_nulls: true,
+            quoted_identifier: true,
+            warnings: Vec::new(),
         }
     }
 }
@@ -38,6 +198,40 @@ impl DatabaseModel {
         Self::default()
     }
 
+    /// Serialize with another model schema version (e.g. `2.4` for SSMS 17 / SQL Server 2016
+    /// tooling). Also sets the matching `FileFormatVersion`. Fails if the version is unknown
+    /// or predates `target_platform`.
+    pub fn set_schema_version(
+        &mut self,
+        version: &str,
+        target_platform: SqlServerVersion,
+    ) -> Result<(), SqlPackageError> {
+        let Some((schema_version, file_format_version, newest_platform)) = SCHEMA_VERSIONS
+            .iter()
+            .find(|(v, _, _)| *v == version.trim())
+        else {
+            let known: Vec<&str> = SCHEMA_VERSIONS.iter().map(|(v, _, _)| *v).collect();
+            return Err(SqlPackageError::UnsupportedSchemaVersion {
+                version: version.to_string(),
+                message: format!("expected one of {}", known.join(", ")),
+            });
+        };
+
+        if target_platform.compatibility_mode() > newest_platform.compatibility_mode() {
+            return Err(SqlPackageError::UnsupportedSchemaVersion {
+                version: version.to_string(),
+                message: format!(
+                    "target platform {:?} requires a newer schema version (this one supports up to {:?})",
+                    target_platform, newest_platform
+                ),
+            });
+        }
+
+        self.schema_version = schema_version.to_string();
+        self.file_format_version = file_format_version.to_string();
+        Ok(())
+    }
+
     pub fn add_element(&mut self, element: ModelElement) {
         self.elements.push(element);
     }
@@ -60,6 +254,210 @@ impl DatabaseModel {
         }
     }
 
+    /// Schema-scoped object named `[schema].[name]`, compared case-insensitively as on a
+    /// default-collation server (see [`ModelElement::schema_and_name`])
+    pub fn find(&self, schema: &str, name: &str) -> Option<&ModelElement> {
+        self.elements.iter().find(|e| {
+            e.schema_and_name().is_some_and(|(s, n)| {
+                s.eq_ignore_ascii_case(schema) && n.eq_ignore_ascii_case(name)
+            })
+        })
+    }
+
+    /// Schemas in model order
+    pub fn schemas(&self) -> impl Iterator<Item = &SchemaElement> {
+        self.elements.iter().filter_map(|e| match e {
+            ModelElement::Schema(s) => Some(s),
+            _ => None,
+        })
+    }
+
+    /// Whether the model has a schema named `name`, compared case-insensitively
+    pub fn has_schema(&self, name: &str) -> bool {
+        self.schemas().any(|s| s.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Tables in model order
+    pub fn tables(&self) -> impl Iterator<Item = &TableElement> {
+        self.elements.iter().filter_map(|e| match e {
+            ModelElement::Table(t) => Some(t),
+            _ => None,
+        })
+    }
+
+    /// Views in model order
+    pub fn views(&self) -> impl Iterator<Item = &ViewElement> {
+        self.elements.iter().filter_map(|e| match e {
+            ModelElement::View(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    /// Stored procedures in model order
+    pub fn procedures(&self) -> impl Iterator<Item = &ProcedureElement> {
+        self.elements.iter().filter_map(|e| match e {
+            ModelElement::Procedure(p) => Some(p),
+            _ => None,
+        })
+    }
+
+    /// Scalar and table-valued functions in model order
+    pub fn functions(&self) -> impl Iterator<Item = &FunctionElement> {
+        self.elements.iter().filter_map(|e| match e {
+            ModelElement::Function(f) => Some(f),
+            _ => None,
+        })
+    }
+
+    /// Rowstore indexes in model order (columnstore and full-text indexes are separate
+    /// element types)
+    pub fn indexes(&self) -> impl Iterator<Item = &IndexElement> {
+        self.elements.iter().filter_map(|e| match e {
+            ModelElement::Index(i) => Some(i),
+            _ => None,
+        })
+    }
+
+    /// Primary key, foreign key, unique, check and default constraints in model order
+    pub fn constraints(&self) -> impl Iterator<Item = &ConstraintElement> {
+        self.elements.iter().filter_map(|e| match e {
+            ModelElement::Constraint(c) => Some(c),
+            _ => None,
+        })
+    }
+
+    /// DML triggers in model order
+    pub fn triggers(&self) -> impl Iterator<Item = &TriggerElement> {
+        self.elements.iter().filter_map(|e| match e {
+            ModelElement::Trigger(t) => Some(t),
+            _ => None,
+        })
+    }
+
+    /// Sequences in model order
+    pub fn sequences(&self) -> impl Iterator<Item = &SequenceElement> {
+        self.elements.iter().filter_map(|e| match e {
+            ModelElement::Sequence(s) => Some(s),
+            _ => None,
+        })
+    }
+
+    /// User-defined table types in model order
+    pub fn table_types(&self) -> impl Iterator<Item = &UserDefinedTypeElement> {
+        self.elements.iter().filter_map(|e| match e {
+            ModelElement::UserDefinedType(u) => Some(u),
+            _ => None,
+        })
+    }
+
+    /// Synonyms in model order
+    pub fn synonyms(&self) -> impl Iterator<Item = &SynonymElement> {
+        self.elements.iter().filter_map(|e| match e {
+            ModelElement::Synonym(s) => Some(s),
+            _ => None,
+        })
+    }
+
+    /// Sort elements, keeping cached names and sources in step
+    ///
+    /// [`ElementOrder::DotNet`] sorts alphabetically (case-insensitive) by:
+    /// 1. Name attribute value (empty string for elements without Name attribute)
+    /// 2. Type attribute value (e.g., "SqlCheckConstraint", "SqlTable")
+    /// 3. Secondary key for disambiguation (DefiningTable reference for inline
+    ///    constraints), descending
+    ///
+    /// Sort keys are built once from the cached names (see `cache_element_names`).
+    pub fn sort_elements(&mut self, order: ElementOrder) {
+        let n = self.elements.len();
+        let mut indices: Vec<usize> = (0..n).collect();
+        match order {
+            ElementOrder::DotNet => {
+                let keys: Vec<_> = (0..n)
+                    .map(|i| {
+                        let name = self.xml_name(i).to_lowercase();
+                        let type_name = self.elements[i].type_name().to_lowercase();
+                        let secondary = self.elements[i].secondary_sort_key().to_lowercase();
+                        let secondary_desc = (name.is_empty() && !secondary.is_empty())
+                            .then_some(Reverse(secondary));
+                        (name, type_name, secondary_desc)
+                    })
+                    .collect();
+                indices.sort_by(|&a, &b| keys[a].cmp(&keys[b]));
+            }
+            ElementOrder::Logical => {
+                let keys: Vec<_> = (0..n).map(|i| self.logical_sort_key(i)).collect();
+                indices.sort_by(|&a, &b| keys[a].cmp(&keys[b]));
+            }
+        }
+        self.apply_order(&indices);
+    }
+
+    /// Sort elements with a custom comparer, keeping cached names and sources in step
+    ///
+    /// The sort is stable, so elements the comparer finds equal keep their order.
+    pub fn sort_elements_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&ModelElement, &ModelElement) -> Ordering,
+    {
+        let mut indices: Vec<usize> = (0..self.elements.len()).collect();
+        indices.sort_by(|&a, &b| compare(&self.elements[a], &self.elements[b]));
+        self.apply_order(&indices);
+    }
+
+    /// `(database-level, schema, owning object, object before its children, type, name)`
+    fn logical_sort_key(&self, index: usize) -> (bool, String, String, u8, &'static str, String) {
+        let element = &self.elements[index];
+        let name = self.xml_name(index).to_lowercase();
+        let Some(schema) = element_schema(element) else {
+            return (
+                false,
+                String::new(),
+                String::new(),
+                0,
+                element.type_name(),
+                name,
+            );
+        };
+        let (owner, rank) = match (element, parent_object(element)) {
+            (ModelElement::Schema(_), _) => (String::new(), 0),
+            (_, Some(parent)) => (parent.to_lowercase(), 2),
+            (_, None) => (self.full_name(index).to_lowercase(), 1),
+        };
+        (
+            true,
+            schema.to_lowercase(),
+            owner,
+            rank,
+            element.type_name(),
+            name,
+        )
+    }
+
+    /// Reorder the element vecs so that position `i` holds the element at `indices[i]`
+    fn apply_order(&mut self, indices: &[usize]) {
+        apply_permutation(&mut self.elements, indices);
+        if self.cached_full_names.len() == indices.len() {
+            apply_permutation(&mut self.cached_full_names, indices);
+            apply_permutation(&mut self.cached_xml_names, indices);
+        }
+        if self.sources.len() == indices.len() {
+            apply_permutation(&mut self.sources, indices);
+        }
+        if self.headers.len() == indices.len() {
+            apply_permutation(&mut self.headers, indices);
+        }
+    }
+
+    /// Where the element at `index` was defined, if known
+    pub fn source(&self, index: usize) -> Option<&SourceLocation> {
+        self.sources.get(index).and_then(Option::as_ref)
+    }
+
+    /// Header comment of the element at `index`, if its batch starts with one
+    pub fn header(&self, index: usize) -> Option<&str> {
+        self.headers.get(index).and_then(Option::as_deref)
+    }
+
     /// Get the cached xml_name_attr for an element by index.
     pub fn xml_name(&self, index: usize) -> &str {
         if index < self.cached_xml_names.len() {
@@ -69,3 +467,42 @@ impl DatabaseModel {
         }
     }
 }
+
+/// Apply a permutation to a vec by rebuilding it in the new order.
+/// After this call, `vec[i] = original_vec[indices[i]]` for all i.
+fn apply_permutation<T>(vec: &mut Vec<T>, indices: &[usize]) {
+    let n = vec.len();
+    debug_assert_eq!(n, indices.len());
+
+    // Move elements out of vec into Options for safe arbitrary-order access
+    let mut opts: Vec<Option<T>> = vec.drain(..).map(Some).collect();
+
+    // Rebuild vec in sorted order using the index permutation
+    for &idx in indices {
+        vec.push(opts[idx].take().expect("index used twice in permutation"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_schema_version() {
+        let mut model = DatabaseModel::new();
+        model
+            .set_schema_version("2.4", SqlServerVersion::Sql130)
+            .unwrap();
+        assert_eq!(model.schema_version, "2.4");
+        assert_eq!(model.file_format_version, "1.2");
+
+        let err = model
+            .set_schema_version("2.4", SqlServerVersion::Sql160)
+            .unwrap_err();
+        assert!(err.to_string().contains("Sql160"), "{}", err);
+        assert!(model
+            .set_schema_version("3.0", SqlServerVersion::Sql160)
+            .is_err());
+        assert_eq!(model.schema_version, "2.4");
+    }
+}
@@ -2,6 +2,8 @@
 
 use std::sync::Arc;
 
+use crate::parser::identifier_utils::escape_identifier;
+
 /// A database model element
 #[derive(Debug, Clone)]
 pub enum ModelElement {
@@ -43,6 +45,40 @@ pub enum ModelElement {
 }
 
 impl ModelElement {
+    /// Every name [`type_name`](Self::type_name) returns, for shell completion
+    pub const TYPE_NAMES: &'static [&'static str] = &[
+        "SqlSchema",
+        "SqlTable",
+        "SqlView",
+        "SqlProcedure",
+        "SqlScalarFunction",
+        "SqlMultiStatementTableValuedFunction",
+        "SqlInlineTableValuedFunction",
+        "SqlIndex",
+        "SqlFullTextIndex",
+        "SqlFullTextCatalog",
+        "SqlPrimaryKeyConstraint",
+        "SqlForeignKeyConstraint",
+        "SqlUniqueConstraint",
+        "SqlCheckConstraint",
+        "SqlDefaultConstraint",
+        "SqlSequence",
+        "SqlTableType",
+        "SqlUserDefinedDataType",
+        "SqlExtendedProperty",
+        "SqlDmlTrigger",
+        "SqlFilegroup",
+        "SqlPartitionFunction",
+        "SqlPartitionScheme",
+        "SqlSynonym",
+        "SqlUser",
+        "SqlRole",
+        "SqlPermissionStatement",
+        "SqlRoleMembership",
+        "SqlColumnStoreIndex",
+        "SqlAlterTableStatement",
+    ];
+
     /// Get the element type name for XML
     pub fn type_name(&self) -> &'static str {
         match self {
@@ -89,46 +125,139 @@ impl ModelElement {
         }
     }
 
+    /// Schema and name of a schema-scoped object (tables, views, routines, types,
+    /// sequences, synonyms, triggers and named constraints). Indexes, extended properties,
+    /// permissions and database-scoped elements return `None`.
+    pub fn schema_and_name(&self) -> Option<(&str, &str)> {
+        match self {
+            ModelElement::Table(t) => Some((&t.schema, &t.name)),
+            ModelElement::View(v) => Some((&v.schema, &v.name)),
+            ModelElement::Procedure(p) => Some((&p.schema, &p.name)),
+            ModelElement::Function(f) => Some((&f.schema, &f.name)),
+            ModelElement::Sequence(s) => Some((&s.schema, &s.name)),
+            ModelElement::UserDefinedType(u) => Some((&u.schema, &u.name)),
+            ModelElement::ScalarType(s) => Some((&s.schema, &s.name)),
+            ModelElement::Synonym(s) => Some((&s.schema, &s.name)),
+            ModelElement::Trigger(t) => Some((&t.schema, &t.name)),
+            ModelElement::Constraint(c) if c.emit_name => Some((&c.table_schema, &c.name)),
+            ModelElement::Raw(r) if !r.name.is_empty() => Some((&r.schema, &r.name)),
+            _ => None,
+        }
+    }
+
+    /// Schema an element belongs to: that of a schema-scoped object, or of the table an
+    /// index, constraint or extended property is on. `None` for database-scoped elements.
+    pub fn schema(&self) -> Option<&str> {
+        match self {
+            ModelElement::Index(i) => Some(&i.table_schema),
+            ModelElement::FullTextIndex(f) => Some(&f.table_schema),
+            ModelElement::ColumnstoreIndex(c) => Some(&c.table_schema),
+            ModelElement::Constraint(c) => Some(&c.table_schema),
+            ModelElement::ExtendedProperty(e) if !e.target_schema.is_empty() => {
+                Some(&e.target_schema)
+            }
+            _ => self.schema_and_name().map(|(schema, _)| schema),
+        }
+    }
+
     /// Get the full name (e.g., [dbo].[Users])
     pub fn full_name(&self) -> String {
         match self {
-            ModelElement::Schema(s) => format!("[{}]", s.name),
-            ModelElement::Table(t) => format!("[{}].[{}]", t.schema, t.name),
-            ModelElement::View(v) => format!("[{}].[{}]", v.schema, v.name),
-            ModelElement::Procedure(p) => format!("[{}].[{}]", p.schema, p.name),
-            ModelElement::Function(f) => format!("[{}].[{}]", f.schema, f.name),
+            ModelElement::Schema(s) => format!("[{}]", escape_identifier(&s.name)),
+            ModelElement::Table(t) => format!(
+                "[{}].[{}]",
+                escape_identifier(&t.schema),
+                escape_identifier(&t.name)
+            ),
+            ModelElement::View(v) => format!(
+                "[{}].[{}]",
+                escape_identifier(&v.schema),
+                escape_identifier(&v.name)
+            ),
+            ModelElement::Procedure(p) => format!(
+                "[{}].[{}]",
+                escape_identifier(&p.schema),
+                escape_identifier(&p.name)
+            ),
+            ModelElement::Function(f) => format!(
+                "[{}].[{}]",
+                escape_identifier(&f.schema),
+                escape_identifier(&f.name)
+            ),
             ModelElement::Index(i) => {
-                format!("[{}].[{}].[{}]", i.table_schema, i.table_name, i.name)
+                format!(
+                    "[{}].[{}].[{}]",
+                    escape_identifier(&i.table_schema),
+                    escape_identifier(&i.table_name),
+                    escape_identifier(&i.name)
+                )
             }
             ModelElement::FullTextIndex(f) => {
                 // Full-text index name format: [schema].[table] (same as table name)
-                format!("[{}].[{}]", f.table_schema, f.table_name)
+                format!(
+                    "[{}].[{}]",
+                    escape_identifier(&f.table_schema),
+                    escape_identifier(&f.table_name)
+                )
             }
             ModelElement::FullTextCatalog(c) => {
-                format!("[{}]", c.name)
+                format!("[{}]", escape_identifier(&c.name))
             }
             ModelElement::Constraint(c) => {
                 // DotNet uses two-part names for constraints: [schema].[constraint_name]
-                format!("[{}].[{}]", c.table_schema, c.name)
+                format!(
+                    "[{}].[{}]",
+                    escape_identifier(&c.table_schema),
+                    escape_identifier(&c.name)
+                )
             }
-            ModelElement::Sequence(s) => format!("[{}].[{}]", s.schema, s.name),
-            ModelElement::UserDefinedType(u) => format!("[{}].[{}]", u.schema, u.name),
-            ModelElement::ScalarType(s) => format!("[{}].[{}]", s.schema, s.name),
+            ModelElement::Sequence(s) => format!(
+                "[{}].[{}]",
+                escape_identifier(&s.schema),
+                escape_identifier(&s.name)
+            ),
+            ModelElement::UserDefinedType(u) => format!(
+                "[{}].[{}]",
+                escape_identifier(&u.schema),
+                escape_identifier(&u.name)
+            ),
+            ModelElement::ScalarType(s) => format!(
+                "[{}].[{}]",
+                escape_identifier(&s.schema),
+                escape_identifier(&s.name)
+            ),
             ModelElement::ExtendedProperty(e) => e.full_name(),
-            ModelElement::Trigger(t) => format!("[{}].[{}]", t.schema, t.name),
+            ModelElement::Trigger(t) => format!(
+                "[{}].[{}]",
+                escape_identifier(&t.schema),
+                escape_identifier(&t.name)
+            ),
             // Filegroups, partition functions, and partition schemes are NOT schema-qualified
-            ModelElement::Filegroup(f) => format!("[{}]", f.name),
-            ModelElement::PartitionFunction(pf) => format!("[{}]", pf.name),
-            ModelElement::PartitionScheme(ps) => format!("[{}]", ps.name),
-            ModelElement::Synonym(s) => format!("[{}].[{}]", s.schema, s.name),
-            ModelElement::User(u) => format!("[{}]", u.name),
-            ModelElement::Role(r) => format!("[{}]", r.name),
+            ModelElement::Filegroup(f) => format!("[{}]", escape_identifier(&f.name)),
+            ModelElement::PartitionFunction(pf) => format!("[{}]", escape_identifier(&pf.name)),
+            ModelElement::PartitionScheme(ps) => format!("[{}]", escape_identifier(&ps.name)),
+            ModelElement::Synonym(s) => format!(
+                "[{}].[{}]",
+                escape_identifier(&s.schema),
+                escape_identifier(&s.name)
+            ),
+            ModelElement::User(u) => format!("[{}]", escape_identifier(&u.name)),
+            ModelElement::Role(r) => format!("[{}]", escape_identifier(&r.name)),
             ModelElement::Permission(p) => p.full_name(),
             ModelElement::RoleMembership(rm) => rm.full_name(),
             ModelElement::ColumnstoreIndex(ci) => {
-                format!("[{}].[{}].[{}]", ci.table_schema, ci.table_name, ci.name)
+                format!(
+                    "[{}].[{}].[{}]",
+                    escape_identifier(&ci.table_schema),
+                    escape_identifier(&ci.table_name),
+                    escape_identifier(&ci.name)
+                )
             }
-            ModelElement::Raw(r) => format!("[{}].[{}]", r.schema, r.name),
+            ModelElement::Raw(r) => format!(
+                "[{}].[{}]",
+                escape_identifier(&r.schema),
+                escape_identifier(&r.name)
+            ),
         }
     }
 
@@ -140,7 +269,11 @@ impl ModelElement {
             // Constraints: emit_name determines if Name attribute is present
             ModelElement::Constraint(c) => {
                 if c.emit_name {
-                    format!("[{}].[{}]", c.table_schema, c.name)
+                    format!(
+                        "[{}].[{}]",
+                        escape_identifier(&c.table_schema),
+                        escape_identifier(&c.name)
+                    )
                 } else {
                     String::new() // No Name attribute for inline constraints without emit_name
                 }
@@ -159,7 +292,11 @@ impl ModelElement {
             ModelElement::Constraint(c) => {
                 if !c.emit_name {
                     // Inline constraint - sort by DefiningTable reference
-                    format!("[{}].[{}]", c.table_schema, c.table_name)
+                    format!(
+                        "[{}].[{}]",
+                        escape_identifier(&c.table_schema),
+                        escape_identifier(&c.table_name)
+                    )
                 } else {
                     String::new()
                 }
@@ -211,6 +348,10 @@ pub struct TableElement {
     pub history_table_schema: Option<String>,
     /// History table name for temporal tables
     pub history_table_name: Option<String>,
+    /// Table-level data compression (heap or inline clustered index)
+    pub data_compression: Option<DataCompressionType>,
+    /// Per-partition data compression from `ON PARTITIONS (...)`
+    pub partition_compression: Vec<(u32, DataCompressionType)>,
 }
 
 /// Column element
@@ -278,6 +419,12 @@ pub struct ProcedureElement {
     pub parameters: Vec<ParameterElement>,
     /// Whether this procedure is natively compiled (WITH NATIVE_COMPILATION)
     pub is_natively_compiled: bool,
+    /// Whether the procedure has WITH SCHEMABINDING (required for natively compiled procedures)
+    pub is_schema_bound: bool,
+    /// `BEGIN ATOMIC WITH (TRANSACTION ISOLATION LEVEL = ...)` of a natively compiled procedure
+    pub atomic_isolation_level: Option<String>,
+    /// `BEGIN ATOMIC WITH (LANGUAGE = ...)` of a natively compiled procedure
+    pub atomic_language: Option<String>,
     /// Dynamic column sources discovered in the procedure body (CTEs, temp tables, table variables)
     pub dynamic_sources: Vec<DynamicColumnSource>,
 }
@@ -380,6 +527,13 @@ pub struct FunctionElement {
     pub return_type: Option<String>,
     /// Whether this function is natively compiled (WITH NATIVE_COMPILATION)
     pub is_natively_compiled: bool,
+    /// Whether the function has WITH SCHEMABINDING
+    pub is_schema_bound: bool,
+    /// Whether the function has WITH RETURNS NULL ON NULL INPUT
+    /// (CALLED ON NULL INPUT is the default)
+    pub returns_null_on_null_input: bool,
+    /// Principal of WITH EXECUTE AS (`CALLER`, `OWNER`, `SELF` or a quoted user name)
+    pub execute_as: Option<String>,
     /// Dynamic column sources discovered in the function body (CTEs, temp tables, table variables)
     pub dynamic_sources: Vec<DynamicColumnSource>,
 }
@@ -402,6 +556,8 @@ pub struct IndexElement {
     pub filter_predicate: Option<String>,
     /// Data compression type (NONE, ROW, PAGE, COLUMNSTORE, COLUMNSTORE_ARCHIVE)
     pub data_compression: Option<DataCompressionType>,
+    /// Per-partition data compression as `(partition number, compression)`
+    pub partition_compression: Vec<(u32, DataCompressionType)>,
     /// Whether PAD_INDEX is ON (applies fill factor to intermediate pages)
     pub is_padded: bool,
 }
@@ -413,6 +569,8 @@ pub struct FullTextColumnElement {
     pub name: String,
     /// Language ID (e.g., 1033 for English)
     pub language_id: Option<u32>,
+    /// Column holding the document type of a varbinary column (TYPE COLUMN)
+    pub type_column: Option<String>,
 }
 
 /// Full-text index element
@@ -428,6 +586,8 @@ pub struct FullTextIndexElement {
     pub catalog: Option<String>,
     /// Change tracking mode (AUTO, MANUAL, OFF)
     pub change_tracking: Option<String>,
+    /// Stoplist (OFF, SYSTEM or a stoplist name)
+    pub stoplist: Option<String>,
     /// Disambiguator for elements sharing the same name (e.g., table)
     pub disambiguator: Option<u32>,
 }
@@ -523,6 +683,16 @@ pub struct ConstraintElement {
     pub referenced_columns: Option<Vec<String>>,
     /// Whether this constraint is clustered (for PK/unique)
     pub is_clustered: Option<bool>,
+    /// Fill factor percentage of the constraint's index (for PK/unique)
+    pub fill_factor: Option<u8>,
+    /// Whether PAD_INDEX is ON for the constraint's index (for PK/unique)
+    pub is_padded: bool,
+    /// Data compression of the constraint's index (for PK/unique)
+    pub data_compression: Option<DataCompressionType>,
+    /// Per-partition data compression of the constraint's index (for PK/unique)
+    pub partition_compression: Vec<(u32, DataCompressionType)>,
+    /// Filegroup from the constraint's ON clause (for PK/unique)
+    pub filegroup: Option<String>,
     /// Whether this is an inline constraint (defined within a column definition).
     /// DotNet DacFx treats ALL column-level constraints as inline, regardless of whether
     /// they have explicit CONSTRAINT names. Only table-level constraints (at end of
@@ -660,6 +830,10 @@ pub struct TriggerElement {
     pub is_delete_trigger: bool,
     /// Trigger type: 2 = AFTER, 3 = INSTEAD OF
     pub trigger_type: u8,
+    /// Whether the trigger has WITH SCHEMABINDING (natively compiled triggers)
+    pub is_schema_bound: bool,
+    /// False if the trigger is disabled by a DISABLE TRIGGER statement
+    pub is_enabled: bool,
 }
 
 /// Generic raw element for statements that couldn't be fully parsed
@@ -725,7 +899,11 @@ impl ExtendedPropertyElement {
                 .unwrap_or("SqlColumn");
             format!(
                 "[{}].[{}].[{}].[{}].[{}]",
-                prefix, self.target_schema, self.target_object, column, self.property_name
+                escape_identifier(prefix),
+                escape_identifier(&self.target_schema),
+                escape_identifier(&self.target_object),
+                escape_identifier(column),
+                escape_identifier(&self.property_name)
             )
         } else {
             // Table/View/Procedure-level property: prefix is from level1type
@@ -736,7 +914,10 @@ impl ExtendedPropertyElement {
                 .unwrap_or("SqlTableBase");
             format!(
                 "[{}].[{}].[{}].[{}]",
-                prefix, self.target_schema, self.target_object, self.property_name
+                escape_identifier(prefix),
+                escape_identifier(&self.target_schema),
+                escape_identifier(&self.target_object),
+                escape_identifier(&self.property_name)
             )
         }
     }
@@ -748,10 +929,16 @@ impl ExtendedPropertyElement {
         if let Some(ref column) = self.target_column {
             format!(
                 "[{}].[{}].[{}]",
-                self.target_schema, self.target_object, column
+                escape_identifier(&self.target_schema),
+                escape_identifier(&self.target_object),
+                escape_identifier(column)
             )
         } else {
-            format!("[{}].[{}]", self.target_schema, self.target_object)
+            format!(
+                "[{}].[{}]",
+                escape_identifier(&self.target_schema),
+                escape_identifier(&self.target_object)
+            )
         }
     }
 }
@@ -856,7 +1043,11 @@ impl PermissionElement {
                 let name = self.target_name.as_deref().unwrap_or("");
                 format!(
                     "[{}].[{}].[{}].[{}].[{}]",
-                    self.action, self.permission, schema, name, self.principal
+                    escape_identifier(&self.action),
+                    escape_identifier(&self.permission),
+                    escape_identifier(schema),
+                    escape_identifier(name),
+                    escape_identifier(&self.principal)
                 )
             }
             "Schema" => {
@@ -866,13 +1057,18 @@ impl PermissionElement {
                     .unwrap_or(self.target_schema.as_deref().unwrap_or(""));
                 format!(
                     "[{}].[{}].[{}].[{}]",
-                    self.action, self.permission, name, self.principal
+                    escape_identifier(&self.action),
+                    escape_identifier(&self.permission),
+                    escape_identifier(name),
+                    escape_identifier(&self.principal)
                 )
             }
             _ => {
                 format!(
                     "[{}].[{}].[{}]",
-                    self.action, self.permission, self.principal
+                    escape_identifier(&self.action),
+                    escape_identifier(&self.permission),
+                    escape_identifier(&self.principal)
                 )
             }
         }
@@ -890,7 +1086,11 @@ pub struct RoleMembershipElement {
 
 impl RoleMembershipElement {
     pub fn full_name(&self) -> String {
-        format!("[{}].[{}]", self.role, self.member)
+        format!(
+            "[{}].[{}]",
+            escape_identifier(&self.role),
+            escape_identifier(&self.member)
+        )
     }
 }
 
@@ -906,6 +1106,8 @@ pub struct ColumnstoreIndexElement {
     pub columns: Vec<String>,
     /// Data compression type (COLUMNSTORE or COLUMNSTORE_ARCHIVE)
     pub data_compression: Option<DataCompressionType>,
+    /// Per-partition data compression as `(partition number, compression)`
+    pub partition_compression: Vec<(u32, DataCompressionType)>,
     /// Filter predicate for filtered NONCLUSTERED columnstore indexes
     pub filter_predicate: Option<String>,
 }
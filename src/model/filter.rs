@@ -0,0 +1,451 @@
+//! Include/exclude filters for building a subset of the model
+//!
+//! Filters are written as `<include|exclude>:<schema|type|name>=<glob>`, e.g.
+//! `exclude:schema=Reporting`, `include:type=SqlTable` or `exclude:name=dbo.tmp_*`.
+//! Globs are case-insensitive. Constraints, indexes, triggers and extended properties
+//! follow the object they belong to, so excluding a table also drops its children.
+//! Objects that are kept but depend on removed ones are reported as
+//! [`DanglingReference`]s, as the filtered dacpac would not deploy.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+use glob::{MatchOptions, Pattern};
+
+use super::{ConstraintType, DatabaseModel, ModelElement};
+use crate::parser::identifier_utils::escape_identifier;
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: false,
+    require_literal_leading_dot: false,
+};
+
+/// Whether matching elements are kept or removed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterAction {
+    Include,
+    Exclude,
+}
+
+/// Element property a filter matches against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterTarget {
+    /// Schema name (e.g. `Reporting`)
+    Schema,
+    /// Element type, with or without the `Sql` prefix (e.g. `SqlTable`, `View`)
+    Type,
+    /// Object name, either bare (`Orders`) or schema-qualified (`Sales.Orders`)
+    Name,
+}
+
+/// A single include/exclude filter
+#[derive(Debug, Clone)]
+pub struct ObjectFilter {
+    pub action: FilterAction,
+    pub target: FilterTarget,
+    pub pattern: Pattern,
+}
+
+impl std::str::FromStr for ObjectFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "Invalid filter '{}': expected <include|exclude>:<schema|type|name>=<glob>",
+                s
+            )
+        };
+        let (action, rest) = s.split_once(':').ok_or_else(invalid)?;
+        let (target, pattern) = rest.split_once('=').ok_or_else(invalid)?;
+
+        let action = match action.trim().to_lowercase().as_str() {
+            "include" => FilterAction::Include,
+            "exclude" => FilterAction::Exclude,
+            _ => return Err(invalid()),
+        };
+        let target = match target.trim().to_lowercase().as_str() {
+            "schema" => FilterTarget::Schema,
+            "type" => FilterTarget::Type,
+            "name" => FilterTarget::Name,
+            _ => return Err(invalid()),
+        };
+        let pattern = Pattern::new(pattern.trim())
+            .map_err(|e| format!("Invalid filter pattern in '{}': {}", s, e))?;
+
+        Ok(ObjectFilter {
+            action,
+            target,
+            pattern,
+        })
+    }
+}
+
+impl ObjectFilter {
+    /// Whether this filter's pattern matches `element`
+    pub fn matches(&self, element: &ModelElement) -> bool {
+        match self.target {
+            FilterTarget::Schema => {
+                element_schema(element).is_some_and(|schema| self.is_match(schema))
+            }
+            FilterTarget::Type => self.matches_type(element.type_name()),
+            FilterTarget::Name => {
+                let (schema, name) = element_name(element);
+                self.matches_name(schema, name)
+            }
+        }
+    }
+
+    /// Whether this filter's pattern matches an element known only by its type, schema
+    /// and name, such as one read from a dacpac's model.xml
+    pub fn matches_parts(&self, type_name: &str, schema: Option<&str>, name: &str) -> bool {
+        match self.target {
+            FilterTarget::Schema => schema.is_some_and(|schema| self.is_match(schema)),
+            FilterTarget::Type => self.matches_type(type_name),
+            FilterTarget::Name => self.matches_name(schema, name),
+        }
+    }
+
+    fn matches_type(&self, type_name: &str) -> bool {
+        self.is_match(type_name)
+            || type_name
+                .strip_prefix("Sql")
+                .is_some_and(|bare| self.is_match(bare))
+    }
+
+    fn matches_name(&self, schema: Option<&str>, name: &str) -> bool {
+        self.is_match(name)
+            || schema.is_some_and(|schema| self.is_match(&format!("{}.{}", schema, name)))
+    }
+
+    fn is_match(&self, text: &str) -> bool {
+        self.pattern.matches_with(text, MATCH_OPTIONS)
+    }
+}
+
+/// Remove elements rejected by `filters` from the model.
+///
+/// An element is kept when it matches at least one applicable include filter (or there are
+/// none) and matches no exclude filter. Child elements follow their parent object.
+/// Returns the number of elements removed.
+pub fn apply_filters(model: &mut DatabaseModel, filters: &[ObjectFilter]) -> usize {
+    if filters.is_empty() {
+        return 0;
+    }
+
+    let excludes: Vec<&ObjectFilter> = filters
+        .iter()
+        .filter(|f| f.action == FilterAction::Exclude)
+        .collect();
+    let is_kept = |element: &ModelElement| {
+        is_kept(filters, element_schema(element).is_some(), |f| {
+            f.matches(element)
+        })
+    };
+
+    // First pass: decide top-level objects; remember removed parents for their children
+    let mut removed_parents: HashSet<String> = HashSet::new();
+    let mut keep: Vec<Option<bool>> = Vec::with_capacity(model.elements.len());
+    for element in &model.elements {
+        if parent_object(element).is_some() {
+            keep.push(None);
+        } else {
+            let kept = is_kept(element);
+            if !kept {
+                removed_parents.insert(element.full_name().to_lowercase());
+            }
+            keep.push(Some(kept));
+        }
+    }
+
+    // Second pass: children are dropped with their parent. Constraints carry inline
+    // annotations shared with their table, so only non-constraint children can be
+    // excluded on their own.
+    for (element, decision) in model.elements.iter().zip(keep.iter_mut()) {
+        if decision.is_none() {
+            let parent_removed = parent_object(element)
+                .is_some_and(|parent| removed_parents.contains(&parent.to_lowercase()));
+            let excluded = !matches!(element, ModelElement::Constraint(_))
+                && excludes.iter().any(|f| f.matches(element));
+            *decision = Some(!parent_removed && !excluded);
+        }
+    }
+
+    let before = model.elements.len();
+    let keep: Vec<bool> = keep.into_iter().map(|k| k.unwrap_or(true)).collect();
+    let mut decisions = keep.iter();
+    model
+        .elements
+        .retain(|_| decisions.next().copied().unwrap_or(true));
+    let mut decisions = keep.iter();
+    model
+        .sources
+        .retain(|_| decisions.next().copied().unwrap_or(true));
+    let mut decisions = keep.iter();
+    model
+        .headers
+        .retain(|_| decisions.next().copied().unwrap_or(true));
+    model.cache_element_names();
+    before - model.elements.len()
+}
+
+/// A reference from an object the filters kept to an object they removed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DanglingReference {
+    /// Full name of the kept object
+    pub from: String,
+    /// Full name of the removed object
+    pub to: String,
+}
+
+impl fmt::Display for DanglingReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} references {}, which the filters leave out",
+            self.from, self.to
+        )
+    }
+}
+
+/// The objects each object of `model` depends on, by full name: the dependencies
+/// model.xml records for views, procedures and functions, and the tables foreign keys
+/// reference. Taken before filtering, to find [`dangling_references`] after it.
+pub fn object_dependencies(
+    model: &DatabaseModel,
+    default_schema: &str,
+) -> BTreeMap<String, Vec<String>> {
+    let mut dependencies = crate::dacpac::object_references(model, default_schema);
+    let tables: HashSet<String> = model
+        .tables()
+        .map(|t| {
+            format!(
+                "[{}].[{}]",
+                escape_identifier(&t.schema),
+                escape_identifier(&t.name)
+            )
+            .to_lowercase()
+        })
+        .collect();
+    for element in &model.elements {
+        let ModelElement::Constraint(c) = element else {
+            continue;
+        };
+        if c.constraint_type != ConstraintType::ForeignKey {
+            continue;
+        }
+        let (Some(parent), Some(referenced)) = (parent_object(element), &c.referenced_table) else {
+            continue;
+        };
+        if tables.contains(&referenced.to_lowercase()) {
+            dependencies
+                .entry(parent)
+                .or_default()
+                .push(referenced.clone());
+        }
+    }
+    dependencies
+}
+
+/// References from objects `model` still has to objects the filters removed, given the
+/// [`object_dependencies`] of the model before filtering
+pub fn dangling_references(
+    model: &DatabaseModel,
+    dependencies: &BTreeMap<String, Vec<String>>,
+) -> Vec<DanglingReference> {
+    let kept: HashSet<String> = model
+        .elements
+        .iter()
+        .filter(|e| parent_object(e).is_none())
+        .map(|e| e.full_name().to_lowercase())
+        .collect();
+    let mut dangling = Vec::new();
+    for (from, targets) in dependencies {
+        if !kept.contains(&from.to_lowercase()) {
+            continue;
+        }
+        for to in targets {
+            let reference = DanglingReference {
+                from: from.clone(),
+                to: to.clone(),
+            };
+            if !kept.contains(&to.to_lowercase()) && !dangling.contains(&reference) {
+                dangling.push(reference);
+            }
+        }
+    }
+    dangling
+}
+
+/// Whether `filters` keep an element given by its type, schema and name, as
+/// [`apply_filters`] decides for top-level objects
+pub fn keeps_parts(
+    filters: &[ObjectFilter],
+    type_name: &str,
+    schema: Option<&str>,
+    name: &str,
+) -> bool {
+    is_kept(filters, schema.is_some(), |f| {
+        f.matches_parts(type_name, schema, name)
+    })
+}
+
+/// Whether an element matches at least one applicable include filter (or there are none)
+/// and no exclude filter. Schema filters do not apply to server/database-scoped elements
+/// such as users.
+fn is_kept(
+    filters: &[ObjectFilter],
+    has_schema: bool,
+    matches: impl Fn(&ObjectFilter) -> bool,
+) -> bool {
+    let mut includes = filters
+        .iter()
+        .filter(|f| {
+            f.action == FilterAction::Include && (f.target != FilterTarget::Schema || has_schema)
+        })
+        .peekable();
+    let included = includes.peek().is_none() || includes.any(&matches);
+    included
+        && !filters
+            .iter()
+            .any(|f| f.action == FilterAction::Exclude && matches(f))
+}
+
+/// Full name of the object an element belongs to (e.g. the table for a constraint, the
+/// securable for an object-level permission)
+pub(crate) fn parent_object(element: &ModelElement) -> Option<String> {
+    match element {
+        ModelElement::Constraint(c) => Some(format!(
+            "[{}].[{}]",
+            escape_identifier(&c.table_schema),
+            escape_identifier(&c.table_name)
+        )),
+        ModelElement::Index(i) => Some(format!(
+            "[{}].[{}]",
+            escape_identifier(&i.table_schema),
+            escape_identifier(&i.table_name)
+        )),
+        ModelElement::FullTextIndex(f) => Some(format!(
+            "[{}].[{}]",
+            escape_identifier(&f.table_schema),
+            escape_identifier(&f.table_name)
+        )),
+        ModelElement::ColumnstoreIndex(c) => Some(format!(
+            "[{}].[{}]",
+            escape_identifier(&c.table_schema),
+            escape_identifier(&c.table_name)
+        )),
+        ModelElement::Trigger(t) => Some(format!(
+            "[{}].[{}]",
+            escape_identifier(&t.parent_schema),
+            escape_identifier(&t.parent_name)
+        )),
+        ModelElement::ExtendedProperty(e) => Some(format!(
+            "[{}].[{}]",
+            escape_identifier(&e.target_schema),
+            escape_identifier(&e.target_object)
+        )),
+        ModelElement::Permission(p) if p.target_type == "Object" => Some(format!(
+            "[{}].[{}]",
+            escape_identifier(p.target_schema.as_deref().unwrap_or("dbo")),
+            escape_identifier(p.target_name.as_deref().unwrap_or(""))
+        )),
+        _ => None,
+    }
+}
+
+/// Schema an element lives in, if it is schema-scoped
+pub(crate) fn element_schema(element: &ModelElement) -> Option<&str> {
+    match element {
+        ModelElement::Schema(s) => Some(&s.name),
+        ModelElement::Table(t) => Some(&t.schema),
+        ModelElement::View(v) => Some(&v.schema),
+        ModelElement::Procedure(p) => Some(&p.schema),
+        ModelElement::Function(f) => Some(&f.schema),
+        ModelElement::Index(i) => Some(&i.table_schema),
+        ModelElement::FullTextIndex(f) => Some(&f.table_schema),
+        ModelElement::Constraint(c) => Some(&c.table_schema),
+        ModelElement::Sequence(s) => Some(&s.schema),
+        ModelElement::UserDefinedType(u) => Some(&u.schema),
+        ModelElement::ScalarType(s) => Some(&s.schema),
+        ModelElement::ExtendedProperty(e) => Some(&e.target_schema),
+        ModelElement::Trigger(t) => Some(&t.schema),
+        ModelElement::Synonym(s) => Some(&s.schema),
+        ModelElement::ColumnstoreIndex(c) => Some(&c.table_schema),
+        ModelElement::Raw(r) => Some(&r.schema),
+        ModelElement::Permission(p) => p.target_schema.as_deref(),
+        ModelElement::FullTextCatalog(_)
+        | ModelElement::Filegroup(_)
+        | ModelElement::PartitionFunction(_)
+        | ModelElement::PartitionScheme(_)
+        | ModelElement::User(_)
+        | ModelElement::Role(_)
+        | ModelElement::RoleMembership(_) => None,
+    }
+}
+
+/// `(schema, name)` used for name filters
+fn element_name(element: &ModelElement) -> (Option<&str>, &str) {
+    match element {
+        ModelElement::Schema(s) => (None, &s.name),
+        ModelElement::Table(t) => (Some(&t.schema), &t.name),
+        ModelElement::View(v) => (Some(&v.schema), &v.name),
+        ModelElement::Procedure(p) => (Some(&p.schema), &p.name),
+        ModelElement::Function(f) => (Some(&f.schema), &f.name),
+        ModelElement::Index(i) => (Some(&i.table_schema), &i.name),
+        ModelElement::FullTextIndex(f) => (Some(&f.table_schema), &f.table_name),
+        ModelElement::FullTextCatalog(c) => (None, &c.name),
+        ModelElement::Constraint(c) => (Some(&c.table_schema), &c.name),
+        ModelElement::Sequence(s) => (Some(&s.schema), &s.name),
+        ModelElement::UserDefinedType(u) => (Some(&u.schema), &u.name),
+        ModelElement::ScalarType(s) => (Some(&s.schema), &s.name),
+        ModelElement::ExtendedProperty(e) => (Some(&e.target_schema), &e.property_name),
+        ModelElement::Trigger(t) => (Some(&t.schema), &t.name),
+        ModelElement::Filegroup(f) => (None, &f.name),
+        ModelElement::PartitionFunction(pf) => (None, &pf.name),
+        ModelElement::PartitionScheme(ps) => (None, &ps.name),
+        ModelElement::Synonym(s) => (Some(&s.schema), &s.name),
+        ModelElement::User(u) => (None, &u.name),
+        ModelElement::Role(r) => (None, &r.name),
+        ModelElement::Permission(p) => (p.target_schema.as_deref(), &p.permission),
+        ModelElement::RoleMembership(rm) => (None, &rm.member),
+        ModelElement::ColumnstoreIndex(ci) => (Some(&ci.table_schema), &ci.name),
+        ModelElement::Raw(r) => (Some(&r.schema), &r.name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_filter() {
+        let filter: ObjectFilter = "exclude:schema=Report*".parse().unwrap();
+        assert_eq!(filter.action, FilterAction::Exclude);
+        assert_eq!(filter.target, FilterTarget::Schema);
+        assert!(filter.is_match("reporting"));
+
+        assert!("include:owner=dbo".parse::<ObjectFilter>().is_err());
+        assert!("keep:schema=dbo".parse::<ObjectFilter>().is_err());
+        assert!("schema=dbo".parse::<ObjectFilter>().is_err());
+    }
+
+    #[test]
+    fn test_keeps_parts() {
+        let filters: Vec<ObjectFilter> = ["include:schema=Sales", "exclude:name=Sales.tmp_*"]
+            .iter()
+            .map(|f| f.parse().unwrap())
+            .collect();
+        assert!(keeps_parts(&filters, "SqlTable", Some("Sales"), "Orders"));
+        assert!(!keeps_parts(
+            &filters,
+            "SqlTable",
+            Some("Sales"),
+            "tmp_Orders"
+        ));
+        assert!(!keeps_parts(&filters, "SqlView", Some("dbo"), "Orders"));
+        // Schema filters do not apply to database-scoped elements
+        assert!(keeps_parts(&filters, "SqlRole", None, "Reader"));
+    }
+}
@@ -3,7 +3,10 @@
 mod builder;
 mod database_model;
 mod elements;
+pub mod filter;
+mod validation;
 
 pub use builder::build_model;
-pub use database_model::DatabaseModel;
+pub use database_model::{DatabaseModel, ElementOrder, OutputMode, ScriptFormat, SourceLocation};
 pub use elements::*;
+pub use filter::{apply_filters, ObjectFilter};
@@ -0,0 +1,294 @@
+//! Checks on the built model for definitions SQL Server would reject at deployment
+//!
+//! DacFx reports these when it builds the project; without them a broken foreign key
+//! only surfaces when the deployment fails, and a misspelled index column becomes a
+//! dangling reference in model.xml.
+
+use std::collections::{BTreeSet, HashMap};
+
+use super::{
+    ColumnElement, ConstraintElement, ConstraintType, DatabaseModel, ModelElement, TableElement,
+};
+use crate::parser::identifier_utils::escape_identifier;
+
+/// Problems found by [`validate_model`]
+#[derive(Debug, Default)]
+pub(super) struct ValidationReport {
+    /// Definitions deployment would reject
+    pub warnings: Vec<String>,
+    /// References to columns missing from their table, which fail the build
+    pub errors: Vec<String>,
+}
+
+/// Check the elements of `model` against the tables they are defined on
+pub(super) fn validate_model(model: &DatabaseModel) -> ValidationReport {
+    let tables: HashMap<String, &TableElement> = model
+        .elements
+        .iter()
+        .filter_map(|element| match element {
+            ModelElement::Table(table) => Some((element.full_name().to_lowercase(), table)),
+            _ => None,
+        })
+        .collect();
+
+    let mut report = ValidationReport::default();
+    for (index, element) in model.elements.iter().enumerate() {
+        let location = model
+            .source(index)
+            .map(|source| format!(" ({}:{})", source.file.display(), source.start_line))
+            .unwrap_or_default();
+        for (table, column) in missing_columns(element, &tables) {
+            report.errors.push(format!(
+                "{} references column [{}], which {} does not have{}",
+                describe(element),
+                escape_identifier(column),
+                table,
+                location
+            ));
+        }
+        if let ModelElement::Constraint(fk) = element {
+            if fk.constraint_type == ConstraintType::ForeignKey {
+                for problem in check_foreign_key(model, fk, &tables) {
+                    report.warnings.push(format!(
+                        "Foreign key {} on {}: {}{}",
+                        element.full_name(),
+                        table_name(&fk.table_schema, &fk.table_name),
+                        problem,
+                        location
+                    ));
+                }
+            }
+        }
+    }
+    report
+}
+
+/// Columns an index, full-text index or constraint names that its table (or, for a
+/// foreign key, the referenced table) does not have, with that table's name
+///
+/// Elements on tables outside the model, such as views or tables in referenced
+/// dacpacs, are not checked, nor are graph pseudo-columns like `$node_id`.
+fn missing_columns<'a>(
+    element: &'a ModelElement,
+    tables: &HashMap<String, &TableElement>,
+) -> Vec<(String, &'a str)> {
+    let (schema, name, columns): (&str, &str, Vec<&str>) = match element {
+        ModelElement::Index(i) => (
+            &i.table_schema,
+            &i.table_name,
+            i.columns
+                .iter()
+                .map(|c| c.name.as_str())
+                .chain(i.include_columns.iter().map(String::as_str))
+                .collect(),
+        ),
+        ModelElement::ColumnstoreIndex(c) => (
+            &c.table_schema,
+            &c.table_name,
+            c.columns.iter().map(String::as_str).collect(),
+        ),
+        ModelElement::FullTextIndex(f) => (
+            &f.table_schema,
+            &f.table_name,
+            f.columns
+                .iter()
+                .flat_map(|c| std::iter::once(c.name.as_str()).chain(c.type_column.as_deref()))
+                .collect(),
+        ),
+        ModelElement::Constraint(c) => (
+            &c.table_schema,
+            &c.table_name,
+            c.columns.iter().map(|col| col.name.as_str()).collect(),
+        ),
+        _ => return vec![],
+    };
+
+    let mut missing = Vec::new();
+    let mut check = |table_name: String, columns: Vec<&'a str>| {
+        let Some(table) = tables.get(&table_name.to_lowercase()) else {
+            return;
+        };
+        for column in columns {
+            if !column.starts_with('$') && find_column(table, column).is_none() {
+                missing.push((table_name.clone(), column));
+            }
+        }
+    };
+    check(table_name(schema, name), columns);
+    if let ModelElement::Constraint(ConstraintElement {
+        referenced_table: Some(referenced),
+        referenced_columns: Some(referenced_columns),
+        ..
+    }) = element
+    {
+        check(
+            referenced.clone(),
+            referenced_columns.iter().map(String::as_str).collect(),
+        );
+    }
+    missing
+}
+
+/// How an element is named in messages, e.g. `Index [dbo].[Users].[IX_Users_Email]`
+fn describe(element: &ModelElement) -> String {
+    let kind = match element {
+        ModelElement::Index(_) => "Index",
+        ModelElement::ColumnstoreIndex(_) => "Columnstore index",
+        ModelElement::FullTextIndex(_) => "Full-text index on",
+        ModelElement::Constraint(c) => match c.constraint_type {
+            ConstraintType::PrimaryKey => "Primary key",
+            ConstraintType::ForeignKey => "Foreign key",
+            ConstraintType::Unique => "Unique constraint",
+            ConstraintType::Check => "Check constraint",
+            ConstraintType::Default => "Default constraint",
+        },
+        _ => element.type_name(),
+    };
+    format!("{} {}", kind, element.full_name())
+}
+
+/// Problems with a foreign key: it must reference the columns of a primary key, unique
+/// constraint or unique index, with as many columns of the same types
+///
+/// Keys referencing tables outside the model (other databases or referenced dacpacs)
+/// are not checked.
+fn check_foreign_key(
+    model: &DatabaseModel,
+    fk: &ConstraintElement,
+    tables: &HashMap<String, &TableElement>,
+) -> Vec<String> {
+    let Some(referenced_name) = &fk.referenced_table else {
+        return vec![];
+    };
+    let Some(referenced) = tables.get(&referenced_name.to_lowercase()) else {
+        return vec![];
+    };
+    let keys = unique_keys(model, referenced);
+
+    // Without a column list the key references the primary key
+    let referenced_columns: Vec<String> = match fk.referenced_columns.as_deref() {
+        Some(columns) if !columns.is_empty() => columns.to_vec(),
+        _ => match keys.iter().find(|(is_primary, _)| *is_primary) {
+            Some((_, columns)) => columns.clone(),
+            None => return vec![format!("{} has no primary key", referenced_name)],
+        },
+    };
+
+    if fk.columns.len() != referenced_columns.len() {
+        return vec![format!(
+            "{} column(s) reference {} column(s) of {}",
+            fk.columns.len(),
+            referenced_columns.len(),
+            referenced_name
+        )];
+    }
+
+    let wanted: BTreeSet<String> = referenced_columns
+        .iter()
+        .map(|c| c.to_lowercase())
+        .collect();
+    let is_key = keys.iter().any(|(_, columns)| {
+        columns
+            .iter()
+            .map(|c| c.to_lowercase())
+            .collect::<BTreeSet<_>>()
+            == wanted
+    });
+    let mut problems = Vec::new();
+    if !is_key {
+        problems.push(format!(
+            "({}) of {} is not a primary key, unique constraint or unique index",
+            column_list(&referenced_columns),
+            referenced_name
+        ));
+    }
+
+    let referencing = tables.get(&table_name(&fk.table_schema, &fk.table_name).to_lowercase());
+    for (column, referenced_column) in fk.columns.iter().zip(&referenced_columns) {
+        let (Some(from), Some(to)) = (
+            referencing.and_then(|table| find_column(table, &column.name)),
+            find_column(referenced, referenced_column),
+        ) else {
+            continue;
+        };
+        if normalize_type(&from.data_type) != normalize_type(&to.data_type) {
+            problems.push(format!(
+                "column [{}] is {} but references {}.[{}], which is {}",
+                escape_identifier(&column.name),
+                from.data_type,
+                referenced_name,
+                escape_identifier(referenced_column),
+                to.data_type
+            ));
+        }
+    }
+    problems
+}
+
+/// Column lists of the table's primary key (flagged `true`), unique constraints and
+/// unfiltered unique indexes
+fn unique_keys(model: &DatabaseModel, table: &TableElement) -> Vec<(bool, Vec<String>)> {
+    let on_table = |schema: &str, name: &str| {
+        schema.eq_ignore_ascii_case(&table.schema) && name.eq_ignore_ascii_case(&table.name)
+    };
+    model
+        .elements
+        .iter()
+        .filter_map(|element| match element {
+            ModelElement::Constraint(c)
+                if matches!(
+                    c.constraint_type,
+                    ConstraintType::PrimaryKey | ConstraintType::Unique
+                ) && on_table(&c.table_schema, &c.table_name) =>
+            {
+                Some((
+                    c.constraint_type == ConstraintType::PrimaryKey,
+                    c.columns.iter().map(|col| col.name.clone()).collect(),
+                ))
+            }
+            ModelElement::Index(i)
+                if i.is_unique
+                    && i.filter_predicate.is_none()
+                    && on_table(&i.table_schema, &i.table_name) =>
+            {
+                Some((
+                    false,
+                    i.columns.iter().map(|col| col.name.clone()).collect(),
+                ))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn find_column<'a>(table: &'a TableElement, name: &str) -> Option<&'a ColumnElement> {
+    table
+        .columns
+        .iter()
+        .find(|column| column.name.eq_ignore_ascii_case(name))
+}
+
+/// A data type compared without case, brackets or spacing: `[nvarchar] (10)` is `NVARCHAR(10)`
+fn normalize_type(data_type: &str) -> String {
+    data_type
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '[' && *c != ']')
+        .collect::<String>()
+        .to_uppercase()
+}
+
+fn table_name(schema: &str, name: &str) -> String {
+    format!(
+        "[{}].[{}]",
+        escape_identifier(schema),
+        escape_identifier(name)
+    )
+}
+
+fn column_list(columns: &[String]) -> String {
+    columns
+        .iter()
+        .map(|c| format!("[{}]", escape_identifier(c)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,369 @@
+//! Human-readable model dump for debugging
+//!
+//! Renders the generated model.xml as an indented tree of elements, their properties and
+//! relationships, so model-building issues can be inspected without reading raw XML:
+//!
+//! ```text
+//! SqlTable [dbo].[Orders]
+//!   IsAnsiNullsOn = True
+//!   Columns
+//!     SqlSimpleColumn [dbo].[Orders].[Id]
+//!       IsNullable = False
+//!       TypeSpecifier
+//!         SqlTypeSpecifier
+//!           Type -> [int] (BuiltIns)
+//!   Schema -> [dbo] (BuiltIns)
+//! ```
+//!
+//! With sources, each top-level element is followed by the file and lines that define
+//! it (`SqlTable [dbo].[Orders]  -- Tables/Orders.sql:1-8`).
+//!
+//! Top-level elements can be limited by type and name globs. Constraints also match the
+//! name of the table they are defined on, so a table is dumped together with them.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use anyhow::{anyhow, Result};
+use glob::{MatchOptions, Pattern};
+use roxmltree::{Document, Node};
+
+use crate::parser::identifier_utils::split_name_parts;
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: false,
+    require_literal_leading_dot: false,
+};
+
+/// Type and name globs selecting which top-level elements are dumped.
+///
+/// An element is dumped when it matches any type pattern (or there are none) and any
+/// name pattern (or there are none).
+#[derive(Debug, Clone, Default)]
+pub struct DumpFilter {
+    /// Element type, with or without the `Sql` prefix (e.g. `SqlTable`, `*Constraint`)
+    pub types: Vec<Pattern>,
+    /// Element name, either bare (`Orders`) or schema-qualified (`dbo.Orders`)
+    pub names: Vec<Pattern>,
+}
+
+impl DumpFilter {
+    /// Build a filter from glob strings
+    pub fn new(types: &[String], names: &[String]) -> Result<Self> {
+        let compile = |patterns: &[String]| -> Result<Vec<Pattern>> {
+            patterns
+                .iter()
+                .map(|p| {
+                    Pattern::new(p.trim())
+                        .map_err(|e| anyhow!("Invalid dump-model pattern '{}': {}", p, e))
+                })
+                .collect()
+        };
+        Ok(Self {
+            types: compile(types)?,
+            names: compile(names)?,
+        })
+    }
+
+    fn matches(&self, element: Node) -> bool {
+        let type_name = element.attribute("Type").unwrap_or_default();
+        let type_matches = self.types.is_empty()
+            || self.types.iter().any(|p| {
+                p.matches_with(type_name, MATCH_OPTIONS)
+                    || type_name
+                        .strip_prefix("Sql")
+                        .is_some_and(|bare| p.matches_with(bare, MATCH_OPTIONS))
+            });
+
+        // Elements defined on a table (constraints) also match the table's name
+        let name_matches = self.names.is_empty()
+            || [element.attribute("Name"), defining_table(element)]
+                .into_iter()
+                .flatten()
+                .any(|name| {
+                    let parts = name_parts(name);
+                    let dotted = parts.join(".");
+                    self.names.iter().any(|p| {
+                        p.matches_with(&dotted, MATCH_OPTIONS)
+                            || parts
+                                .last()
+                                .is_some_and(|bare| p.matches_with(bare, MATCH_OPTIONS))
+                    })
+                });
+
+        type_matches && name_matches
+    }
+}
+
+/// Render the elements of a model.xml document (or `<Model>` fragment) as a tree
+pub fn render_model_dump(model_xml: &str, filter: &DumpFilter) -> Result<String> {
+    render_model_dump_with_sources(model_xml, filter, &HashMap::new())
+}
+
+/// Render the model as [`render_model_dump`] does, noting where each top-level element
+/// was defined
+///
+/// `sources` maps an element's `(Type, Name)` to its location, e.g. `Tables/Orders.sql:1-8`.
+pub fn render_model_dump_with_sources(
+    model_xml: &str,
+    filter: &DumpFilter,
+    sources: &HashMap<(String, String), String>,
+) -> Result<String> {
+    let document = Document::parse(model_xml)?;
+    let model = document
+        .descendants()
+        .find(|n| n.has_tag_name("Model"))
+        .unwrap_or_else(|| document.root_element());
+
+    let mut out = String::new();
+    for element in model
+        .children()
+        .filter(|n| n.has_tag_name("Element") && filter.matches(*n))
+    {
+        let source = element
+            .attribute("Type")
+            .zip(element.attribute("Name"))
+            .and_then(|(t, n)| sources.get(&(t.to_string(), n.to_string())));
+        write_element(&mut out, element, 0, source.map(String::as_str));
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn write_element(out: &mut String, element: Node, depth: usize, source: Option<&str>) {
+    let indent = "  ".repeat(depth);
+    let _ = write!(
+        out,
+        "{}{}",
+        indent,
+        element.attribute("Type").unwrap_or("Element")
+    );
+    if let Some(name) = element.attribute("Name") {
+        let _ = write!(out, " {}", name);
+    }
+    if let Some(disambiguator) = element.attribute("Disambiguator") {
+        let _ = write!(out, " (Disambiguator {})", disambiguator);
+    }
+    if let Some(source) = source {
+        let _ = write!(out, "  -- {}", source);
+    }
+    out.push('\n');
+    write_members(out, element, depth + 1);
+}
+
+/// Properties, relationships and annotations of an element or annotation
+fn write_members(out: &mut String, node: Node, depth: usize) {
+    let indent = "  ".repeat(depth);
+    for child in node.children().filter(Node::is_element) {
+        match child.tag_name().name() {
+            "Property" => write_property(out, child, depth),
+            "Relationship" => write_relationship(out, child, depth),
+            "Annotation" | "AttachedAnnotation" => {
+                let _ = write!(
+                    out,
+                    "{}@{}",
+                    indent,
+                    child
+                        .attribute("Type")
+                        .unwrap_or_else(|| child.tag_name().name())
+                );
+                if let Some(disambiguator) = child.attribute("Disambiguator") {
+                    let _ = write!(out, " (Disambiguator {})", disambiguator);
+                }
+                out.push('\n');
+                write_members(out, child, depth + 1);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn write_property(out: &mut String, property: Node, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let name = property.attribute("Name").unwrap_or_default();
+    // Scripts are stored in a <Value> child rather than the Value attribute
+    let value = property.attribute("Value").map(str::to_string).or_else(|| {
+        property
+            .children()
+            .find(|n| n.has_tag_name("Value"))
+            .map(|v| v.text().unwrap_or_default().to_string())
+    });
+    let value = value.unwrap_or_default();
+    let value = value.trim();
+
+    if value.contains('\n') {
+        let _ = writeln!(out, "{}{} =", indent, name);
+        for line in value.lines() {
+            let _ = writeln!(out, "{}  | {}", indent, line);
+        }
+    } else {
+        let _ = writeln!(out, "{}{} = {}", indent, name, value);
+    }
+}
+
+fn write_relationship(out: &mut String, relationship: Node, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let name = relationship.attribute("Name").unwrap_or_default();
+    let entries: Vec<Node> = relationship
+        .children()
+        .filter(|n| n.has_tag_name("Entry"))
+        .flat_map(|entry| entry.children().filter(Node::is_element))
+        .collect();
+
+    // A single reference fits on one line: `Schema -> [dbo]`
+    if let [reference] = entries.as_slice() {
+        if reference.has_tag_name("References") {
+            let _ = writeln!(out, "{}{} -> {}", indent, name, reference_text(*reference));
+            return;
+        }
+    }
+
+    let _ = writeln!(out, "{}{}", indent, name);
+    for entry in entries {
+        if entry.has_tag_name("References") {
+            let _ = writeln!(out, "{}  -> {}", indent, reference_text(entry));
+        } else if entry.has_tag_name("Element") {
+            write_element(out, entry, depth + 1, None);
+        }
+    }
+}
+
+fn reference_text(reference: Node) -> String {
+    let name = reference.attribute("Name").unwrap_or_default();
+    match reference.attribute("ExternalSource") {
+        Some(source) => format!("{} ({})", name, source),
+        None => name.to_string(),
+    }
+}
+
+/// Name of the table an element (e.g. a constraint) is defined on
+fn defining_table<'a>(element: Node<'a, 'a>) -> Option<&'a str> {
+    element
+        .children()
+        .find(|n| n.has_tag_name("Relationship") && n.attribute("Name") == Some("DefiningTable"))?
+        .descendants()
+        .find(|n| n.has_tag_name("References"))?
+        .attribute("Name")
+}
+
+/// Split `[dbo].[Orders]` into `["dbo", "Orders"]`
+///
+/// Names the tokenizer cannot split are returned whole.
+pub(crate) fn name_parts(name: &str) -> Vec<String> {
+    split_name_parts(name).unwrap_or_else(|| vec![name.to_string()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODEL: &str = r#"<Model>
+  <Element Type="SqlPrimaryKeyConstraint">
+    <Relationship Name="DefiningTable">
+      <Entry>
+        <References Name="[dbo].[Orders]" />
+      </Entry>
+    </Relationship>
+    <Annotation Type="SqlInlineConstraintAnnotation" Disambiguator="3" />
+  </Element>
+  <Element Type="SqlTable" Name="[dbo].[Orders]">
+    <Property Name="IsAnsiNullsOn" Value="True" />
+    <Relationship Name="Columns">
+      <Entry>
+        <Element Type="SqlSimpleColumn" Name="[dbo].[Orders].[Id]">
+          <Relationship Name="TypeSpecifier">
+            <Entry>
+              <Element Type="SqlTypeSpecifier">
+                <Relationship Name="Type">
+                  <Entry>
+                    <References ExternalSource="BuiltIns" Name="[int]" />
+                  </Entry>
+                </Relationship>
+              </Element>
+            </Entry>
+          </Relationship>
+        </Element>
+      </Entry>
+    </Relationship>
+    <Relationship Name="Schema">
+      <Entry>
+        <References ExternalSource="BuiltIns" Name="[dbo]" />
+      </Entry>
+    </Relationship>
+  </Element>
+  <Element Type="SqlView" Name="[dbo].[OpenOrders]">
+    <Property Name="QueryScript">
+      <Value><![CDATA[SELECT [Id]
+FROM [dbo].[Orders]]]></Value>
+    </Property>
+    <Relationship Name="QueryDependencies">
+      <Entry>
+        <References Name="[dbo].[Orders]" />
+      </Entry>
+      <Entry>
+        <References Name="[dbo].[Orders].[Id]" />
+      </Entry>
+    </Relationship>
+  </Element>
+</Model>"#;
+
+    #[test]
+    fn test_render_model_dump() {
+        let dump = render_model_dump(MODEL, &DumpFilter::default()).unwrap();
+        let expected = "\
+SqlPrimaryKeyConstraint
+  DefiningTable -> [dbo].[Orders]
+  @SqlInlineConstraintAnnotation (Disambiguator 3)
+
+SqlTable [dbo].[Orders]
+  IsAnsiNullsOn = True
+  Columns
+    SqlSimpleColumn [dbo].[Orders].[Id]
+      TypeSpecifier
+        SqlTypeSpecifier
+          Type -> [int] (BuiltIns)
+  Schema -> [dbo] (BuiltIns)
+
+SqlView [dbo].[OpenOrders]
+  QueryScript =
+    | SELECT [Id]
+    | FROM [dbo].[Orders]
+  QueryDependencies
+    -> [dbo].[Orders]
+    -> [dbo].[Orders].[Id]
+
+";
+        assert_eq!(dump, expected);
+    }
+
+    #[test]
+    fn test_render_model_dump_filtered() {
+        let filter = DumpFilter::new(&["table".to_string()], &[]).unwrap();
+        let dump = render_model_dump(MODEL, &filter).unwrap();
+        assert!(dump.starts_with("SqlTable [dbo].[Orders]\n"));
+        assert!(!dump.contains("SqlView"));
+
+        // Constraints follow their defining table
+        let filter = DumpFilter::new(&[], &["dbo.orders".to_string()]).unwrap();
+        let dump = render_model_dump(MODEL, &filter).unwrap();
+        assert!(dump.contains("SqlPrimaryKeyConstraint\n"));
+        assert!(dump.contains("SqlTable [dbo].[Orders]\n"));
+        assert!(!dump.contains("SqlView"));
+
+        let filter = DumpFilter::new(&["SqlView".to_string()], &["Open*".to_string()]).unwrap();
+        let dump = render_model_dump(MODEL, &filter).unwrap();
+        assert!(dump.starts_with("SqlView [dbo].[OpenOrders]\n"));
+        assert!(!dump.contains("SqlTable"));
+
+        assert!(DumpFilter::new(&["[".to_string()], &[]).is_err());
+    }
+
+    #[test]
+    fn test_name_parts() {
+        assert_eq!(name_parts("[dbo].[Orders]"), vec!["dbo", "Orders"]);
+        assert_eq!(name_parts("[dbo].[a.b]]c]"), vec!["dbo", "a.b]c"]);
+        assert_eq!(name_parts("Orders"), vec!["Orders"]);
+        assert_eq!(name_parts("[dbo].[A.B]"), vec!["dbo", "A.B"]);
+    }
+}
@@ -0,0 +1,136 @@
+//! Column descriptions from trailing `-- description:` comments in CREATE TABLE
+//!
+//! ```sql
+//! CREATE TABLE [dbo].[Customers] (
+//!     [Id]   INT           NOT NULL,  -- description: Surrogate key
+//!     [Name] NVARCHAR(100) NOT NULL   -- description: Name as printed on invoices
+//! );
+//! ```
+//!
+//! A comment belongs to the column whose definition ends on the same line, before or
+//! after its comma. Comments on a line of their own are ignored.
+
+use super::lexer::{tokenize, Token, Whitespace};
+
+/// Keywords starting a table-level constraint, index or period rather than a column
+const TABLE_ELEMENT_KEYWORDS: &[&str] = &[
+    "CONSTRAINT",
+    "PRIMARY",
+    "UNIQUE",
+    "FOREIGN",
+    "CHECK",
+    "INDEX",
+    "PERIOD",
+];
+
+/// `(column, description)` for each column of a CREATE TABLE statement with a trailing
+/// `-- description:` comment, in definition order
+pub fn extract_column_descriptions(sql: &str) -> Vec<(String, String)> {
+    let mut descriptions: Vec<(String, String)> = Vec::new();
+    let Ok(tokens) = tokenize(sql) else {
+        return descriptions;
+    };
+
+    let mut depth = 0usize;
+    let mut started = false;
+    let mut ended = false;
+    // Column being defined, and the one before it while the next name is pending
+    let mut current: Option<String> = None;
+    let mut previous: Option<String> = None;
+    let mut expecting_name = false;
+    let mut newline = false;
+
+    for token in &tokens {
+        match token {
+            Token::Whitespace(Whitespace::Newline) => newline = true,
+            Token::Whitespace(Whitespace::SingleLineComment { comment, .. }) => {
+                if started && !newline {
+                    let column = if expecting_name { &previous } else { &current };
+                    if let (Some(column), Some(text)) = (column, description_text(comment)) {
+                        descriptions.retain(|(c, _)| !c.eq_ignore_ascii_case(column));
+                        descriptions.push((column.clone(), text.to_string()));
+                    }
+                }
+                newline |= comment.ends_with('\n');
+            }
+            Token::Whitespace(Whitespace::MultiLineComment(comment)) => {
+                newline |= comment.contains('\n');
+            }
+            Token::Whitespace(_) => {}
+            _ if ended => break,
+            _ => {
+                newline = false;
+                match token {
+                    Token::LParen if !started => {
+                        started = true;
+                        depth = 1;
+                        expecting_name = true;
+                    }
+                    Token::LParen => depth += 1,
+                    Token::RParen if started => {
+                        depth -= 1;
+                        if depth == 0 {
+                            // A comment after the closing parenthesis describes the last column
+                            previous = current.take();
+                            expecting_name = true;
+                            ended = true;
+                        }
+                    }
+                    Token::Comma if depth == 1 => {
+                        previous = current.take();
+                        expecting_name = true;
+                    }
+                    _ if expecting_name && depth == 1 => {
+                        expecting_name = false;
+                        current = match token {
+                            Token::Word(w)
+                                if w.quote_style.is_some()
+                                    || !TABLE_ELEMENT_KEYWORDS
+                                        .iter()
+                                        .any(|k| w.value.eq_ignore_ascii_case(k)) =>
+                            {
+                                Some(w.value.clone())
+                            }
+                            _ => None,
+                        };
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    descriptions
+}
+
+/// The text of a `description:` comment (without its `--`)
+fn description_text(comment: &str) -> Option<&str> {
+    let comment = comment.trim();
+    let (key, text) = comment.split_once(':')?;
+    let text = text.trim();
+    (key.trim().eq_ignore_ascii_case("description") && !text.is_empty()).then_some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_column_descriptions() {
+        let sql = "CREATE TABLE [dbo].[Customers] ( -- description: not a column\n\
+                   -- description: on its own line\n\
+                   [Id] INT NOT NULL, -- description: Surrogate key\n\
+                   [Name] NVARCHAR(100) NOT NULL -- Description:  Name on invoices \n\
+                   , [Region] CHAR(2) NULL DEFAULT ('NZ') -- regular comment\n\
+                   , CONSTRAINT [PK_Customers] PRIMARY KEY ([Id]) -- description: the key\n\
+                   , [Notes] NVARCHAR(MAX) NULL) -- description: Free text\n\
+                   -- description: after the table";
+        assert_eq!(
+            extract_column_descriptions(sql),
+            vec![
+                ("Id".to_string(), "Surrogate key".to_string()),
+                ("Name".to_string(), "Name on invoices".to_string()),
+                ("Notes".to_string(), "Free text".to_string()),
+            ]
+        );
+    }
+}
@@ -130,12 +130,17 @@ impl ColumnTokenParser {
         self.base.expect_keyword(Keyword::AS)?;
         self.base.skip_whitespace();
 
-        // Parse the expression (everything in parentheses)
-        let expression = self.parse_parenthesized_expression()?;
+        // Parse the expression (everything in parentheses); T-SQL also allows the
+        // parentheses to be omitted, e.g. `[Total] AS dbo.CalcTotal([Quantity], [Price])`
+        let expression = if self.base.check_token(&Token::LParen) {
+            format!("({})", self.base.consume_parenthesized_contents()?)
+        } else {
+            self.parse_unparenthesized_expression()?
+        };
 
         let mut result = TokenParsedColumn {
             name,
-            computed_expression: Some(format!("({})", expression)),
+            computed_expression: Some(expression),
             ..Default::default()
         };
 
@@ -260,7 +265,7 @@ impl ColumnTokenParser {
 
                 // Parse check expression
                 if self.base.check_token(&Token::LParen) {
-                    let expr = self.parse_parenthesized_expression();
+                    let expr = self.base.consume_parenthesized_contents();
                     result.check_expression = expr;
                     result.check_constraint_name = pending_constraint_name.take();
                     // Same logic as DEFAULT - emit Name only if CONSTRAINT immediately precedes CHECK
@@ -426,7 +431,7 @@ impl ColumnTokenParser {
         // Check for type parameters in parentheses
         self.base.skip_whitespace();
         if self.base.check_token(&Token::LParen) {
-            let params = self.consume_parenthesized_raw()?;
+            let params = self.base.consume_parenthesized_contents()?;
             data_type.push('(');
             data_type.push_str(&params);
             data_type.push(')');
@@ -510,159 +515,92 @@ impl ColumnTokenParser {
     /// Parse a DEFAULT value (handles various forms: function calls, literals, parenthesized expressions)
     fn parse_default_value(&mut self) -> Option<String> {
         self.base.skip_whitespace();
+        let start_pos = self.base.pos();
+        self.skip_default_value()?;
+        let value = self.base.tokens_to_string(start_pos, self.base.pos());
+        Some(value.trim_end().to_string())
+    }
 
-        // Check what kind of default value we have
+    /// Move past a DEFAULT value without building its text, so the caller can take it
+    /// verbatim from the source
+    fn skip_default_value(&mut self) -> Option<()> {
+        // Parenthesized expression like ((0)) or (GETDATE())
         if self.base.check_token(&Token::LParen) {
-            // Parenthesized expression like ((0)) or (GETDATE())
-            let expr = self.parse_parenthesized_expression()?;
-            return Some(format!("({})", expr));
+            self.base.skip_parenthesized();
+            return Some(());
         }
 
-        // Check for function call like GETDATE(), NEWID(), etc.
-        if let Some(token) = self.base.current_token() {
-            if let Token::Word(word) = &token.token {
-                let func_name = word.value.clone();
+        match &self.base.current_token()?.token {
+            // Function call like GETDATE(), NEWID(), etc., or a keyword like NULL
+            Token::Word(_) => {
                 self.base.advance();
+                let after_word = self.base.pos();
                 self.base.skip_whitespace();
-
-                // Check if followed by ()
                 if self.base.check_token(&Token::LParen) {
-                    self.base.advance(); // consume (
-                    self.base.skip_whitespace();
-                    if self.base.check_token(&Token::RParen) {
-                        self.base.advance(); // consume )
-                        return Some(format!("{}()", func_name));
-                    }
-                    // Function with args - reconstruct
-                    let mut args = String::new();
-                    let mut depth = 1;
-                    while !self.base.is_at_end() && depth > 0 {
-                        if let Some(t) = self.base.current_token() {
-                            match &t.token {
-                                Token::LParen => depth += 1,
-                                Token::RParen => depth -= 1,
-                                _ => {}
-                            }
-                            if depth > 0 {
-                                args.push_str(&TokenParser::token_to_string(&t.token));
-                            }
-                            self.base.advance();
-                        }
-                    }
-                    return Some(format!("{}({})", func_name, args.trim()));
+                    self.base.skip_parenthesized();
+                } else {
+                    self.base.set_pos(after_word);
                 }
-
-                // Just a word - could be a keyword like NULL
-                return Some(func_name);
+                Some(())
             }
-        }
-
-        // Check for string literal
-        if let Some(token) = self.base.current_token() {
-            match &token.token {
-                Token::SingleQuotedString(s) => {
-                    let value = format!("'{}'", s.replace('\'', "''"));
-                    self.base.advance();
-                    return Some(value);
-                }
-                Token::NationalStringLiteral(s) => {
-                    let value = format!("N'{}'", s.replace('\'', "''"));
-                    self.base.advance();
-                    return Some(value);
-                }
-                Token::Number(n, _) => {
-                    let value = n.clone();
-                    self.base.advance();
-                    return Some(value);
-                }
-                Token::Minus => {
-                    // Negative number
+            Token::SingleQuotedString(_) | Token::NationalStringLiteral(_) | Token::Number(..) => {
+                self.base.advance();
+                Some(())
+            }
+            // Negative number
+            Token::Minus => {
+                self.base.advance();
+                if matches!(self.base.current_token()?.token, Token::Number(..)) {
                     self.base.advance();
-                    if let Some(next) = self.base.current_token() {
-                        if let Token::Number(n, _) = &next.token {
-                            let value = format!("-{}", n);
-                            self.base.advance();
-                            return Some(value);
-                        }
-                    }
+                    Some(())
+                } else {
+                    None
                 }
-                _ => {}
             }
+            _ => None,
         }
-
-        None
     }
 
-    /// Parse a parenthesized expression and return its contents
-    /// Reconstructs the content from tokens
-    fn parse_parenthesized_expression(&mut self) -> Option<String> {
-        if !self.base.check_token(&Token::LParen) {
-            return None;
-        }
-
-        self.base.advance(); // consume (
-
-        let mut depth = 1;
-        let mut content = String::new();
+    /// Parse a computed column expression written without enclosing parentheses,
+    /// stopping at PERSISTED, NOT NULL, NULL or CONSTRAINT outside nested parentheses
+    /// and CASE expressions (`IS NOT NULL` stays part of the expression)
+    fn parse_unparenthesized_expression(&mut self) -> Option<String> {
+        let mut depth = 0usize;
+        let mut case_depth = 0usize;
+        let mut after_is = false;
+        let start_pos = self.base.pos();
 
-        while !self.base.is_at_end() && depth > 0 {
-            if let Some(token) = self.base.current_token() {
-                match &token.token {
-                    Token::LParen => {
-                        depth += 1;
-                        content.push('(');
-                    }
-                    Token::RParen => {
-                        depth -= 1;
-                        if depth > 0 {
-                            content.push(')');
-                        }
-                        // depth == 0 means we hit the closing paren, don't add it
-                    }
-                    _ => {
-                        content.push_str(&TokenParser::token_to_string(&token.token));
-                    }
+        while let Some(token) = self.base.current_token() {
+            match &token.token {
+                Token::LParen => depth += 1,
+                Token::RParen => depth = depth.saturating_sub(1),
+                Token::Word(w) if w.keyword == Keyword::CASE => case_depth += 1,
+                Token::Word(w) if w.keyword == Keyword::END => {
+                    case_depth = case_depth.saturating_sub(1)
                 }
-                self.base.advance();
-            }
-        }
-
-        Some(content.trim().to_string())
-    }
-
-    /// Consume a parenthesized section and return the raw content
-    fn consume_parenthesized_raw(&mut self) -> Option<String> {
-        if !self.base.check_token(&Token::LParen) {
-            return None;
-        }
-
-        self.base.advance(); // consume (
-
-        let mut depth = 1;
-        let mut content = String::new();
-
-        while !self.base.is_at_end() && depth > 0 {
-            if let Some(token) = self.base.current_token() {
-                match &token.token {
-                    Token::LParen => {
-                        depth += 1;
-                        content.push('(');
-                    }
-                    Token::RParen => {
-                        depth -= 1;
-                        if depth > 0 {
-                            content.push(')');
-                        }
-                    }
-                    _ => {
-                        content.push_str(&TokenParser::token_to_string(&token.token));
-                    }
+                Token::Word(w)
+                    if depth == 0
+                        && case_depth == 0
+                        && !after_is
+                        && w.quote_style.is_none()
+                        && (matches!(
+                            w.keyword,
+                            Keyword::NOT | Keyword::NULL | Keyword::CONSTRAINT
+                        ) || w.value.eq_ignore_ascii_case("PERSISTED")) =>
+                {
+                    break;
                 }
-                self.base.advance();
+                _ => {}
+            }
+            if !matches!(token.token, Token::Whitespace(_)) {
+                after_is = matches!(&token.token, Token::Word(w) if w.keyword == Keyword::IS);
             }
+            self.base.advance();
         }
 
-        Some(content.trim().to_string())
+        let content = self.base.tokens_to_string(start_pos, self.base.pos());
+        let content = content.trim();
+        (!content.is_empty()).then(|| content.to_string())
     }
 }
 
@@ -802,6 +740,20 @@ mod tests {
         assert_eq!(result.nullability, Some(false)); // NOT NULL
     }
 
+    #[test]
+    fn test_column_default_is_verbatim() {
+        let result =
+            parse_column_definition_tokens("[Created] DATETIME NOT NULL DEFAULT getdate ( )")
+                .unwrap();
+        assert_eq!(result.default_value, Some("getdate ( )".to_string()));
+        assert_eq!(result.nullability, Some(false));
+
+        let result =
+            parse_column_definition_tokens("[Name] NVARCHAR(20) NULL DEFAULT ( N'O''Brien' )")
+                .unwrap();
+        assert_eq!(result.default_value, Some("( N'O''Brien' )".to_string()));
+    }
+
     #[test]
     fn test_column_with_default_string() {
         let result =
@@ -856,6 +808,29 @@ mod tests {
         assert!(result.is_persisted);
     }
 
+    #[test]
+    fn test_computed_column_without_parentheses() {
+        let result = parse_column_definition_tokens(
+            "[Total] AS dbo.CalcTotal([Qty], [Price]) PERSISTED NOT NULL",
+        )
+        .unwrap();
+        assert_eq!(
+            result.computed_expression,
+            Some("dbo.CalcTotal([Qty], [Price])".to_string())
+        );
+        assert!(result.is_persisted);
+        assert_eq!(result.nullability, Some(false));
+
+        let result = parse_column_definition_tokens(
+            "[HasNote] AS CASE WHEN [Note] IS NOT NULL THEN 1 ELSE NULL END",
+        )
+        .unwrap();
+        assert_eq!(
+            result.computed_expression,
+            Some("CASE WHEN [Note] IS NOT NULL THEN 1 ELSE NULL END".to_string())
+        );
+    }
+
     #[test]
     fn test_computed_column_with_nullability() {
         let result =
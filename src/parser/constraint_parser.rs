@@ -23,10 +23,16 @@
 //! CONSTRAINT [name] CHECK ([expression])
 //! PRIMARY KEY ([Col1])  -- unnamed
 //! ```
+//!
+//! PRIMARY KEY and UNIQUE constraints may be followed by index options:
+//! ```sql
+//! CONSTRAINT [name] PRIMARY KEY ([Col1]) WITH (FILLFACTOR = 80, DATA_COMPRESSION = PAGE) ON [PRIMARY]
+//! ```
 
 use sqlparser::keywords::Keyword;
 use sqlparser::tokenizer::{Token, TokenWithSpan};
 
+use super::identifier_utils::escape_identifier;
 use super::token_parser_base::TokenParser;
 
 /// Constraint column with sort order
@@ -38,6 +44,21 @@ pub struct TokenParsedConstraintColumn {
     pub descending: bool,
 }
 
+/// Index options of a PRIMARY KEY or UNIQUE constraint (`WITH (...)` and `ON` clauses)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConstraintIndexOptions {
+    /// Fill factor percentage (0-100)
+    pub fill_factor: Option<u8>,
+    /// Whether PAD_INDEX is ON
+    pub is_padded: bool,
+    /// Data compression type (NONE, ROW, PAGE, etc.)
+    pub data_compression: Option<String>,
+    /// Per-partition data compression (`DATA_COMPRESSION = PAGE ON PARTITIONS (1 TO 3)`)
+    pub partition_compression: Vec<(u32, String)>,
+    /// Filegroup named in the ON clause (partition schemes are not recorded)
+    pub filegroup: Option<String>,
+}
+
 /// Parsed constraint result
 #[derive(Debug, Clone)]
 pub enum TokenParsedConstraint {
@@ -45,11 +66,13 @@ pub enum TokenParsedConstraint {
         name: String,
         columns: Vec<TokenParsedConstraintColumn>,
         is_clustered: bool,
+        options: ConstraintIndexOptions,
     },
     Unique {
         name: String,
         columns: Vec<TokenParsedConstraintColumn>,
         is_clustered: bool,
+        options: ConstraintIndexOptions,
     },
     ForeignKey {
         name: String,
@@ -235,11 +258,13 @@ impl ConstraintTokenParser {
 
         // Parse column list with sort order
         let columns = self.parse_constraint_column_list()?;
+        let options = self.parse_constraint_index_options();
 
         Some(TokenParsedConstraint::PrimaryKey {
             name,
             columns,
             is_clustered,
+            options,
         })
     }
 
@@ -266,11 +291,13 @@ impl ConstraintTokenParser {
 
         // Parse column list with sort order
         let columns = self.parse_constraint_column_list()?;
+        let options = self.parse_constraint_index_options();
 
         Some(TokenParsedConstraint::Unique {
             name,
             columns,
             is_clustered,
+            options,
         })
     }
 
@@ -303,7 +330,11 @@ impl ConstraintTokenParser {
 
         // Parse referenced table (schema-qualified)
         let (ref_schema, ref_table) = self.base.parse_schema_qualified_name()?;
-        let referenced_table = format!("[{}].[{}]", ref_schema, ref_table);
+        let referenced_table = format!(
+            "[{}].[{}]",
+            escape_identifier(&ref_schema),
+            escape_identifier(&ref_table)
+        );
         self.base.skip_whitespace();
 
         // Parse referenced columns
@@ -393,6 +424,94 @@ impl ConstraintTokenParser {
         }
     }
 
+    /// Parse the optional `WITH (...)` and `ON <filegroup>` clauses after a
+    /// PRIMARY KEY or UNIQUE column list
+    fn parse_constraint_index_options(&mut self) -> ConstraintIndexOptions {
+        let mut options = ConstraintIndexOptions::default();
+        self.base.skip_whitespace();
+
+        if self.base.check_keyword(Keyword::WITH) {
+            self.base.advance();
+            self.base.skip_whitespace();
+            if self.base.check_token(&Token::LParen) {
+                self.base.advance();
+                self.parse_with_options(&mut options);
+            }
+            self.base.skip_whitespace();
+        }
+
+        if self.base.check_keyword(Keyword::ON) {
+            self.base.advance();
+            self.base.skip_whitespace();
+            if let Some(name) = self.base.parse_identifier() {
+                self.base.skip_whitespace();
+                // ON partition_scheme (column) places the index on a partition scheme
+                if !self.base.check_token(&Token::LParen) {
+                    options.filegroup = Some(name);
+                }
+            }
+        }
+
+        options
+    }
+
+    /// Parse `option = value` pairs up to the closing parenthesis of a WITH clause
+    fn parse_with_options(&mut self, options: &mut ConstraintIndexOptions) {
+        while !self.base.is_at_end() {
+            self.base.skip_whitespace();
+            if self.base.check_token(&Token::RParen) {
+                self.base.advance();
+                break;
+            }
+
+            let Some(option) = self.base.parse_identifier() else {
+                self.base.advance();
+                continue;
+            };
+            self.base.skip_whitespace();
+            if self.base.check_token(&Token::Eq) {
+                self.base.advance();
+                self.base.skip_whitespace();
+            }
+
+            if option.eq_ignore_ascii_case("FILLFACTOR") {
+                options.fill_factor = self
+                    .base
+                    .parse_positive_integer()
+                    .filter(|&value| value <= 100)
+                    .map(|value| value as u8);
+            } else if option.eq_ignore_ascii_case("PAD_INDEX") {
+                options.is_padded = self.base.check_word_ci("ON");
+            } else if option.eq_ignore_ascii_case("DATA_COMPRESSION") {
+                if let Some(compression) = self.base.parse_identifier().map(|c| c.to_uppercase()) {
+                    match self.base.parse_on_partitions() {
+                        Some(partitions) => options
+                            .partition_compression
+                            .extend(partitions.into_iter().map(|p| (p, compression.clone()))),
+                        None => options.data_compression = Some(compression),
+                    }
+                }
+            }
+
+            // Skip the rest of the option up to the next comma
+            let mut depth = 0usize;
+            while !self.base.is_at_end() {
+                if self.base.check_token(&Token::LParen) {
+                    depth += 1;
+                } else if self.base.check_token(&Token::RParen) {
+                    if depth == 0 {
+                        break;
+                    }
+                    depth -= 1;
+                } else if depth == 0 && self.base.check_token(&Token::Comma) {
+                    self.base.advance();
+                    break;
+                }
+                self.base.advance();
+            }
+        }
+    }
+
     /// Parse a simple column list (just names, no ASC/DESC)
     /// Format: ([Col1], [Col2], ...)
     fn parse_simple_column_list(&mut self) -> Option<Vec<String>> {
@@ -443,42 +562,9 @@ impl ConstraintTokenParser {
 
     /// Parse a parenthesized expression and return its contents
     fn parse_parenthesized_expression(&mut self) -> Option<String> {
-        if !self.base.check_token(&Token::LParen) {
-            return None;
-        }
-
-        self.base.advance(); // consume (
-
-        let mut depth = 1;
-        let mut content = String::new();
-
-        while !self.base.is_at_end() && depth > 0 {
-            if let Some(token) = self.base.current_token() {
-                match &token.token {
-                    Token::LParen => {
-                        depth += 1;
-                        content.push('(');
-                    }
-                    Token::RParen => {
-                        depth -= 1;
-                        if depth > 0 {
-                            content.push(')');
-                        }
-                    }
-                    _ => {
-                        content.push_str(&TokenParser::token_to_string(&token.token));
-                    }
-                }
-                self.base.advance();
-            }
-        }
-
-        let content = content.trim().to_string();
-        if content.is_empty() {
-            None
-        } else {
-            Some(content)
-        }
+        self.base
+            .consume_parenthesized_contents()
+            .filter(|content| !content.is_empty())
     }
 }
 
@@ -617,6 +703,7 @@ mod tests {
             name,
             columns,
             is_clustered,
+            ..
         } = result.constraint
         {
             assert_eq!(name, "PK_Users");
@@ -685,6 +772,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_alter_add_pk_with_index_options() {
+        let sql = "ALTER TABLE [dbo].[Logs] ADD CONSTRAINT [PK_Logs] PRIMARY KEY ([Id]) WITH (PAD_INDEX = ON, FILLFACTOR = 80, DATA_COMPRESSION = page ON PARTITIONS (1)) ON [USERDATA];";
+        let result = parse_alter_table_add_constraint_tokens(sql).unwrap();
+
+        if let TokenParsedConstraint::PrimaryKey { options, .. } = result.constraint {
+            assert_eq!(options.fill_factor, Some(80));
+            assert!(options.is_padded);
+            assert_eq!(options.data_compression, None);
+            assert_eq!(options.partition_compression, vec![(1, "PAGE".to_string())]);
+            assert_eq!(options.filegroup.as_deref(), Some("USERDATA"));
+        } else {
+            panic!("Expected PrimaryKey constraint");
+        }
+
+        // Partition schemes are not filegroups
+        let sql = "ALTER TABLE [dbo].[Logs] ADD CONSTRAINT [UQ_Logs] UNIQUE ([Id], [Date]) ON [PS_Date]([Date])";
+        let result = parse_alter_table_add_constraint_tokens(sql).unwrap();
+        if let TokenParsedConstraint::Unique { options, .. } = result.constraint {
+            assert_eq!(options, ConstraintIndexOptions::default());
+        } else {
+            panic!("Expected Unique constraint");
+        }
+    }
+
     // ========================================================================
     // ALTER TABLE ADD CONSTRAINT UNIQUE tests (C2)
     // ========================================================================
@@ -698,6 +810,7 @@ mod tests {
             name,
             columns,
             is_clustered,
+            ..
         } = result.constraint
         {
             assert_eq!(name, "UQ_Email");
@@ -923,6 +1036,7 @@ mod tests {
             name,
             columns,
             is_clustered,
+            ..
         } = result
         {
             assert_eq!(name, "PK_Users");
@@ -955,6 +1069,7 @@ mod tests {
             name,
             columns,
             is_clustered,
+            ..
         } = result
         {
             assert_eq!(name, "UQ_Email");
@@ -10,6 +10,9 @@
 //! CREATE FULLTEXT INDEX ON [schema].[table] ([col1] LANGUAGE 1033, [col2])
 //!     KEY INDEX [pk_name] ON [catalog] WITH CHANGE_TRACKING AUTO;
 //!
+//! CREATE FULLTEXT INDEX ON [schema].[table] ([Content] TYPE COLUMN [Extension])
+//!     KEY INDEX [pk_name] WITH (CHANGE_TRACKING = MANUAL, STOPLIST = [MyStopList]);
+//!
 //! CREATE FULLTEXT CATALOG [name] AS DEFAULT;
 //! ```
 
@@ -25,6 +28,8 @@ pub struct TokenParsedFullTextColumn {
     pub name: String,
     /// Language ID (e.g., 1033 for English)
     pub language_id: Option<u32>,
+    /// Column holding the document type of a varbinary column (TYPE COLUMN)
+    pub type_column: Option<String>,
 }
 
 /// Result of parsing a CREATE FULLTEXT INDEX statement
@@ -42,6 +47,8 @@ pub struct TokenParsedFullTextIndex {
     pub catalog: Option<String>,
     /// Change tracking mode (AUTO, MANUAL, OFF)
     pub change_tracking: Option<String>,
+    /// Stoplist (OFF, SYSTEM or a stoplist name)
+    pub stoplist: Option<String>,
 }
 
 /// Result of parsing a CREATE FULLTEXT CATALOG statement
@@ -142,8 +149,8 @@ impl FullTextTokenParser {
         };
         self.base.skip_whitespace();
 
-        // Parse optional WITH clause (CHANGE_TRACKING)
-        let change_tracking = self.parse_fulltext_with_options();
+        // Parse optional WITH clause (CHANGE_TRACKING, STOPLIST)
+        let (change_tracking, stoplist) = self.parse_fulltext_with_options();
 
         Some(TokenParsedFullTextIndex {
             table_schema,
@@ -152,6 +159,7 @@ impl FullTextTokenParser {
             key_index,
             catalog,
             change_tracking,
+            stoplist,
         })
     }
 
@@ -222,30 +230,31 @@ impl FullTextTokenParser {
                 let mut col = TokenParsedFullTextColumn {
                     name: col_name,
                     language_id: None,
+                    type_column: None,
                 };
 
                 self.base.skip_whitespace();
 
-                // Check for optional LANGUAGE specifier
-                if self.base.check_word_ci("LANGUAGE") {
-                    self.base.advance();
-                    self.base.skip_whitespace();
-                    if let Some(lang_id) = self.base.parse_positive_integer() {
-                        col.language_id = Some(lang_id as u32);
-                    }
-                    self.base.skip_whitespace();
-                }
-
-                // Check for TYPE COLUMN (advanced feature, skip it)
-                if self.base.check_keyword(Keyword::TYPE) {
-                    self.base.advance();
-                    self.base.skip_whitespace();
-                    if self.base.check_word_ci("COLUMN") {
+                // TYPE COLUMN and LANGUAGE may appear in either order
+                loop {
+                    if self.base.check_word_ci("LANGUAGE") {
                         self.base.advance();
                         self.base.skip_whitespace();
-                        // Skip the type column name
-                        let _ = self.base.parse_identifier();
+                        if let Some(lang_id) = self.base.parse_positive_integer() {
+                            col.language_id = Some(lang_id as u32);
+                        }
                         self.base.skip_whitespace();
+                    } else if self.base.check_keyword(Keyword::TYPE) {
+                        self.base.advance();
+                        self.base.skip_whitespace();
+                        if self.base.check_word_ci("COLUMN") {
+                            self.base.advance();
+                            self.base.skip_whitespace();
+                            col.type_column = self.base.parse_identifier();
+                            self.base.skip_whitespace();
+                        }
+                    } else {
+                        break;
                     }
                 }
 
@@ -285,48 +294,71 @@ impl FullTextTokenParser {
         }
     }
 
-    /// Parse WITH clause for fulltext index: WITH CHANGE_TRACKING AUTO|MANUAL|OFF
-    fn parse_fulltext_with_options(&mut self) -> Option<String> {
+    /// Parse WITH clause for fulltext index, returning `(change_tracking, stoplist)`:
+    /// `WITH [(] CHANGE_TRACKING [=] AUTO|MANUAL|OFF [, NO POPULATION], STOPLIST [=] OFF|SYSTEM|name [)]`
+    fn parse_fulltext_with_options(&mut self) -> (Option<String>, Option<String>) {
+        let mut change_tracking = None;
+        let mut stoplist = None;
         if !self.base.check_keyword(Keyword::WITH) {
-            return None;
+            return (change_tracking, stoplist);
         }
         self.base.advance();
         self.base.skip_whitespace();
 
-        // Check for CHANGE_TRACKING keyword
-        if self.base.check_word_ci("CHANGE_TRACKING") {
-            self.base.advance();
-            self.base.skip_whitespace();
-
-            // Parse the mode: AUTO, MANUAL, OFF, or NO POPULATION
-            if self.base.check_word_ci("AUTO") {
+        while !self.base.is_at_end() {
+            if self.base.check_token(&Token::LParen) || self.base.check_token(&Token::Comma) {
                 self.base.advance();
-                return Some("AUTO".to_string());
-            } else if self.base.check_word_ci("MANUAL") {
+                self.base.skip_whitespace();
+                continue;
+            }
+
+            if self.base.check_word_ci("CHANGE_TRACKING") {
                 self.base.advance();
-                return Some("MANUAL".to_string());
-            } else if self.base.check_word_ci("OFF") {
+                self.skip_optional_equals();
+                // Parse the mode: AUTO, MANUAL, OFF
+                for mode in ["AUTO", "MANUAL", "OFF"] {
+                    if self.base.check_word_ci(mode) {
+                        self.base.advance();
+                        change_tracking = Some(mode.to_string());
+                        break;
+                    }
+                }
+                self.base.skip_whitespace();
+            } else if self.base.check_word_ci("NO") {
+                // ", NO POPULATION" after CHANGE_TRACKING OFF
                 self.base.advance();
                 self.base.skip_whitespace();
-                // Check for optional ", NO POPULATION" after OFF
-                if self.base.check_token(&Token::Comma) {
+                if self.base.check_word_ci("POPULATION") {
                     self.base.advance();
                     self.base.skip_whitespace();
                 }
-                if self.base.check_word_ci("NO") {
-                    self.base.advance();
-                    self.base.skip_whitespace();
-                    if self.base.check_word_ci("POPULATION") {
-                        self.base.advance();
+            } else if self.base.check_word_ci("STOPLIST") {
+                self.base.advance();
+                self.skip_optional_equals();
+                stoplist = self.base.parse_identifier().map(|name| {
+                    if name.eq_ignore_ascii_case("OFF") || name.eq_ignore_ascii_case("SYSTEM") {
+                        name.to_uppercase()
+                    } else {
+                        name
                     }
-                }
-                return Some("OFF".to_string());
+                });
+                self.base.skip_whitespace();
+            } else {
+                // SEARCH PROPERTY LIST and other options we don't track end the clause
+                break;
             }
         }
 
-        // Handle STOPLIST or other WITH options we don't specifically track
-        // Just skip to end
-        None
+        (change_tracking, stoplist)
+    }
+
+    /// Skip whitespace and an optional `=` before an option value
+    fn skip_optional_equals(&mut self) {
+        self.base.skip_whitespace();
+        if self.base.check_token(&Token::Eq) {
+            self.base.advance();
+            self.base.skip_whitespace();
+        }
     }
 }
 
@@ -466,6 +498,23 @@ mod tests {
         assert_eq!(result.change_tracking, Some("OFF".to_string()));
     }
 
+    #[test]
+    fn test_fulltext_index_with_option_list() {
+        let sql = r#"CREATE FULLTEXT INDEX ON [dbo].[Documents] ([Content] TYPE COLUMN [Extension] LANGUAGE 1033)
+                     KEY INDEX [PK_Documents] WITH (CHANGE_TRACKING = MANUAL, STOPLIST = [DocStopList]);"#;
+        let result = parse_fulltext_index_tokens(sql).unwrap();
+        assert_eq!(result.columns[0].type_column.as_deref(), Some("Extension"));
+        assert_eq!(result.columns[0].language_id, Some(1033));
+        assert_eq!(result.change_tracking.as_deref(), Some("MANUAL"));
+        assert_eq!(result.stoplist.as_deref(), Some("DocStopList"));
+
+        let sql = r#"CREATE FULLTEXT INDEX ON [dbo].[Documents] ([Content])
+                     KEY INDEX [PK_Documents] WITH STOPLIST off"#;
+        let result = parse_fulltext_index_tokens(sql).unwrap();
+        assert!(result.change_tracking.is_none());
+        assert_eq!(result.stoplist.as_deref(), Some("OFF"));
+    }
+
     #[test]
     fn test_fulltext_index_complete() {
         let sql = r#"CREATE FULLTEXT INDEX ON [dbo].[Documents] (
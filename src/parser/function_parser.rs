@@ -311,42 +311,7 @@ impl FunctionTokenParser {
 
     /// Parse default value (everything up to , or ))
     fn parse_default_value(&mut self) -> String {
-        let mut result = String::new();
-        let mut depth = 0;
-
-        while !self.base.is_at_end() {
-            if let Some(token) = self.base.current_token() {
-                match &token.token {
-                    Token::LParen => {
-                        depth += 1;
-                        result.push('(');
-                        self.base.advance();
-                    }
-                    Token::RParen => {
-                        if depth == 0 {
-                            break; // End of parameters
-                        }
-                        depth -= 1;
-                        result.push(')');
-                        self.base.advance();
-                    }
-                    Token::Comma if depth == 0 => {
-                        break; // Next parameter
-                    }
-                    Token::Whitespace(_) => {
-                        self.base.advance();
-                    }
-                    _ => {
-                        result.push_str(&TokenParser::token_to_string(&token.token));
-                        self.base.advance();
-                    }
-                }
-            } else {
-                break;
-            }
-        }
-
-        result.trim().to_string()
+        self.base.parse_parameter_default(&[])
     }
 
     /// Parse RETURNS clause and determine function type
@@ -0,0 +1,167 @@
+//! Header comments: the comment block a batch starts with
+//!
+//! Object files often open with an author/description block:
+//!
+//! ```sql
+//! /*
+//!  * Author:      J. Smith
+//!  * Description: Open orders for a customer
+//!  */
+//! CREATE PROCEDURE [dbo].[GetOpenOrders] ...
+//! ```
+//!
+//! The header is the text of the `--` and `/* */` comments before the first token, with
+//! comment markers, leading `*`s and separator lines removed. SSMS "Object: ... Script
+//! Date: ..." banners are skipped.
+
+/// The header comment of a batch, if it starts with one
+pub fn extract_header_comment(sql: &str) -> Option<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut rest = sql.trim_start_matches('\u{feff}');
+    loop {
+        rest = rest.trim_start();
+        if let Some(comment) = rest.strip_prefix("--") {
+            let end = comment.find('\n').unwrap_or(comment.len());
+            lines.push(comment[..end].trim_start_matches('-').to_string());
+            rest = &comment[end..];
+        } else if rest.starts_with("/*") {
+            let end = block_comment_end(rest);
+            let body = rest[2..end].trim_end_matches("*/");
+            lines.extend(body.lines().map(|line| {
+                line.trim_start()
+                    .trim_start_matches('*')
+                    .trim_end_matches('*')
+                    .to_string()
+            }));
+            rest = &rest[end..];
+        } else {
+            break;
+        }
+    }
+
+    let mut header = String::new();
+    let mut blank = false;
+    for line in &lines {
+        let line = line.trim();
+        // Separator lines such as `-----` or `=====` count as blank
+        if !line.chars().any(char::is_alphanumeric) || is_ssms_banner(line) {
+            blank = !header.is_empty();
+            continue;
+        }
+        if blank {
+            header.push('\n');
+            blank = false;
+        }
+        if !header.is_empty() {
+            header.push('\n');
+        }
+        header.push_str(line);
+    }
+    (!header.is_empty()).then_some(header)
+}
+
+/// The description a header gives: the text of its `Description:` field (up to the next
+/// field or blank line), or the whole header when it has none
+pub fn header_description(header: &str) -> String {
+    let mut lines = header.lines();
+    let Some(first) = lines.by_ref().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.trim()
+            .eq_ignore_ascii_case("description")
+            .then(|| value.trim())
+    }) else {
+        return header.to_string();
+    };
+
+    let mut description = first.to_string();
+    for line in lines.take_while(|line| !line.trim().is_empty() && field_name(line).is_none()) {
+        if !description.is_empty() {
+            description.push(' ');
+        }
+        description.push_str(line.trim());
+    }
+    description
+}
+
+/// The field a header line starts, e.g. `Author` for `Author: J. Smith`
+fn field_name(line: &str) -> Option<&str> {
+    let (key, _) = line.split_once(':')?;
+    let key = key.trim();
+    let is_field = !key.is_empty()
+        && key.len() <= 30
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == ' ' || c == '_');
+    is_field.then_some(key)
+}
+
+/// `Object:  StoredProcedure [dbo].[GetOrders]    Script Date: 1/2/2024 10:00:00 AM`
+fn is_ssms_banner(line: &str) -> bool {
+    line.starts_with("Object:") && line.contains("Script Date:")
+}
+
+/// Byte index past the block comment `text` starts with; block comments nest in T-SQL
+fn block_comment_end(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let mut depth = 0usize;
+    let mut i = 0;
+    while i + 1 < bytes.len() {
+        match (bytes[i], bytes[i + 1]) {
+            (b'/', b'*') => {
+                depth += 1;
+                i += 2;
+            }
+            (b'*', b'/') => {
+                depth -= 1;
+                i += 2;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_header_comment() {
+        let sql = "/*\n * Author:      J. Smith\n * Description: Open orders\n *              for a customer\n *\n * History: 2024-01-02 created\n */\n-- ---------------\n-- Reviewed by DBA\nCREATE PROCEDURE [dbo].[GetOpenOrders] AS SELECT 1 -- not a header";
+        assert_eq!(
+            extract_header_comment(sql).as_deref(),
+            Some(
+                "Author:      J. Smith\nDescription: Open orders\nfor a customer\n\n\
+                 History: 2024-01-02 created\n\nReviewed by DBA"
+            )
+        );
+
+        let banner = "/****** Object:  StoredProcedure [dbo].[P]    Script Date: 1/2/2024 10:00:00 AM ******/\nCREATE PROCEDURE [dbo].[P] AS SELECT 1";
+        assert_eq!(extract_header_comment(banner), None);
+        assert_eq!(
+            extract_header_comment("/* outer /* nested */ still outer */ SELECT 1").as_deref(),
+            Some("outer /* nested */ still outer")
+        );
+        assert_eq!(
+            extract_header_comment("CREATE TABLE [dbo].[T] ([Id] INT) -- trailing"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_header_description() {
+        assert_eq!(
+            header_description(
+                "Author:      J. Smith\nDescription: Open orders\nfor a customer\nHistory: created"
+            ),
+            "Open orders for a customer"
+        );
+        assert_eq!(
+            header_description("Lists open orders.\nKeep in sync with the API."),
+            "Lists open orders.\nKeep in sync with the API."
+        );
+    }
+}
@@ -64,10 +64,87 @@ pub fn ensure_bracketed(ident: &str) -> String {
     if trimmed.starts_with('[') && trimmed.ends_with(']') {
         trimmed.to_string()
     } else {
-        format!("[{}]", normalize_identifier(trimmed))
+        quote_identifier(&normalize_identifier(trimmed))
     }
 }
 
+/// Escapes `]` as `]]` so an identifier can be written inside brackets.
+///
+/// Identifiers without `]` are borrowed unchanged.
+///
+/// # Examples
+///
+/// ```ignore
+/// assert_eq!(escape_identifier("MyTable"), "MyTable");
+/// assert_eq!(escape_identifier("Weird]Name"), "Weird]]Name");
+/// ```
+pub fn escape_identifier(ident: &str) -> Cow<'_, str> {
+    if ident.contains(']') {
+        Cow::Owned(ident.replace(']', "]]"))
+    } else {
+        Cow::Borrowed(ident)
+    }
+}
+
+/// Wraps a bare identifier in brackets, escaping any `]` it contains.
+///
+/// # Examples
+///
+/// ```ignore
+/// assert_eq!(quote_identifier("My Table"), "[My Table]");
+/// assert_eq!(quote_identifier("Weird]Name"), "[Weird]]Name]");
+/// ```
+pub fn quote_identifier(ident: &str) -> String {
+    format!("[{}]", escape_identifier(ident))
+}
+
+/// Splits a multi-part name into its bare parts using the tokenizer.
+///
+/// Brackets and double quotes are removed and `]]` is unescaped, so dots and
+/// brackets inside quoted parts are kept. Returns `None` unless the name is one or
+/// more identifiers separated by dots.
+///
+/// # Examples
+///
+/// ```ignore
+/// assert_eq!(split_name_parts("[dbo].[A.B]"), Some(vec!["dbo".into(), "A.B".into()]));
+/// assert_eq!(split_name_parts("[Weird]]Name]"), Some(vec!["Weird]Name".into()]));
+/// assert_eq!(split_name_parts("dbo.fn(1)"), None);
+/// ```
+pub fn split_name_parts(name: &str) -> Option<Vec<String>> {
+    let dialect = MsSqlDialect {};
+    let tokens = Tokenizer::new(&dialect, name).tokenize().ok()?;
+    let mut parts = Vec::new();
+    let mut expect_word = true;
+    for token in tokens {
+        match token {
+            Token::Whitespace(_) => {}
+            Token::Word(w) if expect_word => {
+                parts.push(w.value);
+                expect_word = false;
+            }
+            Token::Period if !expect_word => expect_word = true,
+            _ => return None,
+        }
+    }
+    (!parts.is_empty() && !expect_word).then_some(parts)
+}
+
+/// Joins bare name parts into a bracketed multi-part name.
+///
+/// # Examples
+///
+/// ```ignore
+/// assert_eq!(quote_name_parts(&["dbo", "A.B"]), "[dbo].[A.B]");
+/// ```
+pub fn quote_name_parts<S: AsRef<str>>(parts: &[S]) -> String {
+    parts
+        .iter()
+        .map(|part| quote_identifier(part.as_ref()))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
 /// Converts a sqlparser-rs Word token to a properly quoted string.
 ///
 /// This function examines the `quote_style` of the Word token and formats
@@ -86,8 +163,8 @@ pub fn ensure_bracketed(ident: &str) -> String {
 /// ```
 pub fn format_word(word: &sqlparser::tokenizer::Word) -> String {
     match word.quote_style {
-        Some('[') => format!("[{}]", word.value),
-        Some('"') => format!("\"{}\"", word.value),
+        Some('[') => quote_identifier(&word.value),
+        Some('"') => format!("\"{}\"", word.value.replace('"', "\"\"")),
         _ => word.value.clone(),
     }
 }
@@ -107,7 +184,7 @@ pub fn format_word(word: &sqlparser::tokenizer::Word) -> String {
 /// ```
 pub fn format_word_bracketed(word: &sqlparser::tokenizer::Word) -> String {
     if word.quote_style.is_some() {
-        format!("[{}]", word.value)
+        quote_identifier(&word.value)
     } else {
         word.value.clone()
     }
@@ -141,16 +218,16 @@ pub fn extract_word_value(word: &Word) -> &str {
 ///
 /// - `Word` - identifiers with quote style preserved
 /// - `Number` - numeric literals
-/// - `SingleQuotedString` - 'string' literals
-/// - `NationalStringLiteral` - N'string' literals
+/// - `SingleQuotedString` - 'string' literals, with embedded quotes doubled
+/// - `NationalStringLiteral` - N'string' literals, with embedded quotes doubled
 /// - Punctuation tokens (parens, comma, operators, etc.)
 /// - Other tokens fall back to their debug representation
 pub fn format_token(token: &Token) -> String {
     match token {
         Token::Word(w) => format_word(w),
         Token::Number(n, _) => n.clone(),
-        Token::SingleQuotedString(s) => format!("'{}'", s),
-        Token::NationalStringLiteral(s) => format!("N'{}'", s),
+        Token::SingleQuotedString(s) => format!("'{}'", s.replace('\'', "''")),
+        Token::NationalStringLiteral(s) => format!("N'{}'", s.replace('\'', "''")),
         Token::LParen => "(".to_string(),
         Token::RParen => ")".to_string(),
         Token::Comma => ",".to_string(),
@@ -340,7 +417,7 @@ pub fn normalize_object_name(name: &str, default_schema: &str) -> String {
     // Use tokenized parsing to extract schema and name parts
     // The tokenizer automatically handles brackets, quotes, and whitespace
     let (schema, obj_name) = split_qualified_name(trimmed, default_schema);
-    format!("[{}].[{}]", schema, obj_name)
+    quote_name_parts(&[schema, obj_name])
 }
 
 /// Checks if a string is a bracketed identifier (starts with `[` and ends with `]`).
@@ -383,6 +460,9 @@ pub fn is_double_quoted(ident: &str) -> bool {
 /// ```
 pub fn is_qualified_name(name: &str) -> bool {
     let trimmed = name.trim();
+    if let Some(parts) = split_name_parts(trimmed) {
+        return parts.len() > 1;
+    }
     // Check for [schema].[name] pattern
     if trimmed.contains("].[") {
         return true;
@@ -425,8 +505,11 @@ pub fn split_qualified_name<'a>(name: &'a str, default_schema: &'a str) -> (Stri
         return (schema, obj_name);
     }
 
-    // Fallback: single identifier without schema
-    (default_schema.to_string(), normalize_identifier(trimmed))
+    // Single identifier without schema
+    match split_name_parts(trimmed).as_deref() {
+        Some([single]) => (default_schema.to_string(), single.clone()),
+        _ => (default_schema.to_string(), normalize_identifier(trimmed)),
+    }
 }
 
 /// Internal tokenized implementation of split_qualified_name.
@@ -555,6 +638,10 @@ mod tests {
             format_token(&Token::NationalStringLiteral("unicode".to_string())),
             "N'unicode'"
         );
+        assert_eq!(
+            format_token(&Token::NationalStringLiteral("O'Brien".to_string())),
+            "N'O''Brien'"
+        );
     }
 
     #[test]
@@ -621,6 +708,61 @@ mod tests {
         assert!(!is_qualified_name("[MyTable]"));
     }
 
+    #[test]
+    fn test_is_qualified_name_escaped() {
+        assert!(!is_qualified_name("[A.B]"));
+        assert!(!is_qualified_name("[Weird]].Name]"));
+        assert!(is_qualified_name("[dbo].[A.B]"));
+    }
+
+    #[test]
+    fn test_escape_identifier() {
+        assert!(matches!(
+            escape_identifier("MyTable"),
+            Cow::Borrowed("MyTable")
+        ));
+        assert_eq!(escape_identifier("Weird]Name"), "Weird]]Name");
+        assert_eq!(quote_identifier("Weird]Name"), "[Weird]]Name]");
+        assert_eq!(quote_identifier("A.B"), "[A.B]");
+        assert_eq!(ensure_bracketed("Weird]Name"), "[Weird]]Name]");
+    }
+
+    #[test]
+    fn test_split_name_parts() {
+        assert_eq!(split_name_parts("[dbo].[A.B]").unwrap(), vec!["dbo", "A.B"]);
+        assert_eq!(
+            split_name_parts("[dbo].[Weird]]Name].[Col]").unwrap(),
+            vec!["dbo", "Weird]Name", "Col"]
+        );
+        assert_eq!(
+            split_name_parts("dbo . \"My Table\"").unwrap(),
+            vec!["dbo", "My Table"]
+        );
+        assert_eq!(
+            split_name_parts("[Weird]].Name]").unwrap(),
+            vec!["Weird].Name"]
+        );
+        assert_eq!(split_name_parts("dbo.fn(1)"), None);
+        assert_eq!(split_name_parts("dbo."), None);
+        assert_eq!(split_name_parts(""), None);
+    }
+
+    #[test]
+    fn test_escaped_names_round_trip() {
+        for name in ["[dbo].[Weird]]Name]", "[dbo].[A.B]", "[a]]]].[]]b]", "[x]"] {
+            let parts = split_name_parts(name).unwrap();
+            assert_eq!(quote_name_parts(&parts), name);
+        }
+        let (schema, name) = split_qualified_name("[dbo].[Weird]]Name]", "x");
+        assert_eq!((schema.as_str(), name.as_str()), ("dbo", "Weird]Name"));
+        let (schema, name) = split_qualified_name("[A.B]", "dbo");
+        assert_eq!((schema.as_str(), name.as_str()), ("dbo", "A.B"));
+        assert_eq!(
+            normalize_object_name("[Weird]]Name]", "dbo"),
+            "[dbo].[Weird]]Name]"
+        );
+    }
+
     #[test]
     fn test_split_qualified_name_fully_bracketed() {
         let (schema, name) = split_qualified_name("[dbo].[MyTable]", "default");
@@ -0,0 +1,167 @@
+//! Detection of imperative statements that do not belong in a database project
+//!
+//! A database project declares the desired state of the database; deployment works out
+//! how to get there. Statements that change the database in place have no model
+//! representation and are rejected with a suggestion for the declarative alternative:
+//!
+//! ```sql
+//! EXEC sp_rename 'dbo.Orders.Total', 'Amount', 'COLUMN';  -- use the refactor log
+//! ALTER DATABASE CURRENT SET RECOVERY SIMPLE;             -- use project properties
+//! ```
+//!
+//! Module bodies (procedures, functions, triggers) may contain these statements, since
+//! they run when the module is executed rather than at build time. `ALTER DATABASE ...
+//! ADD FILEGROUP` is modeled, and the `ADD FILE` / `ADD LOG FILE` statements of storage
+//! scripts and `ALTER DATABASE SCOPED CONFIGURATION` are skipped, so none of them is
+//! reported.
+
+use super::lexer::Token;
+use super::token_parser_base::TokenParser;
+
+/// An imperative statement found in a batch
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImperativeStatement {
+    /// Statement kind as written in diagnostics (e.g. `sp_rename`, `ALTER DATABASE`)
+    pub statement: &'static str,
+    /// 1-based line of the statement within the batch
+    pub line: usize,
+    /// Declarative alternative to recommend
+    pub suggestion: &'static str,
+}
+
+const SP_RENAME_SUGGESTION: &str = "declare the object under its new name and record the rename in the project's refactor log (.refactorlog), or move the call to a pre/post-deployment script";
+
+const ALTER_DATABASE_SUGGESTION: &str = "set database options as project properties (e.g. <Recovery>, <AllowSnapshotIsolation>) or move the statement to a pre/post-deployment script";
+
+/// Find the first top-level `sp_rename` call or `ALTER DATABASE` statement in `sql`.
+///
+/// Returns `None` for CREATE/ALTER PROCEDURE, FUNCTION and TRIGGER batches.
+pub fn find_imperative_statement(sql: &str) -> Option<ImperativeStatement> {
+    let parser = TokenParser::new(sql)?;
+    let tokens: Vec<_> = parser
+        .tokens()
+        .iter()
+        .filter(|t| !matches!(t.token, Token::Whitespace(_)))
+        .collect();
+    let is_word = |i: usize, word: &str| {
+        matches!(tokens.get(i).map(|t| &t.token), Some(Token::Word(w))
+            if w.quote_style.is_none() && w.value.eq_ignore_ascii_case(word))
+    };
+    // `sp_rename`, `sys.sp_rename` or `[sys].[sp_rename]`
+    let is_sp_rename = |i: usize| {
+        let name = match tokens.get(i).map(|t| &t.token) {
+            Some(Token::Word(w)) => w.value.as_str(),
+            _ => return None,
+        };
+        if name.eq_ignore_ascii_case("sp_rename") {
+            return Some(i);
+        }
+        let qualified = name.eq_ignore_ascii_case("sys")
+            && matches!(tokens.get(i + 1).map(|t| &t.token), Some(Token::Period))
+            && matches!(tokens.get(i + 2).map(|t| &t.token), Some(Token::Word(w))
+                if w.value.eq_ignore_ascii_case("sp_rename"));
+        qualified.then_some(i)
+    };
+
+    // Module definitions: everything after the header is the module body
+    let mut start = 0;
+    if is_word(0, "CREATE") && is_word(1, "OR") && is_word(2, "ALTER") {
+        start = 3;
+    } else if is_word(0, "CREATE") || is_word(0, "ALTER") {
+        start = 1;
+    }
+    if start > 0
+        && ["PROC", "PROCEDURE", "FUNCTION", "TRIGGER"]
+            .iter()
+            .any(|kind| is_word(start, kind))
+    {
+        return None;
+    }
+
+    let line_of = |i: usize| tokens[i].span.start.line as usize;
+    // A batch may start with a bare procedure call, without EXEC
+    if let Some(i) = is_sp_rename(0) {
+        return Some(ImperativeStatement {
+            statement: "sp_rename",
+            line: line_of(i),
+            suggestion: SP_RENAME_SUGGESTION,
+        });
+    }
+    for i in 0..tokens.len() {
+        if is_word(i, "EXEC") || is_word(i, "EXECUTE") {
+            if let Some(i) = is_sp_rename(i + 1) {
+                return Some(ImperativeStatement {
+                    statement: "sp_rename",
+                    line: line_of(i),
+                    suggestion: SP_RENAME_SUGGESTION,
+                });
+            }
+        }
+        // ALTER DATABASE <name> ADD FILEGROUP | FILE | LOG FILE, and
+        // ALTER DATABASE SCOPED CONFIGURATION
+        let is_storage = is_word(i + 3, "ADD")
+            && (is_word(i + 4, "FILEGROUP")
+                || is_word(i + 4, "FILE")
+                || (is_word(i + 4, "LOG") && is_word(i + 5, "FILE")));
+        let is_handled = is_word(i + 2, "SCOPED") || is_storage;
+        if is_word(i, "ALTER") && is_word(i + 1, "DATABASE") && !is_handled {
+            return Some(ImperativeStatement {
+                statement: "ALTER DATABASE",
+                line: line_of(i),
+                suggestion: ALTER_DATABASE_SUGGESTION,
+            });
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_sp_rename() {
+        let sql = "CREATE TABLE [dbo].[T] ([Id] INT);\nEXEC sp_rename 'dbo.T.Id', 'Key', 'COLUMN';";
+        let found = find_imperative_statement(sql).unwrap();
+        assert_eq!(found.statement, "sp_rename");
+        assert_eq!(found.line, 2);
+
+        assert!(find_imperative_statement("sys.sp_rename 'dbo.T', 'U'").is_some());
+        assert!(find_imperative_statement("EXECUTE [sys].[sp_rename] 'dbo.T', 'U'").is_some());
+        assert!(find_imperative_statement("EXEC [dbo].[sp_renamed] 'x'").is_none());
+        assert!(find_imperative_statement("SELECT 'EXEC sp_rename' AS [Text]").is_none());
+    }
+
+    #[test]
+    fn test_find_alter_database() {
+        let found =
+            find_imperative_statement("ALTER DATABASE CURRENT SET RECOVERY SIMPLE").unwrap();
+        assert_eq!(found.statement, "ALTER DATABASE");
+        assert_eq!(found.line, 1);
+
+        let sql = "ALTER DATABASE [$(DatabaseName)] SET ALLOW_SNAPSHOT_ISOLATION ON";
+        assert!(find_imperative_statement(sql).is_some());
+
+        let sql = "ALTER DATABASE [$(DatabaseName)] ADD FILEGROUP [USERDATA]";
+        assert!(find_imperative_statement(sql).is_none());
+        let sql = "ALTER DATABASE [$(DatabaseName)]\n    ADD FILE (NAME = [UserData], FILENAME = '$(DefaultDataPath)$(DefaultFilePrefix)_UserData.ndf') TO FILEGROUP [USERDATA]";
+        assert!(find_imperative_statement(sql).is_none());
+        let sql =
+            "ALTER DATABASE [$(DatabaseName)] ADD LOG FILE (NAME = [Log2], FILENAME = 'log2.ldf')";
+        assert!(find_imperative_statement(sql).is_none());
+        let sql = "ALTER DATABASE SCOPED CONFIGURATION SET MAXDOP = 4";
+        assert!(find_imperative_statement(sql).is_none());
+        assert!(find_imperative_statement("ALTER TABLE [dbo].[T] ADD [X] INT").is_none());
+    }
+
+    #[test]
+    fn test_module_bodies_are_not_checked() {
+        let sql = "CREATE PROCEDURE [dbo].[Rename] AS EXEC sp_rename 'dbo.T', 'U'";
+        assert!(find_imperative_statement(sql).is_none());
+        let sql = "CREATE OR ALTER PROC [dbo].[P] AS ALTER DATABASE CURRENT SET RECOVERY FULL";
+        assert!(find_imperative_statement(sql).is_none());
+        let sql = "ALTER TRIGGER [dbo].[TR] ON [dbo].[T] AFTER INSERT AS EXEC sp_rename 'a', 'b'";
+        assert!(find_imperative_statement(sql).is_none());
+    }
+}
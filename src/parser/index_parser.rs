@@ -12,14 +12,20 @@
 //! CREATE NONCLUSTERED INDEX [IX_Name] ON [dbo].[Table] ([Col]) WHERE [Status] = 'Active'
 //! CREATE NONCLUSTERED INDEX [IX_Name] ON [dbo].[Table] ([Col]) WITH (FILLFACTOR = 80)
 //! ```
+//!
+//! Inline index declarations inside CREATE TABLE (SQL Server 2014+) are also supported:
+//!
+//! ```sql
+//! INDEX [IX_Name] [UNIQUE] [CLUSTERED | NONCLUSTERED] ([Col1], [Col2] DESC) [INCLUDE (...)]
+//! [Col] INT NOT NULL INDEX [IX_Name] NONCLUSTERED
+//! ```
 
-use crate::parser::identifier_utils::format_word_bracketed;
 use crate::util::find_ci;
 use sqlparser::dialect::MsSqlDialect;
 use sqlparser::keywords::Keyword;
 use sqlparser::tokenizer::{Token, TokenWithSpan, Tokenizer};
 
-use super::token_parser_base::TokenParser;
+use super::token_parser_base::{location_to_offset, TokenParser};
 
 /// A column in a parsed index with sort direction
 #[derive(Debug, Clone, Default)]
@@ -69,6 +75,8 @@ pub struct TokenParsedIndex {
     pub filter_predicate: Option<String>,
     /// Data compression type (NONE, ROW, PAGE, etc.)
     pub data_compression: Option<String>,
+    /// Per-partition data compression (`DATA_COMPRESSION = PAGE ON PARTITIONS (1 TO 3)`)
+    pub partition_compression: Vec<(u32, String)>,
     /// Whether PAD_INDEX is ON (applies fill factor to intermediate pages)
     pub is_padded: bool,
 }
@@ -172,6 +180,85 @@ impl IndexTokenParser {
             fill_factor: None,
             filter_predicate: None,
             data_compression: None,
+            partition_compression: Vec::new(),
+            is_padded: false,
+        };
+
+        // Parse optional clauses: INCLUDE, WHERE, WITH
+        self.parse_index_options(&mut result);
+
+        Some(result)
+    }
+
+    /// Parse an inline index declaration from a CREATE TABLE body:
+    /// `INDEX name [UNIQUE] [CLUSTERED | NONCLUSTERED] (columns) [INCLUDE ...] [WHERE ...] [WITH ...]`.
+    ///
+    /// Column-level declarations have no column list; `default_column` supplies the column
+    /// they are declared on. Inline columnstore indexes are not handled here.
+    pub fn parse_inline_index(
+        &mut self,
+        table_schema: &str,
+        table_name: &str,
+        default_column: Option<&str>,
+    ) -> Option<TokenParsedIndex> {
+        self.base.skip_whitespace();
+
+        // Expect INDEX keyword
+        if !self.base.check_keyword(Keyword::INDEX) {
+            return None;
+        }
+        self.base.advance();
+        self.base.skip_whitespace();
+
+        // Parse index name
+        let name = self.base.parse_identifier()?;
+        self.base.skip_whitespace();
+
+        let mut is_unique = false;
+        let mut is_clustered = false;
+
+        // Parse optional UNIQUE
+        if self.base.check_keyword(Keyword::UNIQUE) {
+            is_unique = true;
+            self.base.advance();
+            self.base.skip_whitespace();
+        }
+
+        // Parse optional CLUSTERED or NONCLUSTERED (inline indexes default to NONCLUSTERED)
+        if self.base.check_word_ci("CLUSTERED") {
+            is_clustered = true;
+            self.base.advance();
+            self.base.skip_whitespace();
+        } else if self.base.check_word_ci("NONCLUSTERED") {
+            self.base.advance();
+            self.base.skip_whitespace();
+        }
+
+        if self.base.check_word_ci("COLUMNSTORE") {
+            return None;
+        }
+
+        // Parse column list, or use the column the index is declared on
+        let columns = if self.base.check_token(&Token::LParen) {
+            let columns = self.parse_column_list()?;
+            self.base.skip_whitespace();
+            columns
+        } else {
+            vec![ParsedIndexColumn::new(default_column?.to_string())]
+        };
+
+        let mut result = TokenParsedIndex {
+            name,
+            table_schema: table_schema.to_string(),
+            table_name: table_name.to_string(),
+            columns,
+            include_columns: Vec::new(),
+            is_unique,
+            is_clustered,
+            fill_factor: None,
+            filter_predicate: None,
+            data_compression: None,
+            partition_compression: Vec::new(),
             is_padded: false,
         };
 
@@ -266,7 +353,13 @@ impl IndexTokenParser {
                     self.base.advance();
                     self.base.skip_whitespace();
                     if let Some(compression) = self.base.parse_identifier() {
-                        result.data_compression = Some(compression.to_uppercase());
+                        let compression = compression.to_uppercase();
+                        match self.base.parse_on_partitions() {
+                            Some(partitions) => result
+                                .partition_compression
+                                .extend(partitions.into_iter().map(|p| (p, compression.clone()))),
+                            None => result.data_compression = Some(compression),
+                        }
                     }
                 }
                 self.skip_to_comma_or_paren();
@@ -338,8 +431,8 @@ impl IndexTokenParser {
             return None;
         }
 
-        // Reconstruct the predicate from tokens
-        let predicate = self.tokens_to_string(start_pos, end_pos);
+        // Take the predicate verbatim from the source
+        let predicate = self.base.tokens_to_string(start_pos, end_pos);
         let predicate = predicate.trim().to_string();
 
         if predicate.is_empty() {
@@ -349,44 +442,6 @@ impl IndexTokenParser {
         }
     }
 
-    /// Convert a range of tokens back to a string
-    fn tokens_to_string(&self, start: usize, end: usize) -> String {
-        let tokens = self.base.tokens();
-        let mut result = String::new();
-
-        for token in tokens.iter().take(end.min(tokens.len())).skip(start) {
-            match &token.token {
-                Token::Word(w) => {
-                    result.push_str(&format_word_bracketed(w));
-                }
-                Token::Number(n, _) => result.push_str(n),
-                Token::SingleQuotedString(s) => result.push_str(&format!("'{}'", s)),
-                Token::DoubleQuotedString(s) => result.push_str(&format!("\"{}\"", s)),
-                Token::Whitespace(_) => result.push(' '),
-                Token::Eq => result.push('='),
-                Token::Neq => result.push_str("<>"),
-                Token::Lt => result.push('<'),
-                Token::Gt => result.push('>'),
-                Token::LtEq => result.push_str("<="),
-                Token::GtEq => result.push_str(">="),
-                Token::LParen => result.push('('),
-                Token::RParen => result.push(')'),
-                Token::Comma => result.push(','),
-                Token::Period => result.push('.'),
-                Token::Plus => result.push('+'),
-                Token::Minus => result.push('-'),
-                Token::Mul => result.push('*'),
-                Token::Div => result.push('/'),
-                _ => {
-                    // For other tokens, use the Display impl
-                    result.push_str(&format!("{}", token.token));
-                }
-            }
-        }
-
-        result
-    }
-
     /// Parse CREATE CLUSTERED/NONCLUSTERED COLUMNSTORE INDEX and return columnstore index info
     pub fn parse_create_columnstore_index(&mut self) -> Option<TokenParsedColumnstoreIndex> {
         self.base.skip_whitespace();
@@ -453,6 +508,7 @@ impl IndexTokenParser {
         // Parse optional WHERE and WITH clauses
         let mut filter_predicate = None;
         let mut data_compression = None;
+        let mut partition_compression = Vec::new();
 
         while !self.base.is_at_end() {
             self.base.skip_whitespace();
@@ -487,7 +543,15 @@ impl IndexTokenParser {
                                 self.base.advance();
                                 self.base.skip_whitespace();
                                 if let Some(compression) = self.base.parse_identifier() {
-                                    data_compression = Some(compression.to_uppercase());
+                                    let compression = compression.to_uppercase();
+                                    match self.base.parse_on_partitions() {
+                                        Some(partitions) => partition_compression.extend(
+                                            partitions
+                                                .into_iter()
+                                                .map(|p| (p, compression.clone())),
+                                        ),
+                                        None => data_compression = Some(compression),
+                                    }
                                 }
                             }
                             self.skip_to_comma_or_paren();
@@ -517,6 +581,7 @@ impl IndexTokenParser {
             is_clustered,
             columns,
             data_compression,
+            partition_compression,
             filter_predicate,
         })
     }
@@ -641,6 +706,20 @@ pub fn parse_create_index_tokens(sql: &str) -> Option<TokenParsedIndex> {
     parser.parse_create_index()
 }
 
+/// Parse an inline `INDEX ...` declaration from a CREATE TABLE body.
+///
+/// `default_column` is the column a column-level declaration (without a column list)
+/// is attached to.
+pub fn parse_inline_index_tokens(
+    sql: &str,
+    table_schema: &str,
+    table_name: &str,
+    default_column: Option<&str>,
+) -> Option<TokenParsedIndex> {
+    let mut parser = IndexTokenParser::new(sql)?;
+    parser.parse_inline_index(table_schema, table_name, default_column)
+}
+
 /// Result of parsing a columnstore index definition
 #[derive(Debug, Clone)]
 pub struct TokenParsedColumnstoreIndex {
@@ -656,6 +735,8 @@ pub struct TokenParsedColumnstoreIndex {
     pub columns: Vec<String>,
     /// Data compression type (COLUMNSTORE or COLUMNSTORE_ARCHIVE)
     pub data_compression: Option<String>,
+    /// Per-partition data compression (`ON PARTITIONS (...)`)
+    pub partition_compression: Vec<(u32, String)>,
     /// Filter predicate for filtered NONCLUSTERED columnstore indexes
     pub filter_predicate: Option<String>,
 }
@@ -782,10 +863,10 @@ pub fn extract_index_filter_predicate_tokenized(sql: &str) -> Option<String> {
                         return None;
                     }
 
-                    // Reconstruct predicate from tokens
-                    let predicate =
-                        tokens_to_predicate_string(&tokens[predicate_start..predicate_end]);
-                    let predicate = predicate.trim().to_string();
+                    // Take the predicate verbatim from the source
+                    let start = location_to_offset(sql, tokens[predicate_start].span.start);
+                    let end = location_to_offset(sql, tokens[predicate_end - 1].span.end);
+                    let predicate = sql[start..end].trim().to_string();
 
                     if predicate.is_empty() {
                         return None;
@@ -801,45 +882,6 @@ pub fn extract_index_filter_predicate_tokenized(sql: &str) -> Option<String> {
     None
 }
 
-/// Convert a slice of tokens back to a predicate string
-fn tokens_to_predicate_string(tokens: &[TokenWithSpan]) -> String {
-    use crate::parser::identifier_utils::format_word_bracketed;
-
-    let mut result = String::new();
-
-    for token in tokens {
-        match &token.token {
-            Token::Word(w) => {
-                result.push_str(&format_word_bracketed(w));
-            }
-            Token::Number(n, _) => result.push_str(n),
-            Token::SingleQuotedString(s) => result.push_str(&format!("'{}'", s)),
-            Token::DoubleQuotedString(s) => result.push_str(&format!("\"{}\"", s)),
-            Token::Whitespace(_) => result.push(' '),
-            Token::Eq => result.push('='),
-            Token::Neq => result.push_str("<>"),
-            Token::Lt => result.push('<'),
-            Token::Gt => result.push('>'),
-            Token::LtEq => result.push_str("<="),
-            Token::GtEq => result.push_str(">="),
-            Token::LParen => result.push('('),
-            Token::RParen => result.push(')'),
-            Token::Comma => result.push(','),
-            Token::Period => result.push('.'),
-            Token::Plus => result.push('+'),
-            Token::Minus => result.push('-'),
-            Token::Mul => result.push('*'),
-            Token::Div => result.push('/'),
-            _ => {
-                // For other tokens, use the Display impl
-                result.push_str(&format!("{}", token.token));
-            }
-        }
-    }
-
-    result
-}
-
 /// Extract PAD_INDEX setting from CREATE INDEX SQL statement.
 ///
 /// Returns true if PAD_INDEX = ON is found in the WITH clause.
@@ -882,6 +924,38 @@ mod tests {
     // Basic CREATE INDEX tests
     // ========================================================================
 
+    #[test]
+    fn test_inline_index_declaration() {
+        let result = parse_inline_index_tokens(
+            "INDEX [IX_Orders_Date] UNIQUE CLUSTERED ([OrderDate] DESC, [Id]) WITH (FILLFACTOR = 90)",
+            "dbo",
+            "Orders",
+            None,
+        )
+        .unwrap();
+        assert_eq!(result.name, "IX_Orders_Date");
+        assert_eq!(result.table_name, "Orders");
+        assert!(result.is_unique);
+        assert!(result.is_clustered);
+        assert_eq!(result.columns.len(), 2);
+        assert!(result.columns[0].is_descending);
+        assert_eq!(result.fill_factor, Some(90));
+
+        let result =
+            parse_inline_index_tokens("INDEX IX_Customer", "dbo", "Orders", Some("CustomerId"))
+                .unwrap();
+        assert!(!result.is_clustered);
+        assert_eq!(result.columns[0].name, "CustomerId");
+
+        assert!(parse_inline_index_tokens(
+            "INDEX [CCI_Orders] CLUSTERED COLUMNSTORE",
+            "dbo",
+            "Orders",
+            None
+        )
+        .is_none());
+    }
+
     #[test]
     fn test_create_clustered_index_basic() {
         let sql = "CREATE CLUSTERED INDEX [IX_Table_Col] ON [dbo].[MyTable] ([Col1])";
@@ -1054,6 +1128,22 @@ mod tests {
         assert_eq!(result.data_compression, Some("PAGE".to_string()));
     }
 
+    #[test]
+    fn test_index_with_partition_compression() {
+        let sql = "CREATE NONCLUSTERED INDEX [IX_P] ON [dbo].[Table] ([Col]) WITH (DATA_COMPRESSION = ROW ON PARTITIONS (1), DATA_COMPRESSION = PAGE ON PARTITIONS (2 TO 3), FILLFACTOR = 90)";
+        let result = parse_create_index_tokens(sql).unwrap();
+        assert_eq!(result.data_compression, None);
+        assert_eq!(
+            result.partition_compression,
+            vec![
+                (1, "ROW".to_string()),
+                (2, "PAGE".to_string()),
+                (3, "PAGE".to_string())
+            ]
+        );
+        assert_eq!(result.fill_factor, Some(90));
+    }
+
     #[test]
     fn test_index_with_other_options_ignored() {
         // PAD_INDEX, SORT_IN_TEMPDB, etc. should be parsed but ignored
@@ -1353,6 +1443,21 @@ WITH (FILLFACTOR = 90, DATA_COMPRESSION = PAGE)
         assert!(!result.contains(';'));
     }
 
+    #[test]
+    fn test_extract_filter_predicate_keeps_source_whitespace() {
+        let sql =
+            "CREATE INDEX [IX] ON [dbo].[T] ([Col]) WHERE [Name]   IS NOT NULL\n    AND [Id] > 1;";
+        let result = extract_index_filter_predicate_tokenized(sql).unwrap();
+        assert_eq!(result, "[Name]   IS NOT NULL\n    AND [Id] > 1");
+    }
+
+    #[test]
+    fn test_extract_filter_predicate_keeps_string_literals() {
+        let sql = "CREATE INDEX [IX] ON [dbo].[T] ([Col]) WHERE [Name] <> N'O''Brien' AND [Code] = 'x''y';";
+        let result = extract_index_filter_predicate_tokenized(sql).unwrap();
+        assert_eq!(result, "[Name] <> N'O''Brien' AND [Code] = 'x''y'");
+    }
+
     #[test]
     fn test_extract_filter_predicate_no_where() {
         let sql = "CREATE INDEX [IX] ON [dbo].[T] ([Col])";
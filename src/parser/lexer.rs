@@ -0,0 +1,89 @@
+//! Lexer facade: the one place that knows how sqlparser tokenizes T-SQL
+//!
+//! Token-level code (the model.xml writers, the lint rules) takes its token types and
+//! tokenizer from here rather than from sqlparser, so a sqlparser upgrade that changes
+//! the tokenizer's API touches this module only. [`dump_tokens`] shows how a file
+//! tokenizes, to spot behavior that changed between versions (`compile --parser-compat`).
+
+use std::fmt::Write as _;
+
+use sqlparser::dialect::MsSqlDialect;
+use sqlparser::tokenizer::Tokenizer;
+
+pub use sqlparser::keywords::Keyword;
+pub use sqlparser::tokenizer::{
+    Location, Span, Token, TokenWithSpan, TokenizerError, Whitespace, Word,
+};
+
+/// Tokens of `sql`, as the T-SQL dialect reads it
+pub fn tokenize(sql: &str) -> Result<Vec<Token>, TokenizerError> {
+    Tokenizer::new(&MsSqlDialect {}, sql).tokenize()
+}
+
+/// Tokens of `sql` with their line and column spans
+pub fn tokenize_with_location(sql: &str) -> Result<Vec<TokenWithSpan>, TokenizerError> {
+    Tokenizer::new(&MsSqlDialect {}, sql).tokenize_with_location()
+}
+
+/// The token stream of `sql`, one token per line as `line:column kind text`, leaving out
+/// spaces, tabs and newlines (comments are kept)
+pub fn dump_tokens(sql: &str) -> Result<String, TokenizerError> {
+    let mut dump = String::new();
+    for token in tokenize_with_location(sql)? {
+        let kind = match &token.token {
+            Token::Whitespace(Whitespace::Space | Whitespace::Tab | Whitespace::Newline) => {
+                continue
+            }
+            Token::Whitespace(_) => "Comment".to_string(),
+            Token::Word(word) => match (word.keyword, word.quote_style) {
+                (_, Some(quote)) => format!("Identifier({})", quote),
+                (Keyword::NoKeyword, None) => "Word".to_string(),
+                (keyword, None) => format!("Keyword({:?})", keyword),
+            },
+            Token::Number(..) => "Number".to_string(),
+            Token::SingleQuotedString(_) => "String".to_string(),
+            Token::NationalStringLiteral(_) => "NationalString".to_string(),
+            _ => "Symbol".to_string(),
+        };
+        let start = token.span.start;
+        let _ = writeln!(
+            dump,
+            "{}:{}\t{}\t{}",
+            start.line,
+            start.column,
+            kind,
+            token
+                .token
+                .to_string()
+                .replace('\r', "\\r")
+                .replace('\n', "\\n")
+                .replace('\t', "\\t")
+        );
+    }
+    Ok(dump)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dump_tokens() {
+        assert_eq!(
+            dump_tokens("SELECT [Name], N'x' -- who\nFROM dbo.T WHERE Id = @Id").unwrap(),
+            "1:1\tKeyword(SELECT)\tSELECT\n\
+             1:8\tIdentifier([)\t[Name]\n\
+             1:14\tSymbol\t,\n\
+             1:16\tNationalString\tN'x'\n\
+             1:21\tComment\t-- who\\n\n\
+             2:1\tKeyword(FROM)\tFROM\n\
+             2:6\tWord\tdbo\n\
+             2:9\tSymbol\t.\n\
+             2:10\tWord\tT\n\
+             2:12\tKeyword(WHERE)\tWHERE\n\
+             2:18\tKeyword(ID)\tId\n\
+             2:21\tSymbol\t=\n\
+             2:23\tWord\t@Id\n"
+        );
+    }
+}
@@ -1,18 +1,27 @@
 //! T-SQL parsing
 
+mod column_description_parser;
 mod column_parser;
 mod constraint_parser;
 mod extended_property_parser;
 mod fulltext_parser;
 mod function_parser;
+mod header_comment;
 pub mod ident_extract;
 pub mod identifier_utils;
+mod imperative_parser;
 pub mod index_parser;
+pub mod lexer;
+mod module_options_parser;
+mod permissions_file;
 mod preprocess_parser;
 mod procedure_parser;
+mod schema_parser;
 mod security_parser;
 mod sequence_parser;
+mod source_text;
 mod sqlcmd;
+mod statement_classifier;
 mod statement_parser;
 mod storage_parser;
 mod synonym_parser;
@@ -22,17 +31,31 @@ mod trigger_parser;
 mod tsql_dialect;
 mod tsql_parser;
 
+pub use column_description_parser::extract_column_descriptions;
+pub use constraint_parser::ConstraintIndexOptions;
 pub use function_parser::{extract_function_parameters_tokens, TokenParsedParameter};
+pub use header_comment::{extract_header_comment, header_description};
+pub use module_options_parser::{extract_module_options, ModuleOptions};
 pub use procedure_parser::{
     extract_procedure_parameters_tokens, parse_alter_procedure_full, parse_create_procedure_full,
     TokenParsedProcedure, TokenParsedProcedureParameter,
 };
-pub use sqlcmd::expand_includes;
+pub use source_text::{
+    decode_source, decode_source_with_encoding, encode_source, source_hash, FileEncoding,
+    LineEndings, SourceEncoding,
+};
+pub use sqlcmd::{
+    apply_conditionals, expand_includes, expand_includes_with_hashes, included_files, variable_key,
+};
+pub use statement_classifier::ParseFallback;
+pub use storage_parser::parse_table_compression_tokens;
 pub use tsql_dialect::ExtendedTsqlDialect;
 pub use tsql_parser::{
-    extract_extended_property_from_sql, parse_sql_file, parse_sql_files, ExtractedConstraintColumn,
-    ExtractedDefaultConstraint, ExtractedExtendedProperty, ExtractedFullTextColumn,
-    ExtractedFunctionParameter, ExtractedTableColumn, ExtractedTableConstraint,
-    ExtractedTableTypeColumn, ExtractedTableTypeConstraint, FallbackFunctionType,
-    FallbackStatementType, ParsedStatement, BINARY_MAX_SENTINEL,
+    extract_extended_property_from_sql, extract_table_scripts, parse_sql_content, parse_sql_file,
+    parse_sql_files, parse_sql_files_with_hashes, parse_sql_files_with_options,
+    parse_sql_files_with_variables, ExtractedConstraintColumn, ExtractedDefaultConstraint,
+    ExtractedExtendedProperty, ExtractedFullTextColumn, ExtractedFunctionParameter,
+    ExtractedTableColumn, ExtractedTableConstraint, ExtractedTableTypeColumn,
+    ExtractedTableTypeConstraint, FallbackFunctionType, FallbackStatementType, ParseOptions,
+    ParsedFiles, ParsedStatement, TableScripts, BINARY_MAX_SENTINEL,
 };
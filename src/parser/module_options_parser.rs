@@ -0,0 +1,223 @@
+//! Module option parsing for procedures, functions and triggers
+//!
+//! Extracts the `WITH` options from a module header and, for natively compiled
+//! modules, the options of the `BEGIN ATOMIC WITH (...)` block:
+//!
+//! ```sql
+//! CREATE FUNCTION [dbo].[F] (@Id INT) RETURNS INT
+//! WITH SCHEMABINDING, RETURNS NULL ON NULL INPUT, EXECUTE AS OWNER
+//! AS BEGIN RETURN @Id END
+//!
+//! CREATE PROCEDURE [dbo].[P] @Id INT
+//! WITH NATIVE_COMPILATION, SCHEMABINDING
+//! AS BEGIN ATOMIC WITH (TRANSACTION ISOLATION LEVEL = SNAPSHOT, LANGUAGE = N'us_english')
+//!     ...
+//! END
+//! ```
+
+use super::lexer::{Keyword, Token};
+use super::token_parser_base::TokenParser;
+
+/// Module options from a module header and its `BEGIN ATOMIC` block
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModuleOptions {
+    /// Header `WITH` options, uppercased (e.g. `NATIVE_COMPILATION`, `EXECUTE AS OWNER`)
+    pub options: Vec<String>,
+    /// `BEGIN ATOMIC WITH (...)` options as uppercased name/value pairs,
+    /// e.g. `("TRANSACTION ISOLATION LEVEL", "SNAPSHOT")`, `("LANGUAGE", "us_english")`
+    pub atomic_options: Vec<(String, String)>,
+}
+
+impl ModuleOptions {
+    /// Whether the header lists `option` (case-insensitive)
+    pub fn has_option(&self, option: &str) -> bool {
+        self.options.iter().any(|o| o.eq_ignore_ascii_case(option))
+    }
+
+    /// Principal of an `EXECUTE AS` option (`CALLER`, `OWNER`, `SELF` or a quoted user name)
+    pub fn execute_as(&self) -> Option<&str> {
+        self.options
+            .iter()
+            .find_map(|o| o.strip_prefix("EXECUTE AS "))
+            .map(str::trim)
+    }
+
+    /// Value of a `BEGIN ATOMIC` option (name matched case-insensitively)
+    pub fn atomic_option(&self, name: &str) -> Option<&str> {
+        self.atomic_options
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Extract header `WITH` options and `BEGIN ATOMIC WITH (...)` options from a
+/// procedure, function or trigger definition.
+pub fn extract_module_options(sql: &str) -> ModuleOptions {
+    let mut result = ModuleOptions::default();
+    let Some(parser) = TokenParser::new(sql) else {
+        return result;
+    };
+    let tokens: Vec<&Token> = parser
+        .tokens()
+        .iter()
+        .map(|t| &t.token)
+        .filter(|t| !matches!(t, Token::Whitespace(_)))
+        .collect();
+    let is_word = |i: usize, word: &str| {
+        matches!(tokens.get(i), Some(Token::Word(w))
+            if w.quote_style.is_none() && w.value.eq_ignore_ascii_case(word))
+    };
+
+    // Header: everything up to the AS that starts the body (or BEGIN/RETURN for functions
+    // written without AS). `@p AS INT` is a procedure parameter declaration, so an AS
+    // directly after a variable does not end the header. Trigger options are followed by
+    // the FOR/AFTER/INSTEAD OF event clause, which ends the option list.
+    let mut depth = 0usize;
+    let mut i = 0;
+    let mut in_options = false;
+    let mut current: Vec<String> = Vec::new();
+    while i < tokens.len() {
+        match tokens[i] {
+            Token::LParen => depth += 1,
+            Token::RParen => depth = depth.saturating_sub(1),
+            _ if depth > 0 => {}
+            Token::Word(w)
+                if w.quote_style.is_none()
+                    && w.keyword == Keyword::AS
+                    && !matches!(tokens.get(i.wrapping_sub(1)), Some(Token::Word(p)) if p.value.starts_with('@'))
+                    && !(in_options && is_word(i.wrapping_sub(1), "EXECUTE")) =>
+            {
+                break;
+            }
+            Token::Word(_) if is_word(i, "BEGIN") || is_word(i, "RETURN") => {
+                break;
+            }
+            Token::Word(w) if w.quote_style.is_none() && w.keyword == Keyword::WITH => {
+                in_options = true;
+            }
+            Token::Word(_)
+                if in_options
+                    && (is_word(i, "FOR") || is_word(i, "AFTER") || is_word(i, "INSTEAD")) =>
+            {
+                result.options.push(std::mem::take(&mut current).join(" "));
+                in_options = false;
+            }
+            Token::Comma if in_options => {
+                result.options.push(std::mem::take(&mut current).join(" "))
+            }
+            token if in_options => current.push(TokenParser::token_to_string(token).to_uppercase()),
+            _ => {}
+        }
+        i += 1;
+    }
+    result.options.push(current.join(" "));
+    result.options.retain(|option| !option.is_empty());
+
+    // Body: BEGIN ATOMIC WITH ( name = value, ... )
+    while i + 3 < tokens.len() {
+        if is_word(i, "BEGIN") && is_word(i + 1, "ATOMIC") && is_word(i + 2, "WITH") {
+            break;
+        }
+        i += 1;
+    }
+    if i + 3 >= tokens.len() || tokens[i + 3] != &Token::LParen {
+        return result;
+    }
+    let mut name: Vec<String> = Vec::new();
+    let mut value: Option<Vec<String>> = None;
+    for token in &tokens[i + 4..] {
+        match token {
+            Token::Comma | Token::RParen => {
+                if let Some(value) = value.take() {
+                    result
+                        .atomic_options
+                        .push((name.join(" ").to_uppercase(), value.join(" ")));
+                }
+                name.clear();
+                if *token == &Token::RParen {
+                    break;
+                }
+            }
+            Token::Eq => value = Some(Vec::new()),
+            Token::SingleQuotedString(s) | Token::NationalStringLiteral(s) => {
+                if let Some(value) = value.as_mut() {
+                    value.push(s.clone());
+                }
+            }
+            token => {
+                let text = TokenParser::token_to_string(token);
+                match value.as_mut() {
+                    Some(value) => value.push(text.to_uppercase()),
+                    None => name.push(text),
+                }
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_procedure_module_options() {
+        let sql = "CREATE PROCEDURE [dbo].[P] @Id AS INT, @Name NVARCHAR(50) = N'x'
+WITH NATIVE_COMPILATION, SCHEMABINDING, EXECUTE AS OWNER
+AS BEGIN ATOMIC WITH (TRANSACTION ISOLATION LEVEL = snapshot, LANGUAGE = N'us_english')
+    SELECT [Id] FROM [dbo].[T] WITH (SNAPSHOT);
+END";
+        let options = extract_module_options(sql);
+        assert_eq!(
+            options.options,
+            vec!["NATIVE_COMPILATION", "SCHEMABINDING", "EXECUTE AS OWNER"]
+        );
+        assert!(options.has_option("schemabinding"));
+        assert_eq!(options.execute_as(), Some("OWNER"));
+        assert_eq!(
+            options.atomic_option("TRANSACTION ISOLATION LEVEL"),
+            Some("SNAPSHOT")
+        );
+        assert_eq!(options.atomic_option("LANGUAGE"), Some("us_english"));
+
+        let options = extract_module_options("CREATE PROCEDURE [dbo].[P] AS SELECT 1");
+        assert_eq!(options, ModuleOptions::default());
+    }
+
+    #[test]
+    fn test_extract_function_module_options() {
+        let sql = "CREATE FUNCTION [dbo].[F] (@Id INT = 1) RETURNS INT
+WITH returns null on null input, SCHEMABINDING
+BEGIN
+    WITH [C] AS (SELECT 1 AS [X]) SELECT @Id = [X] FROM [C];
+    RETURN @Id;
+END";
+        let options = extract_module_options(sql);
+        assert_eq!(
+            options.options,
+            vec!["RETURNS NULL ON NULL INPUT", "SCHEMABINDING"]
+        );
+        assert_eq!(options.execute_as(), None);
+
+        let sql = "CREATE FUNCTION [dbo].[T] () RETURNS TABLE WITH SCHEMABINDING AS RETURN (SELECT 1 AS [X])";
+        assert_eq!(extract_module_options(sql).options, vec!["SCHEMABINDING"]);
+
+        let sql = "CREATE FUNCTION [dbo].[G] () RETURNS INT BEGIN WITH [C] AS (SELECT 1 AS [X]) SELECT 1; RETURN 1; END";
+        assert!(extract_module_options(sql).options.is_empty());
+    }
+
+    #[test]
+    fn test_extract_trigger_and_view_module_options() {
+        let sql = "CREATE TRIGGER [dbo].[TR] ON [dbo].[T] WITH NATIVE_COMPILATION, SCHEMABINDING AFTER INSERT AS BEGIN ATOMIC WITH (TRANSACTION ISOLATION LEVEL = SNAPSHOT, LANGUAGE = N'English') SELECT 1; END";
+        let options = extract_module_options(sql);
+        assert_eq!(options.options, vec!["NATIVE_COMPILATION", "SCHEMABINDING"]);
+
+        let sql = "CREATE VIEW [dbo].[V] ([Id]) WITH VIEW_METADATA,\n    SCHEMABINDING AS SELECT [Id] FROM [dbo].[T] WITH CHECK OPTION";
+        let options = extract_module_options(sql);
+        assert!(options.has_option("SCHEMABINDING"));
+        assert!(options.has_option("VIEW_METADATA"));
+        assert!(!options.has_option("CHECK OPTION"));
+    }
+}
@@ -0,0 +1,136 @@
+//! Visual Studio database project permission files (`Database.sqlpermissions`)
+//!
+//! Older database projects kept permissions in an XML file under `Properties` rather
+//! than in scripts:
+//!
+//! ```xml
+//! <Permissions xmlns="urn:Microsoft.VisualStudio.Data.Schema.Permissions">
+//!   <PermissionStatement Action="GRANT">
+//!     <Permission>EXECUTE</Permission>
+//!     <Grantee>AppRole</Grantee>
+//!     <Object Name="GetOrders" Schema="dbo" Type="OBJECT" />
+//!   </PermissionStatement>
+//! </Permissions>
+//! ```
+//!
+//! Each statement is turned into the GRANT, DENY or REVOKE batch it stands for, so the
+//! file compiles like any other script.
+
+use super::identifier_utils::escape_identifier;
+
+/// Extension of permission files
+pub(crate) const PERMISSIONS_EXTENSION: &str = "sqlpermissions";
+
+/// The T-SQL batches a permissions file stands for, or the line and message of the
+/// first error in it
+pub(crate) fn permissions_to_sql(xml: &str) -> Result<String, (usize, String)> {
+    let xml = xml.trim_start_matches('\u{feff}');
+    let document = roxmltree::Document::parse(xml).map_err(|e| {
+        (
+            e.pos().row as usize,
+            format!("invalid permissions file: {}", e),
+        )
+    })?;
+    let mut sql = String::new();
+    for statement in document
+        .root_element()
+        .children()
+        .filter(|n| n.has_tag_name("PermissionStatement"))
+    {
+        let line = document.text_pos_at(statement.range().start).row as usize;
+        let text = |tag: &str| {
+            statement
+                .children()
+                .find(|n| n.has_tag_name(tag))
+                .and_then(|n| n.text())
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+        };
+        let permission = text("Permission").ok_or((
+            line,
+            "permission statement without a Permission".to_string(),
+        ))?;
+        let grantee =
+            text("Grantee").ok_or((line, "permission statement without a Grantee".to_string()))?;
+
+        let action = statement.attribute("Action").unwrap_or("GRANT");
+        let (verb, with_grant_option) = match action.to_uppercase().as_str() {
+            "GRANT" => ("GRANT", false),
+            "GRANT_WITH_GRANT_OPTION" => ("GRANT", true),
+            "DENY" => ("DENY", false),
+            "REVOKE" => ("REVOKE", false),
+            _ => return Err((line, format!("unknown permission action {}", action))),
+        };
+        let preposition = if verb == "REVOKE" { "FROM" } else { "TO" };
+
+        sql.push_str(&format!("{} {}", verb, permission));
+        if let Some(object) = statement.children().find(|n| n.has_tag_name("Object")) {
+            let name = object.attribute("Name").unwrap_or_default();
+            let securable = match (object.attribute("Type"), object.attribute("Schema")) {
+                (None | Some("OBJECT"), Some(schema)) => format!(
+                    "[{}].[{}]",
+                    escape_identifier(schema),
+                    escape_identifier(name)
+                ),
+                (None | Some("OBJECT"), None) => format!("[{}]", escape_identifier(name)),
+                (Some(class), _) => format!("{}::[{}]", class, escape_identifier(name)),
+            };
+            sql.push_str(&format!(" ON {}", securable));
+        }
+        sql.push_str(&format!(
+            " {} [{}]{};\nGO\n",
+            preposition,
+            escape_identifier(grantee),
+            if with_grant_option {
+                " WITH GRANT OPTION"
+            } else {
+                ""
+            }
+        ));
+    }
+    Ok(sql)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permissions_to_sql() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<Permissions xmlns="urn:Microsoft.VisualStudio.Data.Schema.Permissions">
+  <PermissionStatement Action="GRANT">
+    <Permission>CONNECT</Permission>
+    <Grantee>AppUser</Grantee>
+  </PermissionStatement>
+  <PermissionStatement Action="GRANT_WITH_GRANT_OPTION">
+    <Permission>EXECUTE</Permission>
+    <Grantee>AppRole</Grantee>
+    <Object Name="GetOrders" Schema="dbo" Type="OBJECT" />
+  </PermissionStatement>
+  <PermissionStatement Action="DENY">
+    <Permission>SELECT</Permission>
+    <Grantee>Auditors</Grantee>
+    <Object Name="Payroll" Type="SCHEMA" />
+  </PermissionStatement>
+  <PermissionStatement Action="REVOKE">
+    <Permission>DELETE</Permission>
+    <Grantee>AppRole</Grantee>
+    <Object Name="Orders" Schema="dbo" />
+  </PermissionStatement>
+</Permissions>"#;
+        assert_eq!(
+            permissions_to_sql(xml).unwrap(),
+            "GRANT CONNECT TO [AppUser];\nGO\n\
+             GRANT EXECUTE ON [dbo].[GetOrders] TO [AppRole] WITH GRANT OPTION;\nGO\n\
+             DENY SELECT ON SCHEMA::[Payroll] TO [Auditors];\nGO\n\
+             REVOKE DELETE ON [dbo].[Orders] FROM [AppRole];\nGO\n"
+        );
+
+        let missing = "<Permissions>\n  <PermissionStatement Action=\"GRANT\">\n    <Permission>CONNECT</Permission>\n  </PermissionStatement>\n</Permissions>";
+        assert_eq!(
+            permissions_to_sql(missing),
+            Err((2, "permission statement without a Grantee".to_string()))
+        );
+    }
+}
@@ -35,6 +35,8 @@ pub struct TokenParsedProcedure {
     pub schema: String,
     /// Procedure name
     pub name: String,
+    /// Group number of a numbered procedure (`CREATE PROCEDURE [dbo].[P];2`)
+    pub number: Option<u32>,
     /// Procedure parameters (populated by full parsing)
     pub parameters: Vec<TokenParsedProcedureParameter>,
 }
@@ -50,6 +52,8 @@ pub struct TokenParsedProcedureParameter {
     pub is_output: bool,
     /// Whether this is a READONLY table-valued parameter
     pub is_readonly: bool,
+    /// Whether this is a `CURSOR VARYING` parameter (a result set returned through OUTPUT)
+    pub is_varying: bool,
     /// Default value if specified
     pub default_value: Option<String>,
 }
@@ -140,6 +144,7 @@ impl ProcedureTokenParser {
 
         // Parse the schema-qualified name
         let (schema, name) = self.base.parse_schema_qualified_name()?;
+        let number = self.parse_procedure_number();
         self.base.skip_whitespace();
 
         // Parse parameters (between procedure name and AS keyword)
@@ -148,6 +153,7 @@ impl ProcedureTokenParser {
         Some(TokenParsedProcedure {
             schema,
             name,
+            number,
             parameters,
         })
     }
@@ -194,6 +200,7 @@ impl ProcedureTokenParser {
 
         // Parse the schema-qualified name
         let (schema, name) = self.base.parse_schema_qualified_name()?;
+        let number = self.parse_procedure_number();
         self.base.skip_whitespace();
 
         // Parse parameters
@@ -202,6 +209,7 @@ impl ProcedureTokenParser {
         Some(TokenParsedProcedure {
             schema,
             name,
+            number,
             parameters,
         })
     }
@@ -209,13 +217,37 @@ impl ProcedureTokenParser {
     /// Parse a schema-qualified name: [schema].[name] or schema.name or [name] or name
     fn parse_schema_qualified_name(&mut self) -> Option<TokenParsedProcedure> {
         let (schema, name) = self.base.parse_schema_qualified_name()?;
+        let number = self.parse_procedure_number();
         Some(TokenParsedProcedure {
             schema,
             name,
+            number,
             parameters: Vec::new(),
         })
     }
 
+    /// Parse the `;number` suffix of a numbered procedure (`[dbo].[P];2`).
+    ///
+    /// A `;` not followed by a number is a stray separator in the header and is skipped.
+    fn parse_procedure_number(&mut self) -> Option<u32> {
+        let start = self.base.pos();
+        self.base.skip_whitespace();
+        if !self.base.check_token(&Token::SemiColon) {
+            self.base.set_pos(start);
+            return None;
+        }
+        self.base.advance();
+        self.base.skip_whitespace();
+        match self.base.current_token().map(|t| &t.token) {
+            Some(Token::Number(n, _)) => {
+                let number = n.parse().ok();
+                self.base.advance();
+                number
+            }
+            _ => None,
+        }
+    }
+
     /// Parse procedure parameters: @param1 TYPE, @param2 TYPE OUTPUT, @items TYPE READONLY
     /// Parameters continue until AS keyword is found
     fn parse_parameters(&mut self) -> Vec<TokenParsedProcedureParameter> {
@@ -244,6 +276,13 @@ impl ProcedureTokenParser {
                 break;
             }
 
+            // Stray `;` separators in the header (e.g. `@Id INT;` before AS) are ignored
+            if self.base.check_token(&Token::SemiColon) {
+                self.base.advance();
+                self.base.skip_whitespace();
+                continue;
+            }
+
             // Try to parse a parameter (starts with @)
             if let Some(param) = self.parse_single_parameter() {
                 params.push(param);
@@ -271,7 +310,7 @@ impl ProcedureTokenParser {
         params
     }
 
-    /// Parse a single parameter: @name TYPE [= default] [READONLY] [OUTPUT|OUT]
+    /// Parse a single parameter: @name TYPE [VARYING] [= default] [READONLY] [OUTPUT|OUT]
     fn parse_single_parameter(&mut self) -> Option<TokenParsedProcedureParameter> {
         // Parameter name should be a Word starting with @
         let name = self.parse_parameter_name()?;
@@ -281,11 +320,12 @@ impl ProcedureTokenParser {
         let data_type = self.parse_data_type()?;
         self.base.skip_whitespace();
 
-        // Now parse optional modifiers: = default, READONLY, OUTPUT/OUT
+        // Now parse optional modifiers: VARYING, = default, READONLY, OUTPUT/OUT
         // These can appear in various orders, so we loop until we hit a delimiter
         let mut default_value = None;
         let mut is_readonly = false;
         let mut is_output = false;
+        let mut is_varying = false;
 
         loop {
             // Check for default value: = ...
@@ -297,6 +337,14 @@ impl ProcedureTokenParser {
                 continue;
             }
 
+            // Check for VARYING keyword (only valid on CURSOR parameters)
+            if self.base.check_word_ci("VARYING") {
+                is_varying = true;
+                self.base.advance();
+                self.base.skip_whitespace();
+                continue;
+            }
+
             // Check for READONLY keyword
             if self.base.check_word_ci("READONLY") {
                 is_readonly = true;
@@ -322,6 +370,7 @@ impl ProcedureTokenParser {
             data_type,
             is_output,
             is_readonly,
+            is_varying,
             default_value,
         })
     }
@@ -420,6 +469,7 @@ impl ProcedureTokenParser {
                     w.keyword,
                     Keyword::AS | Keyword::BEGIN | Keyword::WITH | Keyword::FOR
                 ) || w.value.eq_ignore_ascii_case("READONLY")
+                    || w.value.eq_ignore_ascii_case("VARYING")
                     || w.value.eq_ignore_ascii_case("OUTPUT")
                     || w.value.eq_ignore_ascii_case("OUT")
                 {
@@ -444,55 +494,8 @@ impl ProcedureTokenParser {
 
     /// Parse default value (everything up to comma, READONLY, OUTPUT, OUT, or AS)
     fn parse_default_value(&mut self) -> String {
-        let mut result = String::new();
-        let mut depth = 0;
-
-        while !self.base.is_at_end() {
-            if let Some(token) = self.base.current_token() {
-                match &token.token {
-                    Token::LParen => {
-                        depth += 1;
-                        result.push('(');
-                        self.base.advance();
-                    }
-                    Token::RParen => {
-                        if depth == 0 {
-                            break; // End of parameters
-                        }
-                        depth -= 1;
-                        result.push(')');
-                        self.base.advance();
-                    }
-                    Token::Comma if depth == 0 => {
-                        break; // Next parameter
-                    }
-                    Token::Word(w)
-                        if depth == 0
-                            && (w.keyword == Keyword::AS
-                                || w.value.eq_ignore_ascii_case("OUTPUT")
-                                || w.value.eq_ignore_ascii_case("OUT")
-                                || w.value.eq_ignore_ascii_case("READONLY")) =>
-                    {
-                        break; // End of default value
-                    }
-                    Token::Whitespace(_) => {
-                        // Add space if result has content
-                        if !result.is_empty() && !result.ends_with(' ') {
-                            result.push(' ');
-                        }
-                        self.base.advance();
-                    }
-                    _ => {
-                        result.push_str(&TokenParser::token_to_string(&token.token));
-                        self.base.advance();
-                    }
-                }
-            } else {
-                break;
-            }
-        }
-
-        result.trim().to_string()
+        self.base
+            .parse_parameter_default(&["AS", "OUTPUT", "OUT", "READONLY", "VARYING"])
     }
 }
 
@@ -529,19 +532,15 @@ pub fn parse_alter_procedure_tokens(sql: &str) -> Option<(String, String)> {
 /// Parse CREATE PROCEDURE from pre-tokenized tokens (Phase 76)
 pub fn parse_create_procedure_tokens_with_tokens(
     tokens: Vec<TokenWithSpan>,
-) -> Option<(String, String)> {
-    let mut parser = ProcedureTokenParser::from_tokens(tokens);
-    let result = parser.parse_create_procedure()?;
-    Some((result.schema, result.name))
+) -> Option<TokenParsedProcedure> {
+    ProcedureTokenParser::from_tokens(tokens).parse_create_procedure()
 }
 
 /// Parse ALTER PROCEDURE from pre-tokenized tokens (Phase 76)
 pub fn parse_alter_procedure_tokens_with_tokens(
     tokens: Vec<TokenWithSpan>,
-) -> Option<(String, String)> {
-    let mut parser = ProcedureTokenParser::from_tokens(tokens);
-    let result = parser.parse_alter_procedure()?;
-    Some((result.schema, result.name))
+) -> Option<TokenParsedProcedure> {
+    ProcedureTokenParser::from_tokens(tokens).parse_alter_procedure()
 }
 
 /// Parse CREATE PROCEDURE with full parameter extraction
@@ -934,6 +933,20 @@ END
         assert!(result.parameters[0].is_output);
     }
 
+    #[test]
+    fn test_full_parse_cursor_varying_output() {
+        let result = parse_create_procedure_full(
+            "CREATE PROCEDURE [dbo].[Test] @Result CURSOR VARYING OUTPUT, @Min INT = 0 AS OPEN @Result",
+        )
+        .unwrap();
+        assert_eq!(result.parameters.len(), 2);
+        assert_eq!(result.parameters[0].data_type, "CURSOR");
+        assert!(result.parameters[0].is_varying);
+        assert!(result.parameters[0].is_output);
+        assert!(!result.parameters[1].is_varying);
+        assert_eq!(result.parameters[1].default_value, Some("0".to_string()));
+    }
+
     #[test]
     fn test_full_parse_complex_types() {
         let result = parse_create_procedure_full(
@@ -1045,6 +1058,22 @@ END
         assert!(params[0].is_readonly);
     }
 
+    #[test]
+    fn test_full_parse_numbered_procedure() {
+        let sql = "CREATE PROCEDURE [dbo].[GetOrders] ;2\n    @Id INT,\n    @Name NVARCHAR(10) OUTPUT;\nAS SELECT @Id";
+        let result = parse_create_procedure_full(sql).unwrap();
+        assert_eq!(result.name, "GetOrders");
+        assert_eq!(result.number, Some(2));
+        assert_eq!(result.parameters.len(), 2);
+        assert_eq!(result.parameters[1].name, "Name");
+        assert!(result.parameters[1].is_output);
+
+        let result = parse_alter_procedure_full("ALTER PROC dbo.GetOrders; AS SELECT 1").unwrap();
+        assert_eq!(result.name, "GetOrders");
+        assert_eq!(result.number, None);
+        assert!(result.parameters.is_empty());
+    }
+
     #[test]
     fn test_full_parse_alter_with_params() {
         let result = parse_alter_procedure_full(
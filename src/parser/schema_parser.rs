@@ -0,0 +1,191 @@
+//! Decomposition of CREATE SCHEMA statements with contained elements
+//!
+//! T-SQL allows tables, views and permissions to be defined inside a CREATE SCHEMA
+//! statement without a separating batch:
+//!
+//! ```sql
+//! CREATE SCHEMA [sales] AUTHORIZATION [dbo]
+//!     CREATE TABLE [Orders] ([Id] INT NOT NULL)
+//!     CREATE VIEW [BigOrders] AS SELECT [Id] FROM [sales].[Orders]
+//!     GRANT SELECT ON [sales].[Orders] TO [public];
+//! ```
+//!
+//! The statement is split into the bare CREATE SCHEMA and one statement per contained
+//! element, so each is parsed as if it had been written on its own. Tables and views
+//! without a schema qualifier belong to the new schema, so their names are qualified.
+
+use super::lexer::{Keyword, Token, TokenWithSpan};
+use super::token_parser_base::TokenParser;
+
+/// A statement split out of a CREATE SCHEMA statement
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaElementStatement {
+    /// SQL text of the statement
+    pub sql: String,
+    /// 0-based line offset of the statement within the original SQL
+    pub line_offset: usize,
+}
+
+/// Split `CREATE SCHEMA ... CREATE TABLE ... GRANT ...` into separate statements.
+///
+/// Returns `None` if `sql` is not a CREATE SCHEMA statement or has no contained elements.
+pub fn split_create_schema_elements(sql: &str) -> Option<Vec<SchemaElementStatement>> {
+    let parser = TokenParser::new(sql)?;
+    let tokens = parser.tokens();
+    let significant: Vec<usize> = (0..tokens.len())
+        .filter(|&i| !matches!(tokens[i].token, Token::Whitespace(_)))
+        .collect();
+
+    // Must start with CREATE SCHEMA <name>
+    let is_keyword = |i: usize, keyword: Keyword| matches!(&tokens[i].token, Token::Word(w) if w.keyword == keyword);
+    let is_word_ci = |i: usize, word: &str| {
+        matches!(&tokens[i].token, Token::Word(w)
+            if w.quote_style.is_none() && w.value.eq_ignore_ascii_case(word))
+    };
+    if significant.len() < 3
+        || !is_keyword(significant[0], Keyword::CREATE)
+        || !is_keyword(significant[1], Keyword::SCHEMA)
+    {
+        return None;
+    }
+    // CREATE SCHEMA AUTHORIZATION <owner> names the schema after its owner
+    let name_index = if is_word_ci(significant[2], "AUTHORIZATION") {
+        *significant.get(3)?
+    } else {
+        significant[2]
+    };
+    let schema_name = match &tokens[name_index].token {
+        Token::Word(w) => w.value.clone(),
+        _ => return None,
+    };
+
+    // Top-level CREATE/GRANT/DENY/REVOKE after the schema header start a contained element
+    let mut depth = 0usize;
+    let mut starts = Vec::new();
+    for &i in &significant[3..] {
+        match &tokens[i].token {
+            Token::LParen => depth += 1,
+            Token::RParen => depth = depth.saturating_sub(1),
+            Token::Word(w)
+                if depth == 0
+                    && w.quote_style.is_none()
+                    && ["CREATE", "GRANT", "DENY", "REVOKE"]
+                        .iter()
+                        .any(|k| w.value.eq_ignore_ascii_case(k)) =>
+            {
+                starts.push(i);
+            }
+            _ => {}
+        }
+    }
+    if starts.is_empty() {
+        return None;
+    }
+
+    let line_starts = line_start_offsets(sql);
+    let offset_of = |token: &TokenWithSpan| {
+        let location = token.span.start;
+        let line_start = line_starts
+            .get((location.line as usize).saturating_sub(1))
+            .copied()
+            .unwrap_or(sql.len());
+        let column = (location.column as usize).saturating_sub(1);
+        line_start
+            + sql[line_start..]
+                .char_indices()
+                .nth(column)
+                .map_or(sql.len() - line_start, |(offset, _)| offset)
+    };
+    let line_offset_of = |token: &TokenWithSpan| (token.span.start.line as usize).saturating_sub(1);
+
+    let mut statements = vec![SchemaElementStatement {
+        sql: sql[..offset_of(&tokens[starts[0]])].trim().to_string(),
+        line_offset: 0,
+    }];
+
+    for (n, &start) in starts.iter().enumerate() {
+        let begin = offset_of(&tokens[start]);
+        let end = starts
+            .get(n + 1)
+            .map_or(sql.len(), |&next| offset_of(&tokens[next]));
+        let mut text = sql[begin..end].trim().to_string();
+
+        // CREATE TABLE/VIEW <name> without a schema qualifier: qualify with the new schema
+        if is_keyword(start, Keyword::CREATE) {
+            let rest: Vec<usize> = significant
+                .iter()
+                .copied()
+                .skip_while(|&i| i <= start)
+                .take(3)
+                .collect();
+            let is_object = rest
+                .first()
+                .is_some_and(|&i| is_keyword(i, Keyword::TABLE) || is_keyword(i, Keyword::VIEW));
+            let unqualified = rest.len() >= 2
+                && matches!(tokens[rest[1]].token, Token::Word(_))
+                && rest
+                    .get(2)
+                    .is_none_or(|&i| tokens[i].token != Token::Period);
+            if is_object && unqualified {
+                let name_offset = offset_of(&tokens[rest[1]]) - begin;
+                text.insert_str(
+                    name_offset,
+                    &format!("[{}].", schema_name.replace(']', "]]")),
+                );
+            }
+        }
+
+        statements.push(SchemaElementStatement {
+            sql: text,
+            line_offset: line_offset_of(&tokens[start]),
+        });
+    }
+
+    Some(statements)
+}
+
+/// Byte offset of the start of each line in `sql`
+fn line_start_offsets(sql: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(sql.match_indices('\n').map(|(i, _)| i + 1))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_create_schema_elements() {
+        let sql = "CREATE SCHEMA [sales] AUTHORIZATION [dbo]\n    CREATE TABLE [Orders] ([Id] INT NOT NULL, CONSTRAINT [CK] CHECK ([Id] > 0))\n    CREATE VIEW [sales].[V] AS SELECT [Id] FROM [sales].[Orders]\n    GRANT SELECT ON [sales].[Orders] TO [public];";
+        let statements = split_create_schema_elements(sql).unwrap();
+
+        assert_eq!(statements.len(), 4);
+        assert_eq!(
+            statements[0].sql,
+            "CREATE SCHEMA [sales] AUTHORIZATION [dbo]"
+        );
+        assert_eq!(
+            statements[1].sql,
+            "CREATE TABLE [sales].[Orders] ([Id] INT NOT NULL, CONSTRAINT [CK] CHECK ([Id] > 0))"
+        );
+        assert_eq!(statements[1].line_offset, 1);
+        assert_eq!(
+            statements[2].sql,
+            "CREATE VIEW [sales].[V] AS SELECT [Id] FROM [sales].[Orders]"
+        );
+        assert_eq!(
+            statements[3].sql,
+            "GRANT SELECT ON [sales].[Orders] TO [public];"
+        );
+        assert_eq!(statements[3].line_offset, 3);
+    }
+
+    #[test]
+    fn test_split_create_schema_without_elements() {
+        assert!(
+            split_create_schema_elements("CREATE SCHEMA [sales] AUTHORIZATION [dbo]").is_none()
+        );
+        assert!(split_create_schema_elements("CREATE TABLE [t] ([Id] INT)").is_none());
+    }
+}
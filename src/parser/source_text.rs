@@ -0,0 +1,308 @@
+//! Source text of project SQL files as it is carried into the model
+//!
+//! Files are decoded to UTF-8 first: a byte order mark identifies UTF-8 and UTF-16 (as
+//! SSMS writes when saving as Unicode); otherwise the project's default encoding is used,
+//! or the encoding is sniffed (UTF-16 without BOM, UTF-8, then Windows-1252).
+//!
+//! Script properties (QueryScript, BodyScript, HeaderContents, ...) are slices of the
+//! source text, so how line endings are treated here is how they appear in model.xml.
+
+use std::borrow::Cow;
+use std::io;
+
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+use sha2::{Digest, Sha256};
+
+/// Text encoding of a SQL file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileEncoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Windows1252,
+}
+
+impl std::str::FromStr for FileEncoding {
+    type Err = String;
+
+    /// Encoding names and code pages as used by .NET (`utf-8`, `unicode`, `1252`, ...)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "utf-8" | "utf8" | "65001" => Ok(FileEncoding::Utf8),
+            "utf-16" | "utf-16le" | "unicode" | "1200" => Ok(FileEncoding::Utf16Le),
+            "utf-16be" | "bigendianunicode" | "1201" => Ok(FileEncoding::Utf16Be),
+            "windows-1252" | "cp1252" | "ansi" | "1252" => Ok(FileEncoding::Windows1252),
+            _ => Err(format!("Unknown file encoding: {}", s)),
+        }
+    }
+}
+
+impl FileEncoding {
+    /// The encoding_rs codec for this encoding
+    fn encoding(self) -> &'static Encoding {
+        match self {
+            FileEncoding::Utf8 => UTF_8,
+            FileEncoding::Utf16Le => UTF_16LE,
+            FileEncoding::Utf16Be => UTF_16BE,
+            FileEncoding::Windows1252 => WINDOWS_1252,
+        }
+    }
+
+    /// The byte order mark of this encoding (none for Windows-1252)
+    fn bom(self) -> &'static [u8] {
+        match self {
+            FileEncoding::Utf8 => b"\xEF\xBB\xBF",
+            FileEncoding::Utf16Le => b"\xFF\xFE",
+            FileEncoding::Utf16Be => b"\xFE\xFF",
+            FileEncoding::Windows1252 => b"",
+        }
+    }
+}
+
+/// How a SQL file was encoded, to write text back to it the same way
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceEncoding {
+    pub encoding: FileEncoding,
+    /// Whether the file starts with a byte order mark
+    pub bom: bool,
+}
+
+/// Decode the bytes of a SQL file to text
+///
+/// A byte order mark always wins. Without one, `default` is used when set; otherwise
+/// UTF-16 is recognized by its zero bytes, valid UTF-8 is taken as UTF-8 and anything
+/// else is read as Windows-1252.
+pub fn decode_source(bytes: &[u8], default: Option<FileEncoding>) -> io::Result<String> {
+    decode_source_with_encoding(bytes, default).map(|(text, _)| text)
+}
+
+/// Decode the bytes of a SQL file to text, as [`decode_source`], along with the encoding
+/// they were read in
+pub fn decode_source_with_encoding(
+    bytes: &[u8],
+    default: Option<FileEncoding>,
+) -> io::Result<(String, SourceEncoding)> {
+    let bom = [
+        FileEncoding::Utf8,
+        FileEncoding::Utf16Le,
+        FileEncoding::Utf16Be,
+    ]
+    .into_iter()
+    .find(|encoding| bytes.starts_with(encoding.bom()));
+    let (encoding, body) = match bom {
+        Some(encoding) => (encoding, &bytes[encoding.bom().len()..]),
+        None => (default.unwrap_or_else(|| sniff_encoding(bytes)), bytes),
+    };
+    let source_encoding = SourceEncoding {
+        encoding,
+        bom: bom.is_some(),
+    };
+
+    if encoding == FileEncoding::Utf8 {
+        // Invalid UTF-8 is an error rather than replacement characters in the model
+        return UTF_8
+            .decode_without_bom_handling_and_without_replacement(body)
+            .map(|text| (text.into_owned(), source_encoding))
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "stream did not contain valid UTF-8",
+                )
+            });
+    }
+    let text = encoding
+        .encoding()
+        .decode_without_bom_handling(body)
+        .0
+        .into_owned();
+    Ok((text, source_encoding))
+}
+
+/// Encode `text` to the bytes of a SQL file read in `encoding`, byte order mark included
+pub fn encode_source(text: &str, encoding: SourceEncoding) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    if encoding.bom {
+        bytes.extend_from_slice(encoding.encoding.bom());
+    }
+    match encoding.encoding {
+        FileEncoding::Utf8 => bytes.extend_from_slice(text.as_bytes()),
+        // encoding_rs only decodes UTF-16
+        FileEncoding::Utf16Le => bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes)),
+        FileEncoding::Utf16Be => bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes)),
+        FileEncoding::Windows1252 => bytes.extend_from_slice(&WINDOWS_1252.encode(text).0),
+    }
+    bytes
+}
+
+/// SHA256 of a source file's bytes (uppercase hex), as the source manifest records it
+pub fn source_hash(bytes: &[u8]) -> String {
+    format!("{:X}", Sha256::digest(bytes))
+}
+
+/// Encoding of a file without a byte order mark
+fn sniff_encoding(bytes: &[u8]) -> FileEncoding {
+    // SQL text is mostly ASCII, so UTF-16 shows as a zero in every other byte
+    let sample = &bytes[..bytes.len().min(1024) & !1];
+    if !sample.is_empty() {
+        let pairs = sample.len() / 2;
+        let zero_high = sample.chunks_exact(2).filter(|p| p[1] == 0).count();
+        let zero_low = sample.chunks_exact(2).filter(|p| p[0] == 0).count();
+        if zero_high * 2 > pairs && zero_low == 0 {
+            return FileEncoding::Utf16Le;
+        }
+        if zero_low * 2 > pairs && zero_high == 0 {
+            return FileEncoding::Utf16Be;
+        }
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        FileEncoding::Utf8
+    } else {
+        FileEncoding::Windows1252
+    }
+}
+
+/// Line endings of script text taken from SQL files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineEndings {
+    /// Keep each file's line endings as written, as DotNet DacFx does (default)
+    #[default]
+    Preserve,
+    /// Convert CRLF to LF
+    Lf,
+    /// Convert lone LF to CRLF
+    Crlf,
+}
+
+impl std::str::FromStr for LineEndings {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "preserve" => Ok(LineEndings::Preserve),
+            "lf" => Ok(LineEndings::Lf),
+            "crlf" => Ok(LineEndings::Crlf),
+            _ => Err(format!("Unknown line endings: {}", s)),
+        }
+    }
+}
+
+impl LineEndings {
+    /// `text` with its line endings converted
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        match self {
+            LineEndings::Preserve => Cow::Borrowed(text),
+            LineEndings::Lf if text.contains("\r\n") => Cow::Owned(text.replace("\r\n", "\n")),
+            LineEndings::Crlf if text.contains('\n') => {
+                Cow::Owned(text.replace("\r\n", "\n").replace('\n', "\r\n"))
+            }
+            _ => Cow::Borrowed(text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16le(text: &str) -> Vec<u8> {
+        text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+    }
+
+    #[test]
+    fn test_decode_source_with_bom() {
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(utf16le("SELECT N'é'"));
+        assert_eq!(decode_source(&bytes, None).unwrap(), "SELECT N'é'");
+
+        let mut bytes = vec![0xFE, 0xFF];
+        bytes.extend("SELECT 1".encode_utf16().flat_map(u16::to_be_bytes));
+        assert_eq!(decode_source(&bytes, None).unwrap(), "SELECT 1");
+
+        // The BOM wins over the project default
+        assert_eq!(
+            decode_source(b"\xEF\xBB\xBFSELECT 1", Some(FileEncoding::Windows1252)).unwrap(),
+            "SELECT 1"
+        );
+    }
+
+    #[test]
+    fn test_decode_source_without_bom() {
+        assert_eq!(
+            decode_source(&utf16le("SELECT 1"), None).unwrap(),
+            "SELECT 1"
+        );
+        assert_eq!(
+            decode_source("SELECT 'café'".as_bytes(), None).unwrap(),
+            "SELECT 'café'"
+        );
+        // Not valid UTF-8: Windows-1252
+        assert_eq!(
+            decode_source(b"SELECT 'caf\xE9 \x80'", None).unwrap(),
+            "SELECT 'café €'"
+        );
+        // The project default applies to files without a BOM
+        assert_eq!(
+            decode_source("é".as_bytes(), Some(FileEncoding::Windows1252)).unwrap(),
+            "Ã©"
+        );
+        assert!(decode_source(b"caf\xE9", Some(FileEncoding::Utf8)).is_err());
+    }
+
+    #[test]
+    fn test_encode_source_round_trip() {
+        let mut utf16 = vec![0xFF, 0xFE];
+        utf16.extend(utf16le("SELECT N'é'"));
+        let files: [&[u8]; 5] = [
+            &utf16,
+            &utf16le("SELECT 1"),
+            b"\xEF\xBB\xBFSELECT 'caf\xC3\xA9'",
+            b"SELECT 'caf\xE9 \x80'",
+            b"SELECT 'caf\xE9 \x81'",
+        ];
+        for bytes in files {
+            let (text, encoding) = decode_source_with_encoding(bytes, None).unwrap();
+            assert_eq!(encode_source(&text, encoding), bytes, "{:?}", text);
+        }
+
+        let (text, encoding) =
+            decode_source_with_encoding("é".as_bytes(), Some(FileEncoding::Windows1252)).unwrap();
+        assert_eq!(
+            encoding,
+            SourceEncoding {
+                encoding: FileEncoding::Windows1252,
+                bom: false
+            }
+        );
+        assert_eq!(encode_source(&text, encoding), "é".as_bytes());
+    }
+
+    #[test]
+    fn test_file_encoding_from_str() {
+        assert_eq!("UTF-8".parse::<FileEncoding>().unwrap(), FileEncoding::Utf8);
+        assert_eq!(
+            "unicode".parse::<FileEncoding>().unwrap(),
+            FileEncoding::Utf16Le
+        );
+        assert_eq!(
+            "1252".parse::<FileEncoding>().unwrap(),
+            FileEncoding::Windows1252
+        );
+        assert!("ebcdic".parse::<FileEncoding>().is_err());
+    }
+
+    #[test]
+    fn test_line_endings_apply() {
+        let mixed = "a\r\nb\nc";
+        assert_eq!(LineEndings::Preserve.apply(mixed), mixed);
+        assert_eq!(LineEndings::Lf.apply(mixed), "a\nb\nc");
+        assert_eq!(LineEndings::Crlf.apply(mixed), "a\r\nb\r\nc");
+        assert!(matches!(LineEndings::Lf.apply("a\nb"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_line_endings_from_str() {
+        assert_eq!("CRLF".parse::<LineEndings>().unwrap(), LineEndings::Crlf);
+        assert_eq!("lf".parse::<LineEndings>().unwrap(), LineEndings::Lf);
+        assert!("cr".parse::<LineEndings>().is_err());
+    }
+}
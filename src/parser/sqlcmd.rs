@@ -1,17 +1,20 @@
 //! SQLCMD directive processing
 //!
 //! Handles SQLCMD directives like `:r` (include file) that are commonly used
-//! in SQL Server deployment scripts.
+//! in SQL Server deployment scripts, and `:IF` / `:ELSE` / `:ENDIF` blocks used
+//! to build environment-specific dacpacs from one project.
 
-use std::collections::HashSet;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
 
 use anyhow::Result;
-use encoding_rs::WINDOWS_1252;
 use regex::Regex;
 
+use super::source_text::{decode_source, source_hash};
 use crate::error::SqlPackageError;
+use crate::util::long_path;
 
 // Cached regex patterns (Phase 63) — compiled once, reused on every call
 static SETVAR_RE: LazyLock<Regex> =
@@ -19,27 +22,17 @@ static SETVAR_RE: LazyLock<Regex> =
 static INCLUDE_RE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r#"(?m)^\s*:r\s+(?:"([^"]+)"|(\S+))[ \t]*\r?\n?"#).unwrap());
 static VAR_SUBST_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\$\((\w+)\)").unwrap());
-
-/// Read a file as a string, trying UTF-8 first, then Windows-1252 as fallback
-fn read_file_with_encoding_fallback(path: &Path) -> std::io::Result<String> {
-    let bytes = std::fs::read(path)?;
-
-    // Try UTF-8 first (handles BOM automatically if present)
-    match String::from_utf8(bytes.clone()) {
-        Ok(s) => Ok(s),
-        Err(_) => {
-            // Fall back to Windows-1252 (common for SQL files created on Windows)
-            let (decoded, _, had_errors) = WINDOWS_1252.decode(&bytes);
-            if had_errors {
-                Err(std::io::Error::new(
-                    std::io::ErrorKind::InvalidData,
-                    "File contains invalid characters",
-                ))
-            } else {
-                Ok(decoded.into_owned())
-            }
-        }
-    }
+static IF_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)^\s*:if\s+(.+?)\s*$").unwrap());
+static ELSE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)^\s*:else\s*$").unwrap());
+static ENDIF_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)^\s*:endif\s*$").unwrap());
+static IF_COMPARISON_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(.+?)\s*(==|=|<>|!=)\s*(.+)$").unwrap());
+
+/// Read an included file as text, decoded as project SQL files are, with the
+/// [`source_hash`] of its bytes
+fn read_included_file(path: &Path) -> std::io::Result<(String, String)> {
+    let bytes = std::fs::read(long_path(path))?;
+    Ok((decode_source(&bytes, None)?, source_hash(&bytes)))
 }
 
 /// Expand all `:r` include directives in SQL content
@@ -59,13 +52,24 @@ fn read_file_with_encoding_fallback(path: &Path) -> std::io::Result<String> {
 /// - An included file cannot be found
 /// - A circular include is detected
 pub fn expand_includes(content: &str, source_file: &Path) -> Result<String> {
+    Ok(expand_includes_with_hashes(content, source_file)?.0)
+}
+
+/// Expand `:r` include directives as [`expand_includes`], also returning the
+/// [`source_hash`] of each included file's bytes as they were read
+pub fn expand_includes_with_hashes(
+    content: &str,
+    source_file: &Path,
+) -> Result<(String, BTreeMap<PathBuf, String>)> {
     let mut visited = HashSet::new();
     visited.insert(
-        source_file
+        long_path(source_file)
             .canonicalize()
             .unwrap_or_else(|_| source_file.to_path_buf()),
     );
-    expand_includes_recursive(content, source_file, &mut visited)
+    let mut hashes = BTreeMap::new();
+    let expanded = expand_includes_recursive(content, source_file, &mut visited, &mut hashes)?;
+    Ok((expanded, hashes))
 }
 
 /// Recursive implementation of include expansion
@@ -73,18 +77,9 @@ fn expand_includes_recursive(
     content: &str,
     source_file: &Path,
     visited: &mut HashSet<PathBuf>,
+    hashes: &mut BTreeMap<PathBuf, String>,
 ) -> Result<String> {
-    // First, extract any :setvar definitions and build a variable map
-    let mut variables = std::collections::HashMap::new();
-    for caps in SETVAR_RE.captures_iter(content) {
-        let var_name = caps.get(1).map(|m| m.as_str()).unwrap_or("");
-        let var_value = caps
-            .get(2)
-            .map(|m| m.as_str().trim_matches('"'))
-            .unwrap_or("");
-        variables.insert(var_name.to_string(), var_value.to_string());
-    }
-
+    let variables = setvar_values(content);
     let source_dir = source_file.parent().unwrap_or(Path::new("."));
     let mut result = String::new();
     let mut last_end = 0;
@@ -95,43 +90,15 @@ fn expand_includes_recursive(
         // Add content before this match
         result.push_str(&content[last_end..match_range.start()]);
 
-        // Extract the file path (either quoted or unquoted)
-        let include_path_str = caps
-            .get(1)
-            .or_else(|| caps.get(2))
-            .map(|m| m.as_str())
-            .unwrap_or("");
-
-        // Substitute SQLCMD variables $(varname)
-        let include_path_str = VAR_SUBST_RE
-            .replace_all(include_path_str, |caps: &regex::Captures| {
-                let var_name = caps.get(1).map(|m| m.as_str()).unwrap_or("");
-                variables
-                    .get(var_name)
-                    .cloned()
-                    .unwrap_or_else(|| format!("$({})", var_name))
-            })
-            .to_string();
-
-        // Normalize path separators (Windows paths use backslash)
-        let include_path_str = include_path_str.replace('\\', "/");
-        let include_path = Path::new(&include_path_str);
-
-        // Resolve relative paths from source file's directory
-        let resolved_path = if include_path.is_absolute() {
-            include_path.to_path_buf()
-        } else {
-            source_dir.join(include_path)
-        };
+        let resolved_path = include_path(&caps, &variables, source_dir);
 
         // Canonicalize for comparison (handles . and ..)
-        let canonical_path =
-            resolved_path
-                .canonicalize()
-                .map_err(|_| SqlPackageError::SqlcmdIncludeNotFound {
-                    path: resolved_path.clone(),
-                    source_file: source_file.to_path_buf(),
-                })?;
+        let canonical_path = long_path(&resolved_path).canonicalize().map_err(|_| {
+            SqlPackageError::SqlcmdIncludeNotFound {
+                path: resolved_path.clone(),
+                source_file: source_file.to_path_buf(),
+            }
+        })?;
 
         // Check for circular includes
         if visited.contains(&canonical_path) {
@@ -147,24 +114,22 @@ fn expand_includes_recursive(
             .into());
         }
 
-        // Read the included file (supports UTF-8 and Windows-1252 encodings)
-        let included_content = read_file_with_encoding_fallback(&canonical_path).map_err(|_| {
+        // Read the included file (UTF-8, UTF-16 or Windows-1252, as project files)
+        let (included_content, hash) = read_included_file(&canonical_path).map_err(|_| {
             SqlPackageError::SqlcmdIncludeNotFound {
                 path: resolved_path.clone(),
                 source_file: source_file.to_path_buf(),
             }
         })?;
 
-        // Strip UTF-8 BOM if present
-        let included_content = included_content
-            .strip_prefix('\u{FEFF}')
-            .unwrap_or(&included_content);
+        hashes.insert(canonical_path.clone(), hash);
 
         // Track this file to detect circular includes
         visited.insert(canonical_path.clone());
 
         // Recursively expand includes in the included file
-        let expanded = expand_includes_recursive(included_content, &canonical_path, visited)?;
+        let expanded =
+            expand_includes_recursive(&included_content, &canonical_path, visited, hashes)?;
 
         // Inline the included content (matching DotNet behavior - no marker comments)
         // DotNet adds CRLF after each include which becomes LF after normalization
@@ -187,6 +152,207 @@ fn expand_includes_recursive(
     Ok(result)
 }
 
+/// Files a script includes through `:r`, directly or from included files
+///
+/// Includes that cannot be read are skipped; expanding the script reports them.
+pub fn included_files(script: &Path) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let mut pending = vec![script.to_path_buf()];
+    while let Some(file) = pending.pop() {
+        let Ok((content, _)) = read_included_file(&file) else {
+            continue;
+        };
+        let variables = setvar_values(&content);
+        let source_dir = file.parent().unwrap_or(Path::new("."));
+        for caps in INCLUDE_RE.captures_iter(&content) {
+            let Ok(path) = long_path(&include_path(&caps, &variables, source_dir)).canonicalize()
+            else {
+                continue;
+            };
+            if !found.contains(&path) {
+                found.push(path.clone());
+                pending.push(path);
+            }
+        }
+    }
+    found
+}
+
+/// `:setvar` definitions in a script
+fn setvar_values(content: &str) -> HashMap<String, String> {
+    let mut variables = HashMap::new();
+    for caps in SETVAR_RE.captures_iter(content) {
+        let var_name = caps.get(1).map(|m| m.as_str()).unwrap_or("");
+        let var_value = caps
+            .get(2)
+            .map(|m| m.as_str().trim_matches('"'))
+            .unwrap_or("");
+        variables.insert(variable_key(var_name), var_value.to_string());
+    }
+    variables
+}
+
+/// The file named by a `:r` directive, resolved from the including file's directory
+fn include_path(
+    caps: &regex::Captures,
+    variables: &HashMap<String, String>,
+    source_dir: &Path,
+) -> PathBuf {
+    // Extract the file path (either quoted or unquoted)
+    let include_path_str = caps
+        .get(1)
+        .or_else(|| caps.get(2))
+        .map(|m| m.as_str())
+        .unwrap_or("");
+
+    // Substitute SQLCMD variables $(varname)
+    let include_path_str = VAR_SUBST_RE
+        .replace_all(include_path_str, |caps: &regex::Captures| {
+            let var_name = caps.get(1).map(|m| m.as_str()).unwrap_or("");
+            variables
+                .get(&variable_key(var_name))
+                .cloned()
+                .unwrap_or_else(|| format!("$({})", var_name))
+        })
+        .to_string();
+
+    // Normalize path separators (Windows paths use backslash)
+    let include_path_str = include_path_str.replace('\\', "/");
+    let include_path = Path::new(&include_path_str);
+
+    // Resolve relative paths from source file's directory
+    if include_path.is_absolute() {
+        include_path.to_path_buf()
+    } else {
+        source_dir.join(include_path)
+    }
+}
+
+/// An open `:IF` block while evaluating conditionals
+struct ConditionalBlock {
+    /// 1-based line of the `:IF` directive
+    line: usize,
+    /// Whether the enclosing block (if any) is emitting lines
+    parent_active: bool,
+    condition: bool,
+    in_else: bool,
+}
+
+impl ConditionalBlock {
+    fn is_active(&self) -> bool {
+        self.parent_active && self.condition != self.in_else
+    }
+}
+
+/// Key of a SQLCMD variable in maps of variable values
+///
+/// Variable names are case-insensitive, so maps are keyed by the upper-case name and a
+/// value inserted under any spelling replaces the previous one.
+pub fn variable_key(name: &str) -> String {
+    name.to_uppercase()
+}
+
+/// Evaluate `:IF` / `:ELSE` / `:ENDIF` conditional blocks against `variables` (keyed by
+/// [`variable_key`]).
+///
+/// `:IF $(Flag)` keeps its block when the variable is set to anything other than an empty
+/// string, `0`, `false`, `no` or `off`. `:IF '$(Env)' = 'prod'` compares case-insensitively
+/// and accepts `=`, `==`, `<>` and `!=`. Blocks may be nested. Undefined variables expand
+/// to an empty string. Skipped lines and the directives themselves become empty lines so
+/// statement line numbers are unchanged.
+pub fn apply_conditionals<'a>(
+    content: &'a str,
+    variables: &HashMap<String, String>,
+    source_file: &Path,
+) -> Result<Cow<'a, str>> {
+    if !content.lines().any(|line| IF_RE.is_match(line)) {
+        return Ok(Cow::Borrowed(content));
+    }
+
+    let error = |line: usize, message: &str| SqlPackageError::SqlcmdConditionalError {
+        path: source_file.to_path_buf(),
+        line,
+        message: message.to_string(),
+    };
+
+    let mut blocks: Vec<ConditionalBlock> = Vec::new();
+    let mut result = String::with_capacity(content.len());
+
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        let line_number = index + 1;
+        let text = line.trim_end_matches(['\r', '\n']);
+        let active = blocks.last().is_none_or(ConditionalBlock::is_active);
+
+        if let Some(caps) = IF_RE.captures(text) {
+            blocks.push(ConditionalBlock {
+                line: line_number,
+                parent_active: active,
+                condition: evaluate_if_condition(&caps[1], variables),
+                in_else: false,
+            });
+        } else if ELSE_RE.is_match(text) {
+            match blocks.last_mut() {
+                Some(block) if !block.in_else => block.in_else = true,
+                Some(_) => return Err(error(line_number, "duplicate :ELSE").into()),
+                None => return Err(error(line_number, ":ELSE without matching :IF").into()),
+            }
+        } else if ENDIF_RE.is_match(text) {
+            if blocks.pop().is_none() {
+                return Err(error(line_number, ":ENDIF without matching :IF").into());
+            }
+        } else if active {
+            result.push_str(text);
+        }
+        result.push_str(&line[text.len()..]);
+    }
+
+    if let Some(block) = blocks.last() {
+        return Err(error(block.line, ":IF without matching :ENDIF").into());
+    }
+
+    Ok(Cow::Owned(result))
+}
+
+/// Evaluate the condition of an `:IF` directive
+fn evaluate_if_condition(condition: &str, variables: &HashMap<String, String>) -> bool {
+    let expand = |text: &str| {
+        let substituted = VAR_SUBST_RE.replace_all(text, |caps: &regex::Captures| {
+            variables
+                .get(&variable_key(&caps[1]))
+                .cloned()
+                .unwrap_or_default()
+        });
+        unquote(substituted.trim()).to_string()
+    };
+
+    match IF_COMPARISON_RE.captures(condition) {
+        Some(caps) => {
+            let equal = expand(&caps[1]).eq_ignore_ascii_case(&expand(&caps[3]));
+            match &caps[2] {
+                "<>" | "!=" => !equal,
+                _ => equal,
+            }
+        }
+        None => {
+            let value = expand(condition);
+            !value.is_empty()
+                && !["0", "false", "no", "off"]
+                    .iter()
+                    .any(|falsy| value.eq_ignore_ascii_case(falsy))
+        }
+    }
+}
+
+/// Strip one pair of matching single or double quotes
+fn unquote(text: &str) -> &str {
+    for quote in ['\'', '"'] {
+        if let Some(inner) = text.strip_prefix(quote).and_then(|t| t.strip_suffix(quote)) {
+            return inner;
+        }
+    }
+    text
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -309,6 +475,25 @@ mod tests {
         assert!(result.contains("SELECT 'b';"));
     }
 
+    #[test]
+    fn test_include_encodings() {
+        let dir = TempDir::new().unwrap();
+        // UTF-16 LE with BOM, as SSMS saves "Unicode" files
+        let mut utf16 = vec![0xFF, 0xFE];
+        utf16.extend("SELECT N'é';".encode_utf16().flat_map(u16::to_le_bytes));
+        fs::write(dir.path().join("unicode.sql"), utf16).unwrap();
+        fs::write(dir.path().join("ansi.sql"), b"SELECT 'caf\xE9';").unwrap();
+        fs::write(dir.path().join("bom.sql"), b"\xEF\xBB\xBFSELECT 'bom';").unwrap();
+        let content = ":r unicode.sql\n:r ansi.sql\n:r bom.sql\n";
+        let source = create_test_file(dir.path(), "main.sql", content);
+
+        let result = expand_includes(content, &source).unwrap();
+        assert_eq!(
+            result,
+            "SELECT N'é';\n\nSELECT 'café';\n\nSELECT 'bom';\n\n"
+        );
+    }
+
     #[test]
     fn test_include_blank_line_handling() {
         // Test that matches DotNet behavior: content + extra LF after each include
@@ -377,4 +562,61 @@ mod tests {
             "Expected 3 newlines (2 blank lines) before final content"
         );
     }
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (variable_key(k), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_apply_conditionals() {
+        let content = "SELECT 1;\n:IF $(IncludeAudit)\nSELECT 'audit';\n:ENDIF\n:if '$(Env)' = 'prod'\nSELECT 'prod';\n:ELSE\nSELECT 'dev';\n:ENDIF\nSELECT 2;";
+        let path = Path::new("main.sql");
+
+        let result = apply_conditionals(content, &vars(&[("Env", "PROD")]), path).unwrap();
+        assert_eq!(result, "SELECT 1;\n\n\n\n\nSELECT 'prod';\n\n\n\nSELECT 2;");
+
+        let result = apply_conditionals(
+            content,
+            &vars(&[("includeaudit", "1"), ("Env", "dev")]),
+            path,
+        )
+        .unwrap();
+        assert!(result.contains("SELECT 'audit';"));
+        assert!(result.contains("SELECT 'dev';"));
+        assert!(!result.contains("SELECT 'prod';"));
+        assert_eq!(result.lines().count(), content.lines().count());
+    }
+
+    #[test]
+    fn test_apply_conditionals_nested() {
+        let content =
+            ":IF $(A)\n:IF '$(B)' <> 'x'\nSELECT 'ab';\n:ENDIF\n:ELSE\nSELECT 'none';\n:ENDIF\n";
+        let path = Path::new("main.sql");
+
+        let result =
+            apply_conditionals(content, &vars(&[("A", "true"), ("B", "y")]), path).unwrap();
+        assert_eq!(result.trim(), "SELECT 'ab';");
+        let result = apply_conditionals(content, &vars(&[("A", "off"), ("B", "y")]), path).unwrap();
+        assert_eq!(result.trim(), "SELECT 'none';");
+    }
+
+    #[test]
+    fn test_apply_conditionals_unbalanced() {
+        let path = Path::new("main.sql");
+        let no_vars = HashMap::new();
+
+        let err = apply_conditionals("SELECT 1;\n:IF $(A)\nSELECT 2;", &no_vars, path).unwrap_err();
+        assert!(err.to_string().contains("line 2"), "{}", err);
+        assert!(err.to_string().contains(":IF without matching :ENDIF"));
+
+        let err = apply_conditionals(":IF $(A)\n:ENDIF\n:ENDIF", &no_vars, path).unwrap_err();
+        assert!(err.to_string().contains(":ENDIF without matching :IF"));
+
+        // Content without :IF is returned untouched
+        let result = apply_conditionals("SELECT 1;\n:ENDIF", &no_vars, path).unwrap();
+        assert!(matches!(result, Cow::Borrowed(_)));
+    }
 }
@@ -0,0 +1,87 @@
+//! Last links of the parsing fallback chain, for statements nothing else can parse
+//!
+//! A batch is parsed by sqlparser, then by the token-based fallback parsers. If both
+//! fail the build normally stops at the batch. With [`ParseFallback::Raw`] a pattern
+//! match on the statement's text classifies it instead: a table or view it creates is
+//! kept as a raw element (its definition as written) with a warning. With
+//! [`ParseFallback::Skip`], statements that cannot be kept that way are skipped with a
+//! warning, so one unsupported statement does not block a large build.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use super::identifier_utils::split_qualified_name;
+
+/// What to do with a statement neither sqlparser nor the fallback parsers can parse
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParseFallback {
+    /// Fail the build (default)
+    #[default]
+    Fail,
+    /// Keep tables and views as raw elements, fail on anything else
+    Raw,
+    /// Keep tables and views as raw elements, skip anything else
+    Skip,
+}
+
+impl std::str::FromStr for ParseFallback {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "fail" => Ok(ParseFallback::Fail),
+            "raw" => Ok(ParseFallback::Raw),
+            "skip" => Ok(ParseFallback::Skip),
+            _ => Err(format!("Unknown parse fallback: {}", s)),
+        }
+    }
+}
+
+/// Identifier: bracketed, quoted or plain
+const IDENTIFIER: &str = r#"(?:\[(?:[^\]]|\]\])+\]|"[^"]+"|[\w@#$]+)"#;
+
+/// `CREATE`/`ALTER` of a schema object, after any leading comments
+static OBJECT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?is)^(?:\s+|--[^\n]*\n?|/\*.*?\*/)*(?:CREATE(?:\s+OR\s+ALTER)?|ALTER)\s+(TABLE|VIEW|PROCEDURE|PROC|FUNCTION|TRIGGER|TYPE|SEQUENCE|SYNONYM)\s+({id}(?:\s*\.\s*{id})?)",
+        id = IDENTIFIER
+    ))
+    .unwrap()
+});
+
+/// Object a statement creates or alters, as `(type, schema, name)` with the type
+/// upper-cased (`PROC` as `PROCEDURE`)
+pub(crate) fn classify_statement(sql: &str) -> Option<(String, String, String)> {
+    let captures = OBJECT_RE.captures(sql)?;
+    let object_type = match captures[1].to_uppercase().as_str() {
+        "PROC" => "PROCEDURE".to_string(),
+        other => other.to_string(),
+    };
+    let (schema, name) = split_qualified_name(&captures[2], "dbo");
+    Some((object_type, schema, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_statement() {
+        assert_eq!(
+            classify_statement(
+                "-- Orders\n/* kept as written */\nCREATE TABLE [Sales].[Orders] ([Id] INT) WITH (LEDGER = ON (APPEND_ONLY = ON));"
+            ),
+            Some(("TABLE".to_string(), "Sales".to_string(), "Orders".to_string()))
+        );
+        assert_eq!(
+            classify_statement("create or alter proc Search AS SELECT 1"),
+            Some((
+                "PROCEDURE".to_string(),
+                "dbo".to_string(),
+                "Search".to_string()
+            ))
+        );
+        assert_eq!(classify_statement("SELECT 1 FROM [dbo].[Orders]"), None);
+    }
+}
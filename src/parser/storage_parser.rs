@@ -1,9 +1,11 @@
 //! Parser for SQL Server storage elements (Filegroup, Partition Function, Partition Scheme)
 //!
 //! These are database-level storage constructs that don't use schema qualification.
+//! Table-level storage options (`CREATE TABLE ... WITH (DATA_COMPRESSION = ...)`) are
+//! parsed here as well.
 
 use crate::parser::token_parser_base::TokenParser;
-use sqlparser::tokenizer::TokenWithSpan;
+use sqlparser::tokenizer::{Token, TokenWithSpan};
 
 /// Result of parsing ALTER DATABASE ... ADD FILEGROUP
 #[derive(Debug, Clone)]
@@ -35,6 +37,15 @@ pub struct ParsedPartitionScheme {
     pub filegroups: Vec<String>,
 }
 
+/// Table-level data compression from `CREATE TABLE ... WITH (DATA_COMPRESSION = ...)`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParsedTableCompression {
+    /// Compression applied to the whole table (heap or clustered index)
+    pub data_compression: Option<String>,
+    /// Per-partition compression (`DATA_COMPRESSION = PAGE ON PARTITIONS (1 TO 3)`)
+    pub partition_compression: Vec<(u32, String)>,
+}
+
 /// Parse ALTER DATABASE ... ADD FILEGROUP statement
 ///
 /// Examples:
@@ -165,6 +176,75 @@ pub fn parse_partition_scheme_tokens(sql: &str) -> Option<ParsedPartitionScheme>
     })
 }
 
+/// Parse DATA_COMPRESSION options from the WITH clause that follows the column list
+/// of a CREATE TABLE statement. Options inside the column list (e.g. constraint or
+/// inline index WITH clauses) are ignored.
+///
+/// Example: `CREATE TABLE [dbo].[T] ([Id] INT) WITH (DATA_COMPRESSION = PAGE);`
+pub fn parse_table_compression_tokens(sql: &str) -> ParsedTableCompression {
+    let mut result = ParsedTableCompression::default();
+    let Some(mut parser) = TokenParser::new(sql) else {
+        return result;
+    };
+
+    // Skip past the balanced column list
+    parser.skip_to_token(&Token::LParen);
+    parser.skip_parenthesized();
+
+    // Find the table-level WITH ( ... ) clause
+    while !parser.is_at_end() {
+        if parser.check_token(&Token::SemiColon) {
+            return result;
+        }
+        if parser.check_word_ci("WITH") {
+            parser.advance();
+            if parser.skip_symbol('(').is_some() {
+                break;
+            }
+            continue;
+        }
+        parser.advance();
+    }
+
+    while !parser.is_at_end() {
+        parser.skip_whitespace();
+        if parser.check_token(&Token::RParen) {
+            break;
+        }
+        if parser.try_skip_keyword("DATA_COMPRESSION") {
+            parser.skip_whitespace();
+            if parser.check_token(&Token::Eq) {
+                parser.advance();
+            }
+            if let Some(compression) = parser.expect_identifier().map(|c| c.to_uppercase()) {
+                match parser.parse_on_partitions() {
+                    Some(partitions) => result
+                        .partition_compression
+                        .extend(partitions.into_iter().map(|p| (p, compression.clone()))),
+                    None => result.data_compression = Some(compression),
+                }
+            }
+        }
+
+        // Skip the rest of the option (e.g. SYSTEM_VERSIONING = ON (...)) up to the next comma
+        while !parser.is_at_end()
+            && !parser.check_token(&Token::Comma)
+            && !parser.check_token(&Token::RParen)
+        {
+            if parser.check_token(&Token::LParen) {
+                parser.skip_parenthesized();
+            } else {
+                parser.advance();
+            }
+        }
+        if parser.check_token(&Token::Comma) {
+            parser.advance();
+        }
+    }
+
+    result
+}
+
 /// Parse filegroup from pre-tokenized tokens (Phase 76)
 pub fn parse_filegroup_tokens_with_tokens(tokens: Vec<TokenWithSpan>) -> Option<ParsedFilegroup> {
     let mut parser = TokenParser::from_tokens(tokens);
@@ -290,6 +370,35 @@ mod tests {
         }
     }
 
+    mod table_compression_tests {
+        use super::*;
+
+        #[test]
+        fn test_parse_table_compression() {
+            let sql = "CREATE TABLE [dbo].[T] ([Id] INT NOT NULL, CONSTRAINT [PK_T] PRIMARY KEY ([Id]) WITH (DATA_COMPRESSION = ROW)) ON [PRIMARY] WITH (DATA_COMPRESSION = page);";
+            let result = parse_table_compression_tokens(sql);
+            assert_eq!(result.data_compression.as_deref(), Some("PAGE"));
+            assert!(result.partition_compression.is_empty());
+        }
+
+        #[test]
+        fn test_parse_table_partition_compression() {
+            let sql = "CREATE TABLE [dbo].[T] ([Id] INT) ON [PS]([Id]) WITH (SYSTEM_VERSIONING = ON (HISTORY_TABLE = [dbo].[H]), DATA_COMPRESSION = ROW ON PARTITIONS (1, 2))";
+            let result = parse_table_compression_tokens(sql);
+            assert_eq!(result.data_compression, None);
+            assert_eq!(
+                result.partition_compression,
+                vec![(1, "ROW".to_string()), (2, "ROW".to_string())]
+            );
+
+            let sql = "CREATE TABLE [dbo].[T] ([Id] INT)";
+            assert_eq!(
+                parse_table_compression_tokens(sql),
+                ParsedTableCompression::default()
+            );
+        }
+    }
+
     mod partition_function_tests {
         use super::*;
 
@@ -388,7 +388,7 @@ impl TableTypeTokenParser {
 
     /// Capture column definition text until next comma, closing paren, or constraint keyword at depth 0
     fn capture_column_text(&mut self) -> String {
-        let mut result = String::new();
+        let start_pos = self.base.pos();
         let mut depth = 0;
 
         while !self.base.is_at_end() {
@@ -400,7 +400,6 @@ impl TableTypeTokenParser {
             match &token.token {
                 Token::LParen => {
                     depth += 1;
-                    result.push_str(&TokenParser::token_to_string(&token.token));
                     self.base.advance();
                 }
                 Token::RParen => {
@@ -409,7 +408,6 @@ impl TableTypeTokenParser {
                         break;
                     }
                     depth -= 1;
-                    result.push_str(&TokenParser::token_to_string(&token.token));
                     self.base.advance();
                 }
                 Token::Comma if depth == 0 => {
@@ -424,54 +422,22 @@ impl TableTypeTokenParser {
                             // Stop capturing - this is the start of a table-level constraint
                             break;
                         }
-                        _ => {
-                            result.push_str(&TokenParser::token_to_string(&token.token));
-                            self.base.advance();
-                        }
+                        _ => self.base.advance(),
                     }
                 }
-                _ => {
-                    result.push_str(&TokenParser::token_to_string(&token.token));
-                    self.base.advance();
-                }
+                _ => self.base.advance(),
             }
         }
 
-        result.trim().to_string()
+        self.base
+            .tokens_to_string(start_pos, self.base.pos())
+            .trim()
+            .to_string()
     }
 
     /// Parse a parenthesized expression (for CHECK constraints)
     fn parse_parenthesized_expression(&mut self) -> Option<String> {
-        if !self.base.check_token(&Token::LParen) {
-            return None;
-        }
-        self.base.advance(); // consume (
-
-        let mut depth = 1;
-        let mut content = String::new();
-
-        while !self.base.is_at_end() && depth > 0 {
-            if let Some(token) = self.base.current_token() {
-                match &token.token {
-                    Token::LParen => {
-                        depth += 1;
-                        content.push('(');
-                    }
-                    Token::RParen => {
-                        depth -= 1;
-                        if depth > 0 {
-                            content.push(')');
-                        }
-                    }
-                    _ => {
-                        content.push_str(&TokenParser::token_to_string(&token.token));
-                    }
-                }
-                self.base.advance();
-            }
-        }
-
-        Some(content.trim().to_string())
+        self.base.consume_parenthesized_contents()
     }
 }
 
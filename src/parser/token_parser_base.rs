@@ -38,9 +38,9 @@
 
 use sqlparser::dialect::MsSqlDialect;
 use sqlparser::keywords::Keyword;
-use sqlparser::tokenizer::{Token, TokenWithSpan, Tokenizer};
+use sqlparser::tokenizer::{Location, Token, TokenWithSpan, Tokenizer};
 
-use super::identifier_utils::format_token;
+use super::identifier_utils::{format_token, format_token_sql};
 
 /// Base token parser with common helper methods for T-SQL parsing.
 ///
@@ -50,6 +50,10 @@ use super::identifier_utils::format_token;
 pub struct TokenParser {
     tokens: Vec<TokenWithSpan>,
     pos: usize,
+    /// The tokenized SQL, when known, so text can be sliced from it verbatim
+    source: Option<String>,
+    /// Byte offset of each line of `source`, from [`compute_line_offsets`]
+    line_offsets: Vec<usize>,
 }
 
 impl TokenParser {
@@ -62,14 +66,24 @@ impl TokenParser {
             .tokenize_with_location()
             .ok()?;
 
-        Some(Self { tokens, pos: 0 })
+        Some(Self {
+            tokens,
+            pos: 0,
+            source: Some(sql.to_string()),
+            line_offsets: compute_line_offsets(sql),
+        })
     }
 
     /// Create a new TokenParser with pre-tokenized tokens.
     ///
     /// Useful when tokens have already been obtained and re-tokenization is not needed.
     pub fn from_tokens(tokens: Vec<TokenWithSpan>) -> Self {
-        Self { tokens, pos: 0 }
+        Self {
+            tokens,
+            pos: 0,
+            source: None,
+            line_offsets: Vec::new(),
+        }
     }
 
     // ========================================================================
@@ -359,6 +373,84 @@ impl TokenParser {
         }
     }
 
+    /// Parse a parameter default expression, up to the `,` or `)` that ends the parameter
+    /// or one of `stop_words` (e.g. `OUTPUT`, `READONLY`) at the top level.
+    ///
+    /// The expression is reproduced as written: literal shapes such as `0x00FF`, `N'it''s'`,
+    /// `-1.50` and the original whitespace are preserved.
+    pub fn parse_parameter_default(&mut self, stop_words: &[&str]) -> String {
+        let mut result = String::new();
+        let mut depth = 0usize;
+
+        while let Some(token) = self.current_token() {
+            match &token.token {
+                Token::LParen => depth += 1,
+                Token::RParen if depth == 0 => break,
+                Token::RParen => depth -= 1,
+                Token::Comma if depth == 0 => break,
+                Token::Word(w)
+                    if depth == 0
+                        && w.quote_style.is_none()
+                        && stop_words.iter().any(|s| w.value.eq_ignore_ascii_case(s)) =>
+                {
+                    break;
+                }
+                _ => {}
+            }
+            result.push_str(&format_token_sql(&token.token));
+            self.advance();
+        }
+
+        result.trim().to_string()
+    }
+
+    /// Parse an `ON PARTITIONS (1, 3 TO 5)` clause, as used by per-partition
+    /// DATA_COMPRESSION options, returning the partition numbers with ranges expanded.
+    ///
+    /// Returns `None` (position unchanged) if the clause is not present.
+    pub fn parse_on_partitions(&mut self) -> Option<Vec<u32>> {
+        // SQL Server supports at most 15,000 partitions
+        const MAX_PARTITION: u64 = 15_000;
+
+        let start = self.pos;
+        self.skip_whitespace();
+        if self.expect_keyword(Keyword::ON).is_some() {
+            self.skip_whitespace();
+            if self.expect_word_ci("PARTITIONS").is_some() {
+                self.skip_whitespace();
+                if self.expect_token(&Token::LParen).is_some() {
+                    let mut partitions = Vec::new();
+                    loop {
+                        self.skip_whitespace();
+                        if self.expect_token(&Token::RParen).is_some() {
+                            return Some(partitions);
+                        }
+                        if self.expect_token(&Token::Comma).is_some() {
+                            continue;
+                        }
+                        let Some(first) = self.parse_positive_integer() else {
+                            break;
+                        };
+                        self.skip_whitespace();
+                        let last = if self.expect_keyword(Keyword::TO).is_some() {
+                            self.skip_whitespace();
+                            match self.parse_positive_integer() {
+                                Some(last) => last,
+                                None => break,
+                            }
+                        } else {
+                            first
+                        };
+                        partitions.extend((first..=last.min(MAX_PARTITION)).map(|n| n as u32));
+                    }
+                }
+            }
+        }
+
+        self.pos = start;
+        None
+    }
+
     // ========================================================================
     // Token string conversion
     // ========================================================================
@@ -373,12 +465,21 @@ impl TokenParser {
 
     /// Convert a range of tokens to a string.
     ///
-    /// Concatenates tokens from `start_pos` to `end_pos` (exclusive).
+    /// Returns the source text of tokens `start_pos` to `end_pos` (exclusive) verbatim,
+    /// so string literals keep their `N` prefix and doubled quotes. Parsers built with
+    /// [`TokenParser::from_tokens`] have no source and concatenate the tokens instead.
     pub fn tokens_to_string(&self, start_pos: usize, end_pos: usize) -> String {
-        self.tokens[start_pos..end_pos]
-            .iter()
-            .map(|t| format_token(&t.token))
-            .collect()
+        let tokens = &self.tokens[start_pos..end_pos];
+        if let (Some(source), Some(first), Some(last)) =
+            (&self.source, tokens.first(), tokens.last())
+        {
+            let start = line_location_to_offset(source, &self.line_offsets, first.span.start);
+            let end = line_location_to_offset(source, &self.line_offsets, last.span.end);
+            if let Some(text) = source.get(start..end) {
+                return text.to_string();
+            }
+        }
+        tokens.iter().map(|t| format_token(&t.token)).collect()
     }
 
     // ========================================================================
@@ -434,6 +535,37 @@ impl TokenParser {
         }
     }
 
+    /// Consume a parenthesized section and return the text between the parentheses,
+    /// trimmed.
+    ///
+    /// Position should be at the opening parenthesis. After this call, position will
+    /// be after the closing parenthesis (or at the end if it is unbalanced).
+    /// Returns `None` if not at a left parenthesis.
+    pub fn consume_parenthesized_contents(&mut self) -> Option<String> {
+        if !self.check_token(&Token::LParen) {
+            return None;
+        }
+        self.advance();
+
+        let start_pos = self.pos;
+        let mut depth = 1;
+        while !self.is_at_end() {
+            if self.check_token(&Token::LParen) {
+                depth += 1;
+            } else if self.check_token(&Token::RParen) {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+            self.advance();
+        }
+        let end_pos = self.pos;
+        self.advance();
+
+        Some(self.tokens_to_string(start_pos, end_pos).trim().to_string())
+    }
+
     /// Consume a parenthesized expression and return its contents as a string.
     ///
     /// Position should be at the opening parenthesis. After this call,
@@ -619,10 +751,86 @@ impl TokenParser {
     }
 }
 
+/// Compute byte offsets for each line in the source text.
+/// Returns a vector where index i contains the byte offset where line (i+1) starts.
+pub fn compute_line_offsets(sql: &str) -> Vec<usize> {
+    let mut offsets = vec![0]; // Line 1 starts at offset 0
+    for (i, ch) in sql.char_indices() {
+        if ch == '\n' {
+            // Next line starts after this newline
+            offsets.push(i + 1);
+        }
+    }
+    offsets
+}
+
+/// Convert a (1-based line, 1-based column) Location to a byte offset.
+///
+/// The column is taken as a byte count, which is exact for ASCII lines; use
+/// [`line_location_to_offset`] when the line may hold other characters.
+pub fn location_to_byte_offset(line_offsets: &[usize], line: u64, column: u64) -> usize {
+    if line == 0 || line as usize > line_offsets.len() {
+        return 0;
+    }
+    let line_start = line_offsets[(line - 1) as usize];
+    // Column is 1-based, so subtract 1 to get offset within line
+    line_start + (column.saturating_sub(1) as usize)
+}
+
+/// Byte offset in `source` of a tokenizer location (1-based line, and 1-based column
+/// counted in characters)
+pub fn location_to_offset(source: &str, location: Location) -> usize {
+    line_location_to_offset(source, &compute_line_offsets(source), location)
+}
+
+/// [`location_to_offset`] with the line offsets of `source` computed once by the caller
+pub fn line_location_to_offset(source: &str, line_offsets: &[usize], location: Location) -> usize {
+    if location.line as usize > line_offsets.len() {
+        return source.len();
+    }
+    let line_start = location_to_byte_offset(line_offsets, location.line, 1);
+    let column = location.column.saturating_sub(1) as usize;
+    source[line_start..]
+        .char_indices()
+        .nth(column)
+        .map_or(source.len(), |(i, _)| line_start + i)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_on_partitions() {
+        let mut parser = TokenParser::new(" ON PARTITIONS (1, 3 TO 5), X").unwrap();
+        assert_eq!(parser.parse_on_partitions(), Some(vec![1, 3, 4, 5]));
+        assert!(parser.check_token(&Token::Comma));
+
+        let mut parser = TokenParser::new(" ON [PRIMARY]").unwrap();
+        assert_eq!(parser.parse_on_partitions(), None);
+        assert_eq!(parser.pos(), 0);
+    }
+
+    #[test]
+    fn test_parse_parameter_default() {
+        let mut parser = TokenParser::new("0x00FF, @b").unwrap();
+        assert_eq!(parser.parse_parameter_default(&[]), "0x00FF");
+        assert!(parser.check_token(&Token::Comma));
+
+        let mut parser = TokenParser::new(" N'it''s' OUTPUT").unwrap();
+        assert_eq!(parser.parse_parameter_default(&["OUTPUT"]), "N'it''s'");
+
+        let mut parser = TokenParser::new("-1.50)").unwrap();
+        assert_eq!(parser.parse_parameter_default(&[]), "-1.50");
+        assert!(parser.check_token(&Token::RParen));
+
+        let mut parser = TokenParser::new("DATEADD(DAY,  -1, '2020-01-01') AS").unwrap();
+        assert_eq!(
+            parser.parse_parameter_default(&["AS"]),
+            "DATEADD(DAY,  -1, '2020-01-01')"
+        );
+    }
+
     #[test]
     fn test_new_creates_parser() {
         let parser = TokenParser::new("SELECT * FROM Users");
@@ -777,4 +985,31 @@ mod tests {
         assert!(s.contains("dbo"));
         assert!(s.contains("Users"));
     }
+
+    #[test]
+    fn test_tokens_to_string_is_verbatim() {
+        let sql = "x = N'O''Brien'\n  + 'é''s'  -- note\nnext";
+        let parser = TokenParser::new(sql).unwrap();
+        let end = parser
+            .tokens()
+            .iter()
+            .position(|t| matches!(&t.token, Token::Word(w) if w.value == "next"))
+            .unwrap();
+        assert_eq!(
+            parser.tokens_to_string(0, end),
+            "x = N'O''Brien'\n  + 'é''s'  -- note\n"
+        );
+    }
+
+    #[test]
+    fn test_tokens_to_string_after_non_ascii_on_later_line() {
+        let sql = "a\n'é' + b\nc";
+        let parser = TokenParser::new(sql).unwrap();
+        let b = parser
+            .tokens()
+            .iter()
+            .position(|t| matches!(&t.token, Token::Word(w) if w.value == "b"))
+            .unwrap();
+        assert_eq!(parser.tokens_to_string(b, b + 1), "b");
+    }
 }
@@ -17,6 +17,12 @@
 //! ```sql
 //! ALTER TRIGGER [schema].[name] ON [schema].[table] AFTER UPDATE AS ...
 //! ```
+//!
+//! ENABLE/DISABLE TRIGGER:
+//! ```sql
+//! DISABLE TRIGGER [schema].[name] ON [schema].[table]
+//! ENABLE TRIGGER ALL ON [schema].[table]
+//! ```
 
 use sqlparser::keywords::Keyword;
 use sqlparser::tokenizer::{Token, TokenWithSpan};
@@ -26,7 +32,8 @@ use super::token_parser_base::TokenParser;
 /// Result of parsing a trigger definition using tokens
 #[derive(Debug, Clone, Default)]
 pub struct TokenParsedTrigger {
-    /// Schema name of the trigger (defaults to "dbo" if not specified)
+    /// Schema name of the trigger (the parent's schema if not specified, as DML triggers
+    /// always live in their parent's schema)
     pub schema: String,
     /// Trigger name
     pub name: String,
@@ -44,6 +51,17 @@ pub struct TokenParsedTrigger {
     pub trigger_type: u8,
 }
 
+/// Result of parsing an ENABLE/DISABLE TRIGGER statement using tokens
+#[derive(Debug, Clone, Default)]
+pub struct TokenParsedTriggerState {
+    /// Names of the affected triggers (without schema); empty for `ALL`
+    pub trigger_names: Vec<String>,
+    /// `(schema, name)` of the parent table/view; `None` for `ON DATABASE` / `ON ALL SERVER`
+    pub parent: Option<(String, String)>,
+    /// True for ENABLE TRIGGER, false for DISABLE TRIGGER
+    pub is_enabled: bool,
+}
+
 /// Token-based trigger definition parser
 pub struct TriggerTokenParser {
     base: TokenParser,
@@ -95,9 +113,18 @@ impl TriggerTokenParser {
         self.base.advance();
         self.base.skip_whitespace();
 
-        // Parse trigger name (schema-qualified)
-        let (trigger_schema, trigger_name) = self.base.parse_schema_qualified_name()?;
+        // Parse trigger name, remembering whether it was schema-qualified
+        let first = self.base.parse_identifier()?;
         self.base.skip_whitespace();
+        let (trigger_schema, trigger_name) = if self.base.check_token(&Token::Period) {
+            self.base.advance();
+            self.base.skip_whitespace();
+            let name = self.base.parse_identifier()?;
+            self.base.skip_whitespace();
+            (Some(first), name)
+        } else {
+            (None, first)
+        };
 
         // Expect ON keyword
         if !self.base.check_keyword(Keyword::ON) {
@@ -110,11 +137,23 @@ impl TriggerTokenParser {
         let (parent_schema, parent_name) = self.base.parse_schema_qualified_name()?;
         self.base.skip_whitespace();
 
+        // Skip trigger options (WITH NATIVE_COMPILATION, SCHEMABINDING, EXECUTE AS ...);
+        // they are read from the definition when building the model
+        if self.base.check_keyword(Keyword::WITH) {
+            while !self.base.is_at_end()
+                && !self.base.check_word_ci("INSTEAD")
+                && !self.base.check_keyword(Keyword::AFTER)
+                && !self.base.check_keyword(Keyword::FOR)
+            {
+                self.base.advance();
+            }
+        }
+
         // Parse trigger type and events
         let (trigger_type, is_insert, is_update, is_delete) = self.parse_trigger_clause()?;
 
         Some(TokenParsedTrigger {
-            schema: trigger_schema,
+            schema: trigger_schema.unwrap_or_else(|| parent_schema.clone()),
             name: trigger_name,
             parent_schema,
             parent_name,
@@ -125,6 +164,69 @@ impl TriggerTokenParser {
         })
     }
 
+    /// Parse `{ENABLE | DISABLE} TRIGGER {name [, ...] | ALL} ON {object | DATABASE | ALL SERVER}`
+    pub fn parse_trigger_state(&mut self) -> Option<TokenParsedTriggerState> {
+        self.base.skip_whitespace();
+
+        let is_enabled = if self.base.check_word_ci("ENABLE") {
+            true
+        } else if self.base.check_word_ci("DISABLE") {
+            false
+        } else {
+            return None;
+        };
+        self.base.advance();
+        self.base.skip_whitespace();
+
+        if !self.base.check_keyword(Keyword::TRIGGER) {
+            return None;
+        }
+        self.base.advance();
+        self.base.skip_whitespace();
+
+        // Trigger names (comma-separated) or ALL
+        let mut trigger_names = Vec::new();
+        if self.base.check_keyword(Keyword::ALL) {
+            self.base.advance();
+            self.base.skip_whitespace();
+        } else {
+            loop {
+                // DML triggers always live in their parent's schema, so only the name matters
+                let (_, name) = self.base.parse_schema_qualified_name()?;
+                trigger_names.push(name);
+                self.base.skip_whitespace();
+
+                if self.base.check_token(&Token::Comma) {
+                    self.base.advance();
+                    self.base.skip_whitespace();
+                } else {
+                    break;
+                }
+            }
+        }
+
+        if !self.base.check_keyword(Keyword::ON) {
+            return None;
+        }
+        self.base.advance();
+        self.base.skip_whitespace();
+
+        // DDL triggers (ON DATABASE / ON ALL SERVER) are not part of the model
+        let parent = if self.base.check_keyword(Keyword::DATABASE)
+            || self.base.check_keyword(Keyword::ALL)
+        {
+            None
+        } else {
+            Some(self.base.parse_schema_qualified_name()?)
+        };
+
+        Some(TokenParsedTriggerState {
+            trigger_names,
+            parent,
+            is_enabled,
+        })
+    }
+
     /// Parse trigger clause: (INSTEAD OF | AFTER | FOR) (INSERT|UPDATE|DELETE)[,...]
     /// Returns (trigger_type, is_insert, is_update, is_delete)
     fn parse_trigger_clause(&mut self) -> Option<(u8, bool, bool, bool)> {
@@ -209,6 +311,14 @@ pub fn parse_create_trigger_tokens_with_tokens(
     parser.parse_create_trigger()
 }
 
+/// Parse ENABLE/DISABLE TRIGGER from pre-tokenized tokens
+pub fn parse_trigger_state_tokens_with_tokens(
+    tokens: Vec<TokenWithSpan>,
+) -> Option<TokenParsedTriggerState> {
+    let mut parser = TriggerTokenParser::from_tokens(tokens);
+    parser.parse_trigger_state()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -282,6 +392,16 @@ mod tests {
         assert!(!result.is_delete);
     }
 
+    #[test]
+    fn test_create_trigger_with_options() {
+        let sql = "CREATE TRIGGER [dbo].[TR_Native] ON [dbo].[Orders] WITH NATIVE_COMPILATION, SCHEMABINDING, EXECUTE AS OWNER AFTER INSERT AS BEGIN ATOMIC WITH (LANGUAGE = N'English') SELECT 1 END";
+        let result = parse_create_trigger_tokens(sql).unwrap();
+        assert_eq!(result.name, "TR_Native");
+        assert_eq!(result.parent_name, "Orders");
+        assert!(result.is_insert);
+        assert_eq!(result.trigger_type, 2);
+    }
+
     #[test]
     fn test_create_or_alter_trigger() {
         let sql = "CREATE OR ALTER TRIGGER [dbo].[TR_Test] ON [dbo].[TestTable] AFTER INSERT AS BEGIN SELECT 1 END";
@@ -335,6 +455,15 @@ mod tests {
         assert_eq!(result.parent_name, "Users");
     }
 
+    #[test]
+    fn test_create_trigger_unqualified_name_takes_parent_schema() {
+        let sql = "CREATE TRIGGER TR_Orders ON [sales].[Orders] AFTER INSERT AS BEGIN SELECT 1 END";
+        let result = parse_create_trigger_tokens(sql).unwrap();
+        assert_eq!(result.schema, "sales");
+        assert_eq!(result.name, "TR_Orders");
+        assert_eq!(result.parent_schema, "sales");
+    }
+
     #[test]
     fn test_create_trigger_multiline() {
         let sql = r#"
@@ -493,4 +622,49 @@ END
         assert!(!result.is_delete);
         assert_eq!(result.trigger_type, 3);
     }
+
+    // ========================================================================
+    // ENABLE/DISABLE TRIGGER tests
+    // ========================================================================
+
+    fn parse_trigger_state(sql: &str) -> Option<TokenParsedTriggerState> {
+        TriggerTokenParser::new(sql)?.parse_trigger_state()
+    }
+
+    #[test]
+    fn test_disable_trigger() {
+        let result =
+            parse_trigger_state("DISABLE TRIGGER [Sales].[TR_Orders_Audit] ON [Sales].[Orders];")
+                .unwrap();
+        assert_eq!(result.trigger_names, vec!["TR_Orders_Audit"]);
+        assert_eq!(
+            result.parent,
+            Some(("Sales".to_string(), "Orders".to_string()))
+        );
+        assert!(!result.is_enabled);
+    }
+
+    #[test]
+    fn test_enable_trigger_list_and_all() {
+        let result = parse_trigger_state("enable trigger TR_A, [TR_B] on Users").unwrap();
+        assert_eq!(result.trigger_names, vec!["TR_A", "TR_B"]);
+        assert_eq!(
+            result.parent,
+            Some(("dbo".to_string(), "Users".to_string()))
+        );
+        assert!(result.is_enabled);
+
+        let result = parse_trigger_state("DISABLE TRIGGER ALL ON [dbo].[Users]").unwrap();
+        assert!(result.trigger_names.is_empty());
+        assert!(!result.is_enabled);
+    }
+
+    #[test]
+    fn test_disable_ddl_trigger_has_no_parent() {
+        let result = parse_trigger_state("DISABLE TRIGGER [TR_Ddl] ON DATABASE").unwrap();
+        assert_eq!(result.parent, None);
+        let result = parse_trigger_state("DISABLE TRIGGER ALL ON ALL SERVER").unwrap();
+        assert_eq!(result.parent, None);
+        assert!(parse_trigger_state("DISABLE TRIGGER [TR_X]").is_none());
+    }
 }
@@ -1,5 +1,6 @@
 //! T-SQL parser using sqlparser-rs
 
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, LazyLock};
 
@@ -15,7 +16,7 @@ use sqlparser::tokenizer::{Token, TokenWithSpan, Tokenizer};
 use super::column_parser::{parse_column_definition_tokens, TokenParsedColumn};
 use super::constraint_parser::{
     parse_alter_table_add_constraint_tokens_with_tokens, parse_alter_table_name_tokens_with_tokens,
-    parse_table_constraint_tokens, TokenParsedConstraint,
+    parse_table_constraint_tokens, ConstraintIndexOptions, TokenParsedConstraint,
 };
 use super::extended_property_parser::parse_extended_property_tokens_with_tokens;
 use super::fulltext_parser::{
@@ -26,15 +27,19 @@ use super::function_parser::{
     parse_create_function_full_with_tokens, parse_create_function_tokens_with_tokens,
     TokenParsedFunctionType,
 };
-use super::identifier_utils::format_token_sql;
+use super::identifier_utils::{escape_identifier, format_token_sql};
+use super::imperative_parser::find_imperative_statement;
 use super::index_parser::{
     extract_index_filter_predicate_tokenized, parse_create_columnstore_index_tokens_with_tokens,
-    parse_create_index_tokens_with_tokens, ParsedIndexColumn,
+    parse_create_index_tokens_with_tokens, parse_inline_index_tokens, ParsedIndexColumn,
+    TokenParsedIndex,
 };
+use super::permissions_file::{permissions_to_sql, PERMISSIONS_EXTENSION};
 use super::preprocess_parser::preprocess_tsql_tokens;
 use super::procedure_parser::{
     parse_alter_procedure_tokens_with_tokens, parse_create_procedure_tokens_with_tokens,
 };
+use super::schema_parser::split_create_schema_elements;
 use super::security_parser::{
     parse_alter_role_membership_tokens_with_tokens, parse_create_role_tokens_with_tokens,
     parse_create_user_tokens_with_tokens, parse_permission_tokens_with_tokens,
@@ -43,6 +48,9 @@ use super::security_parser::{
 use super::sequence_parser::{
     parse_alter_sequence_tokens_with_tokens, parse_create_sequence_tokens_with_tokens,
 };
+use super::source_text::{decode_source, source_hash, FileEncoding, LineEndings};
+use super::sqlcmd::apply_conditionals;
+use super::statement_classifier::{classify_statement, ParseFallback};
 use super::statement_parser::{
     try_parse_alter_view_tokens_with_tokens, try_parse_cte_dml_tokens_with_tokens,
     try_parse_drop_tokens_with_tokens, try_parse_generic_create_tokens_with_tokens,
@@ -50,14 +58,16 @@ use super::statement_parser::{
 };
 use super::storage_parser::{
     parse_filegroup_tokens_with_tokens, parse_partition_function_tokens_with_tokens,
-    parse_partition_scheme_tokens_with_tokens,
+    parse_partition_scheme_tokens_with_tokens, parse_table_compression_tokens,
 };
 use super::synonym_parser::parse_create_synonym_tokens_with_tokens;
 use super::table_type_parser::parse_create_table_type_tokens_with_tokens;
-use super::trigger_parser::parse_create_trigger_tokens_with_tokens;
+use super::trigger_parser::{
+    parse_create_trigger_tokens_with_tokens, parse_trigger_state_tokens_with_tokens,
+};
 use super::tsql_dialect::ExtendedTsqlDialect;
 use crate::error::SqlPackageError;
-use crate::util::{contains_ci, starts_with_ci};
+use crate::util::{check_cancelled, contains_ci, long_path, starts_with_ci, CancellationToken};
 
 /// Sentinel value used to represent MAX in binary types (since sqlparser expects u64)
 pub const BINARY_MAX_SENTINEL: u64 = 2_147_483_647;
@@ -237,6 +247,7 @@ pub enum ExtractedTableConstraint {
         name: String,
         columns: Vec<ExtractedConstraintColumn>,
         is_clustered: bool,
+        options: ConstraintIndexOptions,
     },
     ForeignKey {
         name: String,
@@ -248,6 +259,7 @@ pub enum ExtractedTableConstraint {
         name: String,
         columns: Vec<ExtractedConstraintColumn>,
         is_clustered: bool,
+        options: ConstraintIndexOptions,
     },
     Check {
         name: String,
@@ -262,12 +274,19 @@ pub struct ParsedStatement {
     pub statement: Option<Statement>,
     /// Source file path
     pub source_file: PathBuf,
+    /// 1-based line of the statement's batch in the source file
+    pub start_line: usize,
+    /// Whitespace trimmed from the start of the batch, before `start_line`
+    pub leading_whitespace: String,
     /// Original SQL text (Arc-shared to avoid deep copies into element definitions)
     pub sql_text: Arc<str>,
     /// Fallback-parsed statement type (for procedures/functions that sqlparser can't handle)
     pub fallback_type: Option<FallbackStatementType>,
     /// Default constraints extracted during preprocessing (T-SQL DEFAULT FOR syntax)
     pub extracted_defaults: Vec<ExtractedDefaultConstraint>,
+    /// Why the statement was kept raw or skipped by the parsing fallback chain, see
+    /// [`ParseFallback`]
+    pub warning: Option<String>,
 }
 
 /// A column in a full-text index with optional language specification
@@ -277,6 +296,8 @@ pub struct ExtractedFullTextColumn {
     pub name: String,
     /// Language ID (e.g., 1033 for English)
     pub language_id: Option<u32>,
+    /// Column holding the document type of a varbinary column (TYPE COLUMN)
+    pub type_column: Option<String>,
 }
 
 /// Statement types that require fallback parsing due to sqlparser limitations
@@ -285,6 +306,8 @@ pub enum FallbackStatementType {
     Procedure {
         schema: String,
         name: String,
+        /// Group number of a numbered procedure (`CREATE PROCEDURE [dbo].[P];2`)
+        number: Option<u32>,
     },
     Function {
         schema: String,
@@ -309,6 +332,8 @@ pub enum FallbackStatementType {
         filter_predicate: Option<String>,
         /// Data compression type (NONE, ROW, PAGE, etc.)
         data_compression: Option<String>,
+        /// Per-partition data compression (`DATA_COMPRESSION = PAGE ON PARTITIONS (1 TO 3)`)
+        partition_compression: Vec<(u32, String)>,
         /// Whether PAD_INDEX is ON (applies fill factor to intermediate pages)
         is_padded: bool,
     },
@@ -323,6 +348,8 @@ pub enum FallbackStatementType {
         columns: Vec<String>,
         /// Data compression type (COLUMNSTORE or COLUMNSTORE_ARCHIVE)
         data_compression: Option<String>,
+        /// Per-partition data compression (`ON PARTITIONS (...)`)
+        partition_compression: Vec<(u32, String)>,
         /// Filter predicate for filtered NONCLUSTERED columnstore indexes
         filter_predicate: Option<String>,
     },
@@ -337,12 +364,11 @@ pub enum FallbackStatementType {
         catalog: Option<String>,
         /// Change tracking mode (AUTO, MANUAL, OFF)
         change_tracking: Option<String>,
+        /// Stoplist (OFF, SYSTEM or a stoplist name)
+        stoplist: Option<String>,
     },
     /// Full-text catalog (CREATE FULLTEXT CATALOG ...)
-    FullTextCatalog {
-        name: String,
-        is_default: bool,
-    },
+    FullTextCatalog { name: String, is_default: bool },
     Sequence {
         schema: String,
         name: String,
@@ -406,6 +432,12 @@ pub enum FallbackStatementType {
         history_table_schema: Option<String>,
         /// History table name for temporal tables (from HISTORY_TABLE option)
         history_table_name: Option<String>,
+        /// Indexes declared inline in the table body (`INDEX [IX] NONCLUSTERED (...)`)
+        indexes: Vec<TokenParsedIndex>,
+        /// Table-level DATA_COMPRESSION option (NONE, ROW, PAGE, ...)
+        data_compression: Option<String>,
+        /// Per-partition DATA_COMPRESSION options from `ON PARTITIONS (...)`
+        partition_compression: Vec<(u32, String)>,
     },
     /// Generic fallback for any statement that can't be parsed
     RawStatement {
@@ -430,10 +462,19 @@ pub enum FallbackStatementType {
         /// Trigger type: 2 = AFTER/FOR, 3 = INSTEAD OF
         trigger_type: u8,
     },
-    /// Extended property from sp_addextendedproperty
-    ExtendedProperty {
-        property: ExtractedExtendedProperty,
+    /// ENABLE/DISABLE TRIGGER ... ON table/view
+    TriggerState {
+        /// Affected trigger names; empty for `ALL`
+        trigger_names: Vec<String>,
+        /// Schema of the parent table/view
+        parent_schema: String,
+        /// Name of the parent table/view
+        parent_name: String,
+        /// True for ENABLE TRIGGER, false for DISABLE TRIGGER
+        is_enabled: bool,
     },
+    /// Extended property from sp_addextendedproperty
+    ExtendedProperty { property: ExtractedExtendedProperty },
     /// Constraint added via ALTER TABLE ... ADD CONSTRAINT
     AlterTableAddConstraint {
         table_schema: String,
@@ -485,10 +526,7 @@ pub enum FallbackStatementType {
         default_schema: Option<String>,
     },
     /// CREATE ROLE statement
-    CreateRole {
-        name: String,
-        owner: Option<String>,
-    },
+    CreateRole { name: String, owner: Option<String> },
     /// ALTER ROLE ... ADD/DROP MEMBER statement
     AlterRoleMembership {
         role: String,
@@ -528,9 +566,12 @@ impl ParsedStatement {
         Self {
             statement: Some(statement),
             source_file,
+            start_line: 1,
+            leading_whitespace: String::new(),
             sql_text,
             fallback_type: None,
             extracted_defaults: Vec::new(),
+            warning: None,
         }
     }
 
@@ -544,9 +585,12 @@ impl ParsedStatement {
         Self {
             statement: Some(statement),
             source_file,
+            start_line: 1,
+            leading_whitespace: String::new(),
             sql_text,
             fallback_type: None,
             extracted_defaults,
+            warning: None,
         }
     }
 
@@ -559,11 +603,20 @@ impl ParsedStatement {
         Self {
             statement: None,
             source_file,
+            start_line: 1,
+            leading_whitespace: String::new(),
             sql_text,
             fallback_type: Some(fallback_type),
             extracted_defaults: Vec::new(),
+            warning: None,
         }
     }
+
+    /// First and last line of the statement's batch in the source file (1-based)
+    pub fn line_range(&self) -> (usize, usize) {
+        let lines = self.sql_text.lines().count().max(1);
+        (self.start_line, self.start_line + lines - 1)
+    }
 }
 
 /// Minimum number of files to benefit from parallel processing.
@@ -572,38 +625,174 @@ const PARALLEL_THRESHOLD: usize = 8;
 
 /// Parse multiple SQL files, using parallel processing for larger file sets
 pub fn parse_sql_files(files: &[PathBuf]) -> Result<Vec<ParsedStatement>> {
+    parse_sql_files_with_variables(files, &HashMap::new())
+}
+
+/// Parse multiple SQL files, evaluating SQLCMD `:IF` blocks against `variables` first
+pub fn parse_sql_files_with_variables(
+    files: &[PathBuf],
+    variables: &HashMap<String, String>,
+) -> Result<Vec<ParsedStatement>> {
+    parse_sql_files_with_options(
+        files,
+        &ParseOptions {
+            variables: variables.clone(),
+            ..ParseOptions::default()
+        },
+    )
+}
+
+/// How project SQL files are read before parsing
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// SQLCMD variable values for `:IF` blocks, keyed by [`super::variable_key`]
+    pub variables: HashMap<String, String>,
+    /// Line endings of the source text, and so of every script property
+    pub line_endings: LineEndings,
+    /// Encoding of files without a byte order mark (sniffed when not set)
+    pub default_encoding: Option<FileEncoding>,
+    /// Largest SQL file to read, in bytes (unlimited when not set)
+    pub max_file_size: Option<u64>,
+    /// Longest batch to parse, in bytes (unlimited when not set)
+    pub max_statement_length: Option<usize>,
+    /// Stop before the next file once cancelled
+    pub cancellation: Option<CancellationToken>,
+    /// What to do with statements no parser understands
+    pub fallback: ParseFallback,
+}
+
+/// Statements parsed from SQL files, with the files' hashes
+#[derive(Debug, Default)]
+pub struct ParsedFiles {
+    pub statements: Vec<ParsedStatement>,
+    /// [`source_hash`] of each file's bytes as they were read for parsing
+    pub source_hashes: BTreeMap<PathBuf, String>,
+}
+
+/// Parse multiple SQL files with `options`
+pub fn parse_sql_files_with_options(
+    files: &[PathBuf],
+    options: &ParseOptions,
+) -> Result<Vec<ParsedStatement>> {
+    Ok(parse_sql_files_with_hashes(files, options)?.statements)
+}
+
+/// Parse multiple SQL files with `options`, hashing each file's bytes as it is read
+pub fn parse_sql_files_with_hashes(
+    files: &[PathBuf],
+    options: &ParseOptions,
+) -> Result<ParsedFiles> {
     // Pre-allocate with estimate of ~2 statements per file
-    let mut all_statements = Vec::with_capacity(files.len() * 2);
+    let mut parsed = ParsedFiles {
+        statements: Vec::with_capacity(files.len() * 2),
+        source_hashes: BTreeMap::new(),
+    };
 
     if files.len() >= PARALLEL_THRESHOLD {
         // Parse files in parallel using rayon for larger projects
-        let results: Vec<Result<Vec<ParsedStatement>>> =
-            files.par_iter().map(|file| parse_sql_file(file)).collect();
+        let results: Vec<Result<(Vec<ParsedStatement>, String)>> = files
+            .par_iter()
+            .map(|file| {
+                check_cancelled(options.cancellation.as_ref())?;
+                parse_sql_file_with_options(file, options)
+            })
+            .collect();
 
         // Combine results, propagating the first error if any
-        for result in results {
-            all_statements.extend(result?);
+        for (file, result) in files.iter().zip(results) {
+            let (statements, hash) = result?;
+            parsed.statements.extend(statements);
+            parsed.source_hashes.insert(file.clone(), hash);
         }
     } else {
         // Sequential processing for small projects (avoids rayon overhead)
         for file in files {
-            let statements = parse_sql_file(file)?;
-            all_statements.extend(statements);
+            check_cancelled(options.cancellation.as_ref())?;
+            let (statements, hash) = parse_sql_file_with_options(file, options)?;
+            parsed.statements.extend(statements);
+            parsed.source_hashes.insert(file.clone(), hash);
         }
     }
 
-    Ok(all_statements)
+    Ok(parsed)
 }
 
 /// Parse a single SQL file
 pub fn parse_sql_file(path: &Path) -> Result<Vec<ParsedStatement>> {
-    let content = std::fs::read_to_string(path).map_err(|e| SqlPackageError::SqlFileReadError {
+    Ok(parse_sql_file_with_options(path, &ParseOptions::default())?.0)
+}
+
+/// Parse a SQL file, returning its statements and the [`source_hash`] of its bytes
+fn parse_sql_file_with_options(
+    path: &Path,
+    options: &ParseOptions,
+) -> Result<(Vec<ParsedStatement>, String)> {
+    if let Some(limit) = options.max_file_size {
+        let size = std::fs::metadata(long_path(path))
+            .map_err(|e| SqlPackageError::SqlFileReadError {
+                path: path.to_path_buf(),
+                source: e,
+            })?
+            .len();
+        if size > limit {
+            return Err(SqlPackageError::LimitExceeded {
+                message: format!(
+                    "{} is {} bytes, more than the maximum file size of {} bytes",
+                    path.display(),
+                    size,
+                    limit
+                ),
+            }
+            .into());
+        }
+    }
+
+    let read_error = |e| SqlPackageError::SqlFileReadError {
         path: path.to_path_buf(),
         source: e,
-    })?;
+    };
+    let bytes = std::fs::read(long_path(path)).map_err(read_error)?;
+    let hash = source_hash(&bytes);
+    let content = decode_source(&bytes, options.default_encoding).map_err(read_error)?;
+    let is_permissions_file = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(PERMISSIONS_EXTENSION));
+    let content = if is_permissions_file {
+        permissions_to_sql(&content).map_err(|(line, message)| SqlPackageError::SqlParseError {
+            path: path.to_path_buf(),
+            line,
+            message,
+        })?
+    } else {
+        content
+    };
+    let content = options.line_endings.apply(&content);
+    let content = apply_conditionals(&content, &options.variables, path)?;
+
+    let statements = parse_content(
+        &content,
+        path,
+        options.max_statement_length,
+        options.fallback,
+    )?;
+    Ok((statements, hash))
+}
+
+/// Parse SQL text that did not come from a project file (e.g., stdin).
+/// `path` is only used to label statements and parse errors.
+pub fn parse_sql_content(content: &str, path: &Path) -> Result<Vec<ParsedStatement>> {
+    parse_content(content, path, None, ParseFallback::Fail)
+}
 
+/// Parse SQL text, rejecting batches longer than `max_statement_length` bytes
+fn parse_content(
+    content: &str,
+    path: &Path,
+    max_statement_length: Option<usize>,
+    fallback: ParseFallback,
+) -> Result<Vec<ParsedStatement>> {
     // Strip UTF-8 BOM if present
-    let content = content.strip_prefix('\u{FEFF}').unwrap_or(&content);
+    let content = content.strip_prefix('\u{FEFF}').unwrap_or(content);
 
     // Split on GO statements (batch separator)
     let batches = split_batches(content);
@@ -617,61 +806,191 @@ pub fn parse_sql_file(path: &Path) -> Result<Vec<ParsedStatement>> {
         if trimmed.is_empty() {
             continue;
         }
-
-        // Preprocess T-SQL to handle syntax that sqlparser doesn't support
-        let preprocessed = preprocess_tsql(trimmed);
-
-        // Allocate the SQL text once as Arc<str> — shared across all statements from this batch
-        let sql_arc: Arc<str> = Arc::from(trimmed);
-
-        match Parser::parse_sql(&dialect, &preprocessed.sql) {
-            Ok(parsed) => {
-                for stmt in parsed {
-                    // Use the original SQL text, not preprocessed, for storage
-                    // but include any extracted defaults
-                    if preprocessed.extracted_defaults.is_empty() {
-                        statements.push(ParsedStatement::from_statement(
-                            stmt,
-                            path.to_path_buf(),
-                            Arc::clone(&sql_arc),
-                        ));
-                    } else {
-                        statements.push(ParsedStatement::from_statement_with_defaults(
-                            stmt,
-                            path.to_path_buf(),
-                            Arc::clone(&sql_arc),
-                            preprocessed.extracted_defaults.clone(),
-                        ));
-                    }
-                }
+        // Line of the first non-blank text, where the trimmed batch starts
+        let leading_whitespace =
+            &batch.content[..batch.content.len() - batch.content.trim_start().len()];
+        let start_line = batch.start_line + leading_whitespace.matches('\n').count();
+        let first_new = statements.len();
+        if let Some(limit) = max_statement_length.filter(|limit| trimmed.len() > *limit) {
+            return Err(SqlPackageError::LimitExceeded {
+                message: format!(
+                    "the statement at line {} of {} is {} bytes, more than the maximum statement length of {} bytes",
+                    start_line,
+                    path.display(),
+                    trimmed.len(),
+                    limit
+                ),
             }
-            Err(e) => {
-                // Try fallback parsing for procedures and functions
-                // sqlparser has limited T-SQL support for these statement types
-                if let Some(fallback) = try_fallback_parse(trimmed) {
-                    statements.push(ParsedStatement::from_fallback(
+            .into());
+        }
+
+        // CREATE SCHEMA with contained elements is parsed as one statement per element
+        if contains_ci(trimmed, "CREATE SCHEMA") {
+            if let Some(elements) = split_create_schema_elements(trimmed) {
+                for element in elements {
+                    parse_batch(
+                        &element.sql,
+                        start_line + element.line_offset,
+                        path,
+                        &dialect,
                         fallback,
+                        &mut statements,
+                    )?;
+                }
+                continue;
+            }
+        }
+
+        parse_batch(
+            trimmed,
+            start_line,
+            path,
+            &dialect,
+            fallback,
+            &mut statements,
+        )?;
+        for statement in &mut statements[first_new..] {
+            statement.leading_whitespace = leading_whitespace.to_string();
+        }
+    }
+
+    Ok(statements)
+}
+
+/// Parse one batch (or one statement split out of a batch) and append its statements.
+/// `start_line` is the 1-based line of `sql` in the source file, used for error locations.
+fn parse_batch(
+    sql: &str,
+    start_line: usize,
+    path: &Path,
+    dialect: &ExtendedTsqlDialect,
+    fallback: ParseFallback,
+    statements: &mut Vec<ParsedStatement>,
+) -> Result<()> {
+    // sp_rename and ALTER DATABASE change the database in place and have no model
+    // representation; point at the declarative alternative instead of failing to parse
+    if contains_ci(sql, "sp_rename") || contains_ci(sql, "DATABASE") {
+        if let Some(found) = find_imperative_statement(sql) {
+            return Err(SqlPackageError::ImperativeStatement {
+                path: path.to_path_buf(),
+                line: start_line + found.line - 1,
+                statement: found.statement.to_string(),
+                suggestion: found.suggestion.to_string(),
+            }
+            .into());
+        }
+    }
+
+    // Preprocess T-SQL to handle syntax that sqlparser doesn't support
+    let preprocessed = preprocess_tsql(sql);
+
+    // Allocate the SQL text once as Arc<str> — shared across all statements from this SQL
+    let sql_arc: Arc<str> = Arc::from(sql);
+    let first_new = statements.len();
+
+    match Parser::parse_sql(dialect, &preprocessed.sql) {
+        Ok(parsed) => {
+            for stmt in parsed {
+                // Use the original SQL text, not preprocessed, for storage
+                // but include any extracted defaults
+                if preprocessed.extracted_defaults.is_empty() {
+                    statements.push(ParsedStatement::from_statement(
+                        stmt,
                         path.to_path_buf(),
-                        sql_arc,
+                        Arc::clone(&sql_arc),
                     ));
                 } else {
-                    // Calculate absolute line number from batch offset and error line
-                    let error_msg = e.to_string();
-                    let relative_line = extract_line_from_error(&error_msg).unwrap_or(1);
-                    let absolute_line = batch.start_line + relative_line - 1;
-
+                    statements.push(ParsedStatement::from_statement_with_defaults(
+                        stmt,
+                        path.to_path_buf(),
+                        Arc::clone(&sql_arc),
+                        preprocessed.extracted_defaults.clone(),
+                    ));
+                }
+            }
+        }
+        Err(e) => {
+            // Try fallback parsing for procedures and functions
+            // sqlparser has limited T-SQL support for these statement types
+            if let Some(fallback) = try_fallback_parse(sql) {
+                statements.push(ParsedStatement::from_fallback(
+                    fallback,
+                    path.to_path_buf(),
+                    sql_arc,
+                ));
+            } else {
+                // Calculate absolute line number from batch offset and error line
+                let error_msg = e.to_string();
+                let relative_line = extract_line_from_error(&error_msg).unwrap_or(1);
+                let absolute_line = start_line + relative_line - 1;
+
+                // Last links of the fallback chain: keep the statement raw, or skip it
+                let object = match fallback {
+                    ParseFallback::Fail => None,
+                    _ => classify_statement(sql),
+                };
+                let statement = match object {
+                    Some((object_type, schema, name))
+                        if object_type == "TABLE" || object_type == "VIEW" =>
+                    {
+                        let warning = format!(
+                            "{} [{}].[{}] could not be parsed ({}), kept as written ({}:{})",
+                            object_type.to_lowercase(),
+                            escape_identifier(&schema),
+                            escape_identifier(&name),
+                            error_msg,
+                            path.display(),
+                            start_line
+                        );
+                        Some((object_type, schema, name, warning))
+                    }
+                    object if fallback == ParseFallback::Skip => {
+                        let what = object.map_or("statement".to_string(), |(t, s, n)| {
+                            format!(
+                                "{} [{}].[{}]",
+                                t.to_lowercase(),
+                                escape_identifier(&s),
+                                escape_identifier(&n)
+                            )
+                        });
+                        let warning = format!(
+                            "{} could not be parsed ({}), skipped ({}:{})",
+                            what,
+                            error_msg,
+                            path.display(),
+                            start_line
+                        );
+                        Some(("Skipped".to_string(), String::new(), String::new(), warning))
+                    }
+                    _ => None,
+                };
+                let Some((object_type, schema, name, warning)) = statement else {
                     return Err(SqlPackageError::SqlParseError {
                         path: path.to_path_buf(),
                         line: absolute_line,
                         message: error_msg,
                     }
                     .into());
-                }
+                };
+                let mut statement = ParsedStatement::from_fallback(
+                    FallbackStatementType::RawStatement {
+                        object_type,
+                        schema,
+                        name,
+                    },
+                    path.to_path_buf(),
+                    sql_arc,
+                );
+                statement.warning = Some(warning);
+                statements.push(statement);
             }
         }
     }
 
-    Ok(statements)
+    for statement in &mut statements[first_new..] {
+        statement.start_line = start_line;
+    }
+    Ok(())
 }
 
 /// Try to parse a statement using fallback token-based parsing.
@@ -692,16 +1011,24 @@ fn try_fallback_parse(sql: &str) -> Option<FallbackStatementType> {
         || contains_ci(sql, "CREATE PROC")
         || contains_ci(sql, "CREATE OR ALTER PROC")
     {
-        if let Some((schema, name)) = parse_create_procedure_tokens_with_tokens(tk()) {
-            return Some(FallbackStatementType::Procedure { schema, name });
+        if let Some(proc) = parse_create_procedure_tokens_with_tokens(tk()) {
+            return Some(FallbackStatementType::Procedure {
+                schema: proc.schema,
+                name: proc.name,
+                number: proc.number,
+            });
         }
     }
 
     // Check for ALTER PROCEDURE or ALTER PROC (T-SQL shorthand)
     // Note: sqlparser doesn't support ALTER PROCEDURE, so we use fallback
     if contains_ci(sql, "ALTER PROCEDURE") || contains_ci(sql, "ALTER PROC") {
-        if let Some((schema, name)) = parse_alter_procedure_tokens_with_tokens(tk()) {
-            return Some(FallbackStatementType::Procedure { schema, name });
+        if let Some(proc) = parse_alter_procedure_tokens_with_tokens(tk()) {
+            return Some(FallbackStatementType::Procedure {
+                schema: proc.schema,
+                name: proc.name,
+                number: proc.number,
+            });
         }
     }
 
@@ -748,6 +1075,7 @@ fn try_fallback_parse(sql: &str) -> Option<FallbackStatementType> {
                 is_clustered: parsed.is_clustered,
                 columns: parsed.columns,
                 data_compression: parsed.data_compression,
+                partition_compression: parsed.partition_compression,
                 filter_predicate: parsed.filter_predicate,
             });
         }
@@ -774,6 +1102,7 @@ fn try_fallback_parse(sql: &str) -> Option<FallbackStatementType> {
                 .map(|c| ExtractedFullTextColumn {
                     name: c.name,
                     language_id: c.language_id,
+                    type_column: c.type_column,
                 })
                 .collect();
             return Some(FallbackStatementType::FullTextIndex {
@@ -783,6 +1112,7 @@ fn try_fallback_parse(sql: &str) -> Option<FallbackStatementType> {
                 key_index: parsed.key_index,
                 catalog: parsed.catalog,
                 change_tracking: parsed.change_tracking,
+                stoplist: parsed.stoplist,
             });
         }
     }
@@ -818,6 +1148,16 @@ fn try_fallback_parse(sql: &str) -> Option<FallbackStatementType> {
         }
     }
 
+    // ALTER DATABASE ... ADD FILE / ADD LOG FILE from storage scripts: database files are
+    // not modeled, only the filegroups they belong to
+    if contains_ci(sql, "ALTER DATABASE")
+        && (contains_ci(sql, "ADD FILE") || contains_ci(sql, "ADD LOG FILE"))
+    {
+        return Some(FallbackStatementType::SkippedSecurityStatement {
+            statement_type: "DATABASE_FILE".to_string(),
+        });
+    }
+
     // Check for CREATE PARTITION FUNCTION
     if contains_ci(sql, "CREATE PARTITION FUNCTION") {
         if let Some(parsed) = parse_partition_function_tokens_with_tokens(tk()) {
@@ -939,6 +1279,23 @@ fn try_fallback_parse(sql: &str) -> Option<FallbackStatementType> {
         }
     }
 
+    // Check for ENABLE/DISABLE TRIGGER (DDL trigger targets are not modeled and skipped)
+    if contains_ci(sql, "ENABLE TRIGGER") || contains_ci(sql, "DISABLE TRIGGER") {
+        if let Some(parsed) = parse_trigger_state_tokens_with_tokens(tk()) {
+            return Some(match parsed.parent {
+                Some((parent_schema, parent_name)) => FallbackStatementType::TriggerState {
+                    trigger_names: parsed.trigger_names,
+                    parent_schema,
+                    parent_name,
+                    is_enabled: parsed.is_enabled,
+                },
+                None => FallbackStatementType::SkippedSecurityStatement {
+                    statement_type: "DDL_TRIGGER_STATE".to_string(),
+                },
+            });
+        }
+    }
+
     // Check for security statements — route USER, ROLE, PERMISSION, ROLE_MEMBERSHIP
     // to actual parsers; remaining categories (LOGIN, CERTIFICATE, etc.) are silently skipped
     if let Some(result) = try_security_statement_dispatch_with_tokens(sql, &tk) {
@@ -1290,6 +1647,7 @@ fn convert_token_parsed_constraint(parsed: TokenParsedConstraint) -> ExtractedTa
             name,
             columns,
             is_clustered,
+            options,
         } => ExtractedTableConstraint::PrimaryKey {
             name,
             columns: columns
@@ -1300,11 +1658,13 @@ fn convert_token_parsed_constraint(parsed: TokenParsedConstraint) -> ExtractedTa
                 })
                 .collect(),
             is_clustered,
+            options,
         },
         TokenParsedConstraint::Unique {
             name,
             columns,
             is_clustered,
+            options,
         } => ExtractedTableConstraint::Unique {
             name,
             columns: columns
@@ -1315,6 +1675,7 @@ fn convert_token_parsed_constraint(parsed: TokenParsedConstraint) -> ExtractedTa
                 })
                 .collect(),
             is_clustered,
+            options,
         },
         TokenParsedConstraint::ForeignKey {
             name,
@@ -1736,6 +2097,7 @@ fn extract_index_info_with_tokens(
             fill_factor: parsed.fill_factor,
             filter_predicate: parsed.filter_predicate,
             data_compression: parsed.data_compression,
+            partition_compression: parsed.partition_compression,
             is_padded: parsed.is_padded,
         });
     }
@@ -1803,6 +2165,7 @@ fn extract_index_info_with_tokens(
         fill_factor,
         filter_predicate,
         data_compression,
+        partition_compression: Vec::new(),
         is_padded,
     })
 }
@@ -1868,12 +2231,14 @@ fn extract_table_structure(sql: &str) -> Option<FallbackStatementType> {
     let after_body = &remaining_sql[body_len_with_parens..];
 
     // Parse columns, constraints, and PERIOD FOR SYSTEM_TIME from the table body
-    let (columns, constraints, period) = parse_table_body(&table_body, &name);
+    let (columns, constraints, period, indexes) = parse_table_body(&table_body, &schema, &name);
 
     // Extract temporal table options from WITH clause after the closing parenthesis
     let (is_system_versioned, history_table_schema, history_table_name) =
         extract_system_versioning_options(after_body);
 
+    let compression = parse_table_compression_tokens(sql);
+
     Some(FallbackStatementType::Table {
         schema,
         name,
@@ -1886,9 +2251,99 @@ fn extract_table_structure(sql: &str) -> Option<FallbackStatementType> {
         is_system_versioned,
         history_table_schema,
         history_table_name,
+        indexes,
+        data_compression: compression.data_compression,
+        partition_compression: compression.partition_compression,
     })
 }
 
+/// Default, CHECK and computed column scripts of a CREATE TABLE statement, verbatim
+/// from its source
+///
+/// sqlparser's AST re-renders expressions (`DEFAULT ( 0 )` becomes `(0)`), so tables it
+/// parses take these scripts from the token-based table parser instead.
+#[derive(Debug, Default)]
+pub struct TableScripts {
+    columns: Vec<ExtractedTableColumn>,
+    /// Names and expressions of the CHECK constraints the token parser found at table level
+    checks: Vec<(String, String)>,
+}
+
+impl TableScripts {
+    fn column(&self, name: &str) -> Option<&ExtractedTableColumn> {
+        self.columns
+            .iter()
+            .find(|c| c.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Expression of a column's inline DEFAULT
+    pub fn default_value(&self, column: &str) -> Option<&str> {
+        self.column(column)?.default_value.as_deref()
+    }
+
+    /// Expression of a computed column
+    pub fn computed_expression(&self, column: &str) -> Option<&str> {
+        self.column(column)?.computed_expression.as_deref()
+    }
+
+    /// Expression of a CHECK constraint, found by its explicit name or, for an unnamed
+    /// constraint, by the AST's rendering of the same expression
+    pub fn check_expression(&self, name: Option<&str>, rendered: &str) -> Option<&str> {
+        let column_checks = self.columns.iter().filter_map(|c| {
+            Some((
+                c.check_constraint_name.as_deref().unwrap_or_default(),
+                c.check_expression.as_deref()?,
+            ))
+        });
+        let mut checks = self
+            .checks
+            .iter()
+            .map(|(n, e)| (n.as_str(), e.as_str()))
+            .chain(column_checks);
+        match name {
+            Some(name) => checks.find(|(n, _)| n.eq_ignore_ascii_case(name)),
+            None => {
+                let rendered = canonical_expression(rendered);
+                checks.find(|(_, e)| canonical_expression(e) == rendered)
+            }
+        }
+        .map(|(_, expression)| expression)
+    }
+}
+
+/// An expression without whitespace, case or quote escaping, for comparing renderings
+fn canonical_expression(expression: &str) -> String {
+    expression
+        .replace("''", "'")
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Extract the expression scripts of a CREATE TABLE statement
+pub fn extract_table_scripts(sql: &str) -> TableScripts {
+    match extract_table_structure(sql) {
+        Some(FallbackStatementType::Table {
+            columns,
+            constraints,
+            ..
+        }) => TableScripts {
+            columns,
+            checks: constraints
+                .into_iter()
+                .filter_map(|c| match c {
+                    ExtractedTableConstraint::Check { name, expression } => {
+                        Some((name, expression))
+                    }
+                    _ => None,
+                })
+                .collect(),
+        },
+        _ => TableScripts::default(),
+    }
+}
+
 /// Extract SYSTEM_VERSIONING options from the WITH clause after a CREATE TABLE body.
 /// Returns (is_system_versioned, history_table_schema, history_table_name).
 fn extract_system_versioning_options(after_body: &str) -> (bool, Option<String>, Option<String>) {
@@ -1956,11 +2411,13 @@ struct ParsedSystemTimePeriod {
 /// Parse table body to extract columns, constraints, and PERIOD FOR SYSTEM_TIME
 fn parse_table_body(
     body: &str,
+    table_schema: &str,
     table_name: &str,
 ) -> (
     Vec<ExtractedTableColumn>,
     Vec<ExtractedTableConstraint>,
     ParsedSystemTimePeriod,
+    Vec<TokenParsedIndex>,
 ) {
     // Split by top-level commas (not inside parentheses)
     let parts = split_by_top_level_comma(body);
@@ -1969,6 +2426,7 @@ fn parse_table_body(
     let mut columns = Vec::with_capacity(parts.len());
     let mut constraints = Vec::with_capacity(parts.len().min(4));
     let mut period = ParsedSystemTimePeriod::default();
+    let mut indexes = Vec::new();
 
     for part in parts {
         let trimmed = part.trim();
@@ -1984,6 +2442,15 @@ fn parse_table_body(
             continue;
         }
 
+        // Check for an inline table-level index: INDEX [IX] NONCLUSTERED ([col])
+        if starts_with_ci(trimmed, "INDEX ") || starts_with_ci(trimmed, "INDEX[") {
+            if let Some(index) = parse_inline_index_tokens(trimmed, table_schema, table_name, None)
+            {
+                indexes.push(index);
+            }
+            continue;
+        }
+
         // Check if this is a table-level constraint
         if starts_with_ci(trimmed, "CONSTRAINT")
             || starts_with_ci(trimmed, "PRIMARY KEY")
@@ -1995,14 +2462,48 @@ fn parse_table_body(
                 constraints.push(constraint);
             }
         } else {
-            // This is a column definition
-            if let Some(column) = parse_column_definition(trimmed) {
+            // This is a column definition, possibly with a column-level inline index
+            let (column_def, index_def) = split_column_inline_index(trimmed);
+            if let Some(column) = parse_column_definition(&column_def) {
+                if let Some(index) = index_def.and_then(|def| {
+                    parse_inline_index_tokens(&def, table_schema, table_name, Some(&column.name))
+                }) {
+                    indexes.push(index);
+                }
                 columns.push(column);
             }
         }
     }
 
-    (columns, constraints, period)
+    (columns, constraints, period, indexes)
+}
+
+/// Split a column definition at a top-level `INDEX` keyword
+/// (`[Col] INT NOT NULL INDEX [IX] NONCLUSTERED`), returning the column definition
+/// and the inline index declaration, if any.
+fn split_column_inline_index(def: &str) -> (String, Option<String>) {
+    if !contains_ci(def, "INDEX") {
+        return (def.to_string(), None);
+    }
+    let dialect = MsSqlDialect {};
+    let Ok(tokens) = Tokenizer::new(&dialect, def).tokenize() else {
+        return (def.to_string(), None);
+    };
+
+    let mut depth = 0usize;
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::LParen => depth += 1,
+            Token::RParen => depth = depth.saturating_sub(1),
+            Token::Word(w) if depth == 0 && w.keyword == Keyword::INDEX && i > 0 => {
+                let column_def: String = tokens[..i].iter().map(format_token_sql).collect();
+                let index_def: String = tokens[i..].iter().map(format_token_sql).collect();
+                return (column_def.trim().to_string(), Some(index_def));
+            }
+            _ => {}
+        }
+    }
+    (def.to_string(), None)
 }
 
 /// Parse PERIOD FOR SYSTEM_TIME ([start_col], [end_col])
@@ -2145,8 +2646,9 @@ fn split_by_comma_or_constraint_tokens(
                     }
                 }
 
-                // Mark that we're in a constraint if this is CONSTRAINT keyword
-                if w.keyword == Keyword::CONSTRAINT {
+                // Mark that we're in a constraint if this is CONSTRAINT keyword; an inline
+                // INDEX may also be followed by UNIQUE, which must not start a new part
+                if w.keyword == Keyword::CONSTRAINT || w.keyword == Keyword::INDEX {
                     in_constraint = true;
                 }
 
@@ -2439,6 +2941,26 @@ fn split_batches(content: &str) -> Vec<Batch<'_>> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_extract_table_scripts() {
+        let scripts = extract_table_scripts(
+            "CREATE TABLE [dbo].[T] ([Id] INT NOT NULL DEFAULT ( 0 ), [Calc] AS ([Id]  *  2), \
+             CONSTRAINT [CK_T] CHECK ([Id]  >=  0), CHECK ([Id] < N'x''y'))",
+        );
+        assert_eq!(scripts.default_value("id"), Some("( 0 )"));
+        assert_eq!(scripts.computed_expression("Calc"), Some("([Id]  *  2)"));
+        assert_eq!(
+            scripts.check_expression(Some("CK_T"), "[Id] >= 0"),
+            Some("[Id]  >=  0")
+        );
+        // Unnamed constraints are matched by the AST's rendering of the expression
+        assert_eq!(
+            scripts.check_expression(None, "[Id] < N'x'y'"),
+            Some("[Id] < N'x''y'")
+        );
+        assert_eq!(scripts.check_expression(None, "[Id] > 1"), None);
+    }
+
     #[test]
     fn test_split_batches() {
         let sql = "CREATE TABLE t1 (id INT)\nGO\nCREATE TABLE t2 (id INT)";
@@ -2828,6 +3350,21 @@ CREATE TABLE [dbo].[Products] (
         );
     }
 
+    #[test]
+    fn test_fallback_skip_alter_database_add_file() {
+        for sql in [
+            "ALTER DATABASE [$(DatabaseName)] ADD FILE (NAME = [UserData], FILENAME = 'u.ndf') TO FILEGROUP [USERDATA];",
+            "ALTER DATABASE [$(DatabaseName)] ADD LOG FILE (NAME = [Log2], FILENAME = 'log2.ldf');",
+        ] {
+            match try_fallback_parse(sql) {
+                Some(FallbackStatementType::SkippedSecurityStatement { statement_type }) => {
+                    assert_eq!(statement_type, "DATABASE_FILE");
+                }
+                other => panic!("Expected SkippedSecurityStatement, got {:?}", other),
+            }
+        }
+    }
+
     #[test]
     fn test_fallback_skip_alter_database_scoped_configuration_set() {
         let sql = "ALTER DATABASE SCOPED CONFIGURATION SET MAXDOP = 4;";
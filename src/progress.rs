@@ -0,0 +1,42 @@
+//! Progress reported by [`build_dacpac`](crate::build_dacpac) as its stages finish
+//!
+//! Hosts such as IDE extensions and services set
+//! [`BuildOptions::on_progress`](crate::BuildOptions::on_progress) to show how far a long
+//! build has got.
+
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// A finished stage of a build
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildProgress {
+    /// The project was read and lists this many SQL files
+    ProjectLoaded { sql_files: usize },
+    /// The SQL files were parsed into this many statements
+    SqlParsed { statements: usize },
+    /// The model was built with this many elements (after filters)
+    ModelBuilt { elements: usize },
+    /// A dacpac was written, the main one first and then one per profile
+    DacpacWritten { path: PathBuf },
+}
+
+/// Callback receiving [`BuildProgress`], called on the thread running the build
+#[derive(Clone)]
+pub struct ProgressCallback(Arc<dyn Fn(&BuildProgress) + Send + Sync>);
+
+impl ProgressCallback {
+    pub fn new(callback: impl Fn(&BuildProgress) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(callback))
+    }
+
+    pub fn report(&self, progress: &BuildProgress) {
+        (self.0)(progress)
+    }
+}
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressCallback")
+    }
+}
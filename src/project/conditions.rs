@@ -0,0 +1,299 @@
+//! Conditional compilation of project files
+//!
+//! Supports two ways of leaving environment-specific files out of a build:
+//! - MSBuild `Condition` attributes on `Build` items and their `ItemGroup`, e.g.
+//!   `<Build Include="Azure\*.sql" Condition="'$(TargetPlatform)' == 'Sql160'" />`
+//! - A file-level pragma in the leading `--` comments of a .sql file, e.g.
+//!   `-- @build:exclude-from(Sql130, Sql140)`
+
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use super::SqlServerVersion;
+use crate::util::long_path;
+
+const EXCLUDE_FROM_PRAGMA: &str = "@build:exclude-from(";
+
+/// Evaluate an MSBuild condition. `$(Name)` references are expanded with `lookup`
+/// (unknown properties expand to an empty string). Supports quoted strings, `==`, `!=`,
+/// `!`, `and`, `or` and parentheses; comparisons are case-insensitive as in MSBuild.
+///
+/// Returns `None` if the condition uses syntax outside that subset.
+pub(crate) fn evaluate_condition(
+    condition: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Option<bool> {
+    let tokens = tokenize_condition(condition)?;
+    let mut parser = ConditionParser {
+        tokens: &tokens,
+        pos: 0,
+        lookup,
+    };
+    let result = parser.parse_or()?;
+    (parser.pos == tokens.len()).then_some(result)
+}
+
+/// Platforms listed in `-- @build:exclude-from(...)` pragmas in the file's leading comments
+pub(crate) fn excluded_platforms(path: &Path) -> Vec<String> {
+    let Ok(file) = std::fs::File::open(long_path(path)) else {
+        return Vec::new();
+    };
+
+    let mut platforms = Vec::new();
+    for line in BufReader::new(file).lines().map_while(|l| l.ok()) {
+        let line = line.trim_start_matches('\u{feff}').trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some(comment) = line.strip_prefix("--") else {
+            break;
+        };
+        if let Some(start) = comment.find(EXCLUDE_FROM_PRAGMA) {
+            let args = &comment[start + EXCLUDE_FROM_PRAGMA.len()..];
+            if let Some(end) = args.find(')') {
+                platforms.extend(
+                    args[..end]
+                        .split(',')
+                        .map(|p| p.trim().to_string())
+                        .filter(|p| !p.is_empty()),
+                );
+            }
+        }
+    }
+    platforms
+}
+
+/// Whether a file's pragmas exclude it from builds targeting `target_platform`
+pub(crate) fn is_excluded_for_platform(path: &Path, target_platform: SqlServerVersion) -> bool {
+    excluded_platforms(path).iter().any(|p| {
+        p.parse::<SqlServerVersion>()
+            .is_ok_and(|version| version == target_platform)
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum ConditionToken {
+    Str(String),
+    Word(String),
+    LParen,
+    RParen,
+    Not,
+    Eq,
+    Ne,
+}
+
+fn tokenize_condition(condition: &str) -> Option<Vec<ConditionToken>> {
+    let mut tokens = Vec::new();
+    let mut chars = condition.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '\'' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next()? {
+                        '\'' => break,
+                        c => value.push(c),
+                    }
+                }
+                tokens.push(ConditionToken::Str(value));
+            }
+            '(' => {
+                chars.next();
+                tokens.push(ConditionToken::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(ConditionToken::RParen);
+            }
+            '=' => {
+                chars.next();
+                if chars.next()? != '=' {
+                    return None;
+                }
+                tokens.push(ConditionToken::Eq);
+            }
+            '!' => {
+                chars.next();
+                if chars.peek() == Some(&'=') {
+                    chars.next();
+                    tokens.push(ConditionToken::Ne);
+                } else {
+                    tokens.push(ConditionToken::Not);
+                }
+            }
+            c if c.is_alphanumeric() || c == '$' || c == '_' => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_alphanumeric() || matches!(c, '$' | '_' | '.' | '(' | ')') {
+                        // `$(Name)` may appear unquoted; keep its parentheses in the word
+                        let open = word.matches('(').count() > word.matches(')').count();
+                        if c == ')' && !open {
+                            break;
+                        }
+                        if c == '(' && !word.ends_with('$') {
+                            break;
+                        }
+                        word.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(ConditionToken::Word(word));
+            }
+            _ => return None,
+        }
+    }
+
+    Some(tokens)
+}
+
+struct ConditionParser<'a> {
+    tokens: &'a [ConditionToken],
+    pos: usize,
+    lookup: &'a dyn Fn(&str) -> Option<String>,
+}
+
+impl ConditionParser<'_> {
+    fn parse_or(&mut self) -> Option<bool> {
+        let mut result = self.parse_and()?;
+        while self.eat_keyword("or") {
+            let rhs = self.parse_and()?;
+            result = result || rhs;
+        }
+        Some(result)
+    }
+
+    fn parse_and(&mut self) -> Option<bool> {
+        let mut result = self.parse_unary()?;
+        while self.eat_keyword("and") {
+            let rhs = self.parse_unary()?;
+            result = result && rhs;
+        }
+        Some(result)
+    }
+
+    fn parse_unary(&mut self) -> Option<bool> {
+        match self.tokens.get(self.pos)? {
+            ConditionToken::Not => {
+                self.pos += 1;
+                Some(!self.parse_unary()?)
+            }
+            ConditionToken::LParen => {
+                self.pos += 1;
+                let result = self.parse_or()?;
+                if self.tokens.get(self.pos)? != &ConditionToken::RParen {
+                    return None;
+                }
+                self.pos += 1;
+                Some(result)
+            }
+            _ => self.parse_comparison(),
+        }
+    }
+
+    fn parse_comparison(&mut self) -> Option<bool> {
+        let lhs = self.parse_operand()?;
+        match self.tokens.get(self.pos) {
+            Some(ConditionToken::Eq) => {
+                self.pos += 1;
+                let rhs = self.parse_operand()?;
+                Some(lhs.eq_ignore_ascii_case(&rhs))
+            }
+            Some(ConditionToken::Ne) => {
+                self.pos += 1;
+                let rhs = self.parse_operand()?;
+                Some(!lhs.eq_ignore_ascii_case(&rhs))
+            }
+            _ => match lhs.to_lowercase().as_str() {
+                "true" => Some(true),
+                "false" => Some(false),
+                _ => None,
+            },
+        }
+    }
+
+    fn parse_operand(&mut self) -> Option<String> {
+        let value = match self.tokens.get(self.pos)? {
+            ConditionToken::Str(s) | ConditionToken::Word(s) => self.expand(s),
+            _ => return None,
+        };
+        self.pos += 1;
+        Some(value)
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        match self.tokens.get(self.pos) {
+            Some(ConditionToken::Word(w)) if w.eq_ignore_ascii_case(keyword) => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Expand `$(Name)` property references
+    fn expand(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find("$(") {
+            result.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            match after.find(')') {
+                Some(end) => {
+                    result.push_str(&(self.lookup)(after[..end].trim()).unwrap_or_default());
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    result.push_str(&rest[start..]);
+                    rest = "";
+                }
+            }
+        }
+        result.push_str(rest);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "TargetPlatform" => Some("Sql160".to_string()),
+            "Configuration" => Some("Release".to_string()),
+            _ => None,
+        }
+    }
+
+    fn eval(condition: &str) -> Option<bool> {
+        evaluate_condition(condition, &lookup)
+    }
+
+    #[test]
+    fn test_evaluate_condition() {
+        assert_eq!(eval("'$(TargetPlatform)' == 'Sql160'"), Some(true));
+        assert_eq!(eval("'$(TargetPlatform)' == 'sql130'"), Some(false));
+        assert_eq!(eval(" '$(Configuration)' != 'Debug' "), Some(true));
+        assert_eq!(eval("'$(Missing)' == ''"), Some(true));
+        assert_eq!(
+            eval("'$(TargetPlatform)' == 'Sql130' or ('$(Configuration)' == 'Release' and !false)"),
+            Some(true)
+        );
+        assert_eq!(eval("$(TargetPlatform) == Sql160"), Some(true));
+        assert_eq!(eval("($(TargetPlatform) == Sql160)"), Some(true));
+    }
+
+    #[test]
+    fn test_evaluate_condition_unsupported_syntax() {
+        assert_eq!(eval("Exists('foo.sql')"), None);
+        assert_eq!(eval("'$(TargetPlatform)' == "), None);
+        assert_eq!(eval("'unterminated"), None);
+    }
+}
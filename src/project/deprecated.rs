@@ -0,0 +1,169 @@
+//! Deprecated feature rules: code that blocks raising the database compatibility level
+//!
+//! - `deprecated-type`: `text`, `ntext` and `image` columns, parameters and types
+//! - `old-style-join`: `*=` and `=*` outer joins, rejected since compatibility level 90
+//! - `set-rowcount`: `SET ROWCOUNT` in a view, procedure, function or trigger, which will
+//!   stop affecting INSERT, UPDATE and DELETE statements
+
+use super::lint::{relative_source, LintIssue};
+use super::SqlProject;
+use crate::model::{DatabaseModel, ModelElement};
+use crate::parser::lexer::{tokenize, Token};
+use crate::parser::{extract_function_parameters_tokens, extract_procedure_parameters_tokens};
+
+/// Data types replaced by `varchar(max)`, `nvarchar(max)` and `varbinary(max)`
+const DEPRECATED_TYPES: &[&str] = &["text", "ntext", "image"];
+
+/// Uses of deprecated features, in model order
+pub(super) fn check_deprecated(model: &DatabaseModel, project: &SqlProject) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    for (index, element) in model.elements.iter().enumerate() {
+        let (object_type, typed, body): (&'static str, Vec<(String, String)>, Option<&str>) =
+            match element {
+                ModelElement::Table(t) => (
+                    "Table",
+                    t.columns
+                        .iter()
+                        .map(|c| (format!("column [{}]", c.name), c.data_type.clone()))
+                        .collect(),
+                    None,
+                ),
+                ModelElement::UserDefinedType(u) => (
+                    "Table type",
+                    u.columns
+                        .iter()
+                        .map(|c| (format!("column [{}]", c.name), c.data_type.clone()))
+                        .collect(),
+                    None,
+                ),
+                ModelElement::ScalarType(s) => (
+                    "Data type",
+                    vec![("base type".to_string(), s.base_type.clone())],
+                    None,
+                ),
+                // Routine parameters are kept in the definition
+                ModelElement::Procedure(p) => (
+                    "Procedure",
+                    extract_procedure_parameters_tokens(&p.definition)
+                        .into_iter()
+                        .map(|p| (format!("parameter @{}", p.name), p.data_type))
+                        .collect(),
+                    Some(&p.definition),
+                ),
+                ModelElement::Function(f) => (
+                    "Function",
+                    extract_function_parameters_tokens(&f.definition)
+                        .into_iter()
+                        .map(|p| (format!("parameter {}", p.name), p.data_type))
+                        .collect(),
+                    Some(&f.definition),
+                ),
+                ModelElement::View(v) => ("View", Vec::new(), Some(&v.definition)),
+                ModelElement::Trigger(t) => ("Trigger", Vec::new(), Some(&t.definition)),
+                // Views the parser rejects, as it does `*=` joins
+                ModelElement::Raw(r) if r.sql_type == "SqlView" => {
+                    ("View", Vec::new(), Some(&r.definition))
+                }
+                _ => continue,
+            };
+
+        let mut issue = |rule: &'static str, message: String| {
+            issues.push(LintIssue::Code {
+                rule,
+                object_type,
+                name: element.full_name(),
+                message,
+                location: relative_source(model, index, project),
+            })
+        };
+        for (what, data_type) in typed {
+            let data_type = data_type.trim_matches(['[', ']']).to_lowercase();
+            if DEPRECATED_TYPES.contains(&data_type.as_str()) {
+                issue("deprecated-type", format!("{} is {}", what, data_type));
+            }
+        }
+        if let Some(body) = body {
+            let uses = body_uses(body);
+            if uses.old_style_join {
+                issue("old-style-join", "uses a *= or =* outer join".to_string());
+            }
+            if uses.set_rowcount {
+                issue("set-rowcount", "uses SET ROWCOUNT".to_string());
+            }
+        }
+    }
+    issues
+}
+
+/// Deprecated syntax found in a definition
+#[derive(Debug, Default, PartialEq)]
+struct BodyUses {
+    old_style_join: bool,
+    set_rowcount: bool,
+}
+
+/// Scan a definition's tokens, so comments and strings are ignored
+///
+/// `*=` is also compound assignment (`SET @Total *= 2`), so it only counts as a join in
+/// a WHERE, HAVING or ON condition.
+fn body_uses(body: &str) -> BodyUses {
+    let mut uses = BodyUses::default();
+    let Ok(tokens) = tokenize(body) else {
+        return uses;
+    };
+    let keyword = |token: &Token, name: &str| matches!(token, Token::Word(w) if w.quote_style.is_none() && w.value.eq_ignore_ascii_case(name));
+
+    let mut in_condition = false;
+    let mut previous: Option<&Token> = None;
+    for (i, token) in tokens.iter().enumerate() {
+        if matches!(token, Token::Whitespace(_)) {
+            continue;
+        }
+        if ["WHERE", "HAVING", "ON"].iter().any(|k| keyword(token, k)) {
+            in_condition = true;
+        } else if ["SET", "SELECT", "FROM", "VALUES"]
+            .iter()
+            .any(|k| keyword(token, k))
+        {
+            in_condition = false;
+        }
+
+        let next = tokens.get(i + 1);
+        let joined = matches!(
+            (token, next),
+            (Token::Mul, Some(Token::Eq)) | (Token::Eq, Some(Token::Mul))
+        );
+        if joined && in_condition {
+            uses.old_style_join = true;
+        }
+        if previous.is_some_and(|p| keyword(p, "SET")) && keyword(token, "ROWCOUNT") {
+            uses.set_rowcount = true;
+        }
+        previous = Some(token);
+    }
+    uses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_body_uses() {
+        let uses =
+            body_uses("SELECT o.Id FROM dbo.Orders o, dbo.Customers c WHERE o.CustomerId *= c.Id");
+        assert!(uses.old_style_join);
+        assert!(!uses.set_rowcount);
+
+        let uses = body_uses("SET ROWCOUNT 100; DELETE FROM dbo.Log WHERE Id =*Other");
+        assert!(uses.old_style_join);
+        assert!(uses.set_rowcount);
+
+        // Compound assignment, comments and strings are not deprecated syntax
+        let uses = body_uses(
+            "SET @Total *= 2; UPDATE dbo.Orders SET Total *= 2 WHERE Id = 1;\n\
+             -- SET ROWCOUNT 10\nSELECT 'a *= b', [SET ROWCOUNT]",
+        );
+        assert_eq!(uses, BodyUses::default());
+    }
+}
@@ -0,0 +1,477 @@
+//! Editing a .sqlproj in place
+//!
+//! Changes are spliced into the project file's text, so comments, formatting and the
+//! order of everything else stay as they were and version control shows only the
+//! lines that changed:
+//!
+//! ```ignore
+//! let mut editor = ProjectEditor::open(Path::new("Database.sqlproj"))?;
+//! editor.add_build_item(r"Tables\Orders.sql")?;
+//! editor.set_property("DefaultSchema", "sales")?;
+//! editor.save()?;
+//! ```
+
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::Result;
+use roxmltree::{Document, Node};
+
+use super::sqlproj_parser::parse_sqlproj;
+use crate::error::SqlPackageError;
+use crate::util::long_path;
+
+/// A .sqlproj file being edited
+#[derive(Debug, Clone)]
+pub struct ProjectEditor {
+    path: PathBuf,
+    content: String,
+}
+
+impl ProjectEditor {
+    /// Read a project file for editing
+    pub fn open(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(long_path(path)).map_err(|e| {
+            SqlPackageError::ProjectReadError {
+                path: path.to_path_buf(),
+                source: e,
+            }
+        })?;
+        let editor = Self {
+            path: path.to_path_buf(),
+            content,
+        };
+        editor.document()?;
+        Ok(editor)
+    }
+
+    /// Path of the project file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The project file's text, with any edits
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    /// `Include` values of the project's `Build` items, in file order
+    pub fn build_items(&self) -> Result<Vec<String>> {
+        let doc = self.document()?;
+        Ok(build_nodes(&doc)
+            .filter_map(|n| n.attribute("Include").map(str::to_string))
+            .collect())
+    }
+
+    /// Add a `<Build Include="..." />` item after the last `Build` item, or in a new
+    /// `ItemGroup` when there are none
+    ///
+    /// Returns `false` when an item already includes exactly this path (compared
+    /// case-insensitively, either slash).
+    pub fn add_build_item(&mut self, include: &str) -> Result<bool> {
+        let doc = self.document()?;
+        if build_nodes(&doc).any(|n| {
+            n.attribute("Include")
+                .is_some_and(|i| same_path(i, include))
+        }) {
+            return Ok(false);
+        }
+
+        let eol = self.line_ending();
+        let item = format!("<Build Include=\"{}\" />", escape_xml(include, true));
+        let edit = match build_nodes(&doc)
+            .filter(|n| n.attribute("Include").is_some())
+            .last()
+        {
+            Some(last) => {
+                let indent = self.indent_before(last.range().start);
+                (
+                    last.range().end..last.range().end,
+                    format!("{eol}{indent}{item}"),
+                )
+            }
+            None => self.new_group(&doc, "ItemGroup", &item)?,
+        };
+        self.splice(edit.0, &edit.1);
+        Ok(true)
+    }
+
+    /// Remove the `Build` item including exactly this path, and its `ItemGroup` if that
+    /// leaves the group empty
+    ///
+    /// Returns `false` when no item matches.
+    pub fn remove_build_item(&mut self, include: &str) -> Result<bool> {
+        let doc = self.document()?;
+        let Some(node) = build_nodes(&doc).find(|n| {
+            n.attribute("Include")
+                .is_some_and(|i| same_path(i, include))
+        }) else {
+            return Ok(false);
+        };
+        let group_start = node.parent().map(|p| p.range().start);
+        let range = self.line_range(node.range());
+        self.splice(range, "");
+
+        // Drop the ItemGroup too if nothing else is in it
+        let doc = self.document()?;
+        let empty_group = doc
+            .descendants()
+            .find(|n| Some(n.range().start) == group_start && n.has_tag_name("ItemGroup"))
+            .filter(|group| {
+                !group.children().any(|c| c.is_element() || c.is_comment())
+                    && group.attributes().len() == 0
+            })
+            .map(|group| group.range());
+        if let Some(range) = empty_group {
+            let range = self.line_range(range);
+            self.splice(range, "");
+        }
+        Ok(true)
+    }
+
+    /// Text of the first `PropertyGroup` property named `name`
+    pub fn property(&self, name: &str) -> Result<Option<String>> {
+        let doc = self.document()?;
+        Ok(property_node(&doc, name).map(|n| n.text().unwrap_or_default().to_string()))
+    }
+
+    /// Set a property, replacing the value of the first `PropertyGroup` property of that
+    /// name (the one the build reads) or adding it to the first unconditioned
+    /// `PropertyGroup`
+    pub fn set_property(&mut self, name: &str, value: &str) -> Result<()> {
+        let doc = self.document()?;
+        let value = escape_xml(value, false);
+        if let Some(node) = property_node(&doc, name) {
+            let edit = match node.first_child().filter(|c| c.is_text()) {
+                Some(text) if node.children().count() == 1 => (text.range(), value),
+                _ => (node.range(), format!("<{name}>{value}</{name}>")),
+            };
+            self.splice(edit.0, &edit.1);
+            return Ok(());
+        }
+
+        let eol = self.line_ending();
+        let property = format!("<{name}>{value}</{name}>");
+        let group = doc
+            .root_element()
+            .children()
+            .find(|n| n.has_tag_name("PropertyGroup") && n.attribute("Condition").is_none());
+        let edit = match group.and_then(|g| g.children().rev().find(Node::is_element)) {
+            Some(last) => {
+                let indent = self.indent_before(last.range().start);
+                (
+                    last.range().end..last.range().end,
+                    format!("{eol}{indent}{property}"),
+                )
+            }
+            None => self.new_group(&doc, "PropertyGroup", &property)?,
+        };
+        self.splice(edit.0, &edit.1);
+        Ok(())
+    }
+
+    /// Write the project file back
+    pub fn save(&self) -> Result<()> {
+        std::fs::write(long_path(&self.path), &self.content).map_err(|e| {
+            SqlPackageError::OutputWriteError {
+                path: self.path.clone(),
+                source: e,
+            }
+        })?;
+        Ok(())
+    }
+
+    fn document(&self) -> Result<Document<'_>> {
+        Ok(
+            Document::parse(&self.content).map_err(|e| SqlPackageError::ProjectParseError {
+                path: self.path.clone(),
+                source: e,
+            })?,
+        )
+    }
+
+    /// Insertion for a new `<tag>` group holding `child`, after the project's last
+    /// element (or at the start of an empty project)
+    fn new_group(&self, doc: &Document, tag: &str, child: &str) -> Result<(Range<usize>, String)> {
+        let eol = self.line_ending();
+        let root = doc.root_element();
+        let unit = self.indent_unit(doc);
+        if let Some(last) = root.children().rev().find(Node::is_element) {
+            let at = last.range().end;
+            let indent = self.indent_before(last.range().start);
+            return Ok((
+                at..at,
+                format!("{eol}{indent}<{tag}>{eol}{indent}{unit}{child}{eol}{indent}</{tag}>"),
+            ));
+        }
+
+        let close = self.content[..root.range().end]
+            .rfind("</")
+            .ok_or_else(|| SqlPackageError::InvalidProjectFormat {
+                message: "the Project element has no closing tag".to_string(),
+            })?;
+        let at = self.content[..close].rfind('\n').map_or(close, |i| i + 1);
+        Ok((
+            at..at,
+            format!("{unit}<{tag}>{eol}{unit}{unit}{child}{eol}{unit}</{tag}>{eol}"),
+        ))
+    }
+
+    /// Whitespace between the start of the line and `pos`
+    fn indent_before(&self, pos: usize) -> String {
+        let line_start = self.content[..pos].rfind('\n').map_or(0, |i| i + 1);
+        let prefix = &self.content[line_start..pos];
+        if prefix.trim().is_empty() {
+            prefix.to_string()
+        } else {
+            String::new()
+        }
+    }
+
+    /// One level of indentation, as used between the first group and its children
+    fn indent_unit(&self, doc: &Document) -> String {
+        doc.root_element()
+            .children()
+            .filter(|n| n.is_element())
+            .find_map(|group| {
+                let child = group.children().find(Node::is_element)?;
+                let outer = self.indent_before(group.range().start);
+                let inner = self.indent_before(child.range().start);
+                inner.strip_prefix(&outer).map(str::to_string)
+            })
+            .filter(|unit| !unit.is_empty())
+            .unwrap_or_else(|| "  ".to_string())
+    }
+
+    fn line_ending(&self) -> &'static str {
+        if self.content.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        }
+    }
+
+    /// `range` widened to whole lines when nothing else is on them
+    fn line_range(&self, range: Range<usize>) -> Range<usize> {
+        let line_start = self.content[..range.start].rfind('\n').map_or(0, |i| i + 1);
+        let rest = &self.content[range.end..];
+        let trailing = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+        let after = &rest[trailing..];
+        let newline = if after.starts_with("\r\n") {
+            2
+        } else {
+            usize::from(after.starts_with('\n'))
+        };
+        if self.content[line_start..range.start].trim().is_empty() && newline > 0 {
+            line_start..range.end + trailing + newline
+        } else {
+            range
+        }
+    }
+
+    fn splice(&mut self, range: Range<usize>, text: &str) {
+        self.content.replace_range(range, text);
+    }
+}
+
+/// Add SQL files to a project as `Build` items and save it
+///
+/// Paths are made relative to the project directory and written with backslashes, as
+/// Visual Studio does. Files the project already builds, e.g. through the default
+/// `**/*.sql` of an SDK-style project, are skipped. Returns the items added.
+pub fn add_sql_files(project_path: &Path, files: &[PathBuf]) -> Result<Vec<String>> {
+    let (editor, added) = stage_sql_files(project_path, files)?;
+    if !added.is_empty() {
+        editor.save()?;
+    }
+    Ok(added)
+}
+
+/// The `Build` items [`add_sql_files`] would add, leaving the project file unchanged
+pub fn sql_files_to_add(project_path: &Path, files: &[PathBuf]) -> Result<Vec<String>> {
+    stage_sql_files(project_path, files).map(|(_, added)| added)
+}
+
+/// An editor with the items for `files` added, and the items added
+fn stage_sql_files(project_path: &Path, files: &[PathBuf]) -> Result<(ProjectEditor, Vec<String>)> {
+    let project = parse_sqlproj(project_path)?;
+    let project_dir = absolute(&project.project_dir)?;
+    let built: Vec<PathBuf> = project
+        .sql_files
+        .iter()
+        .filter_map(|f| absolute(f).ok())
+        .collect();
+
+    let mut editor = ProjectEditor::open(project_path)?;
+    let mut added = Vec::new();
+    for file in files {
+        if !long_path(file).is_file() {
+            return Err(SqlPackageError::SqlFileReadError {
+                path: file.clone(),
+                source: std::io::Error::new(std::io::ErrorKind::NotFound, "file not found"),
+            }
+            .into());
+        }
+        let file = absolute(file)?;
+        if built.contains(&file) {
+            continue;
+        }
+        let include = relative_path(&project_dir, &file)
+            .to_string_lossy()
+            .replace('/', "\\");
+        if editor.add_build_item(&include)? {
+            added.push(include);
+        }
+    }
+    Ok((editor, added))
+}
+
+fn build_nodes<'a, 'input>(
+    doc: &'a Document<'input>,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    doc.descendants().filter(|n| {
+        n.has_tag_name("Build") && n.parent().is_some_and(|p| p.has_tag_name("ItemGroup"))
+    })
+}
+
+fn property_node<'a, 'input>(doc: &'a Document<'input>, name: &str) -> Option<Node<'a, 'input>> {
+    doc.descendants().find(|n| {
+        n.has_tag_name(name) && n.parent().is_some_and(|p| p.has_tag_name("PropertyGroup"))
+    })
+}
+
+fn same_path(a: &str, b: &str) -> bool {
+    a.trim().replace('/', "\\").to_lowercase() == b.trim().replace('/', "\\").to_lowercase()
+}
+
+fn escape_xml(value: &str, attribute: bool) -> String {
+    let escaped = value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    if attribute {
+        escaped.replace('"', "&quot;")
+    } else {
+        escaped
+    }
+}
+
+fn absolute(path: &Path) -> Result<PathBuf> {
+    let path = if path.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        path
+    };
+    Ok(
+        std::fs::canonicalize(long_path(path)).map_err(|e| SqlPackageError::ProjectReadError {
+            path: path.to_path_buf(),
+            source: e,
+        })?,
+    )
+}
+
+/// `file` relative to `dir`, both absolute, going up with `..` where needed
+fn relative_path(dir: &Path, file: &Path) -> PathBuf {
+    let dir: Vec<Component> = dir.components().collect();
+    let file: Vec<Component> = file.components().collect();
+    let common = dir.iter().zip(&file).take_while(|(a, b)| a == b).count();
+    let mut relative = PathBuf::new();
+    for _ in common..dir.len() {
+        relative.push("..");
+    }
+    for component in &file[common..] {
+        relative.push(component);
+    }
+    relative
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROJECT: &str = "<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<Project DefaultTargets=\"Build\">
+  <!-- keep me -->
+  <PropertyGroup>
+    <Name>Shop</Name>
+    <DefaultSchema>dbo</DefaultSchema>
+  </PropertyGroup>
+  <ItemGroup>
+    <Build Include=\"Tables\\Orders.sql\" />
+  </ItemGroup>
+</Project>
+";
+
+    fn editor_for(content: &str) -> ProjectEditor {
+        ProjectEditor {
+            path: PathBuf::from("Shop.sqlproj"),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_add_and_remove_build_items() {
+        let mut editor = editor_for(PROJECT);
+        assert!(editor.add_build_item(r"Views\Totals.sql").unwrap());
+        assert!(!editor.add_build_item("views/totals.sql").unwrap());
+        assert_eq!(
+            editor.content(),
+            PROJECT.replace(
+                "Orders.sql\" />\n",
+                "Orders.sql\" />\n    <Build Include=\"Views\\Totals.sql\" />\n"
+            )
+        );
+        assert_eq!(
+            editor.build_items().unwrap(),
+            [r"Tables\Orders.sql", r"Views\Totals.sql"]
+        );
+
+        assert!(editor.remove_build_item(r"Views\Totals.sql").unwrap());
+        assert_eq!(editor.content(), PROJECT);
+        assert!(!editor.remove_build_item(r"Views\Totals.sql").unwrap());
+
+        // Removing the last item drops its group; adding one back creates a group
+        assert!(editor.remove_build_item(r"Tables\Orders.sql").unwrap());
+        assert!(!editor.content().contains("ItemGroup"));
+        assert!(editor.add_build_item("A&B.sql").unwrap());
+        assert!(editor.content().ends_with(
+            "  </PropertyGroup>\n  <ItemGroup>\n    <Build Include=\"A&amp;B.sql\" />\n  </ItemGroup>\n</Project>\n"
+        ));
+    }
+
+    #[test]
+    fn test_set_property() {
+        let mut editor = editor_for(&PROJECT.replace('\n', "\r\n"));
+        assert_eq!(editor.property("Name").unwrap().as_deref(), Some("Shop"));
+        editor.set_property("DefaultSchema", "sales").unwrap();
+        editor.set_property("AnsiNulls", "False").unwrap();
+        assert_eq!(
+            editor.content(),
+            PROJECT
+                .replace(
+                    "<DefaultSchema>dbo</DefaultSchema>\n",
+                    "<DefaultSchema>sales</DefaultSchema>\n    <AnsiNulls>False</AnsiNulls>\n"
+                )
+                .replace('\n', "\r\n")
+        );
+
+        let mut editor = editor_for("<Project>\n</Project>\n");
+        editor.set_property("Name", "A < B").unwrap();
+        assert_eq!(
+            editor.content(),
+            "<Project>\n  <PropertyGroup>\n    <Name>A &lt; B</Name>\n  </PropertyGroup>\n</Project>\n"
+        );
+    }
+
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(
+            relative_path(Path::new("/src/db"), Path::new("/src/db/Tables/A.sql")),
+            PathBuf::from("Tables/A.sql")
+        );
+        assert_eq!(
+            relative_path(Path::new("/src/db"), Path::new("/src/shared/A.sql")),
+            PathBuf::from("../shared/A.sql")
+        );
+    }
+}
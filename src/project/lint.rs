@@ -0,0 +1,324 @@
+//! Project file checks for SQL files that silently drop out of the build
+//!
+//! Reports `.sql` files under the project directory that no item includes, `Build`
+//! items naming files that do not exist, and files included by more than one `Build`
+//! item. These are the usual answers to "why isn't my object in the dacpac". It also
+//! lists the project file elements the build does not apply (see [`super::unrecognized`]).
+//!
+//! [`lint_model`] checks the objects of the built model: their use of deprecated features,
+//! risky permissions and dynamic SQL, files defining several objects when the project
+//! asks for one per file, and with a [`Ruleset`], their names.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::Result;
+use roxmltree::Document;
+
+use super::deprecated::check_deprecated;
+use super::naming::check_naming;
+use super::objects_per_file::check_objects_per_file;
+use super::ruleset::Ruleset;
+use super::security::check_security;
+use super::sqlproj_parser::{parse_sqlproj, SqlProject};
+use super::unrecognized::UnrecognizedElement;
+use crate::error::SqlPackageError;
+use crate::model::{DatabaseModel, SourceLocation};
+use crate::util::long_path;
+
+/// A problem with the files a project includes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LintIssue {
+    /// A `.sql` file under the project directory that no item includes or removes
+    NotInBuild { path: PathBuf },
+    /// A `Build` item naming a file that does not exist
+    MissingFile { include: String },
+    /// A file included by more than one `Build` item (or glob)
+    DuplicateInclude { path: PathBuf, count: usize },
+    /// A project file element the build does not apply
+    UnrecognizedElement(UnrecognizedElement),
+    /// An object whose name does not match the ruleset's pattern for its type
+    Naming {
+        object_type: &'static str,
+        name: String,
+        pattern: String,
+        location: Option<SourceLocation>,
+    },
+    /// A problem in an object's definition, reported by a code rule such as `set-rowcount`
+    Code {
+        rule: &'static str,
+        object_type: &'static str,
+        name: String,
+        message: String,
+        location: Option<SourceLocation>,
+    },
+}
+
+impl LintIssue {
+    /// Names of the rules, see [`LintIssue::rule`]
+    pub const RULES: &'static [&'static str] = &[
+        "not-in-build",
+        "missing-file",
+        "duplicate-include",
+        "unrecognized-element",
+        "naming",
+        "deprecated-type",
+        "old-style-join",
+        "set-rowcount",
+        "broad-grant",
+        "dynamic-sql",
+        "execute-as-owner",
+        "multiple-objects-per-file",
+    ];
+
+    /// Rule name, as used to ignore issues of this kind: `not-in-build`, `missing-file`,
+    /// `duplicate-include`, `unrecognized-element`, `naming`, or a code rule's name
+    pub fn rule(&self) -> &'static str {
+        match self {
+            LintIssue::NotInBuild { .. } => "not-in-build",
+            LintIssue::MissingFile { .. } => "missing-file",
+            LintIssue::DuplicateInclude { .. } => "duplicate-include",
+            LintIssue::UnrecognizedElement(_) => "unrecognized-element",
+            LintIssue::Naming { .. } => "naming",
+            LintIssue::Code { rule, .. } => rule,
+        }
+    }
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LintIssue::NotInBuild { path } => {
+                write!(f, "not in build: {}", path.display())
+            }
+            LintIssue::MissingFile { include } => {
+                write!(f, "missing file: {} (Build item)", include)
+            }
+            LintIssue::DuplicateInclude { path, count } => {
+                write!(f, "duplicate include: {} ({} times)", path.display(), count)
+            }
+            LintIssue::UnrecognizedElement(element) => {
+                write!(f, "unrecognized element: {}", element)
+            }
+            LintIssue::Naming {
+                object_type,
+                name,
+                pattern,
+                location,
+            } => {
+                write!(
+                    f,
+                    "naming: {} {} does not match {}",
+                    object_type, name, pattern
+                )?;
+                if let Some(location) = location {
+                    write!(f, " ({})", location)?;
+                }
+                Ok(())
+            }
+            LintIssue::Code {
+                rule,
+                object_type,
+                name,
+                message,
+                location,
+            } => {
+                write!(f, "{}: {} {} {}", rule, object_type, name, message)?;
+                if let Some(location) = location {
+                    write!(f, " ({})", location)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Check the files of a .sqlproj, returning issues ordered by kind and path
+///
+/// Paths are relative to the project directory. Items are considered whatever their
+/// `Condition`, so files built for another target platform are not reported; `bin` and
+/// `obj` are not searched for files.
+pub fn lint_project(path: &Path) -> Result<Vec<LintIssue>> {
+    let project = parse_sqlproj(path)?;
+    let content = std::fs::read_to_string(long_path(path)).map_err(|e| {
+        SqlPackageError::ProjectReadError {
+            path: path.to_path_buf(),
+            source: e,
+        }
+    })?;
+    let doc = Document::parse(&content).map_err(|e| SqlPackageError::ProjectParseError {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+    let project_dir = if project.project_dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        project.project_dir.as_path()
+    };
+
+    let mut missing = Vec::new();
+    // Files included by Build items, keyed case-insensitively as on Windows
+    let mut build_counts: BTreeMap<String, (PathBuf, usize)> = BTreeMap::new();
+    // Files any item includes or removes
+    let mut referenced: HashSet<String> = project
+        .sql_files
+        .iter()
+        .chain(&project.pre_deploy_script)
+        .chain(&project.post_deploy_script)
+        .map(|file| file_key(project_dir, file))
+        .collect();
+
+    let items = doc
+        .descendants()
+        .filter(|n| n.is_element() && n.parent().is_some_and(|p| p.has_tag_name("ItemGroup")));
+    for item in items {
+        let is_build = item.tag_name().name() == "Build";
+        for attribute in ["Include", "Remove"] {
+            let Some(value) = item.attribute(attribute) else {
+                continue;
+            };
+            for pattern in value.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+                let pattern = pattern.replace('\\', "/");
+                let files = expand(project_dir, &pattern);
+                if is_build && attribute == "Include" {
+                    if files.is_empty() && !pattern.contains('*') {
+                        missing.push(pattern.clone());
+                    }
+                    for file in &files {
+                        build_counts
+                            .entry(file_key(project_dir, file))
+                            .or_insert_with(|| (relative(project_dir, file), 0))
+                            .1 += 1;
+                    }
+                }
+                referenced.extend(files.iter().map(|file| file_key(project_dir, file)));
+            }
+        }
+    }
+
+    let mut issues = Vec::new();
+    let mut orphans: Vec<PathBuf> = walkdir::WalkDir::new(project_dir)
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0
+                || !entry.file_type().is_dir()
+                || !["bin", "obj"]
+                    .iter()
+                    .any(|dir| entry.file_name().eq_ignore_ascii_case(dir))
+        })
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.into_path())
+        .filter(|file| {
+            file.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("sql"))
+                && !referenced.contains(&file_key(project_dir, file))
+        })
+        .map(|file| relative(project_dir, &file))
+        .collect();
+    orphans.sort();
+    issues.extend(
+        orphans
+            .into_iter()
+            .map(|path| LintIssue::NotInBuild { path }),
+    );
+
+    missing.sort();
+    missing.dedup();
+    issues.extend(
+        missing
+            .into_iter()
+            .map(|include| LintIssue::MissingFile { include }),
+    );
+
+    issues.extend(
+        build_counts
+            .into_values()
+            .filter(|(_, count)| *count > 1)
+            .map(|(path, count)| LintIssue::DuplicateInclude { path, count }),
+    );
+
+    issues.extend(
+        project
+            .unrecognized_elements
+            .into_iter()
+            .map(LintIssue::UnrecognizedElement),
+    );
+    Ok(issues)
+}
+
+/// Check the objects of a project's built model with the code rules, returning naming
+/// issues (if the ruleset has patterns), uses of deprecated features and security issues,
+/// each in model order, then objects sharing a file if the project's `<ObjectsPerFile>`
+/// policy is `Single` or `Split`
+pub fn lint_model(
+    model: &DatabaseModel,
+    project: &SqlProject,
+    ruleset: &Ruleset,
+) -> Vec<LintIssue> {
+    let mut issues = check_naming(model, project, &ruleset.naming);
+    issues.extend(check_deprecated(model, project));
+    issues.extend(check_security(model, project, &ruleset.security));
+    issues.extend(check_objects_per_file(model, project));
+    issues
+}
+
+/// Where the element at `index` is defined, with the file relative to the project
+pub(super) fn relative_source(
+    model: &DatabaseModel,
+    index: usize,
+    project: &SqlProject,
+) -> Option<SourceLocation> {
+    let source = model.source(index)?;
+    Some(SourceLocation {
+        file: relative(&project.project_dir, &source.file),
+        ..source.clone()
+    })
+}
+
+/// Existing files matching an item's `Include` or `Remove` value
+fn expand(project_dir: &Path, pattern: &str) -> Vec<PathBuf> {
+    if pattern.contains('*') {
+        let glob_pattern = project_dir.join(pattern);
+        glob::glob(&glob_pattern.to_string_lossy())
+            .map(|paths| {
+                paths
+                    .filter_map(|p| p.ok())
+                    .filter(|p| long_path(p).is_file())
+                    .collect()
+            })
+            .unwrap_or_default()
+    } else {
+        let file = project_dir.join(pattern);
+        if long_path(&file).is_file() {
+            vec![file]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// `file` relative to the project directory, with `.` and `..` resolved
+fn relative(project_dir: &Path, file: &Path) -> PathBuf {
+    let file = file.strip_prefix(project_dir).unwrap_or(file);
+    let mut resolved = PathBuf::new();
+    for component in file.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !resolved.pop() {
+                    resolved.push("..");
+                }
+            }
+            other => resolved.push(other),
+        }
+    }
+    resolved
+}
+
+fn file_key(project_dir: &Path, file: &Path) -> String {
+    relative(project_dir, file)
+        .to_string_lossy()
+        .replace('\\', "/")
+        .to_lowercase()
+}
@@ -1,10 +1,30 @@
 //! SQL project file parsing
 
 mod collation;
+mod conditions;
+mod deprecated;
+mod editor;
+mod lint;
+mod naming;
+mod objects_per_file;
+mod profiles;
+mod properties_files;
+mod ruleset;
+mod sarif;
+mod security;
 mod sqlproj_parser;
+mod unrecognized;
 
 pub use collation::{parse_collation_info, CollationInfo};
+pub use editor::{add_sql_files, sql_files_to_add, ProjectEditor};
+pub use lint::{lint_model, lint_project, LintIssue};
+pub use objects_per_file::{enforce_single_object_files, split_multi_object_files, ObjectsPerFile};
+pub use profiles::{read_profiles_file, DacpacProfile};
+pub use ruleset::{NamingRules, Ruleset, SecurityRules};
+pub use sarif::{format_sarif, LintFormat};
 pub use sqlproj_parser::{
-    parse_sqlproj, DacpacReference, DatabaseOptions, PackageReference, SqlCmdVariable, SqlProject,
+    parse_sqlproj, parse_sqlproj_with_properties, project_from_dir, ContributorArgument,
+    DacpacReference, DatabaseOptions, PackageReference, SkippedFile, SqlCmdVariable, SqlProject,
     SqlServerVersion,
 };
+pub use unrecognized::{UnknownElementPolicy, UnrecognizedElement};
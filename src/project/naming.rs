@@ -0,0 +1,54 @@
+//! Naming convention rule: object names must match their type's pattern in the ruleset
+
+use regex::Regex;
+
+use super::lint::{relative_source, LintIssue};
+use super::ruleset::NamingRules;
+use super::SqlProject;
+use crate::model::{ConstraintType, DatabaseModel, ModelElement};
+
+/// Objects whose names do not match the pattern for their type, in model order
+///
+/// Constraints declared without a name are not checked.
+pub(super) fn check_naming(
+    model: &DatabaseModel,
+    project: &SqlProject,
+    rules: &NamingRules,
+) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    for (index, element) in model.elements.iter().enumerate() {
+        let (object_type, pattern, name): (&'static str, &Option<Regex>, &str) = match element {
+            ModelElement::Table(t) => ("Table", &rules.table, &t.name),
+            ModelElement::View(v) => ("View", &rules.view, &v.name),
+            ModelElement::Procedure(p) => ("Procedure", &rules.procedure, &p.name),
+            ModelElement::Function(f) => ("Function", &rules.function, &f.name),
+            ModelElement::Trigger(t) => ("Trigger", &rules.trigger, &t.name),
+            ModelElement::Sequence(s) => ("Sequence", &rules.sequence, &s.name),
+            ModelElement::Synonym(s) => ("Synonym", &rules.synonym, &s.name),
+            ModelElement::UserDefinedType(u) => ("Table type", &rules.user_defined_type, &u.name),
+            ModelElement::ScalarType(s) => ("Data type", &rules.user_defined_type, &s.name),
+            ModelElement::Index(i) => ("Index", &rules.index, &i.name),
+            ModelElement::ColumnstoreIndex(c) => ("Columnstore index", &rules.index, &c.name),
+            ModelElement::Constraint(c) if c.emit_name => match c.constraint_type {
+                ConstraintType::PrimaryKey => ("Primary key", &rules.primary_key, &c.name),
+                ConstraintType::ForeignKey => ("Foreign key", &rules.foreign_key, &c.name),
+                ConstraintType::Unique => ("Unique constraint", &rules.unique, &c.name),
+                ConstraintType::Check => ("Check constraint", &rules.check, &c.name),
+                ConstraintType::Default => ("Default constraint", &rules.default, &c.name),
+            },
+            _ => continue,
+        };
+        let Some(pattern) = pattern else {
+            continue;
+        };
+        if !pattern.is_match(name) {
+            issues.push(LintIssue::Naming {
+                object_type,
+                name: element.full_name(),
+                pattern: pattern.as_str().to_string(),
+                location: relative_source(model, index, project),
+            });
+        }
+    }
+    issues
+}
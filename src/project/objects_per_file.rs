@@ -0,0 +1,269 @@
+//! The `<ObjectsPerFile>` policy: whether a SQL file may define more than one object
+//!
+//! - `Multiple` (default): files may define any number of objects
+//! - `Single`: a file defining more than one object fails the build
+//! - `Split`: such files are split when the project is built, the first object staying
+//!   in the file and each other one moving to a file of its own next to it
+//!
+//! Objects are tables, views, routines, types, sequences, synonyms and triggers; the
+//! constraints, indexes, permissions and extended properties of an object can share its
+//! file. With `Single` or `Split`, `lint` reports the files under the
+//! `multiple-objects-per-file` rule.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use super::lint::{relative_source, LintIssue};
+use super::SqlProject;
+use crate::error::SqlPackageError;
+use crate::model::{DatabaseModel, ModelElement};
+use crate::parser::{decode_source_with_encoding, encode_source, SourceEncoding};
+use crate::util::long_path;
+
+/// Whether a SQL file may define more than one object, from `<ObjectsPerFile>`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ObjectsPerFile {
+    #[default]
+    Multiple,
+    Single,
+    Split,
+}
+
+impl std::str::FromStr for ObjectsPerFile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "multiple" => Ok(ObjectsPerFile::Multiple),
+            "single" => Ok(ObjectsPerFile::Single),
+            "split" => Ok(ObjectsPerFile::Split),
+            _ => Err(format!(
+                "Unknown ObjectsPerFile value: {} (expected Multiple, Single or Split)",
+                s
+            )),
+        }
+    }
+}
+
+/// An object defined in a file along with others
+struct FileObject {
+    /// Index of the object's element in the model
+    index: usize,
+    schema: String,
+    name: String,
+    full_name: String,
+    /// First line of the object's batch
+    start_line: usize,
+}
+
+/// Files defining more than one object, with their objects in file order
+fn multi_object_files(model: &DatabaseModel) -> BTreeMap<PathBuf, Vec<FileObject>> {
+    let mut files: BTreeMap<PathBuf, Vec<FileObject>> = BTreeMap::new();
+    for (index, element) in model.elements.iter().enumerate() {
+        if matches!(element, ModelElement::Constraint(_)) {
+            continue;
+        }
+        let (Some((schema, name)), Some(source)) = (element.schema_and_name(), model.source(index))
+        else {
+            continue;
+        };
+        let objects = files.entry(source.file.clone()).or_default();
+        // Elements created by one batch are one object
+        if objects.iter().all(|o| o.start_line != source.start_line) {
+            objects.push(FileObject {
+                index,
+                schema: schema.to_string(),
+                name: name.to_string(),
+                full_name: element.full_name(),
+                start_line: source.start_line,
+            });
+        }
+    }
+    files.retain(|_, objects| objects.len() > 1);
+    for objects in files.values_mut() {
+        objects.sort_by_key(|o| o.start_line);
+    }
+    files
+}
+
+/// Objects sharing a file with an object before them, when the policy is `Single` or
+/// `Split`, ordered by file and line
+pub(super) fn check_objects_per_file(
+    model: &DatabaseModel,
+    project: &SqlProject,
+) -> Vec<LintIssue> {
+    if project.objects_per_file == ObjectsPerFile::Multiple {
+        return Vec::new();
+    }
+    let mut issues = Vec::new();
+    for objects in multi_object_files(model).values() {
+        for object in &objects[1..] {
+            issues.push(LintIssue::Code {
+                rule: "multiple-objects-per-file",
+                object_type: object_type(&model.elements[object.index]),
+                name: object.full_name.clone(),
+                message: format!("shares its file with {}", objects[0].full_name),
+                location: relative_source(model, object.index, project),
+            });
+        }
+    }
+    issues
+}
+
+/// Fail if a file defines more than one object and the policy is `Single`
+pub fn enforce_single_object_files(model: &DatabaseModel, project: &SqlProject) -> Result<()> {
+    if project.objects_per_file != ObjectsPerFile::Single {
+        return Ok(());
+    }
+    let files = multi_object_files(model);
+    if files.is_empty() {
+        return Ok(());
+    }
+    Err(SqlPackageError::MultipleObjectsPerFile {
+        files: files
+            .iter()
+            .map(|(file, objects)| {
+                format!(
+                    "{} ({})",
+                    file.strip_prefix(&project.project_dir)
+                        .unwrap_or(file)
+                        .display(),
+                    objects
+                        .iter()
+                        .map(|o| o.full_name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+            .collect(),
+    }
+    .into())
+}
+
+/// Split the files defining more than one object, when the policy is `Split`
+///
+/// The first object stays in its file; each other object, with the batches after it
+/// up to the next object, moves to `<name>.sql` (or `<schema>.<name>.sql` if that is
+/// taken) in the same folder, in the encoding of the original. Files with `:IF` blocks are left alone, with a warning, as
+/// a block could end up split across files. Returns each split file with the files
+/// created from it; with `dry_run`, the files that would be created.
+pub fn split_multi_object_files(
+    model: &DatabaseModel,
+    project: &SqlProject,
+    dry_run: bool,
+) -> Result<Vec<(PathBuf, Vec<PathBuf>)>> {
+    if project.objects_per_file != ObjectsPerFile::Split {
+        return Ok(Vec::new());
+    }
+    let mut split = Vec::new();
+    for (file, objects) in multi_object_files(model) {
+        let bytes =
+            std::fs::read(long_path(&file)).map_err(|e| SqlPackageError::SqlFileReadError {
+                path: file.clone(),
+                source: e,
+            })?;
+        let (text, encoding) = decode_source_with_encoding(&bytes, project.default_file_encoding)
+            .map_err(|e| SqlPackageError::SqlFileReadError {
+            path: file.clone(),
+            source: e,
+        })?;
+        let lines: Vec<&str> = text.split_inclusive('\n').collect();
+        if lines
+            .iter()
+            .any(|line| line.trim_start().to_uppercase().starts_with(":IF"))
+        {
+            eprintln!(
+                "Warning: not splitting {}, it has :IF blocks",
+                file.display()
+            );
+            continue;
+        }
+
+        let dir = file.parent().unwrap_or(Path::new(""));
+        let mut created: Vec<PathBuf> = Vec::new();
+        let mut chunks = Vec::new();
+        for (i, object) in objects.iter().enumerate().skip(1) {
+            let end = objects
+                .get(i + 1)
+                .map_or(lines.len(), |next| next.start_line - 1)
+                .min(lines.len());
+            let start = (object.start_line - 1).min(end);
+            let path = [
+                format!("{}.sql", file_name_part(&object.name)),
+                format!(
+                    "{}.{}.sql",
+                    file_name_part(&object.schema),
+                    file_name_part(&object.name)
+                ),
+            ]
+            .into_iter()
+            .map(|name| dir.join(name))
+            .find(|path| !long_path(path).exists() && !created.contains(path))
+            .ok_or_else(|| SqlPackageError::InvalidProjectFormat {
+                message: format!(
+                    "cannot split {} out of {}: {}.sql and {}.{}.sql already exist",
+                    object.full_name,
+                    file.display(),
+                    object.name,
+                    object.schema,
+                    object.name
+                ),
+            })?;
+            chunks.push((path.clone(), lines[start..end].concat()));
+            created.push(path);
+        }
+
+        if !dry_run {
+            for (path, content) in &chunks {
+                write_file(path, content, encoding)?;
+            }
+            let first_end = objects[1].start_line - 1;
+            write_file(
+                &file,
+                &lines[..first_end.min(lines.len())].concat(),
+                encoding,
+            )?;
+        }
+        split.push((file, created));
+    }
+    Ok(split)
+}
+
+/// Type of an object, as lint messages show it
+fn object_type(element: &ModelElement) -> &'static str {
+    match element {
+        ModelElement::Table(_) => "Table",
+        ModelElement::View(_) => "View",
+        ModelElement::Procedure(_) => "Procedure",
+        ModelElement::Function(_) => "Function",
+        ModelElement::Trigger(_) => "Trigger",
+        ModelElement::Sequence(_) => "Sequence",
+        ModelElement::Synonym(_) => "Synonym",
+        ModelElement::UserDefinedType(_) => "Table type",
+        ModelElement::ScalarType(_) => "Data type",
+        _ => "Object",
+    }
+}
+
+/// `name` with the characters Windows does not allow in file names replaced
+fn file_name_part(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// Write `content` to `path` in the encoding its source file was read in
+fn write_file(path: &Path, content: &str, encoding: SourceEncoding) -> Result<()> {
+    std::fs::write(long_path(path), encode_source(content, encoding)).map_err(|e| {
+        SqlPackageError::OutputWriteError {
+            path: path.to_path_buf(),
+            source: e,
+        }
+        .into()
+    })
+}
@@ -0,0 +1,142 @@
+//! Named filter profiles: extra dacpacs built from the same model
+//!
+//! Each profile is written as `<Name>.dacpac` next to the main dacpac, with its build
+//! filters applied, e.g. a customer-facing package without the internal schemas:
+//!
+//! ```xml
+//! <ItemGroup>
+//!   <DacpacProfile Include="CustomerFacing">
+//!     <Filters>exclude:schema=Internal;exclude:schema=Audit</Filters>
+//!   </DacpacProfile>
+//! </ItemGroup>
+//! ```
+//!
+//! Profiles can also come from a JSON file mapping names to filters:
+//! `{"CustomerFacing": ["exclude:schema=Internal", "exclude:schema=Audit"]}`.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::error::SqlPackageError;
+use crate::model::ObjectFilter;
+use crate::util::long_path;
+
+/// A named set of build filters producing an extra dacpac
+#[derive(Debug, Clone)]
+pub struct DacpacProfile {
+    /// Profile name, also the dacpac's file name
+    pub name: String,
+    /// Filters applied to the model for this dacpac
+    pub filters: Vec<ObjectFilter>,
+}
+
+impl DacpacProfile {
+    /// A profile from its name and filter strings (see [`ObjectFilter`])
+    pub fn new<S: AsRef<str>>(name: &str, filters: &[S]) -> Result<Self> {
+        let name = name.trim();
+        if name.is_empty() || name.contains(['/', '\\', ':']) || name == "." || name == ".." {
+            return Err(SqlPackageError::InvalidProjectFormat {
+                message: format!("invalid dacpac profile name '{}'", name),
+            }
+            .into());
+        }
+        let filters = filters
+            .iter()
+            .map(|filter| filter.as_ref().trim())
+            .filter(|filter| !filter.is_empty())
+            .map(|filter| {
+                filter
+                    .parse()
+                    .map_err(|e| SqlPackageError::InvalidProjectFormat {
+                        message: format!("dacpac profile '{}': {}", name, e),
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            name: name.to_string(),
+            filters,
+        })
+    }
+}
+
+/// Find `DacpacProfile` items in the project file
+///
+/// `Filters` is semicolon-separated and may be given as metadata or an attribute.
+pub(super) fn find_dacpac_profiles(root: &roxmltree::Node) -> Result<Vec<DacpacProfile>> {
+    let mut profiles = Vec::new();
+    for node in root
+        .descendants()
+        .filter(|n| n.has_tag_name("DacpacProfile"))
+    {
+        let Some(name) = node.attribute("Include") else {
+            continue;
+        };
+        let filters = node
+            .attribute("Filters")
+            .map(str::to_string)
+            .or_else(|| {
+                node.children()
+                    .find(|c| c.has_tag_name("Filters"))
+                    .and_then(|c| c.text())
+                    .map(str::to_string)
+            })
+            .unwrap_or_default();
+        let filters: Vec<&str> = filters.split(';').collect();
+        profiles.push(DacpacProfile::new(name, &filters)?);
+    }
+    Ok(profiles)
+}
+
+/// Read profiles from a JSON file of `{"Name": ["filter", ...]}`, ordered by name
+pub fn read_profiles_file(path: &Path) -> Result<Vec<DacpacProfile>> {
+    let content = std::fs::read_to_string(long_path(path)).map_err(|e| {
+        SqlPackageError::ProjectReadError {
+            path: path.to_path_buf(),
+            source: e,
+        }
+    })?;
+    let entries: BTreeMap<String, Vec<String>> =
+        serde_json::from_str(&content).map_err(|e| SqlPackageError::InvalidProjectFormat {
+            message: format!("{}: {}", path.display(), e),
+        })?;
+    entries
+        .iter()
+        .map(|(name, filters)| DacpacProfile::new(name, filters))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_dacpac_profiles() {
+        let doc = roxmltree::Document::parse(
+            r#"<Project>
+  <ItemGroup>
+    <DacpacProfile Include="CustomerFacing">
+      <Filters>exclude:schema=Internal; exclude:schema=Audit;</Filters>
+    </DacpacProfile>
+    <DacpacProfile Include="TablesOnly" Filters="include:type=Table" />
+  </ItemGroup>
+</Project>"#,
+        )
+        .unwrap();
+        let profiles = find_dacpac_profiles(&doc.root_element()).unwrap();
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].name, "CustomerFacing");
+        assert_eq!(profiles[0].filters.len(), 2);
+        assert_eq!(profiles[1].name, "TablesOnly");
+        assert_eq!(profiles[1].filters.len(), 1);
+    }
+
+    #[test]
+    fn test_dacpac_profile_errors() {
+        assert!(DacpacProfile::new("Public", &["exclude:schema=Internal"]).is_ok());
+        assert!(DacpacProfile::new("Public", &["drop:schema=Internal"]).is_err());
+        assert!(DacpacProfile::new("../Public", &["exclude:schema=Internal"]).is_err());
+        assert!(DacpacProfile::new(" ", &["exclude:schema=Internal"]).is_err());
+    }
+}
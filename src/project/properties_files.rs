@@ -0,0 +1,178 @@
+//! Visual Studio database project property files
+//!
+//! Projects created by the Visual Studio 2010 database tools (and SSDT projects migrated
+//! from them) keep some settings outside the project file, under `Properties`:
+//!
+//! - `Database.sqlsettings`: database options such as the collation and ANSI settings
+//! - `Database.sqlpermissions`: permissions, compiled like a script (see
+//!   `parser::permissions_file`)
+//! - `Database.sqlcmdvars`: SQLCMD variables and their values
+//!
+//! They are found through `<PropertiesFile>` items and the `<SqlCommandVariablesFile>`
+//! property, or at those standard paths. Settings in the project file win over the
+//! files.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::sqlproj_parser::{find_item_file, resolve_ignoring_case, SqlCmdVariable};
+use crate::util::long_path;
+
+/// Property files of a project
+#[derive(Debug, Default)]
+pub(crate) struct PropertiesFiles {
+    pub settings: Option<PathBuf>,
+    pub permissions: Option<PathBuf>,
+    pub sqlcmd_variables: Option<PathBuf>,
+}
+
+/// Find the project's property files
+pub(crate) fn find_properties_files(
+    root: &roxmltree::Node,
+    project_dir: &Path,
+    warnings: &mut Vec<String>,
+) -> PropertiesFiles {
+    let mut files = PropertiesFiles::default();
+    let items = root
+        .descendants()
+        .filter(|n| n.has_tag_name("PropertiesFile"))
+        .filter_map(|n| n.attribute("Include"))
+        .chain(
+            root.descendants()
+                .filter(|n| n.has_tag_name("SqlCommandVariablesFile"))
+                .filter_map(|n| n.text()),
+        )
+        .map(|include| include.trim().replace('\\', "/"))
+        .collect::<Vec<_>>();
+    let conventional = [
+        "Properties/Database.sqlsettings",
+        "Properties/Database.sqlpermissions",
+        "Properties/Database.sqlcmdvars",
+    ];
+    for (include, is_item) in items
+        .iter()
+        .map(|include| (include.as_str(), true))
+        .chain(conventional.map(|include| (include, false)))
+    {
+        let slot = match Path::new(include)
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .as_deref()
+        {
+            Some("sqlsettings") => &mut files.settings,
+            Some("sqlpermissions") => &mut files.permissions,
+            Some("sqlcmdvars") => &mut files.sqlcmd_variables,
+            _ => continue,
+        };
+        if slot.is_none() {
+            *slot = if is_item {
+                find_item_file(project_dir, include, "PropertiesFile", warnings)
+            } else {
+                resolve_ignoring_case(project_dir, include)
+            };
+        }
+    }
+    files
+}
+
+/// The properties a `.sqlsettings` file sets, by name (`DefaultCollation`, `AnsiNulls`, ...)
+///
+/// ```xml
+/// <CatalogProperties xmlns="urn:Microsoft.VisualStudio.Data.Schema.Package.CatalogProperties">
+///   <Version>1.0</Version>
+///   <Properties>
+///     <AnsiNulls>True</AnsiNulls>
+///     <DefaultCollation>Latin1_General_CI_AS</DefaultCollation>
+///   </Properties>
+/// </CatalogProperties>
+/// ```
+pub(crate) fn read_database_settings(
+    path: &Path,
+    warnings: &mut Vec<String>,
+) -> HashMap<String, String> {
+    let Some(text) = read_xml_file(path, warnings) else {
+        return HashMap::new();
+    };
+    let Ok(document) = roxmltree::Document::parse(&text) else {
+        warn(
+            warnings,
+            format!("{} is not valid XML, ignoring it", path.display()),
+        );
+        return HashMap::new();
+    };
+    document
+        .descendants()
+        .filter(|n| n.has_tag_name("Properties"))
+        .flat_map(|properties| properties.children().filter(|n| n.is_element()))
+        .filter_map(|n| {
+            Some((
+                n.tag_name().name().to_string(),
+                n.text()?.trim().to_string(),
+            ))
+        })
+        .collect()
+}
+
+/// The variables a `.sqlcmdvars` file defines, with their values as defaults
+///
+/// ```xml
+/// <SqlCommandVariables xmlns="urn:Microsoft.VisualStudio.Data.Schema.Package.SqlCmdVars">
+///   <Version>1.0</Version>
+///   <Properties>
+///     <Property>
+///       <PropertyName>Environment</PropertyName>
+///       <PropertyValue>Development</PropertyValue>
+///     </Property>
+///   </Properties>
+/// </SqlCommandVariables>
+/// ```
+pub(crate) fn read_sqlcmd_variables(
+    path: &Path,
+    warnings: &mut Vec<String>,
+) -> Vec<SqlCmdVariable> {
+    let Some(text) = read_xml_file(path, warnings) else {
+        return Vec::new();
+    };
+    let Ok(document) = roxmltree::Document::parse(&text) else {
+        warn(
+            warnings,
+            format!("{} is not valid XML, ignoring it", path.display()),
+        );
+        return Vec::new();
+    };
+    let child_text = |node: roxmltree::Node, tag: &str| {
+        node.children()
+            .find(|n| n.has_tag_name(tag))
+            .and_then(|n| n.text())
+            .map(|t| t.trim().to_string())
+    };
+    document
+        .descendants()
+        .filter(|n| n.has_tag_name("Property"))
+        .filter_map(|property| {
+            Some(SqlCmdVariable {
+                name: child_text(property, "PropertyName")?,
+                value: String::new(),
+                default_value: child_text(property, "PropertyValue").unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+fn read_xml_file(path: &Path, warnings: &mut Vec<String>) -> Option<String> {
+    match std::fs::read(long_path(path)) {
+        Ok(bytes) => {
+            let text = crate::parser::decode_source(&bytes, None).unwrap_or_default();
+            Some(text.trim_start_matches('\u{feff}').to_string())
+        }
+        Err(e) => {
+            warn(warnings, format!("Cannot read {}: {}", path.display(), e));
+            None
+        }
+    }
+}
+
+fn warn(warnings: &mut Vec<String>, warning: String) {
+    eprintln!("Warning: {}", warning);
+    warnings.push(warning);
+}
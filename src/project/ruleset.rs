@@ -0,0 +1,141 @@
+//! Lint ruleset file: settings for the rules `lint` checks the built model with
+//!
+//! Naming rules give a regular expression per object type that names must match:
+//!
+//! ```toml
+//! [naming]
+//! procedure = "^usp_"
+//! primary_key = "^PK_"
+//! foreign_key = "^FK_"
+//! ```
+//!
+//! Patterns are not anchored unless they say so, and match the object's own name
+//! (`usp_GetOrders`), not its schema.
+//!
+//! Security rules treat grants to `public` as broad; other principals everyone belongs
+//! to can be added:
+//!
+//! ```toml
+//! [security]
+//! broad_principals = ["AllEmployees"]
+//! ```
+
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::Result;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::config::parse_option;
+use crate::error::SqlPackageError;
+use crate::util::long_path;
+
+/// Settings read from a ruleset file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Ruleset {
+    pub naming: NamingRules,
+    pub security: SecurityRules,
+}
+
+/// Name patterns by object type; types without one are not checked
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NamingRules {
+    #[serde(deserialize_with = "parse_option")]
+    pub table: Option<Regex>,
+    #[serde(deserialize_with = "parse_option")]
+    pub view: Option<Regex>,
+    #[serde(deserialize_with = "parse_option")]
+    pub procedure: Option<Regex>,
+    #[serde(deserialize_with = "parse_option")]
+    pub function: Option<Regex>,
+    #[serde(deserialize_with = "parse_option")]
+    pub trigger: Option<Regex>,
+    #[serde(deserialize_with = "parse_option")]
+    pub sequence: Option<Regex>,
+    #[serde(deserialize_with = "parse_option")]
+    pub synonym: Option<Regex>,
+    /// Table types and user-defined data types
+    #[serde(deserialize_with = "parse_option")]
+    pub user_defined_type: Option<Regex>,
+    #[serde(deserialize_with = "parse_option")]
+    pub primary_key: Option<Regex>,
+    #[serde(deserialize_with = "parse_option")]
+    pub foreign_key: Option<Regex>,
+    #[serde(deserialize_with = "parse_option")]
+    pub unique: Option<Regex>,
+    #[serde(deserialize_with = "parse_option")]
+    pub check: Option<Regex>,
+    #[serde(deserialize_with = "parse_option")]
+    pub default: Option<Regex>,
+    /// Row store and columnstore indexes
+    #[serde(deserialize_with = "parse_option")]
+    pub index: Option<Regex>,
+}
+
+/// Settings for the security rules
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SecurityRules {
+    /// Roles and users, besides `public`, that elevated permissions must not be granted to
+    pub broad_principals: Vec<String>,
+}
+
+impl FromStr for Ruleset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        toml::from_str(s).map_err(|e: toml::de::Error| e.to_string().trim_end().to_string())
+    }
+}
+
+impl Ruleset {
+    /// Read a ruleset file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(long_path(path)).map_err(|e| {
+            SqlPackageError::ConfigReadError {
+                path: path.to_path_buf(),
+                source: e,
+            }
+        })?;
+        Ok(content
+            .parse()
+            .map_err(|message| SqlPackageError::InvalidConfig {
+                path: path.to_path_buf(),
+                message,
+            })?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ruleset() {
+        let ruleset: Ruleset = r#"
+[naming]
+procedure = "^usp_"
+primary_key = "^PK_"
+
+[security]
+broad_principals = ["AllEmployees"]
+"#
+        .parse()
+        .unwrap();
+        let naming = &ruleset.naming;
+        assert!(naming.procedure.as_ref().unwrap().is_match("usp_GetOrders"));
+        assert!(!naming.primary_key.as_ref().unwrap().is_match("Orders_PK"));
+        assert!(naming.view.is_none());
+        assert_eq!(ruleset.security.broad_principals, vec!["AllEmployees"]);
+
+        assert!("[naming]\nprocedure = \"^usp_(\""
+            .parse::<Ruleset>()
+            .is_err());
+        assert!("[naming]\nprocedures = \"^usp_\""
+            .parse::<Ruleset>()
+            .is_err());
+    }
+}
@@ -0,0 +1,205 @@
+//! SARIF output for `lint`, for code scanning and security review pipelines
+//!
+//! Issues become SARIF 2.1.0 results with their rule, a message and the file (relative
+//! to the project directory) and lines they were found at. Security rules are reported
+//! as errors tagged `security`, everything else as warnings.
+
+use std::path::Path;
+
+use serde_json::{json, Value};
+
+use super::lint::LintIssue;
+use super::security::SECURITY_RULES;
+
+/// Format of the `lint` report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LintFormat {
+    /// One issue per line (default)
+    #[default]
+    Text,
+    /// SARIF 2.1.0 JSON, see [`format_sarif`]
+    Sarif,
+}
+
+impl std::str::FromStr for LintFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(LintFormat::Text),
+            "sarif" => Ok(LintFormat::Sarif),
+            _ => Err(format!("Unknown lint format: {}", s)),
+        }
+    }
+}
+
+/// What each rule checks, for the SARIF rule descriptions
+fn rule_description(rule: &str) -> &'static str {
+    match rule {
+        "not-in-build" => "SQL file that no project item includes",
+        "missing-file" => "Build item naming a file that does not exist",
+        "duplicate-include" => "File included by more than one Build item",
+        "unrecognized-element" => "Project file element the build does not apply",
+        "naming" => "Object name not matching the ruleset's pattern for its type",
+        "deprecated-type" => "text, ntext or image column, parameter or type",
+        "old-style-join" => "*= or =* outer join",
+        "set-rowcount" => "SET ROWCOUNT in a module",
+        "broad-grant" => "Elevated permission granted to a broad principal",
+        "dynamic-sql" => "Dynamic SQL built from a procedure parameter",
+        "execute-as-owner" => "Module running as its owner or dbo",
+        "multiple-objects-per-file" => "Object sharing its file with another object",
+        _ => "",
+    }
+}
+
+/// Render issues as a SARIF 2.1.0 log; `project_file` locates issues about the
+/// project file itself, such as missing files
+pub fn format_sarif(issues: &[LintIssue], project_file: &Path) -> String {
+    let rules: Vec<Value> = LintIssue::RULES
+        .iter()
+        .map(|rule| {
+            let mut descriptor = json!({
+                "id": rule,
+                "shortDescription": { "text": rule_description(rule) },
+            });
+            if SECURITY_RULES.contains(rule) {
+                descriptor["properties"] = json!({ "tags": ["security"] });
+            }
+            descriptor
+        })
+        .collect();
+
+    let project_file = project_file
+        .file_name()
+        .map_or_else(|| project_file.to_path_buf(), Into::into);
+    let results: Vec<Value> = issues
+        .iter()
+        .map(|issue| {
+            let (message, file, lines) = match issue {
+                LintIssue::NotInBuild { path } | LintIssue::DuplicateInclude { path, .. } => {
+                    (issue.to_string(), path.clone(), None)
+                }
+                LintIssue::MissingFile { .. } => (issue.to_string(), project_file.clone(), None),
+                LintIssue::UnrecognizedElement(element) => (
+                    issue.to_string(),
+                    project_file.clone(),
+                    Some((element.line, element.line)),
+                ),
+                LintIssue::Naming {
+                    object_type,
+                    name,
+                    pattern,
+                    location,
+                } => (
+                    format!("{} {} does not match {}", object_type, name, pattern),
+                    location
+                        .as_ref()
+                        .map_or_else(|| project_file.clone(), |l| l.file.clone()),
+                    location.as_ref().map(|l| (l.start_line, l.end_line)),
+                ),
+                LintIssue::Code {
+                    object_type,
+                    name,
+                    message,
+                    location,
+                    ..
+                } => (
+                    format!("{} {} {}", object_type, name, message),
+                    location
+                        .as_ref()
+                        .map_or_else(|| project_file.clone(), |l| l.file.clone()),
+                    location.as_ref().map(|l| (l.start_line, l.end_line)),
+                ),
+            };
+            let mut physical = json!({
+                "artifactLocation": {
+                    "uri": file.to_string_lossy().replace('\\', "/"),
+                    "uriBaseId": "PROJECTROOT",
+                },
+            });
+            if let Some((start_line, end_line)) = lines {
+                physical["region"] = json!({ "startLine": start_line, "endLine": end_line });
+            }
+            let level = if SECURITY_RULES.contains(&issue.rule()) {
+                "error"
+            } else {
+                "warning"
+            };
+            json!({
+                "ruleId": issue.rule(),
+                "level": level,
+                "message": { "text": message },
+                "locations": [{ "physicalLocation": physical }],
+            })
+        })
+        .collect();
+
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    });
+    let mut sarif = serde_json::to_string_pretty(&log).unwrap_or_default();
+    sarif.push('\n');
+    sarif
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::model::SourceLocation;
+
+    #[test]
+    fn test_format_sarif() {
+        let issues = vec![
+            LintIssue::MissingFile {
+                include: "Views/Gone.sql".to_string(),
+            },
+            LintIssue::Code {
+                rule: "dynamic-sql",
+                object_type: "Procedure",
+                name: "[dbo].[Search]".to_string(),
+                message: "executes dynamic SQL built from @Filter".to_string(),
+                location: Some(SourceLocation {
+                    file: PathBuf::from("Procedures/Search.sql"),
+                    start_line: 1,
+                    end_line: 9,
+                    leading_whitespace: String::new(),
+                }),
+            },
+        ];
+        let sarif = format_sarif(&issues, Path::new("src/Db/Db.sqlproj"));
+        let log: Value = serde_json::from_str(&sarif).unwrap();
+        let run = &log["runs"][0];
+        assert_eq!(log["version"], "2.1.0");
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 12);
+
+        let missing = &run["results"][0];
+        assert_eq!(missing["ruleId"], "missing-file");
+        assert_eq!(missing["level"], "warning");
+        let location = &missing["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "Db.sqlproj");
+        assert!(location.get("region").is_none());
+
+        let dynamic = &run["results"][1];
+        assert_eq!(dynamic["level"], "error");
+        assert_eq!(
+            dynamic["message"]["text"],
+            "Procedure [dbo].[Search] executes dynamic SQL built from @Filter"
+        );
+        let location = &dynamic["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "Procedures/Search.sql");
+        assert_eq!(location["region"]["endLine"], 9);
+    }
+}
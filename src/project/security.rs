@@ -0,0 +1,407 @@
+//! Security rules: definitions a security review would ask about
+//!
+//! - `broad-grant`: `CONTROL`, `ALTER`, `ALTER ANY ...` or `TAKE OWNERSHIP` granted to
+//!   `public` or another principal the ruleset lists as broad
+//! - `dynamic-sql`: a procedure executing SQL built from one of its string parameters,
+//!   through `EXEC (...)` or the statement argument of `sp_executesql`
+//! - `execute-as-owner`: a procedure, function or trigger running `EXECUTE AS OWNER` or
+//!   `EXECUTE AS 'dbo'`
+//!
+//! Parameters wrapped in `QUOTENAME` and values passed to `sp_executesql` as parameters
+//! are safe and not reported.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use super::lint::{relative_source, LintIssue};
+use super::ruleset::SecurityRules;
+use super::SqlProject;
+use crate::model::{DatabaseModel, ModelElement};
+use crate::parser::lexer::{tokenize, Token};
+use crate::parser::{extract_module_options, extract_procedure_parameters_tokens};
+
+/// Names of the security rules, reported as errors in SARIF
+pub(super) const SECURITY_RULES: &[&str] = &["broad-grant", "dynamic-sql", "execute-as-owner"];
+
+/// Permissions that let the grantee change or take over what they are granted on
+fn is_elevated(permission: &str) -> bool {
+    let permission = permission.to_uppercase();
+    matches!(permission.as_str(), "CONTROL" | "ALTER" | "TAKE OWNERSHIP")
+        || permission.starts_with("ALTER ANY ")
+}
+
+/// Risky definitions, in model order
+pub(super) fn check_security(
+    model: &DatabaseModel,
+    project: &SqlProject,
+    rules: &SecurityRules,
+) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    for (index, element) in model.elements.iter().enumerate() {
+        let mut issue = |rule: &'static str, object_type: &'static str, message: String| {
+            issues.push(LintIssue::Code {
+                rule,
+                object_type,
+                name: element.full_name(),
+                message,
+                location: relative_source(model, index, project),
+            })
+        };
+        match element {
+            ModelElement::Permission(p)
+                if p.action.eq_ignore_ascii_case("Grant")
+                    && is_elevated(&p.permission)
+                    && (p.principal.eq_ignore_ascii_case("public")
+                        || rules
+                            .broad_principals
+                            .iter()
+                            .any(|broad| broad.eq_ignore_ascii_case(&p.principal))) =>
+            {
+                issue(
+                    "broad-grant",
+                    "Permission",
+                    format!(
+                        "grants {} to [{}]",
+                        p.permission.to_uppercase(),
+                        p.principal
+                    ),
+                );
+            }
+            ModelElement::Procedure(p) => {
+                let parameters: HashSet<String> =
+                    extract_procedure_parameters_tokens(&p.definition)
+                        .into_iter()
+                        .filter(|p| is_string_type(&p.data_type))
+                        .map(|p| format!("@{}", p.name.to_lowercase()))
+                        .collect();
+                let executed = dynamic_sql_parameters(&p.definition, &parameters);
+                if !executed.is_empty() {
+                    let names: Vec<String> = executed.into_iter().collect();
+                    issue(
+                        "dynamic-sql",
+                        "Procedure",
+                        format!("executes dynamic SQL built from {}", names.join(", ")),
+                    );
+                }
+                if let Some(principal) = owner_principal(&p.definition) {
+                    issue("execute-as-owner", "Procedure", principal);
+                }
+            }
+            ModelElement::Function(f) => {
+                if let Some(principal) = owner_principal(&f.definition) {
+                    issue("execute-as-owner", "Function", principal);
+                }
+            }
+            ModelElement::Trigger(t) => {
+                if let Some(principal) = owner_principal(&t.definition) {
+                    issue("execute-as-owner", "Trigger", principal);
+                }
+            }
+            _ => {}
+        }
+    }
+    issues
+}
+
+/// The message for a module running as its owner or `dbo`
+fn owner_principal(definition: &str) -> Option<String> {
+    let options = extract_module_options(definition);
+    let principal = options.execute_as()?;
+    (principal.eq_ignore_ascii_case("OWNER") || principal.eq_ignore_ascii_case("'dbo'"))
+        .then(|| format!("runs with EXECUTE AS {}", principal))
+}
+
+fn is_string_type(data_type: &str) -> bool {
+    let base = data_type
+        .split('(')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .trim_matches(['[', ']'])
+        .to_lowercase();
+    matches!(
+        base.as_str(),
+        "char" | "varchar" | "nchar" | "nvarchar" | "sysname" | "text" | "ntext"
+    )
+}
+
+/// Statement keywords ending an assigned or executed expression
+const STATEMENT_KEYWORDS: &[&str] = &[
+    "BEGIN",
+    "CLOSE",
+    "DEALLOCATE",
+    "DECLARE",
+    "DELETE",
+    "ELSE",
+    "END",
+    "EXEC",
+    "EXECUTE",
+    "FETCH",
+    "FROM",
+    "IF",
+    "INSERT",
+    "MERGE",
+    "OPEN",
+    "PRINT",
+    "RAISERROR",
+    "RETURN",
+    "SELECT",
+    "SET",
+    "THROW",
+    "UPDATE",
+    "WHERE",
+    "WHILE",
+];
+
+/// Parameters (from `parameters`, lowercased with `@`) whose values reach executed SQL,
+/// directly or through variables assigned from them
+fn dynamic_sql_parameters(definition: &str, parameters: &HashSet<String>) -> BTreeSet<String> {
+    let mut executed = BTreeSet::new();
+    let Ok(tokens) = tokenize(definition) else {
+        return executed;
+    };
+    let tokens: Vec<Token> = tokens
+        .into_iter()
+        .filter(|t| !matches!(t, Token::Whitespace(_)))
+        .collect();
+    let word = |i: usize| match tokens.get(i) {
+        Some(Token::Word(w)) if w.quote_style.is_none() => Some(w.value.to_lowercase()),
+        _ => None,
+    };
+    let variable = |i: usize| word(i).filter(|w| w.starts_with('@'));
+
+    // Variables holding parameter values, with the parameter they came from
+    let mut tainted: HashMap<String, String> = HashMap::new();
+    let mut declaring = false;
+    let mut i = 0;
+    while i < tokens.len() {
+        let keyword = word(i).map(|w| w.to_uppercase());
+        match keyword.as_deref() {
+            Some("DECLARE") => declaring = true,
+            Some(k) if STATEMENT_KEYWORDS.contains(&k) => declaring = false,
+            _ => {}
+        }
+        if matches!(tokens[i], Token::SemiColon) {
+            declaring = false;
+        }
+
+        // SET @v = ..., SELECT @v = ..., SET @v += ... and DECLARE @v type = ...
+        if let Some(target) = variable(i) {
+            let assigns = matches!(
+                keyword_at(&tokens, i.wrapping_sub(1)).as_deref(),
+                Some("SET" | "SELECT")
+            ) || matches!(tokens.get(i.wrapping_sub(1)), Some(Token::Comma))
+                || declaring;
+            let mut j = i + 1;
+            if declaring {
+                // Skip the type, e.g. NVARCHAR(MAX)
+                let mut depth = 0;
+                while let Some(token) = tokens.get(j) {
+                    match token {
+                        Token::LParen => depth += 1,
+                        Token::RParen => depth -= 1,
+                        Token::Eq | Token::Comma | Token::SemiColon if depth == 0 => break,
+                        Token::Word(_) if depth == 0 && j > i + 1 && is_statement(&tokens[j]) => {
+                            break
+                        }
+                        _ => {}
+                    }
+                    j += 1;
+                }
+            }
+            if matches!(tokens.get(j), Some(Token::Plus)) {
+                j += 1;
+            }
+            if assigns && matches!(tokens.get(j), Some(Token::Eq)) {
+                let end = expression_end(&tokens, j + 1);
+                if let Some(source) = taint_source(&tokens[j + 1..end], parameters, &tainted) {
+                    tainted.insert(target, source);
+                }
+                i = end;
+                continue;
+            }
+        }
+
+        if matches!(keyword.as_deref(), Some("EXEC" | "EXECUTE")) {
+            let mut j = i + 1;
+            // EXEC @result = procedure ...
+            if variable(j).is_some() && matches!(tokens.get(j + 1), Some(Token::Eq)) {
+                j += 2;
+            }
+            let statement = if matches!(tokens.get(j), Some(Token::LParen)) {
+                let end = closing_paren(&tokens, j);
+                Some(j + 1..end)
+            } else {
+                // [sys].[sp_executesql] @stmt = ..., takes the statement first
+                let is_name_part = |token: Option<&Token>| match token {
+                    Some(Token::Word(w)) => {
+                        !w.value.eq_ignore_ascii_case("sp_executesql") && !w.value.starts_with('@')
+                    }
+                    Some(Token::Period) => true,
+                    _ => false,
+                };
+                while is_name_part(tokens.get(j)) {
+                    j += 1;
+                }
+                match tokens.get(j) {
+                    Some(Token::Word(w)) if w.value.eq_ignore_ascii_case("sp_executesql") => {
+                        j += 1;
+                        if variable(j).is_some() && matches!(tokens.get(j + 1), Some(Token::Eq)) {
+                            j += 2;
+                        }
+                        Some(j..expression_end(&tokens, j))
+                    }
+                    _ => None,
+                }
+            };
+            if let Some(range) = statement {
+                if let Some(source) = taint_source(&tokens[range.clone()], parameters, &tainted) {
+                    executed.insert(source);
+                }
+                i = range.end.max(i + 1);
+                continue;
+            }
+        }
+        i += 1;
+    }
+    executed
+}
+
+fn keyword_at(tokens: &[Token], i: usize) -> Option<String> {
+    match tokens.get(i) {
+        Some(Token::Word(w)) if w.quote_style.is_none() => Some(w.value.to_uppercase()),
+        _ => None,
+    }
+}
+
+fn is_statement(token: &Token) -> bool {
+    matches!(token, Token::Word(w) if w.quote_style.is_none()
+        && STATEMENT_KEYWORDS.iter().any(|k| w.value.eq_ignore_ascii_case(k)))
+}
+
+/// Index past the expression starting at `start`: up to a `;`, a `,` or a statement
+/// keyword outside parentheses
+fn expression_end(tokens: &[Token], start: usize) -> usize {
+    let mut depth = 0usize;
+    let mut i = start;
+    while let Some(token) = tokens.get(i) {
+        match token {
+            Token::LParen => depth += 1,
+            Token::RParen if depth == 0 => break,
+            Token::RParen => depth -= 1,
+            Token::SemiColon | Token::Comma if depth == 0 => break,
+            _ if depth == 0 && i > start && is_statement(token) => break,
+            _ => {}
+        }
+        i += 1;
+    }
+    i
+}
+
+/// Index of the `)` closing the `(` at `open`, or the end of the tokens
+fn closing_paren(tokens: &[Token], open: usize) -> usize {
+    let mut depth = 0usize;
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        match token {
+            Token::LParen => depth += 1,
+            Token::RParen => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => {}
+        }
+    }
+    tokens.len()
+}
+
+/// The parameter an expression's value comes from, ignoring those inside `QUOTENAME`
+fn taint_source(
+    expression: &[Token],
+    parameters: &HashSet<String>,
+    tainted: &HashMap<String, String>,
+) -> Option<String> {
+    let mut i = 0;
+    while i < expression.len() {
+        match &expression[i] {
+            Token::Word(w)
+                if w.value.eq_ignore_ascii_case("QUOTENAME")
+                    && matches!(expression.get(i + 1), Some(Token::LParen)) =>
+            {
+                i = closing_paren(expression, i + 1);
+            }
+            Token::Word(w) if w.quote_style.is_none() && w.value.starts_with('@') => {
+                let name = w.value.to_lowercase();
+                if parameters.contains(&name) {
+                    return Some(w.value.clone());
+                }
+                if let Some(source) = tainted.get(&name) {
+                    return Some(source.clone());
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn executed(definition: &str) -> Vec<String> {
+        let parameters = ["@filter", "@table"].map(String::from).into();
+        dynamic_sql_parameters(definition, &parameters)
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_dynamic_sql_parameters() {
+        assert_eq!(
+            executed("EXEC ('SELECT * FROM Orders WHERE Name = ''' + @Filter + '''')"),
+            vec!["@Filter"]
+        );
+        assert_eq!(
+            executed(
+                "DECLARE @sql NVARCHAR(MAX) = N'SELECT * FROM ' + @Table;\n\
+                 SET @sql += N' WHERE 1 = 1';\n\
+                 EXEC sys.sp_executesql @sql;"
+            ),
+            vec!["@Table"]
+        );
+        assert_eq!(
+            executed(
+                "DECLARE @where NVARCHAR(100);\n\
+                 SELECT @where = N' WHERE Name = ''' + @Filter + N'''';\n\
+                 DECLARE @sql NVARCHAR(MAX) = N'SELECT * FROM Orders' + @where;\n\
+                 EXECUTE (@sql);"
+            ),
+            vec!["@Filter"]
+        );
+
+        // Quoted names and parameterized statements are safe
+        assert!(executed(
+            "DECLARE @sql NVARCHAR(MAX) = N'SELECT * FROM ' + QUOTENAME(@Table) + N' WHERE Name = @Filter';\n\
+             EXEC sp_executesql @sql, N'@Filter NVARCHAR(50)', @Filter = @Filter;"
+        )
+        .is_empty());
+        assert!(executed("EXEC dbo.GetOrders @Filter; SET @x = @Filter + 'a';").is_empty());
+    }
+
+    #[test]
+    fn test_owner_principal() {
+        assert_eq!(
+            owner_principal("CREATE PROCEDURE dbo.P WITH EXECUTE AS OWNER AS SELECT 1"),
+            Some("runs with EXECUTE AS OWNER".to_string())
+        );
+        assert!(
+            owner_principal("CREATE PROCEDURE dbo.P WITH EXECUTE AS 'dbo' AS SELECT 1").is_some()
+        );
+        assert_eq!(
+            owner_principal("CREATE PROCEDURE dbo.P WITH EXECUTE AS CALLER AS SELECT 1"),
+            None
+        );
+    }
+}
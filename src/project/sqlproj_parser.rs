@@ -1,11 +1,21 @@
 //! Parser for .sqlproj files
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use roxmltree::Document;
 
+use super::conditions::{evaluate_condition, is_excluded_for_platform};
+use super::objects_per_file::ObjectsPerFile;
+use super::profiles::{find_dacpac_profiles, DacpacProfile};
+use super::properties_files::{
+    find_properties_files, read_database_settings, read_sqlcmd_variables,
+};
+use super::unrecognized::{find_unrecognized_elements, UnrecognizedElement};
 use crate::error::SqlPackageError;
+use crate::parser::FileEncoding;
+use crate::util::long_path;
 
 /// SQL Server version target
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -31,6 +41,12 @@ impl std::str::FromStr for SqlServerVersion {
     }
 }
 
+impl std::fmt::Display for SqlServerVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Sql{}", self.compatibility_mode())
+    }
+}
+
 impl SqlServerVersion {
     pub fn dsp_name(&self) -> &'static str {
         match self {
@@ -89,6 +105,15 @@ pub struct SqlCmdVariable {
     pub default_value: String,
 }
 
+/// Deployment contributor argument from sqlproj `<ContributorArguments>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContributorArgument {
+    /// Argument name (e.g., "SqlPackageFilter")
+    pub name: String,
+    /// Argument value (e.g., "IgnoreSchema(dev)")
+    pub value: String,
+}
+
 /// Database options from sqlproj PropertyGroup
 #[derive(Debug, Clone)]
 pub struct DatabaseOptions {
@@ -165,12 +190,25 @@ pub struct SqlProject {
     pub package_references: Vec<PackageReference>,
     /// SQLCMD variables from sqlproj
     pub sqlcmd_variables: Vec<SqlCmdVariable>,
+    /// Variable values used to evaluate `:IF` blocks in SQL files and deploy scripts:
+    /// SQLCMD variable defaults, overridden by values passed to the build. Keyed by
+    /// [`crate::parser::variable_key`], so names match case-insensitively
+    pub build_variables: HashMap<String, String>,
     /// Project directory
     pub project_dir: PathBuf,
     /// Pre-deployment script file (optional, at most one)
     pub pre_deploy_script: Option<PathBuf>,
     /// Post-deployment script file (optional, at most one)
     pub post_deploy_script: Option<PathBuf>,
+    /// Inline `:r` includes when packaging the deploy scripts (default: true); when false
+    /// the directives are kept for SQLCMD to resolve at deploy time
+    pub resolve_deploy_includes: bool,
+    /// Add MS_Description extended properties from header comments (default: false), see
+    /// [`crate::parser::extract_header_comment`]
+    pub header_descriptions: bool,
+    /// Add MS_Description extended properties to columns from trailing `-- description:`
+    /// comments (default: false), see [`crate::parser::extract_column_descriptions`]
+    pub column_descriptions: bool,
     /// ANSI_NULLS setting (default: true)
     pub ansi_nulls: bool,
     /// QUOTED_IDENTIFIER setting (default: true)
@@ -181,13 +219,90 @@ pub struct SqlProject {
     pub dac_version: String,
     /// DAC description for metadata (optional)
     pub dac_description: Option<String>,
+    /// Deployment contributor IDs from `<DeploymentContributors>` (semicolon-separated)
+    pub deployment_contributors: Vec<String>,
+    /// Deployment contributor arguments from `<ContributorArguments>` (semicolon-separated key=value)
+    pub contributor_arguments: Vec<ContributorArgument>,
+    /// Extra dacpacs to build with filters applied, from `<DacpacProfile>` items
+    pub dacpac_profiles: Vec<DacpacProfile>,
+    /// Encoding of SQL files without a byte order mark, from `<DefaultFileEncoding>`
+    /// (sniffed per file when not set)
+    pub default_file_encoding: Option<FileEncoding>,
+    /// Whether a SQL file may define more than one object, from `<ObjectsPerFile>`
+    pub objects_per_file: ObjectsPerFile,
+    /// Files the project names but does not build: `None` and `NotInBuild` items, and
+    /// files removed with `<Build Remove>`
+    pub skipped_files: Vec<SkippedFile>,
+    /// Elements of the project file the build does not apply, such as
+    /// `BuildExtensionConfiguration` items and custom targets
+    pub unrecognized_elements: Vec<UnrecognizedElement>,
+    /// Non-fatal warnings raised while parsing the project file
+    pub warnings: Vec<String>,
+}
+
+/// A file left out of the build by the project file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedFile {
+    /// Path of the file
+    pub path: PathBuf,
+    /// The item leaving it out: `None`, `NotInBuild` or `Build Remove`
+    pub item: &'static str,
+}
+
+/// An implicit empty project with the same defaults `parse_sqlproj` applies
+/// when a property is not specified.
+impl Default for SqlProject {
+    fn default() -> Self {
+        Self {
+            name: "Database".to_string(),
+            target_platform: SqlServerVersion::default(),
+            default_schema: "dbo".to_string(),
+            collation_lcid: 1033,
+            collation_case_sensitive: false,
+            sql_files: Vec::new(),
+            dacpac_references: Vec::new(),
+            package_references: Vec::new(),
+            sqlcmd_variables: Vec::new(),
+            build_variables: HashMap::new(),
+            project_dir: PathBuf::from("."),
+            pre_deploy_script: None,
+            post_deploy_script: None,
+            resolve_deploy_includes: true,
+            header_descriptions: false,
+            column_descriptions: false,
+            ansi_nulls: true,
+            quoted_identifier: true,
+            database_options: DatabaseOptions::default(),
+            dac_version: "1.0.0.0".to_string(),
+            dac_description: None,
+            deployment_contributors: Vec::new(),
+            contributor_arguments: Vec::new(),
+            dacpac_profiles: Vec::new(),
+            default_file_encoding: None,
+            objects_per_file: ObjectsPerFile::default(),
+            skipped_files: Vec::new(),
+            unrecognized_elements: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
 }
 
 /// Parse a .sqlproj file
 pub fn parse_sqlproj(path: &Path) -> Result<SqlProject> {
-    let content = std::fs::read_to_string(path).map_err(|e| SqlPackageError::ProjectReadError {
-        path: path.to_path_buf(),
-        source: e,
+    parse_sqlproj_with_properties(path, &[])
+}
+
+/// Parse a .sqlproj file with MSBuild properties given on the command line, which
+/// override the project's properties and the environment in item `Condition`s
+pub fn parse_sqlproj_with_properties(
+    path: &Path,
+    properties: &[(String, String)],
+) -> Result<SqlProject> {
+    let content = std::fs::read_to_string(long_path(path)).map_err(|e| {
+        SqlPackageError::ProjectReadError {
+            path: path.to_path_buf(),
+            source: e,
+        }
     })?;
 
     let doc = Document::parse(&content).map_err(|e| SqlPackageError::ProjectParseError {
@@ -214,8 +329,19 @@ pub fn parse_sqlproj(path: &Path) -> Result<SqlProject> {
     let default_schema =
         find_property_value(&root, "DefaultSchema").unwrap_or_else(|| "dbo".to_string());
 
+    // Non-fatal project warnings (also printed to stderr as they are found)
+    let mut warnings = Vec::new();
+
+    // Visual Studio database project settings files, overridden by the project's properties
+    let properties_files = find_properties_files(&root, &project_dir, &mut warnings);
+    let settings = properties_files
+        .settings
+        .as_deref()
+        .map(|path| read_database_settings(path, &mut warnings))
+        .unwrap_or_default();
+
     // Parse database options first to get collation name
-    let database_options = parse_database_options(&root);
+    let database_options = parse_database_options(&root, &settings);
 
     // Derive collation LCID and case sensitivity from collation name
     let collation_info = database_options
@@ -227,10 +353,16 @@ pub fn parse_sqlproj(path: &Path) -> Result<SqlProject> {
     let collation_case_sensitive = collation_info.case_sensitive;
 
     // Parse ANSI_NULLS setting (default: true)
-    let ansi_nulls = parse_bool_property(&root, "AnsiNulls", true);
+    let ansi_nulls = parse_setting(&root, &settings, "AnsiNulls", "AnsiNulls", true);
 
     // Parse QUOTED_IDENTIFIER setting (default: true)
-    let quoted_identifier = parse_bool_property(&root, "QuotedIdentifier", true);
+    let quoted_identifier = parse_setting(
+        &root,
+        &settings,
+        "QuotedIdentifier",
+        "QuotedIdentifier",
+        true,
+    );
 
     // Parse DAC version (default: "1.0.0.0" per DacFx behavior)
     let dac_version =
@@ -239,8 +371,49 @@ pub fn parse_sqlproj(path: &Path) -> Result<SqlProject> {
     // Parse DAC description (optional, omit if not specified)
     let dac_description = find_property_value(&root, "DacDescription");
 
-    // Find all SQL files
-    let sql_files = find_sql_files(&root, &project_dir)?;
+    // Parse deployment contributor configuration (recorded in DacMetadata.xml)
+    let deployment_contributors = find_property_value(&root, "DeploymentContributors")
+        .map(|v| parse_deployment_contributors(&v))
+        .unwrap_or_default();
+    let contributor_arguments = find_property_value(&root, "ContributorArguments")
+        .map(|v| parse_contributor_arguments(&v))
+        .unwrap_or_default();
+
+    // Parse the encoding of SQL files without a BOM (optional)
+    let default_file_encoding =
+        find_property_value(&root, "DefaultFileEncoding").and_then(|v| match v.parse() {
+            Ok(encoding) => Some(encoding),
+            Err(e) => {
+                let warning = format!("{}, detecting the encoding of each file", e);
+                eprintln!("Warning: {}", warning);
+                warnings.push(warning);
+                None
+            }
+        });
+
+    // Parse the one-object-per-file policy (optional)
+    let objects_per_file = match find_property_value(&root, "ObjectsPerFile") {
+        Some(v) => v
+            .parse()
+            .map_err(|message| SqlPackageError::InvalidProjectFormat { message })?,
+        None => ObjectsPerFile::default(),
+    };
+
+    // Find all SQL files, and the permissions file, which compiles like one
+    let mut skipped_files = Vec::new();
+    let mut sql_files = find_sql_files(
+        &root,
+        &project_dir,
+        target_platform,
+        properties,
+        &mut skipped_files,
+        &mut warnings,
+    )?;
+    if let Some(permissions) = properties_files.permissions {
+        if !sql_files.contains(&permissions) {
+            sql_files.push(permissions);
+        }
+    }
 
     // Find dacpac references
     let dacpac_references = find_dacpac_references(&root, &project_dir);
@@ -248,11 +421,37 @@ pub fn parse_sqlproj(path: &Path) -> Result<SqlProject> {
     // Find package references (NuGet packages)
     let package_references = find_package_references(&root);
 
-    // Find SQLCMD variables
-    let sqlcmd_variables = find_sqlcmd_variables(&root);
+    // Find SQLCMD variables, adding those only a .sqlcmdvars file defines
+    let mut sqlcmd_variables = find_sqlcmd_variables(&root);
+    if let Some(path) = &properties_files.sqlcmd_variables {
+        for variable in read_sqlcmd_variables(path, &mut warnings) {
+            if !sqlcmd_variables
+                .iter()
+                .any(|v| v.name.eq_ignore_ascii_case(&variable.name))
+            {
+                sqlcmd_variables.push(variable);
+            }
+        }
+    }
+    let build_variables = sqlcmd_variables
+        .iter()
+        .map(|v| {
+            (
+                crate::parser::variable_key(&v.name),
+                v.default_value.clone(),
+            )
+        })
+        .collect();
 
     // Find pre/post deployment scripts
-    let (pre_deploy_script, post_deploy_script) = find_deployment_scripts(&root, &project_dir);
+    let (pre_deploy_script, post_deploy_script) =
+        find_deployment_scripts(&root, &project_dir, &mut warnings);
+
+    // Find dacpac profiles (extra filtered dacpacs)
+    let dacpac_profiles = find_dacpac_profiles(&root)?;
+
+    // Elements the build does not apply, for the build's unknown element policy
+    let unrecognized_elements = find_unrecognized_elements(&root);
 
     Ok(SqlProject {
         name: project_name,
@@ -264,43 +463,185 @@ pub fn parse_sqlproj(path: &Path) -> Result<SqlProject> {
         dacpac_references,
         package_references,
         sqlcmd_variables,
+        build_variables,
         project_dir,
         pre_deploy_script,
         post_deploy_script,
+        resolve_deploy_includes: true,
+        header_descriptions: false,
+        column_descriptions: false,
         ansi_nulls,
         quoted_identifier,
         database_options,
         dac_version,
         dac_description,
+        deployment_contributors,
+        contributor_arguments,
+        dacpac_profiles,
+        default_file_encoding,
+        objects_per_file,
+        skipped_files,
+        unrecognized_elements,
+        warnings,
+    })
+}
+
+/// Build a project from a folder of SQL files, without a .sqlproj
+///
+/// Every .sql file under `dir` (except in bin and obj) is included, with default
+/// project options. Files named `PreDeployment.sql` or `*.PreDeployment.sql` (as Visual
+/// Studio names them) and the PostDeployment equivalents become the pre- and
+/// post-deployment scripts instead, and the files they include with `:r` are left out.
+/// `name` defaults to the folder's name.
+pub fn project_from_dir(
+    dir: &Path,
+    name: Option<&str>,
+    target_platform: SqlServerVersion,
+) -> Result<SqlProject> {
+    if !dir.is_dir() {
+        return Err(SqlPackageError::InvalidProjectFormat {
+            message: format!("{} is not a directory of SQL files", dir.display()),
+        }
+        .into());
+    }
+    let name = name.map(str::to_string).unwrap_or_else(|| {
+        std::fs::canonicalize(long_path(dir))
+            .ok()
+            .and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "Database".to_string())
+    });
+
+    let mut warnings = Vec::new();
+    let mut pre_deploy_script = None;
+    let mut post_deploy_script = None;
+    let mut sql_files = sql_files_in_dir(dir);
+    sql_files.sort();
+    sql_files.retain(|file| {
+        let file_name = file
+            .file_name()
+            .map(|n| n.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let is_script =
+            |suffix: &str| file_name == suffix || file_name.ends_with(&format!(".{}", suffix));
+        let (script, kind) = if is_script("predeployment.sql") {
+            (&mut pre_deploy_script, "PreDeploy")
+        } else if is_script("postdeployment.sql") {
+            (&mut post_deploy_script, "PostDeploy")
+        } else {
+            return !is_excluded_for_platform(file, target_platform);
+        };
+        if script.is_some() {
+            let warning = format!("Multiple {} scripts found, using first one", kind);
+            eprintln!("Warning: {}", warning);
+            warnings.push(warning);
+        } else {
+            *script = Some(file.clone());
+        }
+        false
+    });
+
+    // Files pulled into the deployment scripts with :r are not schema objects
+    let deploy_includes: Vec<PathBuf> = pre_deploy_script
+        .iter()
+        .chain(&post_deploy_script)
+        .flat_map(|script| crate::parser::included_files(script))
+        .collect();
+    sql_files.retain(|file| {
+        long_path(file)
+            .canonicalize()
+            .map_or(true, |file| !deploy_includes.contains(&file))
+    });
+
+    Ok(SqlProject {
+        name,
+        target_platform,
+        sql_files,
+        project_dir: dir.to_path_buf(),
+        pre_deploy_script,
+        post_deploy_script,
+        warnings,
+        ..SqlProject::default()
     })
 }
 
-/// Parse database options from sqlproj PropertyGroup
-fn parse_database_options(root: &roxmltree::Node) -> DatabaseOptions {
+/// Parse a `<DeploymentContributors>` value into contributor IDs.
+/// Format: `Contributor.One;Contributor.Two` (empty entries are ignored)
+fn parse_deployment_contributors(value: &str) -> Vec<String> {
+    value
+        .split(';')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// Parse a `<ContributorArguments>` value into name/value pairs.
+/// Format: `Name1=Value1;Name2=Value2`. Entries without `=` are kept with an empty value.
+fn parse_contributor_arguments(value: &str) -> Vec<ContributorArgument> {
+    value
+        .split(';')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|entry| match entry.split_once('=') {
+            Some((name, value)) => ContributorArgument {
+                name: name.trim().to_string(),
+                value: value.trim().to_string(),
+            },
+            None => ContributorArgument {
+                name: entry.to_string(),
+                value: String::new(),
+            },
+        })
+        .collect()
+}
+
+/// Parse database options from sqlproj PropertyGroup, falling back to a .sqlsettings
+/// file's `settings`
+fn parse_database_options(
+    root: &roxmltree::Node,
+    settings: &HashMap<String, String>,
+) -> DatabaseOptions {
     let mut options = DatabaseOptions::default();
+    let property =
+        |name: &str| find_property_value(root, name).or_else(|| settings.get(name).cloned());
 
     // String properties - override defaults if specified
-    if let Some(collation) = find_property_value(root, "DefaultCollation") {
+    if let Some(collation) = property("DefaultCollation") {
         options.collation = Some(collation);
     }
-    if let Some(page_verify) = find_property_value(root, "PageVerify") {
+    if let Some(page_verify) = property("PageVerify") {
         options.page_verify = Some(page_verify);
     }
-    if let Some(filegroup) = find_property_value(root, "DefaultFilegroup") {
+    if let Some(filegroup) = property("DefaultFilegroup") {
         options.default_filegroup = Some(filegroup);
     }
 
-    // Boolean properties - use helper to reduce boilerplate
-    options.ansi_null_default_on = parse_bool_property(root, "AnsiNullDefaultOn", true);
-    options.ansi_nulls_on = parse_bool_property(root, "AnsiNullsOn", true);
-    options.ansi_warnings_on = parse_bool_property(root, "AnsiWarningsOn", true);
-    options.arith_abort_on = parse_bool_property(root, "ArithAbortOn", true);
-    options.concat_null_yields_null_on = parse_bool_property(root, "ConcatNullYieldsNullOn", true);
-    options.full_text_enabled = parse_bool_property(root, "FullTextEnabled", true);
+    // Boolean properties: the project's name first, then the .sqlsettings name
+    let flag = |name: &str, setting: &str| parse_setting(root, settings, name, setting, true);
+    options.ansi_null_default_on = flag("AnsiNullDefaultOn", "AnsiNullDefault");
+    options.ansi_nulls_on = flag("AnsiNullsOn", "AnsiNulls");
+    options.ansi_warnings_on = flag("AnsiWarningsOn", "AnsiWarnings");
+    options.arith_abort_on = flag("ArithAbortOn", "ArithAbort");
+    options.concat_null_yields_null_on = flag("ConcatNullYieldsNullOn", "ConcatNullYieldsNull");
+    options.full_text_enabled = flag("FullTextEnabled", "EnableFullTextSearch");
 
     options
 }
 
+/// A boolean project property, else the `setting` of a .sqlsettings file, else `default`
+fn parse_setting(
+    root: &roxmltree::Node,
+    settings: &HashMap<String, String>,
+    property_name: &str,
+    setting: &str,
+    default: bool,
+) -> bool {
+    find_property_value(root, property_name)
+        .or_else(|| settings.get(setting).cloned())
+        .map(|v| v.eq_ignore_ascii_case("true"))
+        .unwrap_or(default)
+}
+
 fn find_property_value(root: &roxmltree::Node, property_name: &str) -> Option<String> {
     for node in root.descendants() {
         if node.tag_name().name() == property_name {
@@ -310,13 +651,6 @@ fn find_property_value(root: &roxmltree::Node, property_name: &str) -> Option<St
     None
 }
 
-/// Parse a boolean property from the project file, returning `default` if not found.
-fn parse_bool_property(root: &roxmltree::Node, property_name: &str, default: bool) -> bool {
-    find_property_value(root, property_name)
-        .map(|v| v.eq_ignore_ascii_case("true"))
-        .unwrap_or(default)
-}
-
 /// Find the text content of a child element by tag name.
 fn find_child_text(node: &roxmltree::Node, tag_name: &str) -> Option<String> {
     node.children()
@@ -338,21 +672,41 @@ fn extract_version_from_dsp(dsp: &str) -> Option<SqlServerVersion> {
         .map(|(_, version)| *version)
 }
 
-fn find_sql_files(root: &roxmltree::Node, project_dir: &Path) -> Result<Vec<PathBuf>> {
+fn find_sql_files(
+    root: &roxmltree::Node,
+    project_dir: &Path,
+    target_platform: SqlServerVersion,
+    properties: &[(String, String)],
+    skipped_files: &mut Vec<SkippedFile>,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<PathBuf>> {
     let mut sql_files = Vec::new();
     let mut include_patterns: Vec<String> = Vec::new();
     let mut exclude_patterns: Vec<String> = Vec::new();
+    let mut not_built_patterns: Vec<(String, &'static str)> = Vec::new();
 
-    // Collect Build Include and Remove patterns
+    // Collect Build Include and Remove patterns, and the None and NotInBuild items
     for node in root.descendants() {
-        if node.tag_name().name() == "Build" {
-            if let Some(include) = node.attribute("Include") {
-                include_patterns.push(include.replace('\\', "/"));
-            }
-            if let Some(remove) = node.attribute("Remove") {
-                exclude_patterns.push(remove.replace('\\', "/"));
+        let item = match node.tag_name().name() {
+            "Build" => "Build",
+            "None" => "None",
+            "NotInBuild" => "NotInBuild",
+            _ => continue,
+        };
+        if !is_condition_met(root, &node, target_platform, properties, warnings) {
+            continue;
+        }
+        if let Some(include) = node.attribute("Include") {
+            let include = include.replace('\\', "/");
+            if item == "Build" {
+                include_patterns.push(include);
+            } else {
+                not_built_patterns.push((include, item));
             }
         }
+        if let Some(remove) = node.attribute("Remove").filter(|_| item == "Build") {
+            exclude_patterns.push(remove.replace('\\', "/"));
+        }
     }
 
     // Process include patterns
@@ -370,8 +724,7 @@ fn find_sql_files(root: &roxmltree::Node, project_dir: &Path) -> Result<Vec<Path
             }
         } else if pattern.to_lowercase().ends_with(".sql") {
             // Direct file path
-            let sql_path = project_dir.join(pattern);
-            if sql_path.exists() {
+            if let Some(sql_path) = find_item_file(project_dir, pattern, "Build", warnings) {
                 sql_files.push(sql_path);
             }
         }
@@ -379,51 +732,218 @@ fn find_sql_files(root: &roxmltree::Node, project_dir: &Path) -> Result<Vec<Path
 
     // If no explicit Build items, glob for .sql files in project directory (SDK-style default)
     if sql_files.is_empty() && include_patterns.is_empty() {
-        for entry in walkdir::WalkDir::new(project_dir)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
-            if path.extension().is_some_and(|ext| ext == "sql") {
-                // Skip bin and obj directories
-                let path_str = path.to_string_lossy();
-                if !path_str.contains("/bin/")
-                    && !path_str.contains("/obj/")
-                    && !path_str.contains("\\bin\\")
-                    && !path_str.contains("\\obj\\")
-                {
-                    sql_files.push(path.to_path_buf());
-                }
-            }
-        }
+        sql_files = sql_files_in_dir(project_dir);
     }
 
     // Apply exclusion patterns (after SDK-style glob so Remove works for both styles)
+    let exclude_paths: Vec<PathBuf> = exclude_patterns
+        .iter()
+        .filter(|pattern| !pattern.contains('*'))
+        .map(|pattern| {
+            resolve_ignoring_case(project_dir, pattern).unwrap_or_else(|| project_dir.join(pattern))
+        })
+        .collect();
     if !exclude_patterns.is_empty() {
         sql_files.retain(|file| {
-            for pattern in &exclude_patterns {
-                if pattern.contains('*') {
-                    let glob_pattern = project_dir.join(pattern);
-                    let glob_str = glob_pattern.to_string_lossy();
-                    if let Ok(matcher) = glob::Pattern::new(&glob_str) {
-                        if matcher.matches_path(file) {
-                            return false;
-                        }
-                    }
-                } else {
-                    let exclude_path = project_dir.join(pattern);
-                    if file == &exclude_path {
-                        return false;
-                    }
-                }
+            let removed = exclude_patterns.iter().any(|pattern| {
+                pattern.contains('*')
+                    && glob::Pattern::new(&project_dir.join(pattern).to_string_lossy())
+                        .is_ok_and(|matcher| matcher.matches_path(file))
+            }) || exclude_paths.contains(file);
+            if removed {
+                skip_file(skipped_files, file, "Build Remove");
             }
-            true
+            !removed
         });
     }
 
+    // None and NotInBuild items are not compiled, even when a glob picks them up; a
+    // Build item naming the file itself wins
+    for (pattern, item) in &not_built_patterns {
+        let files = if pattern.contains('*') {
+            glob::glob(&project_dir.join(pattern).to_string_lossy())
+                .map(|paths| paths.filter_map(|p| p.ok()).collect())
+                .unwrap_or_default()
+        } else {
+            resolve_ignoring_case(project_dir, pattern)
+                .into_iter()
+                .collect::<Vec<_>>()
+        };
+        for file in files.into_iter().filter(|file| {
+            file.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("sql"))
+        }) {
+            let named = include_patterns.iter().any(|include| {
+                !include.contains('*')
+                    && resolve_ignoring_case(project_dir, include).as_ref() == Some(&file)
+            });
+            if !named {
+                sql_files.retain(|f| f != &file);
+                skip_file(skipped_files, &file, item);
+            }
+        }
+    }
+
+    // A file reached twice, e.g. through a symlink or a glob and a Build item, is built once
+    let mut seen: HashMap<PathBuf, PathBuf> = HashMap::new();
+    sql_files.retain(|file| {
+        let key = long_path(file)
+            .canonicalize()
+            .unwrap_or_else(|_| file.clone());
+        match seen.get(&key) {
+            Some(first) => {
+                if first != file {
+                    let warning = format!(
+                        "{} is the same file as {}, building it once",
+                        file.display(),
+                        first.display()
+                    );
+                    eprintln!("Warning: {}", warning);
+                    warnings.push(warning);
+                }
+                false
+            }
+            None => {
+                seen.insert(key, file.clone());
+                true
+            }
+        }
+    });
+
+    // Drop files whose `-- @build:exclude-from(...)` pragma names the target platform
+    sql_files.retain(|file| !is_excluded_for_platform(file, target_platform));
+
     Ok(sql_files)
 }
 
+/// Record a file left out of the build, once
+fn skip_file(skipped_files: &mut Vec<SkippedFile>, file: &Path, item: &'static str) {
+    if !skipped_files.iter().any(|skipped| skipped.path == file) {
+        skipped_files.push(SkippedFile {
+            path: file.to_path_buf(),
+            item,
+        });
+    }
+}
+
+/// The file an item's `Include` names, relative to the project directory
+///
+/// Paths that differ from the file on disk only by case work on Windows, so when the
+/// exact path does not exist, a file matching it case-insensitively is used with a
+/// warning (e.g. on a Linux build agent).
+pub(super) fn find_item_file(
+    project_dir: &Path,
+    include: &str,
+    item: &str,
+    warnings: &mut Vec<String>,
+) -> Option<PathBuf> {
+    let path = project_dir.join(include);
+    if long_path(&path).exists() {
+        return Some(path);
+    }
+    let actual = resolve_ignoring_case(project_dir, include)?;
+    let warning = format!(
+        "{} item '{}' matches {} only when ignoring case",
+        item,
+        include,
+        actual.display()
+    );
+    eprintln!("Warning: {}", warning);
+    warnings.push(warning);
+    Some(actual)
+}
+
+/// `dir` joined with the `/`-separated `relative`, each component matched
+/// case-insensitively against the directory entries; `None` when nothing matches
+pub(super) fn resolve_ignoring_case(dir: &Path, relative: &str) -> Option<PathBuf> {
+    let mut resolved = dir.to_path_buf();
+    for component in relative.split('/').filter(|c| !c.is_empty() && *c != ".") {
+        if component == ".." || long_path(&resolved.join(component)).exists() {
+            resolved.push(component);
+            continue;
+        }
+        let wanted = component.to_lowercase();
+        let mut matches: Vec<PathBuf> = std::fs::read_dir(long_path(&resolved))
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().to_lowercase() == wanted)
+            .map(|entry| resolved.join(entry.file_name()))
+            .collect();
+        matches.sort();
+        resolved = matches.into_iter().next()?;
+    }
+    long_path(&resolved).exists().then_some(resolved)
+}
+
+/// All .sql files under `dir`, skipping the bin and obj output directories
+fn sql_files_in_dir(dir: &Path) -> Vec<PathBuf> {
+    let mut sql_files = Vec::new();
+    for entry in walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+    {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "sql") {
+            // Skip bin and obj directories
+            let path_str = path.to_string_lossy();
+            if !path_str.contains("/bin/")
+                && !path_str.contains("/obj/")
+                && !path_str.contains("\\bin\\")
+                && !path_str.contains("\\obj\\")
+            {
+                sql_files.push(path.to_path_buf());
+            }
+        }
+    }
+    sql_files
+}
+
+/// Evaluate the MSBuild `Condition` on an item and its ancestors (e.g. the `ItemGroup`).
+/// `$(TargetPlatform)` expands to the project's platform (e.g. `Sql160`); other properties
+/// come from `properties` (given on the command line, matched case-insensitively as in
+/// MSBuild), then the project file, then the environment. Conditions outside the
+/// supported subset are treated as true with a warning.
+fn is_condition_met(
+    root: &roxmltree::Node,
+    node: &roxmltree::Node,
+    target_platform: SqlServerVersion,
+    properties: &[(String, String)],
+    warnings: &mut Vec<String>,
+) -> bool {
+    let lookup = |name: &str| -> Option<String> {
+        if name.eq_ignore_ascii_case("TargetPlatform") {
+            return Some(format!("Sql{}", target_platform.compatibility_mode()));
+        }
+        properties
+            .iter()
+            .rev()
+            .find(|(property, _)| property.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.clone())
+            .or_else(|| find_property_value(root, name))
+            .or_else(|| std::env::var(name).ok())
+    };
+
+    for ancestor in node.ancestors() {
+        let Some(condition) = ancestor.attribute("Condition") else {
+            continue;
+        };
+        match evaluate_condition(condition, &lookup) {
+            Some(true) => {}
+            Some(false) => return false,
+            None => {
+                let warning = format!(
+                    "Unsupported Condition \"{}\" on <{}>, treating it as true",
+                    condition,
+                    ancestor.tag_name().name()
+                );
+                eprintln!("Warning: {}", warning);
+                warnings.push(warning);
+            }
+        }
+    }
+    true
+}
+
 fn find_dacpac_references(root: &roxmltree::Node, project_dir: &Path) -> Vec<DacpacReference> {
     let mut references = Vec::new();
 
@@ -505,6 +1025,7 @@ fn find_sqlcmd_variables(root: &roxmltree::Node) -> Vec<SqlCmdVariable> {
 fn find_deployment_scripts(
     root: &roxmltree::Node,
     project_dir: &Path,
+    warnings: &mut Vec<String>,
 ) -> (Option<PathBuf>, Option<PathBuf>) {
     let mut pre_deploy: Option<PathBuf> = None;
     let mut post_deploy: Option<PathBuf> = None;
@@ -513,12 +1034,15 @@ fn find_deployment_scripts(
         match node.tag_name().name() {
             "PreDeploy" => {
                 if let Some(include) = node.attribute("Include") {
-                    let script_path = project_dir.join(include.replace('\\', "/"));
-                    if script_path.exists() {
+                    let include = include.replace('\\', "/");
+                    if let Some(script_path) =
+                        find_item_file(project_dir, &include, "PreDeploy", warnings)
+                    {
                         if pre_deploy.is_some() {
-                            eprintln!(
-                                "Warning: Multiple PreDeploy scripts specified, using first one"
-                            );
+                            let warning =
+                                "Multiple PreDeploy scripts specified, using first one".to_string();
+                            eprintln!("Warning: {}", warning);
+                            warnings.push(warning);
                         } else {
                             pre_deploy = Some(script_path);
                         }
@@ -527,12 +1051,15 @@ fn find_deployment_scripts(
             }
             "PostDeploy" => {
                 if let Some(include) = node.attribute("Include") {
-                    let script_path = project_dir.join(include.replace('\\', "/"));
-                    if script_path.exists() {
+                    let include = include.replace('\\', "/");
+                    if let Some(script_path) =
+                        find_item_file(project_dir, &include, "PostDeploy", warnings)
+                    {
                         if post_deploy.is_some() {
-                            eprintln!(
-                                "Warning: Multiple PostDeploy scripts specified, using first one"
-                            );
+                            let warning = "Multiple PostDeploy scripts specified, using first one"
+                                .to_string();
+                            eprintln!("Warning: {}", warning);
+                            warnings.push(warning);
                         } else {
                             post_deploy = Some(script_path);
                         }
@@ -566,4 +1093,30 @@ mod tests {
     fn test_dsp_name() {
         assert!(SqlServerVersion::Sql160.dsp_name().contains("Sql160"));
     }
+
+    #[test]
+    fn test_parse_deployment_contributors() {
+        assert_eq!(
+            parse_deployment_contributors("Contrib.One; Contrib.Two;;"),
+            vec!["Contrib.One".to_string(), "Contrib.Two".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_parse_contributor_arguments() {
+        let args = parse_contributor_arguments("Filter=IgnoreSchema(dev);Verbose");
+        assert_eq!(
+            args,
+            vec![
+                ContributorArgument {
+                    name: "Filter".to_string(),
+                    value: "IgnoreSchema(dev)".to_string(),
+                },
+                ContributorArgument {
+                    name: "Verbose".to_string(),
+                    value: String::new(),
+                },
+            ]
+        );
+    }
 }
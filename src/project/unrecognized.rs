@@ -0,0 +1,178 @@
+//! Project file elements a build does not apply
+//!
+//! A .sqlproj migrated from SSDT may carry items and targets that DacFx acts on but
+//! rust-sqlpackage does not, such as `BuildExtensionConfiguration` items for build
+//! contributors, project references or custom targets. They are collected while parsing
+//! the project; a build warns about them, ignores them or fails on them depending on its
+//! [`UnknownElementPolicy`], and `lint` reports them.
+
+use std::fmt;
+
+use roxmltree::Node;
+
+/// What a build does with project elements it does not apply
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnknownElementPolicy {
+    /// Print a warning for each element and build (default)
+    #[default]
+    Warn,
+    /// Build without mentioning them
+    Ignore,
+    /// Fail the build, listing them
+    Error,
+}
+
+impl std::str::FromStr for UnknownElementPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "warn" => Ok(UnknownElementPolicy::Warn),
+            "ignore" => Ok(UnknownElementPolicy::Ignore),
+            "error" => Ok(UnknownElementPolicy::Error),
+            _ => Err(format!("Unknown element policy: {}", s)),
+        }
+    }
+}
+
+/// An element of the project file that the build does not apply
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnrecognizedElement {
+    /// Tag name, e.g. `BuildExtensionConfiguration`
+    pub element: String,
+    /// The item's `Include`, or the target's `Name`
+    pub include: Option<String>,
+    /// Line in the project file (1-based)
+    pub line: usize,
+    /// Why the element has no effect
+    pub reason: &'static str,
+}
+
+impl fmt::Display for UnrecognizedElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<{}", self.element)?;
+        if let Some(include) = &self.include {
+            write!(f, " {}", include)?;
+        }
+        write!(f, "> at line {}: {}", self.line, self.reason)
+    }
+}
+
+/// Items the build reads, or that have no effect on a dacpac
+const KNOWN_ITEMS: &[&str] = &[
+    "Build",
+    "None",
+    "NotInBuild",
+    "PreDeploy",
+    "PostDeploy",
+    "ArtifactReference",
+    "PackageReference",
+    "SqlCmdVariable",
+    "PropertiesFile",
+    "SqlCommandVariablesFile",
+    "DacpacProfile",
+    "Folder",
+    "Content",
+];
+
+/// Items DacFx acts on that the build does not apply, and why
+const UNSUPPORTED_ITEMS: &[(&str, &str)] = &[
+    (
+        "BuildExtensionConfiguration",
+        "build contributor configuration is not applied",
+    ),
+    (
+        "ProjectReference",
+        "project references are not built; reference the project's dacpac with an ArtifactReference",
+    ),
+    ("Reference", "assembly references are not built"),
+    ("RefactorLog", "refactor logs are not packaged"),
+];
+
+/// Elements of `<Project>` the build reads or that do not affect it
+const KNOWN_PROJECT_CHILDREN: &[&str] = &[
+    "PropertyGroup",
+    "ItemGroup",
+    "Import",
+    "Sdk",
+    "ProjectExtensions",
+];
+
+/// Elements of `<Project>` MSBuild evaluates that the build does not, and why
+const UNSUPPORTED_PROJECT_CHILDREN: &[(&str, &str)] = &[
+    ("Target", "custom targets are not run"),
+    ("UsingTask", "custom tasks are not run"),
+    ("Choose", "Choose blocks are not evaluated"),
+    ("ItemDefinitionGroup", "item definitions are not applied"),
+];
+
+/// The elements under `root` (the `<Project>` element) that the build does not apply,
+/// in document order
+pub(crate) fn find_unrecognized_elements(root: &Node) -> Vec<UnrecognizedElement> {
+    let doc = root.document();
+    let mut found = Vec::new();
+    let mut add = |node: Node, include: Option<&str>, reason: &'static str| {
+        found.push(UnrecognizedElement {
+            element: node.tag_name().name().to_string(),
+            include: include.map(str::to_string),
+            line: doc.text_pos_at(node.range().start).row as usize,
+            reason,
+        });
+    };
+
+    for child in root.children().filter(Node::is_element) {
+        let name = child.tag_name().name();
+        if name == "ItemGroup" {
+            for item in child.children().filter(Node::is_element) {
+                let item_name = item.tag_name().name();
+                if KNOWN_ITEMS.contains(&item_name) {
+                    continue;
+                }
+                let reason = UNSUPPORTED_ITEMS
+                    .iter()
+                    .find(|(name, _)| *name == item_name)
+                    .map_or("unknown item type", |(_, reason)| reason);
+                add(item, item.attribute("Include"), reason);
+            }
+        } else if !KNOWN_PROJECT_CHILDREN.contains(&name) {
+            let reason = UNSUPPORTED_PROJECT_CHILDREN
+                .iter()
+                .find(|(known, _)| *known == name)
+                .map_or("unknown element", |(_, reason)| reason);
+            add(child, child.attribute("Name"), reason);
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_unrecognized_elements() {
+        let xml = r#"<Project Sdk="Microsoft.Build.Sql/0.2.0">
+  <PropertyGroup><Name>Db</Name></PropertyGroup>
+  <ItemGroup>
+    <Build Include="Tables\*.sql" />
+    <Folder Include="Tables" />
+    <BuildExtensionConfiguration Include="Contributor.xml" />
+    <Widget Include="x" />
+  </ItemGroup>
+  <Target Name="AfterBuild" />
+</Project>"#;
+        let doc = roxmltree::Document::parse(xml).unwrap();
+        let found = find_unrecognized_elements(&doc.root_element());
+        let lines: Vec<String> = found.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            lines,
+            [
+                "<BuildExtensionConfiguration Contributor.xml> at line 6: build contributor configuration is not applied",
+                "<Widget x> at line 7: unknown item type",
+                "<Target AfterBuild> at line 9: custom targets are not run",
+            ]
+        );
+        assert_eq!("ERROR".parse(), Ok(UnknownElementPolicy::Error));
+        assert!("fail".parse::<UnknownElementPolicy>().is_err());
+    }
+}
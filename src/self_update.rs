@@ -0,0 +1,267 @@
+//! Version information and updates of the binary from a release mirror
+//!
+//! Build agents without internet access update their pinned binary from a mirror: a
+//! folder (local, mounted or a UNC share, or a `file://` URL) laid out as `just dist`
+//! writes it:
+//!
+//! ```text
+//! <mirror>/latest                                     version installed by default
+//! <mirror>/<version>/rust-sqlpackage-<platform>[.exe]
+//! <mirror>/<version>/rust-sqlpackage-<platform>[.exe].sha256
+//! ```
+//!
+//! `<platform>` is the Rust target the binary was built for, e.g.
+//! `x86_64-unknown-linux-gnu` (see [`platform`]). A binary is only installed when its
+//! SHA256 matches the `.sha256` file next to it.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::error::SqlPackageError;
+
+/// Version of this build
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Rust target of the running binary, as release binaries are named
+///
+/// `None` on platforms no release is built for.
+pub fn platform() -> Option<&'static str> {
+    let musl = cfg!(target_env = "musl");
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("linux", "x86_64") if musl => Some("x86_64-unknown-linux-musl"),
+        ("linux", "x86_64") => Some("x86_64-unknown-linux-gnu"),
+        ("linux", "aarch64") if musl => Some("aarch64-unknown-linux-musl"),
+        ("linux", "aarch64") => Some("aarch64-unknown-linux-gnu"),
+        ("macos", "x86_64") => Some("x86_64-apple-darwin"),
+        ("macos", "aarch64") => Some("aarch64-apple-darwin"),
+        ("windows", "x86_64") => Some("x86_64-pc-windows-msvc"),
+        ("windows", "aarch64") => Some("aarch64-pc-windows-msvc"),
+        _ => None,
+    }
+}
+
+/// What `--version --json` prints, for toolchain inventories
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionInfo {
+    pub name: &'static str,
+    pub version: &'static str,
+    /// Release platform, `null` on platforms without releases
+    pub platform: Option<&'static str>,
+    pub os: &'static str,
+    pub arch: &'static str,
+    /// Optional features compiled in
+    pub features: Vec<&'static str>,
+}
+
+impl VersionInfo {
+    /// Version information of the running binary
+    pub fn current() -> Self {
+        let features = [
+            ("async", cfg!(feature = "async")),
+            ("client", cfg!(feature = "client")),
+            ("testing", cfg!(feature = "testing")),
+            ("tui", cfg!(feature = "tui")),
+            ("xsd-validation", cfg!(feature = "xsd-validation")),
+        ];
+        Self {
+            name: env!("CARGO_PKG_NAME"),
+            version: VERSION,
+            platform: platform(),
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            features: features
+                .into_iter()
+                .filter(|(_, enabled)| *enabled)
+                .map(|(name, _)| name)
+                .collect(),
+        }
+    }
+}
+
+/// A release binary found on a mirror
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Release {
+    pub version: String,
+    pub platform: &'static str,
+    pub binary: PathBuf,
+    /// Expected SHA256 of the binary (uppercase hex)
+    pub sha256: String,
+}
+
+/// Folder of a mirror given as a path or a `file://` URL
+pub fn mirror_dir(mirror: &str) -> Result<PathBuf> {
+    if let Some(path) = mirror.strip_prefix("file://") {
+        // file:///opt/mirror and file://server/share
+        return Ok(match path.strip_prefix('/') {
+            Some(local) if !cfg!(windows) => PathBuf::from(format!("/{}", local)),
+            Some(local) => PathBuf::from(local),
+            None => PathBuf::from(format!("//{}", path)),
+        });
+    }
+    if mirror.contains("://") {
+        return Err(update_error(format!(
+            "mirror {} is not a folder; mount or sync it and pass the path",
+            mirror
+        )));
+    }
+    Ok(PathBuf::from(mirror))
+}
+
+/// The release of `version` (or the mirror's `latest`) for this platform
+pub fn find_release(mirror: &Path, version: Option<&str>) -> Result<Release> {
+    let platform = platform().ok_or_else(|| {
+        update_error(format!(
+            "no releases are built for {}-{}",
+            std::env::consts::ARCH,
+            std::env::consts::OS
+        ))
+    })?;
+    let version = match version {
+        Some(version) => version.to_string(),
+        None => read_text(&mirror.join("latest"))?,
+    };
+    let binary = mirror.join(&version).join(format!(
+        "rust-sqlpackage-{}{}",
+        platform,
+        std::env::consts::EXE_SUFFIX
+    ));
+    if !binary.is_file() {
+        return Err(update_error(format!(
+            "{} has no {} build of {}",
+            mirror.display(),
+            platform,
+            version
+        )));
+    }
+    let mut checksum = binary.clone().into_os_string();
+    checksum.push(".sha256");
+    // `sha256sum` format: the hash, then optionally the file name
+    let sha256 = read_text(Path::new(&checksum))?
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_uppercase();
+    Ok(Release {
+        version,
+        platform,
+        binary,
+        sha256,
+    })
+}
+
+/// Replace the binary at `target` with `release`, after checking its SHA256
+///
+/// The new binary is copied next to `target` and renamed over it; the old one is moved
+/// aside to `<target>.old` first, as Windows cannot overwrite a running executable, and
+/// removed where the platform allows.
+pub fn install(release: &Release, target: &Path) -> Result<()> {
+    let bytes = std::fs::read(&release.binary)
+        .map_err(|e| update_error(format!("cannot read {}: {}", release.binary.display(), e)))?;
+    let actual = format!("{:X}", Sha256::digest(&bytes));
+    if actual != release.sha256 {
+        return Err(update_error(format!(
+            "{} has SHA256 {}, but the mirror lists {}",
+            release.binary.display(),
+            actual,
+            release.sha256
+        )));
+    }
+
+    let write_error = |e| SqlPackageError::OutputWriteError {
+        path: target.to_path_buf(),
+        source: e,
+    };
+    let staged = with_suffix(target, ".new");
+    let old = with_suffix(target, ".old");
+    std::fs::write(&staged, &bytes).map_err(write_error)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755))
+            .map_err(write_error)?;
+    }
+    let _ = std::fs::remove_file(&old);
+    std::fs::rename(target, &old).map_err(write_error)?;
+    if let Err(e) = std::fs::rename(&staged, target) {
+        let _ = std::fs::rename(&old, target);
+        return Err(write_error(e).into());
+    }
+    let _ = std::fs::remove_file(&old);
+    Ok(())
+}
+
+fn read_text(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path)
+        .map(|text| text.trim().to_string())
+        .map_err(|e| update_error(format!("cannot read {}: {}", path.display(), e)))
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn update_error(message: String) -> anyhow::Error {
+    SqlPackageError::SelfUpdateError { message }.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_install_from_mirror() {
+        let Some(platform) = platform() else {
+            return;
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let mirror = dir.path().join("mirror");
+        let release_dir = mirror.join("1.2.0");
+        std::fs::create_dir_all(&release_dir).unwrap();
+        std::fs::write(mirror.join("latest"), "1.2.0\n").unwrap();
+        let binary = release_dir.join(format!(
+            "rust-sqlpackage-{}{}",
+            platform,
+            std::env::consts::EXE_SUFFIX
+        ));
+        std::fs::write(&binary, b"new binary").unwrap();
+        let checksum = format!("{:x}", Sha256::digest(b"new binary"));
+        let mut checksum_path = binary.clone().into_os_string();
+        checksum_path.push(".sha256");
+        std::fs::write(&checksum_path, format!("{}  rust-sqlpackage\n", checksum)).unwrap();
+
+        let release = find_release(&mirror, None).unwrap();
+        assert_eq!(release.version, "1.2.0");
+        assert_eq!(release.sha256, checksum.to_uppercase());
+
+        let target = dir.path().join("rust-sqlpackage");
+        std::fs::write(&target, b"old binary").unwrap();
+        install(&release, &target).unwrap();
+        assert_eq!(std::fs::read(&target).unwrap(), b"new binary");
+        assert!(!with_suffix(&target, ".old").exists());
+
+        std::fs::write(&checksum_path, "0000").unwrap();
+        let tampered = find_release(&mirror, Some("1.2.0")).unwrap();
+        assert!(install(&tampered, &target).is_err());
+        assert!(find_release(&mirror, Some("9.9.9")).is_err());
+    }
+
+    #[test]
+    fn test_mirror_dir() {
+        assert_eq!(
+            mirror_dir("/opt/mirror").unwrap(),
+            PathBuf::from("/opt/mirror")
+        );
+        assert!(mirror_dir("https://releases.example.com").is_err());
+        if !cfg!(windows) {
+            assert_eq!(
+                mirror_dir("file:///opt/mirror").unwrap(),
+                PathBuf::from("/opt/mirror")
+            );
+        }
+    }
+}
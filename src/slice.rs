@@ -0,0 +1,257 @@
+//! Slice a dacpac: extract some objects, and everything they need, into a new dacpac
+//!
+//! Objects are selected with the same include/exclude filters as a filtered build
+//! (`include:schema=Sales`, `exclude:name=Sales.tmp_*`, ...). Their constraints,
+//! indexes, triggers, extended properties and permissions come with them, and so does
+//! every object they reference, transitively: the tables a view reads, the table a
+//! foreign key points at, the role a permission is granted to. The result deploys on
+//! its own, e.g. one domain's schema to a database it shares with other applications.
+//!
+//! Pre- and post-deploy scripts are left out, as they usually touch objects outside the
+//! slice.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use roxmltree::{Document, Node};
+
+use crate::compare::reader::DacpacContents;
+use crate::dacpac::{create_dacpac_from_parts, PackageParts};
+use crate::error::SqlPackageError;
+use crate::model::filter::{keeps_parts, FilterAction};
+use crate::model::ObjectFilter;
+use crate::model_dump::name_parts;
+
+/// Relationships naming the object an element belongs to
+const OWNER_RELATIONSHIPS: &[&str] = &[
+    "DefiningTable",
+    "IndexedObject",
+    "Parent",
+    "Host",
+    "SecuredObject",
+];
+
+/// Element types that are not schema-scoped, whatever their names look like
+const DATABASE_SCOPED_TYPES: &[&str] = &[
+    "SqlUser",
+    "SqlRole",
+    "SqlRoleMembership",
+    "SqlPermissionStatement",
+    "SqlFilegroup",
+    "SqlFullTextCatalog",
+    "SqlPartitionFunction",
+    "SqlPartitionScheme",
+];
+
+/// Options for slicing a dacpac
+#[derive(Debug, Clone, Default)]
+pub struct SliceOptions {
+    /// Dacpac to slice
+    pub input: PathBuf,
+    /// Path of the sliced dacpac
+    pub output_path: PathBuf,
+    /// Filters selecting the objects to keep
+    pub filters: Vec<ObjectFilter>,
+    /// Select and resolve the objects but write nothing
+    pub dry_run: bool,
+}
+
+/// What a slice kept
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SliceSummary {
+    /// Elements in the sliced model
+    pub elements: usize,
+    /// Elements in the input model
+    pub total: usize,
+    /// Objects the filters left out but the selection depends on, as `Type Name`
+    pub dependencies: Vec<String>,
+}
+
+/// An element of the input model
+struct Element<'a, 'input> {
+    node: Node<'a, 'input>,
+    type_name: &'a str,
+    name: &'a str,
+    /// Index of the element this one belongs to, e.g. a constraint's table
+    owner: Option<usize>,
+}
+
+/// Write the objects `filters` select, with their children and dependencies, to a new
+/// dacpac
+pub fn slice_dacpac(options: &SliceOptions) -> Result<SliceSummary> {
+    let contents = DacpacContents::from_path(&options.input)?;
+    let invalid = |message: String| SqlPackageError::InvalidDacpac {
+        path: options.input.clone(),
+        message,
+    };
+    let model_xml = contents
+        .get_string("model.xml")
+        .ok_or_else(|| invalid("no model.xml".to_string()))?;
+    let document = Document::parse(&model_xml)
+        .map_err(|e| invalid(format!("model.xml is not valid XML: {}", e)))?;
+    let model = document
+        .root_element()
+        .children()
+        .find(|n| n.has_tag_name("Model"))
+        .ok_or_else(|| invalid("model.xml has no Model element".to_string()))?;
+
+    let mut elements: Vec<Element> = model
+        .children()
+        .filter(|n| n.has_tag_name("Element"))
+        .map(|node| Element {
+            node,
+            type_name: node.attribute("Type").unwrap_or_default(),
+            name: node.attribute("Name").unwrap_or_default(),
+            owner: None,
+        })
+        .collect();
+
+    // Objects by name; an element whose owner is not in the model stands on its own
+    let owner_names: Vec<Option<&str>> = elements.iter().map(|e| owner_name(e.node)).collect();
+    let mut objects: HashMap<String, Vec<usize>> = HashMap::new();
+    for (index, element) in elements.iter().enumerate() {
+        if owner_names[index].is_none() && !element.name.is_empty() {
+            objects
+                .entry(element.name.to_lowercase())
+                .or_default()
+                .push(index);
+        }
+    }
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); elements.len()];
+    for (index, owner) in owner_names.iter().enumerate() {
+        let owner = owner
+            .and_then(|name| objects.get(&name.to_lowercase()))
+            .and_then(|candidates| candidates.first().copied());
+        if let Some(owner) = owner {
+            elements[index].owner = Some(owner);
+            children[owner].push(index);
+        }
+    }
+
+    let mut selected = vec![false; elements.len()];
+    let mut queue: Vec<usize> = Vec::new();
+    for (index, element) in elements.iter().enumerate() {
+        if element.owner.is_none()
+            && (element.type_name == "SqlDatabaseOptions" || is_kept(element, &options.filters))
+        {
+            queue.push(index);
+        }
+    }
+    let mut seeded = vec![false; elements.len()];
+    for &index in &queue {
+        seeded[index] = true;
+    }
+    let excludes: Vec<ObjectFilter> = options
+        .filters
+        .iter()
+        .filter(|f| f.action == FilterAction::Exclude)
+        .cloned()
+        .collect();
+
+    while let Some(index) = queue.pop() {
+        if selected[index] {
+            continue;
+        }
+        selected[index] = true;
+        let element = &elements[index];
+        // Children follow their object, unless excluded on their own (constraints share
+        // inline annotations with their table, so they always follow)
+        queue.extend(children[index].iter().copied().filter(|&child| {
+            let child = &elements[child];
+            child.type_name.ends_with("Constraint") || is_kept(child, &excludes)
+        }));
+        // Referenced objects, resolving columns and parameters to the object they belong to
+        for reference in element
+            .node
+            .descendants()
+            .filter(|n| n.has_tag_name("References") && n.attribute("ExternalSource").is_none())
+        {
+            let Some(name) = reference.attribute("Name") else {
+                continue;
+            };
+            let parts = name_parts(name);
+            let object = (1..=parts.len()).rev().find_map(|len| {
+                let name = parts[..len]
+                    .iter()
+                    .map(|part| format!("[{}]", part.replace(']', "]]")))
+                    .collect::<Vec<_>>()
+                    .join(".");
+                objects.get(&name.to_lowercase())
+            });
+            queue.extend(object.into_iter().flatten().copied());
+        }
+    }
+
+    let dependencies = (0..elements.len())
+        .filter(|&i| selected[i] && elements[i].owner.is_none() && !seeded[i])
+        .map(|i| format!("{} {}", elements[i].type_name, elements[i].name))
+        .collect();
+
+    // The input's header, and the selected elements in their original order
+    let start = model.range().start;
+    let mut xml = model_xml[..start].to_string();
+    xml.push_str("<Model>");
+    let mut count = 0;
+    for (element, _) in elements.iter().zip(&selected).filter(|(_, s)| **s) {
+        xml.push_str("\n    ");
+        xml.push_str(&model_xml[element.node.range()]);
+        count += 1;
+    }
+    xml.push_str("\n  </Model>\n</DataSchemaModel>");
+
+    if !options.dry_run {
+        let metadata_xml = contents.get_bytes("DacMetadata.xml").unwrap_or_default();
+        let parts = PackageParts {
+            model_xml: xml.as_bytes(),
+            metadata_xml,
+            schema_version: document
+                .root_element()
+                .attribute("SchemaVersion")
+                .unwrap_or("2.9"),
+            predeploy: None,
+            postdeploy: None,
+            source_manifest: None,
+        };
+        create_dacpac_from_parts(&parts, &options.output_path)?;
+    }
+
+    Ok(SliceSummary {
+        elements: count,
+        total: elements.len(),
+        dependencies,
+    })
+}
+
+/// Whether `filters` keep a model.xml element
+fn is_kept(element: &Element, filters: &[ObjectFilter]) -> bool {
+    let parts = name_parts(element.name);
+    let (schema, name) = if element.type_name == "SqlSchema" {
+        (parts.first(), parts.first())
+    } else if DATABASE_SCOPED_TYPES.contains(&element.type_name) || parts.len() < 2 {
+        (None, parts.last())
+    } else {
+        (parts.first(), parts.last())
+    };
+    keeps_parts(
+        filters,
+        element.type_name,
+        schema.map(String::as_str),
+        name.map_or("", String::as_str),
+    )
+}
+
+/// Name of the object an element belongs to, if it is a constraint, index, trigger,
+/// extended property or permission
+fn owner_name<'a>(element: Node<'a, '_>) -> Option<&'a str> {
+    element
+        .children()
+        .find(|n| {
+            n.has_tag_name("Relationship")
+                && n.attribute("Name")
+                    .is_some_and(|name| OWNER_RELATIONSHIPS.contains(&name))
+        })?
+        .descendants()
+        .find(|n| n.has_tag_name("References") && n.attribute("ExternalSource").is_none())?
+        .attribute("Name")
+}
@@ -0,0 +1,339 @@
+//! Machine-readable build summary
+//!
+//! Writes a summary of a build (objects by type, warnings, phase durations and the
+//! output dacpac hash) so CI pipelines can build dashboards without parsing logs, and
+//! the object hashes sidecar: a content hash per model element, to tell which objects
+//! changed between two builds without diffing the dacpacs.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use anyhow::Result;
+use regex::Regex;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::compare::model_xml::element_key;
+use crate::error::SqlPackageError;
+use crate::model::{DatabaseModel, ModelElement};
+
+/// Output format for the build summary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SummaryFormat {
+    #[default]
+    Json,
+}
+
+impl std::str::FromStr for SummaryFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(SummaryFormat::Json),
+            _ => Err(format!("Unknown summary format: {}", s)),
+        }
+    }
+}
+
+impl SummaryFormat {
+    /// File extension used for the default summary path
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SummaryFormat::Json => "json",
+        }
+    }
+}
+
+/// Wall-clock duration of each build phase, in milliseconds
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PhaseDurations {
+    pub parse_project_ms: f64,
+    pub parse_sql_ms: f64,
+    pub build_model_ms: f64,
+    pub write_dacpac_ms: f64,
+    pub total_ms: f64,
+}
+
+impl PhaseDurations {
+    /// Convert a `Duration` to fractional milliseconds
+    pub fn millis(duration: Duration) -> f64 {
+        duration.as_secs_f64() * 1000.0
+    }
+}
+
+/// Summary of a single build
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildSummary {
+    /// Project name
+    pub project: String,
+    /// Path to the generated dacpac
+    pub output_path: PathBuf,
+    /// SHA256 of the generated dacpac file (uppercase hex)
+    pub output_sha256: String,
+    /// Number of SQL files compiled
+    pub source_files: usize,
+    /// Number of parsed SQL statements
+    pub statements: usize,
+    /// Total number of model elements
+    pub total_elements: usize,
+    /// Model element counts keyed by element type (e.g., "SqlTable")
+    pub elements_by_type: BTreeMap<String, usize>,
+    /// Warnings reported during the build
+    pub warnings: Vec<String>,
+    /// Phase durations
+    pub durations: PhaseDurations,
+}
+
+impl BuildSummary {
+    /// Count model elements by their XML type name
+    pub fn count_elements_by_type(model: &DatabaseModel) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for element in &model.elements {
+            *counts.entry(element.type_name().to_string()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Render the summary in the requested format
+    pub fn render(&self, format: SummaryFormat) -> Result<String> {
+        match format {
+            SummaryFormat::Json => Ok(serde_json::to_string_pretty(self)?),
+        }
+    }
+
+    /// Write the summary to `path` in the requested format
+    pub fn write(&self, path: &Path, format: SummaryFormat) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| SqlPackageError::SummaryWriteError {
+                path: path.to_path_buf(),
+                source: e,
+            })?;
+        }
+        let content = self.render(format)?;
+        std::fs::write(path, content).map_err(|e| SqlPackageError::SummaryWriteError {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+        Ok(())
+    }
+}
+
+/// Default summary path: next to the dacpac as `<name>.summary.<ext>`
+pub fn default_summary_path(dacpac_path: &Path, format: SummaryFormat) -> PathBuf {
+    let stem = dacpac_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("output");
+    dacpac_path.with_file_name(format!("{}.summary.{}", stem, format.extension()))
+}
+
+/// Compute the SHA256 of a file as uppercase hex
+pub fn file_sha256(path: &Path) -> Result<String> {
+    let bytes = std::fs::read(path).map_err(|e| SqlPackageError::DacpacReadError {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+    let mut hasher = Sha256::new();
+    hasher.update(&bytes);
+    Ok(format!("{:X}", hasher.finalize()))
+}
+
+/// Default object hashes path: next to the dacpac as `<name>.hashes.json`
+pub fn default_object_hashes_path(dacpac_path: &Path) -> PathBuf {
+    let stem = dacpac_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("output");
+    dacpac_path.with_file_name(format!("{}.hashes.json", stem))
+}
+
+/// `Disambiguator` attributes, numbered across the whole model
+static DISAMBIGUATOR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\s+Disambiguator="[^"]*""#).unwrap());
+
+/// SHA256 (uppercase hex) of each top-level element of `model_xml`, keyed as `compare`
+/// keys elements (e.g. `SqlTable [dbo].[Orders]`)
+///
+/// The hash covers the element's XML as written, with its properties, relationships,
+/// inline children and annotations, leaving out the annotation `Disambiguator`s: they
+/// are numbered across the model, so adding one object would change the hash of others.
+/// Elements sharing a key are hashed together, in document order.
+pub fn object_hashes(model_xml: &str) -> Result<BTreeMap<String, String>> {
+    let document = roxmltree::Document::parse(model_xml)?;
+    let mut hashers: BTreeMap<String, Sha256> = BTreeMap::new();
+    let models = document
+        .root_element()
+        .children()
+        .filter(|n| n.has_tag_name("Model"));
+    for element in models.flat_map(|m| m.children().filter(|n| n.has_tag_name("Element"))) {
+        let text = DISAMBIGUATOR_RE.replace_all(&model_xml[element.range()], "");
+        hashers
+            .entry(element_key(&element).to_string())
+            .or_default()
+            .update(text.as_bytes());
+    }
+    Ok(hashers
+        .into_iter()
+        .map(|(key, hasher)| (key, format!("{:X}", hasher.finalize())))
+        .collect())
+}
+
+/// Write the hashes of the objects in the dacpac at `dacpac_path` to `path` as JSON
+pub fn write_object_hashes(dacpac_path: &Path, path: &Path) -> Result<()> {
+    let contents = crate::compare::reader::DacpacContents::from_path(dacpac_path)?;
+    let model_xml =
+        contents
+            .get_string("model.xml")
+            .ok_or_else(|| SqlPackageError::InvalidDacpac {
+                path: dacpac_path.to_path_buf(),
+                message: "no model.xml".to_string(),
+            })?;
+    let content = serde_json::to_string_pretty(&object_hashes(&model_xml)?)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| SqlPackageError::SummaryWriteError {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+    }
+    std::fs::write(path, content).map_err(|e| SqlPackageError::SummaryWriteError {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+    Ok(())
+}
+
+/// Element counts of a model by type and by schema, as `build --verbose` prints them
+///
+/// Statements kept as raw fallbacks are counted as `Raw <type>`, so a statement that is
+/// no longer classified shows up as a count moving between types.
+pub fn format_model_breakdown(model: &DatabaseModel) -> String {
+    fn label(element: &ModelElement) -> String {
+        match element {
+            ModelElement::Raw(raw) => format!("Raw {}", raw.sql_type),
+            _ => element.type_name().to_string(),
+        }
+    }
+    fn counts(counts: &BTreeMap<String, usize>) -> String {
+        let mut counts: Vec<(&String, &usize)> = counts.iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        counts
+            .iter()
+            .map(|(label, count)| format!("{} {}", label, count))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    let mut by_type = BTreeMap::new();
+    let mut by_schema: BTreeMap<&str, BTreeMap<String, usize>> = BTreeMap::new();
+    let mut raw = 0;
+    for element in &model.elements {
+        let label = label(element);
+        *by_type.entry(label.clone()).or_insert(0) += 1;
+        *by_schema
+            .entry(element.schema().unwrap_or("(database)"))
+            .or_default()
+            .entry(label)
+            .or_insert(0) += 1;
+        if matches!(element, ModelElement::Raw(_)) {
+            raw += 1;
+        }
+    }
+
+    let mut out = format!("Built model with {} elements\n", model.elements.len());
+    if model.elements.is_empty() {
+        return out;
+    }
+    out.push_str(&format!("  By type: {}\n", counts(&by_type)));
+    out.push_str("  By schema:\n");
+    for (schema, types) in &by_schema {
+        let total: usize = types.values().sum();
+        out.push_str(&format!("    {}: {} ({})\n", schema, total, counts(types)));
+    }
+    out.push_str(&format!("  Raw fallbacks: {}\n", raw));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_format_from_str() {
+        assert_eq!(
+            "json".parse::<SummaryFormat>().unwrap(),
+            SummaryFormat::Json
+        );
+        assert_eq!(
+            "JSON".parse::<SummaryFormat>().unwrap(),
+            SummaryFormat::Json
+        );
+        assert!("yaml".parse::<SummaryFormat>().is_err());
+    }
+
+    #[test]
+    fn test_format_model_breakdown() {
+        let project = crate::project::SqlProject::default();
+        let statements = crate::parser::parse_sql_content(
+            "CREATE SCHEMA [Sales];\nGO\n\
+             CREATE TABLE [Sales].[Orders] ([Id] INT NOT NULL PRIMARY KEY);\nGO\n\
+             CREATE TABLE [dbo].[Users] ([Id] INT NOT NULL);\nGO\n\
+             CREATE VIEW [dbo].[UserIds] AS SELECT [Id] FROM [dbo].[Users];",
+            Path::new("Model.sql"),
+        )
+        .unwrap();
+        let model = crate::model::build_model(&statements, &project).unwrap();
+
+        let breakdown = format_model_breakdown(&model);
+        assert!(breakdown.starts_with(&format!(
+            "Built model with {} elements\n",
+            model.elements.len()
+        )));
+        assert!(
+            breakdown.contains("    Sales: 2 (SqlPrimaryKeyConstraint 1, SqlTable 1)\n"),
+            "{}",
+            breakdown
+        );
+        assert!(
+            breakdown.contains("    dbo: 2 (SqlTable 1, SqlView 1)\n"),
+            "{}",
+            breakdown
+        );
+        assert!(breakdown.contains("  Raw fallbacks: 0\n"), "{}", breakdown);
+    }
+
+    #[test]
+    fn test_object_hashes() {
+        let model = |disambiguator: u32, column_type: &str| {
+            format!(
+                r#"<DataSchemaModel><Model>
+  <Element Type="SqlTable" Name="[dbo].[A]">
+    <Annotation Type="SqlInlineConstraintAnnotation" Disambiguator="{}" />
+  </Element>
+  <Element Type="SqlTable" Name="[dbo].[B]"><Property Name="Type" Value="{}" /></Element>
+  <Element Type="SqlDatabaseOptions" />
+</Model></DataSchemaModel>"#,
+                disambiguator, column_type
+            )
+        };
+        let before = object_hashes(&model(3, "int")).unwrap();
+        assert_eq!(
+            before.keys().collect::<Vec<_>>(),
+            [
+                "SqlDatabaseOptions",
+                "SqlTable [dbo].[A]",
+                "SqlTable [dbo].[B]"
+            ]
+        );
+        let after = object_hashes(&model(4, "bigint")).unwrap();
+        assert_eq!(after["SqlTable [dbo].[A]"], before["SqlTable [dbo].[A]"]);
+        assert_ne!(after["SqlTable [dbo].[B]"], before["SqlTable [dbo].[B]"]);
+    }
+
+    #[test]
+    fn test_default_summary_path() {
+        let path = default_summary_path(Path::new("bin/Debug/Db.dacpac"), SummaryFormat::Json);
+        assert_eq!(path, PathBuf::from("bin/Debug/Db.summary.json"));
+    }
+}
@@ -0,0 +1,206 @@
+//! Test utilities for downstream projects (enabled with the `testing` feature)
+//!
+//! Lets teams write regression tests for their own schemas: build a project from an
+//! in-memory file map, produce a dacpac, and compare model.xml against a checked-in
+//! golden file.
+//!
+//! ```rust,ignore
+//! use rust_sqlpackage::testing::{assert_model_xml_matches_golden, InMemoryProject};
+//!
+//! let project = InMemoryProject::new(&[("Tables/Users.sql", "CREATE TABLE dbo.Users (Id INT);")])?;
+//! let model_xml = project.model_xml()?;
+//! assert_model_xml_matches_golden(&model_xml, "tests/golden/users.model.xml");
+//! ```
+//!
+//! Set `UPDATE_GOLDEN=1` to (re)write golden files instead of comparing.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use tempfile::TempDir;
+
+use crate::compare::reader::DacpacContents;
+use crate::error::SqlPackageError;
+use crate::{build_dacpac, BuildOptions};
+
+/// Environment variable that switches golden assertions into update mode
+pub const UPDATE_GOLDEN_ENV: &str = "UPDATE_GOLDEN";
+
+/// Default SDK-style project file used by [`InMemoryProject::new`]
+const DEFAULT_SQLPROJ: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<Project DefaultTargets="Build">
+  <Sdk Name="Microsoft.Build.Sql" Version="2.0.0" />
+  <PropertyGroup>
+    <Name>TestProject</Name>
+    <DSP>Microsoft.Data.Tools.Schema.Sql.Sql160DatabaseSchemaProvider</DSP>
+  </PropertyGroup>
+</Project>"#;
+
+/// A SQL project materialized from an in-memory file map into a temporary directory.
+/// The directory is removed when the value is dropped.
+pub struct InMemoryProject {
+    temp_dir: TempDir,
+    project_path: PathBuf,
+}
+
+impl InMemoryProject {
+    /// Create an SDK-style project (all .sql files included by glob) from `(path, content)` pairs
+    pub fn new(files: &[(&str, &str)]) -> Result<Self> {
+        Self::with_sqlproj(DEFAULT_SQLPROJ, files)
+    }
+
+    /// Create a project with a custom .sqlproj and `(path, content)` pairs.
+    /// Paths are relative to the project directory.
+    pub fn with_sqlproj(sqlproj: &str, files: &[(&str, &str)]) -> Result<Self> {
+        let temp_dir = TempDir::new().map_err(|e| SqlPackageError::DacpacCreationError {
+            message: format!("Failed to create temp directory: {}", e),
+        })?;
+        let project_path = temp_dir.path().join("project.sqlproj");
+        write_file(&project_path, sqlproj)?;
+
+        for (relative_path, content) in files {
+            write_file(&temp_dir.path().join(relative_path), content)?;
+        }
+
+        Ok(Self {
+            temp_dir,
+            project_path,
+        })
+    }
+
+    /// Directory containing the materialized project
+    pub fn project_dir(&self) -> &Path {
+        self.temp_dir.path()
+    }
+
+    /// Path to the .sqlproj file
+    pub fn project_path(&self) -> &Path {
+        &self.project_path
+    }
+
+    /// Build the project and return the path to the dacpac
+    pub fn build(&self) -> Result<PathBuf> {
+        build_dacpac(BuildOptions {
+            project_path: self.project_path.clone(),
+            output_path: Some(self.project_dir().join("bin").join("project.dacpac")),
+            ..Default::default()
+        })
+    }
+
+    /// Build the project and return the generated model.xml
+    pub fn model_xml(&self) -> Result<String> {
+        let dacpac_path = self.build()?;
+        read_model_xml(&dacpac_path)
+    }
+}
+
+/// Read model.xml from a dacpac
+pub fn read_model_xml(dacpac_path: &Path) -> Result<String> {
+    let contents = DacpacContents::from_path(dacpac_path)?;
+    contents.get_string("model.xml").ok_or_else(|| {
+        SqlPackageError::DacpacCreationError {
+            message: format!("{} does not contain model.xml", dacpac_path.display()),
+        }
+        .into()
+    })
+}
+
+/// Assert that `actual` model.xml matches the golden file at `golden_path`.
+///
+/// Line endings are normalized before comparing. When `UPDATE_GOLDEN` is set, the golden
+/// file is (re)written instead. Panics with the first differing line on mismatch.
+pub fn assert_model_xml_matches_golden(actual: &str, golden_path: impl AsRef<Path>) {
+    let golden_path = golden_path.as_ref();
+    let actual = normalize_line_endings(actual);
+
+    if std::env::var_os(UPDATE_GOLDEN_ENV).is_some() {
+        write_file(golden_path, &actual).expect("Failed to write golden file");
+        return;
+    }
+
+    let expected = std::fs::read_to_string(golden_path).unwrap_or_else(|e| {
+        panic!(
+            "Failed to read golden file {}: {} (set {}=1 to create it)",
+            golden_path.display(),
+            e,
+            UPDATE_GOLDEN_ENV
+        )
+    });
+    let expected = normalize_line_endings(&expected);
+
+    if let Some(message) = first_difference(&expected, &actual) {
+        panic!(
+            "model.xml does not match golden file {}\n{}\n(set {}=1 to update)",
+            golden_path.display(),
+            message,
+            UPDATE_GOLDEN_ENV
+        );
+    }
+}
+
+fn normalize_line_endings(content: &str) -> String {
+    content.replace("\r\n", "\n")
+}
+
+/// Describe the first differing line between `expected` and `actual`, if any
+fn first_difference(expected: &str, actual: &str) -> Option<String> {
+    let expected_lines: Vec<&str> = expected.lines().collect();
+    let actual_lines: Vec<&str> = actual.lines().collect();
+    let max_len = expected_lines.len().max(actual_lines.len());
+
+    (0..max_len).find_map(|i| {
+        let e = expected_lines.get(i).copied();
+        let a = actual_lines.get(i).copied();
+        (e != a).then(|| {
+            format!(
+                "line {}:\n  expected: {}\n  actual:   {}",
+                i + 1,
+                e.unwrap_or("<end of file>"),
+                a.unwrap_or("<end of file>")
+            )
+        })
+    })
+}
+
+fn write_file(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| SqlPackageError::OutputWriteError {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+    }
+    std::fs::write(path, content).map_err(|e| SqlPackageError::OutputWriteError {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_memory_project_builds_model_xml() {
+        let project =
+            InMemoryProject::new(&[("Tables/T.sql", "CREATE TABLE [dbo].[T] ([Id] INT);")])
+                .unwrap();
+        let model_xml = project.model_xml().unwrap();
+        assert!(model_xml.contains(r#"Type="SqlTable" Name="[dbo].[T]""#));
+    }
+
+    #[test]
+    fn test_golden_match_ignores_line_endings() {
+        let dir = TempDir::new().unwrap();
+        let golden = dir.path().join("model.xml");
+        std::fs::write(&golden, "<a>\r\n  <b />\r\n</a>\r\n").unwrap();
+        assert_model_xml_matches_golden("<a>\n  <b />\n</a>\n", &golden);
+    }
+
+    #[test]
+    fn test_first_difference_reports_line() {
+        let message = first_difference("a\nb\nc", "a\nx\nc").unwrap();
+        assert!(message.starts_with("line 2:"), "Got: {}", message);
+        assert!(first_difference("a\nb", "a\nb").is_none());
+    }
+}
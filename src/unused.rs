@@ -0,0 +1,227 @@
+//! Report of views, procedures and functions nothing references
+//!
+//! Large legacy projects collect routines no one calls any more. An object is reported
+//! when no other view, procedure, function, trigger, synonym, table expression (computed
+//! column, default, check constraint or index filter) or pre/post-deployment script
+//! mentions it, unless it matches an entry-point pattern. Objects only called from
+//! application code should be listed as entry points:
+//!
+//! ```text
+//! Procedure [dbo].[usp_LegacyExport]  -- Procedures/usp_LegacyExport.sql:1-24
+//! ```
+//!
+//! References are taken as direct: an unused procedure that is the only caller of another
+//! keeps that one off the report until it is removed. Dynamic SQL is not followed.
+
+use std::collections::HashSet;
+
+use anyhow::{anyhow, Result};
+use glob::{MatchOptions, Pattern};
+
+use crate::dacpac::object_references;
+use crate::model::{ConstraintType, DatabaseModel, ModelElement};
+use crate::parser::identifier_utils::escape_identifier;
+use crate::project::SqlProject;
+use crate::util::long_path;
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: false,
+    require_literal_leading_dot: false,
+};
+
+/// A view, procedure or function nothing references
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnusedObject {
+    /// Full name, e.g. `[dbo].[usp_LegacyExport]`
+    pub name: String,
+    /// `View`, `Procedure` or `Function`
+    pub object_type: &'static str,
+    /// File and lines defining the object, relative to the project
+    pub defined_in: Option<String>,
+}
+
+/// Name globs of objects called from outside the database, which are never reported
+///
+/// A pattern matches the schema-qualified name (`api.*`) or the bare name (`usp_*`),
+/// ignoring case.
+#[derive(Debug, Clone, Default)]
+pub struct EntryPoints(Vec<Pattern>);
+
+impl EntryPoints {
+    /// Entry points from glob strings
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Result<Self> {
+        patterns
+            .iter()
+            .map(|p| {
+                let p = p.as_ref().trim();
+                Pattern::new(p).map_err(|e| anyhow!("Invalid entry point pattern '{}': {}", p, e))
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(Self)
+    }
+
+    fn matches(&self, schema: &str, name: &str) -> bool {
+        let qualified = format!("{}.{}", schema, name);
+        self.0.iter().any(|p| {
+            p.matches_with(&qualified, MATCH_OPTIONS) || p.matches_with(name, MATCH_OPTIONS)
+        })
+    }
+}
+
+/// Views, procedures and functions of `model` that nothing references, in model order
+pub fn find_unused_objects(
+    model: &DatabaseModel,
+    project: &SqlProject,
+    entry_points: &EntryPoints,
+) -> Vec<UnusedObject> {
+    let mut referenced: HashSet<String> = object_references(model, &project.default_schema)
+        .into_values()
+        .flatten()
+        .map(|name| name.to_lowercase())
+        .collect();
+
+    // Code the model keeps as text: any identifier in it counts as a reference
+    let mut texts: Vec<String> = Vec::new();
+    for element in &model.elements {
+        match element {
+            ModelElement::Trigger(t) => texts.push(t.definition.to_string()),
+            ModelElement::Raw(r) => texts.push(r.definition.to_string()),
+            ModelElement::Synonym(s) => {
+                let target = format!(
+                    "[{}].[{}]",
+                    escape_identifier(&s.target_schema),
+                    escape_identifier(&s.target_name)
+                );
+                referenced.insert(target.to_lowercase());
+            }
+            ModelElement::Table(t) => texts.extend(
+                t.columns
+                    .iter()
+                    .flat_map(|c| [&c.computed_expression, &c.default_value])
+                    .flatten()
+                    .cloned(),
+            ),
+            ModelElement::Constraint(c)
+                if matches!(
+                    c.constraint_type,
+                    ConstraintType::Check | ConstraintType::Default
+                ) =>
+            {
+                texts.extend(c.definition.clone())
+            }
+            ModelElement::Index(i) => texts.extend(i.filter_predicate.clone()),
+            _ => {}
+        }
+    }
+    for script in project
+        .pre_deploy_script
+        .iter()
+        .chain(&project.post_deploy_script)
+    {
+        if let Ok(text) = std::fs::read_to_string(long_path(script)) {
+            texts.push(text);
+        }
+    }
+    let mentioned: HashSet<String> = texts.iter().flat_map(|text| identifiers(text)).collect();
+
+    model
+        .elements
+        .iter()
+        .enumerate()
+        .filter_map(|(index, element)| {
+            let (object_type, schema, name) = match element {
+                ModelElement::View(v) => ("View", &v.schema, &v.name),
+                ModelElement::Procedure(p) => ("Procedure", &p.schema, &p.name),
+                ModelElement::Function(f) => ("Function", &f.schema, &f.name),
+                _ => return None,
+            };
+            let full_name = element.full_name();
+            if referenced.contains(&full_name.to_lowercase())
+                || mentioned.contains(&name.to_lowercase())
+                || entry_points.matches(schema, name)
+            {
+                return None;
+            }
+            Some(UnusedObject {
+                name: full_name,
+                object_type,
+                defined_in: model
+                    .source(index)
+                    .map(|source| source.display_relative_to(&project.project_dir)),
+            })
+        })
+        .collect()
+}
+
+/// Render the report, one object per line
+pub fn format_unused_objects(objects: &[UnusedObject]) -> String {
+    let mut out = String::new();
+    for object in objects {
+        out.push_str(&format!("{} {}", object.object_type, object.name));
+        if let Some(defined_in) = &object.defined_in {
+            out.push_str(&format!("  -- {}", defined_in));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Lowercased identifiers in SQL text, bracketed or quoted ones without their delimiters
+fn identifiers(text: &str) -> Vec<String> {
+    let mut identifiers = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let close = match c {
+            '[' => ']',
+            '"' => '"',
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut word = c.to_lowercase().to_string();
+                while let Some(&next) = chars.peek() {
+                    if !(next.is_alphanumeric() || matches!(next, '_' | '@' | '#' | '$')) {
+                        break;
+                    }
+                    word.extend(next.to_lowercase());
+                    chars.next();
+                }
+                identifiers.push(word);
+                continue;
+            }
+            _ => continue,
+        };
+        let word: String = chars.by_ref().take_while(|&next| next != close).collect();
+        identifiers.push(word.to_lowercase());
+    }
+    identifiers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identifiers() {
+        assert_eq!(
+            identifiers("EXEC [dbo].[Log Change] @Id; SELECT dbo.fn_Total(x)"),
+            vec![
+                "exec",
+                "dbo",
+                "log change",
+                "id",
+                "select",
+                "dbo",
+                "fn_total",
+                "x"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_entry_points() {
+        let entry_points = EntryPoints::new(&["api.*", "usp_Report*"]).unwrap();
+        assert!(entry_points.matches("api", "GetOrders"));
+        assert!(entry_points.matches("dbo", "USP_ReportSales"));
+        assert!(!entry_points.matches("dbo", "GetOrders"));
+        assert!(EntryPoints::new(&["[invalid"]).is_err());
+    }
+}
@@ -1,5 +1,14 @@
 //! Shared utility helpers.
 
+use std::borrow::Cow;
+use std::path::Path;
+#[cfg(windows)]
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::error::SqlPackageError;
+
 /// Case-insensitive substring search without allocating an uppercase copy.
 #[inline]
 pub fn contains_ci(haystack: &str, needle: &str) -> bool {
@@ -32,3 +41,117 @@ pub fn find_ci(haystack: &str, needle: &str) -> Option<usize> {
         .windows(needle_bytes.len())
         .position(|window| window.eq_ignore_ascii_case(needle_bytes))
 }
+
+/// `path` in a form Windows file APIs accept beyond `MAX_PATH` (260 characters)
+///
+/// On Windows the path is made absolute and given the `\\?\` prefix (`\\?\UNC\` for
+/// network shares), which lifts the limit. Making it absolute also resolves `/`
+/// separators and `.`/`..` components, which verbatim paths do not allow. Use the result
+/// for file system calls only and keep the original for messages and relative paths. On
+/// other platforms the path is returned as is.
+pub fn long_path(path: &Path) -> Cow<'_, Path> {
+    #[cfg(windows)]
+    if let Some(verbatim) = std::path::absolute(path)
+        .ok()
+        .and_then(|absolute| absolute.to_str().and_then(verbatim_path))
+    {
+        return Cow::Owned(PathBuf::from(verbatim));
+    }
+    Cow::Borrowed(path)
+}
+
+/// The `\\?\` form of an absolute Windows path: `C:\a` becomes `\\?\C:\a` and
+/// `\\server\share\a` becomes `\\?\UNC\server\share\a`. `None` for paths that are
+/// already verbatim or device paths, or not absolute.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn verbatim_path(path: &str) -> Option<String> {
+    if path.starts_with(r"\\?\") || path.starts_with(r"\\.\") {
+        None
+    } else if let Some(unc) = path.strip_prefix(r"\\") {
+        Some(format!(r"\\?\UNC\{}", unc))
+    } else if path.as_bytes().get(1) == Some(&b':') && path.as_bytes().get(2) == Some(&b'\\') {
+        Some(format!(r"\\?\{}", path))
+    } else {
+        None
+    }
+}
+
+/// A flag a host application sets to stop a build early
+///
+/// Clones share the flag: keep one and pass another in
+/// [`BuildOptions::cancellation`](crate::BuildOptions::cancellation), then call
+/// [`cancel`](Self::cancel) from any thread. The build checks it between files and
+/// stages and fails with [`SqlPackageError::Cancelled`], leaving any dacpac already
+/// on disk untouched.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask work checking this token to stop
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// `Err(SqlPackageError::Cancelled)` once cancelled
+    pub fn check(&self) -> Result<(), SqlPackageError> {
+        if self.is_cancelled() {
+            Err(SqlPackageError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// [`CancellationToken::check`] for an optional token
+pub(crate) fn check_cancelled(token: Option<&CancellationToken>) -> Result<(), SqlPackageError> {
+    token.map_or(Ok(()), CancellationToken::check)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verbatim_path() {
+        assert_eq!(
+            verbatim_path(r"C:\src\Db\Tables\Orders.sql").as_deref(),
+            Some(r"\\?\C:\src\Db\Tables\Orders.sql")
+        );
+        assert_eq!(
+            verbatim_path(r"\\build01\share\Db\Db.sqlproj").as_deref(),
+            Some(r"\\?\UNC\build01\share\Db\Db.sqlproj")
+        );
+        assert_eq!(verbatim_path(r"\\?\C:\src\Db.sqlproj"), None);
+        assert_eq!(verbatim_path(r"\\.\pipe\sql\query"), None);
+        assert_eq!(verbatim_path(r"Tables\Orders.sql"), None);
+        assert_eq!(verbatim_path("C:Orders.sql"), None);
+    }
+
+    #[test]
+    fn test_cancellation_token_shared_by_clones() {
+        let token = CancellationToken::new();
+        let handle = token.clone();
+        assert!(token.check().is_ok());
+        std::thread::spawn(move || handle.cancel()).join().unwrap();
+        assert!(token.is_cancelled());
+        assert!(matches!(token.check(), Err(SqlPackageError::Cancelled)));
+    }
+
+    #[test]
+    fn test_long_path_keeps_path_off_windows() {
+        let path = Path::new("Tables/Orders.sql");
+        if cfg!(windows) {
+            assert!(long_path(path).to_string_lossy().starts_with(r"\\?\"));
+        } else {
+            assert_eq!(long_path(path), path);
+        }
+    }
+}
@@ -51,6 +51,7 @@ impl TestContext {
             output_path: None,
             target_platform: "Sql160".to_string(),
             verbose: false,
+            ..Default::default()
         }) {
             Ok(dacpac_path) => BuildResult {
                 success: true,
@@ -447,6 +447,7 @@ pub fn run_parity_test(
         output_path: Some(rust_dacpac.clone()),
         target_platform: options.target_platform.clone(),
         verbose: false,
+        ..Default::default()
     })
     .map_err(|e| ParityTestError::RustBuildFailed {
         message: e.to_string(),
@@ -655,6 +656,7 @@ fn build_both_dacpacs(
         output_path: Some(rust_dacpac.clone()),
         target_platform: "Sql150".to_string(),
         verbose: false,
+        ..Default::default()
     })
     .map_err(|e| format!("Rust build failed: {}", e))?;
 
@@ -1108,6 +1110,7 @@ fn test_ampersand_encoding() {
         output_path: Some(rust_dacpac.clone()),
         target_platform: "Sql150".to_string(),
         verbose: false,
+        ..Default::default()
     })
     .expect("Rust build should succeed");
 
@@ -1149,6 +1152,7 @@ fn test_index_naming() {
         output_path: Some(rust_dacpac.clone()),
         target_platform: "Sql150".to_string(),
         verbose: false,
+        ..Default::default()
     })
     .expect("Rust build should succeed");
 
@@ -1708,6 +1712,7 @@ fn test_extract_content_types_from_dacpac() {
         output_path: Some(rust_dacpac.clone()),
         target_platform: "Sql150".to_string(),
         verbose: false,
+        ..Default::default()
     })
     .expect("Rust build should succeed");
 
@@ -1855,6 +1860,7 @@ fn test_extract_dac_metadata_from_dacpac() {
         output_path: Some(rust_dacpac.clone()),
         target_platform: "Sql150".to_string(),
         verbose: false,
+        ..Default::default()
     })
     .expect("Rust build should succeed");
 
@@ -2114,6 +2120,7 @@ fn test_extract_origin_xml_from_dacpac() {
         output_path: Some(rust_dacpac.clone()),
         target_platform: "Sql150".to_string(),
         verbose: false,
+        ..Default::default()
     })
     .expect("Rust build should succeed");
 
@@ -2241,6 +2248,7 @@ fn test_extract_deploy_scripts_from_dacpac() {
         output_path: Some(rust_dacpac.clone()),
         target_platform: "Sql150".to_string(),
         verbose: false,
+        ..Default::default()
     })
     .expect("Rust build should succeed");
 
@@ -4725,6 +4733,7 @@ fn test_canonical_comparison_simple_table() {
         output_path: Some(rust_dacpac.clone()),
         target_platform: "Sql150".to_string(),
         verbose: false,
+        ..Default::default()
     });
 
     if let Err(e) = build_result {
@@ -4803,6 +4812,7 @@ fn test_compare_canonical_dacpacs_function() {
         output_path: Some(rust_dacpac.clone()),
         target_platform: "Sql150".to_string(),
         verbose: false,
+        ..Default::default()
     });
 
     if let Err(e) = build_result {
@@ -4896,6 +4906,7 @@ fn test_canonical_comparison_all_fixtures() {
             output_path: Some(rust_dacpac.clone()),
             target_platform: "Sql150".to_string(),
             verbose: false,
+            ..Default::default()
         })
         .is_err()
         {
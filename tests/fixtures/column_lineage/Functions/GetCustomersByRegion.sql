@@ -0,0 +1,10 @@
+CREATE FUNCTION [dbo].[GetCustomersByRegion](@Region NVARCHAR(50))
+RETURNS TABLE
+AS
+RETURN
+(
+    SELECT c.[Id], c.[Name]
+    FROM [dbo].[Customers] c
+    WHERE c.[Region] = @Region
+);
+GO
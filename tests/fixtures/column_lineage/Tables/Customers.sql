@@ -0,0 +1,6 @@
+CREATE TABLE [dbo].[Customers] (
+    [Id] INT NOT NULL PRIMARY KEY,
+    [Name] NVARCHAR(100) NOT NULL,
+    [Region] NVARCHAR(50) NULL
+);
+GO
@@ -0,0 +1,7 @@
+CREATE VIEW [dbo].[CustomerDirectory]
+AS
+SELECT
+    n.[CustomerId],
+    n.[CustomerName] AS DisplayName
+FROM [dbo].[CustomerNames] n;
+GO
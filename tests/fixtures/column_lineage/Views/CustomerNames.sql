@@ -0,0 +1,8 @@
+CREATE VIEW [dbo].[CustomerNames]
+AS
+SELECT
+    c.[Id] AS CustomerId,
+    c.[Name] AS CustomerName,
+    UPPER(c.[Region]) AS RegionCode
+FROM [dbo].[Customers] c;
+GO
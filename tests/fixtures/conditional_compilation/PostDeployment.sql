@@ -0,0 +1,4 @@
+:IF '$(Environment)' <> 'Production'
+INSERT INTO [dbo].[Customers] ([Id], [Name]) VALUES (1, N'Test customer');
+:ENDIF
+GO
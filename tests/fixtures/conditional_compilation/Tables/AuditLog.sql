@@ -0,0 +1,7 @@
+:IF $(IncludeAudit)
+CREATE TABLE [dbo].[AuditLog] (
+    [Id] INT NOT NULL PRIMARY KEY,
+    [Message] NVARCHAR(400) NOT NULL
+);
+GO
+:ENDIF
@@ -0,0 +1,5 @@
+CREATE TABLE [dbo].[Customers] (
+    [Id] INT NOT NULL PRIMARY KEY,
+    [Name] NVARCHAR(100) NOT NULL
+);
+GO
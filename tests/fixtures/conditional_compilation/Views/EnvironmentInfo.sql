@@ -0,0 +1,10 @@
+:IF '$(Environment)' = 'Production'
+CREATE VIEW [dbo].[EnvironmentInfo]
+AS
+SELECT N'Production' AS [Environment], COUNT(*) AS [Customers] FROM [dbo].[Customers];
+:ELSE
+CREATE VIEW [dbo].[EnvironmentInfo]
+AS
+SELECT N'Test' AS [Environment], COUNT(*) AS [Customers] FROM [dbo].[Customers];
+:ENDIF
+GO
@@ -0,0 +1,10 @@
+/*
+ * Author:      J. Smith
+ * Description: Looks up a customer
+ *              by id
+ * History:     2024-01-02 created
+ */
+CREATE PROCEDURE [dbo].[GetCustomer]
+    @Id INT
+AS
+    SELECT [Id], [Name] FROM [dbo].[Customers] WHERE [Id] = @Id;
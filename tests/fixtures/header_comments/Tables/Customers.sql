@@ -0,0 +1,7 @@
+-- =============================================
+-- Customers who have placed at least one order
+-- =============================================
+CREATE TABLE [dbo].[Customers] (
+    [Id] INT NOT NULL PRIMARY KEY,
+    [Name] NVARCHAR(100) NOT NULL
+);
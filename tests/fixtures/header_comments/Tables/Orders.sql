@@ -0,0 +1,11 @@
+CREATE TABLE [dbo].[Orders] (
+    [Id]         INT            NOT NULL PRIMARY KEY, -- description: Order number
+    [CustomerId] INT            NOT NULL, -- description: Customer who placed the order
+    [Total]      DECIMAL(18, 2) NOT NULL -- description: Total including tax
+);
+GO
+
+EXEC sp_addextendedproperty @name = N'MS_Description', @value = N'Order total in NZD',
+    @level0type = N'SCHEMA', @level0name = N'dbo',
+    @level1type = N'TABLE', @level1name = N'Orders',
+    @level2type = N'COLUMN', @level2name = N'Total';
@@ -0,0 +1,3 @@
+CREATE VIEW [dbo].[CustomerCount]
+AS
+SELECT COUNT(*) AS [Total] FROM [dbo].[Customers];
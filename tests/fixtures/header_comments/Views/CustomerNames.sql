@@ -0,0 +1,9 @@
+-- Customer names for the reporting team
+CREATE VIEW [dbo].[CustomerNames]
+AS
+SELECT [Name] FROM [dbo].[Customers];
+GO
+
+EXEC sp_addextendedproperty @name = N'MS_Description', @value = N'Names of all customers',
+    @level0type = N'SCHEMA', @level0name = N'dbo',
+    @level1type = N'VIEW', @level1name = N'CustomerNames';
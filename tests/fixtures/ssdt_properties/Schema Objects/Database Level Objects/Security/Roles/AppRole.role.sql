@@ -0,0 +1 @@
+CREATE ROLE [AppRole] AUTHORIZATION [dbo];
@@ -0,0 +1,5 @@
+CREATE TABLE [dbo].[Orders] (
+    [Id]    INT            NOT NULL,
+    [Total] DECIMAL (18, 2) NOT NULL,
+    CONSTRAINT [PK_Orders] PRIMARY KEY ([Id])
+);
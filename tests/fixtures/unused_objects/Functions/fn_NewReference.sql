@@ -0,0 +1,6 @@
+CREATE FUNCTION [dbo].[fn_NewReference]()
+RETURNS NVARCHAR(20)
+AS
+BEGIN
+    RETURN N'ORD';
+END;
@@ -0,0 +1,6 @@
+CREATE FUNCTION [dbo].[fn_OrderTotal](@Id INT)
+RETURNS DECIMAL(10, 2)
+AS
+BEGIN
+    RETURN (SELECT [Total] FROM [dbo].[Orders] WHERE [Id] = @Id);
+END;
@@ -0,0 +1,5 @@
+CREATE PROCEDURE [dbo].[usp_AuditOrder]
+AS
+BEGIN
+    SET NOCOUNT ON;
+END;
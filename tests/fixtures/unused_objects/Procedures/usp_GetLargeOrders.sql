@@ -0,0 +1,5 @@
+CREATE PROCEDURE [dbo].[usp_GetLargeOrders]
+AS
+BEGIN
+    SELECT [Id], [dbo].[fn_OrderTotal]([Id]) AS [Total] FROM [dbo].[LargeOrders];
+END;
@@ -0,0 +1,5 @@
+CREATE PROCEDURE [dbo].[usp_LegacyExport]
+AS
+BEGIN
+    SELECT [Id] FROM [dbo].[Orders];
+END;
@@ -0,0 +1,5 @@
+CREATE PROCEDURE [dbo].[usp_SeedOrders]
+AS
+BEGIN
+    INSERT INTO [dbo].[Orders] ([Id], [Total]) VALUES (1, 0);
+END;
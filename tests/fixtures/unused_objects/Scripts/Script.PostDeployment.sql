@@ -0,0 +1 @@
+EXEC [dbo].[usp_SeedOrders];
@@ -0,0 +1,13 @@
+CREATE TABLE [dbo].[Orders]
+(
+    [Id] INT NOT NULL PRIMARY KEY,
+    [Total] DECIMAL(10, 2) NOT NULL,
+    [Reference] NVARCHAR(20) NOT NULL DEFAULT ([dbo].[fn_NewReference]())
+);
+GO
+
+CREATE TRIGGER [dbo].[trg_Orders_Insert] ON [dbo].[Orders] AFTER INSERT
+AS
+BEGIN
+    EXEC [dbo].[usp_AuditOrder];
+END;
@@ -0,0 +1,3 @@
+CREATE VIEW [dbo].[LargeOrders]
+AS
+SELECT [Id], [Total] FROM [dbo].[Orders] WHERE [Total] > 1000;
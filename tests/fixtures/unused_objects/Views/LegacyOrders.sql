@@ -0,0 +1,3 @@
+CREATE VIEW [dbo].[LegacyOrders]
+AS
+SELECT [Id] FROM [dbo].[Orders];
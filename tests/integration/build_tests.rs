@@ -210,6 +210,170 @@ fn test_build_with_pre_post_deploy_scripts() {
     );
 }
 
+#[test]
+fn test_failed_build_keeps_previous_dacpac() {
+    let ctx = TestContext::with_fixture("pre_post_deploy");
+    let dacpac_path = ctx.build_successfully();
+    let previous = std::fs::read(&dacpac_path).unwrap();
+
+    // The deploy script is read while the package is being written
+    std::fs::write(
+        ctx.project_dir.join("PostDeployment.sql"),
+        ":r .\\Missing.sql\n",
+    )
+    .unwrap();
+    let result = rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+        project_path: ctx.project_path(),
+        ..Default::default()
+    });
+    assert!(result.is_err(), "Build should fail on the missing include");
+
+    assert_eq!(std::fs::read(&dacpac_path).unwrap(), previous);
+    let leftovers: Vec<_> = std::fs::read_dir(dacpac_path.parent().unwrap())
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().ends_with(".tmp"))
+        .collect();
+    assert!(leftovers.is_empty(), "Temporary file should be removed");
+}
+
+#[test]
+fn test_cancelled_build_writes_nothing() {
+    let ctx = TestContext::with_fixture("simple_table");
+    let cancellation = rust_sqlpackage::CancellationToken::new();
+    cancellation.cancel();
+
+    let result = rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+        project_path: ctx.project_path(),
+        cancellation: Some(cancellation),
+        ..Default::default()
+    });
+    let error = result.unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<rust_sqlpackage::SqlPackageError>(),
+        Some(rust_sqlpackage::SqlPackageError::Cancelled)
+    ));
+    assert!(!ctx.project_dir.join("bin").exists());
+}
+
+#[test]
+fn test_build_parse_fallback_chain() {
+    use rust_sqlpackage::parser::ParseFallback;
+
+    let ctx = TestContext::with_fixture("simple_table");
+    std::fs::write(
+        ctx.project_dir.join("Quirky.sql"),
+        "CREATE VIEW [dbo].[Quirky] AS SELECT 'unterminated",
+    )
+    .unwrap();
+    let build = |parse_fallback| {
+        rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+            project_path: ctx.project_path(),
+            parse_fallback,
+            ..Default::default()
+        })
+    };
+
+    let error = build(ParseFallback::Fail).unwrap_err().to_string();
+    assert!(error.contains("Quirky.sql at line 1"), "{}", error);
+
+    // The view is kept as written
+    let dacpac_path = build(ParseFallback::Raw).unwrap();
+    let info = DacpacInfo::from_dacpac(&dacpac_path).unwrap();
+    assert!(info
+        .model_xml_content
+        .unwrap()
+        .contains(r#"<Element Type="SqlView" Name="[dbo].[Quirky]">"#));
+
+    // A statement that creates nothing fails unless skipped
+    std::fs::write(
+        ctx.project_dir.join("Merge.sql"),
+        "MERGE INTO [dbo].[Table1] USING [dbo].[Table1] ON",
+    )
+    .unwrap();
+    assert!(build(ParseFallback::Raw).is_err());
+    let dacpac_path = build(ParseFallback::Skip).unwrap();
+    let info = DacpacInfo::from_dacpac(&dacpac_path).unwrap();
+    assert!(info.tables.iter().any(|t| t.contains("Table1")));
+    assert!(info.model_xml_content.unwrap().contains("[dbo].[Quirky]"));
+}
+
+#[test]
+fn test_build_model_overrides() {
+    use rust_sqlpackage::dacpac::{ModelOverrides, PropertyOverride};
+
+    let ctx = TestContext::with_fixture("simple_table");
+    std::fs::write(
+        ctx.project_dir.join("GetItems.sql"),
+        "CREATE PROCEDURE [dbo].[GetItems] AS SELECT 1;",
+    )
+    .unwrap();
+    let dacpac_path = rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+        project_path: ctx.project_path(),
+        model_overrides: ModelOverrides {
+            strip_annotations: vec!["SysCommentsObjectAnnotation".to_string()],
+            properties: vec![PropertyOverride {
+                element_type: Some("SqlProcedure".to_string()),
+                name: "IsQuotedIdentifierOn".to_string(),
+                value: Some("True".to_string()),
+            }],
+        },
+        ..Default::default()
+    })
+    .unwrap();
+
+    let model_xml = DacpacInfo::from_dacpac(&dacpac_path)
+        .unwrap()
+        .model_xml_content
+        .unwrap();
+    assert!(!model_xml.contains("SysCommentsObjectAnnotation"));
+    let procedure = &model_xml[model_xml
+        .find(r#"<Element Type="SqlProcedure" Name="[dbo].[GetItems]">"#)
+        .unwrap()..];
+    let procedure = &procedure[..procedure.find("</Element>").unwrap()];
+    assert!(procedure.contains(r#"<Property Name="IsQuotedIdentifierOn" Value="True" />"#));
+}
+
+#[test]
+fn test_build_escaped_script_format() {
+    let ctx = TestContext::with_fixture("views");
+    let dacpac_path = rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+        project_path: ctx.project_path(),
+        script_format: rust_sqlpackage::model::ScriptFormat::Escaped,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let model_xml = DacpacInfo::from_dacpac(&dacpac_path)
+        .unwrap()
+        .model_xml_content
+        .unwrap();
+    assert!(!model_xml.contains("<![CDATA["));
+    assert!(model_xml.contains(r#"<Property Name="QueryScript">"#));
+    roxmltree::Document::parse(&model_xml).unwrap();
+}
+
+#[test]
+fn test_build_with_thread_limit_matches_default() {
+    let ctx = TestContext::with_fixture("stress_test");
+    let default_dacpac = ctx.build_successfully();
+
+    let limited_dacpac = rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+        project_path: ctx.project_path(),
+        output_path: Some(ctx.project_dir.join("limited.dacpac")),
+        threads: Some(1),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let default_info = DacpacInfo::from_dacpac(&default_dacpac).unwrap();
+    let limited_info = DacpacInfo::from_dacpac(&limited_dacpac).unwrap();
+    assert_eq!(
+        limited_info.model_xml_content,
+        default_info.model_xml_content
+    );
+}
+
 // ============================================================================
 // SQLCMD :r Include Tests
 // ============================================================================
@@ -267,6 +431,210 @@ fn test_build_with_sqlcmd_includes() {
     );
 }
 
+#[test]
+fn test_build_keeps_sqlcmd_includes_when_requested() {
+    let ctx = TestContext::with_fixture("sqlcmd_includes");
+    let dacpac_path = rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+        project_path: ctx.project_path(),
+        output_path: Some(ctx.project_dir.join("bin").join("unresolved.dacpac")),
+        keep_deploy_includes: true,
+        ..Default::default()
+    })
+    .expect("Build should succeed");
+    let info = DacpacInfo::from_dacpac(&dacpac_path).expect("Should parse dacpac");
+
+    let postdeploy = info
+        .postdeploy_content
+        .expect("Should have postdeploy content");
+    assert!(postdeploy.contains("Starting post-deployment"));
+    assert!(
+        postdeploy.contains(r":r Scripts\SeedUsers.sql"),
+        "Postdeploy should keep the :r directives"
+    );
+    assert!(
+        !postdeploy.contains("Seeding users"),
+        "Postdeploy should not inline SeedUsers.sql"
+    );
+}
+
+#[test]
+fn test_build_header_descriptions() {
+    let ctx = TestContext::with_fixture("header_comments");
+
+    // Without the option, header comments are left out of the model
+    let info = DacpacInfo::from_dacpac(&ctx.build_successfully()).unwrap();
+    let model_xml = info.model_xml_content.unwrap();
+    assert!(!model_xml.contains("[SqlTableBase].[dbo].[Customers].[MS_Description]"));
+
+    let dacpac_path = rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+        project_path: ctx.project_path(),
+        output_path: Some(ctx.project_dir.join("bin").join("described.dacpac")),
+        header_descriptions: true,
+        ..Default::default()
+    })
+    .expect("Build should succeed");
+    let info = DacpacInfo::from_dacpac(&dacpac_path).unwrap();
+    let model_xml = info.model_xml_content.unwrap();
+
+    assert!(model_xml.contains("[SqlTableBase].[dbo].[Customers].[MS_Description]"));
+    assert!(model_xml.contains("N'Customers who have placed at least one order'"));
+    // The Description field of an author/description block
+    assert!(model_xml.contains("[SqlProcedure].[dbo].[GetCustomer].[MS_Description]"));
+    assert!(model_xml.contains("N'Looks up a customer by id'"));
+    // A description declared with sp_addextendedproperty is kept
+    assert!(model_xml.contains("N'Names of all customers'"));
+    assert!(!model_xml.contains("N'Customer names for the reporting team'"));
+    // Objects without a header get no description
+    assert!(!model_xml.contains("[SqlView].[dbo].[CustomerCount].[MS_Description]"));
+    // Two generated, plus the view's and the Orders.Total column's own
+    assert_eq!(model_xml.matches("Type=\"SqlExtendedProperty\"").count(), 4);
+}
+
+#[test]
+fn test_build_column_descriptions() {
+    let ctx = TestContext::with_fixture("header_comments");
+    let dacpac_path = rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+        project_path: ctx.project_path(),
+        output_path: Some(ctx.project_dir.join("bin").join("described.dacpac")),
+        column_descriptions: true,
+        ..Default::default()
+    })
+    .expect("Build should succeed");
+    let info = DacpacInfo::from_dacpac(&dacpac_path).unwrap();
+    let model_xml = info.model_xml_content.unwrap();
+
+    assert!(model_xml.contains("[SqlColumn].[dbo].[Orders].[Id].[MS_Description]"));
+    assert!(model_xml.contains("N'Order number'"));
+    assert!(model_xml.contains("N'Customer who placed the order'"));
+    // A description declared with sp_addextendedproperty is kept
+    assert!(model_xml.contains("N'Order total in NZD'"));
+    assert!(!model_xml.contains("N'Total including tax'"));
+    assert_eq!(
+        model_xml
+            .matches("[SqlColumn].[dbo].[Orders].[Total].[MS_Description]")
+            .count(),
+        1
+    );
+    // Header comments are only used with header_descriptions
+    assert!(!model_xml.contains("[SqlTableBase].[dbo].[Customers].[MS_Description]"));
+}
+
+#[test]
+fn test_build_legacy_properties_files() {
+    let ctx = TestContext::with_fixture("ssdt_properties");
+    let dacpac_path = ctx.build_successfully();
+    let info = DacpacInfo::from_dacpac(&dacpac_path).unwrap();
+    let model_xml = info.model_xml_content.unwrap();
+
+    // Database.sqlsettings
+    assert!(model_xml.contains(r#"CollationCaseSensitive="True""#));
+    assert!(model_xml.contains(r#"<Property Name="Collation" Value="Latin1_General_CS_AS" />"#));
+    assert!(model_xml.contains(r#"<Property Name="IsAnsiWarningsOn" Value="False" />"#));
+    assert!(model_xml.contains(r#"<Property Name="IsFullTextEnabled" Value="False" />"#));
+    // Database.sqlpermissions
+    assert!(model_xml.contains(r#"Name="[Grant].[SELECT].[dbo].[Orders].[AppRole]""#));
+    assert!(model_xml.contains(r#"Name="[Deny].[DELETE].[dbo].[Orders].[AppRole]""#));
+    // Database.sqlcmdvars
+    assert!(model_xml.contains(r#"<Metadata Name="Environment" Value="" />"#));
+
+    // Properties in the project file win over Database.sqlsettings
+    let project = ctx.project_path();
+    let content = std::fs::read_to_string(&project).unwrap();
+    std::fs::write(
+        &project,
+        content.replace(
+            "<Name>LegacyDatabase</Name>",
+            "<Name>LegacyDatabase</Name>\n    <AnsiWarningsOn>True</AnsiWarningsOn>",
+        ),
+    )
+    .unwrap();
+    let parsed = rust_sqlpackage::project::parse_sqlproj(&project).unwrap();
+    assert!(parsed.database_options.ansi_warnings_on);
+    assert!(!parsed.database_options.full_text_enabled);
+    assert_eq!(
+        parsed.sqlcmd_variables[0].default_value,
+        "Development".to_string()
+    );
+    assert!(parsed.warnings.is_empty(), "{:?}", parsed.warnings);
+}
+
+#[test]
+fn test_header_comments_in_docs_and_lineage() {
+    let ctx = TestContext::with_fixture("header_comments");
+
+    let docs =
+        rust_sqlpackage::generate_docs(&ctx.project_path(), rust_sqlpackage::DocsFormat::Markdown)
+            .expect("Docs should generate");
+    assert!(docs.contains("Customers who have placed at least one order"));
+    assert!(docs.contains("Looks up a customer by id"));
+    // An MS_Description extended property takes precedence over the header
+    assert!(docs.contains("Names of all customers"));
+    assert!(!docs.contains("reporting team"));
+
+    let json = rust_sqlpackage::generate_lineage(&ctx.project_path()).unwrap();
+    let lineage: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let objects = lineage["objects"].as_array().unwrap();
+    let view = |name: &str| {
+        objects
+            .iter()
+            .find(|o| o["name"] == name)
+            .unwrap_or_else(|| panic!("{} should be in the lineage", name))
+    };
+    assert_eq!(
+        view("[dbo].[CustomerNames]")["header"],
+        "Customer names for the reporting team"
+    );
+    assert!(view("[dbo].[CustomerCount]").get("header").is_none());
+}
+
+#[test]
+fn test_build_from_dir_without_sqlproj() {
+    let ctx = TestContext::with_fixture("e2e_simple");
+    let project_info = DacpacInfo::from_dacpac(&ctx.build_successfully()).unwrap();
+
+    let dacpac_path = rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+        from_dir: Some(ctx.project_dir.clone()),
+        name: Some("Inventory".to_string()),
+        ..Default::default()
+    })
+    .expect("Folder build should succeed");
+    assert_eq!(
+        dacpac_path,
+        ctx.project_dir
+            .join("bin")
+            .join("Debug")
+            .join("Inventory.dacpac")
+    );
+
+    let info = DacpacInfo::from_dacpac(&dacpac_path).unwrap();
+    assert_eq!(info.tables, project_info.tables);
+    assert_eq!(info.views, project_info.views);
+    assert!(info
+        .metadata_xml_content
+        .unwrap()
+        .contains("<Name>Inventory</Name>"));
+}
+
+#[test]
+fn test_build_from_dir_finds_deployment_scripts() {
+    let ctx = TestContext::with_fixture("sqlcmd_includes");
+    let dacpac_path = rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+        from_dir: Some(ctx.project_dir.clone()),
+        output_path: Some(ctx.project_dir.join("bin").join("folder.dacpac")),
+        ..Default::default()
+    })
+    .expect("Folder build should succeed");
+    let info = DacpacInfo::from_dacpac(&dacpac_path).unwrap();
+
+    // Seed scripts included with :r stay out of the model
+    assert_eq!(info.tables.len(), 2, "Tables: {:?}", info.tables);
+    assert!(info.predeploy_content.is_some());
+    assert!(info
+        .postdeploy_content
+        .expect("Should have postdeploy content")
+        .contains("Seeding users"));
+}
+
 // ============================================================================
 // Multiple Tables Test
 // ============================================================================
@@ -305,6 +673,117 @@ fn test_build_multiple_tables() {
     );
 }
 
+#[test]
+fn test_build_objects_per_file_policy() {
+    let ctx = TestContext::with_fixture("simple_table");
+    std::fs::write(
+        ctx.project_dir.join("Orders.sql"),
+        "CREATE TABLE [dbo].[Orders] ([Id] INT NOT NULL PRIMARY KEY);\nGO\n\
+         -- Lines of an order\n\
+         CREATE TABLE [dbo].[OrderLines] ([Id] INT NOT NULL PRIMARY KEY, [OrderId] INT NOT NULL);\nGO\n\
+         CREATE INDEX [IX_OrderLines_OrderId] ON [dbo].[OrderLines] ([OrderId]);\nGO\n",
+    )
+    .unwrap();
+    let sqlproj_path = ctx.project_path();
+    let sqlproj = std::fs::read_to_string(&sqlproj_path).unwrap();
+    let single = sqlproj
+        .replace(
+            "</PropertyGroup>",
+            "  <ObjectsPerFile>Single</ObjectsPerFile>\n  </PropertyGroup>\n  <ItemGroup>\n    <Build Include=\"Table1.sql\" />\n    <Build Include=\"Orders.sql\" />\n  </ItemGroup>",
+        );
+    std::fs::write(&sqlproj_path, &single).unwrap();
+
+    let result = ctx.build();
+    assert!(!result.success);
+    assert!(
+        result.errors[0].contains("Orders.sql ([dbo].[Orders], [dbo].[OrderLines])"),
+        "{:?}",
+        result.errors
+    );
+
+    // Split moves OrderLines, with its index, to a file of its own
+    std::fs::write(&sqlproj_path, single.replace(">Single<", ">Split<")).unwrap();
+    let dacpac_path = rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+        project_path: ctx.project_path(),
+        source_manifest: true,
+        ..Default::default()
+    })
+    .expect("Build should succeed");
+    assert_eq!(
+        std::fs::read_to_string(ctx.project_dir.join("Orders.sql")).unwrap(),
+        "CREATE TABLE [dbo].[Orders] ([Id] INT NOT NULL PRIMARY KEY);\nGO\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(ctx.project_dir.join("OrderLines.sql")).unwrap(),
+        "-- Lines of an order\n\
+         CREATE TABLE [dbo].[OrderLines] ([Id] INT NOT NULL PRIMARY KEY, [OrderId] INT NOT NULL);\nGO\n\
+         CREATE INDEX [IX_OrderLines_OrderId] ON [dbo].[OrderLines] ([OrderId]);\nGO\n"
+    );
+    assert!(std::fs::read_to_string(&sqlproj_path)
+        .unwrap()
+        .contains("<Build Include=\"OrderLines.sql\" />"));
+    let info = DacpacInfo::from_dacpac(&dacpac_path).unwrap();
+    assert!(info.tables.iter().any(|t| t.contains("OrderLines")));
+    // The dacpac is built from the split files
+    let verification =
+        rust_sqlpackage::dacpac::verify_sources(&dacpac_path, &ctx.project_dir).unwrap();
+    assert!(verification.is_ok(), "{:?}", verification);
+    assert_eq!(verification.verified, 3);
+
+    // Nothing left to split
+    ctx.build_successfully();
+    assert!(!ctx.project_dir.join("dbo.OrderLines.sql").exists());
+}
+
+#[test]
+fn test_build_objects_per_file_split_keeps_encoding() {
+    let ctx = TestContext::with_fixture("simple_table");
+    let utf16 = |text: &str| -> Vec<u8> {
+        [0xFF, 0xFE]
+            .into_iter()
+            .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect()
+    };
+    std::fs::write(
+        ctx.project_dir.join("Unicode.sql"),
+        utf16("CREATE TABLE [dbo].[Café] ([Id] INT);\nGO\nCREATE TABLE [dbo].[Crème] ([Id] INT);\nGO\n"),
+    )
+    .unwrap();
+    std::fs::write(
+        ctx.project_dir.join("Ansi.sql"),
+        b"CREATE TABLE [dbo].[Na\xEFve] ([Id] INT);\nGO\nCREATE TABLE [dbo].[Se\xF1or] ([Id] INT);\nGO\n",
+    )
+    .unwrap();
+    let sqlproj = std::fs::read_to_string(ctx.project_path()).unwrap();
+    std::fs::write(
+        ctx.project_path(),
+        sqlproj.replace(
+            "</PropertyGroup>",
+            "  <ObjectsPerFile>Split</ObjectsPerFile>\n  </PropertyGroup>",
+        ),
+    )
+    .unwrap();
+
+    ctx.build_successfully();
+    let read = |name: &str| std::fs::read(ctx.project_dir.join(name)).unwrap();
+    assert_eq!(
+        read("Unicode.sql"),
+        utf16("CREATE TABLE [dbo].[Café] ([Id] INT);\nGO\n")
+    );
+    assert_eq!(
+        read("Crème.sql"),
+        utf16("CREATE TABLE [dbo].[Crème] ([Id] INT);\nGO\n")
+    );
+    assert_eq!(
+        read("Ansi.sql"),
+        b"CREATE TABLE [dbo].[Na\xEFve] ([Id] INT);\nGO\n"
+    );
+    assert_eq!(
+        read("Se\u{F1}or.sql"),
+        b"CREATE TABLE [dbo].[Se\xF1or] ([Id] INT);\nGO\n"
+    );
+}
+
 // ============================================================================
 // SDK-Style Project Tests
 // ============================================================================
@@ -795,3 +1274,889 @@ fn test_output_parameter_model_xml_structure() {
         "Model should contain ComplexProcedure"
     );
 }
+
+// ============================================================================
+// Build Summary Tests
+// ============================================================================
+
+#[test]
+fn test_build_writes_json_summary() {
+    let ctx = TestContext::with_fixture("simple_table");
+    let output_path = ctx.project_dir.join("out").join("simple.dacpac");
+
+    let dacpac_path = rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+        project_path: ctx.project_path(),
+        output_path: Some(output_path.clone()),
+        summary_format: Some(rust_sqlpackage::SummaryFormat::Json),
+        ..Default::default()
+    })
+    .expect("Build should succeed");
+
+    let summary_path = ctx.project_dir.join("out").join("simple.summary.json");
+    assert!(summary_path.exists(), "Summary file should be written");
+
+    let content = std::fs::read_to_string(&summary_path).unwrap();
+    let summary: serde_json::Value = serde_json::from_str(&content).unwrap();
+
+    assert_eq!(summary["project"], "project");
+    assert_eq!(
+        summary["output_path"],
+        dacpac_path.to_string_lossy().as_ref()
+    );
+    assert_eq!(summary["output_sha256"].as_str().unwrap().len(), 64);
+    assert!(summary["elements_by_type"]["SqlTable"].as_u64().unwrap() >= 1);
+    assert!(summary["durations"]["total_ms"].as_f64().unwrap() >= 0.0);
+    assert!(summary["warnings"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_build_writes_object_hashes() {
+    let ctx = TestContext::with_fixture("simple_table");
+    let build = |table_sql: &str| {
+        std::fs::write(ctx.project_dir.join("Table1.sql"), table_sql).unwrap();
+        let dacpac_path = rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+            project_path: ctx.project_path(),
+            object_hashes: true,
+            ..Default::default()
+        })
+        .expect("Build should succeed");
+        let hashes_path = dacpac_path.with_file_name("project.hashes.json");
+        let content = std::fs::read_to_string(hashes_path).unwrap();
+        serde_json::from_str::<std::collections::BTreeMap<String, String>>(&content).unwrap()
+    };
+
+    let before = build("CREATE TABLE [dbo].[Table1] ([c1] INT NOT NULL PRIMARY KEY);\nGO\nCREATE TABLE [dbo].[Table2] ([c1] INT NULL);");
+    assert_eq!(before["SqlTable [dbo].[Table1]"].len(), 64);
+    assert!(before.contains_key("SqlTable [dbo].[Table2]"));
+
+    let after = build("CREATE TABLE [dbo].[Table1] ([c1] INT NOT NULL PRIMARY KEY);\nGO\nCREATE TABLE [dbo].[Table2] ([c1] BIGINT NULL);");
+    assert_eq!(
+        after["SqlTable [dbo].[Table1]"],
+        before["SqlTable [dbo].[Table1]"]
+    );
+    assert_ne!(
+        after["SqlTable [dbo].[Table2]"],
+        before["SqlTable [dbo].[Table2]"]
+    );
+}
+
+#[test]
+fn test_build_source_manifest_verifies_sources() {
+    let ctx = TestContext::with_fixture("simple_table");
+    let dacpac_path = rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+        project_path: ctx.project_path(),
+        source_manifest: true,
+        ..Default::default()
+    })
+    .expect("Build should succeed");
+
+    let info = DacpacInfo::from_dacpac(&dacpac_path).unwrap();
+    assert!(info
+        .content_types_xml_content
+        .unwrap()
+        .contains(r#"Extension="json""#));
+
+    let verify =
+        || rust_sqlpackage::dacpac::verify_sources(&dacpac_path, &ctx.project_dir).unwrap();
+    let verification = verify();
+    assert!(verification.is_ok());
+    assert_eq!(verification.verified, 1);
+
+    std::fs::write(
+        ctx.project_dir.join("Table1.sql"),
+        "CREATE TABLE [dbo].[Table1] ([c1] INT);",
+    )
+    .unwrap();
+    assert_eq!(verify().changed, vec!["Table1.sql".to_string()]);
+
+    std::fs::remove_file(ctx.project_dir.join("Table1.sql")).unwrap();
+    assert_eq!(verify().missing, vec!["Table1.sql".to_string()]);
+}
+
+#[test]
+fn test_build_source_manifest_lists_deploy_includes() {
+    let ctx = TestContext::with_fixture("sqlcmd_includes");
+    let dacpac_path = rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+        project_path: ctx.project_path(),
+        source_manifest: true,
+        ..Default::default()
+    })
+    .expect("Build should succeed");
+
+    let verify =
+        || rust_sqlpackage::dacpac::verify_sources(&dacpac_path, &ctx.project_dir).unwrap();
+    let verification = verify();
+    assert!(verification.is_ok(), "{:?}", verification);
+    // 2 tables, 2 deploy scripts and the 3 scripts they include
+    assert_eq!(verification.verified, 7);
+
+    std::fs::write(
+        ctx.project_dir.join("Scripts").join("SeedUsers.sql"),
+        "PRINT 'changed';",
+    )
+    .unwrap();
+    assert_eq!(verify().changed, vec!["Scripts/SeedUsers.sql".to_string()]);
+}
+
+#[test]
+fn test_build_unknown_element_policy() {
+    use rust_sqlpackage::project::UnknownElementPolicy;
+
+    let ctx = TestContext::with_fixture("simple_table");
+    let project = std::fs::read_to_string(ctx.project_path()).unwrap();
+    std::fs::write(
+        ctx.project_path(),
+        project.replace(
+            "</Project>",
+            "  <ItemGroup>\n    <BuildExtensionConfiguration Include=\"Contributor.xml\" />\n  </ItemGroup>\n</Project>",
+        ),
+    )
+    .unwrap();
+    let build = |policy| {
+        rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+            project_path: ctx.project_path(),
+            summary_format: Some(rust_sqlpackage::SummaryFormat::Json),
+            unknown_elements: policy,
+            ..Default::default()
+        })
+    };
+
+    let error = build(UnknownElementPolicy::Error).unwrap_err().to_string();
+    assert!(
+        error.contains("<BuildExtensionConfiguration Contributor.xml> at line 9"),
+        "{}",
+        error
+    );
+
+    let dacpac_path = build(UnknownElementPolicy::Warn).expect("Build should succeed");
+    let summary: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(dacpac_path.with_file_name("project.summary.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        summary["warnings"][0],
+        "<BuildExtensionConfiguration Contributor.xml> at line 9: build contributor configuration is not applied"
+    );
+
+    build(UnknownElementPolicy::Ignore).expect("Build should succeed");
+}
+
+#[test]
+fn test_build_dry_run_writes_nothing() {
+    let ctx = TestContext::with_fixture("simple_table");
+    let output_dir = ctx.project_dir.join("out");
+
+    let dacpac_path = rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+        project_path: ctx.project_path(),
+        output_dir: Some(output_dir.clone()),
+        summary_format: Some(rust_sqlpackage::SummaryFormat::Json),
+        dry_run: true,
+        ..Default::default()
+    })
+    .expect("Dry run should succeed");
+
+    assert_eq!(dacpac_path, output_dir.join("project.dacpac"));
+    assert!(!output_dir.exists(), "Dry run should not write any files");
+}
+
+// ============================================================================
+// Schema Documentation Tests
+// ============================================================================
+
+#[test]
+fn test_generate_markdown_docs_with_cross_references() {
+    let ctx = TestContext::with_fixture("e2e_simple");
+
+    let docs =
+        rust_sqlpackage::generate_docs(&ctx.project_path(), rust_sqlpackage::DocsFormat::Markdown)
+            .expect("Docs generation should succeed");
+
+    assert!(docs.starts_with("# project\n"));
+    assert!(docs.contains("<a id=\"dbo-products\"></a>"));
+    assert!(
+        docs.contains("| CategoryId | `INT` | No |"),
+        "Docs:\n{}",
+        docs
+    );
+    // Foreign key links to the referenced table
+    assert!(
+        docs.contains("FOREIGN KEY (CategoryId) REFERENCES [dbo.Categories](#dbo-categories) (Id)")
+    );
+    // Procedure parameters and body dependencies
+    assert!(
+        docs.contains("| @CategoryId | `INT` | Input | `NULL` |"),
+        "Docs:\n{}",
+        docs
+    );
+    assert!(docs.contains("- [dbo.GetProducts](#dbo-getproducts)"));
+    // View query dependencies appear as "Referenced By" on the table
+    assert!(docs.contains("- [dbo.ActiveProducts](#dbo-activeproducts)"));
+}
+
+#[test]
+fn test_generate_html_docs() {
+    let ctx = TestContext::with_fixture("e2e_simple");
+
+    let docs =
+        rust_sqlpackage::generate_docs(&ctx.project_path(), rust_sqlpackage::DocsFormat::Html)
+            .expect("Docs generation should succeed");
+
+    assert!(docs.starts_with("<!DOCTYPE html>"));
+    assert!(docs.contains("<h3 id=\"sales-orders\">Sales.Orders</h3>"));
+    assert!(docs.contains("<a href=\"#sales-customers\">Sales.Customers</a>"));
+    assert!(docs.trim_end().ends_with("</html>"));
+}
+
+// ============================================================================
+// ERD Export Tests
+// ============================================================================
+
+#[test]
+fn test_generate_mermaid_erd() {
+    let ctx = TestContext::with_fixture("e2e_simple");
+
+    let erd = rust_sqlpackage::generate_erd(
+        &ctx.project_path(),
+        rust_sqlpackage::ErdFormat::Mermaid,
+        &[],
+    )
+    .expect("ERD generation should succeed");
+
+    assert!(erd.starts_with("erDiagram\n"));
+    assert!(erd.contains("dbo_Products[\"dbo.Products\"] {"));
+    assert!(erd.contains("INT CategoryId FK"));
+    assert!(erd.contains("DECIMAL(18-2) Price"));
+    assert!(erd.contains("dbo_Categories ||--o{ dbo_Products : \"FK_Products_Categories\""));
+    assert!(erd.contains("Sales_Customers ||--o{ Sales_Orders : \"FK_Orders_Customers\""));
+}
+
+#[test]
+fn test_generate_plantuml_erd_scoped_by_schema() {
+    let ctx = TestContext::with_fixture("e2e_simple");
+
+    let erd = rust_sqlpackage::generate_erd(
+        &ctx.project_path(),
+        rust_sqlpackage::ErdFormat::PlantUml,
+        &["sales".to_string()],
+    )
+    .expect("ERD generation should succeed");
+
+    assert!(erd.starts_with("@startuml\n"));
+    assert!(erd.trim_end().ends_with("@enduml"));
+    assert!(erd.contains("entity \"Sales.Orders\" as Sales_Orders {"));
+    assert!(erd.contains("* CustomerId : INT <<FK>>"));
+    assert!(erd.contains("Sales_Customers ||--o{ Sales_Orders : FK_Orders_Customers"));
+    assert!(
+        !erd.contains("dbo_Products"),
+        "dbo tables should be out of scope"
+    );
+}
+
+// ============================================================================
+// Unused Object Tests
+// ============================================================================
+
+#[test]
+fn test_find_unused_objects() {
+    let ctx = TestContext::with_fixture("unused_objects");
+
+    let find = |patterns: &[&str]| {
+        let entry_points = rust_sqlpackage::EntryPoints::new(patterns).unwrap();
+        rust_sqlpackage::find_unused(&ctx.project_path(), &entry_points)
+            .expect("Unused object search should succeed")
+    };
+
+    // Objects referenced by code, a trigger, a default or the post-deployment script are
+    // in use; nothing references the legacy view or either top-level procedure
+    let unused = find(&[]);
+    let mut names: Vec<&str> = unused.iter().map(|o| o.name.as_str()).collect();
+    names.sort_unstable();
+    assert_eq!(
+        names,
+        vec![
+            "[dbo].[LegacyOrders]",
+            "[dbo].[usp_GetLargeOrders]",
+            "[dbo].[usp_LegacyExport]",
+        ]
+    );
+    let export = unused
+        .iter()
+        .find(|o| o.name == "[dbo].[usp_LegacyExport]")
+        .unwrap();
+    assert_eq!(export.object_type, "Procedure");
+    assert_eq!(
+        export.defined_in.as_deref(),
+        Some("Procedures/usp_LegacyExport.sql:1-5")
+    );
+
+    let unused = find(&["usp_Get*", "dbo.Legacy*"]);
+    let names: Vec<&str> = unused.iter().map(|o| o.name.as_str()).collect();
+    assert_eq!(names, vec!["[dbo].[usp_LegacyExport]"]);
+}
+
+// ============================================================================
+// Column Lineage Tests
+// ============================================================================
+
+#[test]
+fn test_generate_column_lineage() {
+    let ctx = TestContext::with_fixture("column_lineage");
+
+    let json = rust_sqlpackage::generate_lineage(&ctx.project_path())
+        .expect("Lineage generation should succeed");
+    let lineage: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    let objects = lineage["objects"].as_array().unwrap();
+    let find = |name: &str| {
+        objects
+            .iter()
+            .find(|o| o["name"] == name)
+            .unwrap_or_else(|| panic!("{} should be traced", name))
+    };
+
+    // View over a table: direct sources, computed expressions have none
+    let names = find("[dbo].[CustomerNames]");
+    assert_eq!(names["type"], "View");
+    assert_eq!(names["defined_in"], "Views/CustomerNames.sql:1-7");
+    assert_eq!(names["columns"][0]["name"], "CustomerId");
+    assert_eq!(names["columns"][0]["source"], "[dbo].[Customers].[Id]");
+    assert!(names["columns"][2]["source"].is_null());
+
+    // View over a view: base_source follows through to the table column
+    let directory = find("[dbo].[CustomerDirectory]");
+    assert_eq!(
+        directory["columns"][1]["source"],
+        "[dbo].[CustomerNames].[CustomerName]"
+    );
+    assert_eq!(
+        directory["columns"][1]["base_source"],
+        "[dbo].[Customers].[Name]"
+    );
+
+    // Inline TVF output columns
+    let tvf = find("[dbo].[GetCustomersByRegion]");
+    assert_eq!(tvf["type"], "InlineTableValuedFunction");
+    assert_eq!(tvf["columns"][1]["base_source"], "[dbo].[Customers].[Name]");
+}
+
+// ============================================================================
+// Model Dump Tests
+// ============================================================================
+
+#[test]
+fn test_dump_model_filtered_by_type_and_name() {
+    let ctx = TestContext::with_fixture("e2e_simple");
+
+    let filter =
+        rust_sqlpackage::DumpFilter::new(&["Table".to_string()], &["dbo.Prod*".to_string()])
+            .unwrap();
+    let dump = rust_sqlpackage::dump_model(&ctx.project_path(), &filter)
+        .expect("Model dump should succeed");
+
+    assert!(
+        dump.starts_with("SqlTable [dbo].[Products]  -- Tables/Products.sql:1-9\n"),
+        "Dump:\n{}",
+        dump
+    );
+    assert!(dump.contains("\n    SqlSimpleColumn [dbo].[Products].[Price]\n"));
+    assert!(dump.contains("          Type -> [decimal] (BuiltIns)\n"));
+    assert!(dump.contains("\n  Schema -> [dbo] (BuiltIns)\n"));
+    assert!(!dump.contains("[dbo].[Categories]\n"));
+    assert!(!dump.contains("SqlForeignKeyConstraint"));
+
+    // Without a type filter, constraints on the table are included
+    let filter = rust_sqlpackage::DumpFilter::new(&[], &["Products".to_string()]).unwrap();
+    let dump = rust_sqlpackage::dump_model(&ctx.project_path(), &filter).unwrap();
+    assert!(dump.contains(
+        "SqlForeignKeyConstraint [dbo].[FK_Products_Categories]  -- Tables/Products.sql:1-9\n"
+    ));
+}
+
+// ============================================================================
+// Dacpac Part Tests
+// ============================================================================
+
+#[test]
+fn test_read_dacpac_part() {
+    let ctx = TestContext::with_fixture("e2e_simple");
+    let dacpac_path = ctx.build_successfully();
+
+    let raw = rust_sqlpackage::read_dacpac_part(&dacpac_path, "model.xml", false).unwrap();
+    let info = DacpacInfo::from_dacpac(&dacpac_path).unwrap();
+    assert_eq!(
+        String::from_utf8(raw).unwrap(),
+        info.model_xml_content.unwrap()
+    );
+
+    let origin = rust_sqlpackage::read_dacpac_part(&dacpac_path, "origin", true).unwrap();
+    let origin = String::from_utf8(origin).unwrap();
+    assert!(
+        origin.starts_with("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<DacOrigin xmlns="),
+        "Origin.xml:\n{}",
+        origin
+    );
+    assert!(origin.contains("\n  <PackageProperties>\n    <Version>"));
+
+    let err = rust_sqlpackage::read_dacpac_part(&dacpac_path, "refactorlog", false).unwrap_err();
+    assert!(err.to_string().contains("no part named refactorlog"));
+}
+
+// ============================================================================
+// Build Filter Tests
+// ============================================================================
+
+fn build_filtered(ctx: &TestContext, filters: &[&str]) -> DacpacInfo {
+    let dacpac_path = rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+        project_path: ctx.project_path(),
+        output_path: Some(ctx.project_dir.join("bin").join("filtered.dacpac")),
+        filters: filters.iter().map(|f| f.parse().unwrap()).collect(),
+        ..Default::default()
+    })
+    .expect("Filtered build should succeed");
+    DacpacInfo::from_dacpac(&dacpac_path).expect("Should parse dacpac")
+}
+
+#[test]
+fn test_build_filter_excludes_schema_with_children() {
+    let ctx = TestContext::with_fixture("e2e_simple");
+    let info = build_filtered(&ctx, &["exclude:schema=sales"]);
+    let model_xml = info.model_xml_content.unwrap();
+
+    assert!(info.tables.iter().any(|t| t.contains("[dbo].[Products]")));
+    assert!(
+        !model_xml.contains("[Sales]"),
+        "Sales objects should be removed"
+    );
+    // Constraints on excluded tables are removed with them
+    assert!(!model_xml.contains("FK_Orders_Customers"));
+    assert!(model_xml.contains("FK_Products_Categories"));
+}
+
+#[test]
+fn test_build_filter_warns_about_dangling_references() {
+    let ctx = TestContext::with_fixture("e2e_simple");
+    let dacpac_path = rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+        project_path: ctx.project_path(),
+        filters: vec!["exclude:name=dbo.Categories".parse().unwrap()],
+        summary_format: Some(rust_sqlpackage::SummaryFormat::Json),
+        ..Default::default()
+    })
+    .expect("Filtered build should succeed");
+    let summary: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(dacpac_path.with_file_name("project.summary.json")).unwrap(),
+    )
+    .unwrap();
+    let warnings: Vec<&str> = summary["warnings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|w| w.as_str())
+        .collect();
+
+    // The view reads the excluded table, and a foreign key references it
+    assert!(
+        warnings.contains(
+            &"[dbo].[ActiveProducts] references [dbo].[Categories], which the filters leave out"
+        ),
+        "{:?}",
+        warnings
+    );
+    assert!(
+        warnings.contains(
+            &"[dbo].[Products] references [dbo].[Categories], which the filters leave out"
+        ),
+        "{:?}",
+        warnings
+    );
+}
+
+#[test]
+fn test_build_filter_drops_permissions_with_their_object() {
+    let ctx = TestContext::with_fixture("e2e_simple");
+    std::fs::write(
+        ctx.project_dir.join("Grants.sql"),
+        "GRANT SELECT ON [dbo].[Categories] TO [public];\nGO\nGRANT SELECT ON [dbo].[Products] TO [public];\n",
+    )
+    .unwrap();
+    let info = build_filtered(&ctx, &["exclude:name=dbo.Categories"]);
+    let model_xml = info.model_xml_content.unwrap();
+
+    // The grant on the excluded table goes with it; the other one stays
+    assert!(!model_xml.contains("[Grant].[SELECT].[dbo].[Categories].[public]"));
+    assert!(model_xml.contains("[Grant].[SELECT].[dbo].[Products].[public]"));
+}
+
+#[test]
+fn test_build_filter_include_type_and_exclude_name() {
+    let ctx = TestContext::with_fixture("e2e_simple");
+    let info = build_filtered(&ctx, &["include:type=Table", "exclude:name=dbo.Cat*"]);
+    let model_xml = info.model_xml_content.unwrap();
+
+    assert!(info.tables.iter().any(|t| t.contains("[dbo].[Products]")));
+    assert!(info.tables.iter().any(|t| t.contains("[Sales].[Orders]")));
+    assert!(!info.tables.iter().any(|t| t.contains("Categories")));
+    assert!(info.views.is_empty(), "Views are not included");
+    assert!(!model_xml.contains("SqlProcedure"));
+    // Index on a kept table follows its table
+    assert!(model_xml.contains("[IX_Products_CategoryId]"));
+}
+
+#[test]
+fn test_build_dacpac_profiles() {
+    let ctx = TestContext::with_fixture("e2e_simple");
+    let project_path = ctx.project_path();
+    let sqlproj = std::fs::read_to_string(&project_path).unwrap().replace(
+        "</Project>",
+        r#"  <ItemGroup>
+    <DacpacProfile Include="CustomerFacing">
+      <Filters>exclude:schema=Sales</Filters>
+    </DacpacProfile>
+    <DacpacProfile Include="TablesOnly" Filters="include:type=View" />
+  </ItemGroup>
+</Project>"#,
+    );
+    std::fs::write(&project_path, sqlproj).unwrap();
+    // The profiles file adds a profile and replaces the project's TablesOnly
+    let profiles_path = ctx.project_dir.join("profiles.json");
+    std::fs::write(
+        &profiles_path,
+        r#"{"TablesOnly": ["include:type=Table"], "NoProcedures": ["exclude:type=Procedure"]}"#,
+    )
+    .unwrap();
+
+    let dacpac_path = rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+        project_path,
+        output_path: Some(ctx.project_dir.join("bin").join("Full.dacpac")),
+        profiles_path: Some(profiles_path),
+        ..Default::default()
+    })
+    .expect("Build with profiles should succeed");
+    let full = DacpacInfo::from_dacpac(&dacpac_path).unwrap();
+    assert!(full.tables.iter().any(|t| t.contains("[Sales].[Orders]")));
+
+    let profile = |name: &str| {
+        DacpacInfo::from_dacpac(&ctx.project_dir.join("bin").join(format!("{}.dacpac", name)))
+            .unwrap_or_else(|e| panic!("{} profile dacpac: {}", name, e))
+    };
+    let customer_facing = profile("CustomerFacing");
+    assert!(customer_facing
+        .tables
+        .iter()
+        .any(|t| t.contains("[dbo].[Products]")));
+    assert!(!customer_facing
+        .model_xml_content
+        .unwrap()
+        .contains("[Sales]"));
+
+    let tables_only = profile("TablesOnly");
+    assert_eq!(tables_only.tables, full.tables);
+    assert!(tables_only.views.is_empty());
+
+    let no_procedures = profile("NoProcedures");
+    assert_eq!(no_procedures.views, full.views);
+    assert!(!no_procedures
+        .model_xml_content
+        .unwrap()
+        .contains("SqlProcedure"));
+}
+
+#[test]
+fn test_build_filter_rejects_invalid_syntax() {
+    let result = "drop:schema=dbo".parse::<rust_sqlpackage::model::ObjectFilter>();
+    assert!(result.is_err());
+}
+
+// ============================================================================
+// Build Limit Tests
+// ============================================================================
+
+#[test]
+fn test_build_limits() {
+    let ctx = TestContext::with_fixture("e2e_simple");
+    let build = |options: rust_sqlpackage::BuildOptions| {
+        rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+            project_path: ctx.project_path(),
+            output_path: Some(ctx.project_dir.join("bin").join("limits.dacpac")),
+            ..options
+        })
+    };
+
+    // Generous limits do not get in the way
+    build(rust_sqlpackage::BuildOptions {
+        max_elements: Some(1000),
+        max_file_size: Some(1_000_000),
+        max_statement_length: Some(100_000),
+        ..Default::default()
+    })
+    .expect("Build within limits should succeed");
+
+    let err = build(rust_sqlpackage::BuildOptions {
+        max_elements: Some(3),
+        ..Default::default()
+    })
+    .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("elements, more than the maximum of 3"),
+        "{}",
+        err
+    );
+
+    let err = build(rust_sqlpackage::BuildOptions {
+        max_file_size: Some(10),
+        ..Default::default()
+    })
+    .unwrap_err()
+    .to_string();
+    assert!(err.starts_with("Build limit exceeded: "), "{}", err);
+    assert!(
+        err.contains(".sql is ") && err.contains("more than the maximum file size of 10 bytes"),
+        "{}",
+        err
+    );
+
+    let err = build(rust_sqlpackage::BuildOptions {
+        max_statement_length: Some(20),
+        ..Default::default()
+    })
+    .unwrap_err()
+    .to_string();
+    assert!(
+        err.contains("the statement at line 1 of ")
+            && err.contains("more than the maximum statement length of 20 bytes"),
+        "{}",
+        err
+    );
+}
+
+// ============================================================================
+// Conditional Compilation Tests
+// ============================================================================
+
+fn build_with_variables(ctx: &TestContext, variables: &[(&str, &str)]) -> DacpacInfo {
+    let dacpac_path = rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+        project_path: ctx.project_path(),
+        output_path: Some(ctx.project_dir.join("bin").join("conditional.dacpac")),
+        variables: variables
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect(),
+        ..Default::default()
+    })
+    .expect("Build with variables should succeed");
+    DacpacInfo::from_dacpac(&dacpac_path).expect("Should parse dacpac")
+}
+
+#[test]
+fn test_build_conditionals_use_project_defaults() {
+    let ctx = TestContext::with_fixture("conditional_compilation");
+    let info = build_with_variables(&ctx, &[]);
+    let model_xml = info.model_xml_content.unwrap();
+
+    assert!(info.tables.iter().any(|t| t.contains("[dbo].[Customers]")));
+    assert!(
+        !info.tables.iter().any(|t| t.contains("AuditLog")),
+        "IncludeAudit defaults to 0"
+    );
+    assert!(model_xml.contains("N'Test' AS [Environment]"));
+    assert!(!model_xml.contains("N'Production'"));
+    assert!(info
+        .postdeploy_content
+        .unwrap()
+        .contains("N'Test customer'"));
+}
+
+#[test]
+fn test_build_conditionals_with_variable_overrides() {
+    let ctx = TestContext::with_fixture("conditional_compilation");
+    let info = build_with_variables(
+        &ctx,
+        &[("Environment", "production"), ("IncludeAudit", "true")],
+    );
+    let model_xml = info.model_xml_content.unwrap();
+
+    assert!(info.tables.iter().any(|t| t.contains("[dbo].[AuditLog]")));
+    assert!(model_xml.contains("N'Production' AS [Environment]"));
+    assert!(!model_xml.contains("N'Test'"));
+    let postdeploy = info.postdeploy_content.unwrap();
+    assert!(!postdeploy.contains("INSERT"));
+    assert!(!postdeploy.contains(":IF"));
+}
+
+#[test]
+fn test_build_conditionals_override_ignores_variable_case() {
+    let ctx = TestContext::with_fixture("conditional_compilation");
+    // Spelled differently from the project's Environment and IncludeAudit
+    let info = build_with_variables(
+        &ctx,
+        &[("ENVIRONMENT", "production"), ("includeaudit", "true")],
+    );
+    let model_xml = info.model_xml_content.unwrap();
+
+    assert!(info.tables.iter().any(|t| t.contains("[dbo].[AuditLog]")));
+    assert!(model_xml.contains("N'Production' AS [Environment]"));
+    assert!(!model_xml.contains("N'Test'"));
+}
+
+#[test]
+fn test_build_conditionals_unbalanced_block_fails() {
+    let ctx = TestContext::with_fixture("conditional_compilation");
+    std::fs::write(
+        ctx.project_dir.join("Tables").join("AuditLog.sql"),
+        ":IF $(IncludeAudit)\nCREATE TABLE [dbo].[AuditLog] ([Id] INT NOT NULL);\n",
+    )
+    .unwrap();
+
+    let result = ctx.build();
+    assert!(!result.success);
+    let errors = result.errors.join("\n");
+    assert!(errors.contains(":IF without matching :ENDIF"), "{}", errors);
+}
+
+// ============================================================================
+// Output Mode Tests
+// ============================================================================
+
+#[test]
+fn test_build_relaxed_output_mode_skips_dacfx_details() {
+    let ctx = TestContext::with_fixture("e2e_simple");
+    let parity = DacpacInfo::from_dacpac(&ctx.build_successfully()).expect("Should parse dacpac");
+    let parity_xml = parity.model_xml_content.unwrap();
+
+    let dacpac_path = rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+        project_path: ctx.project_path(),
+        output_path: Some(ctx.project_dir.join("bin").join("relaxed.dacpac")),
+        output_mode: rust_sqlpackage::model::OutputMode::Relaxed,
+        ..Default::default()
+    })
+    .expect("Relaxed build should succeed");
+    let relaxed = DacpacInfo::from_dacpac(&dacpac_path).expect("Should parse dacpac");
+    assert!(relaxed.is_valid());
+    let relaxed_xml = relaxed.model_xml_content.unwrap();
+
+    assert!(parity_xml.contains("SysCommentsObjectAnnotation"));
+    assert!(!relaxed_xml.contains("SysCommentsObjectAnnotation"));
+    assert!(!relaxed_xml.contains(r#"Name="DefaultLanguage""#));
+    assert!(relaxed_xml.len() < parity_xml.len());
+
+    // Objects and their definitions are unchanged
+    assert_eq!(relaxed.tables, parity.tables);
+    assert_eq!(relaxed.views, parity.views);
+    assert!(relaxed_xml.contains(r#"<Property Name="QueryScript">"#));
+    assert!(relaxed_xml.contains(r#"<Property Name="BodyScript">"#));
+}
+
+#[test]
+fn test_build_logical_element_order() {
+    let ctx = TestContext::with_fixture("e2e_simple");
+    let build = |output_mode| {
+        rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+            project_path: ctx.project_path(),
+            output_path: Some(ctx.project_dir.join("bin").join("logical.dacpac")),
+            output_mode,
+            element_order: rust_sqlpackage::model::ElementOrder::Logical,
+            ..Default::default()
+        })
+    };
+
+    let err = build(rust_sqlpackage::model::OutputMode::Parity).unwrap_err();
+    assert!(
+        err.to_string().contains("needs relaxed output mode"),
+        "{}",
+        err
+    );
+
+    let dacpac_path =
+        build(rust_sqlpackage::model::OutputMode::Relaxed).expect("Logical build should succeed");
+    let xml = DacpacInfo::from_dacpac(&dacpac_path)
+        .expect("Should parse dacpac")
+        .model_xml_content
+        .unwrap();
+    let doc = roxmltree::Document::parse(&xml).unwrap();
+    let model = doc.descendants().find(|n| n.has_tag_name("Model")).unwrap();
+    let elements: Vec<(&str, &str)> = model
+        .children()
+        .filter(|n| n.has_tag_name("Element"))
+        .map(|n| {
+            (
+                n.attribute("Type").unwrap(),
+                n.attribute("Name").unwrap_or(""),
+            )
+        })
+        .collect();
+    let position = |name: &str| {
+        elements
+            .iter()
+            .position(|(_, n)| *n == name)
+            .unwrap_or_else(|| panic!("{} not found in {:?}", name, elements))
+    };
+
+    // Database options first, then each schema followed by its objects, each object
+    // followed by the elements defined on it
+    assert_eq!(elements[0].0, "SqlDatabaseOptions");
+    let sales = position("[Sales]");
+    assert!(elements[1..sales]
+        .iter()
+        .all(|(_, n)| n.is_empty() || n.starts_with("[dbo].")));
+    assert!(elements[sales + 1..]
+        .iter()
+        .all(|(_, n)| n.is_empty() || n.starts_with("[Sales].")));
+    let products = position("[dbo].[Products]");
+    let children: Vec<&str> = elements[products + 1..products + 4]
+        .iter()
+        .map(|(element_type, _)| *element_type)
+        .collect();
+    assert_eq!(
+        children,
+        [
+            "SqlForeignKeyConstraint",
+            "SqlIndex",
+            "SqlPrimaryKeyConstraint"
+        ]
+    );
+    assert_eq!(elements[products + 4].1, "[dbo].[ProductTableType]");
+}
+
+// ============================================================================
+// Model Schema Version Tests
+// ============================================================================
+
+fn build_with_schema_version(
+    ctx: &TestContext,
+    version: &str,
+) -> anyhow::Result<std::path::PathBuf> {
+    rust_sqlpackage::build_dacpac(rust_sqlpackage::BuildOptions {
+        project_path: ctx.project_path(),
+        output_path: Some(ctx.project_dir.join("bin").join("versioned.dacpac")),
+        schema_version: Some(version.to_string()),
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_build_with_older_schema_version() {
+    let ctx = TestContext::with_fixture("simple_table");
+    let sqlproj = std::fs::read_to_string(ctx.project_path()).unwrap();
+    std::fs::write(ctx.project_path(), sqlproj.replace("Sql160", "Sql130")).unwrap();
+
+    let dacpac_path = build_with_schema_version(&ctx, "2.4").expect("Build should succeed");
+    let info = DacpacInfo::from_dacpac(&dacpac_path).expect("Should parse dacpac");
+
+    let model_xml = info.model_xml_content.unwrap();
+    assert!(model_xml.contains(r#"FileFormatVersion="1.2" SchemaVersion="2.4""#));
+    assert!(info
+        .origin_xml_content
+        .unwrap()
+        .contains("<ModelSchemaVersion>2.4</ModelSchemaVersion>"));
+}
+
+#[test]
+fn test_build_rejects_schema_version_older_than_target_platform() {
+    let ctx = TestContext::with_fixture("simple_table");
+
+    let err = build_with_schema_version(&ctx, "2.4").unwrap_err();
+    assert!(err.to_string().contains("Sql160"), "{}", err);
+
+    let err = build_with_schema_version(&ctx, "1.0").unwrap_err();
+    assert!(err.to_string().contains("expected one of"), "{}", err);
+}
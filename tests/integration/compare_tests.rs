@@ -17,7 +17,7 @@ fn test_compare_dacpac_to_itself_is_identical() {
     // Verify structural correctness of the result
     assert!(result.header_result.is_some());
     assert!(result.elements_result.is_some());
-    assert!(result.duplicate_warnings.is_empty());
+    assert!(result.duplicates.is_empty());
 
     let elems = result.elements_result.unwrap();
     assert!(elems.missing_in_rust.is_empty());
@@ -87,7 +87,7 @@ fn test_compare_has_differences_returns_correct_value() {
             extra_in_rust: vec![],
             differences: vec![],
         }),
-        duplicate_warnings: vec![],
+        duplicates: vec![],
     };
     assert!(!result.has_differences());
 
@@ -99,7 +99,7 @@ fn test_compare_has_differences_returns_correct_value() {
         )],
         header_result: None,
         elements_result: None,
-        duplicate_warnings: vec![],
+        duplicates: vec![],
     };
     assert!(result.has_differences());
 
@@ -120,7 +120,194 @@ fn test_compare_has_differences_returns_correct_value() {
             extra_in_rust: vec![],
             differences: vec![],
         }),
-        duplicate_warnings: vec![],
+        duplicates: vec![],
     };
     assert!(result.has_differences());
 }
+
+#[test]
+fn test_compare_difference_counts() {
+    use rust_sqlpackage::compare::types::*;
+
+    let key = |name: &str| ElementKey::Named {
+        element_type: "SqlTable".to_string(),
+        name: name.to_string(),
+    };
+    let result = CompareResult {
+        file_results: vec![
+            (
+                "Origin.xml".to_string(),
+                FileStatus::Skipped("test".to_string()),
+            ),
+            (
+                "DacMetadata.xml".to_string(),
+                FileStatus::Different(vec!["diff".to_string()]),
+            ),
+            ("postdeploy.sql".to_string(), FileStatus::MissingInRust),
+        ],
+        header_result: Some(HeaderResult {
+            is_ok: false,
+            diffs: vec!["a".to_string(), "b".to_string()],
+        }),
+        elements_result: Some(ModelElementsResult {
+            total_rust: 2,
+            total_dotnet: 2,
+            missing_in_rust: vec![key("[dbo].[A]")],
+            extra_in_rust: vec![key("[dbo].[B]")],
+            differences: vec![(key("[dbo].[C]"), vec!["diff".to_string()])],
+        }),
+        duplicates: vec![],
+    };
+    // postdeploy.sql, [dbo].[A] and [dbo].[B]
+    assert_eq!(result.element_differences(), 3);
+    // DacMetadata.xml, two header entries and [dbo].[C]
+    assert_eq!(result.property_differences(), 4);
+}
+
+#[test]
+fn test_compare_reports_duplicate_keys() {
+    use rust_sqlpackage::compare::report::{format_duplicates, format_json_report};
+    use rust_sqlpackage::compare::types::*;
+
+    let element = |name: &str, line: u32| DuplicateElement {
+        key: ElementKey::Named {
+            element_type: "SqlTable".to_string(),
+            name: name.to_string(),
+        },
+        line,
+    };
+    let result = CompareResult {
+        file_results: vec![],
+        header_result: None,
+        elements_result: None,
+        duplicates: vec![DuplicateKey {
+            model: "rust".to_string(),
+            elements: vec![element("[dbo].[Users]", 12), element("[dbo].[USERS]", 40)],
+        }],
+    };
+
+    let text = format_duplicates(&result.duplicates);
+    assert!(
+        text.contains("Duplicate keys in rust model.xml (1)"),
+        "{}",
+        text
+    );
+    assert!(
+        text.contains("    line 40: SqlTable [dbo].[USERS]"),
+        "{}",
+        text
+    );
+
+    let json: serde_json::Value = serde_json::from_str(&format_json_report(&result)).unwrap();
+    assert_eq!(json["duplicates"][0]["model"], "rust");
+    assert_eq!(
+        json["duplicates"][0]["elements"][1],
+        serde_json::json!({ "element": "SqlTable [dbo].[USERS]", "line": 40 })
+    );
+    assert_eq!(json["elements"], serde_json::Value::Null);
+    assert_eq!(json["element_differences"], 0);
+}
+
+#[test]
+fn test_diff_builds_reports_object_changes() {
+    let ctx = TestContext::with_fixture("simple_table");
+    let old_dacpac = ctx.build_successfully();
+    let saved = ctx.project_dir.join("old.dacpac");
+    std::fs::copy(&old_dacpac, &saved).unwrap();
+
+    std::fs::write(
+        ctx.project_dir.join("Table1.sql"),
+        "CREATE TABLE [dbo].[Table1] (\n    [c1] INT NOT NULL PRIMARY KEY,\n    [c2] BIGINT NULL,\n    [c3] NVARCHAR(50) NULL\n);\n",
+    )
+    .unwrap();
+    std::fs::write(
+        ctx.project_dir.join("View1.sql"),
+        "CREATE VIEW [dbo].[View1] AS SELECT [c1] FROM [dbo].[Table1];\n",
+    )
+    .unwrap();
+
+    // A dacpac on one side and the changed project on the other
+    let diff = rust_sqlpackage::compare::diff_builds(&saved, &ctx.project_path()).unwrap();
+
+    assert!(!diff.is_empty());
+    assert!(diff.removed.is_empty());
+    assert_eq!(diff.element_differences(), diff.added.len());
+    assert_eq!(diff.property_differences(), diff.changed.len());
+    assert!(diff
+        .added
+        .iter()
+        .any(|key| key.to_string() == "SqlView [dbo].[View1]"));
+
+    let table = diff
+        .changed
+        .iter()
+        .find(|change| change.key.to_string() == "SqlTable [dbo].[Table1]")
+        .expect("Table1 should be reported as changed");
+    assert!(table
+        .added_children
+        .iter()
+        .any(|key| key.to_string().ends_with("[dbo].[Table1].[c3]")));
+    let c2 = table
+        .changed_children
+        .iter()
+        .find(|child| child.key.to_string().ends_with("[dbo].[Table1].[c2]"))
+        .expect("c2 should be reported as changed");
+    let type_change = c2.property("Type").unwrap();
+    assert_eq!(type_change.old.as_deref(), Some("int"));
+    assert_eq!(type_change.new.as_deref(), Some("bigint"));
+
+    let report = rust_sqlpackage::compare::report::format_model_diff(&diff);
+    assert!(report.contains("+ SqlView [dbo].[View1]"), "{}", report);
+    assert!(report.contains("Type: int -> bigint"), "{}", report);
+
+    // Comparing a build to itself finds nothing
+    let diff = rust_sqlpackage::compare::diff_builds(&saved, &saved).unwrap();
+    assert!(diff.is_empty());
+}
+
+#[test]
+fn test_compare_batch_reports_each_pair() {
+    use rust_sqlpackage::compare::batch::{
+        compare_batch, format_batch_report, load_manifest, BatchOptions,
+    };
+
+    let ctx_a = TestContext::with_fixture("simple_table");
+    let path_a = ctx_a.build_successfully();
+    let ctx_b = TestContext::with_fixture("views");
+    let path_b = ctx_b.build_successfully();
+
+    let dir = tempfile::tempdir().unwrap();
+    let manifest = dir.path().join("pairs.json");
+    let pairs = serde_json::json!([
+        { "name": "Same", "rust": path_a, "dotnet": path_a },
+        { "name": "Different", "rust": path_a, "dotnet": path_b },
+        { "name": "Missing", "rust": path_a, "dotnet": "missing.dacpac" },
+    ]);
+    std::fs::write(&manifest, pairs.to_string()).unwrap();
+
+    let report =
+        compare_batch(load_manifest(&manifest).unwrap(), &BatchOptions::default()).unwrap();
+    let names: Vec<&str> = report
+        .outcomes
+        .iter()
+        .map(|o| o.pair.name.as_str())
+        .collect();
+    assert_eq!(names, ["Same", "Different", "Missing"]);
+    assert!(report.outcomes[0].passed);
+    assert!(!report.outcomes[1].passed);
+    assert!(report.outcomes[1].differences > 0);
+    assert!(report.outcomes[2].result.is_err());
+    assert!(!report.is_ok());
+    assert_eq!(report.passed(), 1);
+    assert_eq!(report.errors(), 1);
+
+    let text = format_batch_report(&report);
+    assert!(text.contains("PASS  Same"), "{}", text);
+    assert!(text.contains("FAIL  Different"), "{}", text);
+    assert!(text.contains("ERROR Missing"), "{}", text);
+    assert!(
+        text.contains("1 of 3 passed, 1 failed, 1 could not be compared"),
+        "{}",
+        text
+    );
+}
@@ -0,0 +1,94 @@
+//! Integration tests for merging dacpacs
+
+use rust_sqlpackage::{merge_dacpacs, MergeOptions, SqlPackageError};
+
+use crate::common::{DacpacInfo, TestContext};
+
+#[test]
+fn test_merge_dacpacs() {
+    let constraints = TestContext::with_fixture("inline_constraints");
+    let constraints_dacpac = constraints.build_successfully();
+    let copy = constraints.project_dir.join("copy.dacpac");
+    std::fs::copy(&constraints_dacpac, &copy).unwrap();
+    let deploy = TestContext::with_fixture("pre_post_deploy");
+    let deploy_dacpac = deploy.build_successfully();
+
+    let output = constraints.project_dir.join("merged").join("Merged.dacpac");
+    let summary = merge_dacpacs(&MergeOptions {
+        inputs: vec![constraints_dacpac.clone(), deploy_dacpac.clone(), copy],
+        output_path: output.clone(),
+        name: Some("Merged".to_string()),
+        dry_run: false,
+    })
+    .unwrap();
+
+    let source = DacpacInfo::from_dacpac(&constraints_dacpac).unwrap();
+    let source_model = source.model_xml_content.unwrap();
+    let source_elements = source_model.matches("\n    <Element ").count();
+    let info = DacpacInfo::from_dacpac(&output).unwrap();
+    assert!(info.is_valid());
+    let model = info.model_xml_content.unwrap();
+    let elements = model.matches("\n    <Element ").count();
+    assert_eq!(summary.elements, elements);
+    // The copy's elements, and the database options both builds have, are shared
+    assert_eq!(summary.shared, source_elements + 1);
+    for table in ["[dbo].[Customer]", "[dbo].[Account]", "[dbo].[Table1]"] {
+        assert!(
+            model.contains(&format!(r#"<Element Type="SqlTable" Name="{}""#, table)),
+            "missing {}",
+            table
+        );
+    }
+    assert_eq!(model.matches(r#"Type="SqlDatabaseOptions""#).count(), 1);
+
+    // Every inline constraint keeps a disambiguator of its own
+    let document = roxmltree::Document::parse(&model).unwrap();
+    let mut disambiguators: Vec<&str> = document
+        .descendants()
+        .filter(|n| n.has_tag_name("Annotation"))
+        .filter_map(|n| n.attribute("Disambiguator"))
+        .collect();
+    let count = disambiguators.len();
+    disambiguators.sort_unstable();
+    disambiguators.dedup();
+    assert!(count > 0);
+    assert_eq!(disambiguators.len(), count);
+
+    let metadata = info.metadata_xml_content.unwrap();
+    assert!(metadata.contains("<Name>Merged</Name>"), "{}", metadata);
+    let predeploy = info.predeploy_content.unwrap();
+    assert!(predeploy.starts_with(&format!(
+        "-- From {}",
+        deploy_dacpac.file_name().unwrap().to_string_lossy()
+    )));
+}
+
+#[test]
+fn test_merge_dacpacs_reports_conflicts() {
+    let ctx = TestContext::with_fixture("simple_table");
+    let old_dacpac = ctx.build_successfully();
+    let saved = ctx.project_dir.join("old.dacpac");
+    std::fs::copy(&old_dacpac, &saved).unwrap();
+    std::fs::write(
+        ctx.project_dir.join("Table1.sql"),
+        "CREATE TABLE [dbo].[Table1] (\n    [c1] INT NOT NULL PRIMARY KEY,\n    [c2] BIGINT NULL\n);\n",
+    )
+    .unwrap();
+    let new_dacpac = ctx.build_successfully();
+
+    let output = ctx.project_dir.join("Merged.dacpac");
+    let error = merge_dacpacs(&MergeOptions {
+        inputs: vec![saved, new_dacpac],
+        output_path: output.clone(),
+        ..Default::default()
+    })
+    .unwrap_err();
+
+    let Some(SqlPackageError::MergeConflict { conflicts }) = error.downcast_ref() else {
+        panic!("expected a merge conflict, got {}", error);
+    };
+    assert!(conflicts
+        .iter()
+        .any(|c| c.starts_with("SqlTable [dbo].[Table1] differs between old.dacpac and")));
+    assert!(!output.exists());
+}
@@ -0,0 +1,45 @@
+//! Integration tests for slicing dacpacs
+
+use rust_sqlpackage::{slice_dacpac, SliceOptions};
+
+use crate::common::{DacpacInfo, TestContext};
+
+#[test]
+fn test_slice_dacpac_with_dependencies() {
+    let ctx = TestContext::with_fixture("e2e_comprehensive");
+    let dacpac = ctx.build_successfully();
+
+    let output = ctx.project_dir.join("Slice.dacpac");
+    let summary = slice_dacpac(&SliceOptions {
+        input: dacpac,
+        output_path: output.clone(),
+        filters: vec![
+            "include:name=dbo.ActiveProducts".parse().unwrap(),
+            "exclude:type=ExtendedProperty".parse().unwrap(),
+        ],
+        dry_run: false,
+    })
+    .unwrap();
+
+    // The view reads Products, whose foreign key needs Categories
+    assert_eq!(
+        summary.dependencies,
+        vec!["SqlTable [dbo].[Categories]", "SqlTable [dbo].[Products]"]
+    );
+    let info = DacpacInfo::from_dacpac(&output).unwrap();
+    assert!(info.is_valid());
+    let model = info.model_xml_content.unwrap();
+    assert_eq!(model.matches("\n    <Element ").count(), summary.elements);
+    assert!(summary.elements < summary.total);
+    for element in [
+        r#"Type="SqlView" Name="[dbo].[ActiveProducts]""#,
+        r#"Type="SqlForeignKeyConstraint" Name="[dbo].[FK_Products_Categories]""#,
+        r#"Type="SqlIndex" Name="[dbo].[Products].[IX_Products_CategoryId]""#,
+        r#"Type="SqlDatabaseOptions""#,
+    ] {
+        assert!(model.contains(element), "missing {}", element);
+    }
+    assert!(!model.contains(r#"Name="[dbo].[AuditLog]""#));
+    assert!(!model.contains("SqlExtendedProperty"));
+    assert!(info.predeploy_content.is_none());
+}
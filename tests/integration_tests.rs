@@ -16,3 +16,9 @@ mod dacpac_compatibility_tests;
 
 #[path = "integration/compare_tests.rs"]
 mod compare_tests;
+
+#[path = "integration/merge_tests.rs"]
+mod merge_tests;
+
+#[path = "integration/slice_tests.rs"]
+mod slice_tests;
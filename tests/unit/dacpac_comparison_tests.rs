@@ -27,14 +27,26 @@ fn create_test_project() -> rust_sqlpackage::project::SqlProject {
         dacpac_references: vec![],
         package_references: vec![],
         sqlcmd_variables: vec![],
+        build_variables: Default::default(),
         project_dir: std::path::PathBuf::new(),
         pre_deploy_script: None,
         post_deploy_script: None,
+        resolve_deploy_includes: true,
+        header_descriptions: false,
+        column_descriptions: false,
         ansi_nulls: true,
         quoted_identifier: true,
         database_options: rust_sqlpackage::project::DatabaseOptions::default(),
         dac_version: "1.0.0.0".to_string(),
         dac_description: None,
+        deployment_contributors: vec![],
+        contributor_arguments: vec![],
+        dacpac_profiles: vec![],
+        default_file_encoding: None,
+        objects_per_file: rust_sqlpackage::project::ObjectsPerFile::Multiple,
+        skipped_files: Vec::new(),
+        unrecognized_elements: Vec::new(),
+        warnings: vec![],
     }
 }
 
@@ -259,3 +259,26 @@ CREATE TABLE [dbo].[Products] (
         "Should have DF_Products_Version constraint"
     );
 }
+
+#[test]
+fn test_constraint_scripts_are_taken_verbatim_from_source() {
+    let sql = r#"
+CREATE TABLE [dbo].[W] (
+    [Id] INT NOT NULL DEFAULT ( 0 ),
+    [N] NVARCHAR(5) NULL CONSTRAINT [CK_W_N] CHECK ([N]  <>  N'a''b'),
+    CHECK ([Id]  >  0)
+);
+"#;
+    let model = parse_and_build_model(sql);
+
+    let mut definitions: Vec<String> = model
+        .elements
+        .iter()
+        .filter_map(|e| match e {
+            rust_sqlpackage::model::ModelElement::Constraint(c) => c.definition.clone(),
+            _ => None,
+        })
+        .collect();
+    definitions.sort();
+    assert_eq!(definitions, vec!["( 0 )", "[Id]  >  0", "[N]  <>  N'a''b'"]);
+}
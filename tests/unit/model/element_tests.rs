@@ -1,4 +1,4 @@
-//! ModelElement Method Tests, Schema Deduplication, Index Property Tests, Constraint Detail Tests
+//! ModelElement Method Tests, Typed Accessor Tests, Schema Deduplication, Index Property Tests, Constraint Detail Tests
 
 use super::parse_and_build_model;
 
@@ -392,3 +392,288 @@ fn test_model_element_full_name_schema() {
         full_name
     );
 }
+
+// ============================================================================
+// Trigger Enabled State Tests
+// ============================================================================
+
+fn trigger_enabled(model: &rust_sqlpackage::model::DatabaseModel, name: &str) -> bool {
+    model
+        .elements
+        .iter()
+        .find_map(|e| match e {
+            rust_sqlpackage::model::ModelElement::Trigger(t) if t.name == name => {
+                Some(t.is_enabled)
+            }
+            _ => None,
+        })
+        .expect("Trigger should exist")
+}
+
+#[test]
+fn test_disable_and_enable_trigger_statements() {
+    let sql = r#"
+CREATE TABLE [dbo].[T] ([Id] INT NOT NULL);
+GO
+CREATE TRIGGER [dbo].[TR_A] ON [dbo].[T] AFTER INSERT AS BEGIN SET NOCOUNT ON; END
+GO
+CREATE TRIGGER [dbo].[TR_B] ON [dbo].[T] AFTER DELETE AS BEGIN SET NOCOUNT ON; END
+GO
+CREATE TRIGGER [dbo].[TR_C] ON [dbo].[T] AFTER UPDATE AS BEGIN SET NOCOUNT ON; END
+GO
+DISABLE TRIGGER ALL ON [dbo].[T];
+GO
+ENABLE TRIGGER [TR_B] ON [dbo].[T];
+GO
+ALTER TABLE [dbo].[T] ENABLE TRIGGER [TR_C];
+GO
+DISABLE TRIGGER [TR_Ddl] ON DATABASE;
+"#;
+    let model = parse_and_build_model(sql);
+
+    assert!(!trigger_enabled(&model, "TR_A"));
+    assert!(trigger_enabled(&model, "TR_B"));
+    assert!(trigger_enabled(&model, "TR_C"));
+}
+
+#[test]
+fn test_alter_table_disable_trigger() {
+    let sql = r#"
+CREATE TABLE [dbo].[T] ([Id] INT NOT NULL);
+GO
+CREATE TRIGGER [dbo].[TR_A] ON [dbo].[T] AFTER INSERT AS BEGIN SET NOCOUNT ON; END
+GO
+ALTER TABLE [dbo].[T] DISABLE TRIGGER [TR_A];
+"#;
+    let model = parse_and_build_model(sql);
+
+    assert!(!trigger_enabled(&model, "TR_A"));
+}
+
+#[test]
+fn test_trigger_schema_and_parent_follow_declared_table() {
+    let sql = r#"
+CREATE SCHEMA [Sales];
+GO
+CREATE TABLE [Sales].[Orders] ([Id] INT NOT NULL);
+GO
+CREATE TRIGGER TR_Orders_Audit ON sales.orders AFTER INSERT AS BEGIN SET NOCOUNT ON; END
+"#;
+    let model = parse_and_build_model(sql);
+    let trigger = model
+        .elements
+        .iter()
+        .find_map(|e| match e {
+            rust_sqlpackage::model::ModelElement::Trigger(t) => Some(t),
+            _ => None,
+        })
+        .expect("Trigger should exist");
+
+    assert_eq!(trigger.schema, "Sales");
+    assert_eq!(trigger.parent_schema, "Sales");
+    assert_eq!(trigger.parent_name, "Orders");
+
+    // Sorted by its name in the parent's schema, right after the table
+    let names: Vec<String> = model.elements.iter().map(|e| e.full_name()).collect();
+    let table = names.iter().position(|n| n == "[Sales].[Orders]").unwrap();
+    assert_eq!(names[table + 1], "[Sales].[TR_Orders_Audit]", "{:?}", names);
+}
+
+// ============================================================================
+// CREATE SCHEMA Contained Element Tests
+// ============================================================================
+
+#[test]
+fn test_create_schema_with_contained_elements() {
+    let sql = r#"
+CREATE SCHEMA [sales] AUTHORIZATION [dbo]
+    CREATE TABLE [Orders] ([Id] INT NOT NULL PRIMARY KEY, [Total] DECIMAL(10, 2) NULL)
+    CREATE VIEW [BigOrders] AS SELECT [Id] FROM [sales].[Orders] WHERE [Total] > 100
+    GRANT SELECT ON [sales].[Orders] TO [public];
+"#;
+    let model = parse_and_build_model(sql);
+    let names: Vec<String> = model.elements.iter().map(|e| e.full_name()).collect();
+
+    assert!(names.contains(&"[sales]".to_string()), "{:?}", names);
+    assert!(
+        names.contains(&"[sales].[Orders]".to_string()),
+        "{:?}",
+        names
+    );
+    assert!(
+        names.contains(&"[sales].[BigOrders]".to_string()),
+        "{:?}",
+        names
+    );
+    assert!(model
+        .elements
+        .iter()
+        .any(|e| matches!(e, rust_sqlpackage::model::ModelElement::Permission(_))));
+}
+
+// ============================================================================
+// Inline Index Declaration Tests
+// ============================================================================
+
+#[test]
+fn test_inline_index_declarations_become_index_elements() {
+    let sql = r#"
+CREATE TABLE [Sales].[Orders] (
+    [Id] INT NOT NULL,
+    [CustomerId] INT NOT NULL INDEX [IX_Orders_Customer],
+    [OrderDate] DATE NOT NULL,
+    INDEX [IX_Orders_Date] CLUSTERED ([OrderDate])
+);
+"#;
+    let model = parse_and_build_model(sql);
+
+    let indexes: Vec<&rust_sqlpackage::model::IndexElement> = model
+        .elements
+        .iter()
+        .filter_map(|e| match e {
+            rust_sqlpackage::model::ModelElement::Index(i) => Some(i),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(indexes.len(), 2);
+    for index in &indexes {
+        assert_eq!(index.table_schema, "Sales");
+        assert_eq!(index.table_name, "Orders");
+    }
+    let date_index = indexes.iter().find(|i| i.name == "IX_Orders_Date").unwrap();
+    assert!(date_index.is_clustered);
+
+    let table = model
+        .elements
+        .iter()
+        .find_map(|e| match e {
+            rust_sqlpackage::model::ModelElement::Table(t) => Some(t),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(table.columns.len(), 3);
+}
+
+// ============================================================================
+// Typed Accessor Tests
+// ============================================================================
+
+#[test]
+fn test_database_model_typed_accessors() {
+    let sql = r#"
+CREATE SCHEMA [Sales];
+GO
+CREATE TABLE [Sales].[Orders] (
+    [Id] INT NOT NULL CONSTRAINT [PK_Orders] PRIMARY KEY,
+    [Total] DECIMAL(10, 2) NOT NULL
+);
+GO
+CREATE INDEX [IX_Orders_Total] ON [Sales].[Orders] ([Total]);
+GO
+CREATE VIEW [Sales].[BigOrders] AS SELECT [Id] FROM [Sales].[Orders] WHERE [Total] > 100;
+GO
+CREATE PROCEDURE [dbo].[GetOrders] AS SELECT [Id] FROM [Sales].[Orders];
+GO
+CREATE SEQUENCE [dbo].[OrderNumbers] AS INT START WITH 1 INCREMENT BY 1;
+"#;
+    let model = parse_and_build_model(sql);
+
+    assert!(model.schemas().any(|s| s.name == "Sales"));
+    let tables: Vec<_> = model.tables().map(|t| t.name.as_str()).collect();
+    assert_eq!(tables, ["Orders"]);
+    assert_eq!(model.views().count(), 1);
+    assert_eq!(model.procedures().next().unwrap().name, "GetOrders");
+    assert_eq!(model.functions().count(), 0);
+    assert_eq!(model.indexes().next().unwrap().name, "IX_Orders_Total");
+    assert_eq!(model.constraints().count(), 1);
+    assert_eq!(model.sequences().count(), 1);
+
+    let view = model.find("sales", "bigorders").unwrap();
+    assert_eq!(view.type_name(), "SqlView");
+    assert_eq!(view.schema_and_name(), Some(("Sales", "BigOrders")));
+    assert!(matches!(
+        model.find("dbo", "OrderNumbers"),
+        Some(rust_sqlpackage::model::ModelElement::Sequence(_))
+    ));
+    assert!(model.find("dbo", "Orders").is_none());
+    // Indexes are not schema-scoped objects
+    assert!(model.find("Sales", "IX_Orders_Total").is_none());
+}
+
+#[test]
+fn test_database_model_element_sources() {
+    let sql = "CREATE TABLE [Sales].[Orders] (\n    [Id] INT NOT NULL CONSTRAINT [PK_Orders] PRIMARY KEY\n);\nGO\n\nCREATE VIEW [Sales].[OrderIds] AS SELECT [Id] FROM [Sales].[Orders];\n";
+    let model = parse_and_build_model(sql);
+
+    let source = |name: &str| {
+        let index = model
+            .elements
+            .iter()
+            .position(|e| e.schema_and_name().is_some_and(|(_, n)| n == name))
+            .unwrap();
+        model.source(index).cloned()
+    };
+    let table = source("Orders").unwrap();
+    assert_eq!((table.start_line, table.end_line), (1, 3));
+    assert_eq!(source("PK_Orders"), Some(table.clone()));
+    let view = source("OrderIds").unwrap();
+    assert_eq!((view.start_line, view.end_line), (6, 6));
+    assert_eq!(view.file, table.file);
+    assert!(view.to_string().ends_with(".sql:6"), "{}", view);
+
+    // Schemas only referenced by objects were not defined anywhere
+    let schema = model
+        .elements
+        .iter()
+        .position(
+            |e| matches!(e, rust_sqlpackage::model::ModelElement::Schema(s) if s.name == "Sales"),
+        )
+        .unwrap();
+    assert_eq!(model.source(schema), None);
+}
+
+#[test]
+fn test_database_model_sort_elements() {
+    use rust_sqlpackage::model::ElementOrder;
+
+    let sql = "CREATE TABLE [Sales].[Orders] ([Id] INT NOT NULL CONSTRAINT [Key_Orders] PRIMARY KEY);\nGO\nCREATE VIEW [dbo].[Totals] AS SELECT 1 AS [N];\nGO\nCREATE TABLE [dbo].[Audit] ([Id] INT NOT NULL);\n";
+    let mut model = parse_and_build_model(sql);
+    let names = |model: &rust_sqlpackage::model::DatabaseModel| -> Vec<String> {
+        (0..model.elements.len())
+            .map(|i| model.full_name(i).to_string())
+            .collect()
+    };
+
+    model.sort_elements(ElementOrder::Logical);
+    assert_eq!(
+        names(&model),
+        [
+            "[dbo]",
+            "[dbo].[Audit]",
+            "[dbo].[Totals]",
+            "[Sales]",
+            "[Sales].[Orders]",
+            "[Sales].[Key_Orders]"
+        ]
+    );
+    let orders = names(&model)
+        .iter()
+        .position(|n| n == "[Sales].[Orders]")
+        .unwrap();
+    assert!(model.source(orders).is_some());
+
+    // A custom comparer; cached names and sources move with their elements
+    model.sort_elements_by(|a, b| {
+        let key = |e: &rust_sqlpackage::model::ModelElement| e.full_name().to_lowercase();
+        key(b).cmp(&key(a))
+    });
+    assert_eq!(names(&model)[0], "[Sales].[Orders]");
+    assert_eq!(model.source(0).unwrap().start_line, 1);
+
+    // DacFx order puts the constraint before its table
+    model.sort_elements(ElementOrder::DotNet);
+    assert_eq!(
+        names(&model)[3..],
+        ["[Sales]", "[Sales].[Key_Orders]", "[Sales].[Orders]"]
+    );
+}
@@ -564,3 +564,36 @@ END
         "Definition should preserve EXECUTE AS OWNER"
     );
 }
+
+#[test]
+fn test_build_model_skips_numbered_procedure_group_members() {
+    let sql = r#"
+CREATE PROCEDURE [dbo].[GetOrders];1
+    @Id INT;
+AS
+    SELECT @Id;
+GO
+CREATE PROCEDURE [dbo].[GetOrders];2
+    @Id INT
+AS
+    SELECT @Id;
+"#;
+    let model = parse_and_build_model(sql);
+
+    let procs: Vec<_> = model
+        .elements
+        .iter()
+        .filter_map(|e| match e {
+            rust_sqlpackage::model::ModelElement::Procedure(p) => Some(p),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        procs.len(),
+        1,
+        "Only the first procedure of the group is kept"
+    );
+    assert_eq!(procs[0].name, "GetOrders");
+    assert_eq!(model.warnings.len(), 1);
+    assert!(model.warnings[0].contains("[dbo].[GetOrders];2"));
+}
@@ -0,0 +1,170 @@
+//! Model Validation Tests
+
+use super::{create_sql_file, create_test_project, parse_and_build_model};
+
+// ============================================================================
+// Foreign Key Validation Tests
+// ============================================================================
+
+#[test]
+fn test_valid_foreign_keys_have_no_warnings() {
+    let sql = r#"
+CREATE TABLE [dbo].[Parent] (
+    [Id] INT NOT NULL PRIMARY KEY,
+    [Code] NVARCHAR(20) NOT NULL,
+    CONSTRAINT [UQ_Parent_Code] UNIQUE ([Code])
+);
+GO
+CREATE TABLE [dbo].[Child] (
+    [Id] INT NOT NULL PRIMARY KEY,
+    [ParentId] int NOT NULL,
+    [ParentCode] nvarchar (20) NULL,
+    CONSTRAINT [FK_Child_Parent] FOREIGN KEY ([ParentId]) REFERENCES [dbo].[Parent]([Id]),
+    CONSTRAINT [FK_Child_Code] FOREIGN KEY ([ParentCode]) REFERENCES [dbo].[Parent]([Code]),
+    CONSTRAINT [FK_Child_External] FOREIGN KEY ([ParentId]) REFERENCES [ext].[Other]([Id])
+);
+"#;
+    let model = parse_and_build_model(sql);
+    assert!(model.warnings.is_empty(), "{:?}", model.warnings);
+}
+
+#[test]
+fn test_foreign_key_type_mismatch_warns() {
+    let sql = r#"
+CREATE TABLE [dbo].[Parent] ([Id] INT NOT NULL PRIMARY KEY);
+GO
+CREATE TABLE [dbo].[Child] (
+    [ParentId] BIGINT NOT NULL,
+    CONSTRAINT [FK_Child_Parent] FOREIGN KEY ([ParentId]) REFERENCES [dbo].[Parent]([Id])
+);
+"#;
+    let model = parse_and_build_model(sql);
+    assert_eq!(model.warnings.len(), 1, "{:?}", model.warnings);
+    assert!(model.warnings[0].starts_with("Foreign key [dbo].[FK_Child_Parent] on [dbo].[Child]"));
+    assert!(
+        model.warnings[0].contains(
+            "column [ParentId] is BIGINT but references [dbo].[Parent].[Id], which is INT"
+        ),
+        "{}",
+        model.warnings[0]
+    );
+}
+
+#[test]
+fn test_foreign_key_to_non_key_columns_warns() {
+    let sql = r#"
+CREATE TABLE [dbo].[Parent] (
+    [Id] INT NOT NULL PRIMARY KEY,
+    [Code] NVARCHAR(20) NOT NULL,
+    [Region] INT NOT NULL
+);
+GO
+CREATE UNIQUE INDEX [IX_Parent_Region] ON [dbo].[Parent] ([Region]) WHERE [Region] > 0;
+GO
+CREATE TABLE [dbo].[Child] (
+    [ParentCode] NVARCHAR(20) NOT NULL,
+    [Region] INT NOT NULL,
+    CONSTRAINT [FK_Child_Code] FOREIGN KEY ([ParentCode]) REFERENCES [dbo].[Parent]([Code]),
+    CONSTRAINT [FK_Child_Region] FOREIGN KEY ([Region]) REFERENCES [dbo].[Parent]([Region])
+);
+"#;
+    let model = parse_and_build_model(sql);
+    assert_eq!(model.warnings.len(), 2, "{:?}", model.warnings);
+    assert!(model.warnings[0].contains(
+        "([Code]) of [dbo].[Parent] is not a primary key, unique constraint or unique index"
+    ));
+    // A filtered unique index can't be referenced
+    assert!(model.warnings[1].contains("([Region]) of [dbo].[Parent] is not a primary key"));
+}
+
+#[test]
+fn test_foreign_key_column_count_mismatch_warns() {
+    let sql = r#"
+CREATE TABLE [dbo].[Parent] (
+    [Id] INT NOT NULL,
+    [Version] INT NOT NULL,
+    CONSTRAINT [PK_Parent] PRIMARY KEY ([Id], [Version])
+);
+GO
+CREATE TABLE [dbo].[Child] (
+    [ParentId] INT NOT NULL,
+    CONSTRAINT [FK_Child_Parent] FOREIGN KEY ([ParentId]) REFERENCES [dbo].[Parent]([Id], [Version])
+);
+"#;
+    let model = parse_and_build_model(sql);
+    assert_eq!(model.warnings.len(), 1, "{:?}", model.warnings);
+    assert!(model.warnings[0].contains("1 column(s) reference 2 column(s) of [dbo].[Parent]"));
+}
+
+// ============================================================================
+// Column Reference Validation Tests
+// ============================================================================
+
+fn build_model_error(sql: &str) -> String {
+    let file = create_sql_file(sql);
+    let statements = rust_sqlpackage::parser::parse_sql_file(file.path()).unwrap();
+    rust_sqlpackage::model::build_model(&statements, &create_test_project())
+        .unwrap_err()
+        .to_string()
+}
+
+#[test]
+fn test_index_on_missing_column_fails() {
+    let error = build_model_error(
+        r#"
+CREATE TABLE [dbo].[Users] ([Id] INT NOT NULL PRIMARY KEY, [Email] NVARCHAR(200) NOT NULL);
+GO
+CREATE INDEX [IX_Users_Email] ON [dbo].[Users] ([Emial]) INCLUDE ([Id], [Name]);
+"#,
+    );
+    assert!(error.starts_with("Model validation failed:"), "{}", error);
+    assert!(
+        error.contains(
+            "Index [dbo].[Users].[IX_Users_Email] references column [Emial], which [dbo].[Users] does not have"
+        ),
+        "{}",
+        error
+    );
+    assert!(error.contains("references column [Name]"), "{}", error);
+    assert!(!error.contains("column [Id]"), "{}", error);
+}
+
+#[test]
+fn test_constraint_on_missing_column_fails() {
+    let error = build_model_error(
+        r#"
+CREATE TABLE [dbo].[Parent] ([Id] INT NOT NULL PRIMARY KEY);
+GO
+CREATE TABLE [dbo].[Child] (
+    [Id] INT NOT NULL,
+    [ParentId] INT NOT NULL,
+    CONSTRAINT [PK_Child] PRIMARY KEY ([ChildId]),
+    CONSTRAINT [FK_Child_Parent] FOREIGN KEY ([ParentId]) REFERENCES [dbo].[Parent]([ParentId])
+);
+"#,
+    );
+    assert!(
+        error.contains("Primary key [dbo].[PK_Child] references column [ChildId], which [dbo].[Child] does not have"),
+        "{}",
+        error
+    );
+    assert!(
+        error.contains("Foreign key [dbo].[FK_Child_Parent] references column [ParentId], which [dbo].[Parent] does not have"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn test_column_references_checked_ignoring_case() {
+    // Names match ignoring case, and tables outside the project aren't checked
+    let sql = r#"
+CREATE TABLE [dbo].[Users] ([Id] INT NOT NULL, [Email] NVARCHAR(200) NOT NULL);
+GO
+CREATE UNIQUE INDEX [IX_Users_Email] ON [dbo].[Users] ([EMAIL]);
+GO
+CREATE INDEX [IX_Other] ON [ext].[Other] ([Missing]);
+"#;
+    let model = parse_and_build_model(sql);
+    assert!(model.warnings.is_empty(), "{:?}", model.warnings);
+}
@@ -225,6 +225,54 @@ CREATE TABLE t2 (id INT)
     assert_eq!(statements.len(), 2);
 }
 
+#[test]
+fn test_statement_line_ranges() {
+    let sql = "-- Orders\n\nCREATE TABLE t1 (\n    id INT\n)\nGO\n\nCREATE VIEW v1 AS SELECT id FROM t1\nGO\n";
+    let file = create_sql_file(sql);
+
+    let statements = rust_sqlpackage::parser::parse_sql_file(file.path()).unwrap();
+    assert_eq!(statements.len(), 2);
+    // Leading comments belong to the batch; blank lines do not
+    assert_eq!(statements[0].line_range(), (1, 5));
+    assert_eq!(statements[1].line_range(), (8, 8));
+}
+
+// ============================================================================
+// File Encoding Tests
+// ============================================================================
+
+#[test]
+fn test_parse_utf16_file_with_bom() {
+    // SSMS "Save as Unicode" writes UTF-16 LE with a BOM
+    let sql =
+        "CREATE TABLE t1 (name NVARCHAR(10) DEFAULT N'Zoë')\r\nGO\r\nCREATE TABLE t2 (id INT)";
+    let mut file = NamedTempFile::with_suffix(".sql").unwrap();
+    file.write_all(&[0xFF, 0xFE]).unwrap();
+    for unit in sql.encode_utf16() {
+        file.write_all(&unit.to_le_bytes()).unwrap();
+    }
+    file.flush().unwrap();
+
+    let statements =
+        rust_sqlpackage::parser::parse_sql_file(file.path()).expect("UTF-16 file should parse");
+    assert_eq!(statements.len(), 2);
+    assert!(statements[0].sql_text.contains("N'Zoë'"));
+}
+
+#[test]
+fn test_parse_windows_1252_file() {
+    // 0xE9 is "é" in Windows-1252 and not valid UTF-8 on its own
+    let mut file = NamedTempFile::with_suffix(".sql").unwrap();
+    file.write_all(b"CREATE TABLE t1 (name VARCHAR(10) DEFAULT 'caf\xE9')")
+        .unwrap();
+    file.flush().unwrap();
+
+    let statements = rust_sqlpackage::parser::parse_sql_file(file.path())
+        .expect("Windows-1252 file should parse");
+    assert_eq!(statements.len(), 1);
+    assert!(statements[0].sql_text.contains("'café'"));
+}
+
 // ============================================================================
 // Error Handling Tests
 // ============================================================================
@@ -238,6 +286,29 @@ fn test_parse_invalid_sql_returns_error() {
     assert!(result.is_err(), "Invalid SQL should return error");
 }
 
+#[test]
+fn test_parse_imperative_statements_suggest_alternative() {
+    let sql =
+        "CREATE TABLE [dbo].[T] ([Id] INT);\nGO\nEXEC sp_rename 'dbo.T.Id', 'Key', 'COLUMN';\n";
+    let file = create_sql_file(sql);
+
+    let error = rust_sqlpackage::parser::parse_sql_file(file.path())
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("sp_rename"), "Got: {}", error);
+    assert!(error.contains("at line 3"), "Got: {}", error);
+    assert!(error.contains("refactor log"), "Got: {}", error);
+
+    let sql = "ALTER DATABASE CURRENT SET RECOVERY SIMPLE;";
+    let file = create_sql_file(sql);
+
+    let error = rust_sqlpackage::parser::parse_sql_file(file.path())
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("ALTER DATABASE"), "Got: {}", error);
+    assert!(error.contains("project properties"), "Got: {}", error);
+}
+
 #[test]
 fn test_parse_file_not_found_error() {
     let result =
@@ -40,8 +40,9 @@ END
     assert_eq!(statements.len(), 1);
 
     // Either sqlparser parsed it or fallback did
-    if let Some(rust_sqlpackage::parser::FallbackStatementType::Procedure { schema, name }) =
-        &statements[0].fallback_type
+    if let Some(rust_sqlpackage::parser::FallbackStatementType::Procedure {
+        schema, name, ..
+    }) = &statements[0].fallback_type
     {
         assert_eq!(schema, "dbo");
         assert_eq!(name, "GetUsers");
@@ -80,7 +81,9 @@ END
 
     // T-SQL @param syntax requires fallback parsing
     match &statements[0].fallback_type {
-        Some(rust_sqlpackage::parser::FallbackStatementType::Procedure { schema, name }) => {
+        Some(rust_sqlpackage::parser::FallbackStatementType::Procedure {
+            schema, name, ..
+        }) => {
             assert_eq!(schema, "dbo");
             assert_eq!(name, "GetUserById");
         }
@@ -114,7 +117,9 @@ END
 
     let statements = result.unwrap();
     match &statements[0].fallback_type {
-        Some(rust_sqlpackage::parser::FallbackStatementType::Procedure { schema, name }) => {
+        Some(rust_sqlpackage::parser::FallbackStatementType::Procedure {
+            schema, name, ..
+        }) => {
             assert_eq!(schema, "sales");
             assert_eq!(name, "UpdateOrder");
         }
@@ -143,7 +148,9 @@ END
 
     let statements = result.unwrap();
     match &statements[0].fallback_type {
-        Some(rust_sqlpackage::parser::FallbackStatementType::Procedure { schema, name }) => {
+        Some(rust_sqlpackage::parser::FallbackStatementType::Procedure {
+            schema, name, ..
+        }) => {
             assert_eq!(schema, "dbo");
             assert_eq!(name, "QuickProc");
         }
@@ -172,8 +179,9 @@ END
 
     let statements = result.unwrap();
     // Check either fallback or sqlparser parsing
-    if let Some(rust_sqlpackage::parser::FallbackStatementType::Procedure { schema, name }) =
-        &statements[0].fallback_type
+    if let Some(rust_sqlpackage::parser::FallbackStatementType::Procedure {
+        schema, name, ..
+    }) = &statements[0].fallback_type
     {
         assert_eq!(schema, "dbo", "Should default to dbo schema");
         assert_eq!(name, "SimpleProc");
@@ -215,7 +223,9 @@ END
 
     // Verify the procedure is parsed with fallback
     match &statements[0].fallback_type {
-        Some(rust_sqlpackage::parser::FallbackStatementType::Procedure { schema, name }) => {
+        Some(rust_sqlpackage::parser::FallbackStatementType::Procedure {
+            schema, name, ..
+        }) => {
             assert_eq!(schema, "dbo");
             assert_eq!(name, "GetNextId");
         }
@@ -372,7 +382,9 @@ END
 
     let statements = result.unwrap();
     match &statements[0].fallback_type {
-        Some(rust_sqlpackage::parser::FallbackStatementType::Procedure { schema, name }) => {
+        Some(rust_sqlpackage::parser::FallbackStatementType::Procedure {
+            schema, name, ..
+        }) => {
             assert_eq!(schema, "sales");
             assert_eq!(name, "CalculateOrderTotal");
         }
@@ -1347,3 +1347,46 @@ CREATE TABLE [dbo].[RegularTable] (
         "Non-temporal table should not contain GENERATED ALWAYS"
     );
 }
+
+#[test]
+fn test_parse_table_with_inline_indexes() {
+    let sql = r#"
+CREATE TABLE [dbo].[Orders] (
+    [Id] INT NOT NULL,
+    [CustomerId] INT NOT NULL INDEX [IX_Orders_Customer] NONCLUSTERED,
+    [OrderDate] DATE NOT NULL,
+    CONSTRAINT [PK_Orders] PRIMARY KEY CLUSTERED ([Id]),
+    INDEX [IX_Orders_Date] NONCLUSTERED ([OrderDate] DESC, [CustomerId]),
+    INDEX [IX_Orders_Unique] UNIQUE ([CustomerId], [OrderDate]) INCLUDE ([Id])
+);
+"#;
+    let file = create_sql_file(sql);
+    let parsed = rust_sqlpackage::parser::parse_sql_file(file.path()).unwrap();
+    assert_eq!(parsed.len(), 1);
+
+    let Some(FallbackStatementType::Table {
+        columns,
+        constraints,
+        indexes,
+        ..
+    }) = &parsed[0].fallback_type
+    else {
+        panic!(
+            "Expected FallbackStatementType::Table, got {:?}",
+            &parsed[0].fallback_type
+        );
+    };
+
+    let column_names: Vec<&str> = columns.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(column_names, vec!["Id", "CustomerId", "OrderDate"]);
+    assert_eq!(constraints.len(), 1);
+
+    assert_eq!(indexes.len(), 3);
+    assert_eq!(indexes[0].name, "IX_Orders_Customer");
+    assert_eq!(indexes[0].columns[0].name, "CustomerId");
+    assert_eq!(indexes[1].name, "IX_Orders_Date");
+    assert!(indexes[1].columns[0].is_descending);
+    assert_eq!(indexes[2].name, "IX_Orders_Unique");
+    assert!(indexes[2].is_unique);
+    assert_eq!(indexes[2].include_columns, vec!["Id"]);
+}
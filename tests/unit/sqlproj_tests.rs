@@ -311,6 +311,61 @@ fn test_find_sql_files_sdk_style_globbing() {
     );
 }
 
+#[test]
+fn test_none_and_removed_items_are_skipped() {
+    let content = r#"<?xml version="1.0" encoding="utf-8"?>
+<Project DefaultTargets="Build" xmlns="http://schemas.microsoft.com/developer/msbuild/2003">
+  <PropertyGroup>
+    <Name>TestProject</Name>
+    <DSP>Microsoft.Data.Tools.Schema.Sql.Sql160DatabaseSchemaProvider</DSP>
+  </PropertyGroup>
+  <ItemGroup>
+    <Build Remove="Scratch/*.sql" />
+    <None Include="Scripts\SeedData.sql" />
+    <NotInBuild Include="Old.sql" />
+  </ItemGroup>
+</Project>"#;
+
+    let temp_dir = create_test_project(
+        content,
+        &[
+            ("Table1.sql", "CREATE TABLE t1 (id INT)"),
+            ("Scratch/Try.sql", "SELECT 1"),
+            ("Scripts/SeedData.sql", "INSERT INTO t1 VALUES (1)"),
+            ("Old.sql", "CREATE TABLE old (id INT)"),
+        ],
+    );
+    let project =
+        rust_sqlpackage::project::parse_sqlproj(&temp_dir.path().join("project.sqlproj")).unwrap();
+
+    let built: Vec<_> = project
+        .sql_files
+        .iter()
+        .map(|f| f.strip_prefix(temp_dir.path()).unwrap().to_path_buf())
+        .collect();
+    assert_eq!(built, vec![PathBuf::from("Table1.sql")]);
+
+    let mut skipped: Vec<_> = project
+        .skipped_files
+        .iter()
+        .map(|s| {
+            (
+                s.path.strip_prefix(temp_dir.path()).unwrap().to_path_buf(),
+                s.item,
+            )
+        })
+        .collect();
+    skipped.sort();
+    assert_eq!(
+        skipped,
+        vec![
+            (PathBuf::from("Old.sql"), "NotInBuild"),
+            (PathBuf::from("Scratch/Try.sql"), "Build Remove"),
+            (PathBuf::from("Scripts/SeedData.sql"), "None"),
+        ]
+    );
+}
+
 #[test]
 fn test_exclude_bin_obj_directories() {
     let content = r#"<?xml version="1.0" encoding="utf-8"?>
@@ -377,6 +432,146 @@ fn test_none_include_excludes_file() {
     assert!(project.sql_files[0].to_string_lossy().contains("Table1"));
 }
 
+#[test]
+fn test_build_item_condition_on_target_platform() {
+    let content = r#"<?xml version="1.0" encoding="utf-8"?>
+<Project DefaultTargets="Build" xmlns="http://schemas.microsoft.com/developer/msbuild/2003">
+  <PropertyGroup>
+    <Name>TestProject</Name>
+    <DSP>Microsoft.Data.Tools.Schema.Sql.Sql130DatabaseSchemaProvider</DSP>
+    <Environment>Azure</Environment>
+  </PropertyGroup>
+  <ItemGroup>
+    <Build Include="Table1.sql" />
+    <Build Include="Sql160Only.sql" Condition="'$(TargetPlatform)' == 'Sql160'" />
+  </ItemGroup>
+  <ItemGroup Condition=" '$(Environment)' == 'Azure' ">
+    <Build Include="AzureOnly.sql" />
+  </ItemGroup>
+</Project>"#;
+
+    let temp_dir = create_test_project(
+        content,
+        &[
+            ("Table1.sql", "CREATE TABLE t1 (id INT)"),
+            ("Sql160Only.sql", "CREATE TABLE t2 (id INT)"),
+            ("AzureOnly.sql", "CREATE TABLE t3 (id INT)"),
+        ],
+    );
+    let sqlproj_path = temp_dir.path().join("project.sqlproj");
+
+    let project = rust_sqlpackage::project::parse_sqlproj(&sqlproj_path).unwrap();
+    let names: Vec<String> = project
+        .sql_files
+        .iter()
+        .map(|f| f.file_name().unwrap().to_string_lossy().to_string())
+        .collect();
+    assert_eq!(names, vec!["Table1.sql", "AzureOnly.sql"]);
+    assert!(project.warnings.is_empty());
+}
+
+#[test]
+fn test_build_item_condition_on_command_line_property() {
+    let content = r#"<?xml version="1.0" encoding="utf-8"?>
+<Project DefaultTargets="Build">
+  <Sdk Name="Microsoft.Build.Sql" Version="2.0.0" />
+  <PropertyGroup>
+    <Name>TestProject</Name>
+    <DSP>Microsoft.Data.Tools.Schema.Sql.Sql160DatabaseSchemaProvider</DSP>
+    <TargetEnv Condition="'$(TargetEnv)' == ''">OnPrem</TargetEnv>
+  </PropertyGroup>
+  <ItemGroup>
+    <Build Include="Table1.sql" />
+    <Build Include="AzureOnly\*.sql" Condition="'$(TargetEnv)'=='Azure'" />
+    <Build Include="OnPremOnly\*.sql" Condition="'$(TargetEnv)'=='OnPrem'" />
+  </ItemGroup>
+</Project>"#;
+
+    let temp_dir = create_test_project(
+        content,
+        &[
+            ("Table1.sql", "CREATE TABLE t1 (id INT)"),
+            ("AzureOnly/Azure1.sql", "CREATE TABLE t2 (id INT)"),
+            ("OnPremOnly/OnPrem1.sql", "CREATE TABLE t3 (id INT)"),
+        ],
+    );
+    let sqlproj_path = temp_dir.path().join("project.sqlproj");
+    let names = |properties: &[(String, String)]| -> Vec<String> {
+        rust_sqlpackage::project::parse_sqlproj_with_properties(&sqlproj_path, properties)
+            .unwrap()
+            .sql_files
+            .iter()
+            .map(|f| f.file_name().unwrap().to_string_lossy().to_string())
+            .collect()
+    };
+
+    assert_eq!(names(&[]), vec!["Table1.sql", "OnPrem1.sql"]);
+    assert_eq!(
+        names(&[("targetenv".to_string(), "Azure".to_string())]),
+        vec!["Table1.sql", "Azure1.sql"]
+    );
+}
+
+#[test]
+fn test_unsupported_build_condition_warns_and_includes() {
+    let content = r#"<?xml version="1.0" encoding="utf-8"?>
+<Project DefaultTargets="Build" xmlns="http://schemas.microsoft.com/developer/msbuild/2003">
+  <PropertyGroup>
+    <Name>TestProject</Name>
+    <DSP>Microsoft.Data.Tools.Schema.Sql.Sql160DatabaseSchemaProvider</DSP>
+  </PropertyGroup>
+  <ItemGroup>
+    <Build Include="Table1.sql" Condition="Exists('Table1.sql')" />
+  </ItemGroup>
+</Project>"#;
+
+    let temp_dir = create_test_project(content, &[("Table1.sql", "CREATE TABLE t1 (id INT)")]);
+    let sqlproj_path = temp_dir.path().join("project.sqlproj");
+
+    let project = rust_sqlpackage::project::parse_sqlproj(&sqlproj_path).unwrap();
+    assert_eq!(project.sql_files.len(), 1);
+    assert_eq!(project.warnings.len(), 1);
+    assert!(project.warnings[0].contains("Unsupported Condition"));
+}
+
+#[test]
+fn test_exclude_from_pragma_skips_file_for_platform() {
+    let content = r#"<?xml version="1.0" encoding="utf-8"?>
+<Project DefaultTargets="Build">
+  <Sdk Name="Microsoft.Build.Sql" Version="2.0.0" />
+  <PropertyGroup>
+    <Name>TestProject</Name>
+    <DSP>Microsoft.Data.Tools.Schema.Sql.Sql130DatabaseSchemaProvider</DSP>
+  </PropertyGroup>
+</Project>"#;
+
+    let temp_dir = create_test_project(
+        content,
+        &[
+            ("Table1.sql", "CREATE TABLE t1 (id INT)"),
+            (
+                "External.sql",
+                "-- Azure-only external table\n-- @build:exclude-from(Sql130, Sql140)\nCREATE TABLE t2 (id INT)",
+            ),
+            (
+                "Late.sql",
+                "CREATE TABLE t3 (id INT)\n-- @build:exclude-from(Sql130)",
+            ),
+        ],
+    );
+    let sqlproj_path = temp_dir.path().join("project.sqlproj");
+
+    let project = rust_sqlpackage::project::parse_sqlproj(&sqlproj_path).unwrap();
+    let mut names: Vec<String> = project
+        .sql_files
+        .iter()
+        .map(|f| f.file_name().unwrap().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    // Pragmas only count in the leading comment block
+    assert_eq!(names, vec!["Late.sql", "Table1.sql"]);
+}
+
 // ============================================================================
 // Dacpac Reference Tests
 // ============================================================================
@@ -758,6 +953,85 @@ fn test_windows_path_backslash_conversion() {
     assert!(project.sql_files[0].exists());
 }
 
+#[test]
+fn test_build_item_case_differs_from_disk() {
+    // Includes written on Windows may not match the casing of the files on disk
+    let content = r#"<?xml version="1.0" encoding="utf-8"?>
+<Project DefaultTargets="Build" xmlns="http://schemas.microsoft.com/developer/msbuild/2003">
+  <PropertyGroup>
+    <Name>TestProject</Name>
+    <DSP>Microsoft.Data.Tools.Schema.Sql.Sql160DatabaseSchemaProvider</DSP>
+  </PropertyGroup>
+  <ItemGroup>
+    <Build Include="tables\customers.sql" />
+    <Build Include="Tables\Orders.sql" />
+    <Build Include="Tables\Missing.sql" />
+    <Build Remove="TABLES\ORDERS.SQL" />
+    <PostDeploy Include="scripts\seed.SQL" />
+  </ItemGroup>
+</Project>"#;
+
+    let temp_dir = create_test_project(
+        content,
+        &[
+            ("Tables/Customers.sql", "CREATE TABLE c (id INT)"),
+            ("Tables/Orders.sql", "CREATE TABLE o (id INT)"),
+            ("Scripts/Seed.sql", "PRINT 'seed'"),
+        ],
+    );
+    let sqlproj_path = temp_dir.path().join("project.sqlproj");
+
+    let project = rust_sqlpackage::project::parse_sqlproj(&sqlproj_path).unwrap();
+    assert_eq!(
+        project.sql_files,
+        vec![temp_dir.path().join("Tables/Customers.sql")]
+    );
+    assert_eq!(
+        project.post_deploy_script,
+        Some(temp_dir.path().join("Scripts/Seed.sql"))
+    );
+    assert_eq!(project.warnings.len(), 2, "{:?}", project.warnings);
+    assert!(project.warnings[0].contains("'tables/customers.sql'"));
+    assert!(project.warnings[0].contains("only when ignoring case"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_symlinked_build_items_built_once() {
+    let content = r#"<?xml version="1.0" encoding="utf-8"?>
+<Project DefaultTargets="Build" xmlns="http://schemas.microsoft.com/developer/msbuild/2003">
+  <PropertyGroup>
+    <Name>TestProject</Name>
+    <DSP>Microsoft.Data.Tools.Schema.Sql.Sql160DatabaseSchemaProvider</DSP>
+  </PropertyGroup>
+  <ItemGroup>
+    <Build Include="Tables\*.sql" />
+    <Build Include="Shared\*.sql" />
+    <Build Include="Tables\Customers.sql" />
+  </ItemGroup>
+</Project>"#;
+
+    let temp_dir = create_test_project(
+        content,
+        &[("Tables/Customers.sql", "CREATE TABLE c (id INT)")],
+    );
+    std::os::unix::fs::symlink(
+        temp_dir.path().join("Tables"),
+        temp_dir.path().join("Shared"),
+    )
+    .unwrap();
+    let sqlproj_path = temp_dir.path().join("project.sqlproj");
+
+    let project = rust_sqlpackage::project::parse_sqlproj(&sqlproj_path).unwrap();
+    assert_eq!(
+        project.sql_files,
+        vec![temp_dir.path().join("Tables/Customers.sql")]
+    );
+    // Only the symlinked path is worth a warning, not the repeated include
+    assert_eq!(project.warnings.len(), 1, "{:?}", project.warnings);
+    assert!(project.warnings[0].contains("Shared"));
+}
+
 // ============================================================================
 // Multiple PropertyGroup Tests
 // ============================================================================
@@ -1021,6 +1295,34 @@ fn test_parse_dac_description() {
     );
 }
 
+#[test]
+fn test_parse_default_file_encoding() {
+    let content = r#"<?xml version="1.0" encoding="utf-8"?>
+<Project DefaultTargets="Build" xmlns="http://schemas.microsoft.com/developer/msbuild/2003">
+  <PropertyGroup>
+    <Name>TestProject</Name>
+    <DSP>Microsoft.Data.Tools.Schema.Sql.Sql160DatabaseSchemaProvider</DSP>
+    <DefaultFileEncoding>Windows-1252</DefaultFileEncoding>
+  </PropertyGroup>
+</Project>"#;
+
+    let temp_dir = create_test_project(content, &[]);
+    let project =
+        rust_sqlpackage::project::parse_sqlproj(&temp_dir.path().join("project.sqlproj")).unwrap();
+    assert_eq!(
+        project.default_file_encoding,
+        Some(rust_sqlpackage::parser::FileEncoding::Windows1252)
+    );
+
+    // Unknown encodings are reported and the encoding is detected per file instead
+    let content = content.replace("Windows-1252", "ebcdic");
+    let temp_dir = create_test_project(&content, &[]);
+    let project =
+        rust_sqlpackage::project::parse_sqlproj(&temp_dir.path().join("project.sqlproj")).unwrap();
+    assert_eq!(project.default_file_encoding, None);
+    assert!(project.warnings.iter().any(|w| w.contains("ebcdic")));
+}
+
 #[test]
 fn test_parse_dac_version_and_description() {
     // When both DacVersion and DacDescription are specified
@@ -1048,6 +1350,50 @@ fn test_parse_dac_version_and_description() {
     );
 }
 
+#[test]
+fn test_parse_deployment_contributors() {
+    let content = r#"<?xml version="1.0" encoding="utf-8"?>
+<Project DefaultTargets="Build" xmlns="http://schemas.microsoft.com/developer/msbuild/2003">
+  <PropertyGroup>
+    <Name>TestProject</Name>
+    <DSP>Microsoft.Data.Tools.Schema.Sql.Sql160DatabaseSchemaProvider</DSP>
+    <DeploymentContributors>MyCompany.Contributor;MyCompany.Filter</DeploymentContributors>
+    <ContributorArguments>Schema=dev;Verbose=true</ContributorArguments>
+  </PropertyGroup>
+</Project>"#;
+
+    let temp_dir = create_test_project(content, &[]);
+    let sqlproj_path = temp_dir.path().join("project.sqlproj");
+
+    let project = rust_sqlpackage::project::parse_sqlproj(&sqlproj_path).unwrap();
+    assert_eq!(
+        project.deployment_contributors,
+        vec!["MyCompany.Contributor", "MyCompany.Filter"]
+    );
+    assert_eq!(project.contributor_arguments.len(), 2);
+    assert_eq!(project.contributor_arguments[0].name, "Schema");
+    assert_eq!(project.contributor_arguments[0].value, "dev");
+    assert_eq!(project.contributor_arguments[1].name, "Verbose");
+    assert_eq!(project.contributor_arguments[1].value, "true");
+}
+
+#[test]
+fn test_parse_deployment_contributors_default_empty() {
+    let content = r#"<?xml version="1.0" encoding="utf-8"?>
+<Project DefaultTargets="Build" xmlns="http://schemas.microsoft.com/developer/msbuild/2003">
+  <PropertyGroup>
+    <Name>TestProject</Name>
+  </PropertyGroup>
+</Project>"#;
+
+    let temp_dir = create_test_project(content, &[]);
+    let sqlproj_path = temp_dir.path().join("project.sqlproj");
+
+    let project = rust_sqlpackage::project::parse_sqlproj(&sqlproj_path).unwrap();
+    assert!(project.deployment_contributors.is_empty());
+    assert!(project.contributor_arguments.is_empty());
+}
+
 // ============================================================================
 // Collation LCID and Case Sensitivity Tests
 // ============================================================================
@@ -1147,3 +1493,305 @@ fn test_parse_collation_binary() {
     assert_eq!(project.collation_lcid, 1033);
     assert!(project.collation_case_sensitive); // Binary is always case-sensitive
 }
+
+// ============================================================================
+// Project Lint Tests
+// ============================================================================
+
+#[test]
+fn test_lint_project() {
+    use rust_sqlpackage::project::{lint_project, LintIssue};
+
+    let content = r#"<?xml version="1.0" encoding="utf-8"?>
+<Project DefaultTargets="Build" xmlns="http://schemas.microsoft.com/developer/msbuild/2003">
+  <ItemGroup>
+    <Build Include="Tables\Orders.sql" />
+    <Build Include="Tables\*.sql" />
+    <Build Include="Views\Gone.sql" />
+    <Build Include="Legacy\Old.sql" Condition="'$(TargetPlatform)' == 'Sql130'" />
+    <None Include="Scripts\Seed.sql" />
+    <PostDeploy Include="Scripts\Script.PostDeployment.sql" />
+  </ItemGroup>
+</Project>"#;
+    let temp_dir = create_test_project(
+        content,
+        &[
+            ("Tables/Orders.sql", "CREATE TABLE Orders (Id INT);"),
+            ("Tables/Customers.sql", "CREATE TABLE Customers (Id INT);"),
+            ("Legacy/Old.sql", "CREATE TABLE Old (Id INT);"),
+            ("Scripts/Seed.sql", ":r .\\Data.sql"),
+            ("Scripts/Script.PostDeployment.sql", ":r .\\Seed.sql"),
+            ("Scratch/Test.sql", "SELECT 1;"),
+            ("bin/Debug/Generated.sql", "SELECT 1;"),
+        ],
+    );
+
+    let issues = lint_project(&temp_dir.path().join("project.sqlproj")).unwrap();
+    assert_eq!(
+        issues,
+        vec![
+            LintIssue::NotInBuild {
+                path: PathBuf::from("Scratch/Test.sql"),
+            },
+            LintIssue::MissingFile {
+                include: "Views/Gone.sql".to_string(),
+            },
+            LintIssue::DuplicateInclude {
+                path: PathBuf::from("Tables/Orders.sql"),
+                count: 2,
+            },
+        ]
+    );
+    assert_eq!(
+        issues[2].to_string(),
+        "duplicate include: Tables/Orders.sql (2 times)"
+    );
+
+    // SDK-style projects build every file by default
+    let content = r#"<Project Sdk="Microsoft.Build.Sql"><PropertyGroup><Name>P</Name></PropertyGroup></Project>"#;
+    let temp_dir = create_test_project(
+        content,
+        &[("Tables/Orders.sql", "CREATE TABLE Orders (Id INT);")],
+    );
+    assert!(lint_project(&temp_dir.path().join("project.sqlproj"))
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_lint_naming_rules() {
+    use rust_sqlpackage::project::{lint_model, LintIssue, Ruleset};
+
+    let content = r#"<Project Sdk="Microsoft.Build.Sql"><PropertyGroup><Name>P</Name></PropertyGroup></Project>"#;
+    let temp_dir = create_test_project(
+        content,
+        &[
+            (
+                "Tables/Orders.sql",
+                "CREATE TABLE [dbo].[Orders] (\n    [Id] INT NOT NULL,\n    [Total] INT NOT NULL DEFAULT 0,\n    CONSTRAINT [Orders_PK] PRIMARY KEY ([Id])\n);",
+            ),
+            (
+                "Procedures/GetOrders.sql",
+                "CREATE PROCEDURE [dbo].[GetOrders]\nAS\nSELECT [Id] FROM [dbo].[Orders];",
+            ),
+            (
+                "Procedures/usp_GetOrder.sql",
+                "CREATE PROCEDURE [dbo].[usp_GetOrder]\nAS\nSELECT [Id] FROM [dbo].[Orders];",
+            ),
+        ],
+    );
+    let ruleset: Ruleset = r#"
+[naming]
+procedure = "^usp_"
+primary_key = "^PK_"
+default = "^DF_"
+"#
+    .parse()
+    .unwrap();
+
+    let (project, model) =
+        rust_sqlpackage::load_model(&temp_dir.path().join("project.sqlproj")).unwrap();
+    let issues = lint_model(&model, &project, &ruleset);
+    let messages: Vec<String> = issues.iter().map(ToString::to_string).collect();
+    // The unnamed default constraint is not checked
+    assert_eq!(
+        messages,
+        vec![
+            "naming: Procedure [dbo].[GetOrders] does not match ^usp_ (Procedures/GetOrders.sql:1-3)",
+            "naming: Primary key [dbo].[Orders_PK] does not match ^PK_ (Tables/Orders.sql:1-5)",
+        ]
+    );
+    assert!(issues.iter().all(|issue| issue.rule() == "naming"));
+    assert!(matches!(
+        &issues[0],
+        LintIssue::Naming { location: Some(location), .. } if location.start_line == 1
+    ));
+}
+
+#[test]
+fn test_lint_deprecated_features() {
+    use rust_sqlpackage::project::{lint_model, Ruleset};
+
+    let content = r#"<Project Sdk="Microsoft.Build.Sql"><PropertyGroup><Name>P</Name></PropertyGroup></Project>"#;
+    let temp_dir = create_test_project(
+        content,
+        &[
+            (
+                "Tables/Documents.sql",
+                "CREATE TABLE [dbo].[Documents] ([Id] INT NOT NULL, [Body] NTEXT NULL, [Scan] IMAGE NULL);",
+            ),
+            (
+                "Procedures/PurgeDocuments.sql",
+                "CREATE PROCEDURE [dbo].[PurgeDocuments] @Note TEXT\nAS\nBEGIN\n    SET ROWCOUNT 500;\n    DELETE FROM [dbo].[Documents];\nEND;",
+            ),
+            (
+                "Views/DocumentPairs.sql",
+                "CREATE VIEW [dbo].[DocumentPairs]\nAS\nSELECT a.[Id]\nFROM [dbo].[Documents] a, [dbo].[Documents] b\nWHERE a.[Id] *= b.[Id];",
+            ),
+            (
+                "Procedures/Modern.sql",
+                "CREATE PROCEDURE [dbo].[Modern]\nAS\nBEGIN\n    -- SET ROWCOUNT was replaced by TOP\n    DECLARE @Total INT = 1;\n    SET @Total *= 2;\nEND;",
+            ),
+        ],
+    );
+
+    let (project, model) =
+        rust_sqlpackage::load_model(&temp_dir.path().join("project.sqlproj")).unwrap();
+    let mut messages: Vec<String> = lint_model(&model, &project, &Ruleset::default())
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    messages.sort();
+    assert_eq!(
+        messages,
+        vec![
+            "deprecated-type: Procedure [dbo].[PurgeDocuments] parameter @Note is text (Procedures/PurgeDocuments.sql:1-6)",
+            "deprecated-type: Table [dbo].[Documents] column [Body] is ntext (Tables/Documents.sql:1)",
+            "deprecated-type: Table [dbo].[Documents] column [Scan] is image (Tables/Documents.sql:1)",
+            "old-style-join: View [dbo].[DocumentPairs] uses a *= or =* outer join (Views/DocumentPairs.sql:1-5)",
+            "set-rowcount: Procedure [dbo].[PurgeDocuments] uses SET ROWCOUNT (Procedures/PurgeDocuments.sql:1-6)",
+        ]
+    );
+}
+
+#[test]
+fn test_lint_objects_per_file() {
+    use rust_sqlpackage::project::{lint_model, Ruleset};
+
+    let content = r#"<Project Sdk="Microsoft.Build.Sql"><PropertyGroup><Name>P</Name><ObjectsPerFile>Single</ObjectsPerFile></PropertyGroup></Project>"#;
+    let temp_dir = create_test_project(
+        content,
+        &[
+            (
+                "Tables/Orders.sql",
+                "CREATE TABLE [dbo].[Orders] ([Id] INT NOT NULL CONSTRAINT [PK_Orders] PRIMARY KEY);\nGO\nCREATE INDEX [IX_Orders] ON [dbo].[Orders] ([Id]);\nGO\nCREATE VIEW [dbo].[OpenOrders]\nAS\nSELECT [Id] FROM [dbo].[Orders];",
+            ),
+            (
+                "Tables/Customers.sql",
+                "CREATE TABLE [dbo].[Customers] ([Id] INT NOT NULL);",
+            ),
+        ],
+    );
+
+    let (project, model) =
+        rust_sqlpackage::load_model(&temp_dir.path().join("project.sqlproj")).unwrap();
+    let messages: Vec<String> = lint_model(&model, &project, &Ruleset::default())
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            "multiple-objects-per-file: View [dbo].[OpenOrders] shares its file with [dbo].[Orders] (Tables/Orders.sql:5-7)",
+        ]
+    );
+
+    // Not reported when files may define several objects
+    let project = rust_sqlpackage::project::SqlProject {
+        objects_per_file: rust_sqlpackage::project::ObjectsPerFile::Multiple,
+        ..project
+    };
+    assert!(lint_model(&model, &project, &Ruleset::default()).is_empty());
+}
+
+#[test]
+fn test_lint_security_rules() {
+    use rust_sqlpackage::project::{format_sarif, lint_model, Ruleset};
+
+    let content = r#"<Project Sdk="Microsoft.Build.Sql"><PropertyGroup><Name>P</Name></PropertyGroup></Project>"#;
+    let temp_dir = create_test_project(
+        content,
+        &[
+            (
+                "Tables/Orders.sql",
+                "CREATE TABLE [dbo].[Orders] ([Id] INT NOT NULL, [Name] NVARCHAR(50) NULL);",
+            ),
+            (
+                "Security/Permissions.sql",
+                "CREATE ROLE [Reporting];\nGO\nGRANT CONTROL ON [dbo].[Orders] TO [public];\nGO\nGRANT ALTER ON [dbo].[Orders] TO [Reporting];\nGO\nGRANT SELECT ON [dbo].[Orders] TO [public];",
+            ),
+            (
+                "Procedures/SearchOrders.sql",
+                "CREATE PROCEDURE [dbo].[SearchOrders] @Filter NVARCHAR(100), @Sort SYSNAME\nAS\nBEGIN\n    DECLARE @sql NVARCHAR(MAX) = N'SELECT * FROM [dbo].[Orders] ORDER BY ' + QUOTENAME(@Sort);\n    SET @sql += N' WHERE [Name] LIKE ''' + @Filter + N'''';\n    EXEC sp_executesql @sql;\nEND;",
+            ),
+            (
+                "Procedures/PurgeOrders.sql",
+                "CREATE PROCEDURE [dbo].[PurgeOrders]\nWITH EXECUTE AS OWNER\nAS\nDELETE FROM [dbo].[Orders];",
+            ),
+        ],
+    );
+
+    let project_path = temp_dir.path().join("project.sqlproj");
+    let (project, model) = rust_sqlpackage::load_model(&project_path).unwrap();
+    let ruleset: Ruleset = "[security]\nbroad_principals = [\"Reporting\"]"
+        .parse()
+        .unwrap();
+    let issues = lint_model(&model, &project, &ruleset);
+    let mut messages: Vec<String> = issues.iter().map(ToString::to_string).collect();
+    messages.sort();
+    assert_eq!(messages.len(), 4, "{:#?}", messages);
+    assert!(messages[0].contains("grants ALTER to [Reporting]"));
+    assert!(messages[1].contains("grants CONTROL to [public]"));
+    assert_eq!(
+        messages[2],
+        "dynamic-sql: Procedure [dbo].[SearchOrders] executes dynamic SQL built from @Filter (Procedures/SearchOrders.sql:1-7)"
+    );
+    assert_eq!(
+        messages[3],
+        "execute-as-owner: Procedure [dbo].[PurgeOrders] runs with EXECUTE AS OWNER (Procedures/PurgeOrders.sql:1-4)"
+    );
+
+    let sarif: serde_json::Value =
+        serde_json::from_str(&format_sarif(&issues, &project_path)).unwrap();
+    let results = sarif["runs"][0]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 4);
+    assert!(results.iter().all(|r| r["level"] == "error"));
+}
+
+// ============================================================================
+// Project Editing Tests
+// ============================================================================
+
+#[test]
+fn test_add_sql_files() {
+    use rust_sqlpackage::project::{add_sql_files, parse_sqlproj, sql_files_to_add};
+
+    let content = "<Project DefaultTargets=\"Build\" xmlns=\"http://schemas.microsoft.com/developer/msbuild/2003\">\r\n  <ItemGroup>\r\n    <Build Include=\"Tables\\Orders.sql\" />\r\n  </ItemGroup>\r\n</Project>\r\n";
+    let temp_dir = create_test_project(
+        content,
+        &[
+            ("Tables/Orders.sql", "CREATE TABLE Orders (Id INT);"),
+            ("Tables/Customers.sql", "CREATE TABLE Customers (Id INT);"),
+        ],
+    );
+    let project_path = temp_dir.path().join("project.sqlproj");
+    let files = [
+        temp_dir.path().join("Tables/Orders.sql"),
+        temp_dir.path().join("Tables/Customers.sql"),
+    ];
+
+    // A dry run reports the item without touching the project
+    assert_eq!(
+        sql_files_to_add(&project_path, &files).unwrap(),
+        vec!["Tables\\Customers.sql".to_string()]
+    );
+    assert_eq!(std::fs::read_to_string(&project_path).unwrap(), content);
+
+    let added = add_sql_files(&project_path, &files).unwrap();
+    assert_eq!(added, vec!["Tables\\Customers.sql".to_string()]);
+    let saved = std::fs::read_to_string(&project_path).unwrap();
+    assert_eq!(
+        saved,
+        content.replace(
+            "Orders.sql\" />\r\n",
+            "Orders.sql\" />\r\n    <Build Include=\"Tables\\Customers.sql\" />\r\n"
+        )
+    );
+    assert_eq!(parse_sqlproj(&project_path).unwrap().sql_files.len(), 2);
+
+    // Adding the same files again leaves the project untouched
+    assert!(add_sql_files(&project_path, &files).unwrap().is_empty());
+    assert_eq!(std::fs::read_to_string(&project_path).unwrap(), saved);
+
+    assert!(add_sql_files(&project_path, &[temp_dir.path().join("Missing.sql")]).is_err());
+}
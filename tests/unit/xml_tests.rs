@@ -6,6 +6,7 @@
 use std::io::Write;
 use std::path::PathBuf;
 
+use rust_sqlpackage::parser::identifier_utils::{quote_name_parts, split_name_parts};
 use tempfile::NamedTempFile;
 
 /// Helper to create a temp SQL file with content
@@ -28,14 +29,26 @@ fn create_test_project() -> rust_sqlpackage::project::SqlProject {
         dacpac_references: vec![],
         package_references: vec![],
         sqlcmd_variables: vec![],
+        build_variables: Default::default(),
         project_dir: PathBuf::new(),
         pre_deploy_script: None,
         post_deploy_script: None,
+        resolve_deploy_includes: true,
+        header_descriptions: false,
+        column_descriptions: false,
         ansi_nulls: true,
         quoted_identifier: true,
         database_options: rust_sqlpackage::project::DatabaseOptions::default(),
         dac_version: "1.0.0.0".to_string(),
         dac_description: None,
+        deployment_contributors: vec![],
+        contributor_arguments: vec![],
+        dacpac_profiles: vec![],
+        default_file_encoding: None,
+        objects_per_file: rust_sqlpackage::project::ObjectsPerFile::Multiple,
+        skipped_files: Vec::new(),
+        unrecognized_elements: Vec::new(),
+        warnings: vec![],
     }
 }
 
@@ -403,46 +416,133 @@ END
     );
 }
 
+/// Helper to generate model XML with the given line-ending handling
+fn generate_model_xml_with_line_endings(
+    sql: &str,
+    line_endings: rust_sqlpackage::parser::LineEndings,
+) -> String {
+    let file = create_sql_file(sql);
+    let options = rust_sqlpackage::parser::ParseOptions {
+        line_endings,
+        ..Default::default()
+    };
+    let statements = rust_sqlpackage::parser::parse_sql_files_with_options(
+        &[file.path().to_path_buf()],
+        &options,
+    )
+    .unwrap();
+    let project = create_test_project();
+    let model = rust_sqlpackage::model::build_model(&statements, &project).unwrap();
+
+    rust_sqlpackage::dacpac::generate_model_xml_string(
+        &model,
+        rust_sqlpackage::project::SqlServerVersion::Sql160,
+        1033,
+        false,
+    )
+}
+
 #[test]
-fn test_script_content_normalizes_crlf_to_lf() {
+fn test_script_content_preserves_crlf_by_default() {
     // Create SQL content with Windows line endings (CRLF)
     let sql = "CREATE PROCEDURE [dbo].[TestProc]\r\nAS\r\nBEGIN\r\n    SELECT 1;\r\nEND\r\n";
     let xml = generate_model_xml(sql);
 
-    // The generated XML should not contain any CRLF sequences
+    // DacFx keeps the source line endings in CDATA scripts
     assert!(
-        !xml.contains("\r\n"),
-        "Generated XML should normalize CRLF to LF. Found CRLF in output."
+        xml.contains("<![CDATA[BEGIN\r\n    SELECT 1;\r\nEND]]>"),
+        "BodyScript should keep CRLF line endings. Got:\n{}",
+        xml
     );
+}
+
+#[test]
+fn test_script_content_normalizes_crlf_to_lf() {
+    let sql = "CREATE PROCEDURE [dbo].[TestProc]\r\nAS\r\nBEGIN\r\n    SELECT 1;\r\nEND\r\n";
+    let xml = generate_model_xml_with_line_endings(sql, rust_sqlpackage::parser::LineEndings::Lf);
 
-    // Should still contain LF line endings in CDATA
+    // The generated XML should not contain any CRLF sequences
     assert!(
-        xml.contains("<![CDATA["),
-        "BodyScript should contain CDATA section"
+        !xml.contains("\r\n"),
+        "Generated XML should normalize CRLF to LF. Found CRLF in output."
     );
 
     // Verify the body content is present (with LF endings)
     assert!(
-        xml.contains("SELECT 1;"),
-        "BodyScript should contain the procedure body"
+        xml.contains("<![CDATA[BEGIN\n    SELECT 1;\nEND]]>"),
+        "BodyScript should contain the procedure body with LF endings"
     );
 }
 
 #[test]
-fn test_view_query_script_normalizes_crlf() {
-    // Create view SQL with Windows line endings
-    let sql = "CREATE VIEW [dbo].[TestView]\r\nAS\r\nSELECT\r\n    1 AS Col1,\r\n    2 AS Col2\r\n";
+fn test_view_scripts_follow_line_endings() {
+    // Mixed line endings: the header uses LF, the query CRLF
+    let sql = "CREATE VIEW [dbo].[TestView]\nAS\r\nSELECT\r\n    1 AS Col1\n";
+
+    // Preserved: the query and the header keep their endings
     let xml = generate_model_xml(sql);
+    assert!(xml.contains("<![CDATA[\r\nSELECT\r\n    1 AS Col1]]>"));
+    assert!(xml.contains(r#"Value="CREATE VIEW [dbo].[TestView]&#xA;AS""#));
 
-    // The generated XML should not contain any CRLF sequences
+    // CRLF: every script property uses CRLF, including the annotation header
+    let xml = generate_model_xml_with_line_endings(sql, rust_sqlpackage::parser::LineEndings::Crlf);
+    assert!(xml.contains("<![CDATA[\r\nSELECT\r\n    1 AS Col1]]>"));
+    assert!(xml.contains(r#"Value="CREATE VIEW [dbo].[TestView]&#xD;&#xA;AS""#));
+
+    // LF: no CRLF anywhere
+    let xml = generate_model_xml_with_line_endings(sql, rust_sqlpackage::parser::LineEndings::Lf);
     assert!(
         !xml.contains("\r\n"),
         "Generated XML should normalize CRLF to LF in QueryScript"
     );
+    assert!(xml.contains("<![CDATA[\nSELECT\n    1 AS Col1]]>"));
+}
 
-    assert!(
-        xml.contains(r#"<Property Name="QueryScript">"#),
-        "View should have QueryScript property"
+// ============================================================================
+// Project SET Option Tests
+// ============================================================================
+
+#[test]
+fn test_project_set_options_apply_to_objects() {
+    let sql = r#"
+CREATE TABLE [dbo].[Orders] ([Id] INT NOT NULL);
+GO
+CREATE VIEW [dbo].[OrderIds] AS SELECT [Id] FROM [dbo].[Orders];
+GO
+CREATE PROCEDURE [dbo].[GetOrders] AS SELECT [Id] FROM [dbo].[Orders];
+"#;
+    let file = create_sql_file(sql);
+    let statements = rust_sqlpackage::parser::parse_sql_file(file.path()).unwrap();
+    let generate = |ansi_nulls: bool, quoted_identifier: bool| {
+        let project = rust_sqlpackage::project::SqlProject {
+            ansi_nulls,
+            quoted_identifier,
+            ..create_test_project()
+        };
+        let model = rust_sqlpackage::model::build_model(&statements, &project).unwrap();
+        rust_sqlpackage::dacpac::generate_model_xml_string(
+            &model,
+            rust_sqlpackage::project::SqlServerVersion::Sql160,
+            1033,
+            false,
+        )
+    };
+
+    // Defaults: IsAnsiNullsOn on every object (and on SqlDatabaseOptions, which follows
+    // the separate <AnsiNullsOn> database option), IsQuotedIdentifierOn left at its default
+    let ansi_nulls_on = |xml: &str| xml.matches(r#"Name="IsAnsiNullsOn" Value="True""#).count();
+    let xml = generate(true, true);
+    assert_eq!(ansi_nulls_on(&xml), 4);
+    assert!(!xml.contains("IsQuotedIdentifierOn"));
+
+    // <AnsiNulls>False</AnsiNulls> and <QuotedIdentifier>False</QuotedIdentifier>
+    let xml = generate(false, false);
+    assert_eq!(ansi_nulls_on(&xml), 1);
+    // Modules record QUOTED_IDENTIFIER; tables do not
+    assert_eq!(
+        xml.matches(r#"Name="IsQuotedIdentifierOn" Value="False""#)
+            .count(),
+        2
     );
 }
 
@@ -665,6 +765,36 @@ fn test_model_xml_has_correct_namespace() {
     );
 }
 
+#[test]
+fn test_compile_sql_returns_model_fragment() {
+    let sql = "CREATE TABLE [dbo].[T] ([Id] INT NOT NULL);\nGO\nCREATE VIEW [dbo].[V] AS SELECT [Id] FROM [dbo].[T];";
+    let fragment = rust_sqlpackage::compile_sql(
+        sql,
+        std::path::Path::new("<stdin>"),
+        rust_sqlpackage::project::SqlServerVersion::Sql160,
+    )
+    .unwrap();
+
+    assert!(fragment.starts_with("<Model>"), "Got:\n{}", fragment);
+    assert!(fragment.contains(r#"Type="SqlTable" Name="[dbo].[T]""#));
+    assert!(fragment.contains(r#"Type="SqlView" Name="[dbo].[V]""#));
+    assert!(
+        !fragment.contains("SqlDatabaseOptions") && !fragment.contains("<Header>"),
+        "Fragment should only contain model elements"
+    );
+}
+
+#[test]
+fn test_compile_sql_reports_parse_error_with_source_label() {
+    let result = rust_sqlpackage::compile_sql(
+        "CREATE TABLE (",
+        std::path::Path::new("<stdin>"),
+        rust_sqlpackage::project::SqlServerVersion::Sql160,
+    );
+    let err = result.unwrap_err().to_string();
+    assert!(err.contains("<stdin>"), "Got: {}", err);
+}
+
 // ============================================================================
 // DacMetadata.xml Tests
 // ============================================================================
@@ -699,6 +829,41 @@ fn test_generate_dac_metadata_version() {
     );
 }
 
+#[test]
+fn test_generate_dac_metadata_deployment_contributors() {
+    let mut project = create_test_project();
+    project.deployment_contributors = vec!["MyCompany.Contributor".to_string()];
+    project.contributor_arguments = vec![rust_sqlpackage::project::ContributorArgument {
+        name: "Schema".to_string(),
+        value: "dev".to_string(),
+    }];
+
+    let mut buffer = Vec::new();
+    rust_sqlpackage::dacpac::generate_metadata_xml(&mut buffer, &project, "1.0.0.0").unwrap();
+    let metadata = String::from_utf8(buffer).unwrap();
+
+    assert!(
+        metadata.contains(r#"<Contributor Id="MyCompany.Contributor" />"#),
+        "Should record deployment contributor. Got:\n{}",
+        metadata
+    );
+    assert!(
+        metadata.contains(r#"<Argument Name="Schema" Value="dev" />"#),
+        "Should record contributor argument. Got:\n{}",
+        metadata
+    );
+}
+
+#[test]
+fn test_generate_dac_metadata_omits_empty_contributors() {
+    let metadata = rust_sqlpackage::dacpac::generate_dac_metadata_xml("TestProject", "1.0.0.0");
+
+    assert!(
+        !metadata.contains("DeploymentContributors") && !metadata.contains("ContributorArguments"),
+        "Should not emit contributor elements when none are configured"
+    );
+}
+
 // ============================================================================
 // Origin.xml Tests
 // ============================================================================
@@ -1056,6 +1221,36 @@ INCLUDE ([TotalAmount], [Status]);
     );
 }
 
+#[test]
+fn test_procedure_cursor_varying_output_parameter() {
+    let sql = r#"
+CREATE PROCEDURE [dbo].[GetCursor]
+    @Result CURSOR VARYING OUTPUT,
+    @Min INT = 0
+AS
+BEGIN
+    SET @Result = CURSOR FORWARD_ONLY STATIC FOR SELECT 1 WHERE 1 > @Min;
+    OPEN @Result;
+END
+"#;
+    let xml = generate_model_xml(sql);
+
+    let start = xml
+        .find(r#"Name="[dbo].[GetCursor].[@Result]">"#)
+        .expect("Should have the cursor parameter");
+    let param = &xml[start..start + xml[start..].find("</Element>").unwrap()];
+    let output = param
+        .find(r#"<Property Name="IsOutput" Value="True" />"#)
+        .expect("Cursor parameter should be OUTPUT");
+    let varying = param
+        .find(r#"<Property Name="Varying" Value="True" />"#)
+        .expect("Cursor parameter should be VARYING");
+    assert!(output < varying);
+    assert!(param.contains(r#"Name="[cursor]""#));
+
+    assert!(xml.contains(r#"<Property Name="DefaultExpressionScript">"#));
+}
+
 // ============================================================================
 // Native Compilation XML Tests
 // ============================================================================
@@ -1080,6 +1275,13 @@ END
         xml
     );
 
+    // SCHEMABINDING is required for natively compiled procedures and emitted after it
+    let native = xml.find(r#"Name="IsNativelyCompiled""#).unwrap();
+    let schema_bound = xml
+        .find(r#"<Property Name="IsSchemaBound" Value="True" />"#)
+        .expect("Natively compiled procedure should have IsSchemaBound=True property");
+    assert!(native < schema_bound);
+
     // Should be a SqlProcedure element
     assert!(
         xml.contains("Type=\"SqlProcedure\""),
@@ -1431,6 +1633,88 @@ END
     );
 }
 
+#[test]
+fn test_function_header_keeps_leading_blank_lines() {
+    // The header covers the batch from its first byte, as DotNet's does
+    let sql = "CREATE TABLE [dbo].[T] ([Id] INT);\r\nGO\r\n\r\n  \r\nCREATE FUNCTION [dbo].[F]()\r\nRETURNS INT\r\nAS\r\nBEGIN\r\n    RETURN 1;\r\nEND\r\n";
+    let xml = generate_model_xml(sql);
+
+    assert!(
+        xml.contains(r#"<Property Name="CreateOffset" Value="6" />"#),
+        "{}",
+        xml
+    );
+    assert!(
+        xml.contains(r#"<Property Name="Length" Value="77" />"#),
+        "{}",
+        xml
+    );
+    assert!(
+        xml.contains(r#"<Property Name="StartLine" Value="3" />"#),
+        "{}",
+        xml
+    );
+    assert!(xml.contains(
+        r#"<Property Name="HeaderContents" Value="&#xD;&#xA;  &#xD;&#xA;CREATE FUNCTION [dbo].[F]()&#xD;&#xA;RETURNS INT&#xD;&#xA;AS&#xD;&#xA;" />"#
+    ), "{}", xml);
+}
+
+#[test]
+fn test_module_annotations_locate_scripts() {
+    // Views, procedures and triggers get SysCommentsObjectAnnotation like functions, with
+    // the script's line in its file and lengths in UTF-16 code units. A script starts
+    // where its batch does, blank lines included
+    let sql = "CREATE TABLE [dbo].[T] ([Id] INT NOT NULL, [Name] NVARCHAR(10));\n\
+               GO\n\
+               CREATE VIEW [dbo].[V] AS SELECT [Id] FROM [dbo].[T];\n\
+               GO\n\
+               \n\
+               -- note\n\
+               CREATE PROCEDURE [dbo].[P] @Name NVARCHAR(10) = N'café' AS SELECT @Name;\n\
+               GO\n\
+               CREATE TRIGGER [dbo].[TR] ON [dbo].[T] AFTER INSERT AS\n\tSELECT 1;\n";
+    let xml = generate_model_xml(sql);
+
+    let annotation = |element: &str| {
+        let start = xml.find(element).expect(element);
+        let element_xml = &xml[start..];
+        let annotation = element_xml
+            .find(r#"<Annotation Type="SysCommentsObjectAnnotation">"#)
+            .expect(element);
+        element_xml[annotation..]
+            .lines()
+            .skip(1)
+            .take_while(|line| !line.contains("</Annotation>"))
+            .map(str::trim)
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    assert_eq!(
+        annotation(r#"<Element Type="SqlView" Name="[dbo].[V]">"#),
+        r#"<Property Name="Length" Value="52" />
+<Property Name="StartLine" Value="3" />
+<Property Name="StartColumn" Value="1" />
+<Property Name="HeaderContents" Value="CREATE VIEW [dbo].[V] AS" />
+<Property Name="FooterContents" Value=";" />"#
+    );
+    assert_eq!(
+        annotation(r#"<Element Type="SqlProcedure" Name="[dbo].[P]">"#),
+        r#"<Property Name="CreateOffset" Value="9" />
+<Property Name="Length" Value="81" />
+<Property Name="StartLine" Value="5" />
+<Property Name="StartColumn" Value="1" />
+<Property Name="HeaderContents" Value="&#xA;-- note&#xA;CREATE PROCEDURE [dbo].[P] @Name NVARCHAR(10) = N'café' AS " />"#
+    );
+    assert_eq!(
+        annotation(r#"<Element Type="SqlDmlTrigger" Name="[dbo].[TR]">"#),
+        r#"<Property Name="Length" Value="65" />
+<Property Name="StartLine" Value="9" />
+<Property Name="StartColumn" Value="1" />
+<Property Name="HeaderContents" Value="CREATE TRIGGER [dbo].[TR] ON [dbo].[T] AFTER INSERT AS" />"#
+    );
+}
+
 #[test]
 fn test_scalar_function_has_ansi_nulls_property() {
     // Scalar functions should have IsAnsiNullsOn property
@@ -1475,3 +1759,436 @@ END
         xml
     );
 }
+
+// ============================================================================
+// Trigger Enabled State XML Tests
+// ============================================================================
+
+#[test]
+fn test_disable_trigger_writes_is_enabled_false() {
+    let sql = r#"
+CREATE TABLE [dbo].[Orders] ([Id] INT NOT NULL PRIMARY KEY);
+GO
+DISABLE TRIGGER [dbo].[TR_Orders_Audit] ON [dbo].[Orders];
+GO
+CREATE TRIGGER [dbo].[TR_Orders_Audit] ON [dbo].[Orders] AFTER INSERT AS BEGIN SET NOCOUNT ON; END
+GO
+CREATE TRIGGER [dbo].[TR_Orders_Check] ON [dbo].[Orders] AFTER UPDATE AS BEGIN SET NOCOUNT ON; END
+"#;
+    let xml = generate_model_xml(sql);
+
+    let audit_start = xml
+        .find(r#"Name="[dbo].[TR_Orders_Audit]""#)
+        .expect("Should have disabled trigger");
+    let audit = &xml[audit_start..audit_start + xml[audit_start..].find("</Element>").unwrap()];
+    assert!(
+        audit.contains(r#"<Property Name="IsEnabled" Value="False" />"#),
+        "Disabled trigger should have IsEnabled=False. Got:\n{}",
+        audit
+    );
+
+    let check_start = xml
+        .find(r#"Name="[dbo].[TR_Orders_Check]""#)
+        .expect("Should have enabled trigger");
+    let check = &xml[check_start..check_start + xml[check_start..].find("</Element>").unwrap()];
+    assert!(!check.contains("IsEnabled"));
+}
+
+#[test]
+fn test_schemabinding_on_views_and_triggers() {
+    let sql = r#"
+CREATE TABLE [dbo].[Orders] ([Id] INT NOT NULL PRIMARY KEY);
+GO
+CREATE VIEW [dbo].[BoundOrders]
+WITH VIEW_METADATA,
+    SCHEMABINDING
+AS SELECT [Id] FROM [dbo].[Orders];
+GO
+CREATE VIEW [dbo].[PlainOrders]
+AS SELECT [Id] FROM [dbo].[Orders] -- not SCHEMABINDING
+GO
+CREATE TRIGGER [dbo].[TR_Orders_Native] ON [dbo].[Orders]
+WITH NATIVE_COMPILATION, SCHEMABINDING
+AFTER INSERT
+AS BEGIN ATOMIC WITH (TRANSACTION ISOLATION LEVEL = SNAPSHOT, LANGUAGE = N'us_english')
+    SELECT 1;
+END
+"#;
+    let xml = generate_model_xml(sql);
+    let element = |name: &str| {
+        let start = xml
+            .find(&format!(r#"Name="{}""#, name))
+            .expect("Should have element");
+        xml[start..start + xml[start..].find("\n    </Element>").unwrap()].to_string()
+    };
+
+    let bound = element("[dbo].[BoundOrders]");
+    assert!(bound.contains(r#"<Property Name="IsSchemaBound" Value="True" />"#));
+    assert!(bound.contains(r#"<Property Name="IsMetadataReported" Value="True" />"#));
+    assert!(!element("[dbo].[PlainOrders]").contains("IsSchemaBound"));
+
+    let trigger = element("[dbo].[TR_Orders_Native]");
+    assert!(trigger.contains(r#"<Property Name="IsInsertTrigger" Value="True" />"#));
+    assert!(trigger.contains(r#"<Property Name="IsSchemaBound" Value="True" />"#));
+}
+
+// ============================================================================
+// Computed Column and CHECK Constraint Function Dependency Tests
+// ============================================================================
+
+#[test]
+fn test_computed_column_udf_expression_dependency() {
+    let sql = r#"
+CREATE TABLE [dbo].[OrderLines] (
+    [Id] INT NOT NULL PRIMARY KEY,
+    [Quantity] INT NOT NULL,
+    [Price] DECIMAL(10, 2) NOT NULL,
+    [Total] AS dbo.CalcTotal([Quantity], [Price])
+);
+"#;
+    let xml = generate_model_xml(sql);
+
+    let start = xml
+        .find(r#"Name="[dbo].[OrderLines].[Total]""#)
+        .expect("Should have computed column");
+    let column = &xml[start..start + xml[start..].find("</Element>").unwrap()];
+    assert!(
+        column.contains(r#"<References Name="[dbo].[CalcTotal]" />"#),
+        "Computed column should depend on the function. Got:\n{}",
+        column
+    );
+    assert!(column.contains(r#"<References Name="[dbo].[OrderLines].[Quantity]" />"#));
+}
+
+#[test]
+fn test_check_constraint_udf_expression_dependency() {
+    let sql = r#"
+CREATE TABLE [dbo].[Bookings] (
+    [Id] INT NOT NULL PRIMARY KEY,
+    [RoomId] INT NOT NULL,
+    CONSTRAINT [CK_Bookings_RoomExists] CHECK ([dbo].[RoomExists]([RoomId]) = 1)
+);
+"#;
+    let xml = generate_model_xml(sql);
+
+    let start = xml
+        .find(r#"Name="[dbo].[CK_Bookings_RoomExists]""#)
+        .expect("Should have check constraint");
+    let constraint = &xml[start..start + xml[start..].find("</Element>").unwrap()];
+    let deps_start = constraint
+        .find(r#"<Relationship Name="CheckExpressionDependencies">"#)
+        .expect("Should have CheckExpressionDependencies");
+    let deps = &constraint[deps_start..];
+    let function_pos = deps
+        .find(r#"<References Name="[dbo].[RoomExists]" />"#)
+        .expect("Check constraint should depend on the function");
+    let column_pos = deps
+        .find(r#"<References Name="[dbo].[Bookings].[RoomId]" />"#)
+        .expect("Check constraint should depend on the column");
+    assert!(function_pos < column_pos);
+}
+
+// ============================================================================
+// Default Constraint Sequence Dependency Tests
+// ============================================================================
+
+#[test]
+fn test_default_constraint_next_value_for_dependency() {
+    let sql = r#"
+CREATE SEQUENCE [dbo].[OrderSeq] AS INT START WITH 1 INCREMENT BY 1;
+GO
+CREATE TABLE [dbo].[Orders] (
+    [Id] INT NOT NULL CONSTRAINT [DF_Orders_Id] DEFAULT (NEXT VALUE FOR [dbo].[OrderSeq]),
+    [Name] NVARCHAR(50) NULL
+);
+"#;
+    let xml = generate_model_xml(sql);
+
+    let start = xml
+        .find(r#"Name="[dbo].[DF_Orders_Id]""#)
+        .expect("Should have default constraint");
+    let constraint = &xml[start..start + xml[start..].find("</Element>").unwrap()];
+    let defining_pos = constraint
+        .find(r#"<Relationship Name="DefiningTable">"#)
+        .unwrap();
+    let deps_pos = constraint
+        .find(r#"<Relationship Name="ExpressionDependencies">"#)
+        .expect("Default constraint should have ExpressionDependencies");
+    let for_column_pos = constraint
+        .find(r#"<Relationship Name="ForColumn">"#)
+        .unwrap();
+    assert!(defining_pos < deps_pos && deps_pos < for_column_pos);
+    assert!(constraint.contains(r#"<References Name="[dbo].[OrderSeq]" />"#));
+}
+
+#[test]
+fn test_default_constraint_without_references_has_no_dependencies() {
+    let sql = r#"
+CREATE TABLE [dbo].[Orders] (
+    [Id] INT NOT NULL,
+    [CreatedAt] DATETIME2 NOT NULL CONSTRAINT [DF_Orders_CreatedAt] DEFAULT (GETDATE())
+);
+"#;
+    let xml = generate_model_xml(sql);
+
+    let start = xml
+        .find(r#"Name="[dbo].[DF_Orders_CreatedAt]""#)
+        .expect("Should have default constraint");
+    let constraint = &xml[start..start + xml[start..].find("</Element>").unwrap()];
+    assert!(!constraint.contains("ExpressionDependencies"));
+}
+
+// ============================================================================
+// Primary Key / Unique Constraint Index Option Tests
+// ============================================================================
+
+#[test]
+fn test_primary_key_constraint_index_options() {
+    let sql = r#"
+CREATE TABLE [dbo].[Orders] (
+    [Id] INT NOT NULL,
+    [Code] NVARCHAR(20) NOT NULL,
+    CONSTRAINT [PK_Orders] PRIMARY KEY CLUSTERED ([Id])
+        WITH (FILLFACTOR = 80, PAD_INDEX = ON, DATA_COMPRESSION = PAGE) ON [PRIMARY],
+    CONSTRAINT [UQ_Orders_Code] UNIQUE ([Code]) ON [USERDATA]
+);
+"#;
+    let xml = generate_model_xml(sql);
+    let element = |name: &str| {
+        let start = xml
+            .find(&format!(r#"Name="{}""#, name))
+            .unwrap_or_else(|| panic!("Should have {}", name));
+        xml[start..start + xml[start..].find("\n    </Element>").unwrap()].to_string()
+    };
+
+    let pk = element("[dbo].[PK_Orders]");
+    assert!(pk.contains(r#"<Property Name="FillFactor" Value="80" />"#));
+    assert!(pk.contains(r#"<Property Name="IsPadded" Value="True" />"#));
+    assert!(pk.contains(r#"<Property Name="CompressionLevel" Value="2" />"#));
+    assert!(pk.contains(r#"<References ExternalSource="BuiltIns" Name="[PRIMARY]" />"#));
+    let positions: Vec<usize> = [
+        "ColumnSpecifications",
+        "DataCompressionOptions",
+        "DefiningTable",
+        "Filegroup",
+    ]
+    .iter()
+    .map(|r| pk.find(&format!(r#"<Relationship Name="{}">"#, r)).unwrap())
+    .collect();
+    assert!(positions.windows(2).all(|w| w[0] < w[1]));
+
+    let unique = element("[dbo].[UQ_Orders_Code]");
+    assert!(!unique.contains("FillFactor"));
+    assert!(!unique.contains("DataCompressionOptions"));
+    assert!(unique.contains(r#"<References Name="[USERDATA]" />"#));
+}
+
+#[test]
+fn test_index_partition_compression_options() {
+    let sql = r#"
+CREATE TABLE [dbo].[Sales] ([Id] INT NOT NULL, [SaleDate] DATE NOT NULL);
+GO
+CREATE NONCLUSTERED INDEX [IX_Sales_SaleDate] ON [dbo].[Sales] ([SaleDate])
+    WITH (DATA_COMPRESSION = PAGE ON PARTITIONS (1 TO 2), DATA_COMPRESSION = ROW ON PARTITIONS (4));
+"#;
+    let xml = generate_model_xml(sql);
+
+    let start = xml
+        .find(r#"Name="[dbo].[Sales].[IX_Sales_SaleDate]""#)
+        .expect("Should have index");
+    let index = &xml[start..start + xml[start..].find("\n    </Element>").unwrap()];
+    assert_eq!(index.matches("SqlDataCompressionOption").count(), 3);
+    for partition in ["1", "2", "4"] {
+        assert!(index.contains(&format!(
+            r#"<Property Name="PartitionNumber" Value="{}" />"#,
+            partition
+        )));
+    }
+}
+
+#[test]
+fn test_index_whole_and_partition_compression_options() {
+    let sql = r#"
+CREATE TABLE [dbo].[Sales] ([Id] INT NOT NULL, [SaleDate] DATE NOT NULL);
+GO
+CREATE NONCLUSTERED INDEX [IX_Sales_SaleDate] ON [dbo].[Sales] ([SaleDate])
+    WITH (DATA_COMPRESSION = ROW, DATA_COMPRESSION = PAGE ON PARTITIONS (1));
+"#;
+    let xml = generate_model_xml(sql);
+
+    let start = xml
+        .find(r#"Name="[dbo].[Sales].[IX_Sales_SaleDate]""#)
+        .expect("Should have index");
+    let index = &xml[start..start + xml[start..].find("\n    </Element>").unwrap()];
+    assert_eq!(index.matches("SqlDataCompressionOption").count(), 2);
+    // ROW (level 1) for the whole index, without a partition, then PAGE (level 2) for partition 1
+    let row = index
+        .find(r#"<Property Name="CompressionLevel" Value="1" />"#)
+        .expect("Should keep the whole-index compression");
+    let page = index
+        .find(r#"<Property Name="CompressionLevel" Value="2" />"#)
+        .expect("Should have the partition compression");
+    assert!(row < page);
+    assert!(!index[row..page].contains("PartitionNumber"));
+    assert_eq!(index.matches("PartitionNumber").count(), 1);
+    assert!(index[page..].contains(r#"<Property Name="PartitionNumber" Value="1" />"#));
+}
+
+#[test]
+fn test_table_data_compression_options() {
+    let sql = r#"
+CREATE TABLE [dbo].[Heap] ([Id] INT NOT NULL) WITH (DATA_COMPRESSION = PAGE);
+GO
+CREATE TABLE [dbo].[Plain] ([Id] INT NOT NULL);
+"#;
+    let xml = generate_model_xml(sql);
+    let element = |name: &str| {
+        let start = xml
+            .find(&format!(r#"Type="SqlTable" Name="{}""#, name))
+            .expect("Should have table");
+        xml[start..start + xml[start..].find("\n    </Element>").unwrap()].to_string()
+    };
+
+    let heap = element("[dbo].[Heap]");
+    assert!(heap.contains(r#"<Relationship Name="DataCompressionOptions">"#));
+    assert!(heap.contains(r#"<Property Name="CompressionLevel" Value="2" />"#));
+    let compression = heap.find("DataCompressionOptions").unwrap();
+    assert!(heap.find(r#"Name="Columns""#).unwrap() < compression);
+    assert!(compression < heap.find(r#"Name="Schema""#).unwrap());
+
+    assert!(!element("[dbo].[Plain]").contains("DataCompressionOptions"));
+}
+
+#[test]
+fn test_scalar_function_with_options() {
+    let sql = r#"
+CREATE FUNCTION [dbo].[Double] (@Value INT)
+RETURNS INT
+WITH SCHEMABINDING, RETURNS NULL ON NULL INPUT, EXECUTE AS OWNER
+AS
+BEGIN
+    RETURN @Value * 2;
+END
+GO
+CREATE FUNCTION [dbo].[Plain] (@Value INT)
+RETURNS INT
+WITH CALLED ON NULL INPUT
+AS
+BEGIN
+    RETURN @Value;
+END
+"#;
+    let xml = generate_model_xml(sql);
+    let element = |name: &str| {
+        let start = xml
+            .find(&format!(r#"Type="SqlScalarFunction" Name="{}""#, name))
+            .expect("Should have function");
+        xml[start..start + xml[start..].find("\n    </Element>").unwrap()].to_string()
+    };
+
+    let double = element("[dbo].[Double]");
+    let positions: Vec<usize> = [
+        r#"<Property Name="DoReturnNullForNullInput" Value="True" />"#,
+        r#"<Property Name="IsAnsiNullsOn" Value="True" />"#,
+        r#"<Property Name="IsOwner" Value="True" />"#,
+        r#"<Property Name="IsSchemaBound" Value="True" />"#,
+    ]
+    .iter()
+    .map(|p| double.find(p).unwrap_or_else(|| panic!("Missing {}", p)))
+    .collect();
+    assert!(positions.windows(2).all(|w| w[0] < w[1]));
+
+    let plain = element("[dbo].[Plain]");
+    assert!(!plain.contains("DoReturnNullForNullInput"));
+    assert!(!plain.contains("IsSchemaBound"));
+    assert!(!plain.contains("IsOwner"));
+}
+
+// ============================================================================
+// Escaped Identifier Tests
+// ============================================================================
+
+#[test]
+fn test_escaped_identifiers_round_trip() {
+    let sql = r#"CREATE TABLE [dbo].[Weird]]Name] ([Id]]x] INT NOT NULL PRIMARY KEY, [Val] INT NULL);
+GO
+CREATE TABLE [dbo].[A.B] ([Id] INT NOT NULL);
+GO
+CREATE VIEW [dbo].[V.1] AS SELECT w.[Id]]x], a.[Id] FROM [dbo].[Weird]]Name] w JOIN [dbo].[A.B] a ON a.[Id] = w.[Id]]x];
+"#;
+    let xml = generate_model_xml(sql);
+
+    // `]` is written as `]]` and dots inside brackets stay part of the name
+    assert!(xml.contains(r#"Name="[dbo].[Weird]]Name]""#));
+    assert!(xml.contains(r#"Name="[dbo].[Weird]]Name].[Id]]x]""#));
+    assert!(xml.contains(r#"Name="[dbo].[A.B]""#));
+    assert!(xml.contains(r#"Name="[dbo].[V.1].[Id]]x]""#));
+    assert!(!xml.contains("[Weird]Name]"));
+    assert!(!xml.contains(r#"Name="[dbo].[Weird]]Name].[Id]""#));
+
+    // Every name splits back into the parts it was written from
+    let doc = roxmltree::Document::parse(&xml).unwrap();
+    let names = doc
+        .descendants()
+        .filter_map(|n| n.attribute("Name"))
+        .filter(|name| name.starts_with('['));
+    for name in names {
+        if let Some(parts) = split_name_parts(name) {
+            assert_eq!(quote_name_parts(&parts), name);
+        }
+    }
+}
+
+/// The references of the relationship `relationship` on the element named `element`
+fn relationship_references(xml: &str, element: &str, relationship: &str) -> Vec<String> {
+    let doc = roxmltree::Document::parse(xml).unwrap();
+    doc.descendants()
+        .filter(|n| n.has_tag_name("Element") && n.attribute("Name") == Some(element))
+        .flat_map(|n| n.children())
+        .filter(|n| n.has_tag_name("Relationship") && n.attribute("Name") == Some(relationship))
+        .flat_map(|n| n.descendants())
+        .filter(|n| n.has_tag_name("References"))
+        .filter_map(|n| n.attribute("Name").map(str::to_string))
+        .collect()
+}
+
+#[test]
+fn test_escaped_column_names_in_view_and_procedure_dependencies() {
+    let sql = r#"CREATE TABLE [dbo].[t] ([Id]]x] INT NOT NULL, [C.D] INT NULL);
+GO
+CREATE VIEW [dbo].[V] AS SELECT [Id]]x] FROM [dbo].[t] WHERE [C.D] = 1;
+GO
+CREATE PROCEDURE [dbo].[P] AS SELECT [Id]]x] FROM [dbo].[t] WHERE [C.D] = 1;
+"#;
+    let xml = generate_model_xml(sql);
+
+    for (element, relationship) in [
+        ("[dbo].[V]", "QueryDependencies"),
+        ("[dbo].[P]", "BodyDependencies"),
+    ] {
+        let refs = relationship_references(&xml, element, relationship);
+        assert!(
+            refs.contains(&"[dbo].[t].[Id]]x]".to_string()),
+            "{} {}: {:?}",
+            element,
+            relationship,
+            refs
+        );
+        assert!(
+            refs.contains(&"[dbo].[t].[C.D]".to_string()),
+            "{} {}: {:?}",
+            element,
+            relationship,
+            refs
+        );
+        assert!(
+            !refs
+                .iter()
+                .any(|r| r == "[dbo].[t].[Id]" || r == "[dbo].[t].[C]"),
+            "{} {}: {:?}",
+            element,
+            relationship,
+            refs
+        );
+    }
+}